@@ -110,6 +110,7 @@ where
 
     let repo = gix::discover(repository_path)?;
     let pack_compression = repo.pack_compression()?;
+    let (window, depth) = repo.pack_window_and_depth()?;
     let repo = repo.into_sync();
     progress.init(Some(2), progress::steps());
     let tips = tips.into_iter();
@@ -214,6 +215,7 @@ where
                 &progress,
                 &interrupt::IS_INTERRUPTED,
                 input_object_expansion,
+                None,
             )?
         };
         stats.counts = count_stats;
@@ -231,11 +233,13 @@ where
             Box::new(progress),
             pack::data::output::entry::iter_from_counts::Options {
                 thread_limit,
-                mode: pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                mode: pack::data::output::entry::iter_from_counts::Mode::DeltaCompression,
                 allow_thin_pack: thin,
                 chunk_size,
                 version: Default::default(),
                 compression: pack_compression,
+                window,
+                depth,
             },
         ))
     };
@@ -322,6 +326,7 @@ fn human_output(
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                delta_compressed_objects,
             },
     }: Statistics,
     mut out: impl std::io::Write,
@@ -342,10 +347,11 @@ fn human_output(
     #[rustfmt::skip]
     writeln!(
         out,
-        "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
+        "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
         "decoded and recompressed", decoded_and_recompressed_objects,
         "pack-to-pack copies", objects_copied_from_pack,
         "ref-delta-objects", ref_delta_objects,
+        "delta-compressed objects", delta_compressed_objects,
         "missing objects", missing_objects,
         width = width
     )?;
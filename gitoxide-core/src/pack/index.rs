@@ -84,6 +84,9 @@ pub fn from_pack(
         object_hash: ctx.object_hash,
         alloc_limit_bytes: None,
         compression: gix::zlib::Compression::BEST_SPEED,
+        bitmap: None,
+        fsync: None,
+        fsync_metadata: None,
     };
     let out = ctx.out;
     let format = ctx.format;
@@ -1,4 +1,8 @@
-use std::{io::BufWriter, path::PathBuf, sync::atomic::AtomicBool};
+use std::{
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
 
 use anyhow::bail;
 use gix::NestedProgress;
@@ -20,6 +24,7 @@ pub fn verify(
 pub fn create(
     index_paths: Vec<PathBuf>,
     output_path: PathBuf,
+    preferred_pack: Option<PathBuf>,
     mut progress: impl NestedProgress + 'static,
     should_interrupt: &AtomicBool,
     object_hash: gix::hash::Kind,
@@ -34,12 +39,67 @@ pub fn create(
         &mut out,
         &mut progress,
         should_interrupt,
-        gix::odb::pack::multi_index::write::Options { object_hash },
+        gix::odb::pack::multi_index::write::Options {
+            object_hash,
+            bitmap: None,
+            preferred_pack,
+        },
     )?;
     out.into_inner()?.commit()?;
     Ok(())
 }
 
+pub struct AppendOptions {
+    pub preferred_pack: Option<PathBuf>,
+    /// If set, only the packs with these file names are added.
+    pub packs: Option<Vec<PathBuf>>,
+    pub object_hash: gix::hash::Kind,
+}
+
+pub fn append(
+    multi_index_path: PathBuf,
+    AppendOptions {
+        preferred_pack,
+        packs,
+        object_hash,
+    }: AppendOptions,
+    mut progress: impl NestedProgress + 'static,
+    mut out: impl std::io::Write,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    let Some(objects_dir) = multi_index_path.parent().and_then(Path::parent) else {
+        bail!(
+            "The multi-index at '{}' must be placed in the 'pack' directory of an object database",
+            multi_index_path.display()
+        )
+    };
+    let store = gix::odb::Store::at_opts(
+        objects_dir.to_owned(),
+        &mut None.into_iter(),
+        gix::odb::store::init::Options {
+            object_hash,
+            ..Default::default()
+        },
+    )?;
+    match store.append_multi_index_layer(
+        &mut progress,
+        should_interrupt,
+        gix::odb::store::multi_index::append::Options {
+            preferred_pack,
+            packs,
+            ..Default::default()
+        },
+    )? {
+        Some(outcome) => writeln!(
+            out,
+            "Added layer {} with {} packs and {} objects, the chain has {} layers now",
+            outcome.checksum, outcome.num_indices, outcome.num_objects, outcome.num_layers
+        )?,
+        None => writeln!(out, "There are no packs to add")?,
+    }
+    Ok(())
+}
+
 #[cfg(feature = "serde")]
 mod info {
     use std::path::PathBuf;
@@ -291,6 +291,9 @@ fn receive_pack_blocking(
         object_hash,
         alloc_limit_bytes: None,
         compression: gix::zlib::Compression::BEST_SPEED,
+        bitmap: None,
+        fsync: None,
+        fsync_metadata: None,
     };
     let outcome = pack::Bundle::write_to_directory(
         &mut input,
@@ -121,8 +121,10 @@ fn write_refs(
                 gix::refs::store::WriteReflog::Disable
             },
             object_hash,
+            format: gix::refs::store::Format::Files,
             precompose_unicode,
             prohibit_windows_device_names: cfg!(windows),
+            fsync: None,
         },
     );
     let edits = refs
@@ -51,11 +51,50 @@ pub fn display_object(
     Ok(())
 }
 
+/// Options for [`cat_batch()`](function::cat_batch()).
+pub struct BatchOptions {
+    pub mode: gix::batch::Mode,
+    /// The format of the line printed for each object, or the default format if `None`.
+    pub format: Option<String>,
+    /// If `true`, print all objects in the object database instead of reading object names.
+    pub all_objects: bool,
+    pub unordered: bool,
+    pub buffer: bool,
+}
+
 pub(super) mod function {
+    use super::BatchOptions;
     use crate::repository::revision::resolve::TreeMode;
 
     pub fn cat(repo: gix::Repository, revspec: &str, out: impl std::io::Write) -> anyhow::Result<()> {
         super::display_object(&repo, repo.rev_parse(revspec)?, TreeMode::Pretty, None, out)?;
         Ok(())
     }
+
+    pub fn cat_batch(
+        repo: gix::Repository,
+        input: impl std::io::BufRead,
+        out: impl std::io::Write,
+        BatchOptions {
+            mode,
+            format,
+            all_objects,
+            unordered,
+            buffer,
+        }: BatchOptions,
+    ) -> anyhow::Result<()> {
+        let mut options = gix::batch::Options::default()
+            .with_mode(mode)
+            .with_buffer(buffer)
+            .with_unordered(unordered);
+        if let Some(format) = format.filter(|format| !format.is_empty()) {
+            options = options.with_format(gix::batch::Format::parse(format.as_str().into())?);
+        }
+        if all_objects {
+            repo.batch_all_objects(out, options)?;
+        } else {
+            repo.batch_objects(input, out, options)?;
+        }
+        Ok(())
+    }
 }
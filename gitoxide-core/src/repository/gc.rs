@@ -0,0 +1,39 @@
+use std::sync::atomic::AtomicBool;
+
+use anyhow::bail;
+
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+pub fn function(
+    repo: gix::Repository,
+    mut progress: impl gix::NestedProgress + 'static,
+    mut out: impl std::io::Write,
+    should_interrupt: &AtomicBool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    let gix::gc::Outcome { repack, pruned_objects } = repo.gc(&mut progress, should_interrupt)?;
+    match repack.index_path {
+        Some(path) => writeln!(out, "Wrote {} objects to {}", repack.num_objects, path.display())?,
+        None => writeln!(out, "There were no objects to pack")?,
+    }
+    writeln!(
+        out,
+        "Removed {} packs and {} loose objects that are now packed",
+        repack.removed_packs.len(),
+        repack.removed_loose_objects
+    )?;
+    if repack.unpacked_objects != 0 {
+        writeln!(
+            out,
+            "Kept {} unreachable objects as loose objects until they expire",
+            repack.unpacked_objects
+        )?;
+    }
+    writeln!(out, "Pruned {pruned_objects} unreachable loose objects")?;
+    Ok(())
+}
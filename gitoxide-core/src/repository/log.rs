@@ -1,4 +1,3 @@
-use anyhow::bail;
 use gix::bstr::{BString, ByteSlice};
 
 pub fn log(mut repo: gix::Repository, out: &mut dyn std::io::Write, path: Option<BString>) -> anyhow::Result<()> {
@@ -25,8 +24,64 @@ fn log_all(repo: gix::Repository, out: &mut dyn std::io::Write) -> Result<(), an
     Ok(())
 }
 
-fn log_file(_repo: gix::Repository, _out: &mut dyn std::io::Write, _path: BString) -> anyhow::Result<()> {
-    bail!("File-based lookup isn't yet implemented in a way that is competitively fast");
+fn log_file(repo: gix::Repository, out: &mut dyn std::io::Write, path: BString) -> anyhow::Result<()> {
+    let head = repo.head()?.peel_to_commit()?;
+    let cache = repo.commit_graph_if_enabled()?;
+    let keys = cache
+        .as_ref()
+        .and_then(gix::commitgraph::Graph::bloom_filter_settings)
+        .map(|settings| gix::commitgraph::bloom::Key::for_path_and_leading_directories(path.as_ref(), &settings));
+    let topo = gix::traverse::commit::topo::Builder::from_iters(&repo.objects, [head.id], None::<Vec<gix::ObjectId>>)
+        .with_commit_graph(repo.commit_graph_if_enabled()?)
+        .build()?;
+
+    let relative_path = gix::path::from_bstr(path.as_bstr());
+    let mut entries = gix::hashtable::HashMap::default();
+    let mut entry_at = |id: gix::ObjectId| -> anyhow::Result<Option<(gix::object::tree::EntryMode, gix::ObjectId)>> {
+        if let Some(entry) = entries.get(&id) {
+            return Ok(*entry);
+        }
+        let entry = repo
+            .find_commit(id)?
+            .tree()?
+            .lookup_entry_by_path(&relative_path)?
+            .map(|entry| (entry.mode(), entry.object_id()));
+        entries.insert(id, entry);
+        Ok(entry)
+    };
+    for info in topo {
+        let info = info?;
+        // The changed-path filter tells if the path definitely didn't change compared to the first parent,
+        // which makes the commit uninteresting without having to look at any tree.
+        let unchanged_in_first_parent = !info.parent_ids.is_empty()
+            && keys
+                .as_ref()
+                .zip(cache.as_ref().and_then(|cache| cache.commit_by_id(info.id)))
+                .and_then(|(keys, commit)| Some((keys, commit.changed_paths_filter()?)))
+                .is_some_and(|(keys, filter)| !keys.iter().all(|key| filter.contains(key)));
+        if unchanged_in_first_parent {
+            continue;
+        }
+
+        // Like `git log -- <path>`, show commits that changed the path compared to all of their parents.
+        let entry = entry_at(info.id)?;
+        let mut changed = if info.parent_ids.is_empty() {
+            entry.is_some()
+        } else {
+            true
+        };
+        for parent_id in &info.parent_ids {
+            if entry_at(*parent_id)? == entry {
+                changed = false;
+                break;
+            }
+        }
+        if changed {
+            write_info(&repo, &mut *out, &info)?;
+        }
+    }
+
+    Ok(())
 }
 
 fn write_info(
@@ -8,7 +8,7 @@ use gix::bstr::BString;
 pub mod archive;
 pub mod branch;
 pub mod cat;
-pub use cat::function::cat;
+pub use cat::function::{cat, cat_batch};
 pub mod blame;
 pub mod commit;
 pub mod config;
@@ -35,6 +35,8 @@ pub use fetch::function::fetch;
 pub mod commitgraph;
 mod fsck;
 pub use fsck::function as fsck;
+pub mod gc;
+pub use gc::function as gc;
 pub mod index;
 pub mod log;
 pub mod mailmap;
@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+
+use bstr::{BString, ByteSlice};
+use gix_error::{ErrorExt, OptionExt, ResultExt, message};
+use gix_hash::ObjectId;
+use gix_merge::blob::builtin_driver::text::Labels;
+use gix_object::{
+    FindExt,
+    tree::{EntryKind, EntryMode},
+};
+
+use super::{Content, Error, Options, Outcome, ThreeWay};
+use crate::patch::{Body, Hunk, Kind, Line, Patch};
+
+/// Apply `patch` to `current`, the content of the file the patch is about, and return the new content.
+///
+/// Text hunks are applied in order, each one at the position where its old lines match, searching outwards from the position
+/// recorded in the hunk header, just like `git apply` does. If a hunk doesn't apply, its context is reduced down to
+/// [`min_context`](Options::min_context) lines, if set. If it still doesn't apply and [`three_way`](Options::three_way)
+/// is set, `expand_prefix` is used to find the blob the patch was created from in `objects`, and the patch is applied to
+/// that blob instead and merged into `current`, which may leave conflict markers.
+///
+/// Binary patches are only applied if `current` matches the [old id](Patch::old_id) of the patch, if present.
+pub fn content(
+    patch: &Patch,
+    current: &[u8],
+    objects: &impl gix_object::Find,
+    expand_prefix: &mut dyn FnMut(gix_hash::Prefix) -> Option<ObjectId>,
+    options: &Options,
+) -> Result<Content, Error> {
+    let path = patch.path();
+    let data = match &patch.body {
+        Body::Empty => current.to_vec(),
+        Body::Binary(None) => {
+            return Err(message!("Cannot apply binary patch to '{path}' without full index line").raise());
+        }
+        Body::Binary(Some(binary)) => {
+            if let Some(old_id) = patch.old_id {
+                let actual = gix_object::compute_hash(old_id.as_oid().kind(), gix_object::Kind::Blob, current)
+                    .or_raise(|| message!("Could not hash the content of '{path}'"))?;
+                if old_id.cmp_oid(&actual).is_ne() {
+                    return Err(message!("The binary patch does not apply to '{path}' as its content differs").raise());
+                }
+            }
+            crate::binary::apply(&binary.forward, current)
+                .or_raise(|| message!("Could not apply binary patch to '{path}'"))?
+        }
+        Body::Text(hunks) => match apply_hunks(current, hunks, options.min_context) {
+            Ok(data) => data,
+            Err(hunk) if !options.three_way => {
+                return Err(message!("Hunk #{} of the patch for '{path}' does not apply", hunk + 1).raise());
+            }
+            Err(_) => return three_way(patch, hunks, current, objects, expand_prefix, options),
+        },
+    };
+    Ok(Content { data, three_way: None })
+}
+
+/// Apply all `patches` in order to the tree with id `tree` and write the resulting tree along with all new blobs
+/// to `objects`.
+///
+/// Files are added, removed, renamed or copied as the patches say, and their content is changed with [`content()`],
+/// using `expand_prefix` and `options` accordingly. A file may be patched more than once, with each patch seeing the
+/// result of the previous one.
+///
+/// It's an error if a patch doesn't apply, if a file to add already exists or if a file to change or remove doesn't exist.
+/// Files that were [merged](Options::three_way) with conflicts are written with conflict markers and listed in the outcome.
+pub fn tree(
+    tree: ObjectId,
+    patches: &[Patch],
+    objects: &(impl gix_object::FindExt + gix_object::Write),
+    expand_prefix: &mut dyn FnMut(gix_hash::Prefix) -> Option<ObjectId>,
+    options: &Options,
+) -> Result<Outcome, Error> {
+    let mut buf = Vec::new();
+    let root = objects
+        .find_tree(&tree, &mut buf)
+        .or_raise(|| message!("Could not find tree {tree} to apply patches to"))?
+        .into_owned();
+    let mut editor = gix_object::tree::Editor::new(root, objects, tree.kind());
+    // Paths changed by previous patches, or `None` if they were removed.
+    let mut changed = BTreeMap::<BString, Option<(EntryMode, ObjectId)>>::new();
+    let mut out = Outcome {
+        tree,
+        merged: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    for patch in patches {
+        let old = match &patch.old_path {
+            Some(path) => Some(
+                lookup(tree, path, &changed, objects)?
+                    .ok_or_raise(|| message!("'{path}' does not exist in tree {tree}"))?,
+            ),
+            None => None,
+        };
+        let new_path = patch.new_path.as_ref();
+        if let Some(path) = new_path.filter(|_| patch.old_path.as_ref() != new_path) {
+            if lookup(tree, path, &changed, objects)?.is_some() {
+                return Err(message!("'{path}' already exists in tree {tree}").raise());
+            }
+        }
+
+        let current = match old {
+            Some((mode, id)) => {
+                if !mode.is_blob_or_symlink() {
+                    return Err(message!("'{}' is not a file and can't be patched", patch.path()).raise());
+                }
+                objects
+                    .find_blob(&id, &mut buf)
+                    .or_raise(|| message!("Could not find blob {id} of '{}'", patch.path()))?
+                    .data
+                    .to_vec()
+            }
+            None => Vec::new(),
+        };
+        let content = super::content(patch, &current, objects, expand_prefix, options)?;
+        if content.three_way.is_some() {
+            out.merged.push(patch.path().clone());
+            if content.has_conflict() {
+                out.conflicts.push(patch.path().clone());
+            }
+        }
+
+        if matches!(patch.kind, Kind::Delete | Kind::Rename { .. }) {
+            let old_path = patch.old_path.as_ref().expect("set for deletions and renames");
+            if patch.kind == Kind::Delete && !content.data.is_empty() {
+                return Err(message!("The patch to remove '{old_path}' leaves content behind").raise());
+            }
+            editor
+                .remove(components(old_path))
+                .or_raise(|| message!("Could not remove '{old_path}' from tree"))?;
+            changed.insert(old_path.clone(), None);
+        }
+        if let Some(new_path) = new_path {
+            let mode = patch
+                .new_mode
+                .or(old.map(|(mode, _)| mode))
+                .unwrap_or_else(|| EntryKind::Blob.into());
+            let id = objects
+                .write_buf(gix_object::Kind::Blob, &content.data)
+                .map_err(std::io::Error::other)
+                .or_raise(|| message!("Could not write the new content of '{new_path}'"))?;
+            editor
+                .upsert(components(new_path), mode.kind(), id)
+                .or_raise(|| message!("Could not add '{new_path}' to tree"))?;
+            changed.insert(new_path.clone(), Some((mode, id)));
+        }
+    }
+    out.tree = editor
+        .write(|tree| objects.write(tree))
+        .map_err(std::io::Error::other)
+        .or_raise(|| message!("Could not write the patched tree"))?;
+    Ok(out)
+}
+
+/// Merge the changes of `hunks` into `current`, using the blob `patch` was created from as base.
+fn three_way(
+    patch: &Patch,
+    hunks: &[Hunk],
+    current: &[u8],
+    objects: &impl gix_object::Find,
+    expand_prefix: &mut dyn FnMut(gix_hash::Prefix) -> Option<ObjectId>,
+    options: &Options,
+) -> Result<Content, Error> {
+    let path = patch.path();
+    let base = patch.old_id.and_then(expand_prefix).ok_or_raise(|| {
+        message!("The patch for '{path}' does not apply and the blob to merge it with is unavailable")
+    })?;
+    let mut buf = Vec::new();
+    let ancestor = objects
+        .find_blob(&base, &mut buf)
+        .or_raise(|| message!("Could not find blob {base} to merge the patch for '{path}'"))?
+        .data;
+    let theirs = apply_hunks(ancestor, hunks, None).map_err(|hunk| {
+        message!(
+            "Hunk #{} of the patch for '{path}' doesn't apply to the blob {base} it was created from",
+            hunk + 1
+        )
+        .raise()
+    })?;
+    let mut input = gix_diff::blob::InternedInput::new(&[][..], &[][..]);
+    let mut data = Vec::new();
+    let resolution = gix_merge::blob::builtin_driver::text(
+        &mut data,
+        &mut input,
+        Labels {
+            ancestor: Some("base".into()),
+            current: Some("ours".into()),
+            other: Some("theirs".into()),
+        },
+        current,
+        ancestor,
+        &theirs,
+        options.text_merge,
+    );
+    Ok(Content {
+        data,
+        three_way: Some(ThreeWay {
+            base,
+            theirs,
+            resolution,
+        }),
+    })
+}
+
+/// Apply all `hunks` to `data`, or return the index of the first hunk that doesn't apply.
+fn apply_hunks(data: &[u8], hunks: &[Hunk], min_context: Option<usize>) -> Result<Vec<u8>, usize> {
+    let mut image: Vec<&[u8]> = data.lines_with_terminator().collect();
+    for (idx, hunk) in hunks.iter().enumerate() {
+        if !apply_hunk(&mut image, hunk, min_context.unwrap_or(usize::MAX)) {
+            return Err(idx);
+        }
+    }
+    Ok(image.concat())
+}
+
+/// Apply `hunk` to the lines in `image`, reducing its context down to `min_context` lines if needed, like Git does.
+/// Return `false` if the hunk couldn't be applied.
+fn apply_hunk<'a>(image: &mut Vec<&'a [u8]>, hunk: &'a Hunk, min_context: usize) -> bool {
+    let preimage: Vec<&[u8]> = hunk
+        .lines
+        .iter()
+        .filter(|line| !matches!(line, Line::Insert(_)))
+        .map(|line| line.content().as_slice())
+        .collect();
+    let postimage: Vec<&[u8]> = hunk
+        .lines
+        .iter()
+        .filter(|line| !matches!(line, Line::Delete(_)))
+        .map(|line| line.content().as_slice())
+        .collect();
+    let is_context = |line: &&Line| matches!(line, Line::Context(_));
+    let mut leading = hunk.lines.iter().take_while(is_context).count();
+    let mut trailing = hunk.lines.iter().rev().take_while(is_context).count();
+    let (mut pre, mut post) = (&preimage[..], &postimage[..]);
+
+    let mut pos = (hunk.new_start as usize).saturating_sub(1);
+    let mut match_beginning = hunk.old_start <= 1;
+    let mut match_end = trailing == 0;
+    loop {
+        if let Some(at) = find_position(image, pre, pos, match_beginning, match_end) {
+            image.splice(at..at + pre.len(), post.iter().copied());
+            return true;
+        }
+        if leading <= min_context && trailing <= min_context {
+            return false;
+        }
+        if match_beginning || match_end {
+            match_beginning = false;
+            match_end = false;
+            continue;
+        }
+        // Reduce the context on both ends if they are equal, or the larger one otherwise.
+        if leading >= trailing {
+            (pre, post) = (&pre[1..], &post[1..]);
+            pos = pos.saturating_sub(1);
+            leading -= 1;
+        }
+        if trailing > leading {
+            (pre, post) = (&pre[..pre.len() - 1], &post[..post.len() - 1]);
+            trailing -= 1;
+        }
+    }
+}
+
+/// Find the position at which `preimage` matches `image`, starting at `pos` and searching outwards,
+/// optionally forcing it to match at the beginning or end of `image`.
+fn find_position(
+    image: &[&[u8]],
+    preimage: &[&[u8]],
+    pos: usize,
+    match_beginning: bool,
+    match_end: bool,
+) -> Option<usize> {
+    let last = image.len().checked_sub(preimage.len())?;
+    let matches = |at: usize| image[at..at + preimage.len()] == *preimage;
+    if match_beginning {
+        return (matches(0) && (!match_end || last == 0)).then_some(0);
+    }
+    if match_end {
+        return matches(last).then_some(last);
+    }
+    let pos = pos.min(last);
+    if matches(pos) {
+        return Some(pos);
+    }
+    let (mut backward, mut forward) = (pos, pos);
+    while backward > 0 || forward < last {
+        if forward < last {
+            forward += 1;
+            if matches(forward) {
+                return Some(forward);
+            }
+        }
+        if backward > 0 {
+            backward -= 1;
+            if matches(backward) {
+                return Some(backward);
+            }
+        }
+    }
+    None
+}
+
+/// Look up `path` in `tree`, taking into account all paths that were `changed` already.
+fn lookup(
+    tree: ObjectId,
+    path: &BString,
+    changed: &BTreeMap<BString, Option<(EntryMode, ObjectId)>>,
+    objects: &impl gix_object::FindExt,
+) -> Result<Option<(EntryMode, ObjectId)>, Error> {
+    if let Some(entry) = changed.get(path) {
+        return Ok(*entry);
+    }
+    let (mut buf, mut lookup_buf) = (Vec::new(), Vec::new());
+    let entry = objects
+        .find_tree_iter(&tree, &mut buf)
+        .or_raise(|| message!("Could not find tree {tree}"))?
+        .lookup_entry(objects, &mut lookup_buf, path.split_str("/"))
+        .map_err(std::io::Error::other)
+        .or_raise(|| message!("Could not look up '{path}' in tree {tree}"))?;
+    Ok(entry.map(|entry| (entry.mode, entry.oid)))
+}
+
+fn components(path: &BString) -> impl Iterator<Item = &bstr::BStr> {
+    path.split_str("/").map(ByteSlice::as_bstr)
+}
@@ -0,0 +1,66 @@
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The error returned by [`content()`] and [`tree()`].
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// A way to configure [`content()`] and [`tree()`].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// If set, allow to reduce the context of each hunk down to this amount of lines if it doesn't apply
+    /// with its full context, just like `git apply -C<n>` does.
+    ///
+    /// If `None`, all context lines must match.
+    pub min_context: Option<usize>,
+    /// If `true` and a text patch doesn't apply, merge it into the current content using the blob it was created from
+    /// as base, just like `git apply --3way` does.
+    ///
+    /// This requires the patch to record the [id of the old blob](crate::Patch::old_id) and the blob to be available.
+    pub three_way: bool,
+    /// The options for merging text in case of a [3-way merge](Self::three_way).
+    pub text_merge: gix_merge::blob::builtin_driver::text::Options,
+}
+
+/// Information about a 3-way merge that was performed as a patch didn't apply cleanly.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ThreeWay {
+    /// The id of the blob the patch was created from, used as base of the merge.
+    pub base: ObjectId,
+    /// The content that results from applying the patch to `base`, which is *their* side of the merge.
+    pub theirs: Vec<u8>,
+    /// The result of the merge, which is [a conflict](gix_merge::blob::Resolution::Conflict) if the
+    /// content contains conflict markers.
+    pub resolution: gix_merge::blob::Resolution,
+}
+
+/// The result of [`content()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Content {
+    /// The content after applying the patch.
+    pub data: Vec<u8>,
+    /// Set if the patch didn't apply cleanly and was merged instead.
+    pub three_way: Option<ThreeWay>,
+}
+
+impl Content {
+    /// Return `true` if the content contains conflict markers as the patch was merged with conflicts.
+    pub fn has_conflict(&self) -> bool {
+        self.three_way
+            .as_ref()
+            .is_some_and(|three_way| three_way.resolution == gix_merge::blob::Resolution::Conflict)
+    }
+}
+
+/// The result of [`tree()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// The id of the tree with all patches applied.
+    pub tree: ObjectId,
+    /// The paths of all files which had to be merged, in the order of the patches.
+    pub merged: Vec<BString>,
+    /// The paths of all files which contain conflict markers, in the order of the patches.
+    pub conflicts: Vec<BString>,
+}
+
+pub(super) mod function;
+pub use function::{content, tree};
@@ -0,0 +1,145 @@
+//! Primitives to decode and apply binary patches as produced by `git diff --binary`.
+use gix_error::{ErrorExt, ResultExt, message};
+
+use crate::patch::BinaryHunk;
+
+/// The error returned by the functions in this module.
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// The alphabet of the base85 encoding used by Git, which differs from the one of Ascii85.
+const BASE85_ALPHABET: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// Decode a single `line` of a binary hunk, without its line ending, and append the decoded bytes to `out`.
+///
+/// The first character of the line encodes the amount of decoded bytes, `A` to `Z` for 1 to 26 and `a` to `z` for 27 to 52,
+/// which is followed by 5 base85 characters for each 4 bytes.
+pub fn decode_line(line: &[u8], out: &mut Vec<u8>) -> Result<(), Error> {
+    let (&len, encoded) = line
+        .split_first()
+        .ok_or_else(|| message!("Binary patch lines must not be empty").raise())?;
+    let len = match len {
+        b'A'..=b'Z' => len - b'A' + 1,
+        b'a'..=b'z' => len - b'a' + 27,
+        _ => return Err(message!("Invalid length character {:?} in binary patch line", len as char).raise()),
+    } as usize;
+    if encoded.len() != len.div_ceil(4) * 5 {
+        return Err(message!(
+            "Binary patch line should have {} characters to encode {len} bytes, got {}",
+            len.div_ceil(4) * 5,
+            encoded.len()
+        )
+        .raise());
+    }
+    let mut remaining = len;
+    for chunk in encoded.chunks(5) {
+        let mut acc = 0u32;
+        for &c in chunk {
+            let digit = BASE85_ALPHABET
+                .iter()
+                .position(|&b| b == c)
+                .ok_or_else(|| message!("Invalid base85 character {:?} in binary patch line", c as char).raise())?;
+            acc = acc
+                .checked_mul(85)
+                .and_then(|acc| acc.checked_add(digit as u32))
+                .ok_or_else(|| message!("Invalid base85 sequence in binary patch line").raise())?;
+        }
+        let bytes = acc.to_be_bytes();
+        let take = remaining.min(4);
+        out.extend_from_slice(&bytes[..take]);
+        remaining -= take;
+    }
+    Ok(())
+}
+
+/// Decompress the zlib stream in `data` which is expected to decompress into exactly `size` bytes.
+pub fn inflate(data: &[u8], size: usize) -> Result<Vec<u8>, Error> {
+    // One more byte than needed to detect if the data decompresses into more than `size` bytes.
+    let mut out = vec![0; size + 1];
+    let (status, _consumed, written) = gix_zlib::Inflate::default()
+        .once(data, &mut out)
+        .or_raise(|| message!("Could not decompress binary patch data"))?;
+    if status != gix_zlib::Status::StreamEnd || written != size {
+        return Err(message!("Binary patch data didn't decompress into the expected {size} bytes").raise());
+    }
+    out.truncate(size);
+    Ok(out)
+}
+
+/// Apply `delta`, in the format used for deltified objects in packs, to `base` and return the result.
+pub fn apply_delta(base: &[u8], delta: &[u8]) -> Result<Vec<u8>, Error> {
+    let corrupt = || message!("Corrupt binary delta").raise();
+    let (base_size, mut delta) = leb64(delta).ok_or_else(corrupt)?;
+    if base_size != base.len() as u64 {
+        return Err(message!(
+            "Binary delta expects {base_size} bytes of base content, got {}",
+            base.len()
+        )
+        .raise());
+    }
+    let result_size;
+    (result_size, delta) = leb64(delta).ok_or_else(corrupt)?;
+    let mut out = Vec::with_capacity(result_size as usize);
+    while let Some((&cmd, rest)) = delta.split_first() {
+        delta = rest;
+        if cmd & 0x80 != 0 {
+            let (mut offset, mut size) = (0usize, 0usize);
+            for (bit, shift) in [(0x01, 0), (0x02, 8), (0x04, 16), (0x08, 24)] {
+                if cmd & bit != 0 {
+                    let (&byte, rest) = delta.split_first().ok_or_else(corrupt)?;
+                    offset |= (byte as usize) << shift;
+                    delta = rest;
+                }
+            }
+            for (bit, shift) in [(0x10, 0), (0x20, 8), (0x40, 16)] {
+                if cmd & bit != 0 {
+                    let (&byte, rest) = delta.split_first().ok_or_else(corrupt)?;
+                    size |= (byte as usize) << shift;
+                    delta = rest;
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            let copy = offset
+                .checked_add(size)
+                .and_then(|end| base.get(offset..end))
+                .ok_or_else(corrupt)?;
+            out.extend_from_slice(copy);
+        } else if cmd != 0 {
+            let len = cmd as usize;
+            let data = delta.get(..len).ok_or_else(corrupt)?;
+            out.extend_from_slice(data);
+            delta = &delta[len..];
+        } else {
+            return Err(corrupt());
+        }
+    }
+    if out.len() as u64 != result_size {
+        return Err(message!("Binary delta should produce {result_size} bytes, got {}", out.len()).raise());
+    }
+    Ok(out)
+}
+
+/// Apply `hunk` to `base` and return the resulting content.
+pub fn apply(hunk: &BinaryHunk, base: &[u8]) -> Result<Vec<u8>, Error> {
+    match hunk {
+        BinaryHunk::Literal(data) => Ok(data.clone()),
+        BinaryHunk::Delta(delta) => apply_delta(base, delta),
+    }
+}
+
+/// Decode a little-endian base-128 varint as used for sizes in delta headers, and return it along with the remaining input.
+fn leb64(mut input: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let (&byte, rest) = input.split_first()?;
+        input = rest;
+        value |= u64::from(byte & 0x7f).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some((value, input));
+        }
+        shift += 7;
+    }
+}
@@ -0,0 +1,26 @@
+//! Parse patches as produced by [`git diff`](https://git-scm.com/docs/git-diff) and apply them to file content or to trees,
+//! similar to what [`git apply`](https://git-scm.com/docs/git-apply) does.
+//!
+//! * [parse()] turns the output of `git diff` or `git format-patch` into a list of [`Patch`]es, including
+//!   rename, copy and mode changes as well as binary patches as produced by `git diff --binary`.
+//! * [apply::content()] applies a single patch to the content of a file, with context fuzz and an optional
+//!   3-way merge fallback through [`gix_merge::blob::builtin_driver::text()`].
+//! * [apply::tree()] applies all patches to a tree, entirely in memory, and writes the resulting tree.
+//!
+//! Updating the index or the worktree is left to the caller.
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+///
+pub mod patch;
+pub use patch::Patch;
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+///
+pub mod binary;
+
+///
+pub mod apply;
@@ -0,0 +1,397 @@
+/// The error returned by [`parse()`](crate::parse()).
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+pub(crate) mod function {
+    use bstr::{BStr, BString, ByteSlice};
+    use gix_error::{ErrorExt, OptionExt, ResultExt, message};
+    use gix_object::tree::EntryMode;
+
+    use super::Error;
+    use crate::patch::{Binary, BinaryHunk, Body, Hunk, Kind, Line, Patch};
+
+    /// Parse all patches in `input`, as produced by `git diff`, `git diff --binary` or `git format-patch`, in order.
+    ///
+    /// Only patches with a `diff --git` header are recognized, and everything that isn't part of a patch,
+    /// like the mail headers and the commit message of `git format-patch` output, is ignored.
+    /// Paths are expected to have a single leading component, like `a/` and `b/`, which is removed.
+    /// Object ids are expected to be of kind `object_hash`, even if they are abbreviated.
+    ///
+    /// It's an error if `input` doesn't contain any patch.
+    pub fn parse(input: &[u8], object_hash: gix_hash::Kind) -> Result<Vec<Patch>, Error> {
+        let mut lines = Lines::new(input);
+        let mut out = Vec::new();
+        while let Some(line) = lines.next() {
+            if let Some(names) = line.strip_prefix(b"diff --git ") {
+                let start = lines.number;
+                out.push(
+                    parse_patch(trim_eol(names), &mut lines, object_hash)
+                        .or_raise(|| message!("Could not parse the patch starting at line {start}"))?,
+                );
+            }
+        }
+        if out.is_empty() {
+            return Err(message!("No valid patches in input").raise());
+        }
+        Ok(out)
+    }
+
+    struct Lines<'a> {
+        inner: std::iter::Peekable<bstr::LinesWithTerminator<'a>>,
+        /// The 1-based number of the line that was returned last.
+        number: usize,
+    }
+
+    impl<'a> Lines<'a> {
+        fn new(input: &'a [u8]) -> Self {
+            Lines {
+                inner: input.lines_with_terminator().peekable(),
+                number: 0,
+            }
+        }
+
+        fn next(&mut self) -> Option<&'a [u8]> {
+            let line = self.inner.next()?;
+            self.number += 1;
+            Some(line)
+        }
+
+        fn peek(&mut self) -> Option<&'a [u8]> {
+            self.inner.peek().copied()
+        }
+    }
+
+    #[derive(Default)]
+    struct Header {
+        old_mode: Option<EntryMode>,
+        new_mode: Option<EntryMode>,
+        is_new: bool,
+        is_deleted: bool,
+        rename: Option<(BString, BString)>,
+        copy: Option<(BString, BString)>,
+        similarity: Option<u8>,
+        old_id: Option<gix_hash::Prefix>,
+        new_id: Option<gix_hash::Prefix>,
+        minus: Option<Option<BString>>,
+        plus: Option<Option<BString>>,
+    }
+
+    fn parse_patch(names: &[u8], lines: &mut Lines<'_>, object_hash: gix_hash::Kind) -> Result<Patch, Error> {
+        let (header_old, header_new) = header_paths(names)?;
+        let mut header = Header::default();
+        let (mut rename_from, mut rename_to, mut copy_from, mut copy_to) = (None, None, None, None);
+        while let Some(line) = lines.peek() {
+            let line = trim_eol(line);
+            if let Some(mode) = line.strip_prefix(b"old mode ") {
+                header.old_mode = Some(parse_mode(mode)?);
+            } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+                header.new_mode = Some(parse_mode(mode)?);
+            } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+                header.old_mode = Some(parse_mode(mode)?);
+                header.is_deleted = true;
+            } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+                header.new_mode = Some(parse_mode(mode)?);
+                header.is_new = true;
+            } else if let Some(path) = line.strip_prefix(b"rename from ") {
+                rename_from = Some(unquote(path)?);
+            } else if let Some(path) = line.strip_prefix(b"rename to ") {
+                rename_to = Some(unquote(path)?);
+            } else if let Some(path) = line.strip_prefix(b"copy from ") {
+                copy_from = Some(unquote(path)?);
+            } else if let Some(path) = line.strip_prefix(b"copy to ") {
+                copy_to = Some(unquote(path)?);
+            } else if let Some(percent) = line.strip_prefix(b"similarity index ") {
+                header.similarity = Some(parse_percent(percent)?);
+            } else if line.starts_with(b"dissimilarity index ") {
+            } else if let Some(index) = line.strip_prefix(b"index ") {
+                parse_index(index, object_hash, &mut header)?;
+            } else if let Some(name) = line.strip_prefix(b"--- ") {
+                header.minus = Some(file_name(name)?);
+            } else if let Some(name) = line.strip_prefix(b"+++ ") {
+                header.plus = Some(file_name(name)?);
+            } else {
+                break;
+            }
+            lines.next();
+        }
+        header.rename = rename_from.zip(rename_to);
+        header.copy = copy_from.zip(copy_to);
+
+        let body = match lines.peek() {
+            Some(line) if line.starts_with(b"@@ ") => {
+                if header.minus.is_none() || header.plus.is_none() {
+                    return Err(message!("Hunks must be preceded by '---' and '+++' lines").raise());
+                }
+                let mut hunks = Vec::new();
+                while lines.peek().is_some_and(|line| line.starts_with(b"@@ ")) {
+                    hunks.push(parse_hunk(lines)?);
+                }
+                Body::Text(hunks)
+            }
+            Some(line) if trim_eol(line) == b"GIT binary patch" => {
+                lines.next();
+                let forward = parse_binary_hunk(lines)?
+                    .ok_or_raise(|| message!("Binary patch at line {} has no data", lines.number))?;
+                let reverse = parse_binary_hunk(lines)?;
+                Body::Binary(Some(Binary { forward, reverse }))
+            }
+            Some(line) if line.starts_with(b"Binary files ") => {
+                lines.next();
+                Body::Binary(None)
+            }
+            _ => Body::Empty,
+        };
+
+        let is_new = header.is_new || header.minus == Some(None);
+        let is_deleted = header.is_deleted || header.plus == Some(None);
+        let (kind, old_path, new_path) = if is_new {
+            (Kind::Add, None, header.plus.flatten().or(header_new))
+        } else if is_deleted {
+            (Kind::Delete, header.minus.flatten().or(header_old), None)
+        } else if let Some((from, to)) = header.rename {
+            (
+                Kind::Rename {
+                    similarity: header.similarity,
+                },
+                Some(from),
+                Some(to),
+            )
+        } else if let Some((from, to)) = header.copy {
+            (
+                Kind::Copy {
+                    similarity: header.similarity,
+                },
+                Some(from),
+                Some(to),
+            )
+        } else {
+            (
+                Kind::Modify,
+                header.minus.flatten().or(header_old),
+                header.plus.flatten().or(header_new),
+            )
+        };
+        if (kind != Kind::Add && old_path.is_none()) || (kind != Kind::Delete && new_path.is_none()) {
+            return Err(message!("Could not determine the file name of the patch").raise());
+        }
+        if kind == Kind::Modify && old_path != new_path {
+            return Err(message!("The old and new file names of a modification must be the same").raise());
+        }
+        Ok(Patch {
+            old_path,
+            new_path,
+            old_mode: header.old_mode,
+            new_mode: header.new_mode,
+            kind,
+            old_id: header.old_id,
+            new_id: header.new_id,
+            body,
+        })
+    }
+
+    /// Parse the names in the `diff --git a/<old> b/<new>` line, which are either quoted, or separated by a space
+    /// which is ambiguous unless both names are the same.
+    fn header_paths(names: &[u8]) -> Result<(Option<BString>, Option<BString>), Error> {
+        let (old, new): (BString, BString) = if names.starts_with(b"\"") {
+            let (old, consumed) =
+                gix_quote::ansi_c::undo(names.as_bstr()).or_raise(|| message!("Invalid quoted file name"))?;
+            let new = names[consumed..].strip_prefix(b" ").unwrap_or(&names[consumed..]);
+            (old.into_owned(), unquote(new)?)
+        } else if let Some(pos) = names.find(b" \"") {
+            (names[..pos].into(), unquote(&names[pos + 1..])?)
+        } else {
+            match names.find_iter(b" ").find(|&pos| {
+                let (old, new) = (&names[..pos], &names[pos + 1..]);
+                strip_component(old.as_bstr()).is_some_and(|old| Some(old) == strip_component(new.as_bstr()))
+            }) {
+                Some(pos) => (names[..pos].into(), names[pos + 1..].into()),
+                None => return Ok((None, None)),
+            }
+        };
+        Ok((
+            strip_component(old.as_ref()).map(Into::into),
+            strip_component(new.as_ref()).map(Into::into),
+        ))
+    }
+
+    /// Parse the name of a `---` or `+++` line, which is `None` for `/dev/null`.
+    fn file_name(name: &[u8]) -> Result<Option<BString>, Error> {
+        let name = if name.starts_with(b"\"") {
+            unquote(name)?
+        } else {
+            // Git appends a tab to names with spaces, and other tools append a timestamp after it.
+            name.split_str("\t").next().unwrap_or_default().into()
+        };
+        if name == "/dev/null" {
+            return Ok(None);
+        }
+        strip_component(name.as_ref())
+            .map(|name| Some(name.into()))
+            .ok_or_raise(|| message!("File name '{name}' doesn't have a leading directory to remove"))
+    }
+
+    fn unquote(path: &[u8]) -> Result<BString, Error> {
+        let (path, _consumed) =
+            gix_quote::ansi_c::undo(path.as_bstr()).or_raise(|| message!("Invalid quoted file name"))?;
+        Ok(path.into_owned())
+    }
+
+    /// Remove the first component of `path`, like `a/`, as `git apply -p1` would.
+    fn strip_component(path: &BStr) -> Option<&BStr> {
+        let pos = path.find_byte(b'/')?;
+        let rest = &path[pos + 1..];
+        (!rest.is_empty()).then(|| rest.as_bstr())
+    }
+
+    fn parse_mode(mode: &[u8]) -> Result<EntryMode, Error> {
+        EntryMode::from_bytes(mode)
+            .filter(|_| !mode.is_empty() && mode.iter().all(u8::is_ascii_digit))
+            .ok_or_raise(|| message!("Invalid file mode '{}'", mode.as_bstr()))
+    }
+
+    fn parse_percent(percent: &[u8]) -> Result<u8, Error> {
+        percent
+            .strip_suffix(b"%")
+            .and_then(parse_number)
+            .ok_or_raise(|| message!("Invalid percentage '{}'", percent.as_bstr()))
+    }
+
+    fn parse_number<T: std::str::FromStr>(number: &[u8]) -> Option<T> {
+        std::str::from_utf8(number).ok()?.parse().ok()
+    }
+
+    /// Parse `<old>..<new>[ <mode>]`.
+    fn parse_index(index: &[u8], object_hash: gix_hash::Kind, header: &mut Header) -> Result<(), Error> {
+        let mut tokens = index.splitn_str(2, " ");
+        let ids = tokens.next().unwrap_or_default();
+        if let Some(mode) = tokens.next() {
+            let mode = parse_mode(mode)?;
+            header.old_mode.get_or_insert(mode);
+            header.new_mode.get_or_insert(mode);
+        }
+        let (old, new) = ids
+            .split_once_str("..")
+            .ok_or_raise(|| message!("Invalid index line 'index {}'", index.as_bstr()))?;
+        let prefix = |hex: &[u8]| -> Result<Option<gix_hash::Prefix>, Error> {
+            if hex.iter().all(|b| *b == b'0') {
+                return Ok(None);
+            }
+            let invalid = || message!("Invalid object id '{}' in index line", hex.as_bstr());
+            if hex.len() < gix_hash::Prefix::MIN_HEX_LEN || hex.len() > object_hash.len_in_hex() {
+                return Err(invalid().raise());
+            }
+            // Pad the abbreviated id so it's parsed as id of the right kind.
+            let mut padded = hex.to_vec();
+            padded.resize(object_hash.len_in_hex(), b'0');
+            let id = gix_hash::ObjectId::from_hex(&padded).or_raise(invalid)?;
+            gix_hash::Prefix::new(&id, hex.len()).map(Some).or_raise(invalid)
+        };
+        header.old_id = prefix(old)?;
+        header.new_id = prefix(new)?;
+        Ok(())
+    }
+
+    /// Parse `@@ -<old_start>[,<old_lines>] +<new_start>[,<new_lines>] @@`.
+    fn parse_range(header: &[u8]) -> Option<(u32, u32, u32, u32)> {
+        let header = header.strip_prefix(b"@@ -")?;
+        let (ranges, _) = header.split_once_str(" @@")?;
+        let (old, new) = ranges.split_once_str(" +")?;
+        let range = |range: &[u8]| -> Option<(u32, u32)> {
+            match range.split_once_str(",") {
+                Some((start, lines)) => Some((parse_number(start)?, parse_number(lines)?)),
+                None => Some((parse_number(range)?, 1)),
+            }
+        };
+        let ((old_start, old_lines), (new_start, new_lines)) = (range(old)?, range(new)?);
+        Some((old_start, old_lines, new_start, new_lines))
+    }
+
+    fn parse_hunk(lines: &mut Lines<'_>) -> Result<Hunk, Error> {
+        let header = trim_eol(lines.next().expect("peeked"));
+        let (old_start, old_lines, new_start, new_lines) = parse_range(header)
+            .ok_or_raise(|| message!("Invalid hunk header '{}' at line {}", header.as_bstr(), lines.number))?;
+        let (mut old_left, mut new_left) = (old_lines, new_lines);
+        let mut out = Vec::new();
+        while old_left > 0 || new_left > 0 {
+            let line = lines
+                .next()
+                .ok_or_raise(|| message!("The hunk at line {} is truncated", lines.number))?;
+            let (line, in_old, in_new) = match line.first() {
+                Some(b' ') => (Line::Context(line[1..].into()), true, true),
+                Some(b'\n' | b'\r') if trim_eol(line).is_empty() => (Line::Context(line.into()), true, true),
+                Some(b'-') => (Line::Delete(line[1..].into()), true, false),
+                Some(b'+') => (Line::Insert(line[1..].into()), false, true),
+                Some(b'\\') => {
+                    strip_newline(&mut out);
+                    continue;
+                }
+                _ => return Err(message!("Corrupt patch at line {}", lines.number).raise()),
+            };
+            let too_long = || message!("The hunk at line {} has more lines than its header says", lines.number);
+            if in_old {
+                old_left = old_left.checked_sub(1).ok_or_raise(too_long)?;
+            }
+            if in_new {
+                new_left = new_left.checked_sub(1).ok_or_raise(too_long)?;
+            }
+            out.push(line);
+        }
+        if lines.peek().is_some_and(|line| line.starts_with(b"\\")) {
+            lines.next();
+            strip_newline(&mut out);
+        }
+        Ok(Hunk {
+            old_start,
+            old_lines,
+            new_start,
+            new_lines,
+            lines: out,
+        })
+    }
+
+    /// Handle `\ No newline at end of file` by removing the newline of the previous line.
+    fn strip_newline(lines: &mut [Line]) {
+        if let Some(Line::Context(line) | Line::Delete(line) | Line::Insert(line)) = lines.last_mut() {
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+        }
+    }
+
+    /// Parse `literal <size>` or `delta <size>`, followed by base85 encoded lines and an empty line.
+    fn parse_binary_hunk(lines: &mut Lines<'_>) -> Result<Option<BinaryHunk>, Error> {
+        let Some(line) = lines.peek() else {
+            return Ok(None);
+        };
+        let line = trim_eol(line);
+        let (is_delta, size) = if let Some(size) = line.strip_prefix(b"literal ") {
+            (false, size)
+        } else if let Some(size) = line.strip_prefix(b"delta ") {
+            (true, size)
+        } else {
+            return Ok(None);
+        };
+        lines.next();
+        let start = lines.number;
+        let size: usize = parse_number(size).ok_or_raise(|| message!("Invalid size in binary hunk at line {start}"))?;
+        let mut data = Vec::new();
+        while let Some(line) = lines.next() {
+            let line = trim_eol(line);
+            if line.is_empty() {
+                break;
+            }
+            crate::binary::decode_line(line, &mut data)
+                .or_raise(|| message!("Invalid binary data at line {}", lines.number))?;
+        }
+        let data = crate::binary::inflate(&data, size).or_raise(|| message!("Invalid binary hunk at line {start}"))?;
+        Ok(Some(if is_delta {
+            BinaryHunk::Delta(data)
+        } else {
+            BinaryHunk::Literal(data)
+        }))
+    }
+
+    fn trim_eol(line: &[u8]) -> &[u8] {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+}
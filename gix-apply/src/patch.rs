@@ -0,0 +1,128 @@
+use bstr::BString;
+use gix_object::tree::EntryMode;
+
+/// A patch for a single file, as parsed from the output of `git diff` by [`parse()`](crate::parse()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Patch {
+    /// The path of the file before the change, or `None` if the file is [added](Kind::Add).
+    pub old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if the file is [deleted](Kind::Delete).
+    pub new_path: Option<BString>,
+    /// The mode of the file before the change, if known.
+    pub old_mode: Option<EntryMode>,
+    /// The mode of the file after the change, if known.
+    pub new_mode: Option<EntryMode>,
+    /// What happens to the file.
+    pub kind: Kind,
+    /// The possibly abbreviated id of the blob before the change, as seen in the `index` header line.
+    ///
+    /// It's `None` if the file is added or if the header line is missing.
+    pub old_id: Option<gix_hash::Prefix>,
+    /// The possibly abbreviated id of the blob after the change, as seen in the `index` header line.
+    ///
+    /// It's `None` if the file is deleted or if the header line is missing.
+    pub new_id: Option<gix_hash::Prefix>,
+    /// The change to the content of the file.
+    pub body: Body,
+}
+
+impl Patch {
+    /// Return the path that the patch is about, which is the new path unless the file is deleted.
+    pub fn path(&self) -> &BString {
+        self.new_path
+            .as_ref()
+            .or(self.old_path.as_ref())
+            .expect("parsing assures that at least one path is set")
+    }
+}
+
+/// What a [`Patch`] does to its file.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Kind {
+    /// The file is changed in place, which includes changes to its mode only.
+    Modify,
+    /// The file is newly added.
+    Add,
+    /// The file is deleted.
+    Delete,
+    /// The file is moved to a new location, possibly with changes to its content.
+    Rename {
+        /// The similarity between the old and the new content in percent, if known.
+        similarity: Option<u8>,
+    },
+    /// The file is copied to a new location, possibly with changes to its content.
+    Copy {
+        /// The similarity between the old and the new content in percent, if known.
+        similarity: Option<u8>,
+    },
+}
+
+/// The change to the content of a file, as part of a [`Patch`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Body {
+    /// The content doesn't change, which happens if only the mode or the location of the file changes.
+    Empty,
+    /// The content changes as described by the given hunks of a unified diff, in order.
+    Text(Vec<Hunk>),
+    /// The content is binary and changes as described.
+    ///
+    /// It's `None` if the patch only says `Binary files … differ`, as produced by `git diff` without `--binary`,
+    /// which can't be applied.
+    Binary(Option<Binary>),
+}
+
+/// A hunk of a unified diff, which replaces lines starting at one position with other lines.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Hunk {
+    /// The 1-based line number at which the hunk starts in the old content, or 0 if the old content is empty.
+    pub old_start: u32,
+    /// The amount of lines the hunk spans in the old content.
+    pub old_lines: u32,
+    /// The 1-based line number at which the hunk starts in the new content, or 0 if the new content is empty.
+    pub new_start: u32,
+    /// The amount of lines the hunk spans in the new content.
+    pub new_lines: u32,
+    /// All lines of the hunk, in order.
+    pub lines: Vec<Line>,
+}
+
+/// A line of a [`Hunk`].
+///
+/// The line contains the exact bytes of the line, including its line ending unless the line is the last one
+/// of a file that doesn't end with a newline.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Line {
+    /// A line that is present in both the old and the new content.
+    Context(BString),
+    /// A line that is only present in the old content.
+    Delete(BString),
+    /// A line that is only present in the new content.
+    Insert(BString),
+}
+
+impl Line {
+    /// Return the content of the line, without its marker.
+    pub fn content(&self) -> &BString {
+        match self {
+            Line::Context(line) | Line::Delete(line) | Line::Insert(line) => line,
+        }
+    }
+}
+
+/// The data of a binary patch, as produced by `git diff --binary`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Binary {
+    /// The hunk to turn the old content into the new content.
+    pub forward: BinaryHunk,
+    /// The hunk to turn the new content into the old content, if present.
+    pub reverse: Option<BinaryHunk>,
+}
+
+/// A hunk of a binary patch, which is already decoded and decompressed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BinaryHunk {
+    /// The full new content.
+    Literal(Vec<u8>),
+    /// A delta in the format used in packs, to be applied to the old content with [`binary::apply_delta()`](crate::binary::apply_delta()).
+    Delta(Vec<u8>),
+}
@@ -0,0 +1,170 @@
+use gix_apply::apply::{self, Options};
+use gix_hash::ObjectId;
+use gix_merge::blob::Resolution;
+use gix_object::FindExt;
+
+use crate::{
+    fixture, parse, read, tree,
+    utils::{expand_prefix, odb},
+};
+
+type Odb = gix_odb::memory::Proxy<gix_odb::Handle>;
+
+fn apply_tree(
+    odb: &Odb,
+    tree: ObjectId,
+    patches: &[gix_apply::Patch],
+    options: Options,
+) -> crate::Result<apply::Outcome> {
+    Ok(apply::tree(tree, patches, odb, &mut expand_prefix(odb), &options).map_err(gix_error::Exn::into_error)?)
+}
+
+/// Return the content of `path` in `tree`.
+fn blob(odb: &Odb, tree: ObjectId, path: &str) -> crate::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let entry = odb
+        .find_tree(&tree, &mut buf)?
+        .to_owned()
+        .entries
+        .into_iter()
+        .find(|entry| entry.filename == path)
+        .expect("present");
+    Ok(odb.find_blob(&entry.oid, &mut buf)?.data.to_vec())
+}
+
+#[test]
+fn tree_with_all_kinds_of_changes_like_git() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let base = tree(&root, &odb, "refs/tags/base")?;
+    let patches = parse(&root, "all.patch")?;
+
+    let out = apply_tree(&odb, base, &patches, Options::default())?;
+    assert_eq!(out.tree, tree(&root, &odb, "refs/heads/changed")?);
+    assert!(out.merged.is_empty() && out.conflicts.is_empty());
+
+    let err = apply_tree(&odb, out.tree, &patches, Options::default()).expect_err("already applied");
+    assert!(format!("{err:?}").contains("'added' already exists in tree"), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn hunks_are_found_at_an_offset() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let patches = parse(&root, "text.patch")?;
+    let out = apply_tree(
+        &odb,
+        tree(&root, &odb, "refs/heads/shifted")?,
+        &patches,
+        Options::default(),
+    )?;
+    assert_eq!(out.tree.to_string(), read_expected(&root, "shifted.expected")?);
+    Ok(())
+}
+
+#[test]
+fn context_can_be_reduced() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let patches = parse(&root, "text.patch")?;
+    let fuzzed = tree(&root, &odb, "refs/heads/fuzzed")?;
+    for min_context in [None, Some(3)] {
+        let options = Options {
+            min_context,
+            ..Default::default()
+        };
+        let err = apply_tree(&odb, fuzzed, &patches, options).expect_err("context doesn't match");
+        assert!(format!("{err:?}").contains("Hunk #1 of the patch for 'text' does not apply"));
+    }
+
+    let options = Options {
+        min_context: Some(2),
+        ..Default::default()
+    };
+    let out = apply_tree(&odb, fuzzed, &patches, options)?;
+    assert_eq!(
+        out.tree.to_string(),
+        read_expected(&root, "fuzzed.expected")?,
+        "same as `git apply -C2`"
+    );
+    Ok(())
+}
+
+#[test]
+fn three_way_merge_like_git() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let patches = parse(&root, "text.patch")?;
+    let three_way = Options {
+        three_way: true,
+        ..Default::default()
+    };
+
+    let diverged = tree(&root, &odb, "refs/heads/diverged")?;
+    assert!(
+        apply_tree(&odb, diverged, &patches, Options::default()).is_err(),
+        "the patch doesn't apply as its context changed"
+    );
+    let current = blob(&odb, diverged, "text")?;
+    let out = apply::content(&patches[0], &current, &odb, &mut expand_prefix(&odb), &three_way)
+        .map_err(gix_error::Exn::into_error)?;
+    assert_eq!(out.data, read(&root, "diverged.expected")?);
+    let merge = out.three_way.as_ref().expect("merged");
+    assert_eq!(merge.resolution, Resolution::Complete);
+    assert!(
+        patches[0].old_id.is_some_and(|id| id.cmp_oid(&merge.base).is_eq()),
+        "the base is the blob the patch was created from"
+    );
+    assert_eq!(
+        merge.theirs,
+        blob(&odb, tree(&root, &odb, "refs/heads/text-changed")?, "text")?,
+        "their side is the result of applying the patch to its original"
+    );
+
+    let out = apply_tree(&odb, diverged, &patches, three_way)?;
+    assert_eq!(out.merged, ["text"]);
+    assert!(out.conflicts.is_empty());
+
+    let conflicting = tree(&root, &odb, "refs/heads/conflicting")?;
+    let out = apply_tree(&odb, conflicting, &patches, three_way)?;
+    assert_eq!(out.merged, ["text"]);
+    assert_eq!(out.conflicts, ["text"]);
+    assert_eq!(
+        blob(&odb, out.tree, "text")?,
+        read(&root, "conflicting.expected")?,
+        "conflict markers are the same as the ones of `git apply --3way`"
+    );
+    Ok(())
+}
+
+#[test]
+fn three_way_needs_the_original_blob() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let patches = parse(&root, "text.patch")?;
+    let options = Options {
+        three_way: true,
+        ..Default::default()
+    };
+    let err = apply::content(&patches[0], b"unrelated\n", &odb, &mut |_| None, &options)
+        .expect_err("there is no blob to merge with");
+    assert!(format!("{:?}", err.into_error()).contains("the blob to merge it with is unavailable"));
+    Ok(())
+}
+
+#[test]
+fn binary_patches_verify_their_preimage() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let patches = parse(&root, "all.patch")?;
+    let binary = &patches[1];
+    let err = apply::content(binary, b"other content", &odb, &mut |_| None, &Options::default())
+        .expect_err("content doesn't match");
+    assert!(format!("{:?}", err.into_error()).contains("does not apply to 'binary.bin' as its content differs"));
+    Ok(())
+}
+
+fn read_expected(root: &std::path::Path, name: &str) -> crate::Result<String> {
+    Ok(String::from_utf8(read(root, name)?)?.trim().to_owned())
+}
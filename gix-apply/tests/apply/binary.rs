@@ -0,0 +1,50 @@
+use gix_apply::binary;
+
+#[test]
+fn decode_line() -> crate::Result {
+    let mut out = Vec::new();
+    binary::decode_line(b"HcmV?d00001", &mut out).map_err(gix_error::Exn::into_error)?;
+    assert_eq!(out.len(), 8, "'H' is the 8th letter");
+    assert_eq!(
+        binary::inflate(&out, 0).map_err(gix_error::Exn::into_error)?,
+        b"",
+        "this is how Git encodes empty content"
+    );
+
+    for (line, expected) in [
+        (&b""[..], "must not be empty"),
+        (b"Acm", "should have 5 characters to encode 1 bytes, got 2"),
+        (b"A\"\"\"\"\"", "Invalid base85 character"),
+        (b"A~~~~~", "Invalid base85 sequence"),
+        (b"1cmV?d", "Invalid length character"),
+    ] {
+        let err = binary::decode_line(line, &mut out).expect_err("invalid");
+        let err = format!("{:?}", err.into_error());
+        assert!(err.contains(expected), "{err}");
+    }
+    Ok(())
+}
+
+#[test]
+fn apply_delta() -> crate::Result {
+    let base = b"hello world";
+    // Base size 11, result size 10, copy 6 bytes at offset 0, insert "then".
+    let delta = b"\x0b\x0a\x90\x06\x04then";
+    assert_eq!(
+        binary::apply_delta(base, delta).map_err(gix_error::Exn::into_error)?,
+        b"hello then"
+    );
+
+    for (delta, expected) in [
+        (&b"\x0a\x0a"[..], "expects 10 bytes of base content, got 11"),
+        (b"\x0b\x0a\x90\x06", "should produce 10 bytes, got 6"),
+        (b"\x0b\x0a\x91\x08\x06", "Corrupt binary delta"),
+        (b"\x0b\x0a\x00", "Corrupt binary delta"),
+        (b"\x0b\x0a\x04abc", "Corrupt binary delta"),
+    ] {
+        let err = binary::apply_delta(base, delta).expect_err("invalid");
+        let err = format!("{:?}", err.into_error());
+        assert!(err.contains(expected), "{err}");
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+pub use gix_testtools::Result;
+
+mod apply;
+mod binary;
+mod parse;
+#[path = "../../../gix-merge/tests/utils/mod.rs"]
+pub mod utils;
+
+fn fixture() -> Result<PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_apply_repo.sh")
+}
+
+/// Read the file `name` which was produced by the fixture script next to the repository.
+fn read(root: &Path, name: &str) -> Result<Vec<u8>> {
+    Ok(std::fs::read(root.join(name))?)
+}
+
+fn parse(root: &Path, name: &str) -> Result<Vec<gix_apply::Patch>> {
+    Ok(gix_apply::parse(&read(root, name)?, gix_testtools::object_hash()).map_err(gix_error::Exn::into_error)?)
+}
+
+/// Return the tree of the commit the loose reference `name`, like `refs/heads/main`, points to.
+fn tree(root: &Path, odb: &impl gix_object::Find, name: &str) -> Result<ObjectId> {
+    let hex = std::fs::read_to_string(root.join("repo/.git").join(name))?;
+    let id = ObjectId::from_hex(hex.trim().as_bytes())?;
+    let mut buf = Vec::new();
+    Ok(odb.find_commit(&id, &mut buf)?.tree())
+}
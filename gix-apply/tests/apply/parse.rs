@@ -0,0 +1,162 @@
+use bstr::ByteSlice;
+use gix_apply::patch::{BinaryHunk, Body, Kind, Line};
+use gix_object::tree::{EntryKind, EntryMode};
+
+use crate::{fixture, parse};
+
+#[test]
+fn all_kinds_of_changes() -> crate::Result {
+    let root = fixture()?;
+    let patches = parse(&root, "all.patch")?;
+    let paths: Vec<_> = patches
+        .iter()
+        .map(|patch| patch.path().to_str_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "added",
+            "binary.bin",
+            "copied",
+            "dir/nested",
+            "exe",
+            "new.bin",
+            "no-newline",
+            "remove-me",
+            "renamed",
+            "spaced name",
+            "text",
+            "ümlaut"
+        ]
+    );
+    let blob = Some(EntryMode::from(EntryKind::Blob));
+
+    let added = &patches[0];
+    assert_eq!(added.kind, Kind::Add);
+    assert_eq!((added.old_path.as_ref(), added.new_mode), (None, blob));
+    assert_eq!(added.old_id, None);
+    assert_eq!(added.new_id.map(|id| id.hex_len()), Some(7), "ids are abbreviated");
+
+    let Body::Binary(Some(binary)) = &patches[1].body else {
+        panic!("binary patches are decoded")
+    };
+    assert!(matches!(binary.forward, BinaryHunk::Delta(_)));
+    assert!(matches!(binary.reverse, Some(BinaryHunk::Delta(_))));
+    assert_eq!(
+        patches[1].old_id.map(|id| id.hex_len()),
+        Some(gix_testtools::object_hash().len_in_hex()),
+        "binary patches have full ids"
+    );
+
+    let copied = &patches[2];
+    assert_eq!(copied.kind, Kind::Copy { similarity: Some(85) });
+    assert_eq!(copied.old_path.as_ref().map(|p| p.as_bstr()), Some("rename-me".into()));
+
+    let exe = &patches[4];
+    assert_eq!(exe.kind, Kind::Modify);
+    assert_eq!(
+        (exe.old_mode, exe.new_mode, &exe.body),
+        (blob, Some(EntryKind::BlobExecutable.into()), &Body::Empty)
+    );
+
+    let Body::Binary(Some(binary)) = &patches[5].body else {
+        panic!("binary patches are decoded")
+    };
+    assert_eq!(binary.forward, BinaryHunk::Literal(b"\xff\xfenew binary\0".to_vec()));
+    assert_eq!(binary.reverse, Some(BinaryHunk::Literal(Vec::new())));
+
+    let Body::Text(hunks) = &patches[6].body else {
+        panic!("text patch")
+    };
+    assert_eq!(
+        hunks[0].lines,
+        [
+            Line::Delete("no newline".into()),
+            Line::Insert("no newline, changed".into())
+        ],
+        "missing newlines at the end of files are respected"
+    );
+
+    assert_eq!(patches[7].kind, Kind::Delete);
+    assert_eq!(patches[7].new_path, None);
+    assert_eq!(patches[8].kind, Kind::Rename { similarity: Some(92) });
+
+    let Body::Text(hunks) = &patches[10].body else {
+        panic!("text patch")
+    };
+    assert_eq!(hunks.len(), 2);
+    assert_eq!(
+        (
+            hunks[1].old_start,
+            hunks[1].old_lines,
+            hunks[1].new_start,
+            hunks[1].new_lines
+        ),
+        (22, 7, 22, 7)
+    );
+    assert_eq!(hunks[1].lines[3], Line::Delete("25\n".into()));
+    Ok(())
+}
+
+#[test]
+fn text_around_patches_is_ignored() -> crate::Result {
+    let root = fixture()?;
+    let patch = crate::read(&root, "text.patch")?;
+    let mut mail = b"From 1234 Mon Sep 17 00:00:00 2001\nSubject: [PATCH] change 15\n\n---\n text | 2 +-\n\n".to_vec();
+    mail.extend_from_slice(&patch);
+    mail.extend_from_slice(b"-- \n2.50.0\n\n");
+    let patches = gix_apply::parse(&mail, gix_testtools::object_hash()).map_err(gix_error::Exn::into_error)?;
+    assert_eq!(patches, parse(&root, "text.patch")?);
+    Ok(())
+}
+
+#[test]
+fn hunk_headers_without_line_counts() -> crate::Result {
+    let patches = gix_apply::parse(
+        b"diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n+b\n",
+        gix_testtools::object_hash(),
+    )
+    .map_err(gix_error::Exn::into_error)?;
+    let Body::Text(hunks) = &patches[0].body else {
+        panic!("text patch")
+    };
+    assert_eq!((hunks[0].old_lines, hunks[0].new_lines), (1, 1));
+    assert_eq!(patches[0].old_id, None, "the index line is optional");
+    Ok(())
+}
+
+#[test]
+fn errors() {
+    for (input, expected) in [
+        (&b"just text\n"[..], "No valid patches in input"),
+        (
+            b"diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n-a\n+b\n",
+            "is truncated",
+        ),
+        (
+            b"diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n-a\n*b\n",
+            "Corrupt patch at line 6",
+        ),
+        (
+            b"diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1 +1\n-a\n+b\n",
+            "Invalid hunk header",
+        ),
+        (
+            b"diff --git a/f b/f\n@@ -1 +1 @@\n-a\n+b\n",
+            "must be preceded by '---' and '+++'",
+        ),
+        (b"diff --git a/f b/f\nnew file mode 10064x\n", "Invalid file mode"),
+        (
+            b"diff --git a/f b/f\nindex 123..4567\n",
+            "Invalid object id '123' in index line",
+        ),
+        (
+            b"diff --git a/f b/f\nGIT binary patch\nliteral 3\nAB\n\n",
+            "Invalid binary data at line 4",
+        ),
+    ] {
+        let err = gix_apply::parse(input, gix_testtools::object_hash()).expect_err("invalid input");
+        let err = format!("{:?}", err.into_error());
+        assert!(err.contains(expected), "{err}");
+    }
+}
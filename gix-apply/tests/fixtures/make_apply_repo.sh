@@ -0,0 +1,79 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+cd repo
+git checkout -q -b main
+
+seq 1 30 > text
+seq 1 5 > remove-me
+seq 1 20 > rename-me
+seq 1 20 > copy-me
+printf 'no newline' > no-newline
+echo exe > exe
+mkdir dir
+echo nested > dir/nested
+echo spaced > "spaced name"
+echo umlaut > "ümlaut"
+{ printf '\000\001\002\003'; seq 1 2000; } > binary.bin
+git add .
+git commit -q -m base
+git tag base
+
+git checkout -q -b changed
+sed -i.bak -e 's/^3$/three/' -e 's/^25$/twenty-five/' text && rm text.bak
+git rm -q remove-me
+git mv rename-me renamed
+sed -i.bak 's/^10$/ten/' renamed && rm renamed.bak
+sed 's/^15$/fifteen/' copy-me > copied
+printf 'no newline, changed' > no-newline
+chmod +x exe
+echo changed >> dir/nested
+echo changed >> "spaced name"
+echo changed >> "ümlaut"
+{ printf '\000\001\002\003'; seq 1 2000 | sed 's/^1000$/thousand/'; } > binary.bin
+printf '\377\376new binary\000' > new.bin
+echo added > added
+git add .
+git commit -q -m changed
+git diff --binary -M -C --find-copies-harder base changed > ../all.patch
+
+# A change to `text` only, to be applied to variations of it.
+git checkout -q -b text-changed base
+sed -i.bak 's/^15$/fifteen/' text && rm text.bak
+git commit -q -am "change 15"
+git diff base text-changed > ../text.patch
+
+git checkout -q -b shifted base
+{ seq 101 105; cat text; } > text.tmp && mv text.tmp text
+git commit -q -am "insert lines at the top"
+git apply --cached ../text.patch
+git write-tree > ../shifted.expected
+git reset -q --hard
+
+git checkout -q -b fuzzed base
+sed -i.bak 's/^12$/twelve/' text && rm text.bak
+git commit -q -am "change 12"
+if git apply --cached ../text.patch 2>/dev/null; then
+  echo "the patch must not apply without fuzz" >&2
+  exit 1
+fi
+git apply --cached -C2 ../text.patch 2>/dev/null
+git write-tree > ../fuzzed.expected
+git reset -q --hard
+
+git checkout -q -b diverged base
+sed -i.bak 's/^18$/eighteen/' text && rm text.bak
+git commit -q -am "change 18"
+git apply --3way ../text.patch 2>/dev/null
+cp text ../diverged.expected
+git reset -q --hard
+
+git checkout -q -b conflicting base
+sed -i.bak 's/^15$/FIFTEEN/' text && rm text.bak
+git commit -q -am "change 15 differently"
+git apply --3way ../text.patch 2>/dev/null || true
+cp text ../conflicting.expected
+git reset -q --hard
+
+git checkout -q main
@@ -70,6 +70,60 @@ mod access {
             })
         }
 
+        /// Create a bitmap from `words`, where bit `n` is stored in `words[n / 64]` as `1 << (n % 64)`.
+        ///
+        /// Runs of words whose bits are all unset or all set are compressed, and trailing words without set bits
+        /// are omitted, just like `git` does it. The bit length is thus always a multiple of 64.
+        ///
+        /// Returns `None` if the amount of bits exceeds `u32::MAX`.
+        pub fn from_words(words: &[u64]) -> Option<Self> {
+            let words = match words.iter().rposition(|word| *word != 0) {
+                Some(last_set) => &words[..=last_set],
+                None => &[0],
+            };
+            let num_bits = u32::try_from(words.len().checked_mul(64)?).ok()?;
+
+            let mut bits = std::vec::Vec::new();
+            let mut rlw;
+            let mut remaining = words;
+            loop {
+                rlw = bits.len();
+                bits.push(0);
+
+                let (run_bit, run_len) = match remaining.first() {
+                    Some(&word @ (0 | u64::MAX)) => (
+                        u64::from(word == u64::MAX),
+                        remaining
+                            .iter()
+                            .take(RLW_LARGEST_RUNNING_COUNT as usize)
+                            .take_while(|w| **w == word)
+                            .count(),
+                    ),
+                    _ => (0, 0),
+                };
+                remaining = &remaining[run_len..];
+
+                let num_literals = remaining
+                    .iter()
+                    .take(RLW_LARGEST_LITERAL_COUNT as usize)
+                    .take_while(|word| !matches!(**word, 0 | u64::MAX))
+                    .count();
+                bits.extend_from_slice(&remaining[..num_literals]);
+                remaining = &remaining[num_literals..];
+
+                bits[rlw] = run_bit | ((run_len as u64) << 1) | ((num_literals as u64) << (1 + RLW_RUNNING_BITS));
+                if remaining.is_empty() {
+                    break;
+                }
+            }
+
+            Some(Vec {
+                num_bits,
+                bits,
+                rlw: rlw as u64,
+            })
+        }
+
         /// Write the bitmap as EWAH bytes to `out`.
         ///
         /// These bytes can be parsed again with [`decode()`](super::decode()).
@@ -141,6 +195,11 @@ mod access {
             Some(())
         }
 
+        /// The amount of 64-bit words used to store the compressed bits, which determines the size of the encoded bitmap.
+        pub fn num_words(&self) -> usize {
+            self.bits.len()
+        }
+
         /// The amount of bits we are currently holding.
         pub fn num_bits(&self) -> usize {
             self.num_bits.try_into().expect("we are not on 16 bit systems")
@@ -169,6 +228,7 @@ mod access {
 
     const RLW_RUNNING_BITS: u64 = 4 * 8;
     const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - 1 - RLW_RUNNING_BITS)) - 1;
 }
 
 /// A growable collection of u64 that are seen as stream of individual bits.
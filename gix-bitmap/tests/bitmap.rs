@@ -97,6 +97,51 @@ mod fuzzed {
         }
     }
 
+    #[test]
+    fn compressed_bitmaps_preserve_all_set_bits() {
+        for (words, expected_num_words) in [
+            (vec![], 1),
+            (vec![0, 0], 1),
+            (vec![0b101], 2),
+            (vec![0, 0, 0, u64::MAX, u64::MAX, 0b11, 0, 1 << 63], 5),
+            (vec![u64::MAX, 42, 0, 0, 0, 0, 0], 2),
+            (
+                (0..1000).map(|idx| if idx % 100 == 0 { u64::MAX } else { 0 }).collect(),
+                19,
+            ),
+        ] {
+            let bitmap = gix_bitmap::ewah::Vec::from_words(&words).expect("small test fixtures must fit into u32");
+            let mut encoded = Vec::new();
+            bitmap
+                .write_to(&mut encoded)
+                .expect("writing a valid test fixture to bytes must succeed");
+            assert_eq!(
+                encoded.len(),
+                4 + 4 + expected_num_words * 8 + 4,
+                "runs of words are compressed"
+            );
+
+            let (bitmap, rest) = gix_bitmap::ewah::decode(&encoded).expect("serialized test fixture must decode");
+            assert!(rest.is_empty(), "serialized test fixture should be fully consumed");
+            let mut actual = Vec::new();
+            assert_eq!(
+                bitmap.for_each_set_bit(|idx| {
+                    actual.push(idx);
+                    Some(())
+                }),
+                Some(()),
+                "well-formed compressed bitmaps must iterate successfully"
+            );
+            let expected: Vec<_> = (0..words.len() * 64)
+                .filter(|idx| words[idx / 64] & (1 << (idx % 64)) != 0)
+                .collect();
+            assert_eq!(
+                actual, expected,
+                "iteration should report exactly the set bits from the source words"
+            );
+        }
+    }
+
     #[test]
     fn zero_padding_bits_in_last_literal_word_are_accepted() {
         let bitmap =
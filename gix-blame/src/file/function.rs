@@ -24,6 +24,7 @@ use crate::{BlameEntry, Error, Options, Outcome, Statistics, types::BlamePathEnt
 ///    - The first commit to be responsible for parts of `file_path`.
 /// * `cache`
 ///    - Optionally, the commitgraph cache.
+///    - If it contains changed-path Bloom filters, commits that didn't change `file_path` are skipped without looking at their trees.
 /// * `resource_cache`
 ///    - Used for diffing trees.
 /// * `file_path`
@@ -234,6 +235,16 @@ pub fn file(
             }
         }
 
+        if let Some((parent_id, parent_commit_time)) = parent_ids.first() {
+            if is_unchanged_in_first_parent(cache.as_ref(), &suspect, current_file_path.as_ref()) {
+                stats.commits_skipped_by_bloom_filter += 1;
+                previous_entry = Some((*parent_id, entry_id));
+                pass_blame_from_to(suspect, *parent_id, &mut hunks_to_blame);
+                queue.insert(*parent_commit_time, *parent_id);
+                continue 'outer;
+            }
+        }
+
         for (pid, (parent_id, parent_commit_time)) in parent_ids.iter().enumerate() {
             if let Some(parent_entry_id) = find_path_entry_in_commit(
                 &odb,
@@ -841,6 +852,29 @@ fn find_path_entry_in_commit(
     Ok(res.map(|e| e.oid))
 }
 
+/// Return `true` if the changed-path Bloom filter of `commit` in `cache` shows that `file_path` definitely wasn't changed
+/// compared to its first parent.
+fn is_unchanged_in_first_parent(
+    cache: Option<&gix_commitgraph::Graph>,
+    commit: &gix_hash::oid,
+    file_path: &BStr,
+) -> bool {
+    let Some(cache) = cache else {
+        return false;
+    };
+    let (Some(settings), Some(filter)) = (
+        cache.bloom_filter_settings(),
+        cache
+            .commit_by_id(commit)
+            .and_then(|commit| commit.changed_paths_filter()),
+    ) else {
+        return false;
+    };
+    !gix_commitgraph::bloom::Key::for_path_and_leading_directories(file_path, &settings)
+        .iter()
+        .all(|key| filter.contains(key))
+}
+
 type ParentIds = SmallVec<[(gix_hash::ObjectId, i64); 2]>;
 
 fn collect_parents(
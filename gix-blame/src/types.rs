@@ -211,6 +211,9 @@ pub struct Statistics {
     pub commits_traversed: usize,
     /// The amount of trees that were decoded to find the entry of the file to blame.
     pub trees_decoded: usize,
+    /// The amount of commits whose changed-path Bloom filter in the commit-graph showed that the file to blame
+    /// wasn't changed compared to their first parent, which saved looking it up in their trees.
+    pub commits_skipped_by_bloom_filter: usize,
     /// The amount of tree-diffs to see if the filepath was added, deleted or modified. These diffs
     /// are likely partial as they are cancelled as soon as a change to the blamed file is
     /// detected.
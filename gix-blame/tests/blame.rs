@@ -395,6 +395,65 @@ fn file_that_was_added_in_two_branches() -> gix_testtools::Result {
     Ok(())
 }
 
+#[test]
+fn changed_path_filters_of_the_commit_graph_yield_the_same_result() -> gix_testtools::Result {
+    let worktree = gix_testtools::scripted_fixture_writable("make_blame_repo.sh")?;
+    let status = std::process::Command::new("git")
+        .args([
+            "commit-graph",
+            "write",
+            "--no-progress",
+            "--reachable",
+            "--changed-paths",
+        ])
+        .current_dir(worktree.path())
+        .env_remove("GIT_DIR")
+        .status()?;
+    assert!(status.success());
+
+    let mut commits_skipped_by_bloom_filter = 0;
+    for case in [
+        "simple",
+        "multiline-hunks",
+        "sub-directory/sub-directory",
+        "after-second-rename",
+        "resolved-conflict",
+        "file-changed-in-two-branches",
+    ] {
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::for_worktree_path(worktree.path().to_owned())?;
+        let cache = gix_commitgraph::Graph::from_info_dir(&worktree.path().join(".git/objects/info"))
+            .map_err(gix_error::Exn::into_error)?;
+        let source_file_name: gix_object::bstr::BString = format!("{case}.txt").into();
+        let outcome = gix_blame::file(
+            &odb,
+            suspect,
+            Some(cache),
+            &mut resource_cache,
+            source_file_name.as_ref(),
+            gix_blame::Options {
+                diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                ranges: BlameRanges::default(),
+                since: None,
+                rewrites: Some(gix_diff::Rewrites::default()),
+                debug_track_path: false,
+            },
+        )?;
+        commits_skipped_by_bloom_filter += outcome.statistics.commits_skipped_by_bloom_filter;
+
+        let baseline = Baseline::collect(worktree.path().join(format!(".git/{case}.baseline")), source_file_name)?;
+        pretty_assertions::assert_eq!(outcome.entries, baseline, "{case}");
+    }
+    assert!(
+        commits_skipped_by_bloom_filter > 0,
+        "most commits don't touch the blamed files, so their trees don't have to be looked at"
+    );
+    Ok(())
+}
+
 #[test]
 fn since() -> gix_testtools::Result {
     let Fixture {
@@ -1,4 +1,4 @@
-use crate::{File, Graph, Position, file, file::Commit};
+use crate::{File, Graph, Position, bloom, file, file::Commit};
 
 /// Access
 impl Graph {
@@ -52,6 +52,18 @@ impl Graph {
     pub fn num_commits(&self) -> u32 {
         self.files.iter().map(File::num_commits).sum()
     }
+
+    /// Returns `true` if all commits in this graph have a [corrected commit date](Commit::corrected_commit_date()),
+    /// or `false` if only their topological [generation](Commit::generation()) is available.
+    pub fn has_corrected_commit_dates(&self) -> bool {
+        self.files.first().has_generation_data()
+    }
+
+    /// Returns the settings of the changed-path Bloom filters of the most recent file that contains them, for use when
+    /// computing [keys](bloom::Key) to query [filters](Commit::changed_paths_filter()) with.
+    pub fn bloom_filter_settings(&self) -> Option<bloom::Settings> {
+        self.files.iter().rev().find_map(File::bloom_filter_settings)
+    }
 }
 
 /// Access fundamentals
@@ -0,0 +1,182 @@
+//! Changed-path Bloom filters, which allow to learn quickly if a path was *not* changed by a commit compared to its first parent.
+//!
+//! Each filter is a set of bits, with the bits set that correspond to the [keys](Key) of all paths changed by a commit,
+//! including their leading directories. If one of the bits of a key isn't set in a filter, the path definitely wasn't
+//! changed, which allows to avoid tree-diffs during path-limited history traversals.
+use bstr::{BStr, ByteSlice};
+
+/// The amount of changed paths above which `git` doesn't store their keys anymore, but a filter that contains everything.
+pub const MAX_CHANGED_PATHS: usize = 512;
+
+const BITS_PER_WORD: u64 = 8;
+const SEED0: u32 = 0x293a_e76f;
+const SEED1: u32 = 0x7e64_6e2c;
+
+/// The parameters used to compute the keys of a filter, as stored in the Bloom Data (BDAT) chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Settings {
+    /// The version of the hash function used to compute keys.
+    ///
+    /// Version `1` mishandles bytes larger than `0x7f` like `git` does, and version `2` is correct murmur3.
+    pub hash_version: u32,
+    /// The amount of hashes that make up the key of each path.
+    pub num_hashes: u32,
+    /// The amount of bits per changed path in each filter.
+    pub bits_per_entry: u32,
+}
+
+impl Default for Settings {
+    /// The settings `git` uses by default.
+    fn default() -> Self {
+        Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10,
+        }
+    }
+}
+
+/// The key of a path, to see if it's [contained](Filter::contains()) in a filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key {
+    hashes: Vec<u32>,
+    settings: Settings,
+}
+
+impl Key {
+    /// Compute the key of `path` for use in filters computed with `settings`.
+    pub fn new(path: &BStr, settings: &Settings) -> Self {
+        let hash_version = settings.hash_version;
+        let hash0 = murmur3(SEED0, path, hash_version);
+        let hash1 = murmur3(SEED1, path, hash_version);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|idx| hash0.wrapping_add(idx.wrapping_mul(hash1)))
+                .collect(),
+            settings: *settings,
+        }
+    }
+
+    /// Compute the keys for `path` and all of its leading directories, which all have to be [contained](Filter::contains())
+    /// in a filter for the path to possibly have changed.
+    pub fn for_path_and_leading_directories(path: &BStr, settings: &Settings) -> Vec<Key> {
+        let mut keys = vec![Key::new(path, settings)];
+        let mut path = path;
+        while let Some(pos) = path.rfind_byte(b'/') {
+            path = path[..pos].as_bstr();
+            keys.push(Key::new(path, settings));
+        }
+        keys
+    }
+}
+
+/// A changed-path Bloom filter of a commit, as stored in a commit-graph file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter<'a> {
+    data: &'a [u8],
+    settings: Settings,
+}
+
+impl<'a> Filter<'a> {
+    pub(crate) fn new(data: &'a [u8], settings: Settings) -> Self {
+        Filter { data, settings }
+    }
+
+    /// Return `false` if the path of `key` definitely wasn't changed, or `true` if it might have been changed.
+    ///
+    /// Note that `true` is also returned if `key` was computed with different [`Settings`] than this filter.
+    pub fn contains(&self, key: &Key) -> bool {
+        if key.settings.hash_version != self.settings.hash_version
+            || key.settings.num_hashes != self.settings.num_hashes
+        {
+            return true;
+        }
+        let num_bits = self.data.len() as u64 * BITS_PER_WORD;
+        if num_bits == 0 {
+            return true;
+        }
+        key.hashes.iter().all(|hash| {
+            let bit = u64::from(*hash) % num_bits;
+            self.data[(bit / BITS_PER_WORD) as usize] & (1 << (bit % BITS_PER_WORD)) != 0
+        })
+    }
+
+    /// Return the settings that were used to compute this filter.
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Return the filter data as stored in the commit-graph.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Compute the data of a filter for a commit whose `changed_paths`, compared to its first parent or the empty tree,
+/// are given, using `settings`.
+///
+/// `changed_paths` are the paths to all changed blobs and submodules, without trees, similar to what `git diff --name-only`
+/// would yield. Their leading directories are added automatically.
+/// If there are more than [`MAX_CHANGED_PATHS`], a filter that contains every path is returned.
+pub fn filter_from_changed_paths<'a>(
+    changed_paths: impl IntoIterator<Item = &'a BStr>,
+    settings: &Settings,
+) -> Vec<u8> {
+    let mut paths = std::collections::HashSet::new();
+    for (idx, mut path) in changed_paths.into_iter().enumerate() {
+        if idx == MAX_CHANGED_PATHS {
+            return vec![0xff];
+        }
+        while paths.insert(path) {
+            let Some(pos) = path.rfind_byte(b'/') else { break };
+            path = path[..pos].as_bstr();
+        }
+    }
+
+    let num_bytes = (paths.len() as u64 * u64::from(settings.bits_per_entry)).div_ceil(BITS_PER_WORD);
+    let mut data = vec![0; num_bytes.max(1) as usize];
+    let num_bits = data.len() as u64 * BITS_PER_WORD;
+    for path in paths {
+        for hash in Key::new(path, settings).hashes {
+            let bit = u64::from(hash) % num_bits;
+            data[(bit / BITS_PER_WORD) as usize] |= 1 << (bit % BITS_PER_WORD);
+        }
+    }
+    data
+}
+
+/// The 32-bit murmur3 hash of `data` with `seed`, which in `hash_version` 1 sign-extends each byte like `git` used to do.
+fn murmur3(mut seed: u32, data: &[u8], hash_version: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let byte = |b: u8| {
+        if hash_version == 1 {
+            b as i8 as u32
+        } else {
+            u32::from(b)
+        }
+    };
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let k = byte(chunk[0]) | (byte(chunk[1]) << 8) | (byte(chunk[2]) << 16) | (byte(chunk[3]) << 24);
+        seed ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        seed = seed.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .enumerate()
+            .fold(0, |k, (idx, b)| k ^ (byte(*b) << (8 * idx)));
+        seed ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
@@ -4,8 +4,8 @@ use std::{
 };
 
 use crate::{
-    File,
-    file::{self, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, commit::Commit},
+    File, bloom,
+    file::{self, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, CORRECTED_DATE_OFFSET_OVERFLOW_MASK, commit::Commit},
 };
 
 /// Access
@@ -127,6 +127,57 @@ impl File {
         &self.data[start..][..entry_size]
     }
 
+    /// Returns the corrected commit date offset of the commit at `pos` as stored in the Generation Data (GDA2) chunk,
+    /// or `None` if there is no such chunk.
+    ///
+    /// Entries referring to the Generation Data Overflow (GDO2) chunk were validated when opening the file.
+    pub(crate) fn corrected_commit_date_offset(&self, pos: file::Position) -> Option<u64> {
+        let start = self.generation_data_offset? + pos.0 as usize * 4;
+        let offset = u32::from_be_bytes(self.data[start..][..4].try_into().expect("4 bytes"));
+        if offset & CORRECTED_DATE_OFFSET_OVERFLOW_MASK == 0 {
+            return Some(u64::from(offset));
+        }
+        let overflow = &self.data[self
+            .generation_data_overflow_range
+            .clone()
+            .expect("validated to be present if needed")];
+        let start = (offset & !CORRECTED_DATE_OFFSET_OVERFLOW_MASK) as usize * 8;
+        Some(u64::from_be_bytes(
+            overflow[start..][..8].try_into().expect("validated to be in bounds"),
+        ))
+    }
+
+    /// Returns the changed-path Bloom filter of the commit at `pos`, or `None` if there is none or if it is corrupt.
+    pub(crate) fn bloom_filter_at(&self, pos: file::Position) -> Option<bloom::Filter<'_>> {
+        let index_offset = self.bloom_filter_index_offset?;
+        let settings = self.bloom_filter_settings?;
+        let data = &self.data[self.bloom_filter_data_range.clone()?];
+        let read_end = |pos: usize| {
+            u32::from_be_bytes(self.data[index_offset + pos * 4..][..4].try_into().expect("4 bytes")) as usize
+        };
+        let pos = pos.0 as usize;
+        let start = if pos == 0 { 0 } else { read_end(pos - 1) };
+        let filter = data.get(start..read_end(pos))?;
+        // An empty filter means it wasn't computed, which is different from a filter with only unset bits.
+        (!filter.is_empty()).then(|| bloom::Filter::new(filter, settings))
+    }
+
+    /// Returns the settings of the changed-path Bloom filters stored in this file, or `None` if it doesn't contain any.
+    pub fn bloom_filter_settings(&self) -> Option<bloom::Settings> {
+        self.bloom_filter_settings
+    }
+
+    /// Returns true if this file contains corrected commit dates, i.e. generation numbers v2.
+    pub(crate) fn has_generation_data(&self) -> bool {
+        self.generation_data_offset.is_some()
+    }
+
+    /// Forget that this file contains corrected commit dates, as they can only be used if all files in the graph have them.
+    pub(crate) fn ignore_generation_data(&mut self) {
+        self.generation_data_offset = None;
+        self.generation_data_overflow_range = None;
+    }
+
     /// Returns the byte slice for this file's entire Extra Edge List (EDGE) chunk.
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
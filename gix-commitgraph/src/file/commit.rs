@@ -36,9 +36,6 @@ impl<'a> Commit<'a> {
             root_tree_id: gix_hash::oid::from_bytes_unchecked(&bytes[..file.hash_len]),
             parent1: ParentEdge::from_raw(read_u32(&bytes[file.hash_len..][..4])),
             parent2: ParentEdge::from_raw(read_u32(&bytes[file.hash_len + 4..][..4])),
-            // TODO: Add support for corrected commit date offset overflow.
-            //      See https://github.com/git/git/commit/e8b63005c48696a26f976f5f9b0ccaf1983e439d and
-            //          https://github.com/git/git/commit/f90fca638e99a031dce8e3aca72427b2f9b4bb38 for more details and hints at a test.
             generation: read_u32(&bytes[file.hash_len + 8..][..4]) >> 2,
             commit_timestamp: u64::from_be_bytes(bytes[file.hash_len + 8..][..8].try_into().unwrap())
                 & 0x0003_ffff_ffff,
@@ -60,6 +57,27 @@ impl<'a> Commit<'a> {
         self.generation
     }
 
+    /// Returns the corrected commit date of this commit, also known as generation number v2, if the commit-graph contains it.
+    ///
+    /// It's the committer timestamp, unless it's not larger than the corrected commit date of any parent, in which case it's
+    /// one more than the largest corrected commit date of its parents. This makes it work like a generation number even
+    /// if clocks of committers are skewed, while staying close to the commit date.
+    ///
+    /// Note that it's `None` if this file doesn't contain corrected commit dates, or if not all files of the owning
+    /// [Graph][crate::Graph] contain them, or if they are corrupt in any of these files.
+    /// Thus, it's `Some` for either all or none of the commits of a [Graph][crate::Graph].
+    pub fn corrected_commit_date(&self) -> Option<u64> {
+        self.file
+            .corrected_commit_date_offset(self.pos)
+            .map(|offset| self.commit_timestamp + offset)
+    }
+
+    /// Returns the changed-path Bloom filter of this commit, which tells if a path was definitely not changed compared to
+    /// its first parent, or `None` if the commit-graph doesn't contain one.
+    pub fn changed_paths_filter(&self) -> Option<crate::bloom::Filter<'a>> {
+        self.file.bloom_filter_at(self.pos)
+    }
+
     /// Returns an iterator over the parent positions for lookup in the owning [Graph][crate::Graph].
     pub fn iter_parents(self) -> Parents<'a> {
         // I didn't find a combinator approach that a) was as strict as ParentIterator, b) supported
@@ -3,10 +3,12 @@ use std::path::{Path, PathBuf};
 use gix_error::{ErrorExt, Exn, Message, ResultExt, message};
 
 use crate::{
-    File,
+    File, bloom,
     file::{
-        BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+        BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        CORRECTED_DATE_OFFSET_OVERFLOW_MASK, EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, GENERATION_DATA_CHUNK_ID,
+        GENERATION_DATA_OVERFLOW_CHUNK_ID, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
 };
 
@@ -130,6 +132,77 @@ impl File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        let generation_data_offset = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                let expected_size = commit_data_count as usize * 4;
+                if chunk_size != expected_size {
+                    return Err(message!("Commit-graph chunk {GENERATION_DATA_CHUNK_ID:?} has invalid size: expected chunk length {expected_size}, got {chunk_size}").raise())
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+        let generation_data_overflow_range = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_OVERFLOW_CHUNK_ID, |chunk_range| {
+                if chunk_range.len() % 8 != 0 {
+                    return Err(message!("Commit-graph chunk {GENERATION_DATA_OVERFLOW_CHUNK_ID:?} has invalid size: chunk size {} is not a multiple of 8", chunk_range.len()).raise())
+                }
+                Ok(chunk_range)
+            })
+            .ok()
+            .transpose()?;
+        // An offset that points to a missing entry of the overflow chunk makes the generation data unusable for the
+        // whole file, as mixing corrected commit dates with topological levels would break generation cutoffs.
+        let (generation_data_offset, generation_data_overflow_range) = match generation_data_offset {
+            Some(offset)
+                if !generation_data_is_consistent(
+                    &data[offset..][..commit_data_count as usize * 4],
+                    generation_data_overflow_range.as_ref().map_or(0, std::ops::Range::len),
+                ) =>
+            {
+                (None, None)
+            }
+            offset => (offset, generation_data_overflow_range),
+        };
+
+        let bloom_filter_index_offset = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                let expected_size = commit_data_count as usize * 4;
+                if chunk_size != expected_size {
+                    return Err(message!("Commit-graph chunk {BLOOM_FILTER_INDEX_CHUNK_ID:?} has invalid size: expected chunk length {expected_size}, got {chunk_size}").raise())
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+        let bloom_filter_data = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID, |chunk_range| {
+                if chunk_range.len() < BLOOM_FILTER_DATA_HEADER_LEN {
+                    return Err(message!("Commit-graph chunk {BLOOM_FILTER_DATA_CHUNK_ID:?} has invalid size: chunk size {} is smaller than its header", chunk_range.len()).raise())
+                }
+                let header = &data[chunk_range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                let read_u32 = |ofs: usize| u32::from_be_bytes(header[ofs..][..4].try_into().expect("4 bytes"));
+                let settings = bloom::Settings {
+                    hash_version: read_u32(0),
+                    num_hashes: read_u32(4),
+                    bits_per_entry: read_u32(8),
+                };
+                Ok((chunk_range.start + BLOOM_FILTER_DATA_HEADER_LEN..chunk_range.end, settings))
+            })
+            .ok()
+            .transpose()?
+            // Like `git`, ignore filters we don't know how to query.
+            .filter(|(_, settings)| matches!(settings.hash_version, 1 | 2) && settings.num_hashes > 0);
+        let (bloom_filter_index_offset, bloom_filter_data_range, bloom_filter_settings) =
+            match (bloom_filter_index_offset, bloom_filter_data) {
+                (Some(index_offset), Some((data_range, settings))) => {
+                    (Some(index_offset), Some(data_range), Some(settings))
+                }
+                _ => (None, None, None),
+            };
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(message!(
@@ -162,10 +235,15 @@ impl File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filter_data_range,
+            bloom_filter_index_offset,
+            bloom_filter_settings,
             commit_data_offset,
             data,
             extra_edges_list_range,
             fan,
+            generation_data_offset,
+            generation_data_overflow_range,
             oid_lookup_offset,
             path,
             hash_len: object_hash.len_in_bytes(),
@@ -201,3 +279,13 @@ fn read_fan(d: &[u8]) -> ([u32; FAN_LEN], usize) {
     }
     (fan, FAN_LEN * 4)
 }
+
+/// Return `true` if all corrected commit date offsets in `generation_data` that overflow refer to an entry
+/// within an overflow chunk of `overflow_len` bytes.
+fn generation_data_is_consistent(generation_data: &[u8], overflow_len: usize) -> bool {
+    generation_data.chunks_exact(4).all(|entry| {
+        let offset = u32::from_be_bytes(entry.try_into().expect("4 bytes"));
+        offset & CORRECTED_DATE_OFFSET_OVERFLOW_MASK == 0
+            || (offset & !CORRECTED_DATE_OFFSET_OVERFLOW_MASK) as usize * 8 + 8 <= overflow_len
+    })
+}
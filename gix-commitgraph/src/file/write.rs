@@ -0,0 +1,349 @@
+use std::{collections::HashMap, io::Write};
+
+use gix_error::{ErrorExt, Exn, Message, ResultExt, message};
+use gix_hash::ObjectId;
+
+use crate::{
+    File, GENERATION_NUMBER_MAX, Graph, MAX_COMMITS, bloom,
+    file::{
+        BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        CORRECTED_DATE_OFFSET_OVERFLOW_MASK, EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN,
+        GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID, HEADER_LEN, LAST_EXTENDED_EDGE_MASK, NO_PARENT,
+        OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    write::Commit,
+};
+
+/// The largest timestamp that can be stored, as it's limited to 34 bits.
+const MAX_TIMESTAMP: u64 = 0x0003_ffff_ffff;
+
+/// A parent as it is going to be written.
+#[derive(Clone, Copy)]
+enum Parent {
+    /// The position of a commit in the graph we write on top of.
+    Base(u32),
+    /// The index of a commit in the file we write.
+    Here(u32),
+}
+
+/// Writing
+impl File {
+    /// Write a commit-graph file containing `commits` to `out`, and return its checksum.
+    ///
+    /// If `base` is set, the file will be a layer on top of it, i.e. commits contained in `base` will be skipped and parents may
+    /// be contained in it. Otherwise, the parents of all `commits` have to be part of `commits` as well.
+    /// `object_hash` is the kind of hash used by all `commits`.
+    /// If `changed_paths` is set, the [changed-path filters](Commit::changed_paths_filter) of `commits` are written, which
+    /// must have been computed with these settings.
+    pub fn write_to(
+        mut commits: Vec<Commit>,
+        base: Option<&Graph>,
+        object_hash: gix_hash::Kind,
+        changed_paths: Option<bloom::Settings>,
+        out: &mut dyn std::io::Write,
+    ) -> Result<ObjectId, Exn<Message>> {
+        if let Some(base) = base {
+            if base.object_hash() != object_hash {
+                return Err(message!(
+                    "Cannot write commit-graph using hash {object_hash:?} on top of a graph using hash {:?}",
+                    base.object_hash()
+                )
+                .raise());
+            }
+            commits.retain(|c| base.lookup(c.id).is_none());
+        }
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+        if commits.len() > MAX_COMMITS as usize {
+            return Err(message!(
+                "Cannot write {} commits as a commit-graph file can only hold up to {MAX_COMMITS} commits",
+                commits.len()
+            )
+            .raise());
+        }
+        if let Some(commit) = commits.iter().find(|c| c.id.kind() != object_hash) {
+            return Err(message!("Commit {} does not use the hash {object_hash:?}", commit.id).raise());
+        }
+
+        let num_base_commits = base.map_or(0, Graph::num_commits);
+        let index_by_id: HashMap<_, _> = commits.iter().enumerate().map(|(idx, c)| (c.id, idx as u32)).collect();
+        let parents = commits
+            .iter()
+            .map(|commit| {
+                commit
+                    .parents
+                    .iter()
+                    .map(|parent| {
+                        index_by_id
+                            .get(parent)
+                            .map(|idx| Parent::Here(*idx))
+                            .or_else(|| base.and_then(|base| base.lookup(parent)).map(|pos| Parent::Base(pos.0)))
+                            .ok_or_else(|| {
+                                message!(
+                                    "Parent {parent} of commit {} is not part of the commit-graph",
+                                    commit.id
+                                )
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let timestamps: Vec<_> = commits
+            .iter()
+            .map(|c| c.committer_timestamp.min(MAX_TIMESTAMP))
+            .collect();
+        let generations = generations(&parents, &timestamps, base);
+        // Corrected commit dates can only be used if all files have them.
+        let write_generation_data = base.is_none_or(|base| base.files.iter().all(File::has_generation_data));
+        let corrected_date_offsets: Vec<_> = generations
+            .iter()
+            .zip(&timestamps)
+            .map(|(generation, timestamp)| generation.corrected_commit_date - timestamp)
+            .collect();
+        let num_offset_overflows = corrected_date_offsets
+            .iter()
+            .filter(|offset| **offset > u64::from(!CORRECTED_DATE_OFFSET_OVERFLOW_MASK))
+            .count();
+        let bloom_filter_data_len: usize = commits
+            .iter()
+            .filter_map(|c| c.changed_paths_filter.as_ref().map(Vec::len))
+            .sum();
+        if changed_paths.is_some() && u32::try_from(bloom_filter_data_len).is_err() {
+            return Err(message!(
+                "Cannot write {bloom_filter_data_len} bytes of changed-path filters as they are limited to 4GB"
+            )
+            .raise());
+        }
+        let num_extra_edges: usize = parents.iter().filter(|p| p.len() > 2).map(|p| p.len() - 1).sum();
+        let base_ids: Vec<_> = base
+            .map(|base| base.files.iter().map(|f| f.checksum().to_owned()).collect())
+            .unwrap_or_default();
+        let num_base_graphs = u8::try_from(base_ids.len()).or_raise(|| {
+            message!(
+                "A commit-graph can only have up to 255 base graphs, got {}",
+                base_ids.len()
+            )
+        })?;
+
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = gix_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (commits.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (commits.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        if write_generation_data {
+            cf.plan_chunk(GENERATION_DATA_CHUNK_ID, (commits.len() * 4) as u64);
+            if num_offset_overflows > 0 {
+                cf.plan_chunk(GENERATION_DATA_OVERFLOW_CHUNK_ID, (num_offset_overflows * 8) as u64);
+            }
+        }
+        if num_extra_edges > 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+        }
+        if changed_paths.is_some() {
+            cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, (commits.len() * 4) as u64);
+            cf.plan_chunk(
+                BLOOM_FILTER_DATA_CHUNK_ID,
+                (BLOOM_FILTER_DATA_HEADER_LEN + bloom_filter_data_len) as u64,
+            );
+        }
+        if !base_ids.is_empty() {
+            cf.plan_chunk(BASE_GRAPHS_LIST_CHUNK_ID, (base_ids.len() * hash_len) as u64);
+        }
+
+        let io_err = || message("Could not write commit-graph file");
+        let mut out = gix_hash::io::Write::new(out, object_hash);
+        out.write_all(SIGNATURE).or_raise(io_err)?;
+        out.write_all(&[
+            1, /* version */
+            object_hash as u8,
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            num_base_graphs,
+        ])
+        .or_raise(io_err)?;
+
+        let position = |parent: &Parent| match parent {
+            Parent::Base(pos) => *pos,
+            Parent::Here(idx) => num_base_commits + idx,
+        };
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN).or_raise(io_err)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => {
+                    let mut fan = [0u32; FAN_LEN];
+                    for commit in &commits {
+                        fan[usize::from(commit.id.first_byte())] += 1;
+                    }
+                    let mut total = 0;
+                    for count in fan {
+                        total += count;
+                        chunk_write.write_all(&total.to_be_bytes()).or_raise(io_err)?;
+                    }
+                }
+                OID_LOOKUP_CHUNK_ID => {
+                    for commit in &commits {
+                        chunk_write.write_all(commit.id.as_bytes()).or_raise(io_err)?;
+                    }
+                }
+                COMMIT_DATA_CHUNK_ID => {
+                    let mut num_extra_edges = 0u32;
+                    for (((commit, parents), generation), timestamp) in
+                        commits.iter().zip(&parents).zip(&generations).zip(&timestamps)
+                    {
+                        chunk_write.write_all(commit.tree.as_bytes()).or_raise(io_err)?;
+                        let parent1 = parents.first().map_or(NO_PARENT, position);
+                        let parent2 = match parents.len() {
+                            0 | 1 => NO_PARENT,
+                            2 => position(&parents[1]),
+                            _ => {
+                                let edge = EXTENDED_EDGES_MASK | num_extra_edges;
+                                num_extra_edges += (parents.len() - 1) as u32;
+                                edge
+                            }
+                        };
+                        for value in [
+                            parent1,
+                            parent2,
+                            (generation.level << 2) | (timestamp >> 32) as u32,
+                            *timestamp as u32,
+                        ] {
+                            chunk_write.write_all(&value.to_be_bytes()).or_raise(io_err)?;
+                        }
+                    }
+                }
+                GENERATION_DATA_CHUNK_ID => {
+                    let mut num_overflows = 0u32;
+                    for offset in &corrected_date_offsets {
+                        let value = match u32::try_from(*offset) {
+                            Ok(offset) if offset & CORRECTED_DATE_OFFSET_OVERFLOW_MASK == 0 => offset,
+                            _ => {
+                                num_overflows += 1;
+                                CORRECTED_DATE_OFFSET_OVERFLOW_MASK | (num_overflows - 1)
+                            }
+                        };
+                        chunk_write.write_all(&value.to_be_bytes()).or_raise(io_err)?;
+                    }
+                }
+                GENERATION_DATA_OVERFLOW_CHUNK_ID => {
+                    for offset in corrected_date_offsets
+                        .iter()
+                        .filter(|offset| **offset > u64::from(!CORRECTED_DATE_OFFSET_OVERFLOW_MASK))
+                    {
+                        chunk_write.write_all(&offset.to_be_bytes()).or_raise(io_err)?;
+                    }
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => {
+                    for parents in parents.iter().filter(|p| p.len() > 2) {
+                        let num_edges = parents.len() - 1;
+                        for (idx, parent) in parents[1..].iter().enumerate() {
+                            let mut edge = position(parent);
+                            if idx + 1 == num_edges {
+                                edge |= LAST_EXTENDED_EDGE_MASK;
+                            }
+                            chunk_write.write_all(&edge.to_be_bytes()).or_raise(io_err)?;
+                        }
+                    }
+                }
+                BLOOM_FILTER_INDEX_CHUNK_ID => {
+                    let mut end = 0u32;
+                    for commit in &commits {
+                        end += commit
+                            .changed_paths_filter
+                            .as_ref()
+                            .map_or(0, |filter| filter.len() as u32);
+                        chunk_write.write_all(&end.to_be_bytes()).or_raise(io_err)?;
+                    }
+                }
+                BLOOM_FILTER_DATA_CHUNK_ID => {
+                    let settings = changed_paths.expect("only planned with settings");
+                    for value in [settings.hash_version, settings.num_hashes, settings.bits_per_entry] {
+                        chunk_write.write_all(&value.to_be_bytes()).or_raise(io_err)?;
+                    }
+                    for filter in commits.iter().filter_map(|c| c.changed_paths_filter.as_ref()) {
+                        chunk_write.write_all(filter).or_raise(io_err)?;
+                    }
+                }
+                BASE_GRAPHS_LIST_CHUNK_ID => {
+                    for id in &base_ids {
+                        chunk_write.write_all(id.as_bytes()).or_raise(io_err)?;
+                    }
+                }
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+        }
+
+        let checksum = out.hash.try_finalize().or_raise(io_err)?;
+        out.inner.write_all(checksum.as_slice()).or_raise(io_err)?;
+        Ok(checksum)
+    }
+}
+
+/// The generation of a commit as it's going to be written.
+#[derive(Clone, Copy)]
+struct Generation {
+    /// The topological level of the commit, or 0 if it's not yet known.
+    level: u32,
+    /// The corrected commit date, or 0 if it's unknown.
+    corrected_commit_date: u64,
+}
+
+/// Compute the generation of each commit whose `parents` and `timestamps` are given, with parents in `base` already having one.
+fn generations(parents: &[Vec<Parent>], timestamps: &[u64], base: Option<&Graph>) -> Vec<Generation> {
+    const UNKNOWN: u32 = 0;
+    let mut generations = vec![
+        Generation {
+            level: UNKNOWN,
+            corrected_commit_date: 0,
+        };
+        parents.len()
+    ];
+    let mut stack = Vec::new();
+    for idx in 0..parents.len() {
+        stack.push(idx);
+        while let Some(&current) = stack.last() {
+            if generations[current].level != UNKNOWN {
+                stack.pop();
+                continue;
+            }
+            let mut max_parent_generation = 0;
+            let mut max_parent_corrected_commit_date = None;
+            let mut parents_are_known = true;
+            for parent in &parents[current] {
+                let generation = match parent {
+                    Parent::Base(pos) => {
+                        let commit = base
+                            .expect("base parents only exist with a base")
+                            .commit_at(crate::Position(*pos));
+                        Generation {
+                            level: commit.generation(),
+                            corrected_commit_date: commit.corrected_commit_date().unwrap_or_default(),
+                        }
+                    }
+                    Parent::Here(idx) => {
+                        let generation = generations[*idx as usize];
+                        if generation.level == UNKNOWN {
+                            parents_are_known = false;
+                            stack.push(*idx as usize);
+                        }
+                        generation
+                    }
+                };
+                max_parent_generation = max_parent_generation.max(generation.level);
+                max_parent_corrected_commit_date =
+                    max_parent_corrected_commit_date.max(Some(generation.corrected_commit_date));
+            }
+            if parents_are_known {
+                generations[current] = Generation {
+                    level: (max_parent_generation + 1).min(GENERATION_NUMBER_MAX),
+                    corrected_commit_date: timestamps[current]
+                        .max(max_parent_corrected_commit_date.map_or(0, |date| date + 1)),
+                };
+                stack.pop();
+            }
+        }
+    }
+    generations
+}
@@ -53,7 +53,12 @@ impl Graph {
     }
 
     /// Create a new commit graph from a list of `files`.
-    pub fn new(files: Vec<File>) -> Result<Self, Message> {
+    ///
+    /// Note that corrected commit dates are only used if all `files` contain them.
+    pub fn new(mut files: Vec<File>) -> Result<Self, Message> {
+        if !files.iter().all(File::has_generation_data) {
+            files.iter_mut().for_each(File::ignore_generation_data);
+        }
         let files = nonempty::NonEmpty::from_vec(files)
             .ok_or_else(|| message!("Commit-graph must contain at least one file"))?;
         let num_commits: u64 = files.iter().map(|f| u64::from(f.num_commits())).sum();
@@ -92,6 +92,7 @@ impl Graph {
             let next_file_start_pos = Position(file_start_pos.0 + file.num_commits());
             let file_stats = file.traverse(|commit| {
                 let mut max_parent_generation = 0u32;
+                let mut max_parent_corrected_commit_date = None;
                 for parent_pos in commit.iter_parents() {
                     let parent_pos = parent_pos.map_err(|err| err.raise_erased())?;
                     if parent_pos >= next_file_start_pos {
@@ -104,6 +105,8 @@ impl Graph {
                     }
                     let parent = self.commit_at(parent_pos);
                     max_parent_generation = max(max_parent_generation, parent.generation());
+                    max_parent_corrected_commit_date =
+                        max(max_parent_corrected_commit_date, parent.corrected_commit_date());
                 }
 
                 // If the max parent generation is GENERATION_NUMBER_MAX, then this commit's
@@ -118,6 +121,18 @@ impl Graph {
                     .raise_erased());
                 }
 
+                if let (Some(corrected_commit_date), Some(max_parent_corrected_commit_date)) =
+                    (commit.corrected_commit_date(), max_parent_corrected_commit_date)
+                {
+                    if corrected_commit_date <= max_parent_corrected_commit_date {
+                        return Err(message!(
+                            "Commit {}'s corrected commit date should be larger than {max_parent_corrected_commit_date} but is {corrected_commit_date}",
+                            commit.id(),
+                        )
+                        .raise_erased());
+                    }
+                }
+
                 processor(commit).or_raise_erased(|| message!("processor failed on commit {id}", id = commit.id()))?;
 
                 Ok(())
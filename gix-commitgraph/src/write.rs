@@ -0,0 +1,272 @@
+//! Types for use with [`write()`](crate::write()) and [`File::write_to()`](crate::File::write_to()).
+use gix_hash::ObjectId;
+
+/// A commit to store in a commit-graph file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The id of the commit's root tree.
+    pub tree: ObjectId,
+    /// The ids of all parents of the commit, in order.
+    pub parents: Vec<ObjectId>,
+    /// The committer time in seconds since the epoch. Values that don't fit into 34 bits are capped.
+    pub committer_timestamp: u64,
+    /// The changed-path Bloom filter of the commit as computed by [`filter_from_changed_paths()`](crate::bloom::filter_from_changed_paths()),
+    /// or `None` if it wasn't computed.
+    ///
+    /// It's only written if [`Options::changed_paths`] is set, and must have been computed with these settings.
+    pub changed_paths_filter: Option<Vec<u8>>,
+}
+
+/// Determine how the layers of a split commit-graph are handled when adding new commits to it, similar to
+/// `git commit-graph write --split=<strategy>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitStrategy {
+    /// Add a layer with the new commits and merge it with the layers below it as long as these aren't larger than
+    /// `size_multiple` times the amount of commits merged so far, or as long as there are more than `max_commits` of them.
+    ///
+    /// This is what `git` does by default.
+    Merge {
+        /// The factor by which a layer has to be larger than all merged commits to not be merged with them.
+        size_multiple: u32,
+        /// If set, keep merging layers as long as the merged layer would contain more than this amount of commits.
+        max_commits: Option<u32>,
+    },
+    /// Always add a new layer with the new commits, without merging existing ones.
+    NoMerge,
+    /// Merge all layers along with the new commits into a single layer.
+    Replace,
+}
+
+impl Default for SplitStrategy {
+    fn default() -> Self {
+        SplitStrategy::Merge {
+            size_multiple: 2,
+            max_commits: None,
+        }
+    }
+}
+
+/// Options for use in [`write()`](crate::write()).
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The kind of hash used by all commits.
+    pub object_hash: gix_hash::Kind,
+    /// If `None`, write a single `commit-graph` file with all commits, replacing everything that was there before.
+    /// Otherwise, add the commits that aren't yet known as a new layer to the `commit-graphs/commit-graph-chain`,
+    /// merging layers according to the given strategy.
+    pub split: Option<SplitStrategy>,
+    /// If set, write the changed-path Bloom filters of all commits that have one, which were computed with these settings.
+    ///
+    /// Filters of commits in existing files are kept if they were computed with the same settings.
+    pub changed_paths: Option<crate::bloom::Settings>,
+}
+
+/// The result of [`write()`](crate::write()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksum of the newly written file, or `None` if there were no new commits to write.
+    pub checksum: Option<ObjectId>,
+    /// The amount of commits stored in the newly written file.
+    pub num_commits: u32,
+    /// The amount of files the commit-graph consists of after writing.
+    pub num_files: usize,
+}
+
+pub(super) mod function {
+    use std::{
+        io::Write,
+        path::{Path, PathBuf},
+    };
+
+    use gix_error::{Exn, Message, ResultExt, message};
+
+    use super::{Commit, Options, Outcome, SplitStrategy};
+    use crate::{File, Graph};
+
+    /// Write the given `commits` into the commit-graph in `info_dir`, typically `.git/objects/info`.
+    ///
+    /// The parents of all `commits` must be contained in `commits` as well, or in the existing split commit-graph if
+    /// [`Options::split`] is set. Commits that are already stored in a split commit-graph are skipped.
+    ///
+    /// Files that aren't needed anymore after writing are removed.
+    pub fn write(
+        info_dir: &Path,
+        commits: impl IntoIterator<Item = Commit>,
+        Options {
+            object_hash,
+            split,
+            changed_paths,
+        }: Options,
+    ) -> Result<Outcome, Exn<Message>> {
+        let mut commits: Vec<_> = commits.into_iter().collect();
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+        let single_file_path = info_dir.join("commit-graph");
+        let chain_dir = info_dir.join("commit-graphs");
+        let chain_path = chain_dir.join("commit-graph-chain");
+        let chain = chain_path
+            .is_file()
+            .then(|| Graph::from_commit_graphs_dir(&chain_dir))
+            .transpose()?;
+        let chain_files: Vec<_> = chain
+            .iter()
+            .flat_map(|graph| graph.files.iter().map(|f| f.path().to_owned()))
+            .collect();
+
+        let Some(strategy) = split else {
+            let num_commits = commits.len() as u32;
+            let mut lock = gix_lock::File::acquire_to_update_resource(
+                &single_file_path,
+                gix_lock::acquire::Fail::Immediately,
+                None,
+            )
+            .or_raise(|| message!("Could not lock '{}'", single_file_path.display()))?;
+            let checksum = File::write_to(commits, None, object_hash, changed_paths, &mut lock)?;
+            drop(chain);
+            lock.commit()
+                .map_err(|err| err.error)
+                .or_raise(|| message!("Could not write '{}'", single_file_path.display()))?;
+            remove_files(chain_files.into_iter().chain(Some(chain_path)));
+            return Ok(Outcome {
+                checksum: Some(checksum),
+                num_commits,
+                num_files: 1,
+            });
+        };
+
+        if single_file_path.is_file() {
+            // The single file takes precedence over the chain, so its commits have to move into the chain.
+            let graph = Graph::from_file(&single_file_path)?;
+            commits.extend(commits_of(&graph, 0, changed_paths)?);
+            commits.sort_by_key(|c| c.id);
+            commits.dedup_by(|a, b| a.id == b.id);
+        }
+
+        let mut files = Vec::new();
+        if let Some(graph) = chain {
+            commits.retain(|c| graph.lookup(c.id).is_none());
+            let num_layers = graph.files.len();
+            let mut keep = num_layers;
+            let mut num_commits = commits.len() as u64;
+            match strategy {
+                SplitStrategy::NoMerge => {}
+                SplitStrategy::Replace => keep = 0,
+                SplitStrategy::Merge {
+                    size_multiple,
+                    max_commits,
+                } => {
+                    while keep > 0 {
+                        let layer_commits = u64::from(graph.files[keep - 1].num_commits());
+                        if layer_commits > u64::from(size_multiple) * num_commits
+                            && max_commits.is_none_or(|max| num_commits <= u64::from(max))
+                        {
+                            break;
+                        }
+                        num_commits += layer_commits;
+                        keep -= 1;
+                    }
+                }
+            }
+            if commits.is_empty() && keep == num_layers {
+                return Ok(Outcome {
+                    checksum: None,
+                    num_commits: 0,
+                    num_files: num_layers,
+                });
+            }
+            commits.extend(commits_of(&graph, keep, changed_paths)?);
+            files = graph.files.into();
+            files.truncate(keep);
+        } else if commits.is_empty() {
+            return Ok(Outcome {
+                checksum: None,
+                num_commits: 0,
+                num_files: 0,
+            });
+        }
+
+        let num_commits = commits.len() as u32;
+        let num_files = files.len() + 1;
+        let base = (!files.is_empty()).then(|| Graph::new(files)).transpose()?;
+        std::fs::create_dir_all(&chain_dir)
+            .or_raise(|| message!("Could not create directory '{}'", chain_dir.display()))?;
+        let mut tempfile = gix_tempfile::new(
+            &chain_dir,
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )
+        .or_raise(|| message!("Could not create temporary file in '{}'", chain_dir.display()))?;
+        let checksum = File::write_to(commits, base.as_ref(), object_hash, changed_paths, &mut tempfile)?;
+        let graph_path = chain_dir.join(format!("graph-{checksum}.graph"));
+        tempfile
+            .persist(&graph_path)
+            .map_err(|err| err.error)
+            .or_raise(|| message!("Could not write '{}'", graph_path.display()))?;
+
+        let mut lock =
+            gix_lock::File::acquire_to_update_resource(&chain_path, gix_lock::acquire::Fail::Immediately, None)
+                .or_raise(|| message!("Could not lock '{}'", chain_path.display()))?;
+        let mut kept_paths = Vec::new();
+        for file in base.iter().flat_map(|base| base.files.iter()) {
+            writeln!(lock, "{}", file.checksum())
+                .or_raise(|| message!("Could not write '{}'", chain_path.display()))?;
+            kept_paths.push(file.path().to_owned());
+        }
+        writeln!(lock, "{checksum}").or_raise(|| message!("Could not write '{}'", chain_path.display()))?;
+        drop(base);
+        lock.commit()
+            .map_err(|err| err.error)
+            .or_raise(|| message!("Could not write '{}'", chain_path.display()))?;
+
+        remove_files(
+            chain_files
+                .into_iter()
+                .filter(|path| !kept_paths.contains(path))
+                .chain(Some(single_file_path)),
+        );
+        Ok(Outcome {
+            checksum: Some(checksum),
+            num_commits,
+            num_files,
+        })
+    }
+
+    /// Extract all commits stored in the files of `graph`, starting at the file with index `first_file`, along with their
+    /// changed-path filters if these were computed with `changed_paths`.
+    fn commits_of(
+        graph: &Graph,
+        first_file: usize,
+        changed_paths: Option<crate::bloom::Settings>,
+    ) -> Result<Vec<Commit>, Message> {
+        graph
+            .files
+            .iter()
+            .skip(first_file)
+            .flat_map(File::iter_commits)
+            .map(|commit| {
+                Ok(Commit {
+                    id: commit.id().to_owned(),
+                    tree: commit.root_tree_id().to_owned(),
+                    parents: commit
+                        .iter_parents()
+                        .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                        .collect::<Result<_, _>>()?,
+                    committer_timestamp: commit.committer_timestamp(),
+                    changed_paths_filter: commit
+                        .changed_paths_filter()
+                        .filter(|filter| Some(filter.settings()) == changed_paths)
+                        .map(|filter| filter.as_bytes().to_vec()),
+                })
+            })
+            .collect()
+    }
+
+    /// Remove files that are obsolete now, ignoring errors as these don't affect the validity of the commit-graph.
+    fn remove_files(paths: impl IntoIterator<Item = PathBuf>) {
+        for path in paths {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
@@ -24,6 +24,11 @@ fn single_commit_huge_dates_generation_v2_also_do_not_allow_huge_dates() {
         "this is the value we would want to see, but it's not possible in V2 either, as that is just about generations"
     );
     assert_eq!(actual.generation(), 1, "generations are fine though");
+    assert_eq!(
+        actual.corrected_commit_date(),
+        Some(1),
+        "the corrected commit date is relative to the truncated timestamp, just like in `git`"
+    );
 }
 
 #[test]
@@ -37,6 +42,11 @@ fn single_commit_huge_dates_overflow_v1() {
         "this is the value we would want to see, but it's not possible in V1"
     );
     assert_eq!(actual.generation(), 1, "generations are fine though");
+    assert_eq!(
+        actual.corrected_commit_date(),
+        None,
+        "there is no generation data in V1"
+    );
 }
 
 #[test]
@@ -69,6 +79,72 @@ fn generation_numbers_overflow_is_handled_in_chained_graph() {
     }
 }
 
+#[test]
+fn corrected_commit_dates_overflow_is_handled_in_chained_graph() {
+    let names = ["future-1", "old-1", "future-2", "old-2", "extra"];
+    let (cg, refs) = graph_and_expected("generation_number_overflow.sh", &names);
+    for (name, expected) in names.iter().zip(4147483646..) {
+        let commit = cg.commit_by_id(refs[*name].id).expect("present");
+        assert_eq!(
+            commit.corrected_commit_date(),
+            Some(expected),
+            "{name}: each commit is one second past its parent, with most offsets stored in the overflow chunk"
+        );
+    }
+}
+
+#[test]
+fn corrupt_corrected_commit_date_overflow_disables_corrected_commit_dates_for_the_whole_graph() -> gix_testtools::Result
+{
+    let repo = gix_testtools::scripted_fixture_writable("generation_number_overflow.sh")?;
+    let graphs_dir = repo.path().join(".git/objects/info/commit-graphs");
+    let mut corrupted = false;
+    for entry in std::fs::read_dir(&graphs_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "graph") {
+            let mut data = std::fs::read(&path)?;
+            if point_overflowing_offsets_out_of_bounds(&mut data) {
+                std::fs::write(&path, data)?;
+                corrupted = true;
+                break;
+            }
+        }
+    }
+    assert!(corrupted, "one of the layers was corrupted, leaving the others intact");
+
+    let cg = gix_commitgraph::Graph::from_commit_graphs_dir(&graphs_dir).map_err(gix_error::Exn::into_error)?;
+    assert!(!cg.has_corrected_commit_dates());
+    for commit in cg.iter_commits() {
+        assert_eq!(
+            commit.corrected_commit_date(),
+            None,
+            "no commit has a corrected commit date, not even those in intact layers"
+        );
+        assert!(commit.generation() > 0, "topological levels are still available");
+    }
+    Ok(())
+}
+
+/// Find the GDA2 chunk in the commit-graph file `data` and make all of its overflowing entries point past the end of
+/// the GDO2 chunk, returning `true` if there was at least one.
+fn point_overflowing_offsets_out_of_bounds(data: &mut [u8]) -> bool {
+    let chunk_count = data[6] as usize;
+    let table = &data[8..][..(chunk_count + 1) * 12];
+    let offset_of = |index: usize| u64::from_be_bytes(table[index * 12 + 4..][..8].try_into().unwrap()) as usize;
+    let Some(index) = (0..chunk_count).find(|index| &table[index * 12..][..4] == b"GDA2") else {
+        return false;
+    };
+    let range = offset_of(index)..offset_of(index + 1);
+    let mut modified = false;
+    for entry in data[range].chunks_exact_mut(4) {
+        if entry[0] & 0x80 != 0 {
+            entry.copy_from_slice(&0xffff_ffff_u32.to_be_bytes());
+            modified = true;
+        }
+    }
+    modified
+}
+
 #[test]
 fn octopus_merges() {
     let (cg, refs) = graph_and_expected(
@@ -0,0 +1,93 @@
+use gix_commitgraph::bloom::{Key, Settings, filter_from_changed_paths};
+
+use crate::graph_and_expected;
+
+const NAMES: [&str; 7] = ["root", "modify", "empty", "many", "remove", "side", "merge"];
+
+fn may_have_changed(cg: &gix_commitgraph::Graph, id: &gix_hash::oid, path: &str) -> bool {
+    let settings = cg.bloom_filter_settings().expect("graph has changed-path filters");
+    let filter = cg
+        .commit_by_id(id)
+        .expect("present")
+        .changed_paths_filter()
+        .expect("each commit has a filter");
+    Key::for_path_and_leading_directories(path.into(), &settings)
+        .iter()
+        .all(|key| filter.contains(key))
+}
+
+#[test]
+fn filters_contain_changed_paths_and_their_directories() {
+    let (cg, refs) = graph_and_expected("changed_paths.sh", &NAMES);
+    assert_eq!(cg.bloom_filter_settings(), Some(Settings::default()));
+    let changed = |name: &str, path: &str| may_have_changed(&cg, refs[name].id(), path);
+
+    for path in ["a", "dir/b", "dir/sub/c", "dir/sub/long-file-name", "ü/ñ"] {
+        assert!(changed("root", path), "{path}: everything was added in the root commit");
+    }
+    assert!(changed("modify", "dir/sub/c"));
+    assert!(!changed("modify", "a"), "a was not touched");
+    assert!(!changed("modify", "ü/ñ"), "non-ASCII paths work as well");
+    assert!(
+        !changed("modify", "dir/b/nested"),
+        "directories must be contained as well"
+    );
+    assert!(changed("side", "ü/ñ"));
+    assert!(!changed("side", "dir/sub/c"));
+    assert!(changed("remove", "a"), "deletions count as change");
+
+    for path in ["a", "dir/sub/c", "many/1"] {
+        assert!(!changed("empty", path), "{path}: nothing changes in an empty commit");
+    }
+    for path in ["a", "dir/sub/c", "anything"] {
+        assert!(
+            changed("many", path),
+            "{path}: filters of commits with too many changes contain every path"
+        );
+    }
+    assert!(
+        changed("merge", "ü/ñ") && !changed("merge", "dir/sub/c"),
+        "merges only see changes compared to their first parent"
+    );
+}
+
+#[test]
+fn filters_from_changed_paths_match_the_ones_of_git() -> gix_testtools::Result {
+    let (cg, refs) = graph_and_expected("changed_paths.sh", &NAMES);
+    let changed_paths_dir = gix_testtools::scripted_fixture_read_only("changed_paths.sh")?.join(".git/changed-paths");
+    let settings = cg.bloom_filter_settings().expect("present");
+    for name in NAMES {
+        let id = refs[name].id();
+        let changed_paths = std::fs::read(changed_paths_dir.join(id.to_string()))?;
+        let actual = filter_from_changed_paths(
+            changed_paths
+                .split(|b| *b == 0)
+                .filter(|path| !path.is_empty())
+                .map(Into::into),
+            &settings,
+        );
+        let expected = cg
+            .commit_by_id(id)
+            .expect("present")
+            .changed_paths_filter()
+            .expect("present");
+        assert_eq!(actual, expected.as_bytes(), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn hash_versions_only_differ_for_paths_with_bytes_above_0x7f() {
+    let v1 = Settings::default();
+    let v2 = Settings {
+        hash_version: 2,
+        ..Settings::default()
+    };
+    let filter = |path: &str, settings: &Settings| filter_from_changed_paths(Some(path.into()), settings);
+    assert_eq!(filter("dir/file", &v1), filter("dir/file", &v2));
+    assert_ne!(
+        filter("dir/fïle", &v1),
+        filter("dir/fïle", &v2),
+        "version 1 sign-extends these bytes"
+    );
+}
@@ -0,0 +1,54 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tagged_commit() {
+  local name=${1:?first argument is the commit message and tag name}
+  git add -A
+  git commit -q --allow-empty -m "$name"
+  git tag "$name"
+}
+
+git init -q
+git checkout -q -b main
+
+mkdir -p dir/sub ü
+echo a > a
+echo b > dir/b
+echo c > dir/sub/c
+echo n > ü/ñ
+echo long > dir/sub/long-file-name
+tagged_commit root
+
+echo c2 > dir/sub/c
+tagged_commit modify
+
+tagged_commit empty
+
+mkdir many
+for i in $(seq 513); do
+  echo "$i" > "many/$i"
+done
+tagged_commit many
+
+git rm -q a
+tagged_commit remove
+
+git checkout -q -b side modify
+echo n2 > ü/ñ
+tagged_commit side
+
+git checkout -q main
+git merge -q --no-edit side
+git tag merge
+
+git commit-graph write --no-progress --reachable --changed-paths
+
+# Record the paths changed by each commit compared to its first parent, which is what its filter is computed from.
+mkdir .git/changed-paths
+for commit in $(git rev-list --all); do
+  if parent=$(git rev-parse -q --verify "$commit^1"); then
+    git diff-tree -r --name-only -z "$parent" "$commit"
+  else
+    git diff-tree -r --root --no-commit-id --name-only -z "$commit"
+  fi > ".git/changed-paths/$commit"
+done
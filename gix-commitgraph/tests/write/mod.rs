@@ -0,0 +1,297 @@
+use std::path::Path;
+
+use gix_commitgraph::{
+    Graph,
+    write::{Commit, Options, Outcome, SplitStrategy},
+};
+use gix_testtools::{scripted_fixture_read_only, scripted_fixture_writable};
+
+fn commits_of(graph: &Graph) -> Vec<Commit> {
+    graph
+        .iter_commits()
+        .map(|commit| Commit {
+            id: commit.id().to_owned(),
+            tree: commit.root_tree_id().to_owned(),
+            parents: commit
+                .iter_parents()
+                .map(|pos| graph.id_at(pos.expect("valid parent")).to_owned())
+                .collect(),
+            committer_timestamp: commit.committer_timestamp(),
+            changed_paths_filter: commit.changed_paths_filter().map(|filter| filter.as_bytes().to_vec()),
+        })
+        .collect()
+}
+
+fn write(info_dir: &Path, commits: Vec<Commit>, split: Option<SplitStrategy>) -> gix_testtools::Result<Outcome> {
+    let object_hash = commits[0].id.kind();
+    Ok(gix_commitgraph::write(
+        info_dir,
+        commits,
+        Options {
+            object_hash,
+            split,
+            changed_paths: None,
+        },
+    )
+    .map_err(gix_error::Exn::into_error)?)
+}
+
+fn open(info_dir: &Path) -> gix_testtools::Result<Graph> {
+    let graph = Graph::from_info_dir(info_dir).map_err(gix_error::Exn::into_error)?;
+    graph
+        .verify_integrity(|_| Ok::<_, gix_error::Message>(()))
+        .map_err(gix_error::Exn::into_error)?;
+    Ok(graph)
+}
+
+fn git_verify(repo: &Path) {
+    let output = std::process::Command::new("git")
+        .args(["commit-graph", "verify", "--no-progress"])
+        .current_dir(repo)
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("git can be executed");
+    assert!(
+        output.status.success(),
+        "git considers the commit-graph valid: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn rewritten_graphs_contain_the_same_commits() -> gix_testtools::Result {
+    for script in [
+        "single_parent.sh",
+        "two_parents.sh",
+        "octopus_merges.sh",
+        "split_chain.sh",
+    ] {
+        let original = open(&scripted_fixture_read_only(script)?.join(".git/objects/info"))?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let outcome = write(tmp.path(), commits_of(&original), None)?;
+        assert_eq!(outcome.num_commits, original.num_commits());
+        assert_eq!(outcome.num_files, 1);
+
+        let rewritten = open(tmp.path())?;
+        assert_eq!(
+            rewritten.iter_ids().collect::<Vec<_>>(),
+            {
+                let mut ids = original.iter_ids().collect::<Vec<_>>();
+                ids.sort();
+                ids
+            },
+            "{script}: all commits are stored in one file"
+        );
+        let mut expected = commits_of(&original);
+        expected.sort_by_key(|c| c.id);
+        assert_eq!(commits_of(&rewritten), expected, "{script}");
+        for commit in original.iter_commits() {
+            assert_eq!(
+                rewritten.commit_by_id(commit.id()).expect("present").generation(),
+                commit.generation(),
+                "{script}: generations are computed like git does"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn split_graphs_are_layered_and_merged() -> gix_testtools::Result {
+    let repo = scripted_fixture_writable("octopus_merges.sh")?;
+    let info_dir = repo.path().join(".git/objects/info");
+    let commits = commits_of(&open(&info_dir)?);
+    let (merges, others): (Vec<_>, Vec<_>) = commits.iter().cloned().partition(|c| c.parents.len() > 2);
+
+    let outcome = write(&info_dir, others.clone(), Some(SplitStrategy::NoMerge))?;
+    assert_eq!(
+        outcome.num_commits, 7,
+        "the commits of the existing single file are moved into the first layer"
+    );
+    assert_eq!(outcome.num_files, 1);
+    assert!(!info_dir.join("commit-graph").exists(), "the single file was removed");
+
+    let outcome = write(&info_dir, commits.clone(), Some(SplitStrategy::NoMerge))?;
+    assert_eq!(outcome.num_commits, 0, "all commits were already present");
+    assert_eq!(outcome.checksum, None);
+    assert_eq!(outcome.num_files, 1);
+
+    let graph_before_merges = open(&info_dir)?;
+    assert_eq!(graph_before_merges.num_commits(), commits.len() as u32);
+    drop(graph_before_merges);
+
+    // Remove the graph to build it up again with layers that don't contain everything.
+    std::fs::remove_dir_all(info_dir.join("commit-graphs"))?;
+    let outcome = write(&info_dir, others, Some(SplitStrategy::NoMerge))?;
+    assert_eq!((outcome.num_commits, outcome.num_files), (5, 1));
+    let outcome = write(&info_dir, merges, Some(SplitStrategy::NoMerge))?;
+    assert_eq!(
+        (outcome.num_commits, outcome.num_files),
+        (2, 2),
+        "new commits are written into their own layer"
+    );
+    let graph = open(&info_dir)?;
+    assert_eq!(commits_of(&graph).len(), commits.len());
+    drop(graph);
+    git_verify(repo.path());
+
+    let outcome = write(&info_dir, commits.clone(), Some(SplitStrategy::Replace))?;
+    assert_eq!(
+        (outcome.num_commits, outcome.num_files),
+        (7, 1),
+        "all layers are merged"
+    );
+    assert_eq!(
+        std::fs::read_dir(info_dir.join("commit-graphs"))?.count(),
+        2,
+        "only the chain and the single layer are left"
+    );
+    git_verify(repo.path());
+
+    let outcome = write(&info_dir, commits.clone(), None)?;
+    assert_eq!((outcome.num_commits, outcome.num_files), (7, 1));
+    assert!(
+        !info_dir.join("commit-graphs").join("commit-graph-chain").exists(),
+        "the chain is removed in favor of the single file"
+    );
+    git_verify(repo.path());
+    Ok(())
+}
+
+#[test]
+fn default_split_strategy_merges_small_layers() -> gix_testtools::Result {
+    let repo = scripted_fixture_writable("split_chain.sh")?;
+    let info_dir = repo.path().join(".git/objects/info");
+    let graph = open(&info_dir)?;
+    assert_eq!(graph.num_commits(), 3);
+    let commits = commits_of(&graph);
+    drop(graph);
+
+    let outcome = write(&info_dir, commits.clone(), Some(SplitStrategy::default()))?;
+    assert_eq!(outcome.checksum, None, "nothing to do if all commits are known");
+    assert_eq!(outcome.num_files, 3);
+
+    let outcome = write(
+        &info_dir,
+        commits.clone(),
+        Some(SplitStrategy::Merge {
+            size_multiple: 2,
+            max_commits: Some(0),
+        }),
+    )?;
+    assert_eq!(outcome.num_files, 3, "without new commits, layers are left alone");
+
+    std::fs::remove_dir_all(info_dir.join("commit-graphs"))?;
+    let root: Vec<_> = commits.iter().filter(|c| c.parents.is_empty()).cloned().collect();
+    write(&info_dir, root, Some(SplitStrategy::default()))?;
+    let outcome = write(&info_dir, commits, Some(SplitStrategy::default()))?;
+    assert_eq!(
+        (outcome.num_commits, outcome.num_files),
+        (3, 1),
+        "the layer with a single commit isn't more than twice as large as the two new commits"
+    );
+    git_verify(repo.path());
+    Ok(())
+}
+
+#[test]
+fn corrected_commit_dates_are_written_like_git_does() -> gix_testtools::Result {
+    let repo = scripted_fixture_writable("generation_number_overflow.sh")?;
+    let info_dir = repo.path().join(".git/objects/info");
+    let original = open(&info_dir)?;
+    let commits = commits_of(&original);
+    let expected: Vec<_> = original
+        .iter_commits()
+        .map(|c| (c.id().to_owned(), c.corrected_commit_date()))
+        .collect();
+    assert!(
+        expected.iter().all(|(_, date)| date.is_some()),
+        "git wrote corrected commit dates to all layers"
+    );
+    drop(original);
+
+    let assert_same_corrected_commit_dates = |graph: &Graph| {
+        for (id, date) in &expected {
+            assert_eq!(
+                graph.commit_by_id(id).expect("present").corrected_commit_date(),
+                *date,
+                "offsets that need the overflow chunk are handled as well"
+            );
+        }
+    };
+    write(&info_dir, commits.clone(), None)?;
+    assert_same_corrected_commit_dates(&open(&info_dir)?);
+    git_verify(repo.path());
+
+    let (roots, others): (Vec<_>, Vec<_>) = commits.into_iter().partition(|c| c.parents.is_empty());
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    write(&info_dir, roots, Some(SplitStrategy::NoMerge))?;
+    let outcome = write(&info_dir, others, Some(SplitStrategy::NoMerge))?;
+    assert_eq!(outcome.num_files, 2);
+    assert_same_corrected_commit_dates(&open(&info_dir)?);
+    git_verify(repo.path());
+    Ok(())
+}
+
+#[test]
+fn changed_path_filters_are_written_like_git_does() -> gix_testtools::Result {
+    let repo = scripted_fixture_writable("changed_paths.sh")?;
+    let info_dir = repo.path().join(".git/objects/info");
+    let original = open(&info_dir)?;
+    let settings = original.bloom_filter_settings();
+    assert!(settings.is_some());
+    let expected_checksum = gix_commitgraph::File::at(info_dir.join("commit-graph"))
+        .map_err(gix_error::Exn::into_error)?
+        .checksum()
+        .to_owned();
+    let commits = commits_of(&original);
+    let object_hash = original.object_hash();
+    drop(original);
+
+    let write = |commits: Vec<Commit>, split| {
+        gix_commitgraph::write(
+            &info_dir,
+            commits,
+            Options {
+                object_hash,
+                split,
+                changed_paths: settings,
+            },
+        )
+        .map_err(gix_error::Exn::into_error)
+    };
+    let outcome = write(commits.clone(), None)?;
+    assert_eq!(
+        outcome.checksum,
+        Some(expected_checksum),
+        "the file is exactly the same as the one written by git"
+    );
+
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    let (roots, others): (Vec<_>, Vec<_>) = commits.iter().cloned().partition(|c| c.parents.is_empty());
+    write(roots, Some(SplitStrategy::NoMerge))?;
+    write(others, Some(SplitStrategy::NoMerge))?;
+    git_verify(repo.path());
+    let graph = open(&info_dir)?;
+    assert_eq!(graph.bloom_filter_settings(), settings);
+    assert_eq!(commits_of(&graph).len(), commits.len());
+    for commit in &commits {
+        assert_eq!(
+            graph
+                .commit_by_id(commit.id)
+                .and_then(|c| c.changed_paths_filter())
+                .map(|filter| filter.as_bytes().to_vec()),
+            commit.changed_paths_filter,
+            "all layers have their filters"
+        );
+    }
+    drop(graph);
+
+    let outcome = write(commits, Some(SplitStrategy::Replace))?;
+    assert_eq!(
+        outcome.checksum,
+        Some(expected_checksum),
+        "filters are retained when merging layers, which makes it the same as a single file"
+    );
+    Ok(())
+}
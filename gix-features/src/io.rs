@@ -44,6 +44,10 @@ pub mod pipe {
             let mut written = 0;
             while !out.is_empty() {
                 if self.buf.is_empty() {
+                    // Like a unix pipe, return what's available instead of blocking until the writer sends more.
+                    if written != 0 {
+                        break;
+                    }
                     match self.channel.recv() {
                         Ok(Ok(buf)) => self.buf = buf,
                         Ok(Err(err)) => return Err(err),
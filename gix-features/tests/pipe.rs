@@ -92,6 +92,19 @@ mod io {
         assert_eq!(buf, &input[..]);
     }
 
+    #[test]
+    fn reads_return_what_is_available() {
+        let (mut writer, mut reader) = io::pipe::unidirectional(2);
+        writer.write_all(b"hello").expect("success");
+        let mut buf = [0u8; 10];
+        assert_eq!(
+            reader.read(&mut buf).expect("success"),
+            5,
+            "the read doesn't block until the buffer is filled, as the writer is still present"
+        );
+        assert_eq!(&buf[..5], b"hello");
+    }
+
     #[test]
     fn small_reads() {
         const BLOCK_SIZE: usize = 20;
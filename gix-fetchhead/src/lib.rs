@@ -1 +1,55 @@
+//! Read and write the [`FETCH_HEAD`](https://git-scm.com/docs/gitrepository-layout) file that `git fetch` leaves behind.
+//!
+//! Each line of the file records one object that was fetched, whether or not it is meant to be merged by `git pull`,
+//! and a description of where it came from, like `<id>\t\tbranch 'main' of https://example.com/repo`.
+//!
+//! * [parse()] reads all lines back into [entries](Entry).
+//! * [`Entry::write_to()`] writes a line in the format Git uses.
+//! * [shorten_url()] turns a remote URL into the form Git puts into the description.
+#![deny(missing_docs)]
 #![forbid(unsafe_code)]
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+
+/// A single line in a `FETCH_HEAD` file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Entry {
+    /// The id of the object that was fetched.
+    pub id: ObjectId,
+    /// If `true`, the object is meant to be merged by `git pull`. Otherwise, the line is marked as `not-for-merge`.
+    pub for_merge: bool,
+    /// The full name of the reference on the remote that the object was fetched from, like `refs/heads/main`,
+    /// or `None` if it was the remote `HEAD`.
+    ///
+    /// Names that weren't references, like an object id that was fetched directly, are kept as is.
+    pub remote_ref: Option<BString>,
+    /// The (typically [shortened](shorten_url())) URL of the remote the object was fetched from.
+    pub url: BString,
+}
+
+/// The marker for lines whose object isn't meant to be merged by `git pull`.
+pub const NOT_FOR_MERGE: &str = "not-for-merge";
+
+///
+pub mod parse;
+
+/// Parse the given `buf` of bytes line by line into [entries](Entry).
+///
+/// Errors may occur per line, but it's up to the caller to stop iteration when one is encountered.
+pub fn parse(buf: &[u8]) -> parse::Lines<'_> {
+    parse::Lines::new(buf)
+}
+
+mod write;
+
+/// Shorten `url` the way Git does before writing it into `FETCH_HEAD`, which removes trailing slashes
+/// and a trailing `.git`.
+pub fn shorten_url(url: &BStr) -> &BStr {
+    let mut url = url.trim_end_with(|c| c == '/').as_bstr();
+    // Like Git, keep `.git` if that would leave less than two bytes of URL.
+    if url.len() > 5 && url.ends_with(b".git") {
+        url = url[..url.len() - ".git".len()].as_bstr();
+    }
+    url
+}
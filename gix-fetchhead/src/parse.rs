@@ -0,0 +1,90 @@
+/// The error returned by [`parse()`](crate::parse()).
+pub type Error = gix_error::Exn<gix_error::ValidationError>;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_error::{ErrorExt, OptionExt, ResultExt, ValidationError};
+use gix_hash::ObjectId;
+
+use crate::{Entry, NOT_FOR_MERGE, write::KINDS};
+
+/// An iterator to parse `FETCH_HEAD` lines on-demand.
+pub struct Lines<'a> {
+    lines: bstr::Lines<'a>,
+    line_no: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Lines {
+            lines: input.as_bstr().lines(),
+            line_no: 0,
+        }
+    }
+}
+
+impl Iterator for Lines<'_> {
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            self.line_no += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return parse_line(line.as_bstr(), self.line_no).into();
+        }
+        None
+    }
+}
+
+fn parse_line(line: &BStr, line_number: usize) -> Result<Entry, Error> {
+    let mut tokens = line.splitn_str(3, "\t");
+    let (Some(hex), Some(marker), Some(description)) = (tokens.next(), tokens.next(), tokens.next()) else {
+        return Err(ValidationError::new_with_input(
+            format!("{line_number}: Expected three tab-separated fields"),
+            line,
+        )
+        .raise());
+    };
+    let id = ObjectId::from_hex(hex)
+        .or_raise(|| ValidationError::new_with_input(format!("{line_number}: Invalid object id"), line))?;
+    let for_merge = match marker {
+        b"" => true,
+        m if m == NOT_FOR_MERGE.as_bytes() => false,
+        _ => {
+            return Err(ValidationError::new_with_input(
+                format!("{line_number}: Expected an empty field or '{NOT_FOR_MERGE}'"),
+                line,
+            )
+            .raise());
+        }
+    };
+    let (remote_ref, url) = parse_description(description.as_bstr())
+        .ok_or_raise(|| ValidationError::new_with_input(format!("{line_number}: Invalid description"), line))?;
+    Ok(Entry {
+        id,
+        for_merge,
+        remote_ref,
+        url: url.to_owned(),
+    })
+}
+
+/// Split `description` into the full name of the remote reference, if present, and the URL.
+fn parse_description(description: &BStr) -> Option<(Option<BString>, &BStr)> {
+    let quoted = KINDS
+        .iter()
+        .find_map(|(prefix, kind)| {
+            description
+                .strip_prefix(kind.as_bytes())
+                .and_then(|rest| rest.strip_prefix(b" '"))
+                .map(|rest| (Some(*prefix), rest))
+        })
+        .or_else(|| description.strip_prefix(b"'").map(|rest| (None, rest)));
+    let Some((prefix, rest)) = quoted else {
+        return Some((None, description));
+    };
+    let end = rest.find("' of ")?;
+    let mut name = BString::from(prefix.unwrap_or_default());
+    name.push_str(&rest[..end]);
+    Some((Some(name), rest[end + "' of ".len()..].as_bstr()))
+}
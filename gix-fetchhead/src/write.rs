@@ -0,0 +1,47 @@
+use bstr::ByteSlice;
+
+use crate::{Entry, NOT_FOR_MERGE};
+
+impl Entry {
+    /// Serialize this entry as a single line, including the trailing newline, into `out` the way Git would write it.
+    ///
+    /// Newlines in the URL are escaped as `\n` to keep the line intact.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write!(out, "{}\t", self.id)?;
+        if !self.for_merge {
+            out.write_all(NOT_FOR_MERGE.as_bytes())?;
+        }
+        out.write_all(b"\t")?;
+        if let Some(name) = self.remote_ref.as_ref().filter(|name| *name != "HEAD") {
+            let (kind, short_name) = kind_and_short_name(name.as_ref());
+            if let Some(kind) = kind {
+                out.write_all(kind.as_bytes())?;
+                out.write_all(b" ")?;
+            }
+            out.write_all(b"'")?;
+            out.write_all(short_name)?;
+            out.write_all(b"' of ")?;
+        }
+        for (idx, part) in self.url.split_str("\n").enumerate() {
+            if idx != 0 {
+                out.write_all(b"\\n")?;
+            }
+            out.write_all(part)?;
+        }
+        out.write_all(b"\n")
+    }
+}
+
+/// The prefixes of full reference names along with the kind Git uses to describe them.
+pub(crate) const KINDS: &[(&str, &str)] = &[
+    ("refs/heads/", "branch"),
+    ("refs/tags/", "tag"),
+    ("refs/remotes/", "remote-tracking branch"),
+];
+
+fn kind_and_short_name(name: &[u8]) -> (Option<&'static str>, &[u8]) {
+    KINDS
+        .iter()
+        .find_map(|(prefix, kind)| name.strip_prefix(prefix.as_bytes()).map(|short| (Some(*kind), short)))
+        .unwrap_or((None, name))
+}
@@ -0,0 +1,33 @@
+use gix_fetchhead::Entry;
+
+pub use gix_testtools::Result;
+
+mod parse;
+mod write;
+
+fn fixture(name: &str) -> Result<Vec<u8>> {
+    Ok(std::fs::read(
+        gix_testtools::scripted_fixture_read_only("make_fetch_head.sh")?.join(name),
+    )?)
+}
+
+fn entries(input: &[u8]) -> Result<Vec<Entry>> {
+    Ok(gix_fetchhead::parse(input)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(gix_error::Exn::into_error)?)
+}
+
+#[test]
+fn shorten_url() {
+    for (input, expected) in [
+        ("https://example.com/repo.git", "https://example.com/repo"),
+        ("https://example.com/repo.git/", "https://example.com/repo"),
+        ("https://example.com/repo//", "https://example.com/repo"),
+        ("../remote/.git", "../remote/"),
+        ("/a.git", "/a"),
+        ("a.git", "a.git"),
+        ("repo", "repo"),
+    ] {
+        assert_eq!(gix_fetchhead::shorten_url(input.into()), expected, "{input}");
+    }
+}
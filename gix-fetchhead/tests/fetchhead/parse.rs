@@ -0,0 +1,101 @@
+use gix_fetchhead::Entry;
+use gix_hash::ObjectId;
+
+use crate::{entries, fixture};
+
+fn names(entries: &[Entry]) -> Vec<(Option<String>, bool)> {
+    entries
+        .iter()
+        .map(|e| (e.remote_ref.as_ref().map(ToString::to_string), e.for_merge))
+        .collect()
+}
+
+#[test]
+fn configured_refspecs() -> crate::Result {
+    let entries = entries(&fixture("default")?)?;
+    assert_eq!(
+        names(&entries),
+        [
+            (Some("refs/heads/main".into()), true),
+            (Some("refs/heads/feature".into()), false)
+        ],
+        "only the branch that is configured to be merged is for-merge"
+    );
+    assert!(entries.iter().all(|e| e.url == "../remote/"));
+    Ok(())
+}
+
+#[test]
+fn remote_head() -> crate::Result {
+    let entries = entries(&fixture("head")?)?;
+    assert_eq!(names(&entries), [(None, true)]);
+    assert_eq!(entries[0].url, "../remote", "HEAD has no description, only the URL");
+    Ok(())
+}
+
+#[test]
+fn explicit_refs_and_object_ids() -> crate::Result {
+    let entries = entries(&fixture("explicit")?)?;
+    let id = entries[2].id.to_string();
+    assert_eq!(
+        names(&entries),
+        [
+            (Some("refs/heads/feature".into()), true),
+            (Some("refs/tags/v1".into()), true),
+            (Some(id), true),
+            (Some("refs/heads/main".into()), true),
+        ],
+        "names that aren't references, like object ids, are kept as is"
+    );
+    Ok(())
+}
+
+#[test]
+fn other_kinds_and_empty_lines() -> crate::Result {
+    let id = ObjectId::null(gix_testtools::object_hash());
+    let input = format!(
+        "{id}\tnot-for-merge\tremote-tracking branch 'origin/main' of https://host/a' of b\n\n{id}\t\t'refs/pull/1/head' of url\n"
+    );
+    let entries = entries(input.as_bytes())?;
+    assert_eq!(
+        entries,
+        [
+            Entry {
+                id,
+                for_merge: false,
+                remote_ref: Some("refs/remotes/origin/main".into()),
+                url: "https://host/a' of b".into(),
+            },
+            Entry {
+                id,
+                for_merge: true,
+                remote_ref: Some("refs/pull/1/head".into()),
+                url: "url".into(),
+            }
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_lines() {
+    let id = ObjectId::null(gix_testtools::object_hash());
+    for (input, expected) in [
+        ("no tabs".to_string(), "1: Expected three tab-separated fields"),
+        (
+            format!("{id}\tmerge\turl"),
+            "1: Expected an empty field or 'not-for-merge'",
+        ),
+        ("abc\t\turl".into(), "1: Invalid object id"),
+        (format!("{id}\t\tbranch 'main of url"), "1: Invalid description"),
+    ] {
+        let err = gix_fetchhead::parse(input.as_bytes())
+            .next()
+            .expect("one line")
+            .expect_err("invalid");
+        assert!(
+            err.to_string().starts_with(expected),
+            "{input:?}: {err} should start with {expected}"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use gix_fetchhead::Entry;
+use gix_hash::ObjectId;
+
+use crate::{entries, fixture};
+
+#[test]
+fn round_trip() -> crate::Result {
+    for name in ["default", "head", "explicit"] {
+        let input = fixture(name)?;
+        let mut out = Vec::new();
+        for entry in entries(&input)? {
+            entry.write_to(&mut out)?;
+        }
+        assert_eq!(out, input, "{name}: Git writes exactly the same");
+    }
+    Ok(())
+}
+
+#[test]
+fn newlines_in_urls_are_escaped() -> crate::Result {
+    let id = ObjectId::null(gix_testtools::object_hash());
+    let mut out = Vec::new();
+    Entry {
+        id,
+        for_merge: false,
+        remote_ref: Some("HEAD".into()),
+        url: "a\nb".into(),
+    }
+    .write_to(&mut out)?;
+    assert_eq!(
+        out,
+        format!("{id}\tnot-for-merge\ta\\nb\n").as_bytes(),
+        "the remote HEAD isn't described"
+    );
+    Ok(())
+}
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git checkout -q -b main
+  echo 1 > file
+  git add file
+  git commit -q -m c1
+  git tag -a -m "annotated" v1
+  git checkout -q -b feature
+  echo 2 > file
+  git commit -q -am c2
+  git checkout -q main
+)
+
+git clone -q remote local
+cd local
+git remote set-url origin ../remote/.git
+
+# A fetch of the configured refspecs, with `main` being for-merge thanks to `branch.main.merge`.
+git fetch -q origin
+cp .git/FETCH_HEAD ../default
+
+# A fetch of the remote HEAD only.
+git fetch -q ../remote HEAD
+cp .git/FETCH_HEAD ../head
+
+# A fetch of explicit refs and an object id.
+git fetch -q origin refs/heads/feature refs/tags/v1 "$(git -C ../remote rev-parse main)" refs/heads/main:refs/remotes/origin/other
+cp .git/FETCH_HEAD ../explicit
@@ -40,6 +40,9 @@ pub mod to_git {
         ReadProcessOutputToBuffer(#[from] std::io::Error),
         #[error("Could not allocate buffer")]
         OutOfMemory(#[from] std::collections::TryReserveError),
+        #[cfg(feature = "lfs")]
+        #[error("The built-in LFS filter failed to clean the file")]
+        Lfs(#[source] gix_error::Error),
     }
 }
 
@@ -59,6 +62,9 @@ pub mod to_worktree {
         Driver(#[from] crate::driver::apply::Error),
         #[error(transparent)]
         Configuration(#[from] super::configuration::Error),
+        #[cfg(feature = "lfs")]
+        #[error("The built-in LFS filter failed to smudge the file")]
+        Lfs(#[source] gix_error::Error),
     }
 }
 
@@ -85,6 +91,8 @@ impl Pipeline {
             _attr_digest: _,
             encoding,
             apply_ident_filter,
+            #[cfg(feature = "lfs")]
+            is_lfs,
         } = Configuration::at_path(
             bstr_rela_path.as_ref(),
             &self.options.drivers,
@@ -94,6 +102,17 @@ impl Pipeline {
         )?;
 
         let mut in_src_buffer = false;
+        #[cfg(feature = "lfs")]
+        let driver = match self.options.lfs.as_ref().filter(|_| is_lfs) {
+            Some(store) => {
+                self.bufs.clear();
+                gix_lfs::filter::clean(store, &mut src, &mut self.bufs.src)
+                    .map_err(|err| to_git::Error::Lfs(err.into_error()))?;
+                in_src_buffer = true;
+                None
+            }
+            None => driver,
+        };
         // this is just an approximation, but it's as good as it gets without reading the actual input.
         let would_convert_eol = eol::convert_to_git(
             b"\r\n",
@@ -187,6 +206,8 @@ impl Pipeline {
             _attr_digest: _,
             encoding,
             apply_ident_filter,
+            #[cfg(feature = "lfs")]
+            is_lfs,
         } = Configuration::at_path(
             rela_path,
             &self.options.drivers,
@@ -212,6 +233,18 @@ impl Pipeline {
             bufs.swap();
         }
 
+        #[cfg(feature = "lfs")]
+        if let Some(store) = self.options.lfs.as_ref().filter(|_| is_lfs) {
+            let (src, _dest) = bufs.src_and_dest();
+            if let Some(content) =
+                gix_lfs::filter::smudge(store, src).map_err(|err| to_worktree::Error::Lfs(err.into_error()))?
+            {
+                return Ok(ToWorktreeOutcome::Process(driver::apply::MaybeDelayed::Immediate(
+                    Box::new(content),
+                )));
+            }
+        }
+
         if let Some(driver) = driver {
             let (mut src, _dest) = bufs.src_and_dest();
             if let Some(maybe_delayed) = self.processes.apply_delayed(
@@ -259,6 +292,8 @@ pub enum ToWorktreeOutcome<'input, 'pipeline> {
     Buffer(&'pipeline [u8]),
     /// An external filter (and only that) was applied and its results *have to be consumed*. Note that the output might be delayed,
     /// which requires special handling to eventually receive it.
+    ///
+    /// This is also used for the content of LFS objects provided by the built-in `lfs` filter.
     Process(driver::apply::MaybeDelayed<'pipeline>),
 }
 
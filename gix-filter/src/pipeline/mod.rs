@@ -29,6 +29,14 @@ pub struct Options {
     pub encodings_with_roundtrip_check: Vec<&'static encoding_rs::Encoding>,
     /// The object hash to use when applying the `ident` filter.
     pub object_hash: gix_hash::Kind,
+    /// If set, files with the `filter=lfs` attribute are handled by a built-in driver that keeps LFS objects in this store,
+    /// which is typically located at `.git/lfs`.
+    ///
+    /// When converting to Git, content is always stored and replaced with a pointer, without invoking the `lfs` driver.
+    /// When converting to the worktree, pointers are replaced with the content of their object if it's present in the store.
+    /// Otherwise, the `lfs` driver is invoked if it's configured so that it can obtain the object, or the pointer is kept as is.
+    #[cfg(feature = "lfs")]
+    pub lfs: Option<gix_lfs::Store>,
 }
 
 /// Context that typically doesn't change throughout the lifetime of a pipeline, for use with `process` filters.
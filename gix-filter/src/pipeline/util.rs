@@ -19,6 +19,9 @@ pub(crate) struct Configuration<'a> {
     pub(crate) encoding: Option<&'static encoding_rs::Encoding>,
     /// Whether or not to apply the `ident` filter
     pub(crate) apply_ident_filter: bool,
+    /// Whether the `filter` attribute names the `lfs` driver.
+    #[cfg(feature = "lfs")]
+    pub(crate) is_lfs: bool,
 }
 
 impl<'driver> Configuration<'driver> {
@@ -96,6 +99,8 @@ impl<'driver> Configuration<'driver> {
         let attrs: SmallVec<[_; crate::pipeline::ATTRS.len()]> = attrs.iter_selected().collect();
         let apply_ident_filter = attrs[1].assignment.state.is_set();
         let driver = extract_driver(drivers, &attrs[2]);
+        #[cfg(feature = "lfs")]
+        let is_lfs = matches!(attrs[2].assignment.state, StateRef::Value(name) if name.as_bstr() == gix_lfs::filter::DRIVER_NAME);
         let encoding = extract_encoding(&attrs[5])?;
 
         let mut digest = extract_crlf(&attrs[4]);
@@ -131,6 +136,8 @@ impl<'driver> Configuration<'driver> {
             digest: digest.expect("always set by now"),
             encoding,
             apply_ident_filter,
+            #[cfg(feature = "lfs")]
+            is_lfs,
         })
     }
 }
@@ -0,0 +1,132 @@
+use std::{io::Read, path::Path};
+
+use bstr::ByteSlice;
+use gix_filter::pipeline::CrlfRoundTripCheck;
+
+use crate::{driver::apply::driver_with_process, pipeline::pipeline};
+
+const CONTENT: &str = "hello world\n";
+const POINTER: &str = "version https://git-lfs.github.com/spec/v1\noid sha256:a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447\nsize 12\n";
+
+fn lfs_driver() -> gix_filter::Driver {
+    gix_filter::Driver {
+        name: "lfs".into(),
+        ..driver_with_process()
+    }
+}
+
+#[test]
+fn clean_and_smudge_with_builtin_driver() -> gix_testtools::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let (mut cache, mut pipe) = pipeline("lfs", || {
+        (
+            vec![lfs_driver()],
+            Vec::new(),
+            CrlfRoundTripCheck::Skip,
+            Default::default(),
+        )
+    })?;
+    pipe.options_mut().lfs = Some(gix_lfs::Store::at(tmp.path()));
+    let mut attributes = |path: &bstr::BStr, attrs: &mut gix_attributes::search::Outcome| {
+        cache
+            .at_entry(path, None, &gix_object::find::Never)
+            .expect("cannot fail")
+            .matching_attributes(attrs);
+    };
+
+    let pointer = pipe
+        .convert_to_git(CONTENT.as_bytes(), Path::new("file.bin"), &mut attributes, &mut |_| {
+            Ok(None)
+        })?
+        .as_bytes()
+        .expect("pointers are small")
+        .to_owned();
+    assert_eq!(
+        pointer.as_bstr(),
+        POINTER,
+        "the built-in driver is used instead of the configured one"
+    );
+    assert!(
+        gix_lfs::Store::at(tmp.path()).contains(&gix_lfs::Pointer::from_bytes(POINTER.as_bytes()).expect("valid")),
+        "the content was stored"
+    );
+
+    let mut out = pipe.convert_to_worktree(
+        POINTER.as_bytes(),
+        "file.bin".into(),
+        &mut attributes,
+        gix_filter::driver::apply::Delay::Forbid,
+    )?;
+    assert!(out.is_changed());
+    assert!(out.as_read().is_some(), "the object is streamed");
+    let mut buf = Vec::new();
+    out.read_to_end(&mut buf)?;
+    assert_eq!(buf.as_bstr(), CONTENT);
+
+    drop(out);
+    let out = pipe.convert_to_worktree(
+        POINTER.as_bytes(),
+        "file.txt".into(),
+        &mut attributes,
+        gix_filter::driver::apply::Delay::Forbid,
+    )?;
+    assert!(!out.is_changed(), "files without the lfs filter are left alone");
+    Ok(())
+}
+
+#[test]
+fn smudge_falls_back_to_configured_driver_if_object_is_missing() -> gix_testtools::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let (mut cache, mut pipe) = pipeline("lfs", || {
+        (
+            vec![lfs_driver()],
+            Vec::new(),
+            CrlfRoundTripCheck::Skip,
+            Default::default(),
+        )
+    })?;
+    pipe.options_mut().lfs = Some(gix_lfs::Store::at(tmp.path()));
+
+    let mut out = pipe.convert_to_worktree(
+        POINTER.as_bytes(),
+        "file.bin".into(),
+        &mut |path, attrs| {
+            cache
+                .at_entry(path, None, &gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        },
+        gix_filter::driver::apply::Delay::Forbid,
+    )?;
+    let mut buf = Vec::new();
+    out.read_to_end(&mut buf)?;
+    assert!(
+        buf.as_bstr().starts_with_str("➡version"),
+        "the configured driver is given a chance to obtain the object"
+    );
+    Ok(())
+}
+
+#[test]
+fn smudge_keeps_pointer_if_object_is_missing_without_driver() -> gix_testtools::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let (mut cache, mut pipe) = pipeline("lfs", || {
+        (vec![], Vec::new(), CrlfRoundTripCheck::Skip, Default::default())
+    })?;
+    pipe.options_mut().lfs = Some(gix_lfs::Store::at(tmp.path()));
+
+    let out = pipe.convert_to_worktree(
+        POINTER.as_bytes(),
+        "file.bin".into(),
+        &mut |path, attrs| {
+            cache
+                .at_entry(path, None, &gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        },
+        gix_filter::driver::apply::Delay::Forbid,
+    )?;
+    assert!(!out.is_changed());
+    assert_eq!(out.as_bytes().expect("unchanged").as_bstr(), POINTER);
+    Ok(())
+}
@@ -4,6 +4,7 @@ use gix_filter::eol;
 
 mod convert_to_git;
 mod convert_to_worktree;
+mod lfs;
 
 #[test]
 fn default() -> crate::Result {
@@ -66,6 +67,7 @@ fn pipeline(
             encodings_with_roundtrip_check,
             crlf_roundtrip_check,
             object_hash: gix_testtools::object_hash(),
+            ..Default::default()
         },
     );
     Ok((cache, pipe))
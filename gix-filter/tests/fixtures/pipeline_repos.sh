@@ -16,3 +16,9 @@ EOF
 * filter=arrow
 EOF
 )
+
+(mkdir lfs && cd lfs
+  cat <<EOF > .gitattributes
+*.bin filter=lfs -text
+EOF
+)
@@ -0,0 +1,85 @@
+//! Make written files durable so they survive a crash or power loss, similar to what `core.fsync` and `core.fsyncMethod`
+//! control in `git`.
+use std::path::Path;
+
+bitflags::bitflags! {
+    /// The parts of a repository that are to be made durable when written, as configured by `core.fsync`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Components: u8 {
+        /// Loose objects.
+        const LOOSE_OBJECT = 1 << 0;
+        /// Pack data files.
+        const PACK = 1 << 1;
+        /// Pack indices, reverse indices, reachability bitmaps and multi-pack indices.
+        const PACK_METADATA = 1 << 2;
+        /// Commit-graph files.
+        const COMMIT_GRAPH = 1 << 3;
+        /// The index file.
+        const INDEX = 1 << 4;
+        /// Loose references, the `packed-refs` file and reflogs.
+        const REFERENCE = 1 << 5;
+
+        /// All objects, loose or packed.
+        const OBJECTS = Self::LOOSE_OBJECT.bits() | Self::PACK.bits();
+        /// Everything that can be regenerated from objects.
+        const DERIVED_METADATA = Self::PACK_METADATA.bits() | Self::COMMIT_GRAPH.bits();
+        /// Everything that is needed to not lose committed data, i.e. objects and references.
+        const COMMITTED = Self::OBJECTS.bits() | Self::REFERENCE.bits();
+        /// Everything that is committed, along with what was added to the index.
+        const ADDED = Self::COMMITTED.bits() | Self::INDEX.bits();
+    }
+}
+
+impl Default for Components {
+    /// Packs and everything derived from them, but not loose objects, just like the platform default of `git`.
+    fn default() -> Self {
+        (Components::OBJECTS | Components::DERIVED_METADATA) - Components::LOOSE_OBJECT
+    }
+}
+
+/// The way files are made durable, as configured by `core.fsyncMethod`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Method {
+    /// Flush file data and metadata all the way to the storage device.
+    ///
+    /// This is the most durable, but also the slowest method.
+    #[default]
+    Fsync,
+    /// Flush file data, but not necessarily all of its metadata.
+    ///
+    /// This is faster, but depending on the filesystem and storage device, data may still be lost.
+    WriteoutOnly,
+    /// Flush loose objects like [`WriteoutOnly`](Method::WriteoutOnly), but make their directory entries durable
+    /// all at once after writing many of them.
+    ///
+    /// All other files are made durable as if [`Fsync`](Method::Fsync) was used.
+    Batch,
+}
+
+/// Make the contents of `file` durable with `method`.
+///
+/// Note that [`Method::Batch`] is the same as [`Method::Fsync`] here, as batching is up to the caller.
+pub fn file(file: &std::fs::File, method: Method) -> std::io::Result<()> {
+    match method {
+        Method::Fsync | Method::Batch => file.sync_all(),
+        Method::WriteoutOnly => file.sync_data(),
+    }
+}
+
+/// Make the entries of the directory at `path` durable, which is needed for files that were created or renamed in it
+/// to be found after a crash.
+///
+/// This does nothing on platforms which can't open directories like files, like Windows.
+pub fn directory(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        std::fs::File::open(path)?.sync_all()
+    }
+    #[cfg(not(unix))]
+    {
+        _ = path;
+        Ok(())
+    }
+}
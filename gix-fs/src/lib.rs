@@ -100,6 +100,9 @@ pub use read_dir::function::read_dir;
 ///
 pub mod dir;
 
+///
+pub mod fsync;
+
 /// Like [`std::env::current_dir()`], but it will `precompose_unicode` if that value is true, if the current directory
 /// is valid unicode and if there are decomposed unicode codepoints.
 ///
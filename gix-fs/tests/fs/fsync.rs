@@ -0,0 +1,24 @@
+use gix_fs::fsync::{Components, Method};
+
+#[test]
+fn default_components_match_git() {
+    assert_eq!(
+        Components::default(),
+        Components::PACK | Components::PACK_METADATA | Components::COMMIT_GRAPH
+    );
+    assert!(Components::ADDED.contains(Components::COMMITTED | Components::INDEX));
+    assert!(!Components::ADDED.contains(Components::DERIVED_METADATA));
+}
+
+#[test]
+fn file_and_directory() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("file");
+    std::fs::write(&path, b"content")?;
+    let file = std::fs::OpenOptions::new().write(true).open(&path)?;
+    for method in [Method::Fsync, Method::WriteoutOnly, Method::Batch] {
+        gix_fs::fsync::file(&file, method)?;
+    }
+    gix_fs::fsync::directory(dir.path())?;
+    Ok(())
+}
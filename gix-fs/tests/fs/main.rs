@@ -2,6 +2,7 @@ type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error + Send +
 
 mod capabilities;
 mod dir;
+mod fsync;
 mod read_dir;
 mod snapshot;
 mod stack;
@@ -72,7 +72,10 @@ impl File {
         );
         let (version, digest) = self.write_to(&mut lock, options)?;
         match lock.into_inner() {
-            Ok(lock) => lock.commit()?,
+            Ok(lock) => match options.fsync {
+                Some(method) => lock.commit_durably(method)?,
+                None => lock.commit()?,
+            },
             Err(err) => return Err(Error::Io(err.into_error().into())),
         };
         self.state.version = version;
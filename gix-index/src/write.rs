@@ -56,6 +56,10 @@ pub struct Options {
     /// via [`File::write()`](crate::File::write()) and [`File::write_to()`](crate::File::write_to()).
     /// Note that
     pub skip_hash: bool,
+    /// If `Some(method)`, make the index file durable with it when written via [`File::write()`](crate::File::write()).
+    ///
+    /// This is controlled by `index` being part of `core.fsync`.
+    pub fsync: Option<gix_fs::fsync::Method>,
 }
 
 impl State {
@@ -70,6 +74,7 @@ impl State {
         Options {
             extensions,
             skip_hash: _,
+            fsync: _,
         }: Options,
     ) -> Result<Version, gix_hash::io::Error> {
         let _span = gix_features::trace::detail!("gix_index::State::write()");
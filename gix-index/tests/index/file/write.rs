@@ -58,6 +58,7 @@ fn skip_hash() -> crate::Result {
     expected.write(Options {
         extensions: Default::default(),
         skip_hash: false,
+        fsync: None,
     })?;
 
     let actual = gix_index::File::at(
@@ -75,6 +76,7 @@ fn skip_hash() -> crate::Result {
     expected.write(Options {
         extensions: Default::default(),
         skip_hash: true,
+        fsync: None,
     })?;
 
     let actual = gix_index::File::at(
@@ -88,6 +90,33 @@ fn skip_hash() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn durable_write() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("index");
+    let mut expected = Loose("conflicting-file").open();
+    expected.set_path(&path);
+    for method in [
+        gix_fs::fsync::Method::Fsync,
+        gix_fs::fsync::Method::WriteoutOnly,
+        gix_fs::fsync::Method::Batch,
+    ] {
+        expected.write(Options {
+            fsync: Some(method),
+            ..Default::default()
+        })?;
+        let actual = gix_index::File::at(
+            &path,
+            expected.checksum().expect("present").kind(),
+            false,
+            Default::default(),
+        )?;
+        assert_eq!(actual.checksum(), expected.checksum());
+        assert_eq!(actual.entries(), expected.entries(), "{method:?}");
+    }
+    Ok(())
+}
+
 #[test]
 fn roundtrips_sparse_index() -> crate::Result {
     // NOTE: I initially tried putting these fixtures into the main roundtrip test above,
@@ -316,6 +345,7 @@ fn only_tree_ext() -> Options {
             tree_cache: true,
         },
         skip_hash: false,
+        fsync: None,
     }
 }
 
@@ -323,5 +353,6 @@ fn options_with(extensions: write::Extensions) -> Options {
     Options {
         extensions,
         skip_hash: false,
+        fsync: None,
     }
 }
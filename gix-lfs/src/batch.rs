@@ -0,0 +1,138 @@
+//! Types of the [batch API](https://github.com/git-lfs/git-lfs/blob/main/docs/api/batch.md), which is used to
+//! obtain the locations to download LFS objects from or upload them to.
+use std::collections::BTreeMap;
+
+use crate::Pointer;
+
+/// The media type of all requests to and responses from the batch API.
+pub const MEDIA_TYPE: &str = "application/vnd.git-lfs+json";
+
+/// The operation to perform with LFS objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    /// Obtain objects from the server.
+    Download,
+    /// Send objects to the server.
+    Upload,
+}
+
+/// A request to perform an [`Operation`] on a set of objects.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Request {
+    /// The operation to perform.
+    pub operation: Operation,
+    /// The transfer adapters the client supports, of which only `basic` is implemented.
+    pub transfers: Vec<String>,
+    /// The objects to perform the operation on.
+    pub objects: Vec<ObjectSpec>,
+    /// The hash algorithm used to identify objects, which is always `sha256`.
+    pub hash_algo: String,
+}
+
+impl Request {
+    /// Create a new request to perform `operation` on all `objects` with the `basic` transfer adapter.
+    pub fn new<'a>(operation: Operation, objects: impl IntoIterator<Item = &'a Pointer>) -> Self {
+        Request {
+            operation,
+            transfers: vec!["basic".into()],
+            objects: objects
+                .into_iter()
+                .map(|pointer| ObjectSpec {
+                    oid: pointer.oid.to_string(),
+                    size: pointer.size,
+                })
+                .collect(),
+            hash_algo: "sha256".into(),
+        }
+    }
+}
+
+/// An object as identified in requests.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ObjectSpec {
+    /// The hex-encoded SHA-256 of the object.
+    pub oid: String,
+    /// The size of the object in bytes.
+    pub size: u64,
+}
+
+/// The response to a [`Request`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct Response {
+    /// The transfer adapter the server chose, `basic` if unset.
+    #[serde(default)]
+    pub transfer: Option<String>,
+    /// The objects of the request, along with what to do with them.
+    pub objects: Vec<Object>,
+}
+
+/// An object in a [`Response`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct Object {
+    /// The hex-encoded SHA-256 of the object.
+    pub oid: String,
+    /// The size of the object in bytes.
+    pub size: u64,
+    /// The actions to perform to transfer the object.
+    ///
+    /// It's unset if nothing needs to be done, like when uploading an object that the server already has.
+    #[serde(default)]
+    pub actions: Option<Actions>,
+    /// Set if the object can't be transferred.
+    #[serde(default)]
+    pub error: Option<ObjectError>,
+}
+
+impl Object {
+    /// Return `true` if this object is the one `pointer` refers to.
+    pub fn matches(&self, pointer: &Pointer) -> bool {
+        self.size == pointer.size && gix_hash::ObjectId::from_hex(self.oid.as_bytes()).ok() == Some(pointer.oid)
+    }
+}
+
+/// The actions to transfer an [`Object`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct Actions {
+    /// How to obtain the object, set for [downloads](Operation::Download).
+    #[serde(default)]
+    pub download: Option<Action>,
+    /// How to send the object, set for [uploads](Operation::Upload).
+    #[serde(default)]
+    pub upload: Option<Action>,
+    /// How to let the server verify an upload once it's done, optional for [uploads](Operation::Upload).
+    #[serde(default)]
+    pub verify: Option<Action>,
+}
+
+/// An HTTP request to perform as part of a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct Action {
+    /// The URL to send the request to.
+    pub href: String,
+    /// Headers to add to the request.
+    #[serde(default)]
+    pub header: BTreeMap<String, String>,
+}
+
+impl Action {
+    /// Return all headers as they would appear in an HTTP request, like `name: value`.
+    pub fn header_lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.header.iter().map(|(name, value)| format!("{name}: {value}"))
+    }
+}
+
+/// The reason an [`Object`] can't be transferred.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ObjectError {
+    /// A code that is similar to an HTTP status code, like `404` if the object doesn't exist.
+    pub code: u32,
+    /// A human-readable description of the error.
+    pub message: String,
+}
+
+impl std::fmt::Display for ObjectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
@@ -0,0 +1,325 @@
+use std::io::{Read, Write};
+
+use base64::Engine;
+use gix_error::{ErrorExt, OptionExt, ResultExt, message};
+use gix_transport::client::blocking_io::http::{self, GetResponse, Http, PostBodyDataKind, PostResponse};
+
+use crate::{Client, Pointer, Store, batch};
+
+/// The error returned by all methods of the [`Client`].
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// The function used to obtain credentials, just like the one used by `gix-protocol`.
+pub type AuthenticateFn<'a> = dyn FnMut(gix_credentials::helper::Action) -> gix_credentials::protocol::Result + 'a;
+
+/// The outcome of [`Client::download()`] and [`Client::upload()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// The objects that were transferred.
+    pub transferred: Vec<Pointer>,
+    /// The objects that the server refused to transfer, along with the reason.
+    pub failed: Vec<(Pointer, batch::ObjectError)>,
+}
+
+/// Return the URL of the LFS server that `git-lfs` would use by default for the remote repository at `url`,
+/// or `None` if the URL doesn't use a protocol that LFS servers can be derived from.
+///
+/// For `https://host/repo`, this is `https://host/repo.git/info/lfs`, and `ssh` URLs are assumed to have a server at
+/// the `https` URL of the same host and path.
+pub fn endpoint_from_url(url: &gix_url::Url) -> Option<String> {
+    let mut url = url.clone();
+    match url.scheme {
+        gix_url::Scheme::Http | gix_url::Scheme::Https => {}
+        gix_url::Scheme::Ssh => {
+            url.scheme = gix_url::Scheme::Https;
+            url.port = None;
+            url.serialize_alternative_form = false;
+            if !url.path.starts_with(b"/") {
+                url.path.insert(0, b'/');
+            }
+        }
+        _ => return None,
+    }
+    url.user = None;
+    url.password = None;
+    while url.path.len() > 1 && url.path.ends_with(b"/") {
+        url.path.pop();
+    }
+    if !url.path.ends_with(b".git") {
+        url.path.extend_from_slice(b".git");
+    }
+    url.path.extend_from_slice(b"/info/lfs");
+    Some(url.to_bstring().to_string())
+}
+
+/// Lifecycle
+impl<H: Http + Default> Client<H> {
+    /// Create a new instance to communicate with the LFS server at `endpoint`, like the one returned by [`endpoint_from_url()`].
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Client {
+            endpoint: endpoint.into(),
+            api: H::default(),
+            options: Default::default(),
+            identity: None,
+        }
+    }
+}
+
+/// Access and configuration
+impl<H: Http + Default> Client<H> {
+    /// Return the URL of the LFS server.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Configure all HTTP requests made by this instance with `options`.
+    pub fn configure(&mut self, options: http::Options) -> Result<(), Error> {
+        self.api
+            .configure(&options)
+            .map_err(|err| message!("Could not configure HTTP client: {err}").raise())?;
+        self.options = options;
+        Ok(())
+    }
+
+    /// Authenticate with `identity` at the batch API, which otherwise is only done once the server requires it.
+    pub fn set_identity(&mut self, identity: gix_sec::identity::Account) {
+        self.identity = Some(identity);
+    }
+}
+
+/// Transfers
+impl<H: Http + Default> Client<H> {
+    /// Ask the server how to perform `operation` on `objects`, obtaining credentials with `authenticate`
+    /// if the server requires authentication.
+    pub fn batch(
+        &mut self,
+        operation: batch::Operation,
+        objects: &[Pointer],
+        authenticate: &mut AuthenticateFn<'_>,
+    ) -> Result<batch::Response, Error> {
+        let request = serde_json::to_vec(&batch::Request::new(operation, objects))
+            .or_raise(|| message("Could not serialize batch request"))?;
+        let response = match self.post_batch(&request) {
+            Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+                let gix_credentials::protocol::Outcome { identity, next } =
+                    authenticate(gix_credentials::helper::Action::get_for_url(self.endpoint.clone()))
+                        .or_raise(|| message!("Could not obtain credentials for '{}'", self.endpoint))?
+                        .ok_or_raise(|| message!("No credentials were provided for '{}'", self.endpoint))?;
+                self.identity = Some(identity);
+                match self.post_batch(&request) {
+                    Ok(response) => {
+                        authenticate(next.store())
+                            .or_raise(|| message!("Could not store credentials for '{}'", self.endpoint))?;
+                        response
+                    }
+                    Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+                        authenticate(next.erase())
+                            .or_raise(|| message!("Could not erase credentials for '{}'", self.endpoint))?;
+                        return Err(err).or_raise(|| message!("Credentials for '{}' were rejected", self.endpoint));
+                    }
+                    Err(err) => return Err(err).or_raise(|| message!("Batch request to '{}' failed", self.endpoint)),
+                }
+            }
+            res => res.or_raise(|| message!("Batch request to '{}' failed", self.endpoint))?,
+        };
+        serde_json::from_slice(&response)
+            .or_raise(|| message!("Could not parse batch response from '{}'", self.endpoint))
+    }
+
+    /// Download all `objects` that aren't present in `store` yet from the server, and add them to `store`,
+    /// obtaining credentials with `authenticate` if the server requires authentication.
+    ///
+    /// The content of each object is verified before it's added.
+    pub fn download(
+        &mut self,
+        store: &Store,
+        objects: &[Pointer],
+        authenticate: &mut AuthenticateFn<'_>,
+    ) -> Result<Outcome, Error> {
+        let missing: Vec<_> = objects.iter().filter(|p| !store.contains(p)).copied().collect();
+        let mut out = Outcome::default();
+        if missing.is_empty() {
+            return Ok(out);
+        }
+        let response = self.batch(batch::Operation::Download, &missing, authenticate)?;
+        for pointer in missing {
+            let object = find_object(&response, &pointer)?;
+            if let Some(err) = &object.error {
+                out.failed.push((pointer, err.clone()));
+                continue;
+            }
+            let action = object
+                .actions
+                .as_ref()
+                .and_then(|actions| actions.download.as_ref())
+                .ok_or_raise(|| message!("The server didn't say how to download LFS object {}", pointer.oid))?;
+
+            let mut http = self.transfer_client()?;
+            let GetResponse { headers, mut body } = http
+                .get(&action.href, &action.href, action.header_lines())
+                .or_raise(|| message!("Could not download LFS object {}", pointer.oid))?;
+            drain(headers).or_raise(|| message!("Could not download LFS object {}", pointer.oid))?;
+            store.insert_verified(&mut body, &pointer)?;
+            out.transferred.push(pointer);
+        }
+        Ok(out)
+    }
+
+    /// Upload all `objects` from `store` to the server unless it already has them,
+    /// obtaining credentials with `authenticate` if the server requires authentication.
+    ///
+    /// It's an error if one of the `objects` isn't present in `store`.
+    pub fn upload(
+        &mut self,
+        store: &Store,
+        objects: &[Pointer],
+        authenticate: &mut AuthenticateFn<'_>,
+    ) -> Result<Outcome, Error> {
+        let mut out = Outcome::default();
+        if objects.is_empty() {
+            return Ok(out);
+        }
+        let response = self.batch(batch::Operation::Upload, objects, authenticate)?;
+        for pointer in objects.iter().copied() {
+            let object = find_object(&response, &pointer)?;
+            if let Some(err) = &object.error {
+                out.failed.push((pointer, err.clone()));
+                continue;
+            }
+            let Some(actions) = object.actions.as_ref() else {
+                // The server already has the object.
+                continue;
+            };
+            let Some(upload) = actions.upload.as_ref() else {
+                continue;
+            };
+
+            let mut content = store
+                .open(&pointer)?
+                .ok_or_raise(|| message!("LFS object {} isn't present in the local store", pointer.oid))?;
+            let mut http = self.transfer_client()?;
+            let PostResponse {
+                mut post_body,
+                headers,
+                body,
+            } = http
+                .put(
+                    &upload.href,
+                    &upload.href,
+                    upload
+                        .header_lines()
+                        .chain(Some("Content-Type: application/octet-stream".into())),
+                    pointer.size,
+                )
+                .or_raise(|| message!("Could not upload LFS object {}", pointer.oid))?;
+            std::io::copy(&mut content, &mut post_body)
+                .or_raise(|| message!("Could not upload LFS object {}", pointer.oid))?;
+            drop(post_body);
+            drain(headers)
+                .and_then(|()| drain(body))
+                .or_raise(|| message!("Could not upload LFS object {}", pointer.oid))?;
+
+            if let Some(verify) = actions.verify.as_ref() {
+                let request = serde_json::to_vec(&batch::ObjectSpec {
+                    oid: pointer.oid.to_string(),
+                    size: pointer.size,
+                })
+                .or_raise(|| message("Could not serialize verification request"))?;
+                let PostResponse {
+                    mut post_body,
+                    headers,
+                    body,
+                } = http
+                    .post(
+                        &verify.href,
+                        &verify.href,
+                        verify.header_lines().chain([
+                            format!("Accept: {}", batch::MEDIA_TYPE),
+                            format!("Content-Type: {}", batch::MEDIA_TYPE),
+                        ]),
+                        PostBodyDataKind::BoundedAndFitsIntoMemory,
+                    )
+                    .or_raise(|| message!("Could not verify upload of LFS object {}", pointer.oid))?;
+                post_body
+                    .write_all(&request)
+                    .or_raise(|| message!("Could not verify upload of LFS object {}", pointer.oid))?;
+                drop(post_body);
+                drain(headers)
+                    .and_then(|()| drain(body))
+                    .or_raise(|| message!("Could not verify upload of LFS object {}", pointer.oid))?;
+            }
+            out.transferred.push(pointer);
+        }
+        Ok(out)
+    }
+}
+
+/// Utilities
+impl<H: Http + Default> Client<H> {
+    /// Send the serialized batch `request` and return the response body, preserving the kind of IO errors
+    /// so authentication failures can be detected.
+    fn post_batch(&mut self, request: &[u8]) -> std::io::Result<Vec<u8>> {
+        let url = format!("{}/objects/batch", self.endpoint.trim_end_matches('/'));
+        let mut headers = vec![
+            concat!("User-Agent: gix-lfs/", env!("CARGO_PKG_VERSION")).to_owned(),
+            format!("Accept: {}", batch::MEDIA_TYPE),
+            format!("Content-Type: {}", batch::MEDIA_TYPE),
+        ];
+        if let Some(identity) = &self.identity {
+            #[cfg(not(feature = "http-client-insecure-credentials"))]
+            if self.endpoint.starts_with("http://") {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "Will not send credentials in clear text over http",
+                ));
+            }
+            headers.push(format!(
+                "Authorization: Basic {}",
+                base64::engine::general_purpose::STANDARD
+                    .encode(format!("{}:{}", identity.username, identity.password))
+            ));
+        }
+
+        let PostResponse {
+            mut post_body,
+            headers,
+            body,
+        } = self
+            .api
+            .post(
+                &url,
+                &self.endpoint,
+                headers,
+                PostBodyDataKind::BoundedAndFitsIntoMemory,
+            )
+            .map_err(std::io::Error::other)?;
+        post_body.write_all(request)?;
+        drop(post_body);
+        drain(headers)?;
+        let mut buf = Vec::new();
+        { body }.read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Create a new HTTP client for transfers, which go to arbitrary URLs and thus can't share the state of the one
+    /// used for the batch API.
+    fn transfer_client(&self) -> Result<H, Error> {
+        let mut http = H::default();
+        http.configure(&self.options)
+            .map_err(|err| message!("Could not configure HTTP client: {err}").raise())?;
+        Ok(http)
+    }
+}
+
+fn find_object<'a>(response: &'a batch::Response, pointer: &Pointer) -> Result<&'a batch::Object, Error> {
+    response
+        .objects
+        .iter()
+        .find(|object| object.matches(pointer))
+        .ok_or_raise(|| message!("The server didn't respond with LFS object {}", pointer.oid))
+}
+
+/// Read all of `read`, which for headers is where HTTP implementations report unsuccessful status codes.
+fn drain(mut read: impl Read) -> std::io::Result<()> {
+    std::io::copy(&mut read, &mut std::io::sink()).map(|_| ())
+}
@@ -0,0 +1,43 @@
+use std::io::Read;
+
+use gix_error::{ResultExt, message};
+
+use crate::{Pointer, Store, pointer};
+
+/// The error returned by [`clean()`] and [`smudge()`].
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// The name of the filter driver that is assigned to LFS files with the `filter=lfs` attribute.
+pub const DRIVER_NAME: &str = "lfs";
+
+/// Read all of `src`, the content of a worktree file, and store it in `store`, writing the pointer file
+/// that refers to it into `out` for storage in Git, just like `git lfs clean` does.
+///
+/// Content that already is a pointer file is written to `out` unchanged, and empty content stays empty.
+pub fn clean(store: &Store, src: &mut dyn Read, out: &mut Vec<u8>) -> Result<(), Error> {
+    out.clear();
+    src.take(pointer::MAX_SIZE as u64 + 1)
+        .read_to_end(out)
+        .or_raise(|| message("Could not read content to clean"))?;
+    if out.is_empty() || Pointer::from_bytes(out).is_ok() {
+        return Ok(());
+    }
+
+    let head = std::mem::take(out);
+    let pointer = store.insert(&mut head.as_slice().chain(src))?;
+    pointer
+        .write_to(out)
+        .or_raise(|| message("Could not write pointer file"))
+}
+
+/// Return the content of the object that the pointer file in `src` refers to if it's present in `store`,
+/// just like `git lfs smudge` does.
+///
+/// Return `None` if `src` isn't a pointer file, in which case it should be used as is,
+/// or if the object isn't present locally, in which case it has to be obtained from elsewhere.
+pub fn smudge(store: &Store, src: &[u8]) -> Result<Option<std::fs::File>, Error> {
+    let Ok(pointer) = Pointer::from_bytes(src) else {
+        return Ok(None);
+    };
+    store.open(&pointer)
+}
@@ -1 +1,70 @@
+//! Handle files tracked with [Git LFS](https://github.com/git-lfs/git-lfs/blob/main/docs/spec.md), whose content is kept
+//! outside of the object database while Git only stores small *pointer files* that refer to it.
+//!
+//! * [`Pointer`] parses and writes pointer files.
+//! * [`Store`] holds the content of LFS objects locally, typically in `.git/lfs/objects`.
+//! * [`filter`] implements the `clean` and `smudge` operations of the `lfs` filter driver on top of a [`Store`],
+//!   which makes it possible to avoid spawning `git-lfs` for objects that are present locally.
+//! * [`Client`] transfers LFS objects between a [`Store`] and a server using the [batch API](batch),
+//!   with the `http-client` feature.
+//!
+//! ## Feature Flags
+#![cfg_attr(
+    all(doc, feature = "document-features"),
+    doc = ::document_features::document_features!()
+)]
+#![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg))]
+#![deny(missing_docs)]
 #![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+use gix_hash::ObjectId;
+
+/// The parsed form of an LFS pointer file, which is stored in Git in place of the actual content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Pointer {
+    /// The SHA-256 hash of the content, which identifies the LFS object.
+    pub oid: ObjectId,
+    /// The size of the content in bytes.
+    pub size: u64,
+}
+
+/// A local store of LFS objects, laid out like `git-lfs` does.
+///
+/// Each object is stored by its hash as `objects/ab/cd/abcd…`, and `tmp/` is used for objects that are being written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Store {
+    /// The directory that contains the `objects` directory, typically `.git/lfs`.
+    dir: PathBuf,
+}
+
+///
+pub mod pointer;
+
+///
+pub mod store;
+
+///
+pub mod filter;
+
+/// A client for the LFS server at an endpoint, which transfers objects with the [batch API](batch) using
+/// the blocking HTTP implementation `H`.
+#[cfg(feature = "http-client")]
+pub struct Client<H> {
+    /// The URL of the LFS server, typically `<remote-url>.git/info/lfs`.
+    endpoint: String,
+    /// The HTTP implementation to use for batch API requests.
+    api: H,
+    /// The options to configure every HTTP implementation with, including the ones used for transfers.
+    options: gix_transport::client::blocking_io::http::Options,
+    /// The identity to authenticate with at the batch API, if known.
+    identity: Option<gix_sec::identity::Account>,
+}
+
+#[cfg(feature = "http-client")]
+pub mod batch;
+
+///
+#[cfg(feature = "http-client")]
+pub mod client;
@@ -0,0 +1,92 @@
+use bstr::ByteSlice;
+use gix_error::{ErrorExt, OptionExt, ResultExt, ValidationError};
+use gix_hash::ObjectId;
+
+use crate::Pointer;
+
+/// The error returned by [`Pointer::from_bytes()`].
+pub type Error = gix_error::Exn<ValidationError>;
+
+/// The version of the specification that pointers are written with.
+pub const VERSION: &str = "https://git-lfs.github.com/spec/v1";
+
+/// The version that pre-release versions of `git-lfs` wrote, which is still accepted when reading.
+const LEGACY_VERSION: &str = "https://hawser.github.com/spec/v1";
+
+/// The maximum size of a pointer file in bytes. Larger files are never considered to be pointers.
+pub const MAX_SIZE: usize = 1024;
+
+/// The prefix of the `oid` value, which indicates the hash algorithm used.
+const OID_PREFIX: &str = "sha256:";
+
+/// Serialization
+impl Pointer {
+    /// Parse a pointer file from `data`, which must be a valid pointer in its entirety.
+    ///
+    /// Keys other than `version`, `oid` and `size` are ignored, except for those of pointer extensions,
+    /// which aren't supported.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        if data.len() > MAX_SIZE {
+            return Err(ValidationError::new("Pointer files can't be larger than 1024 bytes").raise());
+        }
+        if !data.ends_with(b"\n") {
+            return Err(ValidationError::new_with_input("Pointer files must end with a newline", data).raise());
+        }
+        let mut lines = data[..data.len() - 1].split_str(b"\n");
+        let version = lines
+            .next()
+            .and_then(|line| line.strip_prefix(b"version "))
+            .ok_or_raise(|| ValidationError::new_with_input("Pointer files must start with the version", data))?;
+        if version != VERSION.as_bytes() && version != LEGACY_VERSION.as_bytes() {
+            return Err(ValidationError::new_with_input("Unsupported pointer version", version).raise());
+        }
+
+        let (mut oid, mut size) = (None, None);
+        for line in lines {
+            let (key, value) = line
+                .split_once_str(b" ")
+                .ok_or_raise(|| ValidationError::new_with_input("Pointer lines must be 'key value' pairs", line))?;
+            match key {
+                b"oid" => {
+                    let hex = value.strip_prefix(OID_PREFIX.as_bytes()).ok_or_raise(|| {
+                        ValidationError::new_with_input("Only sha256 object ids are supported", value)
+                    })?;
+                    if hex.len() != gix_hash::Kind::Sha256.len_in_hex() || hex.iter().any(u8::is_ascii_uppercase) {
+                        return Err(ValidationError::new_with_input("Invalid object id", value).raise());
+                    }
+                    oid = Some(
+                        ObjectId::from_hex(hex)
+                            .or_raise(|| ValidationError::new_with_input("Invalid object id", value))?,
+                    );
+                }
+                b"size" => {
+                    size = Some(
+                        value
+                            .to_str()
+                            .ok()
+                            .filter(|size| size.bytes().all(|b| b.is_ascii_digit()))
+                            .and_then(|size| size.parse::<u64>().ok())
+                            .ok_or_raise(|| ValidationError::new_with_input("Invalid size", value))?,
+                    );
+                }
+                key if key.starts_with(b"ext-") => {
+                    return Err(ValidationError::new_with_input("Pointer extensions aren't supported", line).raise());
+                }
+                _ => {}
+            }
+        }
+        Ok(Pointer {
+            oid: oid.ok_or_raise(|| ValidationError::new_with_input("The 'oid' is missing", data))?,
+            size: size.ok_or_raise(|| ValidationError::new_with_input("The 'size' is missing", data))?,
+        })
+    }
+
+    /// Serialize this instance to `out` as pointer file, just like `git-lfs` would.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write!(
+            out,
+            "version {VERSION}\noid {OID_PREFIX}{}\nsize {}\n",
+            self.oid, self.size
+        )
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use gix_error::{ErrorExt, ResultExt, message};
+use gix_hash::oid;
+
+use crate::{Pointer, Store};
+
+/// The error returned by [`Store::open()`], [`Store::insert()`] and [`Store::insert_verified()`].
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// Lifecycle
+impl Store {
+    /// Create a store for the LFS directory at `dir`, which is typically `.git/lfs` in the common directory of a repository.
+    ///
+    /// The directory doesn't have to exist yet.
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Store { dir: dir.into() }
+    }
+}
+
+/// Access
+impl Store {
+    /// Return the LFS directory this store was created with.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Return the path at which the content of the object with `oid` is stored, whether it exists or not.
+    pub fn object_path(&self, oid: &oid) -> PathBuf {
+        let hex = oid.to_string();
+        self.dir.join("objects").join(&hex[..2]).join(&hex[2..4]).join(hex)
+    }
+
+    /// Return `true` if the content of the object `pointer` refers to is present, and has the size it's supposed to have.
+    pub fn contains(&self, pointer: &Pointer) -> bool {
+        std::fs::metadata(self.object_path(&pointer.oid)).is_ok_and(|m| m.is_file() && m.len() == pointer.size)
+    }
+
+    /// Open the content of the object `pointer` refers to for reading, or return `None` if it isn't present
+    /// or doesn't have the expected size.
+    pub fn open(&self, pointer: &Pointer) -> Result<Option<std::fs::File>, Error> {
+        let path = self.object_path(&pointer.oid);
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).or_raise(|| message!("Could not open LFS object at '{}'", path.display())),
+        };
+        let len = file
+            .metadata()
+            .or_raise(|| message!("Could not obtain metadata of LFS object at '{}'", path.display()))?
+            .len();
+        Ok((len == pointer.size).then_some(file))
+    }
+}
+
+/// Modification
+impl Store {
+    /// Read all of `content` and store it as LFS object, returning the pointer that refers to it.
+    ///
+    /// The content is streamed into a temporary file in `tmp/` while it's hashed, which is then moved into place
+    /// unless the object already exists.
+    pub fn insert(&self, content: &mut dyn Read) -> Result<Pointer, Error> {
+        let (tmp, pointer) = self.write_to_temporary_file(content)?;
+        self.persist(tmp, &pointer)?;
+        Ok(pointer)
+    }
+
+    /// Read all of `content`, which is expected to be the content of the object `expected` refers to, and store it.
+    ///
+    /// This is useful for content obtained from elsewhere, which isn't stored if it turns out to be different.
+    pub fn insert_verified(&self, content: &mut dyn Read, expected: &Pointer) -> Result<(), Error> {
+        let (tmp, actual) = self.write_to_temporary_file(content)?;
+        if actual != *expected {
+            return Err(message!(
+                "LFS object {} with size {} was expected, but got {} with size {}",
+                expected.oid,
+                expected.size,
+                actual.oid,
+                actual.size
+            )
+            .raise());
+        }
+        self.persist(tmp, &actual)
+    }
+
+    fn write_to_temporary_file(&self, content: &mut dyn Read) -> Result<(tempfile::NamedTempFile, Pointer), Error> {
+        let tmp_dir = self.dir.join("tmp");
+        std::fs::create_dir_all(&tmp_dir)
+            .or_raise(|| message!("Could not create directory at '{}'", tmp_dir.display()))?;
+        let mut tmp = tempfile::NamedTempFile::new_in(&tmp_dir)
+            .or_raise(|| message!("Could not create temporary file in '{}'", tmp_dir.display()))?;
+
+        let mut hasher = gix_hash::hasher(gix_hash::Kind::Sha256);
+        let mut size = 0;
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let bytes_read = match content.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err).or_raise(|| message("Could not read content to store as LFS object")),
+            };
+            let chunk = &buf[..bytes_read];
+            hasher.update(chunk);
+            tmp.write_all(chunk)
+                .or_raise(|| message!("Could not write to temporary file in '{}'", tmp_dir.display()))?;
+            size += bytes_read as u64;
+        }
+        let oid = hasher
+            .try_finalize()
+            .or_raise(|| message("Could not hash the content to store as LFS object"))?;
+        Ok((tmp, Pointer { oid, size }))
+    }
+
+    fn persist(&self, tmp: tempfile::NamedTempFile, pointer: &Pointer) -> Result<(), Error> {
+        if self.contains(pointer) {
+            return Ok(());
+        }
+        let path = self.object_path(&pointer.oid);
+        let dir = path.parent().expect("objects are in a directory");
+        std::fs::create_dir_all(dir).or_raise(|| message!("Could not create directory at '{}'", dir.display()))?;
+        tmp.persist(&path)
+            .map_err(|err| err.error)
+            .or_raise(|| message!("Could not move LFS object into place at '{}'", path.display()))?;
+        Ok(())
+    }
+}
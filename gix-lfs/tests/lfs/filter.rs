@@ -0,0 +1,57 @@
+use std::io::Read;
+
+use gix_lfs::{Store, filter};
+
+use crate::{hello_pointer, hello_pointer_file};
+
+#[test]
+fn clean_then_smudge() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    let mut out = Vec::new();
+    filter::clean(&store, &mut &b"hello world\n"[..], &mut out).map_err(gix_error::Exn::into_error)?;
+    assert_eq!(out, hello_pointer_file().as_bytes());
+    assert!(store.contains(&hello_pointer()));
+
+    let mut content = Vec::new();
+    filter::smudge(&store, &out)
+        .map_err(gix_error::Exn::into_error)?
+        .expect("present")
+        .read_to_end(&mut content)?;
+    assert_eq!(content, b"hello world\n");
+    Ok(())
+}
+
+#[test]
+fn clean_passes_pointers_and_empty_content_through() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    let mut out = Vec::new();
+    filter::clean(&store, &mut hello_pointer_file().as_bytes(), &mut out).map_err(gix_error::Exn::into_error)?;
+    assert_eq!(out, hello_pointer_file().as_bytes());
+    assert!(!store.contains(&hello_pointer()), "nothing was stored");
+
+    filter::clean(&store, &mut &b""[..], &mut out).map_err(gix_error::Exn::into_error)?;
+    assert!(out.is_empty());
+    assert!(!tmp.path().join("objects").exists());
+    Ok(())
+}
+
+#[test]
+fn smudge_without_pointer_or_object() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    assert!(
+        filter::smudge(&store, hello_pointer_file().as_bytes())
+            .map_err(gix_error::Exn::into_error)?
+            .is_none(),
+        "the object isn't present"
+    );
+    assert!(
+        filter::smudge(&store, b"hello world\n")
+            .map_err(gix_error::Exn::into_error)?
+            .is_none(),
+        "this isn't a pointer"
+    );
+    Ok(())
+}
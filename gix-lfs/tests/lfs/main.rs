@@ -0,0 +1,22 @@
+use gix_hash::ObjectId;
+
+mod client;
+mod filter;
+mod pointer;
+mod store;
+
+pub use gix_testtools::Result;
+
+/// The SHA-256 of `hello world\n`.
+const HELLO_OID: &str = "a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447";
+
+fn hello_pointer() -> gix_lfs::Pointer {
+    gix_lfs::Pointer {
+        oid: ObjectId::from_hex(HELLO_OID.as_bytes()).expect("valid"),
+        size: 12,
+    }
+}
+
+fn hello_pointer_file() -> String {
+    format!("version https://git-lfs.github.com/spec/v1\noid sha256:{HELLO_OID}\nsize 12\n")
+}
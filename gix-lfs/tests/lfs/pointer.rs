@@ -0,0 +1,46 @@
+use gix_lfs::Pointer;
+
+use crate::{HELLO_OID, hello_pointer, hello_pointer_file};
+
+#[test]
+fn round_trip() -> crate::Result {
+    let pointer = Pointer::from_bytes(hello_pointer_file().as_bytes()).map_err(gix_error::Exn::into_error)?;
+    assert_eq!(pointer, hello_pointer());
+
+    let mut buf = Vec::new();
+    pointer.write_to(&mut buf)?;
+    assert_eq!(buf, hello_pointer_file().as_bytes(), "it writes pointers like git-lfs");
+    Ok(())
+}
+
+#[test]
+fn legacy_version_and_unknown_keys_are_accepted() -> crate::Result {
+    let pointer = Pointer::from_bytes(
+        format!("version https://hawser.github.com/spec/v1\noid sha256:{HELLO_OID}\nother value\nsize 12\n").as_bytes(),
+    )
+    .map_err(gix_error::Exn::into_error)?;
+    assert_eq!(pointer, hello_pointer());
+    Ok(())
+}
+
+#[test]
+fn invalid() {
+    let version = "version https://git-lfs.github.com/spec/v1";
+    for input in [
+        String::new(),
+        "hello world\n".into(),
+        hello_pointer_file().trim_end().to_owned(),
+        format!("oid sha256:{HELLO_OID}\n{version}\nsize 12\n"),
+        format!("version https://example.com/v2\noid sha256:{HELLO_OID}\nsize 12\n"),
+        format!("{version}\nsize 12\n"),
+        format!("{version}\noid sha256:{HELLO_OID}\n"),
+        format!("{version}\noid sha1:{HELLO_OID}\nsize 12\n"),
+        format!("{version}\noid sha256:{}\nsize 12\n", HELLO_OID.to_uppercase()),
+        format!("{version}\noid sha256:{}\nsize 12\n", &HELLO_OID[1..]),
+        format!("{version}\noid sha256:{HELLO_OID}\nsize -12\n"),
+        format!("{version}\next-0-foo sha256:{HELLO_OID}\noid sha256:{HELLO_OID}\nsize 12\n"),
+        format!("{}{}", hello_pointer_file(), "x".repeat(1024)),
+    ] {
+        assert!(Pointer::from_bytes(input.as_bytes()).is_err(), "{input:?}");
+    }
+}
@@ -0,0 +1,86 @@
+use std::io::Read;
+
+use gix_lfs::Store;
+
+use crate::{HELLO_OID, hello_pointer};
+
+#[test]
+fn insert_and_open() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path().join("lfs"));
+    let pointer = hello_pointer();
+    assert!(!store.contains(&pointer));
+    assert!(
+        store.open(&pointer).map_err(gix_error::Exn::into_error)?.is_none(),
+        "missing objects aren't an error"
+    );
+
+    let inserted = store
+        .insert(&mut &b"hello world\n"[..])
+        .map_err(gix_error::Exn::into_error)?;
+    assert_eq!(inserted, pointer);
+    assert_eq!(
+        store.object_path(&pointer.oid),
+        tmp.path()
+            .join("lfs")
+            .join("objects")
+            .join("a9")
+            .join("48")
+            .join(HELLO_OID),
+        "objects are laid out like git-lfs does"
+    );
+    assert!(store.contains(&pointer));
+    let mut content = String::new();
+    std::io::Read::read_to_string(
+        &mut store
+            .open(&pointer)
+            .map_err(gix_error::Exn::into_error)?
+            .expect("present"),
+        &mut content,
+    )?;
+    assert_eq!(content, "hello world\n");
+
+    assert_eq!(
+        store
+            .insert(&mut &b"hello world\n"[..])
+            .map_err(gix_error::Exn::into_error)?,
+        pointer,
+        "inserting the same content again is fine"
+    );
+    assert_eq!(
+        std::fs::read_dir(tmp.path().join("lfs").join("tmp"))?.count(),
+        0,
+        "no temporary files are left behind"
+    );
+    Ok(())
+}
+
+#[test]
+fn objects_with_unexpected_size_are_considered_missing() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    let pointer = hello_pointer();
+    let path = store.object_path(&pointer.oid);
+    std::fs::create_dir_all(path.parent().expect("parent"))?;
+    std::fs::write(&path, "hello")?;
+
+    assert!(!store.contains(&pointer));
+    assert!(store.open(&pointer).map_err(gix_error::Exn::into_error)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn large_content() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    let pointer = store
+        .insert(&mut std::io::repeat(0).take(100_000))
+        .map_err(gix_error::Exn::into_error)?;
+    assert_eq!(pointer.size, 100_000);
+    assert_eq!(
+        pointer.oid.to_string(),
+        "9192c25b734fcbadbe32dadc28089c60db0e39f90cc20ce2e5733f57261acc0c"
+    );
+    assert_eq!(std::fs::metadata(store.object_path(&pointer.oid))?.len(), 100_000);
+    Ok(())
+}
@@ -73,4 +73,23 @@ impl File {
             }),
         }
     }
+
+    /// Like [`commit()`](Self::commit()), but make everything written so far durable with `method` before moving it into place,
+    /// and make the move itself durable afterwards by syncing the directory containing the resource.
+    ///
+    /// Failing to sync the directory is ignored as the change is already visible at that point,
+    /// and not all platforms and filesystems support it.
+    pub fn commit_durably(
+        mut self,
+        method: gix_fs::fsync::Method,
+    ) -> Result<(PathBuf, Option<std::fs::File>), Error<Self>> {
+        if let Err(error) = self.with_mut(|file| gix_fs::fsync::file(file, method)) {
+            return Err(Error { error, instance: self });
+        }
+        let (resource_path, file) = self.commit()?;
+        if let Some(directory) = resource_path.parent() {
+            gix_fs::fsync::directory(directory).ok();
+        }
+        Ok((resource_path, file))
+    }
 }
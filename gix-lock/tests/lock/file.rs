@@ -84,6 +84,27 @@ mod commit {
         );
         Ok(())
     }
+
+    #[test]
+    fn durably_with_all_methods() -> crate::Result {
+        use std::io::Write;
+        let dir = tempfile::tempdir()?;
+        for method in [
+            gix_fs::fsync::Method::Fsync,
+            gix_fs::fsync::Method::WriteoutOnly,
+            gix_fs::fsync::Method::Batch,
+        ] {
+            let resource = dir.path().join(format!("{method:?}"));
+            let mut file = gix_lock::File::acquire_to_update_resource(&resource, Fail::Immediately, None)?;
+            let lock_path = file.lock_path().to_owned();
+            file.write_all(b"durable")?;
+            let (actual, _open_file) = file.commit_durably(method)?;
+            assert_eq!(actual, resource);
+            assert!(!lock_path.is_file(), "the lock was moved into place");
+            assert_eq!(std::fs::read(resource)?, b"durable".to_vec());
+        }
+        Ok(())
+    }
 }
 
 mod acquire {
@@ -3,6 +3,8 @@ extern crate core;
 
 mod blob;
 mod tree;
+#[path = "../utils/mod.rs"]
+pub mod utils;
 
 pub use gix_testtools::Result;
 
@@ -1,14 +1,14 @@
-use std::path::Path;
-
 use gix_diff::Rewrites;
 use gix_merge::{
     commit::Options,
     tree::{TreatAsUnresolved, apply_index_entries::RemovalMode, treat_as_unresolved},
 };
 use gix_object::Write;
-use gix_worktree::stack::state::attributes;
 
-use crate::tree::baseline::Deviation;
+use crate::{
+    tree::baseline::Deviation,
+    utils::{new_blob_merge_platform, new_diff_resource_cache},
+};
 
 /// ### How to add a new baseline test
 ///
@@ -236,51 +236,4 @@ fn basic_merge_options() -> Options {
     }
 }
 
-fn new_diff_resource_cache(root: &Path) -> gix_diff::blob::Platform {
-    gix_diff::blob::Platform::new(
-        Default::default(),
-        gix_diff::blob::Pipeline::new(Default::default(), Default::default(), Vec::new(), Default::default()),
-        Default::default(),
-        gix_worktree::Stack::new(
-            root,
-            gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::default()),
-            Default::default(),
-            Vec::new(),
-            Vec::new(),
-        ),
-    )
-}
-
-fn new_blob_merge_platform(
-    root: &Path,
-    large_file_threshold_bytes: impl Into<Option<u64>>,
-) -> gix_merge::blob::Platform {
-    let attributes = gix_worktree::Stack::new(
-        root,
-        gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
-            Default::default(),
-            None,
-            attributes::Source::WorktreeThenIdMapping,
-            Default::default(),
-        )),
-        gix_worktree::glob::pattern::Case::Sensitive,
-        Vec::new(),
-        Vec::new(),
-    );
-    let filter = gix_merge::blob::Pipeline::new(
-        Default::default(),
-        gix_filter::Pipeline::default(),
-        gix_merge::blob::pipeline::Options {
-            large_file_threshold_bytes: large_file_threshold_bytes.into().unwrap_or_default(),
-        },
-    );
-    gix_merge::blob::Platform::new(
-        filter,
-        gix_merge::blob::pipeline::Mode::ToGit,
-        attributes,
-        vec![],
-        Default::default(),
-    )
-}
-
 mod baseline;
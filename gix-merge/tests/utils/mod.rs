@@ -0,0 +1,78 @@
+//! Utilities for the tests of `gix-merge` and the crates that merge with it, which include this module by path.
+use std::path::Path;
+
+use gix_hash::ObjectId;
+use gix_worktree::stack::state::attributes;
+
+/// Open the object database of the repository the fixture script created in `root/repo`,
+/// with objects written by tests kept in memory.
+pub fn odb(root: &Path) -> gix_testtools::Result<gix_odb::memory::Proxy<gix_odb::Handle>> {
+    let odb = gix_odb::at_opts(
+        root.join("repo/.git/objects"),
+        Vec::new(),
+        gix_odb::store::init::Options {
+            object_hash: gix_testtools::object_hash(),
+            ..Default::default()
+        },
+    )?;
+    Ok(gix_odb::memory::Proxy::new(odb, gix_testtools::object_hash()))
+}
+
+/// Expand prefixes by looking them up in `odb`.
+pub fn expand_prefix(
+    odb: &gix_odb::memory::Proxy<gix_odb::Handle>,
+) -> impl FnMut(gix_hash::Prefix) -> Option<ObjectId> + '_ {
+    |prefix| {
+        odb.lookup_prefix(prefix, None)
+            .ok()
+            .flatten()
+            .and_then(std::result::Result::ok)
+    }
+}
+
+pub fn new_diff_resource_cache(root: &Path) -> gix_diff::blob::Platform {
+    gix_diff::blob::Platform::new(
+        Default::default(),
+        gix_diff::blob::Pipeline::new(Default::default(), Default::default(), Vec::new(), Default::default()),
+        Default::default(),
+        gix_worktree::Stack::new(
+            root,
+            gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::default()),
+            Default::default(),
+            Vec::new(),
+            Vec::new(),
+        ),
+    )
+}
+
+pub fn new_blob_merge_platform(
+    root: &Path,
+    large_file_threshold_bytes: impl Into<Option<u64>>,
+) -> gix_merge::blob::Platform {
+    let attributes = gix_worktree::Stack::new(
+        root,
+        gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
+            Default::default(),
+            None,
+            attributes::Source::WorktreeThenIdMapping,
+            Default::default(),
+        )),
+        gix_worktree::glob::pattern::Case::Sensitive,
+        Vec::new(),
+        Vec::new(),
+    );
+    let filter = gix_merge::blob::Pipeline::new(
+        Default::default(),
+        gix_filter::Pipeline::default(),
+        gix_merge::blob::pipeline::Options {
+            large_file_threshold_bytes: large_file_threshold_bytes.into().unwrap_or_default(),
+        },
+    );
+    gix_merge::blob::Platform::new(
+        filter,
+        gix_merge::blob::pipeline::Mode::ToGit,
+        attributes,
+        vec![],
+        Default::default(),
+    )
+}
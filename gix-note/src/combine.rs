@@ -0,0 +1,19 @@
+//! Ways to combine the content of two notes for the same object.
+
+/// Concatenate `current` and `new` note content, separated by an empty line, just like `git notes append` does.
+///
+/// If either side is empty, the other side is returned unchanged.
+pub fn concatenate(current: &[u8], new: &[u8]) -> Vec<u8> {
+    if current.is_empty() {
+        return new.to_owned();
+    }
+    if new.is_empty() {
+        return current.to_owned();
+    }
+    let current = current.strip_suffix(b"\n").unwrap_or(current);
+    let mut out = Vec::with_capacity(current.len() + 2 + new.len());
+    out.extend_from_slice(current);
+    out.extend_from_slice(b"\n\n");
+    out.extend_from_slice(new);
+    out
+}
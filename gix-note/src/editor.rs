@@ -0,0 +1,79 @@
+use bstr::{BString, ByteSlice};
+use gix_error::{ResultExt, message};
+use gix_hash::{ObjectId, oid};
+use gix_object::tree::EntryKind;
+
+use crate::{Editor, Note};
+
+/// The error returned by [`Editor`] operations.
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// Lifecycle
+impl<'a> Editor<'a> {
+    /// Create a new editor to change the notes tree with id `notes_tree`, which can be the
+    /// [empty tree](ObjectId::empty_tree()) to start from scratch.
+    /// Use `find` to look up existing trees, which are only ever loaded once.
+    pub fn new(notes_tree: ObjectId, find: &'a dyn gix_object::FindExt) -> Result<Self, crate::find::Error> {
+        let mut buf = Vec::new();
+        let root = find
+            .find_tree(&notes_tree, &mut buf)
+            .or_raise(|| message!("Could not find notes tree {notes_tree}"))?
+            .into();
+        Ok(Editor {
+            tree: gix_object::tree::Editor::new(root, find, notes_tree.kind()),
+            find,
+            base: notes_tree,
+            edits: Default::default(),
+            buf,
+        })
+    }
+}
+
+/// Operations
+impl Editor<'_> {
+    /// Return the note for `target` as it is after all edits so far, or `None` if there is none.
+    pub fn find(&mut self, target: &oid) -> Result<Option<Note>, crate::find::Error> {
+        Ok(self.lookup(target)?.0)
+    }
+
+    /// Set the note of `target` to the blob with id `note`, and return the id of the note blob that was replaced, if any.
+    ///
+    /// Existing notes are changed in place, whereas new notes are placed into the deepest fanout directory that exists for `target`.
+    pub fn upsert(&mut self, target: ObjectId, note: ObjectId) -> Result<Option<ObjectId>, Error> {
+        let (previous, path) = self.lookup(&target)?;
+        self.tree
+            .upsert(path.split_str("/"), EntryKind::Blob, note)
+            .or_raise(|| message!("Could not set the note of {target} at '{path}'"))?;
+        self.edits.insert(target, Some(Note { target, id: note, path }));
+        Ok(previous.map(|note| note.id))
+    }
+
+    /// Remove the note of `target` and return the id of the removed note blob, or `None` if there was no note to remove.
+    pub fn remove(&mut self, target: &oid) -> Result<Option<ObjectId>, Error> {
+        let (previous, _path) = self.lookup(target)?;
+        if let Some(previous) = &previous {
+            self.tree
+                .remove(previous.path.split_str("/"))
+                .or_raise(|| message!("Could not remove the note of {target} at '{}'", previous.path))?;
+            self.edits.insert(target.to_owned(), None);
+        }
+        Ok(previous.map(|note| note.id))
+    }
+
+    /// Write all changed trees to `out` and return the id of the new notes tree, which is the last tree passed to `out`.
+    ///
+    /// Note that trees of fanout directories that became empty are removed, and that editing can continue afterwards.
+    /// See [`gix_object::tree::Editor::write()`] for details.
+    pub fn write<E>(&mut self, out: impl FnMut(&gix_object::Tree) -> Result<ObjectId, E>) -> Result<ObjectId, E> {
+        self.tree.write(out)
+    }
+
+    fn lookup(&mut self, target: &oid) -> Result<(Option<Note>, BString), crate::find::Error> {
+        let (note, path) = crate::find::lookup(&self.base, target, self.find, &mut self.buf)?;
+        Ok(match self.edits.get(target) {
+            Some(Some(edited)) => (Some(edited.clone()), edited.path.clone()),
+            Some(None) => (None, path),
+            None => (note, path),
+        })
+    }
+}
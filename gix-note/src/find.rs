@@ -0,0 +1,76 @@
+use bstr::{BString, ByteVec};
+use gix_error::{ResultExt, message};
+use gix_hash::oid;
+use gix_object::FindExt;
+
+use crate::Note;
+
+/// The error returned by [`find()`](crate::find()).
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+pub(crate) mod function {
+    use gix_hash::oid;
+
+    use super::Error;
+    use crate::Note;
+
+    /// Find the note for `target` in the notes tree with id `notes_tree`, using `objects` to look up trees
+    /// and `buf` for temporary storage.
+    ///
+    /// Return `None` if `target` has no note. Notes are found no matter which fanout they are stored with.
+    pub fn find(
+        notes_tree: &oid,
+        target: &oid,
+        objects: &impl gix_object::Find,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<Note>, Error> {
+        Ok(super::lookup(notes_tree, target, objects, buf)?.0)
+    }
+}
+
+/// Find the note for `target` in `notes_tree`, and return it along with the path at which it is or should be stored.
+/// If there is no note, the path uses the fanout that the tree already provides for `target`.
+pub(crate) fn lookup(
+    notes_tree: &oid,
+    target: &oid,
+    objects: &(impl gix_object::Find + ?Sized),
+    buf: &mut Vec<u8>,
+) -> Result<(Option<Note>, BString), Error> {
+    let hex = target.to_hex().to_string();
+    let mut tree_id = notes_tree.to_owned();
+    let mut path = BString::default();
+    let mut rest = hex.as_str();
+    loop {
+        let tree = objects
+            .find_tree(&tree_id, buf)
+            .or_raise(|| message!("Could not find tree {tree_id} of notes tree {notes_tree}"))?;
+        let mut subtree = None;
+        for entry in &tree.entries {
+            if entry.mode.is_tree() {
+                if rest.len() > 2 && *entry.filename == rest[..2] {
+                    subtree = Some(entry.oid.to_owned());
+                }
+            } else if entry.filename == rest {
+                path.push_str(rest);
+                let note = Note {
+                    target: target.to_owned(),
+                    id: entry.oid.to_owned(),
+                    path: path.clone(),
+                };
+                return Ok((Some(note), path));
+            }
+        }
+        match subtree {
+            Some(id) => {
+                path.push_str(&rest[..2]);
+                path.push_byte(b'/');
+                rest = &rest[2..];
+                tree_id = id;
+            }
+            None => {
+                path.push_str(rest);
+                return Ok((None, path));
+            }
+        }
+    }
+}
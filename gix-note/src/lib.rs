@@ -1 +1,81 @@
+//! Read and edit [git notes](https://git-scm.com/docs/git-notes), which associate additional content with existing objects.
+//!
+//! Notes are kept in the tree of a *notes commit* that is pointed to by a reference like [`refs/notes/commits`](DEFAULT_REF).
+//! Each note is a blob whose path is the hexadecimal id of the object it annotates. To keep individual trees small,
+//! Git may split these paths into a *fanout*, like `ab/cdef…` or `ab/cd/ef…`, which is handled transparently here.
+//!
+//! * [find()] and [list()] read notes from a notes tree.
+//! * [`Editor`] changes notes in a notes tree while respecting the fanout it already uses.
+//! * [`combine`] merges the content of two notes, as needed to append to a note.
+//!
+//! Creating the notes commit and updating the notes reference is left to the caller.
+#![deny(missing_docs)]
 #![forbid(unsafe_code)]
+
+use bstr::{BStr, BString, ByteVec};
+use gix_hash::ObjectId;
+
+/// The name of the reference under which notes are stored if nothing else is configured.
+pub const DEFAULT_REF: &str = "refs/notes/commits";
+
+/// A note as stored in a notes tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Note {
+    /// The id of the object this note annotates.
+    pub target: ObjectId,
+    /// The id of the blob that holds the content of the note.
+    pub id: ObjectId,
+    /// The path to the note blob relative to the root of the notes tree, which reveals the fanout it is stored with.
+    pub path: BString,
+}
+
+/// A utility to add, change or remove notes in a notes tree, built on top of a [tree editor](gix_object::tree::Editor).
+///
+/// New notes are placed in the deepest fanout directory that already exists for them,
+/// and changed notes keep the path they were found at.
+///
+/// ### Deviation
+///
+/// Git rewrites the whole notes tree on each change and recomputes the fanout based on the amount of notes.
+/// We don't rebalance, which leaves the fanout as is, even though Git can read the resulting trees just fine.
+pub struct Editor<'a> {
+    /// The editor doing the actual work.
+    tree: gix_object::tree::Editor<'a>,
+    /// Used to look up trees of the `base`.
+    find: &'a dyn gix_object::FindExt,
+    /// The notes tree that all edits are based on.
+    base: ObjectId,
+    /// All edits made so far, with `None` indicating a removed note, so that lookups see them.
+    edits: std::collections::BTreeMap<ObjectId, Option<Note>>,
+    /// A buffer for object lookups.
+    buf: Vec<u8>,
+}
+
+///
+pub mod combine;
+///
+pub mod editor;
+///
+pub mod find;
+pub use find::function::find;
+///
+pub mod list;
+pub use list::function::list;
+
+/// Expand the possibly abbreviated notes reference `name` into a full reference name just like Git does.
+///
+/// `refs/notes/foo` is returned unchanged, `notes/foo` becomes `refs/notes/foo` and `foo` becomes `refs/notes/foo`.
+/// Note that the result isn't validated.
+pub fn expand_ref_name(name: &BStr) -> BString {
+    if name.starts_with(b"refs/notes/") {
+        name.to_owned()
+    } else if name.starts_with(b"notes/") {
+        let mut out: BString = "refs/".into();
+        out.push_str(name);
+        out
+    } else {
+        let mut out: BString = "refs/notes/".into();
+        out.push_str(name);
+        out
+    }
+}
@@ -0,0 +1,58 @@
+/// The error returned by [`list()`](crate::list()).
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+pub(crate) mod function {
+    use bstr::{BString, ByteVec};
+    use gix_error::{ResultExt, message};
+    use gix_hash::{ObjectId, oid};
+    use gix_object::FindExt;
+
+    use super::Error;
+    use crate::Note;
+
+    /// Return all notes in the notes tree with id `notes_tree`, sorted by the object they annotate, using `objects`
+    /// to look up trees.
+    ///
+    /// Notes are found no matter which fanout they are stored with, while entries that can't be a note are ignored.
+    pub fn list(notes_tree: &oid, objects: &impl gix_object::Find) -> Result<Vec<Note>, Error> {
+        let hex_len = notes_tree.kind().len_in_hex();
+        let mut buf = Vec::new();
+        let mut out = Vec::new();
+        let mut hex = BString::default();
+        let mut trees = vec![(notes_tree.to_owned(), BString::default())];
+        while let Some((tree_id, path)) = trees.pop() {
+            let tree = objects
+                .find_tree(&tree_id, &mut buf)
+                .or_raise(|| message!("Could not find tree {tree_id} of notes tree {notes_tree}"))?;
+            hex.clear();
+            hex.extend(path.iter().copied().filter(|b| *b != b'/'));
+            for entry in &tree.entries {
+                let name = entry.filename;
+                if !name.iter().all(u8::is_ascii_hexdigit) {
+                    continue;
+                }
+                let mut entry_path = path.clone();
+                entry_path.push_str(name);
+                if entry.mode.is_tree() {
+                    if name.len() == 2 && hex.len() + 2 < hex_len {
+                        entry_path.push_byte(b'/');
+                        trees.push((entry.oid.to_owned(), entry_path));
+                    }
+                } else if hex.len() + name.len() == hex_len {
+                    let mut target = hex.clone();
+                    target.push_str(name);
+                    let Ok(target) = ObjectId::from_hex(&target) else {
+                        continue;
+                    };
+                    out.push(Note {
+                        target,
+                        id: entry.oid.to_owned(),
+                        path: entry_path,
+                    });
+                }
+            }
+        }
+        out.sort_by_key(|note| note.target);
+        Ok(out)
+    }
+}
@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+for n in 1 2 3; do
+  echo $n > file
+  git add file
+  git commit -q -m "c$n"
+done
+
+git notes add -m "note for c3" HEAD
+git notes add -m "note for c2" HEAD~1
+
+c1=$(git rev-parse HEAD~2)
+c2=$(git rev-parse HEAD~1)
+# a notes tree with a 2/rest fanout for c1 and a 2/2/rest fanout for c2, along with an entry that isn't a note
+c1_note=$(echo "fanout note for c1" | git hash-object -w --stdin)
+c2_note=$(echo "fanout note for c2" | git hash-object -w --stdin)
+readme=$(echo "not a note" | git hash-object -w --stdin)
+c1_dir=$(printf "100644 blob %s\t%s\n" "$c1_note" "${c1:2}" | git mktree)
+c2_inner=$(printf "100644 blob %s\t%s\n" "$c2_note" "${c2:4}" | git mktree)
+c2_dir=$(printf "040000 tree %s\t%s\n" "$c2_inner" "${c2:2:2}" | git mktree)
+root=$( (printf "040000 tree %s\t%s\n" "$c1_dir" "${c1:0:2}"; printf "040000 tree %s\t%s\n" "$c2_dir" "${c2:0:2}"; printf "100644 blob %s\tREADME\n" "$readme") | git mktree)
+git update-ref refs/notes/fanout "$(git commit-tree -m "notes with fanout" "$root")"
+
+for rev in HEAD~2 HEAD~1 HEAD refs/notes/commits^{tree} refs/notes/fanout^{tree}; do
+  git rev-parse "$rev"
+done > ids
@@ -0,0 +1,112 @@
+use gix_error::Exn;
+use gix_object::{FindExt, Write};
+
+use crate::{fixture, note_content};
+
+#[test]
+fn add_change_and_remove_notes_in_fanout_tree() -> crate::Result {
+    let fix = fixture()?;
+    let [c1, c2, c3] = fix.commits;
+    let new_note = fix.odb.write_buf(gix_object::Kind::Blob, b"new note\n")?;
+
+    let mut editor = gix_note::Editor::new(fix.fanout_tree, &fix.odb).map_err(Exn::into_error)?;
+    assert_eq!(
+        editor.upsert(c3, new_note).map_err(Exn::into_error)?,
+        None,
+        "c3 had no note yet"
+    );
+    let hex = c3.to_hex().to_string();
+    assert_eq!(
+        editor.find(&c3).map_err(Exn::into_error)?.expect("just added").path,
+        hex,
+        "there is no fanout directory for c3, so it's placed at the root"
+    );
+
+    let previous = editor
+        .upsert(c1, new_note)
+        .map_err(Exn::into_error)?
+        .expect("c1 had a note");
+    assert_eq!(note_content(&fix.odb, &previous)?, "fanout note for c1\n");
+
+    let previous = editor.remove(&c2).map_err(Exn::into_error)?.expect("c2 had a note");
+    assert_eq!(note_content(&fix.odb, &previous)?, "fanout note for c2\n");
+    assert_eq!(
+        editor.remove(&c2).map_err(Exn::into_error)?,
+        None,
+        "removing a second time does nothing"
+    );
+
+    let tree = editor.write(|tree| fix.odb.write(tree))?;
+    let notes = gix_note::list(&tree, &fix.odb).map_err(Exn::into_error)?;
+    let mut expected = vec![c1, c3];
+    expected.sort();
+    assert_eq!(notes.iter().map(|n| n.target).collect::<Vec<_>>(), expected);
+    assert!(notes.iter().all(|n| n.id == new_note));
+
+    let c1_note = notes.iter().find(|n| n.target == c1).expect("present");
+    let hex = c1.to_hex().to_string();
+    assert_eq!(
+        c1_note.path,
+        format!("{}/{}", &hex[..2], &hex[2..]),
+        "existing notes keep their fanout"
+    );
+
+    let mut buf = Vec::new();
+    let root = fix.odb.find_tree(&tree, &mut buf)?;
+    let hex = c2.to_hex().to_string();
+    assert!(
+        root.entries.iter().all(|e| *e.filename != hex[..2]),
+        "the now empty fanout directory of c2 was removed"
+    );
+    assert!(
+        root.entries.iter().any(|e| e.filename == "README"),
+        "unrelated entries are kept"
+    );
+    Ok(())
+}
+
+#[test]
+fn new_notes_use_existing_fanout_directories() -> crate::Result {
+    let fix = fixture()?;
+    let [c1, _c2, _c3] = fix.commits;
+    let new_note = fix.odb.write_buf(gix_object::Kind::Blob, b"new note\n")?;
+
+    let mut editor = gix_note::Editor::new(fix.fanout_tree, &fix.odb).map_err(Exn::into_error)?;
+    editor.remove(&c1).map_err(Exn::into_error)?;
+    assert_eq!(
+        editor.find(&c1).map_err(Exn::into_error)?,
+        None,
+        "removals are visible right away"
+    );
+    editor.upsert(c1, new_note).map_err(Exn::into_error)?;
+    let tree = editor.write(|tree| fix.odb.write(tree))?;
+
+    let note = gix_note::find(&tree, &c1, &fix.odb, &mut Vec::new())
+        .map_err(Exn::into_error)?
+        .expect("re-added");
+    let hex = c1.to_hex().to_string();
+    assert_eq!(note.path, format!("{}/{}", &hex[..2], &hex[2..]));
+    assert_eq!(note.id, new_note);
+    Ok(())
+}
+
+#[test]
+fn from_scratch() -> crate::Result {
+    let fix = fixture()?;
+    let [c1, c2, _c3] = fix.commits;
+    let note = fix.odb.write_buf(gix_object::Kind::Blob, b"note\n")?;
+
+    let empty_tree = gix_hash::ObjectId::empty_tree(c1.kind());
+    let mut editor = gix_note::Editor::new(empty_tree, &fix.odb).map_err(Exn::into_error)?;
+    editor.upsert(c1, note).map_err(Exn::into_error)?;
+    editor.upsert(c2, note).map_err(Exn::into_error)?;
+    let tree = editor.write(|tree| fix.odb.write(tree))?;
+
+    let notes = gix_note::list(&tree, &fix.odb).map_err(Exn::into_error)?;
+    assert_eq!(notes.len(), 2);
+    assert!(
+        notes.iter().all(|n| n.path == n.target.to_hex().to_string()),
+        "without any fanout in place, all notes are written to the root"
+    );
+    Ok(())
+}
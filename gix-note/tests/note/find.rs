@@ -0,0 +1,88 @@
+use gix_error::Exn;
+
+use crate::{fixture, note_content};
+
+#[test]
+fn without_fanout() -> crate::Result {
+    let fix = fixture()?;
+    let mut buf = Vec::new();
+    let [c1, c2, c3] = fix.commits;
+    let note = gix_note::find(&fix.notes_tree, &c3, &fix.odb, &mut buf)
+        .map_err(Exn::into_error)?
+        .expect("c3 has a note");
+    assert_eq!(note.target, c3);
+    assert_eq!(note.path, c3.to_hex().to_string(), "notes are stored at the root");
+    assert_eq!(note_content(&fix.odb, &note.id)?, "note for c3\n");
+
+    let note = gix_note::find(&fix.notes_tree, &c2, &fix.odb, &mut buf)
+        .map_err(Exn::into_error)?
+        .expect("c2 has a note");
+    assert_eq!(note_content(&fix.odb, &note.id)?, "note for c2\n");
+
+    assert_eq!(
+        gix_note::find(&fix.notes_tree, &c1, &fix.odb, &mut buf).map_err(Exn::into_error)?,
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn with_fanout() -> crate::Result {
+    let fix = fixture()?;
+    let mut buf = Vec::new();
+    let [c1, c2, c3] = fix.commits;
+    let note = gix_note::find(&fix.fanout_tree, &c1, &fix.odb, &mut buf)
+        .map_err(Exn::into_error)?
+        .expect("c1 has a note");
+    let hex = c1.to_hex().to_string();
+    assert_eq!(note.path, format!("{}/{}", &hex[..2], &hex[2..]));
+    assert_eq!(note_content(&fix.odb, &note.id)?, "fanout note for c1\n");
+
+    let note = gix_note::find(&fix.fanout_tree, &c2, &fix.odb, &mut buf)
+        .map_err(Exn::into_error)?
+        .expect("c2 has a note");
+    let hex = c2.to_hex().to_string();
+    assert_eq!(note.path, format!("{}/{}/{}", &hex[..2], &hex[2..4], &hex[4..]));
+    assert_eq!(note_content(&fix.odb, &note.id)?, "fanout note for c2\n");
+
+    assert_eq!(
+        gix_note::find(&fix.fanout_tree, &c3, &fix.odb, &mut buf).map_err(Exn::into_error)?,
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn list() -> crate::Result {
+    let fix = fixture()?;
+    let [c1, c2, c3] = fix.commits;
+
+    let notes = gix_note::list(&fix.notes_tree, &fix.odb).map_err(Exn::into_error)?;
+    let mut expected = vec![c2, c3];
+    expected.sort();
+    assert_eq!(notes.iter().map(|n| n.target).collect::<Vec<_>>(), expected);
+
+    let notes = gix_note::list(&fix.fanout_tree, &fix.odb).map_err(Exn::into_error)?;
+    let mut expected = vec![c1, c2];
+    expected.sort();
+    assert_eq!(
+        notes.iter().map(|n| n.target).collect::<Vec<_>>(),
+        expected,
+        "notes are found in any fanout, and the README entry is ignored"
+    );
+    for note in notes {
+        assert_eq!(
+            gix_note::find(&fix.fanout_tree, &note.target, &fix.odb, &mut Vec::new()).map_err(Exn::into_error)?,
+            Some(note),
+            "listed notes are the same as the ones that are found"
+        );
+    }
+
+    let empty_tree = gix_hash::ObjectId::empty_tree(fix.notes_tree.kind());
+    assert!(
+        gix_note::list(&empty_tree, &fix.odb)
+            .map_err(Exn::into_error)?
+            .is_empty()
+    );
+    Ok(())
+}
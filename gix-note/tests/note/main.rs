@@ -0,0 +1,67 @@
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+mod editor;
+mod find;
+
+pub use gix_testtools::Result;
+
+/// The object database of the notes fixture, along with the ids of commits `c1` to `c3`, the default notes tree and a notes tree with fanout.
+struct Fixture {
+    odb: gix_odb::memory::Proxy<gix_odb::Handle>,
+    commits: [ObjectId; 3],
+    notes_tree: ObjectId,
+    fanout_tree: ObjectId,
+}
+
+fn fixture() -> Result<Fixture> {
+    let root = gix_testtools::scripted_fixture_read_only("make_notes_repo.sh")?;
+    let ids = std::fs::read_to_string(root.join("ids"))?
+        .lines()
+        .map(|hex| ObjectId::from_hex(hex.as_bytes()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let odb = gix_odb::at_opts(
+        root.join(".git").join("objects"),
+        Vec::new(),
+        gix_odb::store::init::Options {
+            object_hash: gix_testtools::object_hash(),
+            ..Default::default()
+        },
+    )?;
+    Ok(Fixture {
+        odb: gix_odb::memory::Proxy::new(odb, gix_testtools::object_hash()),
+        commits: [ids[0], ids[1], ids[2]],
+        notes_tree: ids[3],
+        fanout_tree: ids[4],
+    })
+}
+
+fn note_content(odb: &impl gix_object::Find, id: &gix_hash::oid) -> Result<String> {
+    let mut buf = Vec::new();
+    Ok(std::str::from_utf8(odb.find_blob(id, &mut buf)?.data)?.to_owned())
+}
+
+#[test]
+fn expand_ref_name() {
+    for (input, expected) in [
+        ("commits", "refs/notes/commits"),
+        ("notes/commits", "refs/notes/commits"),
+        ("refs/notes/commits", "refs/notes/commits"),
+        ("refs/heads/main", "refs/notes/refs/heads/main"),
+    ] {
+        assert_eq!(gix_note::expand_ref_name(input.into()), expected);
+    }
+}
+
+#[test]
+fn combine_concatenate() {
+    use gix_note::combine::concatenate;
+    assert_eq!(concatenate(b"", b"new\n"), b"new\n");
+    assert_eq!(concatenate(b"old\n", b""), b"old\n");
+    assert_eq!(
+        concatenate(b"old\n", b"new\n"),
+        b"old\n\nnew\n",
+        "an empty line separates both notes"
+    );
+    assert_eq!(concatenate(b"old", b"new"), b"old\n\nnew");
+}
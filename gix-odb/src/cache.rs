@@ -260,5 +260,9 @@ mod impls {
         fn entry_by_location(&self, location: &Location) -> Option<gix_pack::find::Entry> {
             self.inner.entry_by_location(location)
         }
+
+        fn bitmap_index(&self) -> Option<std::sync::Arc<gix_pack::bitmap::Index>> {
+            self.inner.bitmap_index()
+        }
     }
 }
@@ -173,6 +173,8 @@ pub struct Store {
     alloc_limit_bytes: Option<usize>,
     /// The compression level to use when writing loose objects.
     loose_compression: gix_zlib::Compression,
+    /// The way to make written loose objects durable, if at all.
+    loose_fsync: Option<gix_fs::fsync::Method>,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
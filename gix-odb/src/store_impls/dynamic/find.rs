@@ -505,6 +505,30 @@ where
             );
         }
     }
+
+    fn bitmap_index(&self) -> Option<std::sync::Arc<gix_pack::bitmap::Index>> {
+        if !(self.store_ref().replacements.is_empty() || self.ignore_replacements) {
+            return None;
+        }
+        // Like `git`, prefer the bitmap of a multi-pack index and otherwise use the first bitmap of a pack we find.
+        let snapshot = self.store.load_all_indices().ok()?;
+        let multi_pack_bitmaps = snapshot.indices.iter().filter_map(|index| match &index.file {
+            handle::SingleOrMultiIndex::Multi { index, .. } => {
+                Some(gix_pack::bitmap::Index::at_multi_index(index.clone()))
+            }
+            handle::SingleOrMultiIndex::Single { .. } => None,
+        });
+        let pack_bitmaps = snapshot.indices.iter().filter_map(|index| match &index.file {
+            handle::SingleOrMultiIndex::Single { index, .. } => {
+                Some(gix_pack::bitmap::Index::at_pack_index(index.clone()))
+            }
+            handle::SingleOrMultiIndex::Multi { .. } => None,
+        });
+        multi_pack_bitmaps
+            .chain(pack_bitmaps)
+            .find_map(|bitmaps| bitmaps.ok().flatten())
+            .map(std::sync::Arc::new)
+    }
 }
 
 impl<S> gix_object::Find for super::Handle<S>
@@ -337,6 +337,15 @@ where
     pub fn refresh_mode(&mut self) -> RefreshMode {
         self.refresh
     }
+
+    /// Make the directory entries of all loose objects written in [batch mode](gix_fs::fsync::Method::Batch) durable,
+    /// which should be done before they are referenced.
+    pub fn flush_loose_object_batch(&self) -> std::io::Result<()> {
+        for db in self.snapshot.borrow().loose_dbs.iter() {
+            db.flush_batch()?;
+        }
+        Ok(())
+    }
 }
 
 impl<S> Drop for super::Handle<S>
@@ -364,6 +373,7 @@ impl TryFrom<&super::Store> for super::Store {
                 alloc_limit_bytes: s.alloc_limit_bytes,
                 current_dir: s.current_dir.clone().into(),
                 loose_compression: s.loose_compression,
+                loose_fsync: s.loose_fsync,
             },
         )
     }
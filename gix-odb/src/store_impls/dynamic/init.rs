@@ -28,6 +28,10 @@ pub struct Options {
     /// Defaults to [`Compression::BEST_SPEED`](gix_zlib::Compression::BEST_SPEED), which is
     /// also what `git` uses unless configured otherwise with `core.looseCompression` or `core.compression`.
     pub loose_compression: gix_zlib::Compression,
+    /// If `Some(method)`, make written loose objects durable with it.
+    ///
+    /// Defaults to `None`, which is also what `git` does unless `loose-object` is part of `core.fsync`.
+    pub loose_fsync: Option<gix_fs::fsync::Method>,
 }
 
 impl Default for Options {
@@ -39,6 +43,7 @@ impl Default for Options {
             alloc_limit_bytes: None,
             current_dir: None,
             loose_compression: gix_zlib::Compression::BEST_SPEED,
+            loose_fsync: None,
         }
     }
 }
@@ -90,6 +95,7 @@ impl Store {
             alloc_limit_bytes,
             current_dir,
             loose_compression,
+            loose_fsync,
         }: Options,
     ) -> std::io::Result<Self> {
         let _span = gix_features::trace::detail!("gix_odb::Store::at()");
@@ -146,6 +152,7 @@ impl Store {
             object_hash,
             alloc_limit_bytes,
             loose_compression,
+            loose_fsync,
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
@@ -252,6 +252,7 @@ impl super::Store {
                                 object_hash: self.object_hash,
                                 alloc_limit_bytes: self.alloc_limit_bytes,
                                 compression: loose_compression,
+                                fsync: self.loose_fsync,
                             },
                         )
                     })
@@ -475,7 +476,7 @@ impl super::Store {
         let mut indices_by_modification_time = Vec::with_capacity(initial_capacity.unwrap_or_default());
         for db_path in db_paths {
             let packs = db_path.join("pack");
-            let entries = match std::fs::read_dir(packs) {
+            let entries = match std::fs::read_dir(&packs) {
                 Ok(e) => e,
                 Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
                 Err(err) => return Err(err.into()),
@@ -492,6 +493,40 @@ impl super::Store {
                 .map(|(p, md)| md.modified().map_err(Error::from).map(|mtime| (p, mtime, md.len())))
                 .collect::<Result<Vec<_>, _>>()?;
 
+            // Like git, prefer the layers of an incremental multi-index over a single multi-index file.
+            let chain = multi_pack_index_object_hash.and_then(|hash| {
+                gix_pack::multi_index::Chain::at(&packs, alloc_limit_bytes)
+                    .ok()
+                    .filter(|chain| chain.layers().first().is_some_and(|layer| layer.object_hash() == hash))
+            });
+            if let Some(chain) = chain {
+                let indices_not_in_chain: Vec<_> = indices
+                    .into_iter()
+                    .filter_map(|(path, a, b)| {
+                        (!is_multipack_index(&path)
+                            && !chain.contains_index(Path::new(path.file_name().expect("file name present"))))
+                        .then_some((Either::IndexPath(path), a, b))
+                    })
+                    .collect();
+                for layer in chain.into_layers() {
+                    if layer.num_indices() > PackId::max_packs_in_multi_index() {
+                        return Err(Error::TooManyPacksInMultiIndex {
+                            index_path: layer.path().to_owned(),
+                            actual: layer.num_indices(),
+                            limit: PackId::max_packs_in_multi_index(),
+                        });
+                    }
+                    let md = std::fs::metadata(layer.path())?;
+                    indices_by_modification_time.push((
+                        Either::MultiIndexFile(Arc::new(layer)),
+                        md.modified()?,
+                        md.len(),
+                    ));
+                }
+                indices_by_modification_time.extend(indices_not_in_chain);
+                continue;
+            }
+
             let multi_index_info = multi_pack_index_object_hash
                 .and_then(|hash| {
                     indices.iter().find_map(|(p, a, b)| {
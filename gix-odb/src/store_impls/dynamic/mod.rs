@@ -92,6 +92,9 @@ pub mod load_index;
 ///
 pub mod verify;
 
+///
+pub mod multi_index;
+
 mod load_one;
 
 mod metrics;
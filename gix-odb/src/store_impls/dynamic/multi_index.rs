@@ -0,0 +1,178 @@
+use std::{
+    ffi::OsStr,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::atomic::AtomicBool,
+};
+
+use gix_features::progress::DynNestedProgress;
+
+use crate::pack::{self, multi_index::Chain};
+
+///
+pub mod append {
+    use std::path::PathBuf;
+
+    use crate::pack;
+
+    /// Returned by [`Store::append_multi_index_layer()`][crate::Store::append_multi_index_layer()].
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        OpenChain(#[from] pack::multi_index::chain::init::Error),
+        #[error(transparent)]
+        Write(#[from] pack::multi_index::write::Error),
+        #[error("Could not turn temporary file into persisted file at '{target}'")]
+        Persist {
+            source: tempfile::PersistError,
+            target: PathBuf,
+        },
+    }
+
+    /// Options for use in [`Store::append_multi_index_layer()`][crate::Store::append_multi_index_layer()].
+    #[derive(Default, Debug, Clone)]
+    pub struct Options {
+        /// The file name of the index or data file of the pack whose objects should come first in the new layer,
+        /// like `git multi-pack-index write --incremental --preferred-pack` would.
+        pub preferred_pack: Option<PathBuf>,
+        /// If `Some`, only add the packs whose index or data file has one of the given file names, like
+        /// `git multi-pack-index write --incremental --stdin-packs` would. Other packs are left for a future layer.
+        ///
+        /// If `None`, all packs that aren't yet in the chain are added.
+        pub packs: Option<Vec<PathBuf>>,
+        /// If `true`, the new layer contains a reverse index and the bit-ranges of its packs, which are needed
+        /// to write reachability bitmaps for it.
+        pub reverse_index: bool,
+        /// If `Some(method)`, make the new layer and the updated chain file durable with it.
+        pub fsync: Option<gix_fs::fsync::Method>,
+    }
+
+    /// Returned by [`Store::append_multi_index_layer()`][crate::Store::append_multi_index_layer()].
+    #[derive(Debug, Clone)]
+    pub struct Outcome {
+        /// The path at which the new layer was written.
+        pub layer_path: PathBuf,
+        /// The checksum of the new layer, as recorded in the chain file.
+        pub checksum: gix_hash::ObjectId,
+        /// The amount of layers in the chain, including the new one.
+        pub num_layers: usize,
+        /// The amount of packs in the new layer.
+        pub num_indices: u32,
+        /// The amount of objects in the new layer.
+        pub num_objects: u32,
+    }
+}
+
+impl crate::Store {
+    /// Write a new layer of the incremental multi-pack index of our object directory, which contains all packs that
+    /// aren't yet contained in one of its layers, and append it to the chain, like
+    /// `git multi-pack-index write --incremental` would.
+    ///
+    /// Existing layers are left untouched, and a new chain is started if there is none yet.
+    /// Note that a non-incremental multi-pack index is ignored once a chain exists, and that packs of alternates
+    /// are never added.
+    ///
+    /// Return `None` if there are no packs to add.
+    pub fn append_multi_index_layer(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: append::Options,
+    ) -> Result<Option<append::Outcome>, append::Error> {
+        let pack_dir = self.path().join("pack");
+        let chain_path = Chain::path(&pack_dir);
+        let base = if chain_path.is_file() {
+            Chain::at(&pack_dir, self.alloc_limit_bytes)?
+        } else {
+            Chain::default()
+        };
+
+        let mut index_paths = Vec::new();
+        let entries = match std::fs::read_dir(&pack_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension() == Some(OsStr::new("idx"))
+                && path.with_extension("pack").is_file()
+                && !base.contains_index(Path::new(path.file_name().expect("file name present")))
+                && options
+                    .packs
+                    .as_ref()
+                    .is_none_or(|packs| packs.iter().any(|name| name.file_stem() == path.file_stem()))
+            {
+                index_paths.push(path);
+            }
+        }
+        if index_paths.is_empty() {
+            return Ok(None);
+        }
+
+        let layer_dir = pack_dir.join(pack::multi_index::chain::DIRECTORY);
+        std::fs::create_dir_all(&layer_dir)?;
+        let mut layer = tempfile::NamedTempFile::new_in(&layer_dir)?;
+        let outcome = {
+            let mut out = BufWriter::new(&mut layer);
+            let outcome = pack::multi_index::write_layer_from_index_paths(
+                index_paths,
+                Some(&base),
+                &mut out,
+                progress,
+                should_interrupt,
+                pack::multi_index::write::Options {
+                    object_hash: self.object_hash,
+                    bitmap: options.reverse_index.then(Default::default),
+                    preferred_pack: options.preferred_pack,
+                },
+            )?;
+            out.flush()?;
+            outcome
+        };
+        if let Some(method) = options.fsync {
+            gix_fs::fsync::file(layer.as_file(), method)?;
+        }
+        let checksum = outcome.multi_index_checksum;
+        let layer_path = Chain::layer_path(&pack_dir, checksum);
+        layer.persist(&layer_path).map_err(|err| append::Error::Persist {
+            source: err,
+            target: layer_path.clone(),
+        })?;
+        let new_layer = pack::multi_index::File::at(&layer_path, self.alloc_limit_bytes).map_err(|source| {
+            pack::multi_index::chain::init::Error::Layer {
+                path: layer_path.clone(),
+                source,
+            }
+        })?;
+
+        // Only now that the new layer is in place, the chain may refer to it.
+        let mut chain = tempfile::NamedTempFile::new_in(&layer_dir)?;
+        for base_layer in base.layers() {
+            writeln!(chain, "{}", base_layer.checksum())?;
+        }
+        writeln!(chain, "{checksum}")?;
+        if let Some(method) = options.fsync {
+            gix_fs::fsync::file(chain.as_file(), method)?;
+        }
+        chain.persist(&chain_path).map_err(|err| append::Error::Persist {
+            source: err,
+            target: chain_path.clone(),
+        })?;
+        if options.fsync.is_some() {
+            // The files are already visible, and not all platforms and filesystems support this.
+            gix_fs::fsync::directory(&layer_dir).ok();
+        }
+
+        Ok(Some(append::Outcome {
+            layer_path,
+            checksum,
+            num_layers: base.layers().len() + 1,
+            num_indices: new_layer.num_indices(),
+            num_objects: new_layer.num_objects(),
+        }))
+    }
+}
@@ -373,7 +373,7 @@ impl IndexAndPacks {
     fn index_names_to_pack_paths(
         multi_index: &gix_pack::multi_index::File,
     ) -> Vec<OnDiskFile<Arc<gix_pack::data::File>>> {
-        let parent_dir = multi_index.path().parent().expect("parent present");
+        let parent_dir = multi_index.pack_dir().expect("parent present");
         multi_index
             .index_names()
             .iter()
@@ -1,7 +1,11 @@
 //! An object database storing each object in a zlib compressed file with its hash in the path
 /// The maximum size that an object header can have. `git2` says 64, and `git` says 32 but also mentions it can be larger.
 const HEADER_MAX_SIZE: usize = 64;
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use gix_features::fs;
 
@@ -19,6 +23,10 @@ pub struct Options {
     /// Git uses [`Compression::BEST_SPEED`](gix_zlib::Compression::BEST_SPEED) unless configured otherwise with
     /// `core.looseCompression` or `core.compression`.
     pub compression: gix_zlib::Compression,
+    /// If `Some(method)`, make written loose objects durable with it, along with the directories they are placed in.
+    ///
+    /// Git only does this if `loose-object` is part of `core.fsync`, with `core.fsyncMethod` as method.
+    pub fsync: Option<gix_fs::fsync::Method>,
 }
 
 impl Default for Options {
@@ -27,12 +35,13 @@ impl Default for Options {
             object_hash: Default::default(),
             alloc_limit_bytes: None,
             compression: gix_zlib::Compression::BEST_SPEED,
+            fsync: None,
         }
     }
 }
 
 /// A database for reading and writing objects to disk, one file per object.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct Store {
     /// The directory in which objects are stored, containing 256 folders representing the hashes first byte.
     pub(crate) path: PathBuf,
@@ -42,6 +51,52 @@ pub struct Store {
     pub(crate) alloc_limit_bytes: Option<usize>,
     /// The compression level to use when writing loose objects.
     pub(crate) compression: gix_zlib::Compression,
+    /// The way to make written objects durable, if at all.
+    pub(crate) fsync: Option<gix_fs::fsync::Method>,
+    /// The directories which received objects in [batch mode](gix_fs::fsync::Method::Batch), shared among all clones.
+    pub(crate) pending_directories: Arc<PendingDirectories>,
+}
+
+impl PartialEq for Store {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+            && self.object_hash == other.object_hash
+            && self.alloc_limit_bytes == other.alloc_limit_bytes
+            && self.compression == other.compression
+            && self.fsync == other.fsync
+    }
+}
+
+impl Eq for Store {}
+
+/// Directories whose entries still have to be made durable, which happens at the latest when they are dropped.
+#[derive(Default)]
+pub(crate) struct PendingDirectories(parking_lot::Mutex<BTreeSet<PathBuf>>);
+
+impl PendingDirectories {
+    pub(crate) fn insert(&self, directory: &Path) {
+        let mut directories = self.0.lock();
+        if !directories.contains(directory) {
+            directories.insert(directory.to_owned());
+        }
+    }
+
+    pub(crate) fn sync(&self) -> std::io::Result<()> {
+        let mut directories = self.0.lock();
+        while let Some(directory) = directories.pop_first() {
+            if let Err(err) = gix_fs::fsync::directory(&directory) {
+                directories.insert(directory);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PendingDirectories {
+    fn drop(&mut self) {
+        self.sync().ok();
+    }
 }
 
 /// Initialization
@@ -56,15 +111,26 @@ impl Store {
             object_hash,
             alloc_limit_bytes,
             compression,
+            fsync,
         } = options;
         Store {
             path: objects_directory.into(),
             object_hash,
             alloc_limit_bytes,
             compression,
+            fsync,
+            pending_directories: Default::default(),
         }
     }
 
+    /// Make the directory entries of all objects written in [batch mode](gix_fs::fsync::Method::Batch) durable,
+    /// which is needed for them to be found after a crash.
+    ///
+    /// This also happens when the last clone of this instance is dropped, but errors are ignored then.
+    pub fn flush_batch(&self) -> std::io::Result<()> {
+        self.pending_directories.sync()
+    }
+
     /// Return the path to our `objects` directory.
     pub fn path(&self) -> &Path {
         &self.path
@@ -1,5 +1,10 @@
-use std::{fs, io, io::Write, path::PathBuf};
+use std::{
+    fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
+use gix_fs::fsync::Method;
 use gix_object::WriteTo;
 use gix_zlib::stream::deflate;
 use tempfile::NamedTempFile;
@@ -201,13 +206,19 @@ impl Store {
         let object_dir = object_path
             .parent()
             .expect("each object path has a 1 hex-bytes directory");
-        if let Err(err) = fs::create_dir(object_dir) {
-            match err.kind() {
-                io::ErrorKind::AlreadyExists => {}
-                _ => return Err(err.into()),
-            }
-        }
+        let created_object_dir = match fs::create_dir(object_dir) {
+            Ok(()) => true,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => false,
+            Err(err) => return Err(err.into()),
+        };
         let file = file.into_inner();
+        if let Some(method) = self.fsync {
+            let method = match method {
+                Method::Batch => Method::WriteoutOnly,
+                method => method,
+            };
+            gix_fs::fsync::file(file.as_file(), method)?;
+        }
         let res = file.persist(&object_path);
         // On windows, we assume that such errors are due to its special filesystem semantics,
         // on any other platform that would be a legitimate error though.
@@ -221,8 +232,28 @@ impl Store {
         }
         res.map_err(|err| Error::Persist {
             source: err,
-            target: object_path,
+            target: object_path.clone(),
         })?;
+        if let Some(method) = self.fsync {
+            self.sync_directories(object_dir, created_object_dir, method);
+        }
         Ok(id)
     }
+
+    /// Make the new entry in `object_dir` durable, along with `object_dir` itself in our directory if it was `created`,
+    /// or remember them for later in batch mode.
+    ///
+    /// Errors are ignored as the object is already visible, and not all platforms and filesystems support this.
+    fn sync_directories(&self, object_dir: &Path, created: bool, method: Method) {
+        let directories = std::iter::once(object_dir).chain(created.then_some(self.path.as_path()));
+        if method == Method::Batch {
+            for directory in directories {
+                self.pending_directories.insert(directory);
+            }
+        } else {
+            for directory in directories {
+                gix_fs::fsync::directory(directory).ok();
+            }
+        }
+    }
 }
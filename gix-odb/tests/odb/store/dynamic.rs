@@ -67,6 +67,8 @@ fn db_with_all_object_sources() -> crate::Result<(gix_odb::Handle, gix_testtools
         &std::sync::atomic::AtomicBool::default(),
         gix_odb::pack::multi_index::write::Options {
             object_hash: gix_hash::Kind::Sha1,
+            bitmap: None,
+            preferred_pack: None,
         },
     )?;
     Ok((gix_odb::at(objects_dir.path())?, objects_dir))
@@ -211,6 +213,64 @@ fn multi_index_alloc_limit_bytes_falls_back_to_plain_indices() -> crate::Result
     Ok(())
 }
 
+#[test]
+fn multi_index_chain_access() -> crate::Result {
+    let objects_dir = gix_testtools::tempfile::tempdir()?;
+    let pack_dir = objects_dir.path().join("pack");
+    std::fs::create_dir(&pack_dir)?;
+    gix_testtools::copy_recursively_into_existing_dir(fixture_path("objects/pack"), &pack_dir)?;
+    let handle = gix_odb::at(objects_dir.path())?;
+    let append = |packs: Option<Vec<std::path::PathBuf>>| {
+        handle.store_ref().append_multi_index_layer(
+            &mut gix_features::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+            gix_odb::store::multi_index::append::Options {
+                packs,
+                ..Default::default()
+            },
+        )
+    };
+
+    let base_pack = std::path::PathBuf::from("pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx");
+    let outcome = append(Some(vec![base_pack.clone()]))?.expect("a new pack was added");
+    assert_eq!(outcome.num_layers, 1);
+    assert_eq!(outcome.num_indices, 1, "only the given pack was added");
+    assert!(
+        append(Some(vec![base_pack]))?.is_none(),
+        "there is nothing to add if all given packs are in the chain"
+    );
+
+    let outcome = append(None)?.expect("new packs were added");
+    assert_eq!(outcome.num_layers, 2);
+    assert_eq!(outcome.num_indices, 2);
+    assert_eq!(
+        gix_odb::pack::multi_index::Chain::at(&pack_dir, None)?.num_objects(),
+        139,
+        "objects in the base layer aren't added again"
+    );
+    assert!(append(None)?.is_none(), "all packs are in the chain now");
+
+    let mut buf = Vec::new();
+    let mut count = 0;
+    for oid in handle.iter()? {
+        let oid = oid?;
+        handle.find(&oid, &mut buf)?;
+        count += 1;
+    }
+    assert_eq!(count, 139, "all objects are found through the layers of the chain");
+    let metrics = handle.store_ref().metrics();
+    assert_eq!(metrics.known_reachable_indices, 2, "each layer is one index");
+    assert_eq!(metrics.known_packs, 3);
+
+    let outcome = handle.store_ref().verify_integrity(
+        &mut gix_features::progress::Discard,
+        &Default::default(),
+        Default::default(),
+    )?;
+    assert_eq!(outcome.index_statistics.len(), 2);
+    Ok(())
+}
+
 #[test]
 fn multi_index_keep_open() -> crate::Result {
     let dir = crate::scripted_fixture_writable("make_repo_multi_index.sh")?;
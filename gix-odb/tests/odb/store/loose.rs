@@ -89,6 +89,34 @@ mod write {
         Ok(())
     }
 
+    #[test]
+    fn fsync_methods_are_respected() -> crate::Result {
+        use gix_fs::fsync::Method;
+        for method in [Method::Fsync, Method::WriteoutOnly, Method::Batch] {
+            let dir = gix_testtools::tempfile::tempdir()?;
+            let db = loose::Store::at(
+                dir.path(),
+                loose::Options {
+                    fsync: Some(method),
+                    ..Default::default()
+                },
+            );
+            let mut buf = Vec::new();
+            for oid in object_ids() {
+                let obj = locate_oid(oid, &mut buf);
+                assert_eq!(db.write_buf(obj.kind, obj.data)?, oid);
+            }
+            db.flush_batch()?;
+            db.flush_batch()?;
+            assert_eq!(
+                db.iter().count(),
+                object_ids().len(),
+                "all objects are written no matter the {method:?} method"
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn read_and_write() -> crate::Result {
         let dir = gix_testtools::tempfile::tempdir()?;
@@ -98,6 +98,7 @@ fn count_objects_unthreaded(odb: &MemoryOdb, ids: &[gix_hash::ObjectId]) -> Vec<
         &progress::Discard,
         &AtomicBool::new(false),
         count::objects::ObjectExpansion::AsIs,
+        None,
     )
     .expect("counting in-memory objects succeeds");
     counts
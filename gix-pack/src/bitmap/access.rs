@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use crate::bitmap::{Entry, File};
+
+/// Access methods
+impl<T> File<T>
+where
+    T: crate::FileData,
+{
+    /// Returns the path from which the bitmap file was loaded.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the kind of hash function used for the object ids in this file.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// Returns the [flags](crate::bitmap::flags) stored in the header of this file.
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    /// Returns the checksum of the pack data file or multi-pack index whose objects this file refers to.
+    pub fn checksum(&self) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from_bytes_or_panic(&self.data[Self::HEADER_LEN..][..self.object_hash.len_in_bytes()])
+    }
+
+    /// Returns all commits with a stored bitmap.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns the bitmap of all objects of `kind`, with each bit at the position of an object in pack order.
+    pub fn objects_of_kind(&self, kind: gix_object::Kind) -> &gix_bitmap::ewah::Vec {
+        &self.kinds[kind_index(kind)]
+    }
+
+    /// Returns `true` if the file stores the hashes of the names of all objects, see [`File::name_hash_at()`].
+    pub fn has_name_hash_cache(&self) -> bool {
+        self.name_hash_cache_ofs.is_some()
+    }
+
+    /// Returns the [hash of the name](crate::data::output::Count::name_hash) of the object at `bit` in pack order,
+    /// or `None` if there is no name-hash cache or if `bit` is out of bounds.
+    pub fn name_hash_at(&self, bit: u32) -> Option<u32> {
+        let start = self.name_hash_cache_ofs? + bit as usize * 4;
+        let end = self.data.len() - self.object_hash.len_in_bytes();
+        self.data.get(start..end)?.get(..4).map(crate::read_u32)
+    }
+
+    /// Decode the bitmap of `entry` as stored, which still has to be combined with the bitmap it refers to
+    /// if its [`xor_offset`](Entry::xor_offset) isn't `0`.
+    pub fn stored_bitmap(&self, entry: &Entry) -> Result<gix_bitmap::ewah::Vec, gix_bitmap::ewah::decode::Error> {
+        gix_bitmap::ewah::decode(&self.data[entry.bitmap_ofs..]).map(|(bitmap, _rest)| bitmap)
+    }
+}
+
+pub(crate) fn kind_index(kind: gix_object::Kind) -> usize {
+    match kind {
+        gix_object::Kind::Commit => 0,
+        gix_object::Kind::Tree => 1,
+        gix_object::Kind::Blob => 2,
+        gix_object::Kind::Tag => 3,
+    }
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use crate::bitmap::{File, Index, Objects, Set, access::kind_index, init};
+
+/// The error returned by [`Index::from_pack_index()`] and [`Index::from_multi_index()`].
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Init(#[from] init::Error),
+    #[error(
+        "The bitmap at '{path}' was created for checksum {actual}, but the pack or multi-pack index has checksum {expected}"
+    )]
+    ChecksumMismatch {
+        path: std::path::PathBuf,
+        expected: gix_hash::ObjectId,
+        actual: gix_hash::ObjectId,
+    },
+    #[error("The multi-pack index at '{path}' has no reverse index, which is needed to use its bitmap")]
+    MissingReverseIndex { path: std::path::PathBuf },
+    #[error("{message}")]
+    Corrupt { message: &'static str },
+}
+
+/// Initialization
+impl Index {
+    /// Open the bitmap file that belongs to the pack of `index`, which is `pack-<hash>.bitmap` next to the index file,
+    /// or return `None` if there is none.
+    pub fn at_pack_index(index: Arc<crate::index::File>) -> Result<Option<Self>, Error> {
+        let path = index.path().with_extension("bitmap");
+        match File::at(&path, index.object_hash()) {
+            Ok(file) => Self::from_pack_index(file, index).map(Some),
+            Err(init::Error::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Open the bitmap file that belongs to `multi_index`, which is `multi-pack-index-<checksum>.bitmap` next to it,
+    /// or return `None` if there is none.
+    ///
+    /// `None` is also returned for [layers](crate::multi_index::File::is_layer()) of a multi-index chain as their
+    /// bitmaps cover the objects of all layers below them, which isn't supported.
+    pub fn at_multi_index(multi_index: Arc<crate::multi_index::File>) -> Result<Option<Self>, Error> {
+        if multi_index.is_layer() {
+            return Ok(None);
+        }
+        let path = multi_index
+            .path()
+            .with_file_name(format!("multi-pack-index-{}.bitmap", multi_index.checksum()));
+        match File::at(&path, multi_index.object_hash()) {
+            Ok(file) => Self::from_multi_index(file, multi_index).map(Some),
+            Err(init::Error::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Use the bitmap `file` with the objects of the pack that belongs to `index`.
+    ///
+    /// Bits are assigned to objects in the order in which they appear in the pack.
+    pub fn from_pack_index(file: File, index: Arc<crate::index::File>) -> Result<Self, Error> {
+        check_checksum(&file, index.pack_checksum())?;
+        let mut index_position_by_bit: Vec<_> = (0..index.num_objects()).collect();
+        index_position_by_bit.sort_by_key(|position| index.pack_offset_at_index(*position));
+        Self::new(file, Objects::Pack(index), index_position_by_bit)
+    }
+
+    /// Use the bitmap `file` with the objects of `multi_index`, which must have a reverse index.
+    ///
+    /// Bits are assigned to objects in the pseudo-pack order of the multi-pack index.
+    pub fn from_multi_index(file: File, multi_index: Arc<crate::multi_index::File>) -> Result<Self, Error> {
+        check_checksum(&file, multi_index.checksum())?;
+        let index_position_by_bit = (0..multi_index.num_objects())
+            .map(|pack_position| multi_index.entry_index_at_pack_position(pack_position))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::MissingReverseIndex {
+                path: multi_index.path().to_owned(),
+            })?;
+        Self::new(file, Objects::MultiPack(multi_index), index_position_by_bit)
+    }
+
+    fn new(file: File, objects: Objects, index_position_by_bit: Vec<u32>) -> Result<Self, Error> {
+        let num_objects = index_position_by_bit.len();
+        let mut bit_by_index_position = vec![u32::MAX; num_objects];
+        for (bit, index_position) in index_position_by_bit.iter().enumerate() {
+            match bit_by_index_position.get_mut(*index_position as usize) {
+                Some(slot) if *slot == u32::MAX => *slot = bit as u32,
+                _ => {
+                    return Err(Error::Corrupt {
+                        message: "the pack order doesn't assign each object exactly one position",
+                    });
+                }
+            }
+        }
+
+        let mut entry_by_index_position = std::collections::HashMap::with_capacity(file.entries().len());
+        for (entry_index, entry) in file.entries().iter().enumerate() {
+            if entry.index_position as usize >= num_objects {
+                return Err(Error::Corrupt {
+                    message: "a bitmap refers to a commit that isn't in the index",
+                });
+            }
+            entry_by_index_position.insert(entry.index_position, entry_index);
+        }
+
+        let kind_set = |kind| {
+            Set::from_ewah(file.objects_of_kind(kind)).ok_or(Error::Corrupt {
+                message: "the bitmap of objects of a kind is malformed",
+            })
+        };
+        use gix_object::Kind::*;
+        let kinds = [kind_set(Commit)?, kind_set(Tree)?, kind_set(Blob)?, kind_set(Tag)?];
+
+        Ok(Index {
+            file,
+            objects,
+            index_position_by_bit,
+            bit_by_index_position,
+            entry_by_index_position,
+            kinds,
+        })
+    }
+}
+
+fn check_checksum(file: &File, expected: gix_hash::ObjectId) -> Result<(), Error> {
+    let actual = file.checksum();
+    if actual != expected {
+        return Err(Error::ChecksumMismatch {
+            path: file.path().to_owned(),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Access
+impl Index {
+    /// Return the underlying bitmap file.
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Return the amount of objects that bits are assigned to, as one past the highest bit.
+    pub fn num_objects(&self) -> usize {
+        self.index_position_by_bit.len()
+    }
+
+    /// Return the bit of the object with `id`, or `None` if it isn't contained in the pack or multi-pack index.
+    pub fn bit_of(&self, id: &gix_hash::oid) -> Option<usize> {
+        let index_position = match &self.objects {
+            Objects::Pack(index) => index.lookup(id),
+            Objects::MultiPack(index) => index.lookup(id),
+        }?;
+        Some(self.bit_by_index_position[index_position as usize] as usize)
+    }
+
+    /// Return the id of the object at `bit`, which ranges from 0 to [`Index::num_objects()`].
+    ///
+    /// # Panics
+    ///
+    /// If `bit` is out of bounds.
+    pub fn id_at(&self, bit: usize) -> &gix_hash::oid {
+        let index_position = self.index_position_by_bit[bit];
+        match &self.objects {
+            Objects::Pack(index) => index.oid_at_index(index_position),
+            Objects::MultiPack(index) => index.oid_at_index(index_position),
+        }
+    }
+
+    /// Return the kind of the object at `bit`, or `None` if the type bitmaps don't know it.
+    pub fn kind_at(&self, bit: usize) -> Option<gix_object::Kind> {
+        use gix_object::Kind::*;
+        [Commit, Tree, Blob, Tag]
+            .into_iter()
+            .find(|kind| self.kinds[kind_index(*kind)].contains(bit))
+    }
+
+    /// Return the set of all objects of `kind`.
+    pub fn objects_of_kind(&self, kind: gix_object::Kind) -> &Set {
+        &self.kinds[kind_index(kind)]
+    }
+
+    /// Return the hash of the name of the object at `bit`, if the bitmap file has a name-hash cache.
+    pub fn name_hash_at(&self, bit: usize) -> Option<u32> {
+        self.file.name_hash_at(bit.try_into().ok()?)
+    }
+
+    /// Return the set of all objects reachable from the commit with `id`, including the commit itself, if a bitmap
+    /// is stored for it.
+    ///
+    /// Stored bitmaps that are malformed are treated as if they didn't exist.
+    pub fn bitmap_of(&self, id: &gix_hash::oid) -> Option<Set> {
+        let index_position = self.index_position_by_bit[self.bit_of(id)?];
+        let mut entry_index = *self.entry_by_index_position.get(&index_position)?;
+        let entries = self.file.entries();
+        let mut set = Set::default();
+        loop {
+            let entry = &entries[entry_index];
+            set.symmetric_difference_with(&Set::from_ewah(&self.file.stored_bitmap(entry).ok()?)?);
+            if entry.xor_offset == 0 {
+                break;
+            }
+            entry_index -= usize::from(entry.xor_offset);
+        }
+        set.fits(self.num_objects()).then_some(set)
+    }
+}
@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use crate::bitmap::{Entry, File, flags};
+
+/// The error returned by [`File::at()`].
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("Could not open bitmap file at '{path}'")]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("{message}")]
+    Corrupt { message: &'static str },
+    #[error("Unsupported bitmap version: {version}")]
+    UnsupportedVersion { version: u16 },
+    #[error("The {kind} bitmap could not be decoded")]
+    BitmapDecode {
+        source: gix_bitmap::ewah::decode::Error,
+        kind: &'static str,
+    },
+}
+
+/// Initialization
+impl File<crate::MMap> {
+    /// Open the bitmap file at the given `path`, whose object ids are hashed with `object_hash`.
+    pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        Self::at_inner(path.as_ref(), object_hash)
+    }
+
+    fn at_inner(path: &Path, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        Self::from_data(data, path.to_owned(), object_hash)
+    }
+}
+
+impl<T> File<T>
+where
+    T: crate::FileData,
+{
+    /// The signature at the beginning of each bitmap file.
+    pub const SIGNATURE: &'static [u8] = b"BITM";
+    /// The size of the header, without the checksum of the pack or multi-pack index.
+    pub const HEADER_LEN: usize = 4 /*signature*/ + 2 /*version*/ + 2 /*flags*/ + 4 /*num entries*/;
+
+    /// Instantiate a bitmap file from `data` as assumed to be read or memory-mapped from `path`.
+    pub fn from_data(data: T, path: PathBuf, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let hash_len = object_hash.len_in_bytes();
+        if data.len() < Self::HEADER_LEN + hash_len * 2 {
+            return Err(Error::Corrupt {
+                message: "bitmap file is truncated and too short",
+            });
+        }
+        let (signature, rest) = data.split_at(4);
+        if signature != Self::SIGNATURE {
+            return Err(Error::Corrupt {
+                message: "Invalid signature",
+            });
+        }
+        let version = u16::from_be_bytes(rest[..2].try_into().expect("2 bytes"));
+        if version != 1 {
+            return Err(Error::UnsupportedVersion { version });
+        }
+        let flags = u16::from_be_bytes(rest[2..4].try_into().expect("2 bytes"));
+        if flags & flags::FULL_DAG == 0 {
+            return Err(Error::Corrupt {
+                message: "bitmaps must contain all reachable objects",
+            });
+        }
+        let num_entries = crate::read_u32(&rest[4..8]);
+
+        let body_end = data.len() - hash_len;
+        let mut ofs = Self::HEADER_LEN + hash_len;
+        let mut decode_kind = |kind| {
+            let (bitmap, rest) = gix_bitmap::ewah::decode(&data[ofs..body_end])
+                .map_err(|source| Error::BitmapDecode { source, kind })?;
+            ofs = body_end - rest.len();
+            Ok::<_, Error>(bitmap)
+        };
+        let kinds = [
+            decode_kind("commit")?,
+            decode_kind("tree")?,
+            decode_kind("blob")?,
+            decode_kind("tag")?,
+        ];
+
+        let mut entries = Vec::new();
+        for idx in 0..num_entries as usize {
+            const ENTRY_HEADER_LEN: usize = 4 /*index position*/ + 1 /*xor offset*/ + 1 /*flags*/;
+            let entry = data[ofs..body_end].get(..ENTRY_HEADER_LEN).ok_or(Error::Corrupt {
+                message: "bitmap entry is truncated",
+            })?;
+            let xor_offset = entry[4];
+            if usize::from(xor_offset) > idx {
+                return Err(Error::Corrupt {
+                    message: "bitmap entry refers to a bitmap before the first one",
+                });
+            }
+            let bitmap_ofs = ofs + ENTRY_HEADER_LEN;
+            entries.push(Entry {
+                index_position: crate::read_u32(&entry[..4]),
+                xor_offset,
+                flags: entry[5],
+                bitmap_ofs,
+            });
+            ofs = bitmap_ofs
+                + ewah_len(&data[bitmap_ofs..body_end]).ok_or(Error::Corrupt {
+                    message: "bitmap of entry is truncated",
+                })?;
+        }
+
+        Ok(File {
+            name_hash_cache_ofs: (flags & flags::HASH_CACHE != 0).then_some(ofs),
+            data,
+            path,
+            object_hash,
+            flags,
+            kinds,
+            entries,
+        })
+    }
+}
+
+/// Return the length of the EWAH-encoded bitmap at the beginning of `data`, or `None` if it's truncated.
+fn ewah_len(data: &[u8]) -> Option<usize> {
+    let num_words = crate::read_u32(data.get(4..8)?) as usize;
+    let len = num_words
+        .checked_mul(8)?
+        .checked_add(4 /*bits*/ + 4 /*words*/ + 4 /*rlw*/)?;
+    (len <= data.len()).then_some(len)
+}
@@ -0,0 +1,90 @@
+//! Reachability bitmaps as stored in `pack-<hash>.bitmap` and `multi-pack-index-<checksum>.bitmap` files.
+//!
+//! Each object of a pack or multi-pack index is assigned a bit, its position in pack order, so that sets of objects
+//! can be represented as bitmaps. For selected commits, the bitmap of all objects reachable from them is stored, which
+//! allows to learn which objects are reachable from these commits without traversing commits and trees.
+use std::{path::PathBuf, sync::Arc};
+
+use crate::MMap;
+
+/// Options of a bitmap file as stored in its header.
+pub mod flags {
+    /// Bitmaps contain all reachable objects, which is required.
+    pub const FULL_DAG: u16 = 0x1;
+    /// A cache of the hashes of the names of all objects follows the bitmaps of commits.
+    pub const HASH_CACHE: u16 = 0x4;
+    /// A lookup table for the bitmaps of commits follows the name-hash cache.
+    pub const LOOKUP_TABLE: u16 = 0x10;
+    /// Bitmaps of pseudo-merge commits follow the name-hash cache.
+    pub const PSEUDO_MERGES: u16 = 0x20;
+}
+
+/// A bitmap file with the reachability bitmaps of selected commits of a pack or multi-pack index,
+/// typically named `pack-<hash>.bitmap` or `multi-pack-index-<checksum>.bitmap`.
+pub struct File<T = MMap> {
+    data: T,
+    path: PathBuf,
+    object_hash: gix_hash::Kind,
+    flags: u16,
+    /// The bitmaps of all commits, trees, blobs and tags, in that order.
+    kinds: [gix_bitmap::ewah::Vec; 4],
+    entries: Vec<Entry>,
+    name_hash_cache_ofs: Option<usize>,
+}
+
+/// The stored reachability bitmap of a commit in a bitmap [`File`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub struct Entry {
+    /// The position of the commit in the lexicographically sorted object ids of the pack index or multi-pack index.
+    pub index_position: u32,
+    /// If not `0`, the stored bitmap has to be combined using XOR with the bitmap of the entry this many entries
+    /// before this one to obtain the actual bitmap.
+    pub xor_offset: u8,
+    /// Flags of the bitmap, with `0x1` signalling that the objects are good candidates for pack reuse.
+    pub flags: u8,
+    /// The offset of the EWAH-encoded bitmap into the file.
+    bitmap_ofs: usize,
+}
+
+/// A set of objects, each represented by a bit at its position in pack order.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Set {
+    words: Vec<u64>,
+}
+
+/// A bitmap [`File`] along with the pack index or multi-pack index whose objects it refers to, which is needed to
+/// assign bits to objects and to compute which objects are [reachable](Index::reachable()) from others.
+pub struct Index {
+    file: File,
+    objects: Objects,
+    /// The position in the pack index or multi-pack index of the object at each bit position.
+    index_position_by_bit: Vec<u32>,
+    /// The bit position of each object, by its position in the pack index or multi-pack index.
+    bit_by_index_position: Vec<u32>,
+    /// The index into the entries of `file` for each commit with a stored bitmap, by its position in the index.
+    entry_by_index_position: std::collections::HashMap<u32, usize>,
+    /// The objects of each kind, for commits, trees, blobs and tags, in that order.
+    kinds: [Set; 4],
+}
+
+enum Objects {
+    Pack(Arc<crate::index::File>),
+    MultiPack(Arc<crate::multi_index::File>),
+}
+
+///
+pub mod init;
+
+mod access;
+
+///
+pub mod index;
+
+mod set;
+
+///
+pub mod reachable;
+
+///
+pub mod write;
+pub use write::write_for_pack;
@@ -0,0 +1,81 @@
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+use crate::bitmap::{Index, Set};
+
+/// The error returned by [`Index::reachable()`].
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Find(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+}
+
+impl Index {
+    /// Return the set of all objects reachable from `tips`, including the tips themselves, like `git rev-list --objects` would,
+    /// or `None` if one of the reachable objects isn't contained in the pack or multi-pack index so the set would be incomplete.
+    ///
+    /// Stored [bitmaps](Index::bitmap_of()) are used for all commits that have one, while all other commits, tags and trees
+    /// are traversed using `objects`.
+    pub fn reachable(
+        &self,
+        tips: impl IntoIterator<Item = ObjectId>,
+        objects: &dyn gix_object::Find,
+    ) -> Result<Option<Set>, Error> {
+        use gix_object::Kind::*;
+
+        let mut set = Set::default();
+        let mut buf = Vec::new();
+        let mut queue: Vec<_> = tips.into_iter().collect();
+        let mut trees = Vec::new();
+        while let Some(id) = queue.pop() {
+            let Some(bit) = self.bit_of(&id) else {
+                return Ok(None);
+            };
+            if set.contains(bit) {
+                continue;
+            }
+            if let Some(bitmap) = self.bitmap_of(&id) {
+                set.union_with(&bitmap);
+                continue;
+            }
+            set.insert(bit);
+            match self.kind_at(bit) {
+                Some(Commit) => {
+                    let mut commit = objects.find_commit_iter(&id, &mut buf)?;
+                    let tree_id = commit.tree_id()?;
+                    let Some(tree_bit) = self.bit_of(&tree_id) else {
+                        return Ok(None);
+                    };
+                    if set.insert(tree_bit) {
+                        trees.push(tree_id);
+                    }
+                    queue.extend(commit.parent_ids());
+                }
+                Some(Tag) => queue.push(objects.find_tag_iter(&id, &mut buf)?.target_id()?),
+                Some(Tree) => trees.push(id),
+                Some(Blob) => {}
+                None => return Ok(None),
+            }
+
+            // Trees are added with everything they contain, so trees that are already contained can be skipped.
+            while let Some(tree_id) = trees.pop() {
+                for entry in objects.find_tree_iter(&tree_id, &mut buf)? {
+                    let entry = entry?;
+                    if entry.mode.is_commit() {
+                        continue;
+                    }
+                    let Some(bit) = self.bit_of(entry.oid) else {
+                        return Ok(None);
+                    };
+                    if set.insert(bit) && entry.mode.is_tree() {
+                        trees.push(entry.oid.to_owned());
+                    }
+                }
+            }
+        }
+        Ok(Some(set))
+    }
+}
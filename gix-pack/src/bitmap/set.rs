@@ -0,0 +1,96 @@
+use crate::bitmap::Set;
+
+impl Set {
+    /// Create a set from the set bits of the EWAH-encoded `bitmap`, or return `None` if it's malformed.
+    pub fn from_ewah(bitmap: &gix_bitmap::ewah::Vec) -> Option<Self> {
+        let mut set = Set {
+            words: vec![0; bitmap.num_bits().div_ceil(64)],
+        };
+        bitmap.for_each_set_bit(|bit| {
+            set.insert(bit);
+            Some(())
+        })?;
+        Some(set)
+    }
+
+    /// Encode this set as EWAH bitmap, or return `None` if there are too many bits to encode.
+    pub fn to_ewah(&self) -> Option<gix_bitmap::ewah::Vec> {
+        gix_bitmap::ewah::Vec::from_words(&self.words)
+    }
+
+    /// Return `true` if the object at `bit` is contained in this set.
+    pub fn contains(&self, bit: usize) -> bool {
+        self.words
+            .get(bit / 64)
+            .is_some_and(|word| word & (1 << (bit % 64)) != 0)
+    }
+
+    /// Add the object at `bit` to this set, and return `true` if it wasn't contained yet.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        let word_index = bit / 64;
+        if word_index >= self.words.len() {
+            self.words.resize(word_index + 1, 0);
+        }
+        let word = &mut self.words[word_index];
+        let mask = 1 << (bit % 64);
+        let inserted = *word & mask == 0;
+        *word |= mask;
+        inserted
+    }
+
+    /// Add all objects of `other` to this set.
+    pub fn union_with(&mut self, other: &Set) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// Remove all objects of `other` from this set.
+    pub fn difference_with(&mut self, other: &Set) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= !other;
+        }
+    }
+
+    /// Keep only the objects that are contained in either this set or `other`, but not in both.
+    pub fn symmetric_difference_with(&mut self, other: &Set) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+
+    /// Return the amount of objects in this set.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Return `true` if there is no object in this set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Return `true` if all objects of this set are at a bit lower than `num_bits`.
+    pub(crate) fn fits(&self, num_bits: usize) -> bool {
+        self.iter().last().is_none_or(|bit| bit < num_bits)
+    }
+
+    /// Return an iterator over the bit positions of all objects in this set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_index, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    word_index * 64 + bit
+                })
+            })
+        })
+    }
+}
@@ -0,0 +1,435 @@
+use std::{
+    collections::{BinaryHeap, HashMap, VecDeque},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+use crate::{
+    bitmap::{File, Set, access::kind_index, flags},
+    data,
+};
+
+/// The error returned by [`write_for_pack()`] and when writing bitmaps along with packs or multi-pack indices.
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("Could not write bitmap file at '{path}'")]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error(transparent)]
+    Hash(#[from] gix_hash::hasher::Error),
+    #[error(transparent)]
+    DecodeEntry(#[from] data::decode::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+    #[error("There are too many objects to assign a bit to each of them")]
+    TooManyObjects,
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Options for writing bitmap files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    /// If `true`, store the [hash of the name](crate::bitmap::File::name_hash_at()) of each object so that objects found
+    /// with bitmaps can be sorted to find good delta bases, like `pack.writeBitmapHashCache` does.
+    pub name_hash_cache: bool,
+    /// If `Some(method)`, make the bitmap file durable with it, as controlled by `pack-metadata` in `core.fsync`.
+    pub fsync: Option<gix_fs::fsync::Method>,
+}
+
+impl Default for Options {
+    /// Write the name-hash cache, just like `git` does by default.
+    fn default() -> Self {
+        Options {
+            name_hash_cache: true,
+            fsync: None,
+        }
+    }
+}
+
+/// The result of writing a bitmap file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The path of the newly written bitmap file.
+    pub path: PathBuf,
+    /// The amount of commits for which a reachability bitmap was stored.
+    pub num_bitmaps: usize,
+}
+
+/// Write the bitmap file for the pack of `bundle` as `pack-<hash>.bitmap` next to its index, or return `None` if the pack
+/// doesn't contain all objects reachable from its commits so that no bitmap can be written.
+///
+/// `should_interrupt` is checked regularly to abort the operation.
+pub fn write_for_pack(
+    bundle: &crate::Bundle,
+    should_interrupt: &AtomicBool,
+    options: Options,
+) -> Result<Option<Outcome>, Error> {
+    let index = &bundle.index;
+    let mut objects: Vec<_> = index
+        .iter()
+        .enumerate()
+        .map(|(index_position, entry)| Object {
+            id: entry.oid,
+            bundle: 0,
+            pack_offset: entry.pack_offset,
+            index_position: index_position as u32,
+        })
+        .collect();
+    objects.sort_by_key(|object| object.pack_offset);
+    write(
+        std::slice::from_ref(bundle),
+        &objects,
+        index.pack_checksum(),
+        &index.path().with_extension("bitmap"),
+        should_interrupt,
+        options,
+    )
+}
+
+/// An object which is assigned the bit of its position in a list of objects in pack order.
+pub(crate) struct Object {
+    pub id: ObjectId,
+    /// The index of the bundle containing the object.
+    pub bundle: u32,
+    pub pack_offset: data::Offset,
+    /// The position of the object in the pack index or multi-pack index, used to identify commits with a stored bitmap.
+    pub index_position: u32,
+}
+
+/// Write a bitmap file to `path` for `objects`, which are assigned bits in the order they are given and are contained
+/// in `bundles`, to be used with the pack data file or multi-pack index with `checksum`.
+pub(crate) fn write(
+    bundles: &[crate::Bundle],
+    objects: &[Object],
+    checksum: ObjectId,
+    path: &Path,
+    should_interrupt: &AtomicBool,
+    Options { name_hash_cache, fsync }: Options,
+) -> Result<Option<Outcome>, Error> {
+    let object_hash = checksum.kind();
+    if u32::try_from(objects.len()).is_err() {
+        return Err(Error::TooManyObjects);
+    }
+    let bit_by_id: HashMap<_, _> = objects
+        .iter()
+        .enumerate()
+        .map(|(bit, object)| (object.id, bit as u32))
+        .collect();
+    let bit_of = |id: &gix_hash::oid| bit_by_id.get(id).map(|bit| *bit as usize);
+    let mut packs = Packs {
+        bundles,
+        objects,
+        inflate: Default::default(),
+    };
+    let mut buf = Vec::new();
+
+    let mut kinds: [Set; 4] = Default::default();
+    for bit in 0..objects.len() {
+        kinds[kind_index(packs.kind(bit)?)].insert(bit);
+        check_interrupt(should_interrupt)?;
+    }
+
+    let mut commits = Vec::new();
+    let mut commit_by_bit = HashMap::new();
+    for bit in kinds[kind_index(Kind::Commit)].iter() {
+        let commit = gix_object::CommitRef::from_bytes(packs.data(bit, &mut buf)?, object_hash)?;
+        let (Some(tree), Some(parents)) = (
+            bit_of(&commit.tree()),
+            commit.parents().map(|id| bit_of(&id)).collect::<Option<Vec<_>>>(),
+        ) else {
+            return Ok(None);
+        };
+        commit_by_bit.insert(bit, commits.len());
+        commits.push(Commit {
+            bit,
+            tree,
+            parents,
+            time: commit.time()?.seconds,
+            generation: 0,
+        });
+    }
+    for commit in &mut commits {
+        for parent in &mut commit.parents {
+            let Some(commit) = commit_by_bit.get(parent) else {
+                return Ok(None);
+            };
+            *parent = *commit;
+        }
+    }
+    assign_generations(&mut commits);
+
+    let mut selected = select_commits(&commits);
+    // Ancestors are computed first so their bitmaps can be reused by their descendants.
+    selected.sort_by_key(|commit| (commits[*commit].generation, commits[*commit].bit));
+
+    let mut bitmap_by_commit = HashMap::new();
+    let mut recent = VecDeque::<Set>::new();
+    let mut entries = Vec::with_capacity(selected.len());
+    let mut name_hashes = vec![0; if name_hash_cache { objects.len() } else { 0 }];
+    for tip in selected {
+        let mut set = Set::default();
+        let mut trees = Vec::new();
+        let mut queue = BinaryHeap::from([(commits[tip].generation, tip)]);
+        // Descendants are visited first, so ancestors are likely to be contained in the bitmap of another commit already.
+        while let Some((_generation, commit)) = queue.pop() {
+            let commit = &commits[commit];
+            if set.contains(commit.bit) {
+                continue;
+            }
+            if let Some(bitmap) = bitmap_by_commit.get(&commit.bit) {
+                set.union_with(&Set::from_ewah(bitmap).expect("bitmaps we encoded can be decoded"));
+                continue;
+            }
+            set.insert(commit.bit);
+            if set.insert(commit.tree) {
+                trees.push(commit.tree);
+            }
+            queue.extend(
+                commit
+                    .parents
+                    .iter()
+                    .map(|parent| (commits[*parent].generation, *parent)),
+            );
+        }
+
+        // Trees are added with everything they contain, so trees that are already contained can be skipped.
+        while let Some(tree) = trees.pop() {
+            for entry in gix_object::TreeRefIter::from_bytes(packs.data(tree, &mut buf)?, object_hash) {
+                let entry = entry?;
+                if entry.mode.is_commit() {
+                    continue;
+                }
+                let Some(bit) = bit_of(entry.oid) else {
+                    return Ok(None);
+                };
+                if set.insert(bit) {
+                    if let Some(name_hash) = name_hashes.get_mut(bit).filter(|hash| **hash == 0) {
+                        *name_hash = crate::name_hash(entry.filename);
+                    }
+                    if entry.mode.is_tree() {
+                        trees.push(bit);
+                    }
+                }
+            }
+        }
+
+        let (xor_offset, bitmap) = std::iter::once((0, set.clone()))
+            .chain(recent.iter().rev().enumerate().map(|(offset, previous)| {
+                let mut xor = set.clone();
+                xor.symmetric_difference_with(previous);
+                (offset + 1, xor)
+            }))
+            .map(|(offset, bitmap)| (offset, bitmap.to_ewah().expect("amount of objects fits into u32")))
+            .min_by_key(|(_offset, bitmap)| bitmap.num_words())
+            .expect("at least the bitmap itself");
+        entries.push((objects[commits[tip].bit].index_position, xor_offset as u8, bitmap));
+        bitmap_by_commit.insert(
+            commits[tip].bit,
+            set.to_ewah().expect("amount of objects fits into u32"),
+        );
+        if recent.len() == MAX_XOR_OFFSET {
+            recent.pop_front();
+        }
+        recent.push_back(set);
+        check_interrupt(should_interrupt)?;
+    }
+
+    let mut out = gix_hash::io::Write::new(Vec::new(), object_hash);
+    let io_err = |source| Error::Io {
+        source,
+        path: path.to_owned(),
+    };
+    (|| -> std::io::Result<()> {
+        out.write_all(File::<crate::MMap>::SIGNATURE)?;
+        out.write_all(&1u16.to_be_bytes())?;
+        let flags = flags::FULL_DAG | if name_hash_cache { flags::HASH_CACHE } else { 0 };
+        out.write_all(&flags.to_be_bytes())?;
+        out.write_all(&(entries.len() as u32).to_be_bytes())?;
+        out.write_all(checksum.as_slice())?;
+        for kind in &kinds {
+            kind.to_ewah()
+                .expect("amount of objects fits into u32")
+                .write_to(&mut out)?;
+        }
+        for (index_position, xor_offset, bitmap) in &entries {
+            out.write_all(&index_position.to_be_bytes())?;
+            out.write_all(&[*xor_offset, 0 /* flags */])?;
+            bitmap.write_to(&mut out)?;
+        }
+        for name_hash in &name_hashes {
+            out.write_all(&name_hash.to_be_bytes())?;
+        }
+        Ok(())
+    })()
+    .map_err(io_err)?;
+    let trailer = out.hash.try_finalize()?;
+    let mut data = out.inner;
+    data.extend_from_slice(trailer.as_slice());
+
+    let temporary_path = path.with_extension("bitmap.tmp");
+    std::fs::File::create(&temporary_path)
+        .and_then(|mut file| {
+            file.write_all(&data)?;
+            fsync.map_or(Ok(()), |method| gix_fs::fsync::file(&file, method))
+        })
+        .and_then(|()| std::fs::rename(&temporary_path, path))
+        .map_err(|err| {
+            std::fs::remove_file(&temporary_path).ok();
+            io_err(err)
+        })?;
+    if let Some(directory) = path.parent().filter(|_| fsync.is_some()) {
+        gix_fs::fsync::directory(directory).ok();
+    }
+    Ok(Some(Outcome {
+        path: path.to_owned(),
+        num_bitmaps: entries.len(),
+    }))
+}
+
+/// The amount of previous bitmaps to try when looking for the one that XORs into the smallest bitmap, like `git` does.
+const MAX_XOR_OFFSET: usize = 10;
+
+fn check_interrupt(should_interrupt: &AtomicBool) -> Result<(), Error> {
+    if should_interrupt.load(Ordering::Relaxed) {
+        return Err(Error::Interrupted);
+    }
+    Ok(())
+}
+
+struct Commit {
+    bit: usize,
+    tree: usize,
+    /// The indices of the parent commits, which are initially bits.
+    parents: Vec<usize>,
+    time: i64,
+    /// One more than the highest generation of all parents, with root commits having generation 1.
+    generation: u32,
+}
+
+fn assign_generations(commits: &mut [Commit]) {
+    for start in 0..commits.len() {
+        let mut stack = vec![start];
+        while let Some(&commit) = stack.last() {
+            if commits[commit].generation != 0 {
+                stack.pop();
+                continue;
+            }
+            let num_pending = stack.len();
+            let mut generation = 0;
+            for parent in &commits[commit].parents {
+                match commits[*parent].generation {
+                    0 => stack.push(*parent),
+                    parent_generation => generation = generation.max(parent_generation),
+                }
+            }
+            if stack.len() == num_pending {
+                commits[commit].generation = generation + 1;
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// Select the indices of the commits to store a bitmap for, like `git` does it.
+///
+/// All commits are selected in small packs, otherwise all of the most recent ones, and then fewer and fewer
+/// further back in history, preferring merge commits.
+fn select_commits(commits: &[Commit]) -> Vec<usize> {
+    const MUST_REGION: usize = 100;
+    const MIN_REGION: usize = 20_000;
+    const MIN_COMMITS: usize = 100;
+    const MAX_COMMITS: usize = 5_000;
+
+    let mut by_time: Vec<_> = (0..commits.len()).collect();
+    by_time.sort_by_key(|commit| std::cmp::Reverse(commits[*commit].time));
+    if by_time.len() < MUST_REGION {
+        return by_time;
+    }
+
+    let next_commit_index = |idx: usize| {
+        if idx <= MUST_REGION {
+            0
+        } else if idx <= MIN_REGION {
+            (idx - MUST_REGION).min(MIN_COMMITS)
+        } else {
+            (idx - MIN_REGION).clamp(MIN_COMMITS, MAX_COMMITS)
+        }
+    };
+    let mut selected = Vec::new();
+    let mut idx = 0;
+    loop {
+        let next = next_commit_index(idx);
+        if idx + next >= by_time.len() {
+            break;
+        }
+        let candidates = &by_time[idx..=idx + next];
+        selected.push(
+            candidates
+                .iter()
+                .rev()
+                .find(|commit| commits[**commit].parents.len() > 1)
+                .copied()
+                .unwrap_or(candidates[next]),
+        );
+        idx += next + 1;
+    }
+    selected
+}
+
+/// Access to the objects in `bundles`.
+struct Packs<'a> {
+    bundles: &'a [crate::Bundle],
+    objects: &'a [Object],
+    inflate: gix_zlib::Inflate,
+}
+
+impl Packs<'_> {
+    fn kind(&mut self, bit: usize) -> Result<Kind, Error> {
+        let object = &self.objects[bit];
+        let bundle = &self.bundles[object.bundle as usize];
+        Ok(bundle
+            .pack
+            .decode_header(
+                bundle
+                    .pack
+                    .entry(object.pack_offset)
+                    .map_err(data::decode::Error::from)?,
+                &mut self.inflate,
+                &|id| in_pack_entry(bundle, id).map(data::decode::header::ResolvedBase::InPack),
+            )?
+            .kind)
+    }
+
+    fn data<'b>(&mut self, bit: usize, buf: &'b mut Vec<u8>) -> Result<&'b [u8], Error> {
+        let object = &self.objects[bit];
+        let bundle = &self.bundles[object.bundle as usize];
+        bundle.pack.decode_entry(
+            bundle
+                .pack
+                .entry(object.pack_offset)
+                .map_err(data::decode::Error::from)?,
+            buf,
+            &mut self.inflate,
+            &|id, _out| in_pack_entry(bundle, id).map(data::decode::entry::ResolvedBase::InPack),
+            &mut crate::cache::Never,
+        )?;
+        Ok(buf)
+    }
+}
+
+fn in_pack_entry(bundle: &crate::Bundle, id: &gix_hash::oid) -> Option<data::Entry> {
+    let index_position = bundle.index.lookup(id)?;
+    bundle
+        .pack
+        .entry(bundle.index.pack_offset_at_index(index_position))
+        .ok()
+}
@@ -14,4 +14,8 @@ pub enum Error {
     Persist(#[from] gix_tempfile::handle::persist::Error<Writable>),
     #[error(transparent)]
     IndexWrite(#[from] crate::index::write::Error),
+    #[error("Could not open the newly written pack to write its bitmap")]
+    BundleInit(#[from] crate::bundle::init::Error),
+    #[error(transparent)]
+    BitmapWrite(#[from] crate::bitmap::write::Error),
 }
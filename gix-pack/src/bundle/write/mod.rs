@@ -142,6 +142,7 @@ impl crate::Bundle {
             data_path,
             index_path,
             keep_path,
+            bitmap_path,
         } = crate::Bundle::inner_write(
             directory,
             progress,
@@ -159,6 +160,7 @@ impl crate::Bundle {
             data_path,
             index_path,
             keep_path,
+            bitmap_path,
         })
     }
 
@@ -243,6 +245,7 @@ impl crate::Bundle {
             data_path,
             index_path,
             keep_path,
+            bitmap_path,
         } = crate::Bundle::inner_write(
             directory,
             progress,
@@ -260,6 +263,7 @@ impl crate::Bundle {
             data_path,
             index_path,
             keep_path,
+            bitmap_path,
         })
     }
 
@@ -273,6 +277,9 @@ impl crate::Bundle {
             object_hash,
             alloc_limit_bytes,
             compression: _,
+            bitmap,
+            fsync,
+            fsync_metadata,
         }: Options,
         data_file: SharedTempFile,
         mut pack_entries_iter: Box<dyn Iterator<Item = Result<data::input::Entry, data::input::Error>> + 'a>,
@@ -311,6 +318,7 @@ impl crate::Bundle {
                         data_path: None,
                         index_path: None,
                         keep_path: None,
+                        bitmap_path: None,
                     }
                 } else {
                     let data_path = directory.join(format!("pack-{}.pack", outcome.data_hash.to_hex()));
@@ -323,26 +331,50 @@ impl crate::Bundle {
                         let keep_path = data_path.with_extension("keep");
 
                         std::fs::write(&keep_path, b"")?;
-                        Arc::try_unwrap(data_file)
+                        let mut data_file = Arc::try_unwrap(data_file)
                             .expect("only one handle left after pack was consumed")
                             .into_inner()
                             .into_inner()
-                            .map_err(|err| Error::from(err.into_error()))?
-                            .persist(&data_path)?;
+                            .map_err(|err| Error::from(err.into_error()))?;
+                        if let Some(method) = fsync {
+                            data_file.with_mut(|file| gix_fs::fsync::file(file.as_file(), method))??;
+                        }
+                        data_file.persist(&data_path)?;
                         Some(keep_path)
                     };
                     if !index_path.is_file() {
+                        if let Some(method) = fsync_metadata {
+                            index_file.with_mut(|file| gix_fs::fsync::file(file.as_file(), method))??;
+                        }
                         index_file
                             .persist(&index_path)
                             .inspect_err(|_err| {
                                 gix_features::trace::warn!("pack file at \"{}\" is retained despite failing to move the index file into place. You can use plumbing to make it usable.",data_path.display());
                             })?;
                     }
+                    if fsync.is_some() || fsync_metadata.is_some() {
+                        // The pack is already in place at this point, and not all platforms and filesystems support this.
+                        gix_fs::fsync::directory(directory).ok();
+                    }
+                    let bitmap_path = match bitmap {
+                        Some(options) => {
+                            let bitmap_path = index_path.with_extension("bitmap");
+                            if bitmap_path.is_file() {
+                                Some(bitmap_path)
+                            } else {
+                                let bundle = crate::Bundle::at(&index_path, object_hash)?;
+                                crate::bitmap::write_for_pack(&bundle, should_interrupt, options)?
+                                    .map(|outcome| outcome.path)
+                            }
+                        }
+                        None => None,
+                    };
                     WriteOutcome {
                         outcome,
                         data_path: Some(data_path),
                         index_path: Some(index_path),
                         keep_path,
+                        bitmap_path,
                     }
                 }
             }
@@ -362,6 +394,7 @@ impl crate::Bundle {
                 data_path: None,
                 index_path: None,
                 keep_path: None,
+                bitmap_path: None,
             },
         })
     }
@@ -389,4 +422,5 @@ struct WriteOutcome {
     data_path: Option<PathBuf>,
     index_path: Option<PathBuf>,
     keep_path: Option<PathBuf>,
+    bitmap_path: Option<PathBuf>,
 }
@@ -24,6 +24,19 @@ pub struct Options {
     /// these with the same level it uses for loose objects, which is `core.looseCompression` or
     /// `core.compression`, or level 1 if neither is set.
     pub compression: gix_zlib::Compression,
+    /// If `Some`, write a reachability bitmap next to the pack with the given options, like `git repack --write-bitmap-index` does.
+    ///
+    /// No bitmap is written if the pack doesn't contain all objects reachable from its commits,
+    /// which typically is the case for packs received when fetching.
+    pub bitmap: Option<crate::bitmap::write::Options>,
+    /// If `Some(method)`, make the pack data file durable with it before moving it into place, along with the directory containing it.
+    ///
+    /// `git` does this by default as `pack` is part of `core.fsync` unless configured otherwise.
+    pub fsync: Option<gix_fs::fsync::Method>,
+    /// If `Some(method)`, make the index file durable with it, as controlled by `pack-metadata` in `core.fsync`.
+    ///
+    /// Note that bitmaps are made durable as configured in their [options](crate::bitmap::write::Options::fsync).
+    pub fsync_metadata: Option<gix_fs::fsync::Method>,
 }
 
 impl Default for Options {
@@ -36,6 +49,9 @@ impl Default for Options {
             object_hash: Default::default(),
             alloc_limit_bytes: None,
             compression: gix_zlib::Compression::BEST_SPEED,
+            bitmap: None,
+            fsync: None,
+            fsync_metadata: None,
         }
     }
 }
@@ -63,6 +79,8 @@ pub struct Outcome {
     /// The file is created right before moving the pack data and index data into place (i.e. `data_path` and `index_path`)
     /// and is expected to be removed by the caller when ready.
     pub keep_path: Option<PathBuf>,
+    /// The path to the reachability bitmap of the pack, if one was [requested](Options::bitmap) and could be written.
+    pub bitmap_path: Option<PathBuf>,
 }
 
 impl Outcome {
@@ -0,0 +1,244 @@
+/// The amount of bytes in blocks of the base object that are indexed, and the smallest amount of bytes we copy from it.
+const BLOCK_SIZE: usize = 16;
+/// The largest amount of bytes copied with a single instruction, as larger values aren't understood by old versions of `git`.
+const MAX_COPY_SIZE: usize = 0x10000;
+/// The largest amount of bytes inserted with a single instruction.
+const MAX_INSERT_SIZE: usize = 0x7f;
+/// The amount of positions with the same hash we try to match at most, to avoid degenerating on repetitive data.
+const MAX_CANDIDATES: usize = 64;
+/// The multiplier of the rolling hash over `BLOCK_SIZE` bytes.
+const HASH_PRIME: u32 = 0x0100_0193;
+
+/// An index over all blocks of a base object to quickly find where bytes of a target object can be copied from,
+/// similar to what `git` does in `diff-delta.c`.
+pub(crate) struct Index {
+    base: Vec<u8>,
+    /// The first position of a block with the hash selecting this bucket, or `u32::MAX`.
+    buckets: Vec<u32>,
+    /// The next position of a block in the same bucket, indexed by `position / BLOCK_SIZE`.
+    next: Vec<u32>,
+    shift: u32,
+}
+
+fn block_hash(block: &[u8]) -> u32 {
+    block.iter().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(HASH_PRIME).wrapping_add(u32::from(*byte))
+    })
+}
+
+impl Index {
+    /// Index all blocks of `base`.
+    pub(crate) fn new(base: Vec<u8>) -> Self {
+        let num_blocks = base.len() / BLOCK_SIZE;
+        let num_buckets = num_blocks.max(1).next_power_of_two();
+        let mut index = Index {
+            base: Vec::new(),
+            buckets: vec![u32::MAX; num_buckets],
+            next: vec![u32::MAX; num_blocks],
+            shift: u32::BITS - num_buckets.trailing_zeros(),
+        };
+        // Insert back to front so chains list the earliest positions first.
+        for block in (0..num_blocks).rev() {
+            let pos = block * BLOCK_SIZE;
+            let bucket = index.bucket(block_hash(&base[pos..][..BLOCK_SIZE]));
+            index.next[block] = index.buckets[bucket];
+            index.buckets[bucket] = pos as u32;
+        }
+        index.base = base;
+        index
+    }
+
+    fn bucket(&self, hash: u32) -> usize {
+        if self.shift == u32::BITS {
+            0
+        } else {
+            (hash.wrapping_mul(0x9e37_79b9) >> self.shift) as usize
+        }
+    }
+
+    /// The amount of bytes in the indexed base object.
+    pub(crate) fn base_len(&self) -> usize {
+        self.base.len()
+    }
+
+    /// Return the position and length of the longest match of `target[pos..]` in the base object, with `hash` being the hash
+    /// of the block at `pos`.
+    fn longest_match(&self, target: &[u8], pos: usize, hash: u32) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        let mut candidate = self.buckets[self.bucket(hash)];
+        let mut tries = 0;
+        while candidate != u32::MAX && tries < MAX_CANDIDATES {
+            tries += 1;
+            let base_pos = candidate as usize;
+            let len = self.base[base_pos..]
+                .iter()
+                .zip(&target[pos..])
+                .take(MAX_COPY_SIZE)
+                .take_while(|(a, b)| a == b)
+                .count();
+            if len >= BLOCK_SIZE && best.is_none_or(|(_, best_len)| len > best_len) {
+                best = Some((base_pos, len));
+                if len == MAX_COPY_SIZE {
+                    break;
+                }
+            }
+            candidate = self.next[base_pos / BLOCK_SIZE];
+        }
+        best
+    }
+
+    /// Write a delta to `out` which turns our base object into `target`, or return `false` if it would be larger than `max_size`.
+    ///
+    /// `out` is cleared beforehand, and its contents are unspecified if `false` is returned.
+    pub(crate) fn encode(&self, target: &[u8], max_size: usize, out: &mut Vec<u8>) -> bool {
+        out.clear();
+        encode_header_size(self.base.len() as u64, out);
+        encode_header_size(target.len() as u64, out);
+
+        let mut insert_start = 0;
+        let mut pos = 0;
+        let mut hash = None;
+        // The factor by which the byte leaving the rolling hash was multiplied.
+        let leaving_factor = (1..BLOCK_SIZE).fold(1u32, |acc, _| acc.wrapping_mul(HASH_PRIME));
+        while pos + BLOCK_SIZE <= target.len() {
+            let current = *hash.get_or_insert_with(|| block_hash(&target[pos..][..BLOCK_SIZE]));
+            match self.longest_match(target, pos, current) {
+                Some((mut base_pos, mut len)) => {
+                    // Extend the match backwards into bytes we would otherwise insert.
+                    while pos > insert_start && base_pos > 0 && self.base[base_pos - 1] == target[pos - 1] {
+                        pos -= 1;
+                        base_pos -= 1;
+                        len += 1;
+                    }
+                    encode_insert(&target[insert_start..pos], out);
+                    let mut remaining = len;
+                    while remaining > 0 {
+                        let size = remaining.min(MAX_COPY_SIZE);
+                        encode_copy(base_pos, size, out);
+                        base_pos += size;
+                        remaining -= size;
+                    }
+                    pos += len;
+                    insert_start = pos;
+                    hash = None;
+                }
+                None => {
+                    if pos + BLOCK_SIZE < target.len() {
+                        hash = Some(
+                            current
+                                .wrapping_sub(u32::from(target[pos]).wrapping_mul(leaving_factor))
+                                .wrapping_mul(HASH_PRIME)
+                                .wrapping_add(u32::from(target[pos + BLOCK_SIZE])),
+                        );
+                    }
+                    pos += 1;
+                }
+            }
+            if out.len() > max_size {
+                return false;
+            }
+        }
+        encode_insert(&target[insert_start..], out);
+        out.len() <= max_size
+    }
+}
+
+fn encode_header_size(mut size: u64, out: &mut Vec<u8>) {
+    while size >= 0x80 {
+        out.push((size as u8 & 0x7f) | 0x80);
+        size >>= 7;
+    }
+    out.push(size as u8);
+}
+
+fn encode_insert(mut data: &[u8], out: &mut Vec<u8>) {
+    while !data.is_empty() {
+        let (chunk, rest) = data.split_at(data.len().min(MAX_INSERT_SIZE));
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+        data = rest;
+    }
+}
+
+fn encode_copy(offset: usize, size: usize, out: &mut Vec<u8>) {
+    let cmd_pos = out.len();
+    out.push(0);
+    let mut cmd = 0b1000_0000;
+    for (bit, byte) in (offset as u32).to_le_bytes().into_iter().enumerate() {
+        if byte != 0 {
+            cmd |= 1 << bit;
+            out.push(byte);
+        }
+    }
+    // A size of 0 is interpreted as `MAX_COPY_SIZE`, which is exactly what we need when it's truncated to 3 bytes.
+    for (bit, byte) in (size as u32).to_le_bytes().into_iter().take(3).enumerate() {
+        if byte != 0 {
+            cmd |= 1 << (bit + 4);
+            out.push(byte);
+        }
+    }
+    out[cmd_pos] = cmd;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Index;
+    use crate::data::delta::{apply, decode_header_size};
+
+    fn round_trip(base: &[u8], target: &[u8]) -> usize {
+        let mut delta = Vec::new();
+        assert!(Index::new(base.to_vec()).encode(target, usize::MAX, &mut delta));
+        let (base_size, consumed) = decode_header_size(&delta).unwrap();
+        assert_eq!(base_size, base.len() as u64);
+        let (target_size, consumed_target) = decode_header_size(&delta[consumed..]).unwrap();
+        assert_eq!(target_size, target.len() as u64);
+        let mut out = vec![0; target.len()];
+        apply(base, &mut out, &delta[consumed + consumed_target..]).unwrap();
+        assert_eq!(out, target);
+        delta.len()
+    }
+
+    fn pseudo_random(len: usize, mut seed: u32) -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                seed as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn edge_cases() {
+        round_trip(b"", b"");
+        round_trip(b"", b"hello");
+        round_trip(b"hello", b"");
+        round_trip(b"short", b"short");
+    }
+
+    #[test]
+    fn similar_objects_produce_small_deltas() {
+        let base = pseudo_random(200_000, 42);
+        let mut target = base.clone();
+        target[1000] ^= 0xff;
+        target.splice(50_000..50_000, b"an insertion".iter().copied());
+        target.drain(150_000..150_100);
+        target.extend_from_slice(b"appended");
+        let size = round_trip(&base, &target);
+        assert!(size < 200, "copies of up to 64KB are used, got {size} bytes");
+
+        let unrelated = pseudo_random(2_000, 7);
+        assert!(round_trip(&base, &unrelated) > unrelated.len(), "inserts have overhead");
+    }
+
+    #[test]
+    fn max_size_is_respected() {
+        let base = pseudo_random(1_000, 1);
+        let target = pseudo_random(1_000, 2);
+        let mut out = Vec::new();
+        let index = Index::new(base.clone());
+        assert!(!index.encode(&target, 100, &mut out));
+        assert!(index.encode(&base, 100, &mut out));
+    }
+}
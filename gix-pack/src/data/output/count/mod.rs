@@ -1,4 +1,5 @@
 use gix_hash::ObjectId;
+use gix_object::bstr::BStr;
 
 use crate::data::output::Count;
 
@@ -35,10 +36,20 @@ impl Count {
         Count {
             id: oid.into(),
             entry_pack_location: PackLocation::LookedUp(location),
+            name_hash: 0,
+            no_delta: false,
         }
     }
 }
 
+/// Hash `name`, typically the file name of a tree entry, so that similar names produce close values to make
+/// them sort next to each other.
+///
+/// It's the same hash `git` uses for this purpose, which is mostly affected by the last 16 characters of `name`.
+pub fn name_hash(name: &BStr) -> u32 {
+    crate::name_hash(name)
+}
+
 #[path = "objects/mod.rs"]
 mod objects_impl;
 pub use objects_impl::{objects, objects_unthreaded};
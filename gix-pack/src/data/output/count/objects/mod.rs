@@ -2,6 +2,7 @@ use std::{cell::RefCell, sync::atomic::AtomicBool};
 
 use gix_features::parallel;
 use gix_hash::ObjectId;
+use gix_object::bstr::BStr;
 
 use crate::data::output;
 
@@ -43,6 +44,23 @@ pub fn objects<Find>(
 where
     Find: crate::Find + Send + Clone,
 {
+    let objects_ids = match input_object_expansion {
+        ObjectExpansion::AllReachable => match db.bitmap_index() {
+            Some(bitmaps) => {
+                let ids = objects_ids
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(Error::InputIteration)?;
+                if let Some(res) =
+                    expand::from_bitmaps(&db, &bitmaps, &ids, &objects.counter(), should_interrupt, true)?
+                {
+                    return Ok(res);
+                }
+                Box::new(ids.into_iter().map(Ok))
+            }
+            None => objects_ids,
+        },
+        _ => objects_ids,
+    };
     let lower_bound = objects_ids.size_hint().0;
     let (chunk_size, thread_limit, _) = parallel::optimize_chunk_size_and_thread_limit(
         chunk_size,
@@ -82,6 +100,7 @@ where
                     objects,
                     should_interrupt,
                     true, /*allow pack lookups*/
+                    None,
                 )
             }
         },
@@ -90,13 +109,32 @@ where
 }
 
 /// Like [`objects()`] but using a single thread only to mostly save on the otherwise required overhead.
+///
+/// If `no_delta` is set, it's called with the path of each blob found while traversing trees, relative to the root tree,
+/// to learn if it should be marked as [`no_delta`](output::Count::no_delta), typically because the `delta` attribute is unset
+/// for it. Note that objects found through [reachability bitmaps](crate::Find::bitmap_index()) don't have a path, just like in `git`.
 pub fn objects_unthreaded(
     db: &dyn crate::Find,
     object_ids: &mut dyn Iterator<Item = Result<ObjectId, Box<dyn std::error::Error + Send + Sync + 'static>>>,
     objects: &dyn gix_features::progress::Count,
     should_interrupt: &AtomicBool,
     input_object_expansion: ObjectExpansion,
+    no_delta: Option<&mut dyn FnMut(&BStr) -> bool>,
 ) -> Result<(Vec<output::Count>, Outcome), Error> {
+    let mut object_ids = object_ids;
+    let mut collected_ids;
+    if input_object_expansion == ObjectExpansion::AllReachable {
+        if let Some(bitmaps) = db.bitmap_index() {
+            let ids = object_ids
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Error::InputIteration)?;
+            if let Some(res) = expand::from_bitmaps(db, &bitmaps, &ids, &objects.counter(), should_interrupt, false)? {
+                return Ok(res);
+            }
+            collected_ids = ids.into_iter().map(Ok);
+            object_ids = &mut collected_ids;
+        }
+    }
     let seen_objs = RefCell::new(gix_hashtable::HashSet::default());
 
     let (mut buf1, mut buf2) = (Vec::new(), Vec::new());
@@ -110,6 +148,7 @@ pub fn objects_unthreaded(
         &objects.counter(),
         should_interrupt,
         false, /*allow pack lookups*/
+        no_delta,
     )
 }
 
@@ -120,7 +159,10 @@ mod expand {
     };
 
     use gix_hash::{ObjectId, oid};
-    use gix_object::{CommitRefIter, Data, TagRefIter};
+    use gix_object::{
+        CommitRefIter, Data, TagRefIter,
+        bstr::{BStr, BString},
+    };
 
     use super::{
         tree,
@@ -143,6 +185,7 @@ mod expand {
         objects: &gix_features::progress::AtomicStep,
         should_interrupt: &AtomicBool,
         allow_pack_lookups: bool,
+        mut no_delta: Option<&mut dyn FnMut(&BStr) -> bool>,
     ) -> Result<(Vec<output::Count>, Outcome), Error> {
         use ObjectExpansion::*;
 
@@ -150,8 +193,8 @@ mod expand {
         let mut tree_traversal_state = gix_traverse::tree::breadthfirst::State::default();
         let mut tree_diff_state = gix_diff::tree::State::default();
         let mut parent_commit_ids = Vec::new();
-        let mut traverse_delegate = tree::traverse::AllUnseen::new(seen_objs);
-        let mut changes_delegate = tree::changes::AllNew::new(seen_objs);
+        let mut traverse_delegate = tree::traverse::AllUnseen::new(seen_objs, no_delta.is_some());
+        let mut changes_delegate = tree::changes::AllNew::new(seen_objs, no_delta.is_some());
         let mut outcome = Outcome::default();
 
         let stats = &mut outcome;
@@ -220,6 +263,8 @@ mod expand {
                                     out = objects.dissolve(stats);
                                     &traverse_delegate.non_trees
                                 } else {
+                                    // Objects are only recorded once, so the additions compared to all parents are kept.
+                                    changes_delegate.clear();
                                     for commit_id in &parent_commit_ids {
                                         let parent_tree_id = {
                                             let (parent_commit_obj, location) = db.find(commit_id, buf2)?;
@@ -251,7 +296,6 @@ mod expand {
                                             )
                                         };
 
-                                        changes_delegate.clear();
                                         let objects = CountingObjects::new(db);
                                         gix_diff::tree(
                                             parent_tree,
@@ -265,8 +309,17 @@ mod expand {
                                     }
                                     &changes_delegate.objects
                                 };
-                                for id in objects_ref.iter() {
-                                    out.push(id_to_count(db, buf2, id, objects, stats, allow_pack_lookups));
+                                for (id, name_hash, path) in objects_ref.iter() {
+                                    out.push(id_to_count(
+                                        db,
+                                        buf2,
+                                        id,
+                                        *name_hash,
+                                        is_no_delta(&mut no_delta, path.as_ref()),
+                                        objects,
+                                        stats,
+                                        allow_pack_lookups,
+                                    ));
                                 }
                                 break;
                             }
@@ -281,21 +334,18 @@ mod expand {
                         push_obj_count_unique(&mut out, seen_objs, &id, obj.1.clone(), objects, stats, false);
                         match obj.0.kind {
                             Tree => {
-                                traverse_delegate.clear();
-                                {
-                                    let objects = ExpandedCountingObjects::new(db, out, objects);
-                                    gix_traverse::tree::breadthfirst(
-                                        gix_object::TreeRefIter::from_bytes(obj.0.data, obj.0.object_hash),
-                                        &mut tree_traversal_state,
-                                        &objects,
-                                        &mut traverse_delegate,
-                                    )
-                                    .map_err(Error::TreeTraverse)?;
-                                    out = objects.dissolve(stats);
-                                }
-                                for id in &traverse_delegate.non_trees {
-                                    out.push(id_to_count(db, buf1, id, objects, stats, allow_pack_lookups));
-                                }
+                                out = push_tree_contents(
+                                    db,
+                                    gix_object::TreeRefIter::from_bytes(obj.0.data, obj.0.object_hash),
+                                    out,
+                                    &mut traverse_delegate,
+                                    &mut tree_traversal_state,
+                                    buf2,
+                                    objects,
+                                    stats,
+                                    allow_pack_lookups,
+                                    &mut no_delta,
+                                )?;
                                 break;
                             }
                             Commit => {
@@ -318,7 +368,88 @@ mod expand {
                         }
                     }
                 }
-                AsIs => push_obj_count_unique(&mut out, seen_objs, &id, location, objects, stats, false),
+                AllReachable => {
+                    use gix_object::Kind::*;
+                    let mut id = id;
+                    let mut obj = (obj, location);
+                    loop {
+                        let inserted =
+                            push_obj_count_unique(&mut out, seen_objs, &id, obj.1.clone(), objects, stats, false);
+                        match obj.0.kind {
+                            Tree => {
+                                if inserted {
+                                    out = push_tree_contents(
+                                        db,
+                                        gix_object::TreeRefIter::from_bytes(obj.0.data, obj.0.object_hash),
+                                        out,
+                                        &mut traverse_delegate,
+                                        &mut tree_traversal_state,
+                                        buf2,
+                                        objects,
+                                        stats,
+                                        allow_pack_lookups,
+                                        &mut no_delta,
+                                    )?;
+                                }
+                                break;
+                            }
+                            Commit => {
+                                // Commits are only walked by whoever saw them first, which also walks their ancestors.
+                                if !inserted {
+                                    break;
+                                }
+                                parent_commit_ids.clear();
+                                parent_commit_ids.push(id);
+                                let mut is_input = true;
+                                while let Some(commit_id) = parent_commit_ids.pop() {
+                                    let (commit, location) = db.find(&commit_id, buf1)?;
+                                    if !is_input {
+                                        objects.fetch_add(1, Ordering::Relaxed);
+                                        stats.decoded_objects += 1;
+                                        stats.expanded_objects += 1;
+                                        out.push(output::Count::from_data(commit_id, location));
+                                    }
+                                    is_input = false;
+                                    let mut commit_iter = CommitRefIter::from_bytes(commit.data, commit.object_hash);
+                                    let tree_id = commit_iter.tree_id().map_err(Error::CommitDecode)?;
+                                    parent_commit_ids
+                                        .extend(commit_iter.parent_ids().filter(|id| seen_objs.insert(*id)));
+
+                                    let (tree, location) = db.find(&tree_id, buf1)?;
+                                    if push_obj_count_unique(
+                                        &mut out, seen_objs, &tree_id, location, objects, stats, true,
+                                    ) {
+                                        out = push_tree_contents(
+                                            db,
+                                            gix_object::TreeRefIter::from_bytes(tree.data, tree.object_hash),
+                                            out,
+                                            &mut traverse_delegate,
+                                            &mut tree_traversal_state,
+                                            buf2,
+                                            objects,
+                                            stats,
+                                            allow_pack_lookups,
+                                            &mut no_delta,
+                                        )?;
+                                    }
+                                }
+                                break;
+                            }
+                            Blob => break,
+                            Tag => {
+                                id = TagRefIter::from_bytes(obj.0.data, obj.0.object_hash)
+                                    .target_id()
+                                    .expect("every tag has a target");
+                                stats.expanded_objects += 1;
+                                obj = db.find(&id, buf1)?;
+                                continue;
+                            }
+                        }
+                    }
+                }
+                AsIs => {
+                    push_obj_count_unique(&mut out, seen_objs, &id, location, objects, stats, false);
+                }
             }
         }
         outcome.total_objects = out.len();
@@ -334,7 +465,7 @@ mod expand {
         objects: &gix_features::progress::AtomicStep,
         statistics: &mut Outcome,
         count_expanded: bool,
-    ) {
+    ) -> bool {
         let inserted = all_seen.insert(id.to_owned());
         if inserted {
             objects.fetch_add(1, Ordering::Relaxed);
@@ -344,13 +475,103 @@ mod expand {
             }
             out.push(output::Count::from_data(id, location));
         }
+        inserted
+    }
+
+    /// Push all objects contained in `tree` that weren't seen yet to `out`, and return it.
+    #[expect(clippy::too_many_arguments)]
+    fn push_tree_contents<H: util::InsertImmutable>(
+        db: &dyn crate::Find,
+        tree: gix_object::TreeRefIter<'_>,
+        out: Vec<output::Count>,
+        traverse_delegate: &mut tree::traverse::AllUnseen<'_, H>,
+        tree_traversal_state: &mut gix_traverse::tree::breadthfirst::State,
+        buf: &mut Vec<u8>,
+        objects: &gix_features::progress::AtomicStep,
+        stats: &mut Outcome,
+        allow_pack_lookups: bool,
+        no_delta: &mut Option<&mut dyn FnMut(&BStr) -> bool>,
+    ) -> Result<Vec<output::Count>, Error> {
+        traverse_delegate.clear();
+        let expanded_objects = ExpandedCountingObjects::new(db, out, objects);
+        gix_traverse::tree::breadthfirst(tree, tree_traversal_state, &expanded_objects, traverse_delegate)
+            .map_err(Error::TreeTraverse)?;
+        let mut out = expanded_objects.dissolve(stats);
+        for (id, name_hash, path) in &traverse_delegate.non_trees {
+            out.push(id_to_count(
+                db,
+                buf,
+                id,
+                *name_hash,
+                is_no_delta(no_delta, path.as_ref()),
+                objects,
+                stats,
+                allow_pack_lookups,
+            ));
+        }
+        Ok(out)
+    }
+
+    /// Ask `no_delta` if the object at `path` shouldn't be delta-compressed, if both are available.
+    fn is_no_delta(no_delta: &mut Option<&mut dyn FnMut(&BStr) -> bool>, path: Option<&BString>) -> bool {
+        match (no_delta, path) {
+            (Some(no_delta), Some(path)) => no_delta(path.as_ref()),
+            _ => false,
+        }
+    }
+
+    /// Count all objects reachable from `ids` using `bitmaps`, or return `None` if the bitmaps don't cover all of them.
+    pub fn from_bitmaps(
+        db: &dyn crate::Find,
+        bitmaps: &crate::bitmap::Index,
+        ids: &[ObjectId],
+        objects: &gix_features::progress::AtomicStep,
+        should_interrupt: &AtomicBool,
+        allow_pack_lookups: bool,
+    ) -> Result<Option<(Vec<output::Count>, Outcome)>, Error> {
+        let traversal_objects = CountingObjects::new(db);
+        let Some(reachable) = bitmaps
+            .reachable(ids.iter().copied(), &traversal_objects)
+            .map_err(Error::Bitmap)?
+        else {
+            return Ok(None);
+        };
+        let mut outcome = Outcome {
+            input_objects: ids.len(),
+            decoded_objects: traversal_objects.into_count(),
+            ..Default::default()
+        };
+
+        let mut out = Vec::with_capacity(reachable.len());
+        let mut buf = Vec::new();
+        for bit in reachable.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            out.push(id_to_count(
+                db,
+                &mut buf,
+                bitmaps.id_at(bit),
+                bitmaps.name_hash_at(bit).unwrap_or_default(),
+                false,
+                objects,
+                &mut outcome,
+                allow_pack_lookups,
+            ));
+        }
+        outcome.expanded_objects = out.len().saturating_sub(outcome.input_objects);
+        outcome.total_objects = out.len();
+        Ok(Some((out, outcome)))
     }
 
     #[inline]
+    #[expect(clippy::too_many_arguments)]
     fn id_to_count(
         db: &dyn crate::Find,
         buf: &mut Vec<u8>,
         id: &oid,
+        name_hash: u32,
+        no_delta: bool,
         objects: &gix_features::progress::AtomicStep,
         statistics: &mut Outcome,
         allow_pack_lookups: bool,
@@ -364,6 +585,8 @@ mod expand {
             } else {
                 PackLocation::NotLookedUp
             },
+            name_hash,
+            no_delta,
         }
     }
 
@@ -1,3 +1,71 @@
+/// The full path of the entry that is currently visited, which is only tracked if paths are needed.
+#[derive(Default)]
+pub struct Path {
+    enabled: bool,
+    current: gix_object::bstr::BString,
+    deque: std::collections::VecDeque<gix_object::bstr::BString>,
+}
+
+impl Path {
+    pub fn new(enabled: bool) -> Self {
+        Path {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// Return the current path, or `None` if paths aren't tracked.
+    pub fn current(&self) -> Option<gix_object::bstr::BString> {
+        self.enabled.then(|| self.current.clone())
+    }
+
+    pub fn clear(&mut self) {
+        self.current.clear();
+        self.deque.clear();
+    }
+
+    fn pop_front_and_set_current(&mut self) {
+        if self.enabled {
+            self.current = self
+                .deque
+                .pop_front()
+                .expect("every call is matched with push_back_component");
+        }
+    }
+
+    fn pop_back_and_set_current(&mut self) {
+        if self.enabled {
+            self.current = self.deque.pop_back().unwrap_or_default();
+        }
+    }
+
+    fn push_back_component(&mut self, component: &gix_object::bstr::BStr) {
+        if self.enabled {
+            self.push(component);
+            self.deque.push_back(self.current.clone());
+        }
+    }
+
+    fn push(&mut self, component: &gix_object::bstr::BStr) {
+        use gix_object::bstr::ByteVec;
+        if !self.enabled || component.is_empty() {
+            return;
+        }
+        if !self.current.is_empty() {
+            self.current.push(b'/');
+        }
+        self.current.push_str(component);
+    }
+
+    fn pop(&mut self) {
+        use gix_object::bstr::ByteSlice;
+        if self.enabled {
+            let end = self.current.rfind_byte(b'/').unwrap_or(0);
+            self.current.truncate(end);
+        }
+    }
+}
+
 pub mod changes {
     use gix_diff::tree::{
         Visit,
@@ -6,25 +74,35 @@ pub mod changes {
     use gix_hash::ObjectId;
     use gix_object::bstr::BStr;
 
-    use crate::data::output::count::objects_impl::util::InsertImmutable;
+    use super::Path;
+    use crate::data::output::count::{name_hash, objects_impl::util::InsertImmutable};
 
     pub struct AllNew<'a, H> {
-        pub objects: Vec<ObjectId>,
+        /// The ids of all new objects along with the [hash of their name](name_hash()) and their path, if tracked
+        /// and if it's not a tree.
+        pub objects: Vec<(ObjectId, u32, Option<gix_object::bstr::BString>)>,
         all_seen: &'a H,
+        /// The hash of the name of the entry that is visited next.
+        current_name_hash: u32,
+        path: Path,
     }
 
     impl<'a, H> AllNew<'a, H>
     where
         H: InsertImmutable,
     {
-        pub fn new(all_seen: &'a H) -> Self {
+        /// Create a new instance which also records the path of each object if `track_paths` is `true`.
+        pub fn new(all_seen: &'a H, track_paths: bool) -> Self {
             AllNew {
                 objects: Default::default(),
                 all_seen,
+                current_name_hash: 0,
+                path: Path::new(track_paths),
             }
         }
         pub fn clear(&mut self) {
             self.objects.clear();
+            self.path.clear();
         }
     }
 
@@ -32,13 +110,22 @@ pub mod changes {
     where
         H: InsertImmutable,
     {
-        fn pop_front_tracked_path_and_set_current(&mut self) {}
+        fn pop_front_tracked_path_and_set_current(&mut self) {
+            self.path.pop_front_and_set_current();
+        }
 
-        fn push_back_tracked_path_component(&mut self, _component: &BStr) {}
+        fn push_back_tracked_path_component(&mut self, component: &BStr) {
+            self.path.push_back_component(component);
+        }
 
-        fn push_path_component(&mut self, _component: &BStr) {}
+        fn push_path_component(&mut self, component: &BStr) {
+            self.current_name_hash = name_hash(component);
+            self.path.push(component);
+        }
 
-        fn pop_path_component(&mut self) {}
+        fn pop_path_component(&mut self) {
+            self.path.pop();
+        }
 
         fn visit(&mut self, change: Change) -> Action {
             match change {
@@ -53,7 +140,12 @@ pub mod changes {
                     }
                     let inserted = self.all_seen.insert(oid);
                     if inserted {
-                        self.objects.push(oid);
+                        let path = if entry_mode.is_tree() {
+                            None
+                        } else {
+                            self.path.current()
+                        };
+                        self.objects.push((oid, self.current_name_hash, path));
                     }
                 }
                 Change::Deletion { .. } => {}
@@ -68,25 +160,32 @@ pub mod traverse {
     use gix_object::{bstr::BStr, tree::EntryRef};
     use gix_traverse::tree::{Visit, visit::Action};
 
-    use crate::data::output::count::objects_impl::util::InsertImmutable;
+    use super::Path;
+    use crate::data::output::count::{name_hash, objects_impl::util::InsertImmutable};
 
     pub struct AllUnseen<'a, H> {
-        pub non_trees: Vec<ObjectId>,
+        /// The ids of all unseen objects that aren't trees along with the [hash of their name](name_hash())
+        /// and their path, if tracked.
+        pub non_trees: Vec<(ObjectId, u32, Option<gix_object::bstr::BString>)>,
         all_seen: &'a H,
+        path: Path,
     }
 
     impl<'a, H> AllUnseen<'a, H>
     where
         H: InsertImmutable,
     {
-        pub fn new(all_seen: &'a H) -> Self {
+        /// Create a new instance which also records the path of each object if `track_paths` is `true`.
+        pub fn new(all_seen: &'a H, track_paths: bool) -> Self {
             AllUnseen {
                 non_trees: Default::default(),
                 all_seen,
+                path: Path::new(track_paths),
             }
         }
         pub fn clear(&mut self) {
             self.non_trees.clear();
+            self.path.clear();
         }
     }
 
@@ -94,15 +193,25 @@ pub mod traverse {
     where
         H: InsertImmutable,
     {
-        fn pop_back_tracked_path_and_set_current(&mut self) {}
+        fn pop_back_tracked_path_and_set_current(&mut self) {
+            self.path.pop_back_and_set_current();
+        }
 
-        fn pop_front_tracked_path_and_set_current(&mut self) {}
+        fn pop_front_tracked_path_and_set_current(&mut self) {
+            self.path.pop_front_and_set_current();
+        }
 
-        fn push_back_tracked_path_component(&mut self, _component: &BStr) {}
+        fn push_back_tracked_path_component(&mut self, component: &BStr) {
+            self.path.push_back_component(component);
+        }
 
-        fn push_path_component(&mut self, _component: &BStr) {}
+        fn push_path_component(&mut self, component: &BStr) {
+            self.path.push(component);
+        }
 
-        fn pop_path_component(&mut self) {}
+        fn pop_path_component(&mut self) {
+            self.path.pop();
+        }
 
         fn visit_tree(&mut self, entry: &EntryRef<'_>) -> Action {
             let inserted = self.all_seen.insert(entry.oid.to_owned());
@@ -119,7 +228,8 @@ pub mod traverse {
             }
             let inserted = self.all_seen.insert(entry.oid.to_owned());
             if inserted {
-                self.non_trees.push(entry.oid.to_owned());
+                self.non_trees
+                    .push((entry.oid.to_owned(), name_hash(entry.filename), self.path.current()));
             }
             std::ops::ControlFlow::Continue(true)
         }
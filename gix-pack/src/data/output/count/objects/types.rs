@@ -51,6 +51,14 @@ pub enum ObjectExpansion {
     ///
     /// This mode is useful to build a pack containing only new objects compared to a previous state.
     TreeAdditionsComparedToAncestor,
+    /// If the input object is a commit, turn it and all of its ancestors into pack entries, along with their trees and
+    /// everything reachable from them, just like `git rev-list --objects` would.
+    /// Otherwise, the same as [`TreeContents`][ObjectExpansion::TreeContents].
+    ///
+    /// This mode is useful if all objects reachable from a set of tips should be added, as in cloning a repository.
+    /// If the database provides [reachability bitmaps](crate::Find::bitmap_index()), they are used to avoid traversing
+    /// most commits and trees, which makes counting nearly instant.
+    AllReachable,
 }
 
 /// Configuration options for the pack generation functions provided in [this module][crate::data::output].
@@ -91,6 +99,8 @@ pub enum Error {
     TreeTraverse(gix_traverse::tree::breadthfirst::Error),
     #[error(transparent)]
     TreeChanges(gix_diff::tree::Error),
+    #[error(transparent)]
+    Bitmap(crate::bitmap::reachable::Error),
     #[error("Operation interrupted")]
     Interrupted,
 }
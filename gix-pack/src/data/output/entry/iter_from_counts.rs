@@ -1,3 +1,5 @@
+mod delta;
+
 pub(crate) mod function {
     use std::{cmp::Ordering, sync::Arc};
 
@@ -10,7 +12,7 @@ pub(crate) mod function {
         },
     };
 
-    use super::{Error, Mode, Options, Outcome, ProgressId, reduce, util};
+    use super::{Error, Mode, Options, Outcome, ProgressId, delta, reduce, util};
     use crate::data::output;
 
     /// Given a known list of object `counts`, calculate entries ready to be put into a data pack.
@@ -25,7 +27,8 @@ pub(crate) mod function {
     /// * `progress`
     ///   * a way to obtain progress information
     /// * `options`
-    ///   * more configuration
+    ///   * more configuration, including the [`Mode`] which determines if deltas are searched for objects that
+    ///     can't be copied from existing packs.
     ///
     /// _Returns_ the checksum of the pack
     ///
@@ -46,19 +49,22 @@ pub(crate) mod function {
         mut counts: Vec<output::Count>,
         db: Find,
         mut progress: Box<dyn DynNestedProgress + 'static>,
-        Options {
+        options: Options,
+    ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error>>
+    + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error>>
+    where
+        Find: crate::Find + Send + Clone + 'static,
+    {
+        let Options {
             version,
             mode,
             allow_thin_pack,
             thread_limit,
             chunk_size,
             compression,
-        }: Options,
-    ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error>>
-    + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error>>
-    where
-        Find: crate::Find + Send + Clone + 'static,
-    {
+            window: _,
+            depth: _,
+        } = options;
         assert!(
             matches!(version, crate::data::Version::V2),
             "currently we can only write version 2"
@@ -99,7 +105,7 @@ pub(crate) mod function {
             progress.lock().show_throughput(start);
         }
         let counts_range_by_pack_id = match mode {
-            Mode::PackCopyAndBaseObjects => {
+            Mode::PackCopyAndBaseObjects | Mode::DeltaCompression => {
                 let mut progress = progress.add_child_with_id("sorting".into(), ProgressId::SortEntries.into());
                 progress.init(Some(counts.len()), gix_features::progress::count("counts"));
                 let start = std::time::Instant::now();
@@ -136,6 +142,17 @@ pub(crate) mod function {
             }
         };
 
+        let plans = match mode {
+            Mode::PackCopyAndBaseObjects => None,
+            Mode::DeltaCompression => Some(Arc::new(delta::plan(
+                &mut counts,
+                &counts_range_by_pack_id,
+                &db,
+                options,
+                &mut *progress,
+            ))),
+        };
+
         let counts = Arc::new(counts);
         let progress = Arc::new(parking_lot::Mutex::new(progress));
         let chunks = util::ChunkRanges::new(chunk_size, counts.len());
@@ -158,12 +175,24 @@ pub(crate) mod function {
                 let counts = Arc::clone(&counts);
                 move |(chunk_id, chunk_range): (SequenceId, std::ops::Range<usize>), (buf, progress)| {
                     let mut out = Vec::new();
-                    let chunk = &counts[chunk_range];
+                    let chunk = &counts[chunk_range.clone()];
                     let mut stats = Outcome::default();
                     let mut pack_offsets_to_id = None;
                     progress.init(Some(chunk.len()), gix_features::progress::count("objects"));
 
-                    for count in chunk.iter() {
+                    for (index, count) in chunk_range.zip(chunk) {
+                        if let Some(plans) = &plans {
+                            out.push(util::entry_from_plan(
+                                &plans[index],
+                                count,
+                                &db,
+                                buf,
+                                compression,
+                                &mut stats,
+                            )?);
+                            progress.inc();
+                            continue;
+                        }
                         out.push(match count
                             .entry_pack_location
                             .as_ref()
@@ -210,30 +239,12 @@ pub(crate) mod function {
                                 match entry {
                                     Some(entry) => {
                                         stats.objects_copied_from_pack += 1;
-                                        entry
+                                        entry.map_err(Error::from)
                                     }
-                                    None => match db.try_find(&count.id, buf).map_err(Error::Find)? {
-                                        Some((obj, _location)) => {
-                                            stats.decoded_and_recompressed_objects += 1;
-                                            output::Entry::from_data(count, &obj, compression)
-                                        }
-                                        None => {
-                                            stats.missing_objects += 1;
-                                            Ok(output::Entry::invalid())
-                                        }
-                                    },
+                                    None => util::base_entry(count, &db, buf, compression, &mut stats),
                                 }
                             }
-                            None => match db.try_find(&count.id, buf).map_err(Error::Find)? {
-                                Some((obj, _location)) => {
-                                    stats.decoded_and_recompressed_objects += 1;
-                                    output::Entry::from_data(count, &obj, compression)
-                                }
-                                None => {
-                                    stats.missing_objects += 1;
-                                    Ok(output::Entry::invalid())
-                                }
-                            },
+                            None => util::base_entry(count, &db, buf, compression, &mut stats),
                         }?);
                         progress.inc();
                     }
@@ -246,6 +257,61 @@ pub(crate) mod function {
 }
 
 mod util {
+    use super::{Error, Outcome, delta::Plan};
+    use crate::data::output;
+
+    /// Decode the object of `count` and turn it into a base object entry, or into an invalid entry if it's missing.
+    pub fn base_entry(
+        count: &output::Count,
+        db: &impl crate::Find,
+        buf: &mut Vec<u8>,
+        compression: gix_zlib::Compression,
+        stats: &mut Outcome,
+    ) -> Result<output::Entry, Error> {
+        Ok(match db.try_find(&count.id, buf).map_err(Error::Find)? {
+            Some((obj, _location)) => {
+                stats.decoded_and_recompressed_objects += 1;
+                output::Entry::from_data(count, &obj, compression)?
+            }
+            None => {
+                stats.missing_objects += 1;
+                output::Entry::invalid()
+            }
+        })
+    }
+
+    /// Create the entry for `count` according to its `plan`.
+    pub fn entry_from_plan(
+        plan: &Plan,
+        count: &output::Count,
+        db: &impl crate::Find,
+        buf: &mut Vec<u8>,
+        compression: gix_zlib::Compression,
+        stats: &mut Outcome,
+    ) -> Result<output::Entry, Error> {
+        Ok(match plan {
+            Plan::Base => base_entry(count, db, buf, compression, stats)?,
+            Plan::Copy(kind) => match count
+                .entry_pack_location
+                .as_ref()
+                .and_then(|location| db.entry_by_location(location))
+            {
+                Some(entry) => {
+                    stats.objects_copied_from_pack += 1;
+                    if matches!(kind, output::entry::Kind::DeltaOid { .. }) {
+                        stats.ref_delta_objects += 1;
+                    }
+                    output::Entry::from_pack_entry_with_kind(entry, count, *kind)?
+                }
+                None => base_entry(count, db, buf, compression, stats)?,
+            },
+            Plan::Delta { base_index, data } => {
+                stats.delta_compressed_objects += 1;
+                output::Entry::from_delta(count, *base_index, data, compression)?
+            }
+        })
+    }
+
     #[derive(Clone)]
     pub struct ChunkRanges {
         cursor: usize,
@@ -336,6 +402,9 @@ mod types {
         pub objects_copied_from_pack: usize,
         /// The amount of objects that ref to their base as ref-delta, an indication for a thin back being created.
         pub ref_delta_objects: usize,
+        /// The amount of objects that were stored as delta against a similar object found while
+        /// [searching for deltas](Mode::DeltaCompression).
+        pub delta_compressed_objects: usize,
     }
 
     impl Outcome {
@@ -346,12 +415,14 @@ mod types {
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                delta_compressed_objects,
             }: Self,
         ) {
             self.decoded_and_recompressed_objects += decoded_objects;
             self.missing_objects += missing_objects;
             self.objects_copied_from_pack += objects_copied_from_pack;
             self.ref_delta_objects += ref_delta_objects;
+            self.delta_compressed_objects += delta_compressed_objects;
         }
     }
 
@@ -364,6 +435,15 @@ mod types {
         /// from existing pack compression and spending the smallest possible time on compressing unpacked objects at
         /// the cost of bandwidth.
         PackCopyAndBaseObjects,
+        /// Copy base objects and deltas from packs like [`PackCopyAndBaseObjects`](Mode::PackCopyAndBaseObjects), but try
+        /// to store all other objects as deltas against similar objects in the pack.
+        ///
+        /// For that, objects are sorted by type, [name](crate::data::output::Count::name_hash) and size, to find the smallest
+        /// delta against the objects preceding them within a [window](Options::window).
+        /// Objects marked with [`no_delta`](crate::data::output::Count::no_delta) are left as they are.
+        ///
+        /// This takes more time, but produces much smaller packs, particularly from loose objects.
+        DeltaCompression,
     }
 
     /// Configuration options for the pack generation functions provided in [`iter_from_counts()`][crate::data::output::entry::iter_from_counts()].
@@ -393,6 +473,16 @@ mod types {
         /// also the default that `git` uses when writing packs, unless configured otherwise
         /// with `pack.compression`.
         pub compression: gix_zlib::Compression,
+        /// The amount of objects to consider when searching for the best delta for an object, including the object itself,
+        /// with values below 2 disabling the search.
+        ///
+        /// It's only used with [`Mode::DeltaCompression`] and is the equivalent of `pack.window`, which defaults to 10.
+        pub window: usize,
+        /// The maximum length of chains of deltas, with `0` disabling the search for deltas.
+        /// Reused deltas in longer chains are stored as base objects or new deltas instead.
+        ///
+        /// It's only used with [`Mode::DeltaCompression`] and is the equivalent of `pack.depth`, which defaults to 50.
+        pub depth: usize,
     }
 
     impl Default for Options {
@@ -404,6 +494,8 @@ mod types {
                 chunk_size: 10,
                 version: Default::default(),
                 compression: gix_zlib::Compression::DEFAULT,
+                window: 10,
+                depth: 50,
             }
         }
     }
@@ -427,6 +519,8 @@ mod types {
         ResolveCounts,
         /// Layout pack entries for placement into a pack (by pack-id and by offset).
         SortEntries,
+        /// Search deltas for objects that would otherwise be stored as base objects.
+        FindDeltas,
     }
 
     impl From<ProgressId> for gix_features::progress::Id {
@@ -434,6 +528,7 @@ mod types {
             match v {
                 ProgressId::ResolveCounts => *b"ECRC",
                 ProgressId::SortEntries => *b"ECSE",
+                ProgressId::FindDeltas => *b"ECFD",
             }
         }
     }
@@ -0,0 +1,404 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use gix_features::{
+    parallel,
+    progress::{
+        Progress,
+        prodash::{Count, DynNestedProgress},
+    },
+};
+
+use super::{Options, ProgressId};
+use crate::data::{
+    self,
+    output::{self, entry::Kind},
+};
+
+/// The way a counted object is turned into an entry.
+pub enum Plan {
+    /// Decode the object and deflate it as base object.
+    Base,
+    /// Copy the pack entry of the object as is, which is of the given kind.
+    Copy(Kind),
+    /// Store the object as delta against the object at `base_index`, using the given delta instructions.
+    Delta { base_index: usize, data: Vec<u8> },
+}
+
+impl Plan {
+    fn base_index_mut(&mut self) -> Option<&mut usize> {
+        match self {
+            Plan::Copy(Kind::DeltaRef { object_index }) => Some(object_index),
+            Plan::Delta { base_index, .. } => Some(base_index),
+            Plan::Copy(_) | Plan::Base => None,
+        }
+    }
+}
+
+/// An object which could be stored as delta, or serve as base for other deltas.
+struct Candidate {
+    /// The index into the list of counts.
+    index: usize,
+    kind: gix_object::Kind,
+    size: u64,
+    name_hash: u32,
+    /// The length of the longest chain of reused deltas based on this object, which becomes longer if it's stored as delta itself.
+    height: usize,
+}
+
+/// A delta found for the object at `target`.
+struct Found {
+    target: usize,
+    base: usize,
+    delta: Vec<u8>,
+}
+
+/// Objects smaller than this aren't worth storing as delta, which is what `git` does as well.
+const MIN_SIZE: u64 = 50;
+/// The smallest amount of candidates to handle per thread, as each list of candidates starts with an empty window.
+const MIN_CANDIDATES_PER_CHUNK: usize = 1000;
+
+/// Decide how each of the `counts` should be written, and search deltas for all objects which would otherwise be written
+/// as base objects.
+///
+/// `counts` are expected to be sorted by pack and offset, with `counts_range_by_pack_id` pointing to each pack's counts.
+/// Afterwards, `counts` are reordered so that each delta comes after its base, and the returned plans correspond to them.
+/// No delta chain will be longer than `depth`, including the chains of reused deltas.
+pub fn plan<Find>(
+    counts: &mut Vec<output::Count>,
+    counts_range_by_pack_id: &[(u32, std::ops::Range<usize>)],
+    db: &Find,
+    Options {
+        thread_limit,
+        allow_thin_pack,
+        version,
+        window,
+        depth,
+        ..
+    }: Options,
+    progress: &mut dyn DynNestedProgress,
+) -> Vec<Plan>
+where
+    Find: crate::Find + Send + Clone,
+{
+    let mut plans = Vec::with_capacity(counts.len());
+    let mut candidates = Vec::new();
+    {
+        let mut buf = Vec::new();
+        let mut pack_offsets_to_id = None;
+        for (index, count) in counts.iter().enumerate() {
+            let mut decoded_candidate = |buf: &mut Vec<u8>| {
+                if let Ok(Some((obj, _location))) = db.try_find(&count.id, buf) {
+                    candidates.push(Candidate {
+                        index,
+                        kind: obj.kind,
+                        size: obj.data.len() as u64,
+                        name_hash: count.name_hash,
+                        height: 0,
+                    });
+                }
+                // Errors and missing objects are reported when writing the entry.
+                Plan::Base
+            };
+            let Some((location, entry)) = count
+                .entry_pack_location
+                .as_ref()
+                .and_then(|location| db.entry_by_location(location).map(|entry| (location, entry)))
+                .filter(|(_, entry)| entry.version == version)
+            else {
+                plans.push(decoded_candidate(&mut buf));
+                continue;
+            };
+            let pack_offset_must_be_zero = 0;
+            let Ok(pack_entry) = data::Entry::from_bytes(&entry.data, pack_offset_must_be_zero, count.id.kind()) else {
+                plans.push(Plan::Base);
+                continue;
+            };
+            use crate::data::entry::Header;
+            let kind = match pack_entry.header {
+                Header::Commit => gix_object::Kind::Commit,
+                Header::Tree => gix_object::Kind::Tree,
+                Header::Blob => gix_object::Kind::Blob,
+                Header::Tag => gix_object::Kind::Tag,
+                Header::OfsDelta { base_distance } => {
+                    let base_offset = location
+                        .pack_offset
+                        .checked_sub(base_distance)
+                        .expect("pack-offset - distance is firmly within the pack");
+                    let pack_range = counts_range_by_pack_id[counts_range_by_pack_id
+                        .binary_search_by_key(&location.pack_id, |e| e.0)
+                        .expect("pack-id always present")]
+                    .1
+                    .clone();
+                    let base_index = counts[pack_range.clone()]
+                        .binary_search_by(|e| {
+                            e.entry_pack_location
+                                .as_ref()
+                                .expect("packed")
+                                .pack_offset
+                                .cmp(&base_offset)
+                        })
+                        .ok()
+                        .map(|idx| pack_range.start + idx);
+                    let plan = match base_index {
+                        Some(object_index) => Some(Plan::Copy(Kind::DeltaRef { object_index })),
+                        None if allow_thin_pack => {
+                            if pack_offsets_to_id
+                                .as_ref()
+                                .is_none_or(|(pack_id, _)| *pack_id != location.pack_id)
+                            {
+                                pack_offsets_to_id = Some((
+                                    location.pack_id,
+                                    db.pack_offsets_and_oid(location.pack_id)
+                                        .map(|mut v| {
+                                            v.sort_by_key(|e| e.0);
+                                            v
+                                        })
+                                        .expect("pack used for counts is still available"),
+                                ));
+                            }
+                            let (_, cache) = pack_offsets_to_id.as_ref().expect("just set");
+                            cache
+                                .binary_search_by_key(&base_offset, |e| e.0)
+                                .ok()
+                                .map(|idx| Plan::Copy(Kind::DeltaOid { id: cache[idx].1 }))
+                        }
+                        None => None,
+                    };
+                    plans.push(plan.unwrap_or_else(|| decoded_candidate(&mut buf)));
+                    continue;
+                }
+                Header::RefDelta { .. } => {
+                    plans.push(decoded_candidate(&mut buf));
+                    continue;
+                }
+            };
+            candidates.push(Candidate {
+                index,
+                kind,
+                size: pack_entry.decompressed_size,
+                name_hash: count.name_hash,
+                height: 0,
+            });
+            plans.push(Plan::Copy(Kind::Base(kind)));
+        }
+
+        // Reused deltas keep their chains, so the ones that would be deeper than `depth` are decoded and searched like
+        // base objects instead. Their bases are always handled first as they are in the same pack, at a lower offset.
+        let mut reused_depth = vec![0; plans.len()];
+        for index in 0..plans.len() {
+            let chain_depth = match plans[index] {
+                Plan::Copy(Kind::DeltaRef { object_index }) => reused_depth[object_index] + 1,
+                Plan::Copy(Kind::DeltaOid { .. }) => 1,
+                _ => continue,
+            };
+            if chain_depth <= depth {
+                reused_depth[index] = chain_depth;
+                continue;
+            }
+            if let Ok(Some((obj, _location))) = db.try_find(&counts[index].id, &mut buf) {
+                candidates.push(Candidate {
+                    index,
+                    kind: obj.kind,
+                    size: obj.data.len() as u64,
+                    name_hash: counts[index].name_hash,
+                    height: 0,
+                });
+            }
+            plans[index] = Plan::Base;
+        }
+        let mut height = vec![0; plans.len()];
+        for (index, chain_depth) in reused_depth.into_iter().enumerate().filter(|(_, depth)| *depth > 0) {
+            let mut base = index;
+            while let Plan::Copy(Kind::DeltaRef { object_index }) = plans[base] {
+                base = object_index;
+            }
+            height[base] = height[base].max(chain_depth);
+        }
+        for candidate in &mut candidates {
+            candidate.height = height[candidate.index];
+        }
+    }
+
+    candidates.retain(|c| c.size >= MIN_SIZE && !counts[c.index].no_delta);
+    // Similar objects are likely to have the same type and name, and larger objects are preferred as bases.
+    candidates.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then(a.name_hash.cmp(&b.name_hash))
+            .then(b.size.cmp(&a.size))
+            .then(a.index.cmp(&b.index))
+    });
+    if window > 1 && depth > 0 && !candidates.is_empty() {
+        let progress = Arc::new(parking_lot::Mutex::new(
+            progress.add_child_with_id("finding deltas".into(), ProgressId::FindDeltas.into()),
+        ));
+        progress
+            .lock()
+            .init(Some(candidates.len()), gix_features::progress::count("objects"));
+        let start = std::time::Instant::now();
+        let chunk_size = (candidates.len() / parallel::num_threads(thread_limit)).max(MIN_CANDIDATES_PER_CHUNK);
+        let found = parallel::in_parallel_if(
+            || candidates.len() > MIN_CANDIDATES_PER_CHUNK,
+            candidates.chunks(chunk_size),
+            thread_limit,
+            {
+                let db = db.clone();
+                move |_n| (db, Vec::new())
+            },
+            {
+                let counts = &*counts;
+                let progress = Arc::clone(&progress);
+                move |chunk, (db, buf)| {
+                    let found = find_deltas(chunk, counts, db, buf, window, depth);
+                    progress.lock().inc_by(chunk.len());
+                    found
+                }
+            },
+            Collect::default(),
+        )
+        .expect("infallible");
+        progress.lock().show_throughput(start);
+        for Found { target, base, delta } in found {
+            plans[target] = Plan::Delta {
+                base_index: base,
+                data: delta,
+            };
+        }
+    }
+
+    reorder_bases_first(counts, plans)
+}
+
+/// Find deltas for all candidates in `chunk`, each one tried against up to `window - 1` of its predecessors.
+fn find_deltas(
+    chunk: &[Candidate],
+    counts: &[output::Count],
+    db: &impl crate::Find,
+    buf: &mut Vec<u8>,
+    window: usize,
+    max_depth: usize,
+) -> Vec<Found> {
+    struct Slot {
+        index: usize,
+        kind: gix_object::Kind,
+        depth: usize,
+        base: data::delta::Index,
+    }
+
+    let mut out = Vec::new();
+    let mut slots = VecDeque::<Slot>::with_capacity(window);
+    let mut delta = Vec::new();
+    for candidate in chunk {
+        let count = &counts[candidate.index];
+        let Ok(Some((obj, _location))) = db.try_find(&count.id, buf) else {
+            continue;
+        };
+        let target = obj.data;
+        let mut best: Option<(usize, usize, Vec<u8>)> = None;
+        for slot in slots.iter().rev() {
+            // Reused deltas based on the candidate end up deeper if it becomes a delta.
+            if slot.kind != candidate.kind || slot.depth + candidate.height >= max_depth {
+                continue;
+            }
+            let base_size = slot.base.base_len();
+            if target.len() < base_size / 32 {
+                continue;
+            }
+            // Like `git`, prefer deltas with shorter chains by demanding them to be smaller.
+            let (max_size, ref_depth) = match &best {
+                None => ((target.len() / 2).saturating_sub(count.id.kind().len_in_bytes()), 1),
+                Some((_, depth, delta)) => (delta.len().saturating_sub(1), *depth),
+            };
+            let max_size = max_size * (max_depth - slot.depth) / (max_depth + 1 - ref_depth);
+            if target.len().saturating_sub(base_size) >= max_size {
+                continue;
+            }
+            if slot.base.encode(target, max_size, &mut delta) {
+                best = Some((slot.index, slot.depth + 1, std::mem::take(&mut delta)));
+            }
+        }
+        let depth = best.as_ref().map_or(0, |(_, depth, _)| *depth);
+        if let Some((base, _, delta)) = best {
+            out.push(Found {
+                target: candidate.index,
+                base,
+                delta,
+            });
+        }
+        if slots.len() + 1 == window {
+            slots.pop_front();
+        }
+        slots.push_back(Slot {
+            index: candidate.index,
+            kind: candidate.kind,
+            depth,
+            base: data::delta::Index::new(target.to_vec()),
+        });
+    }
+    out
+}
+
+/// Order `counts` and their `plans` by the length of their delta chains so each base is written before its deltas,
+/// while keeping the order of objects with chains of the same length.
+fn reorder_bases_first(counts: &mut Vec<output::Count>, mut plans: Vec<Plan>) -> Vec<Plan> {
+    const UNKNOWN: usize = usize::MAX;
+    let mut chain_len = vec![UNKNOWN; plans.len()];
+    let mut chain = Vec::new();
+    for index in 0..plans.len() {
+        let mut current = index;
+        while chain_len[current] == UNKNOWN {
+            match plans[current].base_index_mut() {
+                Some(base) => {
+                    chain.push(current);
+                    current = *base;
+                }
+                None => chain_len[current] = 0,
+            }
+        }
+        let mut len = chain_len[current];
+        while let Some(index) = chain.pop() {
+            len += 1;
+            chain_len[index] = len;
+        }
+    }
+
+    let mut order: Vec<_> = (0..plans.len()).collect();
+    order.sort_by_key(|index| chain_len[*index]);
+    let mut new_index = vec![0; order.len()];
+    for (new, old) in order.iter().enumerate() {
+        new_index[*old] = new;
+    }
+
+    let mut items: Vec<_> = counts.drain(..).zip(plans.drain(..)).map(Some).collect();
+    for old in order {
+        let (count, mut plan) = items[old].take().expect("each index is used once");
+        if let Some(base) = plan.base_index_mut() {
+            *base = new_index[*base];
+        }
+        counts.push(count);
+        plans.push(plan);
+    }
+    plans
+}
+
+/// Collect all deltas found in parallel.
+#[derive(Default)]
+struct Collect {
+    found: Vec<Found>,
+}
+
+impl parallel::Reduce for Collect {
+    type Input = Vec<Found>;
+    type FeedProduce = ();
+    type Output = Vec<Found>;
+    type Error = std::convert::Infallible;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.found.extend(item);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.found)
+    }
+}
@@ -63,7 +63,7 @@ impl output::Entry {
     /// Create an Entry from a previously counted object which is located in a pack. It's `entry` is provided here.
     /// The `version` specifies what kind of target `Entry` version the caller desires.
     pub fn from_pack_entry(
-        mut entry: find::Entry,
+        entry: find::Entry,
         count: &output::Count,
         potential_bases: &[output::Count],
         bases_index_offset: usize,
@@ -112,22 +112,40 @@ impl output::Entry {
             }
             RefDelta { base_id: _ } => None, // ref deltas are for thin packs or legacy, repack them as base objects
         }
-        .map(|kind| {
-            Ok(output::Entry {
-                id: count.id.to_owned(),
-                kind,
-                decompressed_size: pack_entry.decompressed_size as usize,
-                compressed_data: {
-                    entry.data.copy_within(pack_entry.data_offset as usize.., 0);
-                    entry.data.resize(
-                        entry.data.len()
-                            - usize::try_from(pack_entry.data_offset).expect("offset representable as usize"),
-                        0,
-                    );
-                    entry.data
-                },
-            })
-        })
+        .map(|kind| Ok(Self::from_pack_entry_data(entry, &pack_entry, count, kind)))
+    }
+
+    /// Like [`from_pack_entry()`](Self::from_pack_entry()), but for use when the `kind` of the entry was already determined,
+    /// which also means the pack `entry` is known to match the desired pack version.
+    pub(crate) fn from_pack_entry_with_kind(
+        entry: find::Entry,
+        count: &output::Count,
+        kind: Kind,
+    ) -> Result<Self, Error> {
+        let pack_offset_must_be_zero = 0;
+        let pack_entry = data::Entry::from_bytes(&entry.data, pack_offset_must_be_zero, count.id.kind())?;
+        Ok(Self::from_pack_entry_data(entry, &pack_entry, count, kind))
+    }
+
+    fn from_pack_entry_data(
+        mut entry: find::Entry,
+        pack_entry: &data::Entry,
+        count: &output::Count,
+        kind: Kind,
+    ) -> Self {
+        output::Entry {
+            id: count.id.to_owned(),
+            kind,
+            decompressed_size: pack_entry.decompressed_size as usize,
+            compressed_data: {
+                entry.data.copy_within(pack_entry.data_offset as usize.., 0);
+                entry.data.resize(
+                    entry.data.len() - usize::try_from(pack_entry.data_offset).expect("offset representable as usize"),
+                    0,
+                );
+                entry.data
+            },
+        }
     }
 
     /// Create a new instance from the given `oid` and its corresponding git object data `obj`,
@@ -144,17 +162,25 @@ impl output::Entry {
             id: count.id.to_owned(),
             kind: Kind::Base(obj.kind),
             decompressed_size: obj.data.len(),
-            compressed_data: {
-                let mut out = gix_zlib::stream::deflate::Write::new(Vec::new(), compression);
-                if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
-                    match err.kind() {
-                        std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
-                        err => unreachable!("Should never see other errors than zlib, but got {:?}", err),
-                    }
-                }
-                out.flush()?;
-                out.into_inner()
+            compressed_data: deflate(obj.data, compression)?,
+        })
+    }
+
+    /// Create a new instance from the given `count` and its `delta` instructions against the object at `base_index`
+    /// in the list of all entries, deflating it with `compression`.
+    pub fn from_delta(
+        count: &output::Count,
+        base_index: usize,
+        delta: &[u8],
+        compression: gix_zlib::Compression,
+    ) -> Result<Self, Error> {
+        Ok(output::Entry {
+            id: count.id.to_owned(),
+            kind: Kind::DeltaRef {
+                object_index: base_index,
             },
+            decompressed_size: delta.len(),
+            compressed_data: deflate(delta, compression)?,
         })
     }
 
@@ -191,3 +217,15 @@ impl output::Entry {
         }
     }
 }
+
+fn deflate(mut data: &[u8], compression: gix_zlib::Compression) -> Result<Vec<u8>, Error> {
+    let mut out = gix_zlib::stream::deflate::Write::new(Vec::new(), compression);
+    if let Err(err) = std::io::copy(&mut data, &mut out) {
+        match err.kind() {
+            std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
+            err => unreachable!("Should never see other errors than zlib, but got {:?}", err),
+        }
+    }
+    out.flush()?;
+    Ok(out.into_inner())
+}
@@ -14,6 +14,13 @@ pub struct Count {
     pub id: ObjectId,
     /// A way to locate a pack entry in the object database, only available if the object is in a pack.
     pub entry_pack_location: count::PackLocation,
+    /// A hash of the name of the tree entry the object was found at as computed by [`count::name_hash()`], or `0` if unknown.
+    ///
+    /// It's used to place objects of the same name next to each other when searching for deltas.
+    pub name_hash: u32,
+    /// If `true`, the object will neither be stored as delta nor serve as delta base, like `git` does for objects at paths
+    /// which have the `delta` attribute unset.
+    pub no_delta: bool,
 }
 
 /// An entry to be written to a file.
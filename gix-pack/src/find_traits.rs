@@ -61,6 +61,14 @@ pub trait Find {
     /// Custom implementations might be interested in providing their own meta-data with `object`,
     /// which currently isn't possible as the `Locate` trait requires GATs to work like that.
     fn entry_by_location(&self, location: &data::entry::Location) -> Option<find::Entry>;
+
+    /// Return the [reachability bitmaps](crate::bitmap) of a pack or multi-pack index in the database, if there are any,
+    /// to quickly learn which objects are reachable from commits.
+    ///
+    /// The default implementation returns `None`, as if there were no bitmaps.
+    fn bitmap_index(&self) -> Option<std::sync::Arc<crate::bitmap::Index>> {
+        None
+    }
 }
 
 mod ext {
@@ -194,6 +202,10 @@ mod find_impls {
         fn entry_by_location(&self, location: &data::entry::Location) -> Option<find::Entry> {
             (*self).entry_by_location(location)
         }
+
+        fn bitmap_index(&self) -> Option<std::sync::Arc<crate::bitmap::Index>> {
+            (*self).bitmap_index()
+        }
     }
 
     impl<T> super::Find for std::sync::Arc<T>
@@ -224,6 +236,10 @@ mod find_impls {
         fn entry_by_location(&self, object: &data::entry::Location) -> Option<find::Entry> {
             self.deref().entry_by_location(object)
         }
+
+        fn bitmap_index(&self) -> Option<std::sync::Arc<crate::bitmap::Index>> {
+            self.deref().bitmap_index()
+        }
     }
 
     impl<T> super::Find for Rc<T>
@@ -254,6 +270,10 @@ mod find_impls {
         fn entry_by_location(&self, location: &data::entry::Location) -> Option<find::Entry> {
             self.deref().entry_by_location(location)
         }
+
+        fn bitmap_index(&self) -> Option<std::sync::Arc<crate::bitmap::Index>> {
+            self.deref().bitmap_index()
+        }
     }
 
     impl<T> super::Find for Box<T>
@@ -284,5 +304,9 @@ mod find_impls {
         fn entry_by_location(&self, location: &data::entry::Location) -> Option<find::Entry> {
             self.deref().entry_by_location(location)
         }
+
+        fn bitmap_index(&self) -> Option<std::sync::Arc<crate::bitmap::Index>> {
+            self.deref().bitmap_index()
+        }
     }
 }
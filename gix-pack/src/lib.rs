@@ -28,6 +28,9 @@ pub trait FileData: Deref<Target = [u8]> {}
 
 impl<T> FileData for T where T: Deref<Target = [u8]> {}
 
+///
+pub mod bitmap;
+
 ///
 pub mod bundle;
 /// A bundle of pack data and the corresponding pack index
@@ -93,6 +96,13 @@ fn read_u64(b: &[u8]) -> u64 {
     u64::from_be_bytes(b.try_into().unwrap())
 }
 
+/// The hash of `name` that `git` uses to sort objects by name, see `data::output::count::name_hash()`.
+fn name_hash(name: &[u8]) -> u32 {
+    name.iter()
+        .filter(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        .fold(0, |hash, b| (hash >> 2).wrapping_add(u32::from(*b) << 24))
+}
+
 fn exact_vec<T>(capacity: usize) -> Vec<T> {
     let mut v = Vec::new();
     v.reserve_exact(capacity);
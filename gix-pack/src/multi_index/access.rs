@@ -37,6 +37,20 @@ where
     pub fn path(&self) -> &Path {
         &self.path
     }
+    /// Returns `true` if this file is a layer of a [chain](crate::multi_index::Chain), as it's stored
+    /// in the [`multi-pack-index.d`](crate::multi_index::chain::DIRECTORY) directory.
+    pub fn is_layer(&self) -> bool {
+        self.path
+            .parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == crate::multi_index::chain::DIRECTORY)
+    }
+    /// Returns the directory containing the packs whose [index names](File::index_names()) we store, which is the
+    /// directory of our [`path()`](File::path()), or its parent directory if we are [a layer](File::is_layer()).
+    pub fn pack_dir(&self) -> Option<&Path> {
+        let parent = self.path.parent()?;
+        if self.is_layer() { parent.parent() } else { Some(parent) }
+    }
     /// Returns the amount of indices stored in this multi-index file. It's the same as [File::index_names().len()][File::index_names()],
     /// and returned as one past the highest known index.
     pub fn num_indices(&self) -> PackIndex {
@@ -135,6 +149,41 @@ where
         (pack_index, pack_offset)
     }
 
+    /// Return the index ranging from 0 to [File::num_objects()] of the object at `pack_position` in the pseudo-pack order,
+    /// or `None` if there is no reverse index chunk or if `pack_position` is out of bounds.
+    ///
+    /// The pseudo-pack order is the order of objects as if all packs were concatenated, starting with the preferred pack,
+    /// with duplicate objects removed. It's used to assign bit positions to objects in multi-pack [bitmaps](crate::bitmap).
+    pub fn entry_index_at_pack_position(&self, pack_position: u32) -> Option<EntryIndex> {
+        let ofs = self.reverse_index_ofs?;
+        (pack_position < self.num_objects).then(|| crate::read_u32(&self.data[ofs + pack_position as usize * 4..][..4]))
+    }
+
+    /// Return the index of the preferred pack, whose objects come first in the pseudo-pack order and are chosen over
+    /// duplicates in other packs, or `None` if there is no reverse index chunk to tell.
+    pub fn preferred_pack(&self) -> Option<PackIndex> {
+        let entry_index = self.entry_index_at_pack_position(0)?;
+        (entry_index < self.num_objects).then(|| self.pack_id_and_pack_offset_at_index(entry_index).0)
+    }
+
+    /// Return the positions in the pseudo-pack order taken by the objects of the pack at `pack_index`,
+    /// or `None` if there is no bitmapped packs chunk or if `pack_index` is out of bounds.
+    ///
+    /// The range is empty if no object of the pack is contained in this file as they are all duplicates.
+    pub fn pack_positions_of_pack(&self, pack_index: PackIndex) -> Option<Range<u32>> {
+        let ofs = self.bitmapped_packs_ofs?;
+        if pack_index >= self.num_indices {
+            return None;
+        }
+        let entry = &self.data[ofs + pack_index as usize * 8..][..8];
+        let (start, len) = (crate::read_u32(&entry[..4]), crate::read_u32(&entry[4..]));
+        Some(if len == 0 {
+            0..0
+        } else {
+            start..start.saturating_add(len)
+        })
+    }
+
     /// Return an iterator over all entries within this file.
     pub fn iter(&self) -> impl Iterator<Item = Entry> + '_ {
         (0..self.num_objects).map(move |idx| {
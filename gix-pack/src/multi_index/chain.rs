@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use crate::multi_index::{EntryIndex, File};
+
+/// The name of the directory next to the packs which contains the layers of an incremental multi-index along with their chain.
+pub const DIRECTORY: &str = "multi-pack-index.d";
+
+/// The name of the file in [`DIRECTORY`] which lists the checksums of all layers, one per line, starting with the base layer.
+pub const FILE_NAME: &str = "multi-pack-index-chain";
+
+///
+pub mod init {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Chain::at()`](crate::multi_index::Chain::at()) and [`Chain::from_layers()`](crate::multi_index::Chain::from_layers()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the multi-index chain file at '{path}'")]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("The multi-index chain file at '{path}' contains an invalid line: {line:?}")]
+        InvalidLine { path: PathBuf, line: String },
+        #[error("Could not open the multi-index layer at '{path}'")]
+        Layer {
+            path: PathBuf,
+            source: crate::multi_index::init::Error,
+        },
+        #[error("The multi-index layer at '{path}' has checksum {actual}, but {expected} was expected")]
+        ChecksumMismatch {
+            path: PathBuf,
+            expected: gix_hash::ObjectId,
+            actual: gix_hash::ObjectId,
+        },
+        #[error("The multi-index layer at '{path}' uses {actual}, but the layers before it use {expected}")]
+        ObjectHashMismatch {
+            path: PathBuf,
+            expected: gix_hash::Kind,
+            actual: gix_hash::Kind,
+        },
+        #[error("The layers of the multi-index chain contain more than 2^32 packs or objects")]
+        TooLarge,
+    }
+}
+
+/// A chain of multi-index files, called layers, each of which contains only packs and objects that aren't contained
+/// in the layers before it, as stored in the [`multi-pack-index.d`](DIRECTORY) directory.
+///
+/// This makes it possible to add packs to a multi-index without rewriting it, similar to how commit-graphs can be split.
+#[derive(Default)]
+pub struct Chain {
+    layers: Vec<File>,
+}
+
+/// Initialization
+impl Chain {
+    /// Open all layers listed in the chain file of the `multi-pack-index.d` directory in `pack_dir`,
+    /// which is the directory containing the packs.
+    ///
+    /// `alloc_limit_bytes` is passed to [`File::at()`] for each layer.
+    pub fn at(pack_dir: impl AsRef<Path>, alloc_limit_bytes: Option<usize>) -> Result<Self, init::Error> {
+        let pack_dir = pack_dir.as_ref();
+        let path = Self::path(pack_dir);
+        let content = std::fs::read_to_string(&path).map_err(|source| init::Error::Io {
+            source,
+            path: path.clone(),
+        })?;
+        let mut layers = Vec::new();
+        for line in content.lines() {
+            let expected = gix_hash::ObjectId::from_hex(line.as_bytes()).map_err(|_| init::Error::InvalidLine {
+                path: path.clone(),
+                line: line.into(),
+            })?;
+            let layer_path = Self::layer_path(pack_dir, expected);
+            let layer = File::at(&layer_path, alloc_limit_bytes).map_err(|source| init::Error::Layer {
+                path: layer_path.clone(),
+                source,
+            })?;
+            let actual = layer.checksum();
+            if actual != expected {
+                return Err(init::Error::ChecksumMismatch {
+                    path: layer_path,
+                    expected,
+                    actual,
+                });
+            }
+            layers.push(layer);
+        }
+        Self::from_layers(layers)
+    }
+
+    /// Create a chain from `layers`, starting with the base layer.
+    pub fn from_layers(layers: Vec<File>) -> Result<Self, init::Error> {
+        if let Some(first) = layers.first() {
+            let expected = first.object_hash();
+            if let Some(layer) = layers.iter().find(|layer| layer.object_hash() != expected) {
+                return Err(init::Error::ObjectHashMismatch {
+                    path: layer.path().to_owned(),
+                    expected,
+                    actual: layer.object_hash(),
+                });
+            }
+        }
+        let totals_fit = layers
+            .iter()
+            .try_fold((0u32, 0u32), |(indices, objects), layer| {
+                Some((
+                    indices.checked_add(layer.num_indices())?,
+                    objects.checked_add(layer.num_objects())?,
+                ))
+            })
+            .is_some();
+        if !totals_fit {
+            return Err(init::Error::TooLarge);
+        }
+        Ok(Chain { layers })
+    }
+}
+
+/// Access
+impl Chain {
+    /// Return the path to the chain file for the packs in `pack_dir`.
+    pub fn path(pack_dir: &Path) -> PathBuf {
+        pack_dir.join(DIRECTORY).join(FILE_NAME)
+    }
+
+    /// Return the path to the layer with `checksum` for the packs in `pack_dir`.
+    pub fn layer_path(pack_dir: &Path, checksum: gix_hash::ObjectId) -> PathBuf {
+        pack_dir
+            .join(DIRECTORY)
+            .join(format!("multi-pack-index-{checksum}.midx"))
+    }
+
+    /// Return all layers, starting with the base layer.
+    pub fn layers(&self) -> &[File] {
+        &self.layers
+    }
+
+    /// Turn this instance into its layers, starting with the base layer.
+    pub fn into_layers(self) -> Vec<File> {
+        self.layers
+    }
+
+    /// Return the amount of packs in all layers.
+    pub fn num_indices(&self) -> u32 {
+        self.layers.iter().map(File::num_indices).sum()
+    }
+
+    /// Return the amount of objects in all layers.
+    pub fn num_objects(&self) -> u32 {
+        self.layers.iter().map(File::num_objects).sum()
+    }
+
+    /// Return `true` if the pack whose index has the file name `index_name` is contained in one of the layers.
+    pub fn contains_index(&self, index_name: &Path) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| layer.index_names().iter().any(|name| name == index_name))
+    }
+
+    /// Find `id` in all layers and return the index of the layer containing it along with its entry index within that layer,
+    /// or `None` if it isn't contained in any layer.
+    pub fn lookup(&self, id: impl AsRef<gix_hash::oid>) -> Option<(usize, EntryIndex)> {
+        let id = id.as_ref();
+        self.layers
+            .iter()
+            .enumerate()
+            .find_map(|(layer_index, layer)| layer.lookup(id).map(|entry_index| (layer_index, entry_index)))
+    }
+}
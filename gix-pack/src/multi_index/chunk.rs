@@ -310,3 +310,65 @@ pub mod large_offsets {
         8 * large_offsets as u64
     }
 }
+
+/// Information about the reverse index chunk, which maps positions in the pseudo-pack order to entries of the multi-index.
+pub mod reverse_index {
+    use std::ops::Range;
+
+    /// The id uniquely identifying the reverse index table.
+    pub const ID: gix_chunk::Id = *b"RIDX";
+
+    /// Returns true if the `offset` range seems to match the size required for the untrusted `num_objects`.
+    pub fn is_valid(offset: &Range<usize>, num_objects: u32) -> bool {
+        (offset.end - offset.start) == (num_objects as usize).saturating_mul(4)
+    }
+
+    /// Write the position of each entry of the multi-index in `pseudo_pack_order` to `out`.
+    pub(crate) fn write(pseudo_pack_order: &[u32], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        for entry_index in pseudo_pack_order {
+            out.write_all(&entry_index.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Return the number of bytes needed to store the position of `num_objects`.
+    pub(crate) fn storage_size(num_objects: usize) -> u64 {
+        4 * num_objects as u64
+    }
+}
+
+/// Information about the bitmapped packs chunk, which stores the range of bits in the pseudo-pack order taken by each pack.
+pub mod bitmapped_packs {
+    use std::ops::Range;
+
+    /// The id uniquely identifying the bitmapped packs table.
+    pub const ID: gix_chunk::Id = *b"BTMP";
+
+    /// The position stored for packs that don't contribute any object.
+    pub(crate) const UNKNOWN_POSITION: u32 = u32::MAX;
+
+    /// Returns true if the `offset` range seems to match the size required for the untrusted `num_packs`.
+    pub fn is_valid(offset: &Range<usize>, num_packs: u32) -> bool {
+        (offset.end - offset.start) == (num_packs as usize).saturating_mul(4 + 4)
+    }
+
+    /// Write the position of the first object of each pack in the pseudo-pack order, along with the amount of objects
+    /// it contributes, from `ranges` to `out`.
+    pub(crate) fn write(ranges: &[Range<u32>], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        for range in ranges {
+            let start = if range.is_empty() {
+                UNKNOWN_POSITION
+            } else {
+                range.start
+            };
+            out.write_all(&start.to_be_bytes())?;
+            out.write_all(&(range.end - range.start).to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Return the number of bytes needed to store the bit-ranges of `num_packs`.
+    pub(crate) fn storage_size(num_packs: usize) -> u64 {
+        8 * num_packs as u64
+    }
+}
@@ -36,11 +36,17 @@ pub struct File<T = MMap> {
     lookup_ofs: usize,
     offsets_ofs: usize,
     large_offsets_ofs: Option<usize>,
+    reverse_index_ofs: Option<usize>,
+    bitmapped_packs_ofs: Option<usize>,
 }
 
 ///
 pub mod write;
-pub use write::function::write_from_index_paths;
+pub use write::function::{write_from_index_paths, write_layer_from_index_paths};
+
+///
+pub mod chain;
+pub use chain::Chain;
 
 ///
 mod access;
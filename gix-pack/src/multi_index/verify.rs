@@ -144,7 +144,7 @@ where
         C: crate::cache::DecodeEntry,
         F: Fn() -> C + Send + Clone,
     {
-        let parent = self.path.parent().ok_or_else(|| {
+        let parent = self.pack_dir().ok_or_else(|| {
             index::traverse::Error::Processor(integrity::Error::InvalidPath {
                 path: self.path.clone(),
             })
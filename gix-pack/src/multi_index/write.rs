@@ -1,4 +1,4 @@
-use std::time::SystemTime;
+use std::{path::PathBuf, time::SystemTime};
 
 use crate::multi_index;
 
@@ -13,6 +13,12 @@ mod error {
         Interrupted,
         #[error(transparent)]
         OpenIndex(#[from] crate::index::init::Error),
+        #[error("Could not open a pack to write the bitmap")]
+        OpenBundle(#[from] crate::bundle::init::Error),
+        #[error(transparent)]
+        BitmapWrite(#[from] crate::bitmap::write::Error),
+        #[error("The preferred pack '{}' isn't among the packs to write", name.display())]
+        PreferredPackNotFound { name: std::path::PathBuf },
     }
 }
 pub use error::Error;
@@ -26,16 +32,30 @@ pub(crate) struct Entry {
     index_mtime: SystemTime,
 }
 
-/// Options for use in [`multi_index::write_from_index_paths()`].
+/// Options for use in [`multi_index::write_from_index_paths()`] and [`multi_index::write_layer_from_index_paths()`].
 pub struct Options {
     /// The kind of hash to use for objects and to expect in the input files.
     pub object_hash: gix_hash::Kind,
+    /// If `Some`, write a reachability bitmap with the given options as `multi-pack-index-<checksum>.bitmap` into the
+    /// directory of the pack indices, which is where the multi-index is expected to be placed.
+    ///
+    /// The multi-index then also contains a reverse index, and the objects of the pack with the oldest index file are preferred
+    /// over duplicates in other packs, like `git multi-pack-index write --bitmap` does.
+    /// No bitmap is written if the packs don't contain all objects reachable from their commits.
+    pub bitmap: Option<crate::bitmap::write::Options>,
+    /// The file name of the index or data file of the pack whose objects should be preferred over duplicates in other packs,
+    /// and which comes first in the pseudo-pack order used for bitmaps, like `git multi-pack-index write --preferred-pack`.
+    ///
+    /// If `None`, the pack with the oldest index file is preferred if a bitmap is written.
+    pub preferred_pack: Option<PathBuf>,
 }
 
-/// The result of [`multi_index::write_from_index_paths()`].
+/// The result of [`multi_index::write_from_index_paths()`] and [`multi_index::write_layer_from_index_paths()`].
 pub struct Outcome {
     /// The calculated multi-index checksum of the file at `multi_index_path`.
     pub multi_index_checksum: gix_hash::ObjectId,
+    /// The path to the reachability bitmap of the multi-index, if one was [requested](Options::bitmap) and could be written.
+    pub bitmap_path: Option<PathBuf>,
 }
 
 /// The progress ids used in [`crate::multi_index::write_from_index_paths()`].
@@ -70,7 +90,7 @@ impl<T> multi_index::File<T> {
 
 pub(super) mod function {
     use std::{
-        path::PathBuf,
+        path::{Path, PathBuf},
         sync::atomic::{AtomicBool, Ordering},
         time::{Instant, SystemTime},
     };
@@ -85,11 +105,72 @@ pub(super) mod function {
     ///
     /// Progress is sent to `progress` and interruptions checked via `should_interrupt`.
     pub fn write_from_index_paths(
+        index_paths: Vec<PathBuf>,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        write(
+            index_paths,
+            &multi_index::Chain::default(),
+            false,
+            out,
+            progress,
+            should_interrupt,
+            options,
+        )
+    }
+
+    /// Create a new layer of an incremental multi-index for writing to `out`, which builds upon the layers of `base`
+    /// and contains all packs of the index files at `index_paths` which aren't already in `base`.
+    /// Objects that are already contained in `base` are not written again.
+    ///
+    /// The layer is expected to be placed into [`multi-pack-index.d`](multi_index::chain::DIRECTORY) with a
+    /// [name derived from its checksum](multi_index::Chain::layer_path()), and its checksum needs to be appended
+    /// to the [chain file](multi_index::Chain::path()) to make it visible.
+    ///
+    /// ### Deviation
+    ///
+    /// No bitmap is written for layers. If [`Options::bitmap`] is set, the layer contains a reverse index and
+    /// the bit-ranges of its packs, which allows `git` to write a bitmap for it.
+    pub fn write_layer_from_index_paths(
         mut index_paths: Vec<PathBuf>,
+        base: Option<&multi_index::Chain>,
         out: &mut dyn std::io::Write,
         progress: &mut dyn DynNestedProgress,
         should_interrupt: &AtomicBool,
-        Options { object_hash }: Options,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        if let Some(base) = base {
+            index_paths.retain(|path| {
+                !base.contains_index(Path::new(path.file_name().expect("index paths have a file name")))
+            });
+        }
+        write(
+            index_paths,
+            base.unwrap_or(&multi_index::Chain::default()),
+            true,
+            out,
+            progress,
+            should_interrupt,
+            options,
+        )
+    }
+
+    /// Write a multi-index without the objects in `base`, which is a layer of an incremental multi-index if `is_layer` is `true`.
+    fn write(
+        mut index_paths: Vec<PathBuf>,
+        base: &multi_index::Chain,
+        is_layer: bool,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        Options {
+            object_hash,
+            bitmap,
+            preferred_pack: preferred_pack_name,
+        }: Options,
     ) -> Result<Outcome, Error> {
         let out = gix_hash::io::Write::new(out, object_hash);
         let (index_paths_sorted, index_filenames_sorted) = {
@@ -101,6 +182,7 @@ pub(super) mod function {
             (index_paths, file_names)
         };
 
+        let mut preferred_pack = None::<(u32, SystemTime)>;
         let entries = {
             let mut entries = Vec::new();
             let start = Instant::now();
@@ -111,15 +193,26 @@ pub(super) mod function {
             progress.init(Some(index_paths_sorted.len()), gix_features::progress::count("indices"));
 
             // This could be parallelized… but it's probably not worth it unless you have 500mio objects.
-            for (index_id, index) in index_paths_sorted.iter().enumerate() {
-                let mtime = index
+            for (index_id, index_path) in index_paths_sorted.iter().enumerate() {
+                let mtime = index_path
                     .metadata()
                     .and_then(|m| m.modified())
                     .unwrap_or(SystemTime::UNIX_EPOCH);
-                let index = crate::index::File::at(index, object_hash)?;
+                let index = crate::index::File::at(index_path, object_hash)?;
+                let is_preferred = match preferred_pack_name.as_deref() {
+                    Some(name) => index_path.file_stem() == name.file_stem(),
+                    None => {
+                        bitmap.is_some()
+                            && index.num_objects() != 0
+                            && preferred_pack.is_none_or(|(_, oldest_mtime)| mtime < oldest_mtime)
+                    }
+                };
+                if is_preferred {
+                    preferred_pack = Some((index_id as u32, mtime));
+                }
 
                 entries.reserve(index.num_objects() as usize);
-                entries.extend(index.iter().map(|e| Entry {
+                entries.extend(index.iter().filter(|e| base.lookup(e.oid).is_none()).map(|e| Entry {
                     id: e.oid,
                     pack_index: index_id as u32,
                     pack_offset: e.pack_offset,
@@ -131,12 +224,18 @@ pub(super) mod function {
                 }
             }
             progress.show_throughput(start);
+            if let Some(name) = preferred_pack_name.filter(|_| preferred_pack.is_none()) {
+                return Err(Error::PreferredPackNotFound { name });
+            }
 
             let start = Instant::now();
             progress.set_name("Deduplicate".into());
             progress.init(Some(entries.len()), gix_features::progress::count("entries"));
+            let is_preferred =
+                |entry: &Entry| preferred_pack.is_some_and(|(pack_index, _)| pack_index == entry.pack_index);
             entries.sort_by(|l, r| {
                 l.id.cmp(&r.id)
+                    .then_with(|| is_preferred(l).cmp(&is_preferred(r)).reverse())
                     .then_with(|| l.index_mtime.cmp(&r.index_mtime).reverse())
                     .then_with(|| l.pack_index.cmp(&r.pack_index))
             });
@@ -172,6 +271,43 @@ pub(super) mod function {
             );
         }
 
+        // Objects are ordered as if all packs were concatenated, starting with the preferred one.
+        let pseudo_pack_order = bitmap.is_some().then(|| {
+            let mut order: Vec<_> = (0..entries.len() as u32).collect();
+            order.sort_by_key(|entry_index| {
+                let entry = &entries[*entry_index as usize];
+                (
+                    preferred_pack.is_none_or(|(pack_index, _)| pack_index != entry.pack_index),
+                    entry.pack_index,
+                    entry.pack_offset,
+                )
+            });
+            order
+        });
+        // Each pack takes a contiguous range of positions in the pseudo-pack order.
+        let pack_positions = pseudo_pack_order.as_ref().map(|order| {
+            let mut ranges = vec![0..0; index_paths_sorted.len()];
+            for (position, entry_index) in order.iter().enumerate() {
+                let position = position as u32;
+                let range = &mut ranges[entries[*entry_index as usize].pack_index as usize];
+                if range.start == range.end {
+                    *range = position..position;
+                }
+                range.end = position + 1;
+            }
+            ranges
+        });
+        if let Some(order) = &pseudo_pack_order {
+            cf.plan_chunk(
+                multi_index::chunk::reverse_index::ID,
+                multi_index::chunk::reverse_index::storage_size(order.len()),
+            );
+            cf.plan_chunk(
+                multi_index::chunk::bitmapped_packs::ID,
+                multi_index::chunk::bitmapped_packs::storage_size(index_paths_sorted.len()),
+            );
+        }
+
         let mut write_progress =
             progress.add_child_with_id("Writing multi-index".into(), ProgressId::BytesWritten.into());
         let write_start = Instant::now();
@@ -214,6 +350,14 @@ pub(super) mod function {
                         num_large_offsets.expect("available if planned"),
                         &mut chunk_write,
                     ),
+                    multi_index::chunk::reverse_index::ID => multi_index::chunk::reverse_index::write(
+                        pseudo_pack_order.as_deref().expect("available if planned"),
+                        &mut chunk_write,
+                    ),
+                    multi_index::chunk::bitmapped_packs::ID => multi_index::chunk::bitmapped_packs::write(
+                        pack_positions.as_deref().expect("available if planned"),
+                        &mut chunk_write,
+                    ),
                     unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
                 }
                 .map_err(gix_hash::io::Error::from)?;
@@ -232,7 +376,42 @@ pub(super) mod function {
             .map_err(gix_hash::io::Error::from)?;
         out.progress.show_throughput(write_start);
 
-        Ok(Outcome { multi_index_checksum })
+        let bitmap_path = match (bitmap, pseudo_pack_order, index_paths_sorted.first()) {
+            (Some(options), Some(order), Some(first_index_path)) if !is_layer => {
+                let bundles = index_paths_sorted
+                    .iter()
+                    .map(|path| crate::Bundle::at(path, object_hash))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let objects: Vec<_> = order
+                    .into_iter()
+                    .map(|entry_index| {
+                        let entry = &entries[entry_index as usize];
+                        crate::bitmap::write::Object {
+                            id: entry.id,
+                            bundle: entry.pack_index,
+                            pack_offset: entry.pack_offset,
+                            index_position: entry_index,
+                        }
+                    })
+                    .collect();
+                let path = first_index_path.with_file_name(format!("multi-pack-index-{multi_index_checksum}.bitmap"));
+                crate::bitmap::write::write(
+                    &bundles,
+                    &objects,
+                    multi_index_checksum,
+                    &path,
+                    should_interrupt,
+                    options,
+                )?
+                .map(|outcome| outcome.path)
+            }
+            _ => None,
+        };
+
+        Ok(Outcome {
+            multi_index_checksum,
+            bitmap_path,
+        })
     }
 }
 
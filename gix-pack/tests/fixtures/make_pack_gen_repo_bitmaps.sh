@@ -0,0 +1,63 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_files() {
+  local base_dir=${1:?directory to write them into}
+  local num_files=${2:?amount of files to write}
+  local nonce=${3:?something to make files more unique}
+
+  mkdir -p "$base_dir"
+  for file_id in $(seq -w "$num_files"); do
+    seq "$file_id" > "$base_dir/$file_id"
+    echo "$nonce" >> "$base_dir/$file_id"
+  done
+}
+
+function make_history() {
+  local dirs=(. a b c a/a a/b a/c a/a/a)
+  local rounds=${1:?amount of commits to make}
+  local repack_every=${2:?amount of commits after which to create a new pack}
+
+  git checkout -q -b main
+  for round in $(seq "$rounds"); do
+    dir_index=$(( round % ${#dirs[@]} ))
+    num_files=$(( (round + 1) * 3 ))
+    write_files "${dirs[$dir_index]}" $num_files "$round"
+    git add .
+    git commit -qm "$round $num_files"
+    if [ $(( round % repack_every )) = 0 ]; then
+      git repack -q
+    fi
+  done
+
+  git checkout -q -b side main~5
+  write_files side 5 "side"
+  git add .
+  git commit -qm "side"
+  git tag -m "a tag object" annotated
+  git checkout -q main
+}
+
+function record_reachable_objects() {
+  mkdir .git/reachable
+  for rev in main main~3 side annotated; do
+    name=$(echo "$rev" | tr '~' '-')
+    git rev-parse "$rev" > ".git/reachable/$name.tip"
+    git rev-list --objects "$rev" | cut -d ' ' -f 1 | sort > ".git/reachable/$name"
+  done
+}
+
+git init -q pack
+(cd pack
+  make_history 20 100
+  git -c pack.writeBitmapHashCache=true repack -adbq
+  record_reachable_objects
+)
+
+git init -q multi-pack
+(cd multi-pack
+  make_history 20 7
+  git repack -q
+  git -c pack.writeBitmapLookupTable=true -c pack.writeBitmapHashCache=false multi-pack-index write --bitmap
+  record_reachable_objects
+)
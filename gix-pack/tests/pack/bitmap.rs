@@ -0,0 +1,272 @@
+use std::{path::Path, sync::Arc};
+
+use gix_hash::ObjectId;
+use gix_pack::bitmap;
+
+fn fixture(kind: &str) -> crate::Result<(std::path::PathBuf, bitmap::Index)> {
+    let repo = crate::scripted_fixture_read_only("make_pack_gen_repo_bitmaps.sh")?.join(kind);
+    let pack_dir = repo.join(".git/objects/pack");
+    let multi_index = pack_dir.join("multi-pack-index");
+    let index = if multi_index.is_file() {
+        bitmap::Index::at_multi_index(Arc::new(gix_pack::multi_index::File::at(multi_index, None)?))?
+    } else {
+        let index_path = std::fs::read_dir(&pack_dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .find(|path| path.extension().is_some_and(|ext| ext == "idx"))
+            .expect("one pack");
+        bitmap::Index::at_pack_index(Arc::new(gix_pack::index::File::at(index_path, crate::object_hash())?))?
+    };
+    Ok((repo, index.expect("bitmap present")))
+}
+
+fn reachable_according_to_git(repo: &Path, name: &str) -> crate::Result<(ObjectId, Vec<ObjectId>)> {
+    let dir = repo.join(".git/reachable");
+    let tip = std::fs::read_to_string(dir.join(format!("{name}.tip")))?;
+    let ids = std::fs::read_to_string(dir.join(name))?
+        .lines()
+        .map(|hex| ObjectId::from_hex(hex.as_bytes()))
+        .collect::<Result<_, _>>()?;
+    Ok((ObjectId::from_hex(tip.trim().as_bytes())?, ids))
+}
+
+fn assert_reachable_objects_match_git(kind: &str) -> crate::Result {
+    let (repo, index) = fixture(kind)?;
+    let odb = gix_odb::at_opts(
+        repo.join(".git/objects"),
+        None,
+        gix_odb::store::init::Options {
+            object_hash: crate::object_hash(),
+            ..Default::default()
+        },
+    )?;
+    for name in ["main", "main-3", "side", "annotated"] {
+        let (tip, expected) = reachable_according_to_git(&repo, name)?;
+        let reachable = index
+            .reachable(Some(tip), &odb)?
+            .expect("all objects are in the bitmapped pack");
+        let mut actual: Vec<_> = reachable.iter().map(|bit| index.id_at(bit).to_owned()).collect();
+        actual.sort();
+        assert_eq!(
+            actual, expected,
+            "{kind}: objects reachable from {name} are the same as for git"
+        );
+    }
+
+    let (main, _) = reachable_according_to_git(&repo, "main")?;
+    assert!(
+        index.bitmap_of(&main).is_some(),
+        "{kind}: the tip of the main branch has a bitmap"
+    );
+    Ok(())
+}
+
+#[test]
+fn pack_bitmaps_yield_the_same_reachable_objects_as_git() -> crate::Result {
+    assert_reachable_objects_match_git("pack")
+}
+
+#[test]
+fn multi_pack_bitmaps_yield_the_same_reachable_objects_as_git() -> crate::Result {
+    assert_reachable_objects_match_git("multi-pack")
+}
+
+#[test]
+fn file_contents() -> crate::Result {
+    for (kind, has_name_hash_cache, has_lookup_table) in [("pack", true, false), ("multi-pack", false, true)] {
+        let (_repo, index) = fixture(kind)?;
+        let file = index.file();
+        assert!(!file.entries().is_empty(), "{kind}: commits were selected");
+        assert_eq!(
+            file.has_name_hash_cache(),
+            has_name_hash_cache,
+            "{kind}: only written if configured"
+        );
+        assert_eq!(
+            file.flags() & bitmap::flags::LOOKUP_TABLE != 0,
+            has_lookup_table,
+            "{kind}: the lookup table is skipped"
+        );
+        if has_name_hash_cache {
+            assert!(
+                (0..index.num_objects()).any(|bit| index.name_hash_at(bit).is_some_and(|hash| hash != 0)),
+                "{kind}: names of blobs and trees are hashed"
+            );
+        }
+
+        use gix_object::Kind::*;
+        let num_objects_by_kind: usize = [Commit, Tree, Blob, Tag]
+            .into_iter()
+            .map(|kind| index.objects_of_kind(kind).len())
+            .sum();
+        assert_eq!(
+            num_objects_by_kind,
+            index.num_objects(),
+            "{kind}: each object has exactly one kind"
+        );
+        assert_eq!(
+            index.objects_of_kind(Tag).len(),
+            1,
+            "{kind}: there is one annotated tag"
+        );
+    }
+    Ok(())
+}
+
+mod write {
+    use std::{path::Path, sync::atomic::AtomicBool};
+
+    use gix_hash::ObjectId;
+    use gix_pack::bitmap;
+
+    use super::{fixture, reachable_according_to_git};
+
+    /// Assert that `actual` has the same objects of each kind as `expected`, and the same reachable objects for all commits
+    /// that `expected` stores a bitmap for.
+    fn assert_same_objects(actual: &bitmap::Index, expected: &bitmap::Index) {
+        let ids = |index: &bitmap::Index, set: &bitmap::Set| {
+            let mut ids: Vec<_> = set.iter().map(|bit| index.id_at(bit).to_owned()).collect();
+            ids.sort();
+            ids
+        };
+        use gix_object::Kind::*;
+        for kind in [Commit, Tree, Blob, Tag] {
+            assert_eq!(
+                ids(actual, actual.objects_of_kind(kind)),
+                ids(expected, expected.objects_of_kind(kind)),
+                "{kind} objects are the same"
+            );
+        }
+        let mut num_bitmaps = 0;
+        for bit in expected.objects_of_kind(Commit).iter() {
+            let id = expected.id_at(bit);
+            let Some(expected_bitmap) = expected.bitmap_of(id) else {
+                continue;
+            };
+            assert_eq!(
+                ids(
+                    actual,
+                    &actual.bitmap_of(id).expect("all commits have bitmaps in small packs")
+                ),
+                ids(expected, &expected_bitmap),
+                "the objects reachable from {id} are the same"
+            );
+            num_bitmaps += 1;
+        }
+        assert_ne!(num_bitmaps, 0, "git stored bitmaps to compare with");
+    }
+
+    fn assert_reachable_objects_match_git(repo: &Path, index: &bitmap::Index) -> crate::Result {
+        let odb = gix_odb::at_opts(
+            repo.join(".git/objects"),
+            None,
+            gix_odb::store::init::Options {
+                object_hash: crate::object_hash(),
+                ..Default::default()
+            },
+        )?;
+        for name in ["main", "main-3", "side", "annotated"] {
+            let (tip, expected) = reachable_according_to_git(repo, name)?;
+            let reachable = index.reachable(Some(tip), &odb)?.expect("all objects are in the pack");
+            let mut actual: Vec<ObjectId> = reachable.iter().map(|bit| index.id_at(bit).to_owned()).collect();
+            actual.sort();
+            assert_eq!(
+                actual, expected,
+                "objects reachable from {name} are the same as for git"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(not(feature = "wasm"), feature = "streaming-input"))]
+    fn along_with_a_pack() -> crate::Result {
+        let (repo, expected) = fixture("pack")?;
+        let pack_path = expected.file().path().with_extension("pack");
+        let dir = gix_testtools::tempfile::TempDir::new()?;
+        let outcome = gix_pack::Bundle::write_to_directory(
+            &mut std::io::BufReader::new(std::fs::File::open(&pack_path)?),
+            Some(dir.path()),
+            &mut gix_features::progress::Discard,
+            &AtomicBool::new(false),
+            None::<gix_object::find::Never>,
+            gix_pack::bundle::write::Options {
+                object_hash: crate::object_hash(),
+                bitmap: Some(Default::default()),
+                ..Default::default()
+            },
+        )?;
+        let bitmap_path = outcome.bitmap_path.expect("the pack is complete");
+        assert_eq!(
+            bitmap_path.file_name(),
+            pack_path.with_extension("bitmap").file_name(),
+            "the bitmap is named after the pack"
+        );
+
+        let index = bitmap::Index::at_pack_index(std::sync::Arc::new(gix_pack::index::File::at(
+            outcome.index_path.expect("written"),
+            crate::object_hash(),
+        )?))?
+        .expect("bitmap was written");
+        assert!(index.file().has_name_hash_cache(), "written by default");
+        assert_eq!(
+            index.file().entries().len(),
+            index.objects_of_kind(gix_object::Kind::Commit).len(),
+            "all commits are selected in small packs"
+        );
+        assert_same_objects(&index, &expected);
+        assert_reachable_objects_match_git(&repo, &index)
+    }
+
+    #[test]
+    fn along_with_a_multi_index() -> crate::Result {
+        let (repo, expected) = fixture("multi-pack")?;
+        let dir = gix_testtools::tempfile::TempDir::new()?;
+        let mut index_paths = Vec::new();
+        for entry in std::fs::read_dir(repo.join(".git/objects/pack"))? {
+            let path = entry?.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("idx") => index_paths.push(dir.path().join(path.file_name().expect("present"))),
+                Some("pack") => {}
+                _ => continue,
+            }
+            std::fs::copy(&path, dir.path().join(path.file_name().expect("present")))?;
+        }
+        assert!(index_paths.len() > 1, "the fixture has multiple packs");
+
+        let multi_index_path = dir.path().join("multi-pack-index");
+        let outcome = gix_pack::multi_index::write_from_index_paths(
+            index_paths,
+            &mut std::fs::File::create(&multi_index_path)?,
+            &mut gix_features::progress::Discard,
+            &AtomicBool::new(false),
+            gix_pack::multi_index::write::Options {
+                object_hash: crate::object_hash(),
+                bitmap: Some(bitmap::write::Options {
+                    name_hash_cache: false,
+                    fsync: Some(gix_fs::fsync::Method::Fsync),
+                }),
+                preferred_pack: None,
+            },
+        )?;
+        assert_eq!(
+            outcome.bitmap_path,
+            Some(
+                dir.path()
+                    .join(format!("multi-pack-index-{}.bitmap", outcome.multi_index_checksum))
+            ),
+            "the bitmap is named after the multi-index"
+        );
+
+        let multi_index = gix_pack::multi_index::File::at(&multi_index_path, None)?;
+        assert!(
+            multi_index.entry_index_at_pack_position(0).is_some(),
+            "a reverse index is written along with the bitmap"
+        );
+        let index = bitmap::Index::at_multi_index(std::sync::Arc::new(multi_index))?.expect("bitmap was written");
+        assert!(!index.file().has_name_hash_cache(), "it was disabled");
+        assert_same_objects(&index, &expected);
+        assert_reachable_objects_match_git(&repo, &index)
+    }
+}
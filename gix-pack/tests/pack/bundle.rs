@@ -107,6 +107,7 @@ mod write_to_directory {
             index_path: None,
             data_path: None,
             keep_path: None,
+            bitmap_path: None,
             object_hash: gix_hash::Kind::Sha1,
         })
     }
@@ -170,6 +171,9 @@ mod write_to_directory {
                 object_hash: gix_hash::Kind::Sha1,
                 alloc_limit_bytes: prevent_allocation,
                 compression: gix_zlib::Compression::BEST_SPEED,
+                bitmap: None,
+                fsync: None,
+                fsync_metadata: None,
             },
         )
         .expect_err("a zero allocation limit rejects the first non-empty decoded object");
@@ -205,6 +209,9 @@ mod write_to_directory {
                 object_hash: gix_hash::Kind::Sha1,
                 alloc_limit_bytes: None,
                 compression: gix_zlib::Compression::BEST_SPEED,
+                bitmap: None,
+                fsync: Some(gix_fs::fsync::Method::Fsync),
+                fsync_metadata: Some(gix_fs::fsync::Method::WriteoutOnly),
             },
         )
         .map_err(Into::into)
@@ -1,4 +1,4 @@
-use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, atomic::AtomicBool};
 
 use gix_features::{
     parallel::{InOrderIter, reduce::Finalize},
@@ -134,6 +134,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 16,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("b920bbb055e1efb9080592a409d3975738b6efb3"),
                 None,
@@ -179,6 +180,7 @@ fn traversals() -> crate::Result {
                         gix_hash::Kind::Sha256 => 71,
                         _ => unimplemented!(),
                     },
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("25114bd8820b393c402cd53ad8ec7f6a84bb0633"),
                 Some(hex_to_id("29ab9797aff1ca826afb699680356695d19c5acb")),
@@ -220,6 +222,7 @@ fn traversals() -> crate::Result {
                         _ => unimplemented!(),
                     },
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("d83d42128e40957c5174920189a0390b5a70f446"),
                 None,
@@ -241,6 +244,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -262,6 +266,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -439,6 +444,364 @@ fn entry_sizes_depend_on_compression_level() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn delta_compression_stores_similar_objects_as_deltas() -> crate::Result {
+    use gix_object::Write;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let db = Arc::new(gix_odb::Store::at_opts(
+        tmp.path().into(),
+        &mut None.into_iter(),
+        Default::default(),
+    )?)
+    .to_cache_arc();
+    let ids = (0..20)
+        .map(|version| {
+            let content = (0..200)
+                .map(|line| {
+                    if line == version * 10 {
+                        format!("line {line} was changed in version {version}\n")
+                    } else {
+                        format!("line {line} stays the same\n")
+                    }
+                })
+                .collect::<String>();
+            db.write_buf(gix_object::Kind::Blob, content.as_bytes())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let write_entries = |mode| -> crate::Result<(Vec<output::Entry>, entry::iter_from_counts::Outcome)> {
+        let counts = ids
+            .iter()
+            .map(|id| output::Count::from_data(*id, None))
+            .collect::<Vec<_>>();
+        let mut entries_iter = output::entry::iter_from_counts(
+            counts,
+            db.clone(),
+            Box::new(progress::Discard),
+            output::entry::iter_from_counts::Options {
+                mode,
+                ..Default::default()
+            },
+        );
+        let entries: Vec<_> = InOrderIter::from(entries_iter.by_ref())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok((entries, entries_iter.finalize()?))
+    };
+
+    let (base_entries, base_stats) = write_entries(entry::iter_from_counts::Mode::PackCopyAndBaseObjects)?;
+    assert_eq!(base_stats.delta_compressed_objects, 0);
+    assert_eq!(base_stats.decoded_and_recompressed_objects, ids.len());
+
+    let (delta_entries, delta_stats) = write_entries(entry::iter_from_counts::Mode::DeltaCompression)?;
+    assert_eq!(
+        delta_stats.delta_compressed_objects,
+        ids.len() - 1,
+        "all but the first object can be stored as delta"
+    );
+    assert_eq!(delta_entries.len(), ids.len());
+    let size = |entries: &[output::Entry]| entries.iter().map(|e| e.compressed_data.len()).sum::<usize>();
+    assert!(
+        size(&delta_entries) * 3 < size(&base_entries),
+        "deltas are much smaller than the objects themselves: {} vs {}",
+        size(&delta_entries),
+        size(&base_entries)
+    );
+
+    write_and_verify(
+        db,
+        delta_entries,
+        gix_hash::Kind::Sha1,
+        gix_hash::Kind::Sha1.null(),
+        None,
+    )
+}
+
+#[test]
+#[cfg(all(not(feature = "wasm"), feature = "streaming-input"))]
+fn delta_chains_are_not_deeper_than_depth_even_if_deltas_are_reused() -> crate::Result {
+    use gix_object::Write;
+    let open_db = |path: &std::path::Path| -> crate::Result<gix_odb::HandleArc> {
+        let mut db = Arc::new(gix_odb::Store::at_opts(
+            path.into(),
+            &mut None.into_iter(),
+            Default::default(),
+        )?)
+        .to_cache_arc();
+        db.prevent_pack_unload();
+        Ok(db)
+    };
+    let write_entries = |db: &gix_odb::HandleArc,
+                         ids: Vec<gix_hash::ObjectId>,
+                         window: usize,
+                         depth: usize|
+     -> crate::Result<(Vec<output::Entry>, entry::iter_from_counts::Outcome)> {
+        let (counts, _) = output::count::objects(
+            db.clone(),
+            Box::new(ids.into_iter().map(Ok)),
+            &progress::Discard,
+            &AtomicBool::new(false),
+            count::objects::Options {
+                input_object_expansion: count::objects::ObjectExpansion::AsIs,
+                ..Default::default()
+            },
+        )?;
+        let mut entries_iter = output::entry::iter_from_counts(
+            counts,
+            db.clone(),
+            Box::new(progress::Discard),
+            output::entry::iter_from_counts::Options {
+                mode: entry::iter_from_counts::Mode::DeltaCompression,
+                window,
+                depth,
+                ..Default::default()
+            },
+        );
+        let entries: Vec<_> = InOrderIter::from(entries_iter.by_ref())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok((entries, entries_iter.finalize()?))
+    };
+    let max_chain_depth = |entries: &[output::Entry]| {
+        let mut depths = Vec::<usize>::with_capacity(entries.len());
+        for entry in entries {
+            depths.push(match entry.kind {
+                entry::Kind::DeltaRef { object_index } => depths[object_index] + 1,
+                entry::Kind::DeltaOid { .. } => 1,
+                entry::Kind::Base(_) => 0,
+            });
+        }
+        depths.into_iter().max().unwrap_or_default()
+    };
+    // Each version extends the previous one, so with a window of 2 they form a single chain.
+    let content = |version: usize| {
+        (0..100 + version * 10)
+            .flat_map(|line| format!("line {line} of a file that grows\n").into_bytes())
+            .collect::<Vec<_>>()
+    };
+
+    let loose = gix_testtools::tempfile::TempDir::new()?;
+    let db = open_db(loose.path())?;
+    let ids = (0..20)
+        .map(|version| db.write_buf(gix_object::Kind::Blob, &content(version)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (entries, _) = write_entries(&db, ids.clone(), 2, 50)?;
+    assert_eq!(max_chain_depth(&entries), ids.len() - 1, "a single long chain");
+
+    let packed = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::create_dir(packed.path().join("pack"))?;
+    let mut pack = Vec::new();
+    let num_entries = entries.len() as u32;
+    for bytes in output::bytes::FromEntriesIter::new(
+        std::iter::once(Ok::<_, entry::iter_from_counts::Error>(entries)),
+        &mut pack,
+        num_entries,
+        pack::data::Version::V2,
+        gix_hash::Kind::Sha1,
+    ) {
+        bytes?;
+    }
+    pack::Bundle::write_to_directory(
+        &mut pack.as_slice(),
+        Some(&packed.path().join("pack")),
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+        None::<gix_odb::HandleArc>,
+        Default::default(),
+    )?;
+    let db = open_db(packed.path())?;
+    let larger_loose_version = db.write_buf(gix_object::Kind::Blob, &content(20))?;
+    let ids: Vec<_> = ids.into_iter().chain(Some(larger_loose_version)).collect();
+
+    let depth = 3;
+    let (entries, stats) = write_entries(&db, ids, 10, depth)?;
+    assert!(
+        stats.objects_copied_from_pack > 0,
+        "deltas are still reused if their chains are short enough"
+    );
+    assert_eq!(
+        max_chain_depth(&entries),
+        depth,
+        "reused chains are broken up, and packed bases with reused deltas only become deltas if there is room"
+    );
+    write_and_verify(db, entries, gix_hash::Kind::Sha1, gix_hash::Kind::Sha1.null(), None)
+}
+
+#[test]
+fn no_delta_is_decided_by_path_of_blobs() -> crate::Result {
+    use gix_object::{Write, bstr::BString, tree::EntryKind};
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let db = Arc::new(gix_odb::Store::at_opts(
+        tmp.path().into(),
+        &mut None.into_iter(),
+        Default::default(),
+    )?)
+    .to_cache_arc();
+    let write_tree = |entries: Vec<(EntryKind, &str, gix_hash::ObjectId)>| {
+        let mut tree = gix_object::Tree {
+            entries: entries
+                .into_iter()
+                .map(|(mode, filename, oid)| gix_object::tree::Entry {
+                    mode: mode.into(),
+                    filename: filename.into(),
+                    oid,
+                })
+                .collect(),
+        };
+        tree.entries.sort();
+        db.write(&tree)
+    };
+    let write_commit = |tree: gix_hash::ObjectId, parent: Option<gix_hash::ObjectId>| {
+        let parent = parent.map(|id| format!("parent {id}\n")).unwrap_or_default();
+        let signature = "author <author@example.com> 0 +0000";
+        db.write_buf(
+            gix_object::Kind::Commit,
+            format!("tree {tree}\n{parent}author {signature}\ncommitter {signature}\n\nmessage\n").as_bytes(),
+        )
+    };
+
+    let top_bin = db.write_buf(gix_object::Kind::Blob, b"top.bin")?;
+    let top_txt = db.write_buf(gix_object::Kind::Blob, b"top.txt")?;
+    let nested_bin_v1 = db.write_buf(gix_object::Kind::Blob, b"dir/nested.bin v1")?;
+    let nested_bin_v2 = db.write_buf(gix_object::Kind::Blob, b"dir/nested.bin v2")?;
+    let dir_v1 = write_tree(vec![(EntryKind::Blob, "nested.bin", nested_bin_v1)])?;
+    let dir_v2 = write_tree(vec![(EntryKind::Blob, "nested.bin", nested_bin_v2)])?;
+    let root = |dir| {
+        write_tree(vec![
+            (EntryKind::Tree, "dir", dir),
+            (EntryKind::Blob, "top.bin", top_bin),
+            (EntryKind::Blob, "top.txt", top_txt),
+        ])
+    };
+    let (root_v1, root_v2) = (root(dir_v1)?, root(dir_v2)?);
+    let first = write_commit(root_v1, None)?;
+    let second = write_commit(root_v2, Some(first))?;
+
+    for (input, expansion, expected_paths, expected_no_delta) in [
+        (
+            vec![root_v2],
+            count::objects::ObjectExpansion::TreeContents,
+            &["dir/nested.bin", "top.bin", "top.txt"][..],
+            vec![nested_bin_v2, top_bin],
+        ),
+        (
+            vec![second],
+            count::objects::ObjectExpansion::AllReachable,
+            &["dir/nested.bin", "dir/nested.bin", "top.bin", "top.txt"],
+            vec![nested_bin_v1, nested_bin_v2, top_bin],
+        ),
+        (
+            vec![second],
+            count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+            &["dir/nested.bin"],
+            vec![nested_bin_v2],
+        ),
+    ] {
+        let mut paths = Vec::<BString>::new();
+        let mut no_delta = |path: &gix_object::bstr::BStr| {
+            paths.push(path.to_owned());
+            path.ends_with(b".bin")
+        };
+        let (counts, _stats) = output::count::objects_unthreaded(
+            &db,
+            &mut input.into_iter().map(Ok),
+            &progress::Discard,
+            &AtomicBool::new(false),
+            expansion,
+            Some(&mut no_delta),
+        )?;
+        paths.sort();
+        assert_eq!(
+            paths, expected_paths,
+            "{expansion:?}: paths are relative to the root tree"
+        );
+        let mut actual_no_delta: Vec<_> = counts
+            .iter()
+            .filter_map(|count| count.no_delta.then_some(count.id))
+            .collect();
+        actual_no_delta.sort();
+        let mut expected_no_delta = expected_no_delta;
+        expected_no_delta.sort();
+        assert_eq!(actual_no_delta, expected_no_delta, "{expansion:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn all_reachable_objects_are_counted_using_bitmaps() -> crate::Result {
+    for db_kind in [DbKind::WithPackBitmap, DbKind::WithMultiPackBitmap] {
+        let db = db(db_kind, object_hash())?;
+        assert!(pack::Find::bitmap_index(&db).is_some(), "the fixture has bitmaps");
+        let reachable = db
+            .store_ref()
+            .path()
+            .parent()
+            .expect("objects directory is in .git")
+            .join("reachable");
+        let read_ids = |name: &str| -> crate::Result<Vec<gix_hash::ObjectId>> {
+            std::fs::read_to_string(reachable.join(name))?
+                .lines()
+                .map(|hex| gix_hash::ObjectId::from_hex(hex.as_bytes()).map_err(Into::into))
+                .collect()
+        };
+
+        for (tips, expected_decoded_objects) in [(&["main"][..], 0), (&["main", "side", "annotated"], 1)] {
+            let tip_ids = tips
+                .iter()
+                .map(|name| read_ids(&format!("{name}.tip")))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            let mut expected = tips
+                .iter()
+                .map(|name| read_ids(name))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            expected.sort();
+            expected.dedup();
+
+            for thread_limit in [Some(1), None] {
+                let (counts, stats) = output::count::objects(
+                    db.clone(),
+                    Box::new(tip_ids.clone().into_iter().map(Ok)),
+                    &progress::Discard,
+                    &AtomicBool::new(false),
+                    count::objects::Options {
+                        input_object_expansion: count::objects::ObjectExpansion::AllReachable,
+                        thread_limit,
+                        ..Default::default()
+                    },
+                )?;
+                let mut actual: Vec<_> = counts.iter().map(|count| count.id).collect();
+                actual.sort();
+                assert_eq!(actual, expected, "the same objects as `git rev-list --objects`");
+                assert_eq!(
+                    stats,
+                    output::count::objects::Outcome {
+                        input_objects: tip_ids.len(),
+                        expanded_objects: expected.len() - tip_ids.len(),
+                        decoded_objects: expected_decoded_objects,
+                        total_objects: expected.len(),
+                    },
+                    "only commits and tags without stored bitmap are decoded"
+                );
+                assert!(
+                    counts.iter().all(|count| !count.entry_pack_location.is_none()),
+                    "all objects are located in a pack"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 #[test]
 #[cfg(all(not(feature = "wasm"), feature = "streaming-input"))]
 fn empty_pack_is_allowed() {
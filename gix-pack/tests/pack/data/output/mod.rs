@@ -30,15 +30,22 @@ fn size_of_count() {
 enum DbKind {
     DeterministicGeneratedContent,
     DeterministicGeneratedContentMultiIndex,
+    WithPackBitmap,
+    WithMultiPackBitmap,
 }
 
 fn db(kind: DbKind, object_hash: gix_hash::Kind) -> crate::Result<gix_odb::HandleArc> {
     use DbKind::*;
-    let name = match kind {
-        DeterministicGeneratedContent => "make_pack_gen_repo.sh",
-        DeterministicGeneratedContentMultiIndex => "make_pack_gen_repo_multi_index.sh",
+    let (name, repo) = match kind {
+        DeterministicGeneratedContent => ("make_pack_gen_repo.sh", "."),
+        DeterministicGeneratedContentMultiIndex => ("make_pack_gen_repo_multi_index.sh", "."),
+        WithPackBitmap => ("make_pack_gen_repo_bitmaps.sh", "pack"),
+        WithMultiPackBitmap => ("make_pack_gen_repo_bitmaps.sh", "multi-pack"),
     };
-    let path: PathBuf = crate::scripted_fixture_read_only(name)?.join(".git").join("objects");
+    let path: PathBuf = crate::scripted_fixture_read_only(name)?
+        .join(repo)
+        .join(".git")
+        .join("objects");
     gix_odb::Store::at_opts(
         path,
         &mut None.into_iter(),
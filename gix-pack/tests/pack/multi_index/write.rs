@@ -59,6 +59,165 @@ fn from_a_hash_parameterized_pack() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn layers_of_a_chain() -> crate::Result {
+    let (dir, index_paths) = copy_static_packs()?;
+    let pack_dir = dir.path();
+
+    write_layer(pack_dir, index_paths[..1].to_vec(), None, None)?;
+    let base = gix_pack::multi_index::Chain::at(pack_dir, None)?;
+    assert_eq!(base.layers().len(), 1);
+    assert_eq!(base.num_indices(), 1);
+
+    let checksum = write_layer(pack_dir, index_paths.clone(), Some(&base), None)?;
+    let chain = gix_pack::multi_index::Chain::at(pack_dir, None)?;
+    assert_eq!(chain.layers().len(), 2);
+    assert_eq!(chain.layers()[1].checksum(), checksum);
+    assert_eq!(
+        chain.layers()[1].index_names(),
+        vec![
+            PathBuf::from("pack-a2bf8e71d8c18879e499335762dd95119d93d9f1.idx"),
+            PathBuf::from("pack-c0438c19fb16422b6bbcce24387b3264416d485b.idx"),
+        ],
+        "packs of the base aren't added again"
+    );
+    assert_eq!(chain.num_indices(), 3);
+    assert_eq!(
+        chain.num_objects(),
+        139,
+        "objects of the base aren't added again, like in a multi-index of all packs"
+    );
+
+    let full = write_multi_index_from_pack_dir(pack_dir, gix_hash::Kind::Sha1)?;
+    for entry in full.file.iter() {
+        assert!(
+            chain.lookup(entry.oid).is_some(),
+            "{} is in one of the layers",
+            entry.oid
+        );
+    }
+
+    for layer in chain.layers() {
+        assert!(layer.is_layer());
+        assert_eq!(
+            layer.pack_dir(),
+            Some(pack_dir),
+            "packs are found next to the layer directory"
+        );
+        layer.verify_integrity(&mut progress::Discard, &AtomicBool::new(false), Default::default())?;
+    }
+    Ok(())
+}
+
+#[test]
+fn preferred_pack_comes_first_in_the_pseudo_pack_order() -> crate::Result {
+    let (dir, index_paths) = copy_static_packs()?;
+    let pack_dir = dir.path();
+
+    write_layer(
+        pack_dir,
+        index_paths,
+        None,
+        Some("pack-c0438c19fb16422b6bbcce24387b3264416d485b.pack".into()),
+    )?;
+    let chain = gix_pack::multi_index::Chain::at(pack_dir, None)?;
+    let file = &chain.layers()[0];
+    assert_eq!(file.preferred_pack(), Some(2));
+
+    let ranges: Vec<_> = (0..file.num_indices())
+        .map(|pack_index| file.pack_positions_of_pack(pack_index).expect("chunk present"))
+        .collect();
+    assert_eq!(ranges[2].start, 0, "the preferred pack comes first");
+    assert_eq!(
+        ranges.iter().map(|range| range.end - range.start).sum::<u32>(),
+        file.num_objects(),
+        "all objects have a position"
+    );
+    assert_eq!(file.pack_positions_of_pack(file.num_indices()), None);
+    Ok(())
+}
+
+#[test]
+fn unknown_preferred_pack_is_an_error() -> crate::Result {
+    let index_paths = static_index_paths()?;
+    let res = gix_pack::multi_index::write_from_index_paths(
+        index_paths,
+        &mut Vec::new(),
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+        gix_pack::multi_index::write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            bitmap: None,
+            preferred_pack: Some("pack-does-not-exist.pack".into()),
+        },
+    );
+    assert!(matches!(
+        res,
+        Err(gix_pack::multi_index::write::Error::PreferredPackNotFound { .. })
+    ));
+    Ok(())
+}
+
+fn static_index_paths() -> crate::Result<Vec<PathBuf>> {
+    let mut index_paths = std::fs::read_dir(fixture_path("objects/pack"))?
+        .filter_map(|r| {
+            let idx_path = r.ok()?.path();
+            (idx_path.extension()? == "idx").then_some(idx_path)
+        })
+        .collect::<Vec<_>>();
+    index_paths.sort();
+    Ok(index_paths)
+}
+
+/// Copy the static SHA-1 packs into a writable directory and return it along with the sorted paths of the copied indices.
+fn copy_static_packs() -> crate::Result<(gix_testtools::tempfile::TempDir, Vec<PathBuf>)> {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let mut index_paths = Vec::new();
+    for ro_index in static_index_paths()? {
+        let index_path = dir
+            .path()
+            .join(ro_index.file_name().expect("index paths have file names"));
+        std::fs::copy(&ro_index, &index_path)?;
+        std::fs::copy(ro_index.with_extension("pack"), index_path.with_extension("pack"))?;
+        index_paths.push(index_path);
+    }
+    Ok((dir, index_paths))
+}
+
+/// Write a layer with the packs of `index_paths` on top of `base` and append it to the chain in `pack_dir`.
+fn write_layer(
+    pack_dir: &Path,
+    index_paths: Vec<PathBuf>,
+    base: Option<&gix_pack::multi_index::Chain>,
+    preferred_pack: Option<PathBuf>,
+) -> crate::Result<gix_hash::ObjectId> {
+    let mut out = Vec::new();
+    let outcome = gix_pack::multi_index::write_layer_from_index_paths(
+        index_paths,
+        base,
+        &mut out,
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+        gix_pack::multi_index::write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            bitmap: Some(Default::default()),
+            preferred_pack,
+        },
+    )?;
+    assert_eq!(outcome.bitmap_path, None, "bitmaps aren't written for layers");
+    let checksum = outcome.multi_index_checksum;
+
+    std::fs::create_dir_all(pack_dir.join(gix_pack::multi_index::chain::DIRECTORY))?;
+    std::fs::write(gix_pack::multi_index::Chain::layer_path(pack_dir, checksum), out)?;
+    let checksums = base
+        .into_iter()
+        .flat_map(|base| base.layers().iter().map(gix_pack::multi_index::File::checksum))
+        .chain(Some(checksum));
+    let chain: Vec<_> = checksums.map(|checksum| format!("{checksum}\n")).collect();
+    std::fs::write(gix_pack::multi_index::Chain::path(pack_dir), chain.concat())?;
+    Ok(checksum)
+}
+
 struct WrittenMultiIndex {
     file: gix_pack::multi_index::File,
     dir: gix_testtools::tempfile::TempDir,
@@ -121,7 +280,11 @@ fn write_multi_index_from_pack_dir(pack_dir: &Path, object_hash: gix_hash::Kind)
         &mut out,
         &mut progress::Discard,
         &AtomicBool::new(false),
-        gix_pack::multi_index::write::Options { object_hash },
+        gix_pack::multi_index::write::Options {
+            object_hash,
+            bitmap: None,
+            preferred_pack: None,
+        },
     )?;
     let file = gix_pack::multi_index::File::at(output_path, None)?;
 
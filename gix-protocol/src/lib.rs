@@ -8,6 +8,9 @@
 //!     - [list references](LsRefsCommand)
 //!          - create a mapping between [refspecs and references](fetch::RefMap)
 //!     - [receive a pack](fetch())
+//! * or, after a handshake with the `receive-pack` service, [send a pack](push()) along with reference updates
+//!
+//! On the server side, [`upload_pack()`] answers the commands of such clients, and [`receive_pack()`] accepts their pushes.
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -56,6 +59,19 @@ pub use fetch::function::fetch;
 mod remote_progress;
 pub use remote_progress::RemoteProgress;
 
+///
+pub mod push;
+#[cfg(feature = "blocking-client")]
+pub use push::function::push;
+
+pub mod upload_pack;
+#[cfg(feature = "upload-pack")]
+pub use upload_pack::function::upload_pack;
+
+pub mod receive_pack;
+#[cfg(feature = "receive-pack")]
+pub use receive_pack::function::receive_pack;
+
 ///
 pub mod handshake;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
@@ -0,0 +1,35 @@
+/// The error returned by [`push()`](crate::push()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Client(#[from] crate::transport::client::Error),
+    #[error("Server lack feature {feature:?}: {description}")]
+    MissingServerFeature {
+        feature: &'static str,
+        description: &'static str,
+    },
+    #[error("Unknown object format {format:?} advertised by the server")]
+    UnknownObjectFormat { format: bstr::BString },
+    #[error("The server uses the {server} object format, but the pushed objects are {client}")]
+    ObjectFormatMismatch {
+        server: gix_hash::Kind,
+        client: gix_hash::Kind,
+    },
+    #[error("Failed to send the push request")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to write the pack to send")]
+    WritePack(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("Could not decode the status report of the server")]
+    Report(#[from] crate::push::report::Error),
+}
+
+impl crate::transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Client(err) => err.is_spurious(),
+            Error::Io(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
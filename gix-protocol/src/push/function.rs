@@ -0,0 +1,205 @@
+use std::{
+    io::{Read, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use bstr::{BString, ByteSlice, ByteVec};
+use gix_features::progress::DynNestedProgress;
+
+use crate::{
+    push::{Command, Context, Error, Options, ProgressId, Report},
+    transport::{
+        client::{
+            MessageKind, WriteMode,
+            blocking_io::{ExtendedBufRead, HandleProgress, Transport},
+        },
+        packetline::{self, PacketLineRef},
+    },
+};
+
+/// Perform one push operation, relying on a `transport` for which a [handshake](crate::handshake()) with the `receive-pack`
+/// service was performed already, to send `commands` to the server.
+///
+/// `write_pack(pack_write, progress, interrupt)` is called to write a pack with all objects the server needs to perform
+/// `commands`, but only if at least one of them doesn't delete a reference.
+/// `progress` and `should_interrupt` are passed to all potentially long-running parts of the operation, and receive
+/// the progress messages of the remote.
+///
+/// The `Context` and `Options` further define parts of this `push` operation.
+///
+/// As opposed to a full `git push`, this operation does *not*…
+///
+/// * …determine which objects to send
+/// * …update local refs that track the remote refs
+///
+/// Return `Ok(None)` if there was nothing to do as `commands` is empty. **In this case the interaction must still be ended**,
+/// for instance with the help of [`SendFlushOnDrop`](crate::SendFlushOnDrop) which can wrap `transport`.
+/// Otherwise, return `Ok(Some(report))` with the report of the server about the outcome of each command, after which the server
+/// already terminated the interaction.
+///
+/// Note that the server refusing to perform any of the `commands` isn't an error, and needs to be checked in the returned [`Report`].
+pub fn push<T, E>(
+    commands: &[Command],
+    write_pack: impl FnOnce(&mut dyn std::io::Write, &mut dyn DynNestedProgress, &AtomicBool) -> Result<(), E>,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    Context {
+        handshake,
+        transport,
+        user_agent,
+        trace_packetlines,
+    }: Context<'_, T>,
+    Options { atomic, push_options }: Options,
+) -> Result<Option<Report>, Error>
+where
+    T: Transport,
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let _span = gix_trace::coarse!("gix_protocol::push()");
+    let Some(first) = commands.first() else {
+        return Ok(None);
+    };
+    let capabilities = &handshake.capabilities;
+    let server_object_hash = match capabilities.capability("object-format").and_then(|c| c.value()) {
+        Some(format) => format
+            .to_str()
+            .ok()
+            .and_then(|format| format.parse::<gix_hash::Kind>().ok())
+            .ok_or_else(|| Error::UnknownObjectFormat { format: format.into() })?,
+        None => gix_hash::Kind::Sha1,
+    };
+    let object_hash = first.old.kind();
+    if server_object_hash != object_hash {
+        return Err(Error::ObjectFormatMismatch {
+            server: server_object_hash,
+            client: object_hash,
+        });
+    }
+
+    let mut features = Vec::<BString>::new();
+    if capabilities.contains("report-status-v2") {
+        features.push("report-status-v2".into());
+    } else if capabilities.contains("report-status") {
+        features.push("report-status".into());
+    } else {
+        return Err(Error::MissingServerFeature {
+            feature: "report-status",
+            description: "without a status report there is no way to know which references were updated",
+        });
+    }
+    let use_sideband = capabilities.contains("side-band-64k");
+    if use_sideband {
+        features.push("side-band-64k".into());
+    }
+    if commands.iter().any(Command::is_delete) && !capabilities.contains("delete-refs") {
+        return Err(Error::MissingServerFeature {
+            feature: "delete-refs",
+            description: "references can only be deleted if the server allows it",
+        });
+    }
+    if atomic {
+        if !capabilities.contains("atomic") {
+            return Err(Error::MissingServerFeature {
+                feature: "atomic",
+                description: "atomic pushes need to be implemented by the server",
+            });
+        }
+        features.push("atomic".into());
+    }
+    if !push_options.is_empty() {
+        if !capabilities.contains("push-options") {
+            return Err(Error::MissingServerFeature {
+                feature: "push-options",
+                description: "push options can only be passed to servers which accept them",
+            });
+        }
+        features.push("push-options".into());
+    }
+    if capabilities.contains("ofs-delta") {
+        features.push("ofs-delta".into());
+    }
+    if capabilities.contains("object-format") {
+        features.push(format!("object-format={object_hash}").into());
+    }
+    if let (name, Some(value)) = user_agent {
+        if capabilities.contains(name) {
+            features.push(format!("{name}={value}").into());
+        }
+    }
+
+    let mut request = transport.request(WriteMode::Binary, MessageKind::Flush, trace_packetlines)?;
+    for (idx, command) in commands.iter().enumerate() {
+        let mut line = command.to_bstring();
+        if idx == 0 {
+            line.push_byte(0);
+            line.push_str(features.join(&b' '));
+        }
+        request.write_all(&line)?;
+    }
+    request.write_message(MessageKind::Flush)?;
+    if !push_options.is_empty() {
+        for option in &push_options {
+            request.write_all(option)?;
+        }
+        request.write_message(MessageKind::Flush)?;
+    }
+
+    let (mut writer, mut reader) = request.into_parts();
+    if !commands.iter().all(Command::is_delete) {
+        progress.set_name("sending pack".into());
+        write_pack(&mut writer, progress, should_interrupt).map_err(|err| Error::WritePack(err.into()))?;
+    }
+    writer.flush()?;
+    drop(writer);
+
+    let mut lines = Vec::new();
+    if use_sideband {
+        setup_remote_progress(progress, &mut reader, should_interrupt);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let mut data = data.as_slice();
+        while !data.is_empty() {
+            match packetline::decode::streaming(data).map_err(std::io::Error::other)? {
+                packetline::decode::Stream::Complete { line, bytes_consumed } => {
+                    match line {
+                        PacketLineRef::Data(line) => lines.push(BString::from(line)),
+                        PacketLineRef::Flush | PacketLineRef::Delimiter | PacketLineRef::ResponseEnd => break,
+                    }
+                    data = &data[bytes_consumed..];
+                }
+                packetline::decode::Stream::Incomplete { .. } => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "the status report ended in the middle of a packet line",
+                    )
+                    .into());
+                }
+            }
+        }
+    } else {
+        while let Some(line) = reader.readline() {
+            if let Some(line) = line?.map_err(std::io::Error::other)?.as_slice() {
+                lines.push(BString::from(line));
+            }
+        }
+    }
+    Ok(Some(Report::from_lines(lines.iter().map(|line| line.as_slice()))?))
+}
+
+fn setup_remote_progress<'a>(
+    progress: &mut dyn DynNestedProgress,
+    reader: &mut Box<dyn ExtendedBufRead<'a> + Unpin + 'a>,
+    should_interrupt: &'a AtomicBool,
+) {
+    reader.set_progress_handler(Some(Box::new({
+        let mut remote_progress = progress.add_child_with_id("remote".to_string(), ProgressId::RemoteProgress.into());
+        move |is_err: bool, data: &[u8]| {
+            crate::RemoteProgress::translate_to_progress(is_err, data, &mut remote_progress);
+            if should_interrupt.load(Ordering::Relaxed) {
+                std::ops::ControlFlow::Break(())
+            } else {
+                std::ops::ControlFlow::Continue(())
+            }
+        }
+    }) as HandleProgress<'a>));
+}
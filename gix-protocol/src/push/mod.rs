@@ -0,0 +1,30 @@
+/// A module providing low-level primitives to send objects and reference updates to a `git receive-pack` server.
+///
+/// ### Order for sending a pack
+///
+/// * [handshake](crate::handshake()) with [`Service::ReceivePack`](crate::transport::Service::ReceivePack), which
+///   always advertises refs and capabilities in the V1 format
+/// * [push](crate::push()) the [commands](Command) along with a pack containing all objects the server needs
+///   to perform them
+/// * [officially terminate the connection](crate::indicate_end_of_interaction()) only if nothing was pushed,
+///   as the server hangs up after sending its [report](Report).
+///
+/// Note that this flow doesn't involve computing the pack or deciding which references to update, nor does
+/// it update any local references after the server accepted the commands.
+///
+/// Pushing is only supported with blocking clients for now.
+// Note: for ease of use, this is tested end-to-end in `gix` itself. The test-suite here only covers parsing.
+mod types;
+pub use types::*;
+
+///
+pub mod report;
+pub use report::Report;
+
+#[cfg(feature = "blocking-client")]
+mod error;
+#[cfg(feature = "blocking-client")]
+pub use error::Error;
+
+#[cfg(feature = "blocking-client")]
+pub(crate) mod function;
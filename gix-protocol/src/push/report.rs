@@ -0,0 +1,173 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The error returned by [`Report::from_lines()`].
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("The report didn't start with the status of unpacking the pack")]
+    MissingUnpackStatus,
+    #[error("Could not parse line {line:?} of the status report")]
+    MalformedLine { line: BString },
+    #[error("An option was reported before the status of a reference: {line:?}")]
+    OptionWithoutRef { line: BString },
+    #[error("Could not decode the object id in {line:?}")]
+    DecodeId {
+        line: BString,
+        source: gix_hash::decode::Error,
+    },
+}
+
+/// The report of a server after receiving a pack and performing the reference updates of a push,
+/// as sent if the `report-status` or `report-status-v2` capabilities are used.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    /// If set, the server couldn't unpack the pack for the given reason, and no reference was updated.
+    pub unpack_error: Option<BString>,
+    /// The status of each reference the server was asked to update, in the order of the report.
+    pub refs: Vec<Ref>,
+}
+
+/// The status of a single reference update as part of a [`Report`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ref {
+    /// The full name of the reference that was pushed, like `refs/heads/main`.
+    pub name: BString,
+    /// Whether or not the update was performed.
+    pub status: Status,
+    /// With `report-status-v2`, the name of the reference that was actually updated, if the server changed it.
+    pub rewritten_name: Option<BString>,
+    /// With `report-status-v2`, the previous value of the reference that was actually updated, if the server reported it.
+    pub old_id: Option<gix_hash::ObjectId>,
+    /// With `report-status-v2`, the new value of the reference that was actually updated, if the server reported it.
+    pub new_id: Option<gix_hash::ObjectId>,
+    /// With `report-status-v2`, `true` if the server reported that the update wasn't a fast-forward.
+    pub forced_update: bool,
+}
+
+/// Whether or not a reference was updated.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Status {
+    /// The update was performed.
+    Ok,
+    /// The update was refused for the given reason.
+    Rejected(BString),
+}
+
+impl Report {
+    /// Parse the report from all of its `lines`, without the trailing flush packet.
+    ///
+    /// Trailing newlines are ignored.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a [u8]>) -> Result<Self, Error> {
+        let mut lines = lines.into_iter().map(|line| line.strip_suffix(b"\n").unwrap_or(line));
+        let unpack_status = lines
+            .next()
+            .and_then(|line| line.strip_prefix(b"unpack "))
+            .ok_or(Error::MissingUnpackStatus)?;
+        let mut out = Report {
+            unpack_error: (unpack_status != b"ok").then(|| unpack_status.into()),
+            refs: Vec::new(),
+        };
+        for line in lines {
+            let malformed = || Error::MalformedLine { line: line.into() };
+            let (kind, rest) = line.split_once_str(" ").ok_or_else(malformed)?;
+            match kind {
+                b"ok" => out.refs.push(Ref::new(rest.as_bstr(), Status::Ok)),
+                b"ng" => {
+                    let (name, reason) = rest.split_once_str(" ").unwrap_or((rest, b"unknown reason"));
+                    out.refs.push(Ref::new(name.as_bstr(), Status::Rejected(reason.into())));
+                }
+                b"option" => {
+                    let last = out
+                        .refs
+                        .last_mut()
+                        .ok_or_else(|| Error::OptionWithoutRef { line: line.into() })?;
+                    let (key, value) = match rest.split_once_str(" ") {
+                        Some((key, value)) => (key, Some(value)),
+                        None => (rest, None),
+                    };
+                    let decode_id = |value: Option<&[u8]>| {
+                        gix_hash::ObjectId::from_hex(value.ok_or_else(malformed)?).map_err(|err| Error::DecodeId {
+                            line: line.into(),
+                            source: err,
+                        })
+                    };
+                    match key {
+                        b"refname" => last.rewritten_name = Some(value.ok_or_else(malformed)?.into()),
+                        b"old-oid" => last.old_id = Some(decode_id(value)?),
+                        b"new-oid" => last.new_id = Some(decode_id(value)?),
+                        b"forced-update" => last.forced_update = true,
+                        // Be lenient to allow the protocol to evolve.
+                        _ => {}
+                    }
+                }
+                _ => return Err(malformed()),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Serialize this report into the lines [`from_lines()`](Self::from_lines()) parses, each without trailing newline.
+    pub fn to_lines(&self) -> Vec<BString> {
+        let mut out = Vec::with_capacity(self.refs.len() + 1);
+        let mut line = BString::from("unpack ");
+        line.push_str(
+            self.unpack_error
+                .as_deref()
+                .map_or(b"ok".as_bstr(), |err| err.as_bstr()),
+        );
+        out.push(line);
+        for r in &self.refs {
+            let mut line = BString::from(match r.status {
+                Status::Ok => "ok ",
+                Status::Rejected(_) => "ng ",
+            });
+            line.push_str(&r.name);
+            if let Status::Rejected(reason) = &r.status {
+                line.push_byte(b' ');
+                line.push_str(reason);
+            }
+            out.push(line);
+            if let Some(name) = &r.rewritten_name {
+                let mut line = BString::from("option refname ");
+                line.push_str(name);
+                out.push(line);
+            }
+            if let Some(id) = r.old_id {
+                out.push(format!("option old-oid {id}").into());
+            }
+            if let Some(id) = r.new_id {
+                out.push(format!("option new-oid {id}").into());
+            }
+            if r.forced_update {
+                out.push("option forced-update".into());
+            }
+        }
+        out
+    }
+
+    /// Return `true` if the pack was unpacked and all references were updated.
+    pub fn is_success(&self) -> bool {
+        self.unpack_error.is_none() && self.refs.iter().all(|r| r.status == Status::Ok)
+    }
+
+    /// Return the status of the reference named `name`, if it was reported.
+    pub fn find(&self, name: &BStr) -> Option<&Ref> {
+        self.refs.iter().find(|r| r.name == name)
+    }
+}
+
+impl Ref {
+    fn new(name: &BStr, status: Status) -> Self {
+        Ref {
+            name: name.into(),
+            status,
+            rewritten_name: None,
+            old_id: None,
+            new_id: None,
+            forced_update: false,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use bstr::{BString, ByteVec};
+
+/// A reference update for the server to perform, as sent in a single command line.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Command {
+    /// The object the remote reference is expected to point to, or the null id if it is expected to not exist.
+    ///
+    /// The server refuses to perform the update if this doesn't match.
+    pub old: gix_hash::ObjectId,
+    /// The object the remote reference should point to, or the null id if it should be deleted.
+    pub new: gix_hash::ObjectId,
+    /// The full name of the reference on the remote, like `refs/heads/main`.
+    pub name: BString,
+}
+
+impl Command {
+    /// Return `true` if this command creates a reference.
+    pub fn is_create(&self) -> bool {
+        self.old.is_null() && !self.new.is_null()
+    }
+
+    /// Return `true` if this command deletes a reference.
+    pub fn is_delete(&self) -> bool {
+        self.new.is_null()
+    }
+
+    /// Serialize this command as it's sent to the server, like `<old> <new> <name>`.
+    pub fn to_bstring(&self) -> BString {
+        let mut out = BString::from(format!("{} {} ", self.old, self.new));
+        out.push_str(&self.name);
+        out
+    }
+}
+
+/// Options for use in [`push()`](crate::push()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If `true`, ask the server to either perform all reference updates or none of them.
+    ///
+    /// The server must support the `atomic` capability.
+    pub atomic: bool,
+    /// Strings to pass to the hooks of the server, similar to `git push --push-option`.
+    ///
+    /// If not empty, the server must support the `push-options` capability.
+    pub push_options: Vec<BString>,
+}
+
+/// For use in [`push()`](crate::push()).
+#[cfg(feature = "handshake")]
+pub struct Context<'a, T> {
+    /// The outcome of the handshake performed with the remote, which must have been for the `receive-pack` service.
+    pub handshake: &'a crate::Handshake,
+    /// The transport to send the commands and the pack with.
+    pub transport: &'a mut T,
+    /// How to self-identify during the `push` call.
+    ///
+    /// This could be read from the `gitoxide.userAgent` configuration variable.
+    pub user_agent: crate::command::Feature,
+    /// If `true`, output all packetlines using the `gix-trace` machinery.
+    pub trace_packetlines: bool,
+}
+
+/// The progress ids used during various steps of the push operation.
+///
+/// Note that tagged progress isn't very widely available yet, but support can be improved as needed.
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// The progress name is defined by the remote and the progress messages it sets, along with their progress values and limits.
+    RemoteProgress,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::RemoteProgress => *b"PURP",
+        }
+    }
+}
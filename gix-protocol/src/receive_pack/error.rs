@@ -0,0 +1,26 @@
+/// The error returned by [`receive_pack()`](crate::receive_pack()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("Failed to communicate with the client")]
+    Io(#[from] std::io::Error),
+    #[error("Could not decode a packet line sent by the client")]
+    DecodePacketline(#[from] crate::transport::packetline::decode::Error),
+    #[error("Failed to obtain the references to advertise")]
+    Refs(#[source] crate::receive_pack::DelegateError),
+    #[error("Could not parse the command {line:?}")]
+    MalformedCommand { line: bstr::BString },
+    #[error("The client uses the object format {client:?}, but we use {server}")]
+    ObjectFormatMismatch {
+        server: gix_hash::Kind,
+        client: bstr::BString,
+    },
+    #[error("The client ended the interaction prematurely")]
+    UnexpectedEof,
+    #[error("Failed to receive the pack")]
+    ReceivePack(#[source] crate::receive_pack::DelegateError),
+    #[error("Failed to update the references")]
+    UpdateRefs(#[source] crate::receive_pack::DelegateError),
+    #[error("Expected the status of {expected} commands, but got {actual}")]
+    StatusCount { expected: usize, actual: usize },
+}
@@ -0,0 +1,305 @@
+use std::{
+    io::{Read, Write},
+    sync::atomic::AtomicBool,
+};
+
+use bstr::{BString, ByteSlice, ByteVec};
+use gix_hash::ObjectId;
+
+use crate::{
+    push::{Command, Report, report},
+    receive_pack::{Delegate, Error, Options, Update},
+    transport::packetline::{
+        Channel, PacketLineRef,
+        blocking_io::{StreamingPeekableIter, encode},
+    },
+};
+
+/// The largest amount of data a single sideband packet line can hold.
+const MAX_BAND_DATA_LEN: usize = 65515;
+
+/// Serve the `receive-pack` service to a client, reading its commands and pack from `read` and writing all responses
+/// to `write`, with `delegate` providing access to the repository.
+///
+/// After advertising all references, the commands of the client are received along with the pack, which is passed
+/// to [`Delegate::receive_pack()`] before [`Delegate::update_refs()`] performs the commands.
+/// The outcome is then sent to the client if it asked for a status report.
+/// `should_interrupt` is passed to [`Delegate::receive_pack()`].
+///
+/// Errors are reported to the client before they are returned, if possible, which also happens if the pack couldn't be
+/// received or references couldn't be updated.
+///
+/// Note that `read` must not contain the initial request that is sent to a `git` daemon.
+pub fn receive_pack(
+    read: impl Read,
+    mut write: impl Write,
+    delegate: &mut impl Delegate,
+    should_interrupt: &AtomicBool,
+    Options {
+        user_agent,
+        advertise_push_options,
+        trace_packetlines,
+    }: Options,
+) -> Result<(), Error> {
+    let _span = gix_trace::coarse!("gix_protocol::receive_pack()");
+    let object_hash = delegate.object_hash();
+    let refs = match delegate.refs() {
+        Ok(refs) => refs,
+        Err(err) => return fail(&mut write, Error::Refs(err)),
+    };
+
+    let mut capabilities = BString::from("report-status delete-refs side-band-64k quiet atomic ofs-delta");
+    if advertise_push_options {
+        capabilities.push_str(" push-options");
+    }
+    capabilities.push_str(format!(" object-format={object_hash} "));
+    match user_agent {
+        (name, Some(value)) => capabilities.push_str(format!("{name}={value}")),
+        (name, None) => capabilities.push_str(name),
+    }
+    let mut refs = refs.into_iter();
+    // Without references, a dummy is needed to transmit the capabilities.
+    let (name, id) = refs
+        .next()
+        .unwrap_or_else(|| ("capabilities^{}".into(), object_hash.null()));
+    let mut line = BString::from(format!("{id} "));
+    line.push_str(name);
+    line.push_byte(0);
+    line.push_str(capabilities);
+    encode::text_to_write(&line, &mut write)?;
+    for (name, id) in refs {
+        let mut line = BString::from(format!("{id} "));
+        line.push_str(name);
+        encode::text_to_write(&line, &mut write)?;
+    }
+    encode::flush_to_write(&mut write)?;
+    write.flush()?;
+
+    let mut lines = StreamingPeekableIter::new(read, &[], trace_packetlines);
+    let mut commands = Vec::new();
+    let mut features = Features::default();
+    loop {
+        let line = match read_line(&mut lines)? {
+            Some(Some(line)) => line,
+            Some(None) => break,
+            None if commands.is_empty() => return Ok(()),
+            None => return Err(Error::UnexpectedEof),
+        };
+        let command = match line.find_byte(0) {
+            Some(pos) if commands.is_empty() => {
+                features = match Features::parse(&line[pos + 1..], object_hash) {
+                    Ok(features) => features,
+                    Err(err) => return fail(&mut write, err),
+                };
+                &line[..pos]
+            }
+            _ => line.as_slice(),
+        };
+        match parse_command(command, object_hash) {
+            Some(command) => commands.push(command),
+            None => return fail(&mut write, Error::MalformedCommand { line }),
+        }
+    }
+    if commands.is_empty() {
+        return Ok(());
+    }
+
+    let mut push_options = Vec::new();
+    if features.push_options {
+        loop {
+            match read_line(&mut lines)? {
+                Some(Some(option)) => push_options.push(option),
+                Some(None) => break,
+                None => return Err(Error::UnexpectedEof),
+            }
+        }
+    }
+
+    let unpack = if commands.iter().all(Command::is_delete) {
+        Ok(())
+    } else {
+        let mut read = lines.into_inner();
+        let mut pack = std::io::BufReader::new(&mut read);
+        let mut band = Band {
+            channel: Channel::Progress,
+            out: &mut write,
+        };
+        let mut no_progress = std::io::sink();
+        delegate.receive_pack(
+            &mut pack,
+            &commands,
+            if features.side_band && !features.quiet {
+                &mut band
+            } else {
+                &mut no_progress
+            },
+            should_interrupt,
+        )
+    };
+
+    let (unpack_error, statuses, err) = match unpack {
+        Ok(()) => match delegate.update_refs(
+            &commands,
+            Update {
+                atomic: features.atomic,
+                push_options: &push_options,
+            },
+        ) {
+            Ok(statuses) if statuses.len() == commands.len() => (None, statuses, None),
+            Ok(statuses) => (
+                None,
+                vec![report::Status::Rejected("internal error".into()); commands.len()],
+                Some(Error::StatusCount {
+                    expected: commands.len(),
+                    actual: statuses.len(),
+                }),
+            ),
+            Err(err) => (
+                None,
+                vec![report::Status::Rejected(err.to_string().into()); commands.len()],
+                Some(Error::UpdateRefs(err)),
+            ),
+        },
+        Err(err) => (
+            Some(err.to_string().into()),
+            vec![report::Status::Rejected("unpacker error".into()); commands.len()],
+            Some(Error::ReceivePack(err)),
+        ),
+    };
+
+    if features.report_status {
+        let report = Report {
+            unpack_error,
+            refs: commands
+                .into_iter()
+                .zip(statuses)
+                .map(|(command, status)| report::Ref {
+                    name: command.name,
+                    status,
+                    rewritten_name: None,
+                    old_id: None,
+                    new_id: None,
+                    forced_update: false,
+                })
+                .collect(),
+        };
+        let mut buf = Vec::new();
+        for line in report.to_lines() {
+            encode::text_to_write(&line, &mut buf)?;
+        }
+        encode::flush_to_write(&mut buf)?;
+        if features.side_band {
+            Band {
+                channel: Channel::Data,
+                out: &mut write,
+            }
+            .write_all(&buf)?;
+        } else {
+            write.write_all(&buf)?;
+        }
+    }
+    if features.side_band {
+        encode::flush_to_write(&mut write)?;
+    }
+    write.flush()?;
+    err.map_or(Ok(()), Err)
+}
+
+/// The capabilities the client asked for.
+#[derive(Default)]
+struct Features {
+    report_status: bool,
+    side_band: bool,
+    quiet: bool,
+    atomic: bool,
+    push_options: bool,
+}
+
+impl Features {
+    fn parse(capabilities: &[u8], object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let mut out = Features::default();
+        for capability in capabilities.split_str(" ") {
+            match capability {
+                b"report-status" => out.report_status = true,
+                b"side-band-64k" => out.side_band = true,
+                b"quiet" => out.quiet = true,
+                b"atomic" => out.atomic = true,
+                b"push-options" => out.push_options = true,
+                _ => {
+                    if let Some(format) = capability.strip_prefix(b"object-format=") {
+                        if format != object_hash.to_string().as_bytes() {
+                            return Err(Error::ObjectFormatMismatch {
+                                server: object_hash,
+                                client: format.into(),
+                            });
+                        }
+                    }
+                    // Like `git`, ignore everything we don't know to allow the protocol to evolve.
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Parse a command line like `<old> <new> <name>`.
+fn parse_command(line: &[u8], object_hash: gix_hash::Kind) -> Option<Command> {
+    let mut tokens = line.splitn_str(3, " ");
+    let mut id = || {
+        ObjectId::from_hex(tokens.next()?)
+            .ok()
+            .filter(|id| id.kind() == object_hash)
+    };
+    let (old, new) = (id()?, id()?);
+    let name = tokens.next().filter(|name| !name.is_empty())?;
+    Some(Command {
+        old,
+        new,
+        name: name.into(),
+    })
+}
+
+/// A writer to send everything written to it on `channel`.
+struct Band<W> {
+    channel: Channel,
+    out: W,
+}
+
+impl<W: Write> Write for Band<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for chunk in buf.chunks(MAX_BAND_DATA_LEN) {
+            encode::band_to_write(self.channel, chunk, &mut self.out)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Send `err` to the client as `ERR` packet line and return it.
+fn fail(mut write: impl Write, err: Error) -> Result<(), Error> {
+    encode::error_to_write(format!("receive-pack: {err}").as_bytes(), &mut write)
+        .and_then(|_| write.flush())
+        .ok();
+    Err(err)
+}
+
+/// Read the next line, returning `Some(None)` on flush and `None` if the client hung up.
+fn read_line(lines: &mut StreamingPeekableIter<impl Read>) -> Result<Option<Option<BString>>, Error> {
+    Ok(match lines.read_line() {
+        None => None,
+        Some(Err(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => None,
+        Some(Err(err)) => return Err(err.into()),
+        Some(Ok(line)) => match line? {
+            PacketLineRef::Data(data) => Some(Some(data.strip_suffix(b"\n").unwrap_or(data).into())),
+            PacketLineRef::Flush => Some(None),
+            PacketLineRef::Delimiter | PacketLineRef::ResponseEnd => {
+                return Err(Error::MalformedCommand {
+                    line: "<special packet line>".into(),
+                });
+            }
+        },
+    })
+}
@@ -0,0 +1,21 @@
+//! The server side of the `receive-pack` service, accepting packs and reference updates pushed by clients.
+//!
+//! Generally, there is the following order of operations.
+//!
+//! * obtain a `read` and `write` end to communicate with the client, like the standard input and output of a process
+//!   or a TCP connection after the initial request of the client was read.
+//! * implement a [`Delegate`] to provide references, receive packs and update references, usually backed by a repository.
+//! * call [`receive_pack()`](crate::receive_pack()) to advertise all references, receive the commands and the pack of the client
+//!   and report the outcome.
+//!
+//! Note that the protocol version is always V0, as `receive-pack` isn't available in protocol V2.
+mod types;
+pub use types::*;
+
+#[cfg(feature = "receive-pack")]
+mod error;
+#[cfg(feature = "receive-pack")]
+pub use error::Error;
+
+#[cfg(feature = "receive-pack")]
+pub(crate) mod function;
@@ -0,0 +1,62 @@
+use bstr::BString;
+use gix_hash::ObjectId;
+
+use crate::push::{Command, report::Status};
+
+/// The options for use in [`receive_pack()`](crate::receive_pack()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The name and value of the agent to advertise, with the name usually being `agent`.
+    pub user_agent: crate::command::Feature,
+    /// If `true`, advertise the `push-options` capability to allow clients to send options along with their commands.
+    pub advertise_push_options: bool,
+    /// If `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    pub trace_packetlines: bool,
+}
+
+/// Everything the client sent along with its commands, which affects how references are updated.
+#[derive(Debug, Clone, Copy)]
+pub struct Update<'a> {
+    /// If `true`, either all commands must be performed, or none of them.
+    pub atomic: bool,
+    /// The push options sent by the client, like `git push --push-option`.
+    pub push_options: &'a [BString],
+}
+
+/// The error type used by [`Delegate`] implementations.
+pub type DelegateError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Provide everything [`receive_pack()`](crate::receive_pack()) needs to know about a repository, and to change it.
+pub trait Delegate {
+    /// The kind of hash used by the repository.
+    fn object_hash(&self) -> gix_hash::Kind;
+
+    /// Return the full name and the object id of all references to advertise, which clients use to determine the objects to send
+    /// and the expected previous value of the references they update.
+    ///
+    /// Symbolic references like `HEAD` are usually not advertised.
+    fn refs(&mut self) -> Result<Vec<(BString, ObjectId)>, DelegateError>;
+
+    /// Read a pack with all objects needed to perform `commands` from `pack`, writing human-readable progress messages
+    /// to `progress`.
+    ///
+    /// The received objects should not become visible to other users of the repository until
+    /// [`update_refs()`](Self::update_refs()) accepts the commands. It's also the place to assure that all objects
+    /// needed by the commands are present.
+    ///
+    /// It's only called if at least one of `commands` doesn't delete a reference.
+    /// The operation should stop if `should_interrupt` is `true`.
+    fn receive_pack(
+        &mut self,
+        pack: &mut dyn std::io::BufRead,
+        commands: &[Command],
+        progress: &mut dyn std::io::Write,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<(), DelegateError>;
+
+    /// Perform `commands` as requested by `update`, and return the status of each one of them in order.
+    ///
+    /// This is the place to refuse commands, for instance to enforce policies, before any reference is changed.
+    /// It's called once the pack was received, or if all commands delete references.
+    fn update_refs(&mut self, commands: &[Command], update: Update<'_>) -> Result<Vec<Status>, DelegateError>;
+}
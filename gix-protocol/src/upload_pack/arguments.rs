@@ -0,0 +1,201 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+
+/// The error returned when parsing [`Fetch`] or [`LsRefs`] arguments.
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("Unknown argument {argument:?}")]
+    Unknown { argument: BString },
+    #[error("Could not decode the object id in argument {argument:?}")]
+    DecodeId {
+        argument: BString,
+        source: gix_hash::decode::Error,
+    },
+    #[error("Argument {argument:?} doesn't have a valid numeric value")]
+    InvalidNumber { argument: BString },
+    #[error("The filter specification {spec:?} isn't supported")]
+    UnsupportedFilter { spec: BString },
+    #[error("Argument {argument:?} can't be combined with the previous deepen arguments")]
+    ConflictingDeepen { argument: BString },
+}
+
+/// A way to limit the history sent to the client, as requested with one of the `deepen` arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Deepen {
+    /// Send no more than `depth` commits along each line of history, as in `deepen <depth>`.
+    Depth(u32),
+    /// Don't send commits that are older than the given time, as in `deepen-since <timestamp>`.
+    Since(gix_date::SecondsSinceUnixEpoch),
+    /// Don't send commits reachable from any of the given references, as in `deepen-not <ref>`.
+    Not(Vec<BString>),
+}
+
+/// A way to omit objects from the pack, as requested with `filter <spec>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// Omit all blobs, as in `blob:none`.
+    BlobNone,
+    /// Omit all blobs larger than the given amount of bytes, as in `blob:limit=<n>[kmg]`.
+    BlobLimit(u64),
+    /// Omit all trees and blobs deeper than the given depth, with `0` omitting all of them, as in `tree:<depth>`.
+    TreeDepth(u64),
+}
+
+impl Filter {
+    /// Parse a filter specification like `blob:none`, or return `None` if it isn't supported.
+    pub fn from_spec(spec: &BStr) -> Option<Self> {
+        if spec == "blob:none" {
+            return Some(Filter::BlobNone);
+        }
+        if let Some(limit) = spec.strip_prefix(b"blob:limit=") {
+            let (number, factor) = match limit.last()? {
+                b'k' | b'K' => (&limit[..limit.len() - 1], 1024),
+                b'm' | b'M' => (&limit[..limit.len() - 1], 1024 * 1024),
+                b'g' | b'G' => (&limit[..limit.len() - 1], 1024 * 1024 * 1024),
+                _ => (limit, 1),
+            };
+            let number: u64 = number.to_str().ok()?.parse().ok()?;
+            return number.checked_mul(factor).map(Filter::BlobLimit);
+        }
+        let depth = spec.strip_prefix(b"tree:")?;
+        depth.to_str().ok()?.parse().ok().map(Filter::TreeDepth)
+    }
+}
+
+/// The arguments of an `ls-refs` command.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LsRefs {
+    /// If `true`, show the target of symbolic references.
+    pub symrefs: bool,
+    /// If `true`, show the object annotated tags point to.
+    pub peel: bool,
+    /// If `true`, show symbolic references whose target doesn't exist yet, like `HEAD` in a new repository.
+    pub unborn: bool,
+    /// Only show references starting with one of these prefixes, or all of them if empty.
+    pub ref_prefixes: Vec<BString>,
+}
+
+impl LsRefs {
+    /// Parse `lines` of arguments, each without trailing newline.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a [u8]>) -> Result<Self, Error> {
+        let mut out = LsRefs::default();
+        for line in lines {
+            match line {
+                b"symrefs" => out.symrefs = true,
+                b"peel" => out.peel = true,
+                b"unborn" => out.unborn = true,
+                _ => match line.strip_prefix(b"ref-prefix ") {
+                    Some(prefix) => out.ref_prefixes.push(prefix.into()),
+                    None => return Err(Error::Unknown { argument: line.into() }),
+                },
+            }
+        }
+        Ok(out)
+    }
+
+    /// Return `true` if a reference with `name` should be shown.
+    pub fn matches(&self, name: &BStr) -> bool {
+        self.ref_prefixes.is_empty() || self.ref_prefixes.iter().any(|prefix| name.starts_with(prefix))
+    }
+}
+
+/// The arguments of a `fetch` command.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Fetch {
+    /// The objects the client wants.
+    pub wants: Vec<ObjectId>,
+    /// The objects the client has, sent to find the objects both sides have in common.
+    pub haves: Vec<ObjectId>,
+    /// If `true`, the negotiation is over and the pack should be sent.
+    pub done: bool,
+    /// If `true`, the pack may contain deltas against objects the client has, but that aren't part of the pack.
+    pub thin_pack: bool,
+    /// If `true`, don't send progress information.
+    pub no_progress: bool,
+    /// If `true`, also send annotated tags pointing to objects that are sent.
+    pub include_tag: bool,
+    /// If `true`, the pack may contain deltas that refer to their base object by offset.
+    pub ofs_delta: bool,
+    /// The commits the client has without their parents, as it's a shallow repository.
+    pub shallow: Vec<ObjectId>,
+    /// The way the history to send should be limited, if at all.
+    pub deepen: Option<Deepen>,
+    /// If `true`, the [`Deepen::Depth`] is relative to the current shallow boundary of the client.
+    pub deepen_relative: bool,
+    /// The filter to omit objects from the pack, if set.
+    pub filter: Option<Filter>,
+    /// If `true`, all sections of the response should be sent using sidebands.
+    pub sideband_all: bool,
+    /// If `true`, the pack should only be sent once the client says it's `done`.
+    pub wait_for_done: bool,
+}
+
+impl Fetch {
+    /// Parse `lines` of arguments, each without trailing newline.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a [u8]>) -> Result<Self, Error> {
+        let mut out = Fetch::default();
+        for line in lines {
+            let flag = match line {
+                b"done" => Some(&mut out.done),
+                b"thin-pack" => Some(&mut out.thin_pack),
+                b"no-progress" => Some(&mut out.no_progress),
+                b"include-tag" => Some(&mut out.include_tag),
+                b"ofs-delta" => Some(&mut out.ofs_delta),
+                b"deepen-relative" => Some(&mut out.deepen_relative),
+                b"sideband-all" => Some(&mut out.sideband_all),
+                b"wait-for-done" => Some(&mut out.wait_for_done),
+                _ => None,
+            };
+            if let Some(flag) = flag {
+                *flag = true;
+                continue;
+            }
+
+            let (name, value) = line
+                .split_once_str(b" ")
+                .ok_or_else(|| Error::Unknown { argument: line.into() })?;
+            let id = || {
+                ObjectId::from_hex(value).map_err(|source| Error::DecodeId {
+                    argument: line.into(),
+                    source,
+                })
+            };
+            match name {
+                b"want" => out.wants.push(id()?),
+                b"have" => out.haves.push(id()?),
+                b"shallow" => out.shallow.push(id()?),
+                b"deepen" => out.set_deepen(Deepen::Depth(number(value, line)?), line)?,
+                b"deepen-since" => out.set_deepen(Deepen::Since(number(value, line)?), line)?,
+                b"deepen-not" => match &mut out.deepen {
+                    Some(Deepen::Not(refs)) => refs.push(value.into()),
+                    _ => out.set_deepen(Deepen::Not(vec![value.into()]), line)?,
+                },
+                b"filter" => {
+                    out.filter = Some(
+                        Filter::from_spec(value.as_bstr())
+                            .ok_or_else(|| Error::UnsupportedFilter { spec: value.into() })?,
+                    );
+                }
+                _ => return Err(Error::Unknown { argument: line.into() }),
+            }
+        }
+        Ok(out)
+    }
+
+    fn set_deepen(&mut self, deepen: Deepen, line: &[u8]) -> Result<(), Error> {
+        if self.deepen.is_some() {
+            return Err(Error::ConflictingDeepen { argument: line.into() });
+        }
+        self.deepen = Some(deepen);
+        Ok(())
+    }
+}
+
+fn number<T: std::str::FromStr>(value: &[u8], line: &[u8]) -> Result<T, Error> {
+    value
+        .to_str()
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| Error::InvalidNumber { argument: line.into() })
+}
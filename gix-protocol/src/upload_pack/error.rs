@@ -0,0 +1,35 @@
+/// The error returned by [`upload_pack()`](crate::upload_pack()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("Failed to communicate with the client")]
+    Io(#[from] std::io::Error),
+    #[error("Could not decode a packet line sent by the client")]
+    DecodePacketline(#[from] crate::transport::packetline::decode::Error),
+    #[error("Expected a command, but got {line:?}")]
+    ExpectedCommand { line: bstr::BString },
+    #[error("Unknown command {command:?}")]
+    UnknownCommand { command: bstr::BString },
+    #[error("Client sent unknown capability {capability:?}")]
+    UnknownCapability { capability: bstr::BString },
+    #[error("The client uses the object format {client:?}, but we use {server}")]
+    ObjectFormatMismatch {
+        server: gix_hash::Kind,
+        client: bstr::BString,
+    },
+    #[error("The client ended the {command} command prematurely")]
+    UnexpectedEof { command: &'static str },
+    #[error("Could not parse the arguments of the {command} command")]
+    Arguments {
+        command: &'static str,
+        source: crate::upload_pack::arguments::Error,
+    },
+    #[error("The client wants {id}, which doesn't exist")]
+    NotOurRef { id: gix_hash::ObjectId },
+    #[error("Failed to obtain the references to advertise")]
+    Refs(#[source] crate::upload_pack::DelegateError),
+    #[error("Failed to compute the shallow boundary")]
+    Shallow(#[source] crate::upload_pack::DelegateError),
+    #[error("Failed to write the pack")]
+    WritePack(#[source] crate::upload_pack::DelegateError),
+}
@@ -0,0 +1,372 @@
+use std::{
+    cell::RefCell,
+    io::{Read, Write},
+    sync::atomic::AtomicBool,
+};
+
+use bstr::{BString, ByteVec};
+
+use crate::{
+    Command,
+    handshake::Ref,
+    transport::packetline::{
+        Channel, PacketLineRef,
+        blocking_io::{StreamingPeekableIter, encode},
+    },
+    upload_pack::{Delegate, Error, Options, Pack, arguments},
+};
+
+/// The largest amount of data a single sideband packet line can hold.
+const MAX_BAND_DATA_LEN: usize = 65515;
+
+/// Serve the `upload-pack` service to a client using protocol V2, reading its requests from `read` and writing all responses
+/// to `write`, with `delegate` providing access to the repository.
+///
+/// After advertising our capabilities, `ls-refs` and `fetch` commands are answered until the client ends the interaction
+/// with a flush packet or by closing `read`.
+/// `should_interrupt` is passed to [`Delegate::write_pack()`].
+///
+/// Errors are reported to the client before they are returned, if possible.
+///
+/// Note that `read` must not contain the initial request that is sent to a `git` daemon.
+pub fn upload_pack(
+    read: impl Read,
+    mut write: impl Write,
+    delegate: &mut impl Delegate,
+    should_interrupt: &AtomicBool,
+    Options {
+        user_agent,
+        trace_packetlines,
+    }: Options,
+) -> Result<(), Error> {
+    let _span = gix_trace::coarse!("gix_protocol::upload_pack()");
+    let object_hash = delegate.object_hash();
+    for line in [
+        "version 2".into(),
+        match user_agent {
+            (name, Some(value)) => format!("{name}={value}"),
+            (name, None) => name.to_owned(),
+        },
+        "ls-refs=unborn".into(),
+        "fetch=shallow filter".into(),
+        "server-option".into(),
+        format!("object-format={object_hash}"),
+    ] {
+        encode::text_to_write(line.as_bytes(), &mut write)?;
+    }
+    encode::flush_to_write(&mut write)?;
+    write.flush()?;
+
+    let mut lines = StreamingPeekableIter::new(read, &[], trace_packetlines);
+    loop {
+        let command = match read_line(&mut lines)? {
+            Line::Eof | Line::Flush => return Ok(()),
+            Line::Data(line) => match line.strip_prefix(b"command=") {
+                Some(b"ls-refs") => Command::LsRefs,
+                Some(b"fetch") => Command::Fetch,
+                Some(command) => {
+                    return fail(
+                        &mut write,
+                        Error::UnknownCommand {
+                            command: command.into(),
+                        },
+                    );
+                }
+                None => return fail(&mut write, Error::ExpectedCommand { line }),
+            },
+            Line::Delimiter | Line::ResponseEnd => {
+                return fail(
+                    &mut write,
+                    Error::ExpectedCommand {
+                        line: "<special packet line>".into(),
+                    },
+                );
+            }
+        };
+
+        let mut has_arguments = false;
+        loop {
+            match read_line(&mut lines)? {
+                Line::Data(capability) => {
+                    if let Some(format) = capability.strip_prefix(b"object-format=") {
+                        if format != object_hash.to_string().as_bytes() {
+                            return fail(
+                                &mut write,
+                                Error::ObjectFormatMismatch {
+                                    server: object_hash,
+                                    client: format.into(),
+                                },
+                            );
+                        }
+                    } else if !(capability.starts_with(b"agent=") || capability.starts_with(b"server-option=")) {
+                        return fail(&mut write, Error::UnknownCapability { capability });
+                    }
+                }
+                Line::Delimiter => {
+                    has_arguments = true;
+                    break;
+                }
+                Line::Flush => break,
+                Line::Eof | Line::ResponseEnd => {
+                    return Err(Error::UnexpectedEof {
+                        command: command.as_str(),
+                    });
+                }
+            }
+        }
+        let mut args = Vec::new();
+        if has_arguments {
+            loop {
+                match read_line(&mut lines)? {
+                    Line::Data(arg) => args.push(arg),
+                    Line::Flush => break,
+                    Line::Delimiter | Line::ResponseEnd | Line::Eof => {
+                        return Err(Error::UnexpectedEof {
+                            command: command.as_str(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let res = match command {
+            Command::LsRefs => ls_refs(&args, &mut write, delegate),
+            Command::Fetch => fetch(&args, &mut write, delegate, should_interrupt),
+        };
+        match res {
+            Ok(()) => write.flush()?,
+            Err(Failure::Unreported(err)) => return fail(&mut write, err),
+            Err(Failure::Reported(err)) => return Err(err),
+        }
+    }
+}
+
+enum Failure {
+    /// The error wasn't sent to the client yet, which can be done with an `ERR` packet line as no sideband is in use.
+    Unreported(Error),
+    /// The error was already sent to the client, or couldn't be sent.
+    Reported(Error),
+}
+
+impl From<std::io::Error> for Failure {
+    fn from(err: std::io::Error) -> Self {
+        Failure::Reported(err.into())
+    }
+}
+
+fn ls_refs(args: &[BString], mut write: impl Write, delegate: &mut impl Delegate) -> Result<(), Failure> {
+    let args = arguments::LsRefs::from_lines(args.iter().map(|arg| arg.as_slice())).map_err(|source| {
+        Failure::Unreported(Error::Arguments {
+            command: Command::LsRefs.as_str(),
+            source,
+        })
+    })?;
+    let refs = delegate.refs().map_err(|err| Failure::Unreported(Error::Refs(err)))?;
+    for r in refs {
+        let line: BString = match r {
+            Ref::Direct { full_ref_name, object } => {
+                if !args.matches(full_ref_name.as_ref()) {
+                    continue;
+                }
+                format!("{object} {full_ref_name}").into()
+            }
+            Ref::Peeled {
+                full_ref_name,
+                tag,
+                object,
+            } => {
+                if !args.matches(full_ref_name.as_ref()) {
+                    continue;
+                }
+                let mut line: BString = format!("{tag} {full_ref_name}").into();
+                if args.peel {
+                    line.push_str(format!(" peeled:{object}"));
+                }
+                line
+            }
+            Ref::Symbolic {
+                full_ref_name,
+                target,
+                tag,
+                object,
+            } => {
+                if !args.matches(full_ref_name.as_ref()) {
+                    continue;
+                }
+                let mut line: BString = format!("{} {full_ref_name}", tag.unwrap_or(object)).into();
+                if args.symrefs {
+                    line.push_str(" symref-target:");
+                    line.push_str(&target);
+                }
+                if args.peel && tag.is_some() {
+                    line.push_str(format!(" peeled:{object}"));
+                }
+                line
+            }
+            Ref::Unborn { full_ref_name, target } => {
+                if !args.unborn || !args.matches(full_ref_name.as_ref()) {
+                    continue;
+                }
+                let mut line: BString = format!("unborn {full_ref_name}").into();
+                if args.symrefs {
+                    line.push_str(" symref-target:");
+                    line.push_str(&target);
+                }
+                line
+            }
+        };
+        encode::text_to_write(&line, &mut write)?;
+    }
+    encode::flush_to_write(&mut write)?;
+    Ok(())
+}
+
+fn fetch(
+    args: &[BString],
+    mut write: impl Write,
+    delegate: &mut impl Delegate,
+    should_interrupt: &AtomicBool,
+) -> Result<(), Failure> {
+    let args = arguments::Fetch::from_lines(args.iter().map(|arg| arg.as_slice())).map_err(|source| {
+        Failure::Unreported(Error::Arguments {
+            command: Command::Fetch.as_str(),
+            source,
+        })
+    })?;
+    if let Some(id) = args.wants.iter().find(|id| !delegate.contains(id)) {
+        return Err(Failure::Unreported(Error::NotOurRef { id: *id }));
+    }
+    let common: Vec<_> = args.haves.iter().filter(|id| delegate.contains(id)).copied().collect();
+
+    if !args.done {
+        encode::text_to_write(b"acknowledgments", &mut write)?;
+        if common.is_empty() {
+            encode::text_to_write(b"NAK", &mut write)?;
+        }
+        for id in &common {
+            encode::text_to_write(format!("ACK {id}").as_bytes(), &mut write)?;
+        }
+        if args.wait_for_done || !delegate.is_ready(&args.wants, &common) {
+            encode::flush_to_write(&mut write)?;
+            return Ok(());
+        }
+        encode::text_to_write(b"ready", &mut write)?;
+        encode::delim_to_write(&mut write)?;
+    }
+
+    let mut shallow = Vec::new();
+    if args.deepen.is_some() || !args.shallow.is_empty() {
+        let info = delegate
+            .shallow(&args)
+            .map_err(|err| Failure::Unreported(Error::Shallow(err)))?;
+        encode::text_to_write(b"shallow-info", &mut write)?;
+        for id in &info.shallow {
+            encode::text_to_write(format!("shallow {id}").as_bytes(), &mut write)?;
+        }
+        for id in &info.unshallow {
+            encode::text_to_write(format!("unshallow {id}").as_bytes(), &mut write)?;
+        }
+        encode::delim_to_write(&mut write)?;
+        shallow = info.shallow;
+        shallow.extend(args.shallow.iter().filter(|id| !info.unshallow.contains(id)));
+    }
+
+    encode::text_to_write(b"packfile", &mut write)?;
+    let write = RefCell::new(write);
+    let res = {
+        let mut out = std::io::BufWriter::with_capacity(
+            MAX_BAND_DATA_LEN,
+            Band {
+                channel: Channel::Data,
+                out: &write,
+            },
+        );
+        let mut progress = Band {
+            channel: Channel::Progress,
+            out: &write,
+        };
+        let mut no_progress = std::io::sink();
+        delegate
+            .write_pack(
+                Pack {
+                    arguments: &args,
+                    common: &common,
+                    shallow: &shallow,
+                },
+                &mut out,
+                if args.no_progress {
+                    &mut no_progress
+                } else {
+                    &mut progress
+                },
+                should_interrupt,
+            )
+            .map_err(Error::WritePack)
+            .and_then(|()| out.flush().map_err(Into::into))
+    };
+    let mut write = write.into_inner();
+    if let Err(err) = res {
+        send_error_on_band(&mut write, &err);
+        return Err(Failure::Reported(err));
+    }
+    encode::flush_to_write(&mut write)?;
+    Ok(())
+}
+
+/// A writer to send everything written to it on `channel`.
+struct Band<'a, W> {
+    channel: Channel,
+    out: &'a RefCell<W>,
+}
+
+impl<W: Write> Write for Band<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut out = self.out.borrow_mut();
+        for chunk in buf.chunks(MAX_BAND_DATA_LEN) {
+            encode::band_to_write(self.channel, chunk, &mut *out)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.out.borrow_mut().flush()
+    }
+}
+
+fn send_error_on_band(mut write: impl Write, err: &Error) {
+    let message = format!("error: {err}\n");
+    encode::band_to_write(Channel::Error, message.as_bytes(), &mut write)
+        .and_then(|_| encode::flush_to_write(&mut write))
+        .and_then(|_| write.flush())
+        .ok();
+}
+
+/// Send `err` to the client as `ERR` packet line and return it.
+fn fail(mut write: impl Write, err: Error) -> Result<(), Error> {
+    encode::error_to_write(format!("upload-pack: {err}").as_bytes(), &mut write)
+        .and_then(|_| write.flush())
+        .ok();
+    Err(err)
+}
+
+enum Line {
+    Data(BString),
+    Flush,
+    Delimiter,
+    ResponseEnd,
+    Eof,
+}
+
+fn read_line(lines: &mut StreamingPeekableIter<impl Read>) -> Result<Line, Error> {
+    Ok(match lines.read_line() {
+        None => Line::Eof,
+        Some(Err(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => Line::Eof,
+        Some(Err(err)) => return Err(err.into()),
+        Some(Ok(line)) => match line? {
+            PacketLineRef::Data(data) => Line::Data(data.strip_suffix(b"\n").unwrap_or(data).into()),
+            PacketLineRef::Flush => Line::Flush,
+            PacketLineRef::Delimiter => Line::Delimiter,
+            PacketLineRef::ResponseEnd => Line::ResponseEnd,
+        },
+    })
+}
@@ -0,0 +1,23 @@
+//! The server side of the `upload-pack` service, answering `ls-refs` and `fetch` commands of protocol V2.
+//!
+//! Generally, there is the following order of operations.
+//!
+//! * obtain a `read` and `write` end to communicate with the client, like the standard input and output of a process
+//!   or a TCP connection after the initial request of the client was read.
+//! * implement a [`Delegate`] to provide references and packs, usually backed by a repository.
+//! * call [`upload_pack()`](crate::upload_pack()) to serve all commands the client sends until it ends the interaction.
+//!
+//! Note that the protocol version is always V2, as it's the only one the server side implements.
+mod types;
+pub use types::*;
+
+///
+pub mod arguments;
+
+#[cfg(feature = "upload-pack")]
+mod error;
+#[cfg(feature = "upload-pack")]
+pub use error::Error;
+
+#[cfg(feature = "upload-pack")]
+pub(crate) mod function;
@@ -0,0 +1,78 @@
+use gix_hash::ObjectId;
+
+use crate::upload_pack::arguments;
+
+/// The shallow boundary of the client after a fetch, as communicated in the `shallow-info` section.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Shallow {
+    /// Commits that will be sent without their parents.
+    pub shallow: Vec<ObjectId>,
+    /// Commits the client has without their parents, but whose parents will be sent.
+    pub unshallow: Vec<ObjectId>,
+}
+
+/// Everything needed to produce the pack to send to the client.
+#[derive(Debug, Clone, Copy)]
+pub struct Pack<'a> {
+    /// The arguments of the `fetch` command.
+    pub arguments: &'a arguments::Fetch,
+    /// The objects the client has that are also present on our side, which don't have to be sent.
+    pub common: &'a [ObjectId],
+    /// The commits whose parents must not be sent, which includes the new shallow boundary and the shallow commits
+    /// the client already has and which didn't get unshallowed.
+    pub shallow: &'a [ObjectId],
+}
+
+/// The options for use in [`upload_pack()`](crate::upload_pack()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The name and value of the agent to advertise, with the name usually being `agent`.
+    pub user_agent: crate::command::Feature,
+    /// If `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    pub trace_packetlines: bool,
+}
+
+/// The error type used by [`Delegate`] implementations.
+pub type DelegateError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Provide everything [`upload_pack()`](crate::upload_pack()) needs to know about a repository.
+pub trait Delegate {
+    /// The kind of hash used by the repository.
+    fn object_hash(&self) -> gix_hash::Kind;
+
+    /// Return all references to advertise, with `HEAD` first if it exists.
+    ///
+    /// Symbolic references other than `HEAD` may be returned as well.
+    fn refs(&mut self) -> Result<Vec<crate::handshake::Ref>, DelegateError>;
+
+    /// Return `true` if the object with `id` exists.
+    ///
+    /// This is used to validate the objects the client wants, and to find the ones it has that we have as well.
+    fn contains(&mut self, id: &gix_hash::oid) -> bool;
+
+    /// Return `true` if enough objects in `common` are known to send a pack containing `wants` without further negotiation.
+    ///
+    /// The default implementation is ready as soon as there is at least one object in common.
+    fn is_ready(&mut self, wants: &[ObjectId], common: &[ObjectId]) -> bool {
+        _ = wants;
+        !common.is_empty()
+    }
+
+    /// Compute the shallow boundary of the client after sending the objects it wants, as determined by the
+    /// [`deepen`](arguments::Fetch::deepen) and [`shallow`](arguments::Fetch::shallow) fields of `arguments`.
+    ///
+    /// It's only called if the client is shallow or wants to be.
+    fn shallow(&mut self, arguments: &arguments::Fetch) -> Result<Shallow, DelegateError>;
+
+    /// Write a pack with all objects the client needs according to `pack` to `out`, writing human-readable
+    /// progress messages to `progress`.
+    ///
+    /// The operation should stop if `should_interrupt` is `true`.
+    fn write_pack(
+        &mut self,
+        pack: Pack<'_>,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn std::io::Write,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<(), DelegateError>;
+}
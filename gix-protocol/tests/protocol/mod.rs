@@ -8,8 +8,13 @@ pub fn fixture_bytes(path: &str) -> Vec<u8> {
 mod command;
 pub mod fetch;
 mod handshake;
+mod push;
+#[cfg(feature = "receive-pack")]
+mod receive_pack;
 pub use fetch::_impl::{FetchConnection, fetch};
 pub mod remote_progress;
+#[cfg(feature = "upload-pack")]
+mod upload_pack;
 
 #[gix_protocol::bisync::bisync]
 #[cfg_attr(feature = "blocking-client", test)]
@@ -0,0 +1,140 @@
+mod report {
+    use gix_protocol::push::{Report, report::Status};
+
+    fn parse(lines: &[&str]) -> Result<Report, gix_protocol::push::report::Error> {
+        Report::from_lines(lines.iter().map(|line| line.as_bytes()))
+    }
+
+    #[test]
+    fn all_refs_updated() -> crate::Result {
+        let report = parse(&["unpack ok\n", "ok refs/heads/main\n", "ok refs/tags/v1\n"])?;
+        assert!(report.is_success());
+        assert_eq!(report.unpack_error, None);
+        assert_eq!(report.refs.len(), 2);
+        assert_eq!(report.refs[0].name, "refs/heads/main");
+        assert_eq!(report.refs[0].status, Status::Ok);
+        assert_eq!(report.refs[0].rewritten_name, None);
+        assert_eq!(report.find("refs/tags/v1".into()).map(|r| &r.status), Some(&Status::Ok));
+        Ok(())
+    }
+
+    #[test]
+    fn rejected_refs_and_unpack_errors() -> crate::Result {
+        let report = parse(&[
+            "unpack index-pack abnormal exit",
+            "ng refs/heads/main non-fast-forward",
+            "ng refs/heads/other",
+        ])?;
+        assert!(!report.is_success());
+        assert_eq!(report.unpack_error, Some("index-pack abnormal exit".into()));
+        assert_eq!(report.refs[0].status, Status::Rejected("non-fast-forward".into()));
+        assert_eq!(
+            report.refs[1].status,
+            Status::Rejected("unknown reason".into()),
+            "a missing reason is tolerated"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn v2_options_are_associated_with_the_preceding_ref() -> crate::Result {
+        let old = "1111111111111111111111111111111111111111";
+        let new = "2222222222222222222222222222222222222222";
+        let report = parse(&[
+            "unpack ok",
+            "ok refs/for/main/topic",
+            "option refname refs/changes/1/1",
+            &format!("option old-oid {old}"),
+            &format!("option new-oid {new}"),
+            "option forced-update",
+            "ok refs/heads/main",
+        ])?;
+        assert!(report.is_success());
+        let rewritten = &report.refs[0];
+        assert_eq!(rewritten.rewritten_name, Some("refs/changes/1/1".into()));
+        assert_eq!(rewritten.old_id, Some(gix_hash::ObjectId::from_hex(old.as_bytes())?));
+        assert_eq!(rewritten.new_id, Some(gix_hash::ObjectId::from_hex(new.as_bytes())?));
+        assert!(rewritten.forced_update);
+        let plain = &report.refs[1];
+        assert_eq!(plain.rewritten_name, None);
+        assert!(!plain.forced_update);
+        Ok(())
+    }
+
+    #[test]
+    fn serialization_round_trips() -> crate::Result {
+        let id = "1111111111111111111111111111111111111111";
+        for lines in [
+            &[
+                "unpack ok",
+                "ok refs/heads/main",
+                "ng refs/heads/other non-fast-forward",
+            ][..],
+            &["unpack index-pack abnormal exit", "ng refs/heads/main unpacker error"],
+            &[
+                "unpack ok",
+                "ok refs/for/main",
+                "option refname refs/changes/1/1",
+                &format!("option old-oid {id}"),
+                "option forced-update",
+            ],
+        ] {
+            let report = parse(lines)?;
+            assert_eq!(report.to_lines(), lines, "lines are reproduced exactly");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_reports() {
+        assert!(matches!(
+            parse(&[]),
+            Err(gix_protocol::push::report::Error::MissingUnpackStatus)
+        ));
+        assert!(matches!(
+            parse(&["ok refs/heads/main"]),
+            Err(gix_protocol::push::report::Error::MissingUnpackStatus)
+        ));
+        assert!(matches!(
+            parse(&["unpack ok", "option forced-update"]),
+            Err(gix_protocol::push::report::Error::OptionWithoutRef { .. })
+        ));
+        assert!(matches!(
+            parse(&["unpack ok", "weird refs/heads/main"]),
+            Err(gix_protocol::push::report::Error::MalformedLine { .. })
+        ));
+        assert!(matches!(
+            parse(&["unpack ok", "ok refs/heads/main", "option old-oid not-hex"]),
+            Err(gix_protocol::push::report::Error::DecodeId { .. })
+        ));
+    }
+}
+
+mod command {
+    use gix_protocol::push::Command;
+
+    #[test]
+    fn serialization_and_kind() {
+        let null = gix_hash::Kind::Sha1.null();
+        let id = gix_hash::ObjectId::from_hex(b"1111111111111111111111111111111111111111").expect("valid");
+        let create = Command {
+            old: null,
+            new: id,
+            name: "refs/heads/main".into(),
+        };
+        assert!(create.is_create());
+        assert!(!create.is_delete());
+        assert_eq!(
+            create.to_bstring(),
+            format!("{null} {id} refs/heads/main"),
+            "it's the line sent to the server"
+        );
+        let delete = Command {
+            old: id,
+            new: null,
+            name: "refs/heads/main".into(),
+        };
+        assert!(delete.is_delete());
+        assert!(!delete.is_create());
+    }
+}
@@ -0,0 +1,306 @@
+use std::sync::atomic::AtomicBool;
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+use gix_protocol::{
+    push::{Command, report::Status},
+    receive_pack::{Delegate, DelegateError, Error, Options, Update},
+    transport::packetline::{self, PacketLineRef, blocking_io::encode},
+};
+
+fn id(hex: char) -> ObjectId {
+    ObjectId::from_hex(hex.to_string().repeat(40).as_bytes()).expect("valid")
+}
+
+#[derive(Default)]
+struct Repo {
+    no_refs: bool,
+    packs: Vec<(Vec<u8>, Vec<Command>)>,
+    updates: Vec<(Vec<Command>, bool, Vec<BString>)>,
+    fail_unpack: bool,
+}
+
+impl Delegate for Repo {
+    fn object_hash(&self) -> gix_hash::Kind {
+        gix_hash::Kind::Sha1
+    }
+
+    fn refs(&mut self) -> Result<Vec<(BString, ObjectId)>, DelegateError> {
+        if self.no_refs {
+            return Ok(Vec::new());
+        }
+        Ok(vec![
+            ("refs/heads/main".into(), id('1')),
+            ("refs/tags/v1".into(), id('2')),
+        ])
+    }
+
+    fn receive_pack(
+        &mut self,
+        pack: &mut dyn std::io::BufRead,
+        commands: &[Command],
+        progress: &mut dyn std::io::Write,
+        _should_interrupt: &AtomicBool,
+    ) -> Result<(), DelegateError> {
+        let mut data = Vec::new();
+        pack.read_to_end(&mut data)?;
+        self.packs.push((data, commands.to_vec()));
+        progress.write_all(b"Resolving deltas: 100% (0/0), done.\n")?;
+        if self.fail_unpack {
+            return Err("index-pack abnormal exit".into());
+        }
+        Ok(())
+    }
+
+    fn update_refs(&mut self, commands: &[Command], update: Update<'_>) -> Result<Vec<Status>, DelegateError> {
+        self.updates
+            .push((commands.to_vec(), update.atomic, update.push_options.to_vec()));
+        Ok(commands
+            .iter()
+            .map(|command| {
+                if command.name.starts_with(b"refs/heads/protected") {
+                    Status::Rejected("protected".into())
+                } else {
+                    Status::Ok
+                }
+            })
+            .collect())
+    }
+}
+
+enum Input<'a> {
+    Line(&'a str),
+    Flush,
+    Raw(&'a [u8]),
+}
+
+fn serve(repo: &mut Repo, push_options: bool, input: &[Input<'_>]) -> (Result<(), Error>, Vec<String>) {
+    let mut read = Vec::new();
+    for line in input {
+        match line {
+            Input::Line(line) => encode::text_to_write(line.as_bytes(), &mut read).map(|_| ()),
+            Input::Flush => encode::flush_to_write(&mut read).map(|_| ()),
+            Input::Raw(data) => std::io::Write::write_all(&mut read, data),
+        }
+        .expect("in-memory writes work");
+    }
+    let mut write = Vec::new();
+    let res = gix_protocol::receive_pack(
+        read.as_slice(),
+        &mut write,
+        repo,
+        &AtomicBool::default(),
+        Options {
+            user_agent: ("agent", Some("git/test".into())),
+            advertise_push_options: push_options,
+            trace_packetlines: false,
+        },
+    );
+    (res, decode(&write))
+}
+
+/// Decode all packet lines in `data`, showing sideband lines with the channel they are on, and decoding the status report
+/// sent on the data channel.
+fn decode(mut data: &[u8]) -> Vec<String> {
+    let mut out = Vec::new();
+    while !data.is_empty() {
+        let packetline::decode::Stream::Complete { line, bytes_consumed } =
+            packetline::decode::streaming(data).expect("valid")
+        else {
+            panic!("incomplete line")
+        };
+        match line {
+            PacketLineRef::Data([1, report @ ..]) => {
+                out.extend(decode(report).into_iter().map(|line| format!("[1] {line}")));
+            }
+            PacketLineRef::Data([2, progress @ ..]) => out.push(format!("[2] {}", progress.trim_end().as_bstr())),
+            PacketLineRef::Data(d) => out.push(d.trim_end().to_str_lossy().replace('\0', " | ")),
+            PacketLineRef::Flush => out.push("0000".into()),
+            PacketLineRef::Delimiter => out.push("0001".into()),
+            PacketLineRef::ResponseEnd => out.push("0002".into()),
+        }
+        data = &data[bytes_consumed..];
+    }
+    out
+}
+
+const CAPABILITIES: &str =
+    "report-status delete-refs side-band-64k quiet atomic ofs-delta object-format=sha1 agent=git/test";
+
+fn advertisement() -> Vec<String> {
+    vec![
+        format!("{} refs/heads/main | {CAPABILITIES}", id('1')),
+        format!("{} refs/tags/v1", id('2')),
+        "0000".into(),
+    ]
+}
+
+#[test]
+fn advertisement_only() {
+    let (res, out) = serve(&mut Repo::default(), false, &[]);
+    assert!(res.is_ok(), "the client just hung up");
+    assert_eq!(out, advertisement());
+
+    let (res, out) = serve(&mut Repo::default(), false, &[Input::Flush]);
+    assert!(res.is_ok(), "the client had nothing to push");
+    assert_eq!(out, advertisement());
+}
+
+#[test]
+fn advertisement_without_refs_and_with_push_options() {
+    let (res, out) = serve(
+        &mut Repo {
+            no_refs: true,
+            ..Default::default()
+        },
+        true,
+        &[],
+    );
+    assert!(res.is_ok());
+    assert_eq!(
+        out,
+        [
+            format!(
+                "{} capabilities^{{}} | report-status delete-refs side-band-64k quiet atomic ofs-delta push-options object-format=sha1 agent=git/test",
+                gix_hash::Kind::Sha1.null()
+            ),
+            "0000".into()
+        ]
+    );
+}
+
+#[test]
+fn push_with_sideband_and_push_options() {
+    use Input::*;
+    let mut repo = Repo::default();
+    let (res, out) = serve(
+        &mut repo,
+        true,
+        &[
+            Line(&format!(
+                "{} {} refs/heads/main\0report-status side-band-64k atomic push-options object-format=sha1 agent=git/2.0",
+                id('1'),
+                id('3')
+            )),
+            Line(&format!("{} {} refs/heads/protected", id('4'), id('5'))),
+            Flush,
+            Line("ci.skip"),
+            Flush,
+            Raw(b"PACK"),
+        ],
+    );
+    assert!(res.is_ok());
+    assert_eq!(
+        out[advertisement().len()..],
+        [
+            "[2] Resolving deltas: 100% (0/0), done.",
+            "[1] unpack ok",
+            "[1] ok refs/heads/main",
+            "[1] ng refs/heads/protected protected",
+            "[1] 0000",
+            "0000"
+        ]
+    );
+    assert_eq!(repo.packs.len(), 1);
+    assert_eq!(repo.packs[0].0, b"PACK", "the pack is everything after the commands");
+    assert_eq!(repo.packs[0].1.len(), 2);
+    let (commands, atomic, push_options) = &repo.updates[0];
+    assert_eq!(
+        commands[0],
+        Command {
+            old: id('1'),
+            new: id('3'),
+            name: "refs/heads/main".into()
+        }
+    );
+    assert!(*atomic);
+    assert_eq!(push_options, &["ci.skip"]);
+}
+
+#[test]
+fn deletions_without_pack_and_sideband() {
+    use Input::*;
+    let mut repo = Repo::default();
+    let null = gix_hash::Kind::Sha1.null();
+    let (res, out) = serve(
+        &mut repo,
+        false,
+        &[
+            Line(&format!("{} {null} refs/tags/v1\0report-status quiet", id('2'))),
+            Flush,
+        ],
+    );
+    assert!(res.is_ok());
+    assert_eq!(out[advertisement().len()..], ["unpack ok", "ok refs/tags/v1", "0000"]);
+    assert!(repo.packs.is_empty(), "deletions don't need a pack");
+    assert!(!repo.updates[0].1, "not atomic");
+}
+
+#[test]
+fn unpack_failures_are_reported_and_prevent_updates() {
+    use Input::*;
+    let mut repo = Repo {
+        fail_unpack: true,
+        ..Default::default()
+    };
+    let (res, out) = serve(
+        &mut repo,
+        false,
+        &[
+            Line(&format!(
+                "{} {} refs/heads/main\0report-status side-band-64k quiet",
+                id('1'),
+                id('3')
+            )),
+            Flush,
+            Raw(b"PACK"),
+        ],
+    );
+    assert!(matches!(res, Err(Error::ReceivePack(_))));
+    assert_eq!(
+        out[advertisement().len()..],
+        [
+            "[1] unpack index-pack abnormal exit",
+            "[1] ng refs/heads/main unpacker error",
+            "[1] 0000",
+            "0000"
+        ],
+        "quiet suppresses progress"
+    );
+    assert!(repo.updates.is_empty());
+}
+
+#[test]
+fn malformed_commands_and_object_format_mismatches() {
+    use Input::*;
+    let (res, out) = serve(&mut Repo::default(), false, &[Line("1111 2222 refs/heads/main"), Flush]);
+    assert!(matches!(res, Err(Error::MalformedCommand { .. })));
+    assert_eq!(
+        out[advertisement().len()..],
+        ["ERR receive-pack: Could not parse the command \"1111 2222 refs/heads/main\""]
+    );
+
+    let (res, _out) = serve(
+        &mut Repo::default(),
+        false,
+        &[
+            Line(&format!(
+                "{} {} refs/heads/main\0report-status object-format=sha256",
+                id('1'),
+                id('3')
+            )),
+            Flush,
+        ],
+    );
+    assert!(matches!(res, Err(Error::ObjectFormatMismatch { .. })));
+
+    let (res, _out) = serve(
+        &mut Repo::default(),
+        false,
+        &[Line(&format!("{} {} refs/heads/main", id('1'), id('3')))],
+    );
+    assert!(
+        matches!(res, Err(Error::UnexpectedEof)),
+        "commands must be terminated with a flush"
+    );
+}
@@ -0,0 +1,369 @@
+mod arguments {
+    use gix_protocol::upload_pack::arguments::{Deepen, Error, Fetch, Filter, LsRefs};
+
+    fn fetch(lines: &[&str]) -> Result<Fetch, Error> {
+        Fetch::from_lines(lines.iter().map(|line| line.as_bytes()))
+    }
+
+    #[test]
+    fn fetch_flags_ids_and_deepen() -> crate::Result {
+        let id = "1111111111111111111111111111111111111111";
+        let args = fetch(&[
+            &format!("want {id}"),
+            &format!("have {id}"),
+            &format!("shallow {id}"),
+            "done",
+            "thin-pack",
+            "ofs-delta",
+            "no-progress",
+            "deepen 3",
+            "filter blob:limit=2k",
+        ])?;
+        let id = gix_hash::ObjectId::from_hex(id.as_bytes())?;
+        assert_eq!(args.wants, [id]);
+        assert_eq!(args.haves, [id]);
+        assert_eq!(args.shallow, [id]);
+        assert!(args.done && args.thin_pack && args.ofs_delta && args.no_progress);
+        assert!(!args.include_tag);
+        assert_eq!(args.deepen, Some(Deepen::Depth(3)));
+        assert_eq!(args.filter, Some(Filter::BlobLimit(2048)));
+
+        let args = fetch(&["deepen-not refs/heads/a", "deepen-not refs/heads/b"])?;
+        assert_eq!(
+            args.deepen,
+            Some(Deepen::Not(vec!["refs/heads/a".into(), "refs/heads/b".into()]))
+        );
+        assert_eq!(fetch(&["deepen-since 1234"])?.deepen, Some(Deepen::Since(1234)));
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_errors() {
+        assert!(matches!(fetch(&["weird"]), Err(Error::Unknown { .. })));
+        assert!(matches!(fetch(&["want 123"]), Err(Error::DecodeId { .. })));
+        assert!(matches!(fetch(&["deepen many"]), Err(Error::InvalidNumber { .. })));
+        assert!(matches!(
+            fetch(&["deepen 1", "deepen-since 1234"]),
+            Err(Error::ConflictingDeepen { .. })
+        ));
+        assert!(matches!(
+            fetch(&["filter sparse:oid=abc"]),
+            Err(Error::UnsupportedFilter { .. })
+        ));
+    }
+
+    #[test]
+    fn filter_specs() {
+        assert_eq!(Filter::from_spec("blob:none".into()), Some(Filter::BlobNone));
+        assert_eq!(Filter::from_spec("blob:limit=10".into()), Some(Filter::BlobLimit(10)));
+        assert_eq!(
+            Filter::from_spec("blob:limit=1m".into()),
+            Some(Filter::BlobLimit(1024 * 1024))
+        );
+        assert_eq!(Filter::from_spec("tree:0".into()), Some(Filter::TreeDepth(0)));
+        assert_eq!(Filter::from_spec("blob:limit=".into()), None);
+        assert_eq!(Filter::from_spec("combine:blob:none+tree:0".into()), None);
+    }
+
+    #[test]
+    fn ls_refs() -> crate::Result {
+        let args = LsRefs::from_lines(["symrefs", "unborn", "ref-prefix refs/heads/"].map(str::as_bytes))?;
+        assert!(args.symrefs && args.unborn && !args.peel);
+        assert!(args.matches("refs/heads/main".into()));
+        assert!(!args.matches("refs/tags/v1".into()));
+        assert!(
+            LsRefs::default().matches("refs/tags/v1".into()),
+            "no prefix matches everything"
+        );
+        assert!(LsRefs::from_lines([b"peel-all".as_slice()]).is_err());
+        Ok(())
+    }
+}
+
+mod serve {
+    use std::sync::atomic::AtomicBool;
+
+    use bstr::ByteSlice;
+    use gix_hash::ObjectId;
+    use gix_protocol::{
+        handshake::Ref,
+        transport::packetline::{self, PacketLineRef, blocking_io::encode},
+        upload_pack::{Delegate, DelegateError, Options, Pack, Shallow, arguments},
+    };
+
+    fn id(hex: char) -> ObjectId {
+        ObjectId::from_hex(hex.to_string().repeat(40).as_bytes()).expect("valid")
+    }
+
+    #[derive(Default)]
+    struct Repo {
+        packs: Vec<(arguments::Fetch, Vec<ObjectId>, Vec<ObjectId>)>,
+    }
+
+    impl Delegate for Repo {
+        fn object_hash(&self) -> gix_hash::Kind {
+            gix_hash::Kind::Sha1
+        }
+
+        fn refs(&mut self) -> Result<Vec<Ref>, DelegateError> {
+            Ok(vec![
+                Ref::Symbolic {
+                    full_ref_name: "HEAD".into(),
+                    target: "refs/heads/main".into(),
+                    tag: None,
+                    object: id('1'),
+                },
+                Ref::Direct {
+                    full_ref_name: "refs/heads/main".into(),
+                    object: id('1'),
+                },
+                Ref::Peeled {
+                    full_ref_name: "refs/tags/v1".into(),
+                    tag: id('2'),
+                    object: id('1'),
+                },
+            ])
+        }
+
+        fn contains(&mut self, id: &gix_hash::oid) -> bool {
+            id.to_hex().to_string().starts_with(['1', '2', '3'])
+        }
+
+        fn shallow(&mut self, _arguments: &arguments::Fetch) -> Result<Shallow, DelegateError> {
+            Ok(Shallow {
+                shallow: vec![id('3')],
+                unshallow: vec![],
+            })
+        }
+
+        fn write_pack(
+            &mut self,
+            pack: Pack<'_>,
+            out: &mut dyn std::io::Write,
+            progress: &mut dyn std::io::Write,
+            _should_interrupt: &AtomicBool,
+        ) -> Result<(), DelegateError> {
+            self.packs
+                .push((pack.arguments.clone(), pack.common.to_vec(), pack.shallow.to_vec()));
+            progress.write_all(b"Counting objects: 1, done.\n")?;
+            out.write_all(b"PACK")?;
+            Ok(())
+        }
+    }
+
+    enum Input<'a> {
+        Line(&'a str),
+        Delim,
+        Flush,
+    }
+
+    fn serve(repo: &mut Repo, input: &[Input<'_>]) -> (Result<(), gix_protocol::upload_pack::Error>, Vec<String>) {
+        let mut read = Vec::new();
+        for line in input {
+            match line {
+                Input::Line(line) => encode::text_to_write(line.as_bytes(), &mut read),
+                Input::Delim => encode::delim_to_write(&mut read),
+                Input::Flush => encode::flush_to_write(&mut read),
+            }
+            .expect("in-memory writes work");
+        }
+        let mut write = Vec::new();
+        let res = gix_protocol::upload_pack(
+            read.as_slice(),
+            &mut write,
+            repo,
+            &AtomicBool::default(),
+            Options {
+                user_agent: ("agent", Some("git/test".into())),
+                trace_packetlines: false,
+            },
+        );
+        (res, decode(&write))
+    }
+
+    fn decode(mut data: &[u8]) -> Vec<String> {
+        let mut out = Vec::new();
+        while !data.is_empty() {
+            let packetline::decode::Stream::Complete { line, bytes_consumed } =
+                packetline::decode::streaming(data).expect("valid")
+            else {
+                panic!("incomplete line")
+            };
+            out.push(match line {
+                PacketLineRef::Data(d) => match d.first() {
+                    Some(1) => format!("[1] {}", d[1..].as_bstr()),
+                    Some(2) => format!("[2] {}", d[1..].trim_end().as_bstr()),
+                    Some(3) => format!("[3] {}", d[1..].trim_end().as_bstr()),
+                    _ => d.trim_end().to_str_lossy().into_owned(),
+                },
+                PacketLineRef::Flush => "0000".into(),
+                PacketLineRef::Delimiter => "0001".into(),
+                PacketLineRef::ResponseEnd => "0002".into(),
+            });
+            data = &data[bytes_consumed..];
+        }
+        out
+    }
+
+    const ADVERTISEMENT: &[&str] = &[
+        "version 2",
+        "agent=git/test",
+        "ls-refs=unborn",
+        "fetch=shallow filter",
+        "server-option",
+        "object-format=sha1",
+        "0000",
+    ];
+
+    #[test]
+    fn advertisement_only() {
+        let (res, out) = serve(&mut Repo::default(), &[]);
+        assert!(res.is_ok(), "the client just hung up");
+        assert_eq!(out, ADVERTISEMENT);
+    }
+
+    #[test]
+    fn ls_refs_with_symrefs_peel_and_prefixes() {
+        use Input::*;
+        let (res, out) = serve(
+            &mut Repo::default(),
+            &[
+                Line("command=ls-refs"),
+                Line("agent=git/client"),
+                Line("object-format=sha1"),
+                Delim,
+                Line("symrefs"),
+                Line("peel"),
+                Flush,
+                Line("command=ls-refs"),
+                Delim,
+                Line("ref-prefix refs/tags/"),
+                Flush,
+                Flush,
+            ],
+        );
+        assert!(res.is_ok());
+        let one = id('1');
+        let two = id('2');
+        assert_eq!(
+            out[ADVERTISEMENT.len()..],
+            [
+                format!("{one} HEAD symref-target:refs/heads/main"),
+                format!("{one} refs/heads/main"),
+                format!("{two} refs/tags/v1 peeled:{one}"),
+                "0000".into(),
+                format!("{two} refs/tags/v1"),
+                "0000".into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn fetch_negotiation_then_pack_with_shallow_info() {
+        use Input::*;
+        let mut repo = Repo::default();
+        let want = format!("want {}", id('1'));
+        let (res, out) = serve(
+            &mut repo,
+            &[
+                Line("command=fetch"),
+                Delim,
+                Line(&want),
+                Line(&format!("have {}", id('a'))),
+                Flush,
+                Line("command=fetch"),
+                Delim,
+                Line(&want),
+                Line(&format!("have {}", id('a'))),
+                Line(&format!("have {}", id('2'))),
+                Line("deepen 1"),
+                Flush,
+            ],
+        );
+        assert!(res.is_ok());
+        assert_eq!(
+            out[ADVERTISEMENT.len()..],
+            [
+                "acknowledgments".into(),
+                "NAK".into(),
+                "0000".into(),
+                "acknowledgments".into(),
+                format!("ACK {}", id('2')),
+                "ready".into(),
+                "0001".into(),
+                "shallow-info".into(),
+                format!("shallow {}", id('3')),
+                "0001".into(),
+                "packfile".into(),
+                "[2] Counting objects: 1, done.".into(),
+                "[1] PACK".into(),
+                "0000".into(),
+            ]
+        );
+        assert_eq!(repo.packs.len(), 1);
+        let (args, common, shallow) = &repo.packs[0];
+        assert_eq!(args.wants, [id('1')]);
+        assert_eq!(common, &[id('2')]);
+        assert_eq!(shallow, &[id('3')]);
+    }
+
+    #[test]
+    fn fetch_done_without_progress() {
+        use Input::*;
+        let (res, out) = serve(
+            &mut Repo::default(),
+            &[
+                Line("command=fetch"),
+                Delim,
+                Line(&format!("want {}", id('1'))),
+                Line("no-progress"),
+                Line("done"),
+                Flush,
+            ],
+        );
+        assert!(res.is_ok());
+        assert_eq!(out[ADVERTISEMENT.len()..], ["packfile", "[1] PACK", "0000"]);
+    }
+
+    #[test]
+    fn errors_are_sent_to_the_client() {
+        use Input::*;
+        let (res, out) = serve(
+            &mut Repo::default(),
+            &[
+                Line("command=fetch"),
+                Delim,
+                Line(&format!("want {}", id('f'))),
+                Line("done"),
+                Flush,
+            ],
+        );
+        assert!(matches!(res, Err(gix_protocol::upload_pack::Error::NotOurRef { .. })));
+        assert_eq!(
+            out[ADVERTISEMENT.len()..],
+            [format!(
+                "ERR upload-pack: The client wants {}, which doesn't exist",
+                id('f')
+            )]
+        );
+
+        let (res, out) = serve(&mut Repo::default(), &[Line("command=push"), Flush]);
+        assert!(matches!(
+            res,
+            Err(gix_protocol::upload_pack::Error::UnknownCommand { .. })
+        ));
+        assert_eq!(
+            out[ADVERTISEMENT.len()..],
+            ["ERR upload-pack: Unknown command \"push\""]
+        );
+
+        let (res, _out) = serve(
+            &mut Repo::default(),
+            &[Line("command=ls-refs"), Line("object-format=sha256"), Flush],
+        );
+        assert!(matches!(
+            res,
+            Err(gix_protocol::upload_pack::Error::ObjectFormatMismatch { .. })
+        ));
+    }
+}
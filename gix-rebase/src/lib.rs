@@ -1 +1,23 @@
+//! Rebase commits onto a new base, entirely in memory, and keep track of rebases in a way that is compatible with
+//! [`git rebase`](https://git-scm.com/docs/git-rebase).
+//!
+//! * [replay()] picks a list of commits one by one onto a new base by merging their trees with [`gix_merge::tree()`],
+//!   and stops at the first commit that doesn't apply cleanly.
+//! * [`todo`] reads and writes the todo lists Git uses to describe what remains to be done.
+//! * [`State`] reads and writes the state of a rebase that stopped, as stored in `.git/rebase-merge/`,
+//!   so that `git rebase --continue` can pick it up.
+//!
+//! Selecting the commits to rebase, updating references and touching the index or worktree is left to the caller.
+#![deny(missing_docs)]
 #![forbid(unsafe_code)]
+
+///
+pub mod replay;
+pub use replay::function::replay;
+
+///
+pub mod state;
+pub use state::State;
+
+///
+pub mod todo;
@@ -0,0 +1,129 @@
+use gix_error::{ResultExt, message};
+use gix_hash::{ObjectId, oid};
+use gix_merge::blob::builtin_driver::text::Labels;
+use gix_object::FindExt;
+
+use super::{Empty, Error, Options, Outcome, Step, Stop};
+
+/// Replay `commits`, oldest first, one by one on top of the commit `onto`, creating a new commit for each of them
+/// whose committer is `committer`, just like `git rebase` would.
+///
+/// The changes of each commit are obtained by comparing its tree to the tree of its first parent, or the empty tree
+/// if it has no parent, and merged into the tree of the previously replayed commit with [`gix_merge::tree()`].
+/// Author, message and encoding are kept, while extra headers, like signatures, are dropped as they would be invalid.
+///
+/// If a commit can't be applied cleanly as there are [unresolved conflicts](Options::treat_as_unresolved),
+/// the operation stops and the returned [outcome](Outcome::stopped) provides the conflicting merge result.
+/// To continue, call this function again with the conflicts resolved in a new commit, and with the commits that remain.
+///
+/// `objects` are used to read commits and trees and to write merged blobs, trees and the new commits.
+/// `diff_resource_cache` and `blob_merge` are used to merge trees, while `abbreviate_hash(id)` shortens ids for use
+/// in conflict markers.
+///
+/// ### Deviation
+///
+/// * Commits aren't skipped if an equivalent change was already applied upstream, which `git rebase` detects
+///   with patch-ids. Instead, they are dropped if they become empty, as configured with [`Options::empty`].
+#[expect(clippy::too_many_arguments)]
+pub fn replay<'objects>(
+    onto: ObjectId,
+    commits: &[ObjectId],
+    committer: gix_actor::SignatureRef<'_>,
+    objects: &'objects (impl gix_object::FindObjectOrHeader + gix_object::Write),
+    diff_resource_cache: &mut gix_diff::blob::Platform,
+    blob_merge: &mut gix_merge::blob::Platform,
+    abbreviate_hash: &mut dyn FnMut(&oid) -> String,
+    options: Options,
+) -> Result<Outcome<'objects>, Error> {
+    let mut buf = Vec::new();
+    let mut head = onto;
+    let mut head_tree = objects
+        .find_commit(&onto, &mut buf)
+        .or_raise(|| message!("Could not find commit {onto} to rebase onto"))?
+        .tree();
+    let mut diff_state = gix_diff::tree::State::default();
+    let mut steps = Vec::with_capacity(commits.len());
+    for &commit_id in commits {
+        let commit = objects
+            .find_commit(&commit_id, &mut buf)
+            .or_raise(|| message!("Could not find commit {commit_id} to replay"))?;
+        let summary = commit.message_summary().into_owned();
+        let commit = commit
+            .into_owned()
+            .or_raise(|| message!("Could not decode commit {commit_id}"))?;
+        let base_tree = match commit.parents.first() {
+            Some(parent) => objects
+                .find_commit(parent, &mut buf)
+                .or_raise(|| message!("Could not find parent {parent} of commit {commit_id}"))?
+                .tree(),
+            None => ObjectId::empty_tree(commit_id.kind()),
+        };
+
+        let other = format!("{} ({summary})", abbreviate_hash(&commit_id));
+        let ancestor = format!("parent of {other}");
+        let mut tree_merge = gix_merge::tree(
+            &base_tree,
+            &head_tree,
+            &commit.tree,
+            Labels {
+                ancestor: Some(ancestor.as_str().into()),
+                current: Some("HEAD".into()),
+                other: Some(other.as_str().into()),
+            },
+            objects,
+            |buf| objects.write_buf(gix_object::Kind::Blob, buf),
+            &mut diff_state,
+            diff_resource_cache,
+            blob_merge,
+            options.tree_merge.clone(),
+        )
+        .or_raise(|| message!("Could not merge the changes of commit {commit_id} into {head}"))?;
+        if tree_merge.has_unresolved_conflicts(options.treat_as_unresolved) {
+            return Ok(Outcome {
+                head,
+                steps,
+                stopped: Some(Stop {
+                    commit: commit_id,
+                    tree_merge,
+                }),
+            });
+        }
+
+        let tree = tree_merge
+            .tree
+            .write(|tree| objects.write(tree))
+            .map_err(std::io::Error::other)
+            .or_raise(|| message!("Could not write the merged tree of commit {commit_id}"))?;
+        let became_empty = tree == head_tree && base_tree != commit.tree;
+        if became_empty && options.empty == Empty::Drop {
+            steps.push(Step {
+                commit: commit_id,
+                rewritten: None,
+            });
+            continue;
+        }
+
+        head = objects
+            .write(&gix_object::Commit {
+                tree,
+                parents: [head].into(),
+                author: commit.author,
+                committer: committer.into(),
+                encoding: commit.encoding,
+                message: commit.message,
+                extra_headers: Vec::new(),
+            })
+            .map_err(std::io::Error::other)
+            .or_raise(|| message!("Could not write the rebased commit of {commit_id}"))?;
+        head_tree = tree;
+        steps.push(Step {
+            commit: commit_id,
+            rewritten: Some(head),
+        });
+    }
+    Ok(Outcome {
+        head,
+        steps,
+        stopped: None,
+    })
+}
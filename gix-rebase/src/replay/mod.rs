@@ -0,0 +1,61 @@
+use gix_hash::ObjectId;
+
+/// The error returned by [`replay()`](crate::replay()).
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// Define what to do with commits that become empty as their changes are already present in the new base.
+///
+/// Note that commits that are empty to begin with are always kept.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Empty {
+    /// Skip commits that become empty, just like `git rebase --empty=drop` does.
+    #[default]
+    Drop,
+    /// Create empty commits, just like `git rebase --empty=keep` does.
+    Keep,
+}
+
+/// A way to configure [`replay()`](crate::replay()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// Options to define how the tree of each commit should be merged into the new base.
+    pub tree_merge: gix_merge::tree::Options,
+    /// Determine which conflicts are considered unresolved, which stops the rebase at the commit that caused them.
+    pub treat_as_unresolved: gix_merge::tree::TreatAsUnresolved,
+    /// What to do with commits that become empty.
+    pub empty: Empty,
+}
+
+/// A commit that was replayed successfully.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Step {
+    /// The commit that was replayed.
+    pub commit: ObjectId,
+    /// The newly created commit, or `None` if it became empty and was dropped.
+    pub rewritten: Option<ObjectId>,
+}
+
+/// Information about the commit a rebase stopped at as its changes couldn't be applied cleanly.
+#[derive(Clone)]
+pub struct Stop<'a> {
+    /// The commit whose changes conflicted with the new base.
+    pub commit: ObjectId,
+    /// The outcome of merging the changes of `commit` into the new base, with at least one unresolved conflict.
+    pub tree_merge: gix_merge::tree::Outcome<'a>,
+}
+
+/// The result of [`replay()`](crate::replay()).
+#[derive(Clone)]
+pub struct Outcome<'a> {
+    /// The commit that was replayed last, or the new base if nothing was replayed yet.
+    /// If the rebase didn't stop, this is the new tip.
+    pub head: ObjectId,
+    /// All commits that were replayed successfully, in order.
+    pub steps: Vec<Step>,
+    /// The commit the rebase stopped at due to conflicts, or `None` if all commits were replayed.
+    ///
+    /// The commits that remain to be replayed are the ones after it.
+    pub stopped: Option<Stop<'a>>,
+}
+
+pub(super) mod function;
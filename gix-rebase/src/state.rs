@@ -0,0 +1,231 @@
+use std::path::Path;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_error::{ErrorExt, OptionExt, ResultExt, message};
+use gix_hash::ObjectId;
+
+use crate::{replay::Empty, todo};
+
+/// The error returned by [`State::from_dir()`] and [`State::write_to()`].
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// The name of the directory inside of the git directory that holds the state of a rebase, like `.git/rebase-merge`.
+pub const DIR_NAME: &str = "rebase-merge";
+
+/// The commit a rebase stopped at, along with what's needed to commit it once the conflicts are resolved.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Stopped {
+    /// The commit whose changes couldn't be applied cleanly.
+    pub commit: ObjectId,
+    /// The message of `commit`, to be used for the commit that resolves the conflicts.
+    pub message: BString,
+    /// The author of `commit`, to be used for the commit that resolves the conflicts.
+    pub author: gix_actor::Signature,
+}
+
+/// The state of a rebase in progress, as stored by `git rebase` in [`.git/rebase-merge`](DIR_NAME).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct State {
+    /// The full name of the branch that is rebased, like `refs/heads/main`, or `None` if `HEAD` was detached.
+    pub head_name: Option<BString>,
+    /// The commit the branch is rebased onto.
+    pub onto: ObjectId,
+    /// The tip of the branch before the rebase started.
+    pub orig_head: ObjectId,
+    /// The commits that were already processed, including the one the rebase [stopped](Self::stopped) at.
+    pub done: Vec<todo::Line>,
+    /// The commits that remain to be processed.
+    pub todo: Vec<todo::Line>,
+    /// What to do with commits that become empty.
+    pub empty: Empty,
+    /// The commit the rebase stopped at, if any.
+    pub stopped: Option<Stopped>,
+}
+
+/// The name of the branch in `head-name` if `HEAD` is detached.
+const DETACHED_HEAD: &str = "detached HEAD";
+
+impl State {
+    /// Read the state of a rebase from `dir`, typically `.git/rebase-merge`, and use `object_hash` to parse object ids.
+    ///
+    /// Return `None` if `dir` doesn't exist, which indicates that there is no rebase in progress.
+    pub fn from_dir(dir: &Path, object_hash: gix_hash::Kind) -> Result<Option<Self>, Error> {
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+        let read = |name: &str| -> Result<Option<BString>, Error> {
+            let path = dir.join(name);
+            match std::fs::read(&path) {
+                Ok(buf) => Ok(Some(buf.into())),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err).or_raise(|| message!("Could not read '{}'", path.display())),
+            }
+        };
+        let read_id = |name: &str| -> Result<ObjectId, Error> {
+            let hex = read(name)?.ok_or_raise(|| message!("The file '{name}' is missing"))?;
+            ObjectId::from_hex(hex.trim()).or_raise(|| message!("The file '{name}' didn't contain an object id"))
+        };
+        let read_todo = |name: &str| -> Result<Vec<todo::Line>, Error> {
+            todo::parse(&read(name)?.unwrap_or_default())
+                .or_raise(|| message!("Could not parse the todo list in '{name}'"))
+        };
+
+        let head_name = read("head-name")?
+            .map(|name| name.trim().as_bstr().to_owned())
+            .filter(|name| name != DETACHED_HEAD);
+        let stopped = match read("stopped-sha")? {
+            Some(hex) => {
+                let commit = ObjectId::from_hex(hex.trim())
+                    .or_raise(|| message!("The file 'stopped-sha' didn't contain a full object id"))?;
+                let author_script = read("author-script")?.unwrap_or_default();
+                Some(Stopped {
+                    commit,
+                    message: read("message")?.unwrap_or_default(),
+                    author: parse_author_script(author_script.as_ref())
+                        .ok_or_raise(|| message!("Could not parse 'author-script'"))?,
+                })
+            }
+            None => None,
+        };
+        let state = State {
+            head_name,
+            onto: read_id("onto")?,
+            orig_head: read_id("orig-head")?,
+            done: read_todo("done")?,
+            todo: read_todo("git-rebase-todo")?,
+            empty: if dir.join("keep_redundant_commits").is_file() {
+                Empty::Keep
+            } else {
+                Empty::Drop
+            },
+            stopped,
+        };
+        if state.onto.kind() != object_hash {
+            return Err(message!("Expected object ids of kind {object_hash}, got {}", state.onto.kind()).raise());
+        }
+        Ok(Some(state))
+    }
+
+    /// Write this state into `dir`, typically `.git/rebase-merge`, which is created if needed, in the layout
+    /// that `git rebase` uses so it can continue the rebase.
+    ///
+    /// Files that belong to a [stopped](Self::stopped) rebase are removed if this rebase didn't stop.
+    pub fn write_to(&self, dir: &Path) -> Result<(), Error> {
+        std::fs::create_dir_all(dir).or_raise(|| message!("Could not create directory '{}'", dir.display()))?;
+        let write = |name: &str, content: &[u8]| -> Result<(), Error> {
+            let path = dir.join(name);
+            std::fs::write(&path, content).or_raise(|| message!("Could not write '{}'", path.display()))
+        };
+        let write_todo = |name: &str, lines: &[todo::Line]| -> Result<(), Error> {
+            let mut buf = Vec::new();
+            for line in lines {
+                line.write_to(&mut buf).expect("writing to a vector never fails");
+            }
+            write(name, &buf)
+        };
+
+        let mut head_name = self.head_name.clone().unwrap_or_else(|| DETACHED_HEAD.into());
+        head_name.push(b'\n');
+        write("head-name", &head_name)?;
+        write("onto", format!("{}\n", self.onto).as_bytes())?;
+        write("orig-head", format!("{}\n", self.orig_head).as_bytes())?;
+        write("interactive", b"")?;
+        let (empty_file, other_empty_file) = match self.empty {
+            Empty::Drop => ("drop_redundant_commits", "keep_redundant_commits"),
+            Empty::Keep => ("keep_redundant_commits", "drop_redundant_commits"),
+        };
+        write(empty_file, b"")?;
+        remove(&dir.join(other_empty_file))?;
+        write_todo("done", &self.done)?;
+        write_todo("git-rebase-todo", &self.todo)?;
+        write("msgnum", format!("{}\n", self.done.len()).as_bytes())?;
+        write("end", format!("{}\n", self.done.len() + self.todo.len()).as_bytes())?;
+
+        match &self.stopped {
+            Some(stopped) => {
+                write("stopped-sha", format!("{}\n", stopped.commit).as_bytes())?;
+                write("message", &stopped.message)?;
+                write(
+                    "author-script",
+                    &author_script(stopped.author.to_ref(&mut Default::default())),
+                )?;
+            }
+            None => {
+                for name in ["stopped-sha", "message", "author-script"] {
+                    remove(&dir.join(name))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn remove(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).or_raise(|| message!("Could not remove '{}'", path.display())),
+    }
+}
+
+/// Produce the script that Git sources to obtain the author of a commit.
+fn author_script(author: gix_actor::SignatureRef<'_>) -> BString {
+    let mut out = BString::default();
+    for (key, value) in [
+        ("GIT_AUTHOR_NAME", author.name.to_owned()),
+        ("GIT_AUTHOR_EMAIL", author.email.to_owned()),
+        ("GIT_AUTHOR_DATE", {
+            let mut date = BString::from("@");
+            date.push_str(author.time.trim());
+            date
+        }),
+    ] {
+        out.push_str(key);
+        out.push(b'=');
+        out.push_str(gix_quote::single(value.as_ref()));
+        out.push(b'\n');
+    }
+    out
+}
+
+fn parse_author_script(script: &BStr) -> Option<gix_actor::Signature> {
+    let (mut name, mut email, mut time) = (None, None, None);
+    for line in script.lines() {
+        let (key, value) = line.split_once_str("=")?;
+        let value = unquote(value.as_bstr())?;
+        match key {
+            b"GIT_AUTHOR_NAME" => name = Some(value),
+            b"GIT_AUTHOR_EMAIL" => email = Some(value),
+            b"GIT_AUTHOR_DATE" => {
+                let value = value.strip_prefix(b"@").unwrap_or(&value);
+                time = Some(gix_date::parse_header(value.to_str().ok()?)?);
+            }
+            _ => {}
+        }
+    }
+    Some(gix_actor::Signature {
+        name: name?,
+        email: email?,
+        time: time?,
+    })
+}
+
+/// Undo the quoting of [`gix_quote::single()`].
+fn unquote(mut value: &BStr) -> Option<BString> {
+    let mut out = BString::default();
+    value = value.strip_prefix(b"'")?.as_bstr();
+    loop {
+        let end = value.find_byte(b'\'')?;
+        out.push_str(&value[..end]);
+        value = value[end + 1..].as_bstr();
+        if value.is_empty() {
+            return Some(out);
+        }
+        // An escaped character, like `'\''`, continues the quoted value.
+        let [b'\\', escaped, b'\'', rest @ ..] = value.as_bytes() else {
+            return None;
+        };
+        out.push(*escaped);
+        value = rest.as_bstr();
+    }
+}
@@ -0,0 +1,103 @@
+//! Read and write the todo lists of a rebase, like `git-rebase-todo` and `done`.
+use bstr::{BStr, BString, ByteSlice};
+use gix_error::{ErrorExt, ResultExt, ValidationError};
+use gix_hash::ObjectId;
+
+/// The error returned by [`parse()`].
+pub type Error = gix_error::Exn<ValidationError>;
+
+/// What to do with a commit in a todo list.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Action {
+    /// Use the commit as is.
+    Pick,
+    /// Use the commit, but edit its message.
+    Reword,
+    /// Use the commit, but stop for amending it.
+    Edit,
+    /// Meld the commit into the previous one, combining their messages.
+    Squash,
+    /// Meld the commit into the previous one, keeping only the message of the previous one.
+    Fixup,
+    /// Remove the commit.
+    Drop,
+}
+
+impl Action {
+    /// Return the name of the action as Git writes it.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Pick => "pick",
+            Action::Reword => "reword",
+            Action::Edit => "edit",
+            Action::Squash => "squash",
+            Action::Fixup => "fixup",
+            Action::Drop => "drop",
+        }
+    }
+
+    /// Parse the long or short name of an action, like `pick` or `p`.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"pick" | b"p" => Action::Pick,
+            b"reword" | b"r" => Action::Reword,
+            b"edit" | b"e" => Action::Edit,
+            b"squash" | b"s" => Action::Squash,
+            b"fixup" | b"f" => Action::Fixup,
+            b"drop" | b"d" => Action::Drop,
+            _ => return None,
+        })
+    }
+}
+
+/// A line in a todo list, like `pick <id> <subject>`.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Line {
+    /// What to do with the commit.
+    pub action: Action,
+    /// The id of the commit to process.
+    pub id: ObjectId,
+    /// The subject of the commit, which is only informational.
+    pub subject: BString,
+}
+
+impl Line {
+    /// Serialize this instance as a single line, including the trailing newline, into `out`.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write!(out, "{} {}", self.action.as_str(), self.id)?;
+        if !self.subject.is_empty() {
+            out.write_all(b" ")?;
+            out.write_all(&self.subject)?;
+        }
+        out.write_all(b"\n")
+    }
+}
+
+/// Parse all lines in `buf`, skipping empty lines and comments.
+///
+/// Note that only lines referring to commits by their full id are supported. Commands that don't refer to a commit,
+/// like `exec` or `label`, are rejected.
+pub fn parse(buf: &[u8]) -> Result<Vec<Line>, Error> {
+    buf.lines()
+        .map(ByteSlice::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with(b"#"))
+        .map(|(idx, line)| parse_line(line.as_bstr(), idx + 1))
+        .collect()
+}
+
+fn parse_line(line: &BStr, line_number: usize) -> Result<Line, Error> {
+    let mut tokens = line.splitn_str(3, " ");
+    let action = tokens
+        .next()
+        .and_then(Action::from_bytes)
+        .ok_or_else(|| ValidationError::new_with_input(format!("{line_number}: Unsupported command"), line).raise())?;
+    let id = tokens.next().unwrap_or_default();
+    let id = ObjectId::from_hex(id)
+        .or_raise(|| ValidationError::new_with_input(format!("{line_number}: Expected a full object id"), line))?;
+    Ok(Line {
+        action,
+        id,
+        subject: tokens.next().unwrap_or_default().into(),
+    })
+}
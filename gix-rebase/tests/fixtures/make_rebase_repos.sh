@@ -0,0 +1,47 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+cd repo
+git checkout -q -b main
+seq 1 10 > numbers
+echo a > letters
+git add numbers letters
+git commit -q -m base
+git tag base
+
+git checkout -q -b topic
+echo b >> letters
+git commit -q -am "add b"
+echo upstream > shared
+git add shared
+git commit -q -m "add shared file"
+sed -i.bak 's/^5$/five/' numbers && rm numbers.bak
+git commit -q -am "change five"
+
+git checkout -q main
+echo upstream > shared
+git add shared
+git commit -q -m "add shared file upstream"
+seq 0 10 > numbers
+git commit -q -am "prepend zero"
+
+git branch clean-topic topic
+git branch conflicting-topic topic
+git checkout -q main
+sed -i.bak 's/^5$/FIVE/' numbers && rm numbers.bak
+git commit -q -am "change five differently"
+git branch conflicting-main
+
+# A rebase that applies cleanly, with one commit becoming empty.
+git checkout -q -b clean-rebased clean-topic
+git rebase -q main~1
+
+# A rebase that stops at the last commit due to a conflict.
+git checkout -q conflicting-topic
+git rebase -q main >/dev/null 2>&1 || true
+test -d .git/rebase-merge
+cp -R .git/rebase-merge ../rebase-merge-stopped
+git rebase --abort
+
+git checkout -q main
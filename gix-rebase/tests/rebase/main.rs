@@ -0,0 +1,21 @@
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+
+pub use gix_testtools::Result;
+
+mod replay;
+mod state;
+mod todo;
+#[path = "../../../gix-merge/tests/utils/mod.rs"]
+pub mod utils;
+
+fn fixture() -> Result<PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_rebase_repos.sh")
+}
+
+/// Read the loose reference `name`, like `refs/heads/main`, from the repository in `root`.
+fn id(root: &Path, name: &str) -> Result<ObjectId> {
+    let hex = std::fs::read_to_string(root.join("repo/.git").join(name))?;
+    Ok(ObjectId::from_hex(hex.trim().as_bytes())?)
+}
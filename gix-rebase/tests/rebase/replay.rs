@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_rebase::replay::{Empty, Options, Outcome};
+
+use crate::{
+    fixture, id,
+    utils::{new_blob_merge_platform, new_diff_resource_cache, odb},
+};
+
+#[test]
+fn clean_replay_matches_git() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let commits = commits_to_rebase(&odb, &root, "refs/heads/clean-topic")?;
+    let onto = parent(&odb, id(&root, "refs/heads/main")?)?;
+
+    let out = replay(&root, &odb, onto, &commits, Empty::Drop)?;
+    assert!(out.stopped.is_none());
+    assert_eq!(out.steps.len(), 3);
+    assert_eq!(
+        out.steps.iter().map(|step| step.commit).collect::<Vec<_>>(),
+        commits,
+        "all commits are processed in order"
+    );
+    assert_eq!(
+        out.steps[1].rewritten, None,
+        "the commit that adds the shared file was already applied upstream, and was dropped"
+    );
+    assert_eq!(
+        out.head,
+        id(&root, "refs/heads/clean-rebased")?,
+        "author, committer and message are the same, so are the commits"
+    );
+
+    let out = replay(&root, &odb, onto, &commits, Empty::Keep)?;
+    assert!(
+        out.steps.iter().all(|step| step.rewritten.is_some()),
+        "nothing is dropped"
+    );
+    let kept = out.steps[1].rewritten.expect("kept");
+    let mut buf = Vec::new();
+    let kept_commit = odb.find_commit(&kept, &mut buf)?;
+    assert_eq!(kept_commit.message, "add shared file\n");
+    let kept_tree = kept_commit.tree();
+    assert_eq!(
+        odb.find_commit(&parent(&odb, kept)?, &mut Vec::new())?.tree(),
+        kept_tree,
+        "the kept commit doesn't change anything"
+    );
+    Ok(())
+}
+
+#[test]
+fn stop_at_conflict() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let commits = commits_to_rebase(&odb, &root, "refs/heads/conflicting-topic")?;
+    let onto = id(&root, "refs/heads/conflicting-main")?;
+
+    let out = replay(&root, &odb, onto, &commits, Empty::Drop)?;
+    assert_eq!(out.steps.len(), 2, "the first two commits apply cleanly");
+    assert_eq!(
+        out.head,
+        out.steps[0].rewritten.expect("picked"),
+        "the second one was dropped"
+    );
+
+    let stopped = out.stopped.expect("the last commit conflicts");
+    assert_eq!(stopped.commit, commits[2]);
+    assert_eq!(stopped.tree_merge.conflicts.len(), 1);
+    assert_eq!(stopped.tree_merge.conflicts[0].ours.location(), "numbers");
+    Ok(())
+}
+
+fn replay<'a>(
+    root: &Path,
+    odb: &'a gix_odb::memory::Proxy<gix_odb::Handle>,
+    onto: ObjectId,
+    commits: &[ObjectId],
+    empty: Empty,
+) -> crate::Result<Outcome<'a>> {
+    let committer = gix_actor::SignatureRef {
+        name: "committer".into(),
+        email: "committer@example.com".into(),
+        time: "946771200 +0000",
+    };
+    Ok(gix_rebase::replay(
+        onto,
+        commits,
+        committer,
+        odb,
+        &mut new_diff_resource_cache(root),
+        &mut new_blob_merge_platform(root, None),
+        &mut |id| id.to_hex_with_len(7).to_string(),
+        Options {
+            empty,
+            ..Default::default()
+        },
+    )
+    .map_err(gix_error::Exn::into_error)?)
+}
+
+fn parent(odb: &impl gix_object::Find, id: ObjectId) -> crate::Result<ObjectId> {
+    let mut buf = Vec::new();
+    Ok(odb.find_commit(&id, &mut buf)?.parents().next().expect("has parent"))
+}
+
+/// Return the first-parent ancestry of `tip` up to the `base` tag, oldest first.
+fn commits_to_rebase(odb: &impl gix_object::Find, root: &Path, tip: &str) -> crate::Result<Vec<ObjectId>> {
+    let base = id(root, "refs/tags/base")?;
+    let mut commits = Vec::new();
+    let mut current = id(root, tip)?;
+    while current != base {
+        commits.push(current);
+        current = parent(odb, current)?;
+    }
+    commits.reverse();
+    Ok(commits)
+}
@@ -0,0 +1,102 @@
+use gix_rebase::{State, replay::Empty, state::DIR_NAME};
+
+use crate::{fixture, id};
+
+#[test]
+fn read_stopped_state_written_by_git() -> crate::Result {
+    let root = fixture()?;
+    let dir = root.join("rebase-merge-stopped");
+    let state = State::from_dir(&dir, gix_testtools::object_hash())
+        .map_err(gix_error::Exn::into_error)?
+        .expect("present");
+
+    assert_eq!(
+        state.head_name.as_ref().map(ToString::to_string).as_deref(),
+        Some("refs/heads/conflicting-topic")
+    );
+    assert_eq!(state.onto, id(&root, "refs/heads/conflicting-main")?);
+    assert_eq!(state.orig_head, id(&root, "refs/heads/conflicting-topic")?);
+    assert_eq!(state.empty, Empty::Drop);
+    assert!(state.todo.is_empty());
+    assert_eq!(state.done.len(), 2);
+
+    let stopped = state.stopped.as_ref().expect("stopped due to a conflict");
+    assert_eq!(stopped.commit, state.orig_head, "it stopped at the last commit");
+    assert_eq!(stopped.message, "change five\n\n");
+    assert_eq!(stopped.author.name, "author");
+    assert_eq!(stopped.author.email, "author@example.com");
+    assert_eq!(stopped.author.time.seconds, 946684800);
+    Ok(())
+}
+
+#[test]
+fn write_produces_files_like_git() -> crate::Result {
+    let root = fixture()?;
+    let git_dir = root.join("rebase-merge-stopped");
+    let state = State::from_dir(&git_dir, gix_testtools::object_hash())
+        .map_err(gix_error::Exn::into_error)?
+        .expect("present");
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let dir = tmp.path().join(DIR_NAME);
+    state.write_to(&dir).map_err(gix_error::Exn::into_error)?;
+    for name in [
+        "head-name",
+        "onto",
+        "orig-head",
+        "interactive",
+        "drop_redundant_commits",
+        "done",
+        "git-rebase-todo",
+        "msgnum",
+        "end",
+        "stopped-sha",
+        "message",
+        "author-script",
+    ] {
+        assert_eq!(
+            std::fs::read(dir.join(name))?,
+            std::fs::read(git_dir.join(name))?,
+            "{name} should match what Git wrote"
+        );
+    }
+    assert_eq!(
+        State::from_dir(&dir, gix_testtools::object_hash())
+            .map_err(gix_error::Exn::into_error)?
+            .as_ref(),
+        Some(&state),
+        "round-trips"
+    );
+
+    let mut state = state;
+    state.head_name = None;
+    state.empty = Empty::Keep;
+    state.stopped = None;
+    state.write_to(&dir).map_err(gix_error::Exn::into_error)?;
+    for name in ["stopped-sha", "message", "author-script", "drop_redundant_commits"] {
+        assert!(
+            !dir.join(name).exists(),
+            "{name} is removed as it doesn't apply anymore"
+        );
+    }
+    assert_eq!(std::fs::read(dir.join("head-name"))?, b"detached HEAD\n");
+    assert_eq!(
+        State::from_dir(&dir, gix_testtools::object_hash())
+            .map_err(gix_error::Exn::into_error)?
+            .as_ref(),
+        Some(&state),
+        "round-trips"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_directory_means_no_rebase_in_progress() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    assert_eq!(
+        State::from_dir(&tmp.path().join(DIR_NAME), gix_testtools::object_hash())
+            .map_err(gix_error::Exn::into_error)?,
+        None
+    );
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use gix_rebase::todo::{Action, Line, parse};
+
+use crate::fixture;
+
+#[test]
+fn parse_git_todo_list_with_comments() -> crate::Result {
+    let dir = fixture()?.join("rebase-merge-stopped");
+    let lines = parse(&std::fs::read(dir.join("git-rebase-todo.backup"))?).map_err(gix_error::Exn::into_error)?;
+    assert_eq!(
+        lines.iter().map(|line| line.subject.to_string()).collect::<Vec<_>>(),
+        ["add b", "change five"]
+    );
+    assert!(lines.iter().all(|line| line.action == Action::Pick));
+
+    let done = std::fs::read(dir.join("done"))?;
+    assert_eq!(
+        parse(&done).map_err(gix_error::Exn::into_error)?,
+        lines,
+        "all commits were processed"
+    );
+
+    let mut buf = Vec::new();
+    for line in &lines {
+        line.write_to(&mut buf)?;
+    }
+    assert_eq!(buf, done, "lines are written just like Git does");
+    Ok(())
+}
+
+#[test]
+fn short_actions_and_missing_subjects() -> crate::Result {
+    let id = gix_hash::Kind::Sha1.null();
+    let lines = parse(format!("p {id}\n\n  f {id} subject with spaces  \n# comment\nd {id}\n").as_bytes())
+        .map_err(gix_error::Exn::into_error)?;
+    assert_eq!(
+        lines,
+        [
+            Line {
+                action: Action::Pick,
+                id,
+                subject: "".into()
+            },
+            Line {
+                action: Action::Fixup,
+                id,
+                subject: "subject with spaces".into()
+            },
+            Line {
+                action: Action::Drop,
+                id,
+                subject: "".into()
+            }
+        ]
+    );
+
+    let mut buf = Vec::new();
+    lines[0].write_to(&mut buf)?;
+    assert_eq!(
+        buf,
+        format!("pick {id}\n").as_bytes(),
+        "no trailing space without subject"
+    );
+    Ok(())
+}
+
+#[test]
+fn unsupported_lines() {
+    for input in ["exec make", "pick abc", "pick", "label onto"] {
+        let err = parse(input.as_bytes()).expect_err("invalid or unsupported");
+        assert!(
+            err.to_string().starts_with("1: "),
+            "{input}: the line number is included"
+        );
+    }
+}
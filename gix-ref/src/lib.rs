@@ -14,6 +14,8 @@
 //!     * one reference maps to a file on disk
 //!   * **packed**
 //!     * references are stored in a single human-readable file, along with their targets if they are symbolic.
+//! * **[reftable][reftable::Stack]**
+//!   * references and their logs are stored in a stack of binary tables, with newer tables shadowing older ones.
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -29,7 +31,7 @@ use gix_object::bstr::{BStr, BString};
 
 #[path = "store/mod.rs"]
 mod store_impl;
-pub use store_impl::{file, packed};
+pub use store_impl::{file, packed, reftable};
 
 mod fullname;
 ///
@@ -70,8 +72,22 @@ pub mod store {
             /// to avoid side effects. This only needs to be `true` on Windows, but can be `true` on other platforms
             /// if they need to remain compatible with Windows.
             pub prohibit_windows_device_names: bool,
+            /// The format in which references are stored, the equivalent of `extensions.refStorage`.
+            pub format: super::Format,
+            /// If `Some(method)`, make written references, `packed-refs` and reflogs durable with it.
+            /// This is the equivalent of `reference` being part of `core.fsync`.
+            pub fsync: Option<gix_fs::fsync::Method>,
         }
     }
+    /// The format in which a repository stores its references.
+    #[derive(Default, Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Clone, Copy)]
+    pub enum Format {
+        /// Store references as loose files and in `packed-refs`, with reflogs in `logs/`.
+        #[default]
+        Files,
+        /// Store references and their logs in a stack of [reftables](crate::reftable) in `reftable/`.
+        Reftable,
+    }
     /// The way a file store handles the reflog
     #[derive(Default, Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Clone, Copy)]
     pub enum WriteReflog {
@@ -86,20 +102,12 @@ pub mod store {
 
     /// A thread-local handle for interacting with a [`Store`][crate::Store] to find and iterate references.
     #[derive(Clone)]
-    #[expect(
-        dead_code,
-        reason = "the general reference-store handle is scaffolding for planned ref-table support"
-    )]
     pub(crate) struct Handle {
         /// A way to access shared state with the requirement that interior mutability doesn't leak or is incorporated into error types
         /// if it could. The latter can't happen if references to said internal aren't ever returned.
         state: handle::State,
     }
 
-    #[expect(
-        dead_code,
-        reason = "the general reference-store state is scaffolding for planned ref-table support"
-    )]
     pub(crate) enum State {
         Loose { store: file::Store },
     }
@@ -116,10 +124,6 @@ pub mod store {
 
 /// The git reference store.
 /// TODO: Figure out if handles are needed at all, which depends on the ref-table implementation.
-#[expect(
-    dead_code,
-    reason = "callers still use file::Store directly while this general store awaits ref-table support"
-)]
 pub(crate) struct Store {
     inner: store::State,
 }
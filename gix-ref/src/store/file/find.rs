@@ -164,6 +164,12 @@ impl file::Store {
         let full_name = precomposed_partial_name
             .unwrap_or(partial_name)
             .construct_full_name_ref(inbetween, path_buf, consider_pseudo_ref);
+        if let Some(stacks) = &self.reftable {
+            let reference = self.reftable_find(stacks, full_name)?;
+            if reference.is_some() || !file::reftable::is_file_only(full_name) {
+                return Ok(reference);
+            }
+        }
         let content_buf = match self.ref_contents(full_name) {
             Ok(content_buf) => content_buf,
             Err(err) if err.kind() == io::ErrorKind::NotADirectory => return Ok(None),
@@ -457,6 +463,15 @@ mod error {
         PackedRef(#[from] packed::find::Error),
         #[error("Could not open the packed refs buffer when trying to find references.")]
         PackedOpen(#[from] packed::buffer::open::Error),
+        #[error("Could not open the reftable stack when trying to find references")]
+        ReftableOpen(#[from] crate::reftable::stack::open::Error),
+        #[error("A reftable lookup failed")]
+        ReftableDecode(#[from] crate::reftable::table::decode::Error),
+        #[error("The reftable record {name:?} does not have a valid name or target")]
+        ReftableReference {
+            name: gix_object::bstr::BString,
+            source: gix_validate::reference::name::Error,
+        },
     }
 
     impl From<Infallible> for Error {
@@ -41,6 +41,16 @@ impl SortedLoosePaths {
             }),
         }
     }
+
+    /// Return an instance that doesn't yield any paths.
+    pub fn empty(base: PathBuf) -> Self {
+        SortedLoosePaths {
+            base,
+            prefix: None,
+            suffix: None,
+            file_walk: None,
+        }
+    }
 }
 
 impl Iterator for SortedLoosePaths {
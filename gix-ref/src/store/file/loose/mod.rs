@@ -43,6 +43,8 @@ mod init {
                 object_hash,
                 precompose_unicode,
                 prohibit_windows_device_names,
+                format,
+                fsync,
             }: crate::store::init::Options,
         ) -> Self {
             file::Store {
@@ -55,6 +57,8 @@ mod init {
                 packed: gix_fs::SharedFileSnapshotMut::new().into(),
                 object_hash,
                 precompose_unicode,
+                reftable: file::reftable::stacks_for(format),
+                fsync,
             }
         }
 
@@ -71,6 +75,8 @@ mod init {
                 object_hash,
                 precompose_unicode,
                 prohibit_windows_device_names,
+                format,
+                fsync,
             }: crate::store::init::Options,
         ) -> Self {
             file::Store {
@@ -83,6 +89,8 @@ mod init {
                 packed: gix_fs::SharedFileSnapshotMut::new().into(),
                 object_hash,
                 precompose_unicode,
+                reftable: file::reftable::stacks_for(format),
+                fsync,
             }
         }
     }
@@ -34,10 +34,16 @@ pub struct Store {
     /// Iterated references will be returned verbatim, thus when sending them over the wire they have to be precomposed
     /// as needed.
     pub precompose_unicode: bool,
+    /// If `Some(method)`, make written loose references, `packed-refs` and reflogs durable with it,
+    /// along with the directories containing them.
+    pub fsync: Option<gix_fs::fsync::Method>,
     /// A packed buffer which can be mapped in one version and shared as such.
     /// It's updated only in one spot, which is prior to reading it based on file stamps.
     /// Doing it like this has the benefit of being able to hand snapshots out to people without blocking others from updating it.
     packed: packed::modifiable::MutableSharedBuffer,
+    /// The reftable stacks seen so far if references are stored in the [reftable format](crate::store::Format::Reftable),
+    /// which are shared among clones so tables don't have to be read again.
+    reftable: Option<reftable::SharedStacks>,
 }
 
 mod access {
@@ -116,6 +122,8 @@ pub struct Transaction<'s, 'p> {
     packed_transaction: Option<crate::store_impl::packed::Transaction>,
     updates: Option<Vec<transaction::Edit>>,
     packed_refs: transaction::PackedRefs<'p>,
+    /// The locked stacks of all edits if references are stored in reftables.
+    reftable_locks: Vec<crate::reftable::stack::Lock>,
 }
 
 ///
@@ -144,5 +152,7 @@ pub mod transaction;
 ///
 pub mod packed;
 
+mod reftable;
+
 mod raw_ext;
 pub use raw_ext::ReferenceExt;
@@ -26,6 +26,8 @@ pub struct LooseThenPacked<'p, 's> {
     iter_packed: Option<Peekable<packed::Iter<'p>>>,
     iter_git_dir: Peekable<SortedLoosePaths>,
     iter_common_dir: Option<Peekable<SortedLoosePaths>>,
+    /// All references to return if the store keeps them in reftables, in which case there are no loose or packed ones.
+    iter_reftable: Option<std::vec::IntoIter<Reference>>,
     buf: Vec<u8>,
 }
 
@@ -151,6 +153,10 @@ impl Iterator for LooseThenPacked<'_, '_> {
                 None => git_dir.peek().map(|r| (r, IterKind::Git)),
             }
         }
+        if let Some(reftable_iter) = self.iter_reftable.as_mut() {
+            let reference = reftable_iter.next()?;
+            return Some(Ok(self.strip_namespace(reference)));
+        }
         match self.iter_packed.as_mut() {
             Some(packed_iter) => match (
                 peek_loose(&mut self.iter_git_dir, self.iter_common_dir.as_mut()),
@@ -429,6 +435,23 @@ impl file::Store {
         common_dir_info: Option<IterInfo<'_>>,
         packed: Option<&'p packed::Buffer>,
     ) -> std::io::Result<LooseThenPacked<'p, 's>> {
+        if let Some(stacks) = &self.reftable {
+            let is_pseudo = matches!(git_dir_info, IterInfo::Pseudo { .. });
+            let refs = self
+                .reftable_refs(stacks, git_dir_info.prefix().as_deref(), is_pseudo)
+                .map_err(std::io::Error::other)?;
+            return Ok(LooseThenPacked {
+                git_dir: self.git_dir(),
+                common_dir: self.common_dir(),
+                object_hash: self.object_hash,
+                iter_packed: None,
+                iter_git_dir: SortedLoosePaths::empty(self.git_dir.clone()).peekable(),
+                iter_common_dir: None,
+                iter_reftable: Some(refs.into_iter()),
+                buf: Vec::new(),
+                namespace: self.namespace.as_ref(),
+            });
+        }
         Ok(LooseThenPacked {
             git_dir: self.git_dir(),
             common_dir: self.common_dir(),
@@ -446,6 +469,7 @@ impl file::Store {
             },
             iter_git_dir: git_dir_info.into_iter(),
             iter_common_dir: common_dir_info.map(IterInfo::into_iter),
+            iter_reftable: None,
             buf: Vec::new(),
             namespace: self.namespace.as_ref(),
         })
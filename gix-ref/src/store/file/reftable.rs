@@ -0,0 +1,218 @@
+//! Support for stores keeping their references in [reftables](crate::reftable) instead of loose files and `packed-refs`.
+//!
+//! This lives in [`file::Store`] rather than in the general store as that is what all users, including `gix`, hold on to.
+//! Pseudo-refs like `FETCH_HEAD` are always stored as files, and linked worktrees keep their own stack, so reftable
+//! repositories need most of what [`file::Store`] does anyway.
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use gix_features::threading::{MutableOnDemand, OwnShared, get_mut};
+use gix_object::bstr::{BStr, ByteSlice};
+
+use crate::{
+    FullName, FullNameRef, Reference, Target,
+    reftable::{self, LogValue, RefRecord, RefValue},
+    store::{Format, WriteReflog},
+    store_impl::file::{self, find},
+};
+
+/// All stacks seen by a store, keyed by their directory.
+pub(in crate::store_impl::file) type SharedStacks = OwnShared<MutableOnDemand<Vec<reftable::Stack>>>;
+
+pub(in crate::store_impl::file) fn stacks_for(format: Format) -> Option<SharedStacks> {
+    match format {
+        Format::Files => None,
+        Format::Reftable => Some(OwnShared::new(MutableOnDemand::new(Vec::new()))),
+    }
+}
+
+/// Return `true` if `name` is always stored as file, even if all other references are stored in reftables.
+pub(in crate::store_impl::file) fn is_file_only(name: &FullNameRef) -> bool {
+    matches!(name.as_bstr().as_bytes(), b"FETCH_HEAD" | b"MERGE_HEAD")
+}
+
+/// Turn `record` into a reference, or return `None` if it is a deletion.
+pub(in crate::store_impl::file) fn to_reference(record: RefRecord) -> Result<Option<Reference>, find::Error> {
+    let invalid = |source| find::Error::ReftableReference {
+        name: record.name.clone(),
+        source,
+    };
+    let (target, peeled) = match record.value {
+        RefValue::Deletion => return Ok(None),
+        RefValue::Object(id) => (Target::Object(id), None),
+        RefValue::Peeled { target, peeled } => (Target::Object(target), Some(peeled)),
+        RefValue::Symbolic(target) => (Target::Symbolic(target.try_into().map_err(invalid)?), None),
+    };
+    Ok(Some(Reference {
+        name: record.name.clone().try_into().map_err(invalid)?,
+        target,
+        peeled,
+    }))
+}
+
+/// Access
+impl file::Store {
+    /// Return the format in which this store keeps its references.
+    pub fn format(&self) -> Format {
+        if self.reftable.is_some() {
+            Format::Reftable
+        } else {
+            Format::Files
+        }
+    }
+
+    /// Return an up-to-date version of the reftable stack holding all shared references,
+    /// or `None` if this store [doesn't use reftables](Self::format()).
+    ///
+    /// Use it to inspect the tables directly or to [compact](reftable::stack::Lock::compact()) them.
+    pub fn reftable_stack(&self) -> Result<Option<reftable::Stack>, reftable::stack::open::Error> {
+        self.reftable
+            .as_ref()
+            .map(|stacks| self.reftable_stack_at(stacks, &self.common_dir_resolved().join("reftable")))
+            .transpose()
+    }
+
+    /// Return the most recent version of the stack in `dir`, reusing tables we have seen before.
+    pub(in crate::store_impl::file) fn reftable_stack_at(
+        &self,
+        stacks: &SharedStacks,
+        dir: &Path,
+    ) -> Result<reftable::Stack, reftable::stack::open::Error> {
+        let mut stacks = get_mut(stacks);
+        match stacks.iter_mut().find(|stack| stack.dir() == dir) {
+            Some(stack) => {
+                if let Some(reloaded) = stack.reload()? {
+                    *stack = reloaded;
+                }
+                Ok(stack.clone())
+            }
+            None => {
+                let stack = reftable::Stack::at(dir, self.object_hash)?;
+                stacks.push(stack.clone());
+                Ok(stack)
+            }
+        }
+    }
+
+    /// Remember `stack` as most recent version of the stack in its directory.
+    pub(in crate::store_impl::file) fn reftable_stack_update(&self, stacks: &SharedStacks, stack: reftable::Stack) {
+        let mut stacks = get_mut(stacks);
+        match stacks.iter_mut().find(|existing| existing.dir() == stack.dir()) {
+            Some(existing) => *existing = stack,
+            None => stacks.push(stack),
+        }
+    }
+
+    /// Return the directory of the stack holding `name`, along with the name of its record in that stack.
+    pub(in crate::store_impl::file) fn reftable_location(&self, name: &FullNameRef) -> (PathBuf, FullName) {
+        // Worktree-private references live in the stack of their worktree, which is where their reflogs would be.
+        let is_reflog = true;
+        let (base, name) = self.to_base_dir_and_relative_name(name, is_reflog);
+        let name = match &self.namespace {
+            Some(namespace) => namespace.to_owned().into_namespaced_name(name),
+            None => name.to_owned(),
+        };
+        (base.join("reftable"), name)
+    }
+
+    /// Find the reference `name` in its stack, or return `None` if it doesn't exist.
+    pub(in crate::store_impl::file) fn reftable_find(
+        &self,
+        stacks: &SharedStacks,
+        name: &FullNameRef,
+    ) -> Result<Option<Reference>, find::Error> {
+        let (dir, full_name) = self.reftable_location(name);
+        let stack = self.reftable_stack_at(stacks, &dir)?;
+        let Some(record) = stack.find_ref(full_name.as_bstr())? else {
+            return Ok(None);
+        };
+        let mut reference = to_reference(record)?;
+        if let Some((reference, namespace)) = reference.as_mut().zip(self.namespace.as_ref()) {
+            reference.strip_namespace(namespace);
+        }
+        Ok(reference)
+    }
+
+    /// Return all references whose name starts with `prefix`, sorted by name, or all pseudo-references if `pseudo` is `true`.
+    pub(in crate::store_impl::file) fn reftable_refs(
+        &self,
+        stacks: &SharedStacks,
+        prefix: Option<&BStr>,
+        pseudo: bool,
+    ) -> Result<Vec<Reference>, find::Error> {
+        let matches = |name: &BStr| {
+            if pseudo {
+                !name.contains(&b'/') && name.ends_with(b"HEAD")
+            } else {
+                name.starts_with(prefix.map_or(b"refs/".as_slice(), |prefix| prefix.as_bytes()))
+            }
+        };
+        let mut refs = BTreeMap::new();
+        let own = self.git_dir.join("reftable");
+        let common = self.common_dir.as_ref().map(|dir| dir.join("reftable"));
+        for (dir, is_common) in common.map(|dir| (dir, true)).into_iter().chain(Some((own, false))) {
+            for record in self.reftable_stack_at(stacks, &dir)?.refs()? {
+                if !matches(record.name.as_bstr()) {
+                    continue;
+                }
+                let Some(reference) = to_reference(record)? else {
+                    continue;
+                };
+                // The stack of the main worktree also has the private references of the main worktree, which we don't see.
+                let is_private = reference
+                    .name
+                    .category()
+                    .is_some_and(|category| category.is_worktree_private());
+                if is_common && is_private {
+                    continue;
+                }
+                refs.insert(reference.name.clone(), reference);
+            }
+        }
+        Ok(refs.into_values().collect())
+    }
+
+    /// Return `true` if a log entry should be written for the reference stored as `name` in `stack`, similar to what
+    /// decides if reflog files are written.
+    pub(in crate::store_impl::file) fn reftable_should_write_reflog(
+        &self,
+        stack: &reftable::Stack,
+        name: &BStr,
+        force_create_reflog: bool,
+    ) -> Result<bool, reftable::table::decode::Error> {
+        Ok(match self.write_reflog {
+            WriteReflog::Disable => false,
+            WriteReflog::Always => true,
+            WriteReflog::Normal => {
+                force_create_reflog
+                    || self.should_autocreate_reflog(&gix_path::from_bstr(name))
+                    || !stack.logs(name)?.is_empty()
+            }
+        })
+    }
+
+    /// Write the log of `name` in the format of reflog files into `buf`, oldest entry first, or return `false` if it has none.
+    pub(in crate::store_impl::file) fn reftable_reflog(
+        &self,
+        stacks: &SharedStacks,
+        name: &FullNameRef,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<bool> {
+        let (dir, full_name) = self.reftable_location(name);
+        let logs = self
+            .reftable_stack_at(stacks, &dir)
+            .map_err(io::Error::other)?
+            .logs(full_name.as_bstr())
+            .map_err(io::Error::other)?;
+        buf.clear();
+        for record in logs.iter().rev() {
+            if let LogValue::Update(line) = &record.value {
+                line.write_to(buf)?;
+            }
+        }
+        Ok(!logs.is_empty())
+    }
+}
@@ -1,6 +1,15 @@
+use gix_hash::ObjectId;
+
 use crate::{
     Target,
-    store_impl::file::{Transaction, transaction::PackedRefs},
+    reftable::{self, LogRecord, LogValue, RefRecord, RefValue},
+    store_impl::{
+        file,
+        file::{
+            Transaction,
+            transaction::{Edit, PackedRefs},
+        },
+    },
     transaction::{Change, LogChange, RefEdit, RefLog},
 };
 
@@ -31,6 +40,9 @@ impl Transaction<'_, '_> {
 
     fn commit_inner(self, committer: Option<gix_actor::SignatureRef<'_>>) -> Result<Vec<RefEdit>, Error> {
         let mut updates = self.updates.expect("BUG: must call prepare before commit");
+        if let Some(stacks) = &self.store.reftable {
+            return commit_reftable(self.store, stacks, updates, self.reftable_locks, committer);
+        }
         let delete_loose_refs = matches!(
             self.packed_refs,
             PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(_)
@@ -41,48 +53,22 @@ impl Transaction<'_, '_> {
             assert!(!change.update.deref, "Deref mode is turned into splits and turned off");
             match &change.update.change {
                 // reflog first, then reference
-                Change::Update { log, new, expected } => {
+                Change::Update { log, new, .. } => {
                     let lock = change.lock.take();
                     let (update_ref, update_reflog) = match log.mode {
                         RefLog::Only => (false, true),
                         RefLog::AndReference => (true, true),
                     };
                     if update_reflog {
-                        let log_update = match new {
-                            Target::Symbolic(_) => {
-                                // Special HACK: no reflog for symref changes as there is no OID involved which the reflog needs.
-                                // Unless, the ref is new and we can obtain a peeled id
-                                // identified by the expectation of what could be there, as is the case when cloning.
-                                match expected {
-                                    PreviousValue::ExistingMustMatch(Target::Object(oid)) => {
-                                        Some((Some(gix_hash::ObjectId::null(oid.kind())), oid))
-                                    }
-                                    _ => None,
-                                }
-                            }
-                            Target::Object(new_oid) => {
-                                let previous = match expected {
-                                    // Here, this means that the ref already existed, and that it will receive (even transitively)
-                                    // the given value
-                                    PreviousValue::MustExistAndMatch(Target::Object(oid)) => Some(oid.to_owned()),
-                                    _ => None,
-                                }
-                                .or(change.leaf_referent_previous_oid);
-                                Some((previous, new_oid))
-                            }
-                        };
-                        if let Some((previous, new_oid)) = log_update {
-                            let do_update = previous.as_ref() != Some(new_oid);
-                            if do_update {
-                                self.store.reflog_create_or_append(
-                                    change.update.name.as_ref(),
-                                    previous,
-                                    new_oid,
-                                    committer,
-                                    log.message.as_ref(),
-                                    log.force_create_reflog,
-                                )?;
-                            }
+                        if let Some((previous, new_oid)) = log_update(change) {
+                            self.store.reflog_create_or_append(
+                                change.update.name.as_ref(),
+                                previous,
+                                &new_oid,
+                                committer,
+                                log.message.as_ref(),
+                                log.force_create_reflog,
+                            )?;
                         }
                     }
                     // Don't do anything else while keeping the lock after potentially updating the reflog.
@@ -93,7 +79,14 @@ impl Transaction<'_, '_> {
                         continue;
                     }
                     if update_ref {
-                        if let Some(Err(err)) = lock.map(gix_lock::Marker::commit) {
+                        let res = lock.map(gix_lock::Marker::commit);
+                        if let Some(Ok(reference_path)) = res.as_ref().filter(|_| self.store.fsync.is_some()) {
+                            if let Some(directory) = reference_path.parent() {
+                                // The reference is already in place, and not all platforms and filesystems support this.
+                                gix_fs::fsync::directory(directory).ok();
+                            }
+                        }
+                        if let Some(Err(err)) = res {
                             // TODO: when Kind::IsADirectory becomes stable, use that.
                             let err = if err.instance.resource_path().is_dir() {
                                 gix_tempfile::remove_dir::empty_depth_first(err.instance.resource_path())
@@ -176,6 +169,118 @@ impl Transaction<'_, '_> {
         Ok(updates.into_iter().map(|edit| edit.update).collect())
     }
 }
+/// Return the previous and the new object id to write into the reflog for the update in `change`,
+/// or `None` if no log entry should be written as the object id doesn't change.
+fn log_update(change: &Edit) -> Option<(Option<ObjectId>, ObjectId)> {
+    let Change::Update { new, expected, .. } = &change.update.change else {
+        return None;
+    };
+    let (previous, new_oid) = match new {
+        Target::Symbolic(_) => {
+            // Special HACK: no reflog for symref changes as there is no OID involved which the reflog needs.
+            // Unless, the ref is new and we can obtain a peeled id
+            // identified by the expectation of what could be there, as is the case when cloning.
+            // Otherwise, both the previous and the new target must peel to an object, as is the case when switching branches,
+            // which is logged even if the object doesn't change.
+            match expected {
+                PreviousValue::ExistingMustMatch(Target::Object(oid)) => (Some(ObjectId::null(oid.kind())), *oid),
+                _ => {
+                    return change
+                        .leaf_referent_previous_oid
+                        .zip(change.symbolic_target_new_oid)
+                        .map(|(previous, new_oid)| (Some(previous), new_oid));
+                }
+            }
+        }
+        Target::Object(new_oid) => {
+            let previous = match expected {
+                // Here, this means that the ref already existed, and that it will receive (even transitively)
+                // the given value
+                PreviousValue::MustExistAndMatch(Target::Object(oid)) => Some(oid.to_owned()),
+                _ => None,
+            }
+            .or(change.leaf_referent_previous_oid);
+            (previous, *new_oid)
+        }
+    };
+    (previous != Some(new_oid)).then_some((previous, new_oid))
+}
+
+/// Write all `updates` into new tables of the stacks locked by `locks`, one per stack, instead of writing files.
+fn commit_reftable(
+    store: &file::Store,
+    stacks: &file::reftable::SharedStacks,
+    updates: Vec<Edit>,
+    locks: Vec<reftable::stack::Lock>,
+    committer: Option<gix_actor::SignatureRef<'_>>,
+) -> Result<Vec<RefEdit>, Error> {
+    let mut tables: Vec<_> = locks.into_iter().map(|lock| (lock, Vec::new(), Vec::new())).collect();
+    for change in &updates {
+        assert!(!change.update.deref, "Deref mode is turned into splits and turned off");
+        let (dir, name) = store.reftable_location(change.update.name.as_ref());
+        let (lock, refs, logs) = tables
+            .iter_mut()
+            .find(|(lock, _, _)| lock.stack().dir() == dir)
+            .expect("stacks of all edits are locked");
+        let update_index = lock.next_update_index();
+        let name = name.into_inner();
+        match &change.update.change {
+            Change::Update { log, new, .. } => {
+                if log.mode == RefLog::AndReference {
+                    refs.push(RefRecord {
+                        name: name.clone(),
+                        update_index,
+                        value: match new {
+                            Target::Object(id) => RefValue::Object(*id),
+                            Target::Symbolic(target) => RefValue::Symbolic(target.as_bstr().to_owned()),
+                        },
+                    });
+                }
+                let Some((previous, new_oid)) = log_update(change) else {
+                    continue;
+                };
+                if store.reftable_should_write_reflog(lock.stack(), name.as_ref(), log.force_create_reflog)? {
+                    let committer = committer.ok_or(file::log::create_or_update::Error::MissingCommitter)?;
+                    logs.push(LogRecord {
+                        name,
+                        update_index,
+                        value: LogValue::Update(crate::log::Line {
+                            previous_oid: previous.unwrap_or_else(|| new_oid.kind().null()),
+                            new_oid,
+                            signature: committer.trim().to_owned().map_err(Error::CommitterTime)?,
+                            message: log.message.clone(),
+                        }),
+                    });
+                }
+            }
+            Change::Delete { log: mode, .. } => {
+                if *mode == RefLog::AndReference {
+                    refs.push(RefRecord {
+                        name: name.clone(),
+                        update_index,
+                        value: RefValue::Deletion,
+                    });
+                }
+                // Like with reflog files, the log goes away with the reference, which requires deleting each entry.
+                for entry in lock.stack().logs(name.as_ref())? {
+                    logs.push(LogRecord {
+                        value: LogValue::Deletion,
+                        ..entry
+                    });
+                }
+            }
+        }
+    }
+    for (lock, refs, logs) in tables {
+        if refs.is_empty() && logs.is_empty() {
+            continue;
+        }
+        let stack = lock.commit(refs, logs)?;
+        store.reftable_stack_update(stacks, stack);
+    }
+    Ok(updates.into_iter().map(|edit| edit.update).collect())
+}
+
 mod error {
     use gix_object::bstr::BString;
 
@@ -197,6 +302,12 @@ mod error {
         DeleteReflog { full_name: BString, source: std::io::Error },
         #[error("The reflog could not be created or updated")]
         CreateOrUpdateRefLog(#[from] file::log::create_or_update::Error),
+        #[error("The time of the committer could not be parsed for use in the reflog")]
+        CommitterTime(#[source] gix_actor::date::Error),
+        #[error("The reftable stack could not be read")]
+        ReftableDecode(#[from] crate::reftable::table::decode::Error),
+        #[error("The new reftable could not be added to the stack")]
+        ReftableCommit(#[from] crate::reftable::stack::commit::Error),
     }
 }
 pub use error::Error;
@@ -39,6 +39,9 @@ pub(in crate::store_impl::file) struct Edit {
     /// For symbolic refs, this is the previous OID to put into the reflog instead of our own previous value. It's the
     /// peeled value of the leaf referent.
     leaf_referent_previous_oid: Option<ObjectId>,
+    /// For updates that make a reference symbolic, this is the peeled value of its new target, to be used as new OID
+    /// in the reflog.
+    symbolic_target_new_oid: Option<ObjectId>,
 }
 
 impl Edit {
@@ -73,6 +76,7 @@ impl file::Store {
             packed_transaction: None,
             updates: None,
             packed_refs: PackedRefs::default(),
+            reftable_locks: Vec::new(),
         }
     }
 }
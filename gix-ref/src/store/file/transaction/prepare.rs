@@ -47,6 +47,31 @@ impl Transaction<'_, '_> {
             })
     }
 
+    /// Read the current value of a reference from the locked stack that contains it.
+    fn read_existing_reftable_ref(
+        store: &file::Store,
+        name: &FullNameRef,
+        locks: &[crate::reftable::stack::Lock],
+    ) -> Result<Option<Reference>, Error> {
+        let (dir, full_name) = store.reftable_location(name);
+        let lock = locks
+            .iter()
+            .find(|lock| lock.stack().dir() == dir)
+            .expect("stacks of all edits are locked");
+        let record = lock
+            .stack()
+            .find_ref(full_name.as_bstr())
+            .map_err(file::find::Error::from)?;
+        Ok(record
+            .map(file::reftable::to_reference)
+            .transpose()?
+            .flatten()
+            .map(|r| Reference {
+                name: name.to_owned(),
+                ..r
+            }))
+    }
+
     /// Map a lock-acquisition failure to our error type, surfacing genuine I/O errors
     /// (such as a path collision reported as `NotADirectory`) as [`Error::Io`] rather than
     /// burying them in [`Error::LockAcquire`], which is reserved for actual contention.
@@ -65,6 +90,7 @@ impl Transaction<'_, '_> {
         store: &file::Store,
         lock_fail_mode: gix_lock::acquire::Fail,
         packed: Option<&packed::Buffer>,
+        reftable_locks: Option<&[crate::reftable::stack::Lock]>,
         change: &mut Edit,
         direct_to_packed_refs: bool,
     ) -> Result<(), Error> {
@@ -78,19 +104,31 @@ impl Transaction<'_, '_> {
         // The lock file itself (e.g. `CON.lock`) is also a device name,
         // so acquiring it would fail or open the device instead of
         // returning the configured validation error.
-        store.check_windows_device_name(change.update.name.as_ref())?;
+        if reftable_locks.is_none() {
+            store.check_windows_device_name(change.update.name.as_ref())?;
+        }
 
         let lock = match &mut change.update.change {
             Change::Delete { expected, .. } => {
-                let (base, relative_path) = store.reference_path_with_base(change.update.name.as_ref());
-                let lock = gix_lock::Marker::acquire_to_hold_resource(
-                    base.join(relative_path.as_ref()),
-                    lock_fail_mode,
-                    Some(base.clone().into_owned()),
-                )
-                .map_err(|err| Self::lock_acquire_error(err, "borrowcheck won't allow change.name()"))?;
-
-                let existing_ref = Self::read_existing_ref(store, change.update.name.as_ref(), packed)?;
+                let (lock, existing_ref) = match reftable_locks {
+                    Some(locks) => (
+                        None,
+                        Self::read_existing_reftable_ref(store, change.update.name.as_ref(), locks)?,
+                    ),
+                    None => {
+                        let (base, relative_path) = store.reference_path_with_base(change.update.name.as_ref());
+                        let lock = gix_lock::Marker::acquire_to_hold_resource(
+                            base.join(relative_path.as_ref()),
+                            lock_fail_mode,
+                            Some(base.clone().into_owned()),
+                        )
+                        .map_err(|err| Self::lock_acquire_error(err, "borrowcheck won't allow change.name()"))?;
+                        (
+                            Some(lock),
+                            Self::read_existing_ref(store, change.update.name.as_ref(), packed)?,
+                        )
+                    }
+                };
 
                 match (&expected, &existing_ref) {
                     (PreviousValue::MustNotExist, _) => {
@@ -124,7 +162,7 @@ impl Transaction<'_, '_> {
                     *expected = PreviousValue::MustExistAndMatch(existing.target);
                 }
 
-                Some(lock)
+                lock
             }
             Change::Update { expected, new, .. } => {
                 let (base, relative_path) = store.reference_path_with_base(change.update.name.as_ref());
@@ -141,9 +179,16 @@ impl Transaction<'_, '_> {
                         )
                     })
                 };
-                let mut lock = obtain_lock()?;
-
-                let existing_ref = Self::read_existing_ref(store, change.update.name.as_ref(), packed)?;
+                let (lock, existing_ref) = match reftable_locks {
+                    Some(locks) => (
+                        None,
+                        Self::read_existing_reftable_ref(store, change.update.name.as_ref(), locks)?,
+                    ),
+                    None => (
+                        Some(obtain_lock()?),
+                        Self::read_existing_ref(store, change.update.name.as_ref(), packed)?,
+                    ),
+                };
 
                 match (&expected, &existing_ref) {
                     (PreviousValue::Any, _)
@@ -205,16 +250,21 @@ impl Transaction<'_, '_> {
                 };
 
                 let keep_lock_for_loose_source_delete = direct_to_packed_refs && matches!(new, Target::Object(_));
-                if (is_effective && !direct_to_packed_refs) || is_symbolic {
-                    lock.with_mut(|file| match new {
-                        Target::Object(oid) => writeln!(file, "{oid}"),
-                        Target::Symbolic(name) => writeln!(file, "ref: {}", name.0),
-                    })?;
-                    Some(lock.close()?)
-                } else if keep_lock_for_loose_source_delete {
-                    Some(lock.close()?)
-                } else {
-                    None
+                match lock {
+                    // References in reftables are only written when committing.
+                    None => None,
+                    Some(mut lock) if (is_effective && !direct_to_packed_refs) || is_symbolic => {
+                        lock.with_mut(|file| match new {
+                            Target::Object(oid) => writeln!(file, "{oid}"),
+                            Target::Symbolic(name) => writeln!(file, "ref: {}", name.0),
+                        })?;
+                        if let Some(method) = store.fsync {
+                            lock.with_mut(|file| gix_fs::fsync::file(file, method))?;
+                        }
+                        Some(lock.close()?)
+                    }
+                    Some(lock) if keep_lock_for_loose_source_delete => Some(lock.close()?),
+                    Some(_) => None,
                 }
             }
         };
@@ -256,6 +306,7 @@ impl Transaction<'_, '_> {
                 lock: None,
                 parent_index: None,
                 leaf_referent_previous_oid: None,
+                symbolic_target_new_oid: None,
             })
             .collect();
         updates
@@ -272,6 +323,7 @@ impl Transaction<'_, '_> {
                     lock: None,
                     parent_index: Some(idx),
                     leaf_referent_previous_oid: None,
+                    symbolic_target_new_oid: None,
                 },
             )
             .map_err(Error::PreprocessingFailed)?;
@@ -281,7 +333,18 @@ impl Transaction<'_, '_> {
             | PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(_) => Some(0_usize),
             PackedRefs::DeletionsOnly => None,
         };
-        if maybe_updates_for_packed_refs.is_some()
+        if let Some(stacks) = &store.reftable {
+            for edit in &updates {
+                let (dir, _) = store.reftable_location(edit.update.name.as_ref());
+                if self.reftable_locks.iter().all(|lock| lock.stack().dir() != dir) {
+                    let lock = store
+                        .reftable_stack_at(stacks, &dir)?
+                        .lock(ref_files_lock_fail_mode)?
+                        .with_fsync(store.fsync);
+                    self.reftable_locks.push(lock);
+                }
+            }
+        } else if maybe_updates_for_packed_refs.is_some()
             || self.store.packed_refs_path().is_file()
             || self.store.packed_refs_lock_path().is_file()
         {
@@ -359,6 +422,7 @@ impl Transaction<'_, '_> {
                                     packed_refs_lock_fail_mode,
                                     self.store.precompose_unicode,
                                     self.store.namespace.clone(),
+                                    self.store.fsync,
                                 )
                                 .map_err(Error::PackedTransactionAcquire)
                             })
@@ -383,6 +447,7 @@ impl Transaction<'_, '_> {
                 self.store,
                 ref_files_lock_fail_mode,
                 self.packed_transaction.as_ref().and_then(packed::Transaction::buffer),
+                store.reftable.is_some().then_some(self.reftable_locks.as_slice()),
                 change,
                 matches!(
                     self.packed_refs,
@@ -427,6 +492,46 @@ impl Transaction<'_, '_> {
                 }
             }
         }
+
+        // Symbolic references have no object id to put into the reflog, so peel them to obtain one. This allows
+        // symbolic updates to be logged if both the previous and the new target peel to an object, as is the case when
+        // switching branches, and provides the previous object id when detaching a symbolic reference.
+        let packed = self.packed_transaction.as_ref().and_then(packed::Transaction::buffer);
+        for cid in 0..updates.len() {
+            let change = &updates[cid];
+            let Change::Update {
+                log:
+                    LogChange {
+                        mode: RefLog::AndReference,
+                        ..
+                    },
+                new,
+                expected: PreviousValue::MustExistAndMatch(previous),
+            } = &change.update.change
+            else {
+                continue;
+            };
+            match (new, previous) {
+                (Target::Symbolic(referent), previous) => {
+                    let peeled_previous = match previous {
+                        Target::Object(oid) => Some(*oid),
+                        Target::Symbolic(name) if name == referent => continue,
+                        Target::Symbolic(name) => peel_symbolic(store, packed, &updates, name.as_ref(), false),
+                    };
+                    let peeled_new = peel_symbolic(store, packed, &updates, referent.as_ref(), true);
+                    if let (Some(previous), Some(new)) = (peeled_previous, peeled_new) {
+                        let change = &mut updates[cid];
+                        change.leaf_referent_previous_oid = Some(previous);
+                        change.symbolic_target_new_oid = Some(new);
+                    }
+                }
+                (Target::Object(_), Target::Symbolic(name)) if change.leaf_referent_previous_oid.is_none() => {
+                    let peeled_previous = peel_symbolic(store, packed, &updates, name.as_ref(), false);
+                    updates[cid].leaf_referent_previous_oid = peeled_previous;
+                }
+                (Target::Object(_), _) => {}
+            }
+        }
         self.updates = Some(updates);
         Ok(self)
     }
@@ -446,6 +551,46 @@ impl Transaction<'_, '_> {
     }
 }
 
+/// Follow the symbolic reference `name` until it points to an object and return it, or `None` if that's not possible.
+/// If `use_updates` is `true`, values that references will have after this transaction take precedence over the ones on disk.
+fn peel_symbolic(
+    store: &file::Store,
+    packed: Option<&packed::Buffer>,
+    updates: &[Edit],
+    name: &FullNameRef,
+    use_updates: bool,
+) -> Option<gix_hash::ObjectId> {
+    const MAX_HOPS: usize = 5;
+    let mut name = name.to_owned();
+    for _ in 0..MAX_HOPS {
+        let update = use_updates
+            .then(|| {
+                updates.iter().find_map(|edit| match &edit.update.change {
+                    Change::Update {
+                        log:
+                            LogChange {
+                                mode: RefLog::AndReference,
+                                ..
+                            },
+                        new,
+                        ..
+                    } if edit.update.name == name => Some(new.clone()),
+                    _ => None,
+                })
+            })
+            .flatten();
+        let target = match update {
+            Some(target) => target,
+            None => store.find_existing_inner(name.as_ref(), packed).ok()?.target,
+        };
+        match target {
+            Target::Object(oid) => return Some(oid),
+            Target::Symbolic(referent) => name = referent,
+        }
+    }
+    None
+}
+
 fn possibly_adjust_name_for_prefixes(name: &FullNameRef) -> Option<FullName> {
     match name.category_and_short_name() {
         Some((c, sn)) => {
@@ -514,6 +659,12 @@ mod error {
         },
         #[error("Could not read reference")]
         ReferenceDecode(#[from] file::loose::reference::decode::Error),
+        #[error("The reftable stack could not be opened")]
+        ReftableOpen(#[from] crate::reftable::stack::open::Error),
+        #[error("The reftable stack could not be locked")]
+        ReftableLock(#[from] crate::reftable::stack::lock::Error),
+        #[error("Could not read reference from reftable")]
+        ReftableFind(#[from] file::find::Error),
     }
 }
 
@@ -13,10 +13,7 @@ pub use error::Error;
 
 use crate::file;
 
-#[expect(
-    dead_code,
-    reason = "callers still initialize file::Store directly while the general store awaits ref-table support"
-)]
+#[expect(dead_code, reason = "callers use file::Store directly, which also handles reftables")]
 impl crate::Store {
     /// Create a new store at the given location, typically the `.git/` directory.
     /// Use [`opts`](crate::store::init::Options) to adjust settings.
@@ -43,6 +43,8 @@ pub(crate) struct Transaction {
     precompose_unicode: bool,
     /// The namespace to use when preparing or writing refs
     namespace: Option<Namespace>,
+    /// If set, make the written `packed-refs` file durable with it.
+    fsync: Option<gix_fs::fsync::Method>,
 }
 
 /// A reference as parsed from the `packed-refs` file
@@ -15,6 +15,7 @@ impl packed::Transaction {
         lock: gix_lock::File,
         precompose_unicode: bool,
         namespace: Option<Namespace>,
+        fsync: Option<gix_fs::fsync::Method>,
     ) -> Self {
         packed::Transaction {
             buffer,
@@ -23,6 +24,7 @@ impl packed::Transaction {
             closed_lock: None,
             precompose_unicode,
             namespace,
+            fsync,
         }
     }
 }
@@ -218,7 +220,10 @@ impl packed::Transaction {
         if num_written_lines == 0 {
             std::fs::remove_file(file.resource_path())?;
         } else {
-            file.commit()?;
+            match self.fsync {
+                Some(method) => file.commit_durably(method)?,
+                None => file.commit()?,
+            };
         }
         drop(refs_sorted);
         Ok(())
@@ -264,6 +269,7 @@ pub(crate) fn buffer_into_transaction(
     lock_mode: gix_lock::acquire::Fail,
     precompose_unicode: bool,
     namespace: Option<Namespace>,
+    fsync: Option<gix_fs::fsync::Method>,
 ) -> Result<packed::Transaction, gix_lock::acquire::Error> {
     let lock = gix_lock::File::acquire_to_update_resource(&buffer.path, lock_mode, None)?;
     Ok(packed::Transaction {
@@ -273,6 +279,7 @@ pub(crate) fn buffer_into_transaction(
         edits: None,
         precompose_unicode,
         namespace,
+        fsync,
     })
 }
 
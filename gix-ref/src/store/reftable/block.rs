@@ -0,0 +1,470 @@
+//! Reading and writing of the blocks that make up a table, along with the records they contain.
+use std::borrow::Cow;
+
+use gix_hash::ObjectId;
+use gix_object::bstr::{BString, ByteSlice};
+
+use crate::{
+    log::Line,
+    store_impl::reftable::{LogRecord, LogValue, RefRecord, RefValue, table::decode::Error, varint},
+};
+
+pub(crate) const MAGIC: &[u8; 4] = b"REFT";
+pub(crate) const REF: u8 = b'r';
+pub(crate) const LOG: u8 = b'g';
+/// The amount of records after which a full key is written so that it can be used for binary searches.
+pub(crate) const RESTART_INTERVAL: usize = 16;
+/// The identifier of SHA1 in version 2 headers, `sha1`.
+pub(crate) const SHA1_ID: u32 = 0x7368_6131;
+/// The identifier of SHA256 in version 2 headers, `s256`.
+pub(crate) const SHA256_ID: u32 = 0x7332_3536;
+
+/// Return the length of the file header for the given `version`.
+pub(crate) fn header_len(version: u8) -> usize {
+    if version == 1 { 24 } else { 28 }
+}
+
+/// Return the length of the footer for the given `version`, which repeats the header.
+pub(crate) fn footer_len(version: u8) -> usize {
+    header_len(version) + 5 * 8 + 4
+}
+
+/// The version to write tables in for `object_hash`, as version 1 is implied to use SHA1.
+pub(crate) fn version_for(object_hash: gix_hash::Kind) -> u8 {
+    if object_hash as u8 == 1 { 1 } else { 2 }
+}
+
+/// Write the file header for a table with the given parameters into `out`.
+pub(crate) fn write_header(
+    out: &mut Vec<u8>,
+    object_hash: gix_hash::Kind,
+    block_size: u32,
+    min_update_index: u64,
+    max_update_index: u64,
+) {
+    let version = version_for(object_hash);
+    out.extend_from_slice(MAGIC);
+    out.push(version);
+    out.extend_from_slice(&block_size.to_be_bytes()[1..]);
+    out.extend_from_slice(&min_update_index.to_be_bytes());
+    out.extend_from_slice(&max_update_index.to_be_bytes());
+    if version != 1 {
+        let id = if object_hash as u8 == 1 { SHA1_ID } else { SHA256_ID };
+        out.extend_from_slice(&id.to_be_bytes());
+    }
+}
+
+pub(crate) fn be16(data: &[u8]) -> u16 {
+    u16::from_be_bytes([data[0], data[1]])
+}
+
+pub(crate) fn be24(data: &[u8]) -> u32 {
+    u32::from_be_bytes([0, data[0], data[1], data[2]])
+}
+
+pub(crate) fn be32(data: &[u8]) -> u32 {
+    u32::from_be_bytes(data[..4].try_into().expect("enough bytes"))
+}
+
+pub(crate) fn be64(data: &[u8]) -> u64 {
+    u64::from_be_bytes(data[..8].try_into().expect("enough bytes"))
+}
+
+/// A block of records as read from a table, with log blocks being inflated.
+pub(crate) struct Block<'a> {
+    /// The bytes of the block, starting at the block's offset in the table, which means the file header is included
+    /// for the first block.
+    data: Cow<'a, [u8]>,
+    /// The offset of the first record.
+    records_start: usize,
+    /// The offset of the restart table, and thus the end of all records.
+    records_end: usize,
+    restart_count: usize,
+    /// The table offset right after this block, without padding skipped.
+    pub end: usize,
+}
+
+impl<'a> Block<'a> {
+    /// Read the block at `offset` in `table` whose file header has `header_len` bytes, or return `None` if the block
+    /// there isn't of type `kind`. `end` is the offset at which the footer starts.
+    pub(crate) fn at(
+        table: &'a [u8],
+        offset: usize,
+        end: usize,
+        kind: u8,
+        header_len: usize,
+    ) -> Result<Option<Self>, Error> {
+        let header_off = if offset == 0 { header_len } else { 0 };
+        let records_start = header_off + 4;
+        let corrupt = || Error::Corrupt { offset };
+        if offset + records_start > end {
+            return Ok(None);
+        }
+        let header = &table[offset + header_off..offset + records_start];
+        if header[0] != kind {
+            return Ok(None);
+        }
+        let block_len = be24(&header[1..]) as usize;
+        if block_len < records_start + 2 {
+            return Err(corrupt());
+        }
+        let (data, block_end) = if kind == LOG {
+            let mut data = Vec::with_capacity(block_len + 1);
+            data.extend_from_slice(&table[offset..offset + records_start]);
+            data.resize(block_len + 1, 0);
+            let mut inflate = gix_zlib::Inflate::default();
+            let (status, consumed, written) = inflate
+                .once(&table[offset + records_start..end], &mut data[records_start..])
+                .map_err(|source| Error::Inflate { offset, source })?;
+            if status != gix_zlib::Status::StreamEnd || written != block_len - records_start {
+                return Err(corrupt());
+            }
+            data.truncate(block_len);
+            (Cow::Owned(data), offset + records_start + consumed)
+        } else {
+            if offset + block_len > end {
+                return Err(corrupt());
+            }
+            (Cow::Borrowed(&table[offset..offset + block_len]), offset + block_len)
+        };
+        let restart_count = be16(&data[block_len - 2..]) as usize;
+        let records_end = block_len
+            .checked_sub(2 + 3 * restart_count)
+            .filter(|end| *end >= records_start)
+            .ok_or_else(corrupt)?;
+        Ok(Some(Block {
+            data,
+            records_start,
+            records_end,
+            restart_count,
+            end: block_end,
+        }))
+    }
+
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The offset of the first record in the block.
+    pub(crate) fn first_record(&self) -> usize {
+        self.records_start
+    }
+
+    /// Return `true` if `pos` is past the last record.
+    pub(crate) fn is_exhausted(&self, pos: usize) -> bool {
+        pos >= self.records_end
+    }
+
+    /// Decode the key of the record at `pos` into `key`, which has to contain the key of the previous record,
+    /// and return the value type. `pos` is moved to the start of the value.
+    pub(crate) fn read_key(&self, pos: &mut usize, key: &mut Vec<u8>) -> Result<u8, Error> {
+        let corrupt = || Error::Corrupt { offset: *pos };
+        let data = &self.data[..self.records_end];
+        let (prefix_len, consumed) = varint::decode(&data[*pos..]).ok_or_else(corrupt)?;
+        let mut cursor = *pos + consumed;
+        let (suffix_and_type, consumed) = varint::decode(&data[cursor..]).ok_or_else(corrupt)?;
+        cursor += consumed;
+        let suffix_len = (suffix_and_type >> 3) as usize;
+        let prefix_len = prefix_len as usize;
+        if prefix_len > key.len() || cursor + suffix_len > data.len() {
+            return Err(corrupt());
+        }
+        key.truncate(prefix_len);
+        key.extend_from_slice(&data[cursor..][..suffix_len]);
+        *pos = cursor + suffix_len;
+        Ok((suffix_and_type & 0x7) as u8)
+    }
+
+    /// Return the position of the record to start a linear search for `key` at, using a binary search through the restart points.
+    pub(crate) fn seek(&self, key: &[u8]) -> Result<usize, Error> {
+        let mut buf = Vec::new();
+        let (mut lo, mut hi) = (0, self.restart_count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let mut pos = self.restart(mid)?;
+            buf.clear();
+            self.read_key(&mut pos, &mut buf)?;
+            if buf.as_slice() <= key {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        match lo {
+            0 => Ok(self.records_start),
+            idx => self.restart(idx - 1),
+        }
+    }
+
+    fn restart(&self, idx: usize) -> Result<usize, Error> {
+        let offset = be24(&self.data[self.records_end + 3 * idx..]) as usize;
+        if offset < self.records_start || offset >= self.records_end {
+            return Err(Error::Corrupt { offset });
+        }
+        Ok(offset)
+    }
+}
+
+/// Return the offset of the next block at or after `offset`, skipping the padding that may follow a block.
+pub(crate) fn skip_padding(table: &[u8], mut offset: usize, end: usize) -> usize {
+    while offset < end && table[offset] == 0 {
+        offset += 1;
+    }
+    offset
+}
+
+fn read_id(data: &[u8], pos: &mut usize, object_hash: gix_hash::Kind) -> Result<ObjectId, Error> {
+    let len = object_hash.len_in_bytes();
+    let bytes = data.get(*pos..*pos + len).ok_or(Error::Corrupt { offset: *pos })?;
+    *pos += len;
+    Ok(ObjectId::from_bytes_or_panic(bytes))
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64, Error> {
+    let (value, consumed) =
+        varint::decode(data.get(*pos..).unwrap_or_default()).ok_or(Error::Corrupt { offset: *pos })?;
+    *pos += consumed;
+    Ok(value)
+}
+
+fn read_string(data: &[u8], pos: &mut usize) -> Result<BString, Error> {
+    let len = read_varint(data, pos)? as usize;
+    let bytes = data.get(*pos..*pos + len).ok_or(Error::Corrupt { offset: *pos })?;
+    *pos += len;
+    Ok(bytes.into())
+}
+
+/// Decode the value of a ref record of `value_type` named `name` at `pos` in `block`.
+pub(crate) fn read_ref(
+    block: &Block<'_>,
+    pos: &mut usize,
+    name: &[u8],
+    value_type: u8,
+    object_hash: gix_hash::Kind,
+    min_update_index: u64,
+) -> Result<RefRecord, Error> {
+    let data = block.data();
+    let offset = *pos;
+    let update_index = min_update_index
+        .checked_add(read_varint(data, pos)?)
+        .ok_or(Error::Corrupt { offset })?;
+    let value = match value_type {
+        0 => RefValue::Deletion,
+        1 => RefValue::Object(read_id(data, pos, object_hash)?),
+        2 => RefValue::Peeled {
+            target: read_id(data, pos, object_hash)?,
+            peeled: read_id(data, pos, object_hash)?,
+        },
+        3 => RefValue::Symbolic(read_string(data, pos)?),
+        _ => return Err(Error::Corrupt { offset }),
+    };
+    Ok(RefRecord {
+        name: name.into(),
+        update_index,
+        value,
+    })
+}
+
+/// Decode the value of a log record of `value_type` with `key` at `pos` in `block`.
+pub(crate) fn read_log(
+    block: &Block<'_>,
+    pos: &mut usize,
+    key: &[u8],
+    value_type: u8,
+    object_hash: gix_hash::Kind,
+) -> Result<LogRecord, Error> {
+    let data = block.data();
+    let offset = *pos;
+    let (name, update_index) = split_log_key(key).ok_or(Error::Corrupt { offset })?;
+    let value = match value_type {
+        0 => LogValue::Deletion,
+        1 => {
+            let previous_oid = read_id(data, pos, object_hash)?;
+            let new_oid = read_id(data, pos, object_hash)?;
+            let name = read_string(data, pos)?;
+            let email = read_string(data, pos)?;
+            let seconds = read_varint(data, pos)?;
+            let tz = data.get(*pos..*pos + 2).ok_or(Error::Corrupt { offset: *pos })?;
+            *pos += 2;
+            let mut message = read_string(data, pos)?;
+            if message.last() == Some(&b'\n') {
+                message.pop();
+            }
+            LogValue::Update(Line {
+                previous_oid,
+                new_oid,
+                signature: gix_actor::Signature {
+                    name,
+                    email,
+                    time: gix_actor::date::Time {
+                        seconds: seconds
+                            .try_into()
+                            .unwrap_or(gix_actor::date::SecondsSinceUnixEpoch::MAX),
+                        offset: tz_to_seconds(be16(tz) as i16),
+                    },
+                },
+                message,
+            })
+        }
+        _ => return Err(Error::Corrupt { offset }),
+    };
+    Ok(LogRecord {
+        name: name.into(),
+        update_index,
+        value,
+    })
+}
+
+/// Turn the `hhmm` representation of a timezone into seconds.
+fn tz_to_seconds(tz: i16) -> i32 {
+    let abs = i32::from(tz).abs();
+    let seconds = (abs / 100) * 3600 + (abs % 100) * 60;
+    if tz < 0 { -seconds } else { seconds }
+}
+
+/// Turn an offset in seconds into the `hhmm` representation of a timezone.
+fn seconds_to_tz(offset: i32) -> i16 {
+    let abs = offset.abs();
+    let tz = ((abs / 3600) * 100 + (abs % 3600) / 60).min(i32::from(i16::MAX)) as i16;
+    if offset < 0 { -tz } else { tz }
+}
+
+/// Return the key of a log record for the ref `name` written at `update_index`, which sorts newer entries first.
+pub(crate) fn log_key(name: &[u8], update_index: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(name.len() + 9);
+    key.extend_from_slice(name);
+    key.push(0);
+    key.extend_from_slice(&(u64::MAX - update_index).to_be_bytes());
+    key
+}
+
+fn split_log_key(key: &[u8]) -> Option<(&[u8], u64)> {
+    let split = key.len().checked_sub(9)?;
+    let (name, rest) = key.split_at(split);
+    (rest[0] == 0).then(|| (name, u64::MAX - be64(&rest[1..])))
+}
+
+/// Encode the value of `record` into `out` and return its value type.
+pub(crate) fn write_ref(record: &RefRecord, min_update_index: u64, out: &mut Vec<u8>) -> u8 {
+    varint::encode(record.update_index - min_update_index, out);
+    match &record.value {
+        RefValue::Deletion => 0,
+        RefValue::Object(id) => {
+            out.extend_from_slice(id.as_bytes());
+            1
+        }
+        RefValue::Peeled { target, peeled } => {
+            out.extend_from_slice(target.as_bytes());
+            out.extend_from_slice(peeled.as_bytes());
+            2
+        }
+        RefValue::Symbolic(target) => {
+            write_string(target, out);
+            3
+        }
+    }
+}
+
+/// Encode the value of `record` into `out` and return its value type.
+pub(crate) fn write_log(record: &LogRecord, out: &mut Vec<u8>) -> u8 {
+    match &record.value {
+        LogValue::Deletion => 0,
+        LogValue::Update(line) => {
+            out.extend_from_slice(line.previous_oid.as_bytes());
+            out.extend_from_slice(line.new_oid.as_bytes());
+            write_string(&line.signature.name, out);
+            write_string(&line.signature.email, out);
+            varint::encode(line.signature.time.seconds.max(0) as u64, out);
+            out.extend_from_slice(&seconds_to_tz(line.signature.time.offset).to_be_bytes());
+            // Like git, store messages with a trailing newline unless they are empty.
+            let message = line.message.trim_end_with(|c| c == '\n');
+            let newline = usize::from(!message.is_empty());
+            varint::encode((message.len() + newline) as u64, out);
+            out.extend_from_slice(message);
+            out.extend_from_slice(&b"\n"[..newline]);
+            1
+        }
+    }
+}
+
+fn write_string(value: &[u8], out: &mut Vec<u8>) {
+    varint::encode(value.len() as u64, out);
+    out.extend_from_slice(value);
+}
+
+/// Accumulates records into a single block.
+pub(crate) struct Writer {
+    /// The block so far, with `header_len` bytes reserved for the file header.
+    buf: Vec<u8>,
+    header_len: usize,
+    block_size: usize,
+    restarts: Vec<usize>,
+    last_key: Vec<u8>,
+    entries: usize,
+}
+
+impl Writer {
+    pub(crate) fn new(kind: u8, header_len: usize, block_size: usize) -> Self {
+        let mut buf = vec![0; header_len];
+        buf.extend_from_slice(&[kind, 0, 0, 0]);
+        Writer {
+            buf,
+            header_len,
+            block_size,
+            restarts: Vec::new(),
+            last_key: Vec::new(),
+            entries: 0,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries == 0
+    }
+
+    /// The offset at which records start, past the block header.
+    pub(crate) fn records_start(&self) -> usize {
+        self.header_len + 4
+    }
+
+    /// Add a record with `key` and a `value` of `value_type`, or return `false` if it doesn't fit into the block anymore.
+    pub(crate) fn add(&mut self, key: &[u8], value_type: u8, value: &[u8]) -> bool {
+        let is_restart = self.entries % RESTART_INTERVAL == 0;
+        let prefix_len = if is_restart {
+            0
+        } else {
+            self.last_key.iter().zip(key).take_while(|(a, b)| a == b).count()
+        };
+        let mut record = Vec::with_capacity(key.len() - prefix_len + value.len() + 8);
+        varint::encode(prefix_len as u64, &mut record);
+        varint::encode(
+            (((key.len() - prefix_len) as u64) << 3) | u64::from(value_type),
+            &mut record,
+        );
+        record.extend_from_slice(&key[prefix_len..]);
+        record.extend_from_slice(value);
+
+        let num_restarts = self.restarts.len() + usize::from(is_restart);
+        if self.buf.len() + record.len() + 3 * num_restarts + 2 > self.block_size {
+            return false;
+        }
+        if is_restart {
+            self.restarts.push(self.buf.len());
+        }
+        self.buf.extend_from_slice(&record);
+        self.last_key.clear();
+        self.last_key.extend_from_slice(key);
+        self.entries += 1;
+        true
+    }
+
+    /// Finish the block and return its bytes, with the space for the file header at the beginning being zeroed.
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        for restart in &self.restarts {
+            self.buf.extend_from_slice(&(*restart as u32).to_be_bytes()[1..]);
+        }
+        self.buf.extend_from_slice(&(self.restarts.len() as u16).to_be_bytes());
+        let len = (self.buf.len() as u32).to_be_bytes();
+        self.buf[self.header_len + 1..self.header_len + 4].copy_from_slice(&len[1..]);
+        self.buf
+    }
+}
@@ -0,0 +1,92 @@
+//! Read and write [reftables](https://git-scm.com/docs/reftable), a binary format storing references and their logs
+//! in a stack of immutable tables, where newer tables shadow the records of older ones.
+//!
+//! Repositories using this format are created with `extensions.refStorage=reftable` and keep their tables in `.git/reftable`.
+use std::path::PathBuf;
+
+use gix_features::threading::OwnShared;
+use gix_hash::ObjectId;
+use gix_object::bstr::BString;
+
+/// A reference as stored in a [`Table`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct RefRecord {
+    /// The full name of the reference.
+    pub name: BString,
+    /// The update index of the change that wrote this record.
+    pub update_index: u64,
+    /// The value of the reference.
+    pub value: RefValue,
+}
+
+/// The value of a [`RefRecord`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub enum RefValue {
+    /// The reference was deleted, which hides records of the same name in older tables.
+    Deletion,
+    /// The reference points to an object.
+    Object(ObjectId),
+    /// The reference points to an annotated tag `target` which ultimately points to `peeled`.
+    Peeled {
+        /// The object the reference points to.
+        target: ObjectId,
+        /// The object `target` peels to.
+        peeled: ObjectId,
+    },
+    /// The reference is symbolic and points to the reference with the given full name.
+    Symbolic(BString),
+}
+
+/// An entry in the log of a reference as stored in a [`Table`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct LogRecord {
+    /// The full name of the reference whose log this is.
+    pub name: BString,
+    /// The update index of the change that wrote this record, which orders the log.
+    pub update_index: u64,
+    /// The value of the log entry.
+    pub value: LogValue,
+}
+
+/// The value of a [`LogRecord`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub enum LogValue {
+    /// The log entry was deleted, which hides the entry with the same update index in older tables.
+    Deletion,
+    /// A change of the reference.
+    Update(crate::log::Line),
+}
+
+/// A single reftable with ref and log records, typically read from a file in a [`Stack`].
+pub struct Table {
+    data: Vec<u8>,
+    object_hash: gix_hash::Kind,
+    block_size: u32,
+    min_update_index: u64,
+    max_update_index: u64,
+    header_len: usize,
+    footer_offset: usize,
+    /// The offsets of all ref blocks along with the name of their first record.
+    ref_blocks: Vec<(usize, BString)>,
+    /// The offset of the first log block, if there are logs.
+    log_offset: Option<usize>,
+}
+
+/// The tables listed in a `tables.list` file, which together form the state of all references and their logs.
+#[derive(Debug, Clone)]
+pub struct Stack {
+    dir: PathBuf,
+    object_hash: gix_hash::Kind,
+    /// The file names of all tables and the tables themselves, oldest first.
+    tables: Vec<(String, OwnShared<Table>)>,
+}
+
+mod block;
+mod varint;
+
+///
+pub mod stack;
+///
+pub mod table;
+///
+pub mod write;
@@ -0,0 +1,412 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use gix_features::threading::OwnShared;
+use gix_object::bstr::{BStr, BString};
+
+use crate::store_impl::reftable::{LogRecord, LogValue, RefRecord, RefValue, Stack, Table, table, write};
+
+/// The name of the file listing all tables of a stack, oldest first.
+pub const TABLES_LIST: &str = "tables.list";
+
+///
+pub mod open {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Stack::at()`](crate::reftable::Stack::at()) and [`Stack::reload()`](crate::reftable::Stack::reload()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the list of tables at '{}'", path.display())]
+        ReadTablesList { source: std::io::Error, path: PathBuf },
+        #[error(transparent)]
+        Table(#[from] crate::reftable::table::open::Error),
+    }
+}
+
+///
+pub mod lock {
+    /// The error returned by [`Stack::lock()`](crate::reftable::Stack::lock()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Could not lock the list of tables")]
+        Acquire(#[from] gix_lock::acquire::Error),
+        #[error("Could not reload the stack after locking it")]
+        Open(#[from] super::open::Error),
+    }
+}
+
+///
+pub mod commit {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Lock::commit()`](super::Lock::commit()) and [`Lock::compact()`](super::Lock::compact()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("The ref records of the new table must have an update index of at least {min}")]
+        StaleUpdateIndex { min: u64 },
+        #[error("Could not read the records of the tables to compact")]
+        Decode(#[from] crate::reftable::table::decode::Error),
+        #[error("Could not encode the new table")]
+        Write(#[from] crate::reftable::write::Error),
+        #[error("Could not write '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("Could not obtain a lock for a new table")]
+        Acquire(#[from] gix_lock::acquire::Error),
+        #[error("Could not read the table that was just written")]
+        Open(#[from] crate::reftable::table::open::Error),
+    }
+}
+
+/// The amount of times we retry loading a stack if one of its tables vanished due to a concurrent compaction.
+const MAX_LOAD_ATTEMPTS: usize = 5;
+
+/// Initialization
+impl Stack {
+    /// Open the stack in `dir`, typically `.git/reftable`, whose tables are expected to store object ids of kind `object_hash`.
+    ///
+    /// A missing `tables.list` file is treated like an empty stack.
+    pub fn at(dir: impl Into<PathBuf>, object_hash: gix_hash::Kind) -> Result<Self, open::Error> {
+        let mut stack = Stack {
+            dir: dir.into(),
+            object_hash,
+            tables: Vec::new(),
+        };
+        if let Some(reloaded) = stack.reload()? {
+            stack = reloaded;
+        }
+        Ok(stack)
+    }
+
+    /// Return the most recent version of this stack if the list of tables changed on disk, or `None` if it is still current.
+    ///
+    /// Tables that are still part of the stack are reused.
+    pub fn reload(&self) -> Result<Option<Self>, open::Error> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let names = read_tables_list(&self.dir)?;
+            if names.len() == self.tables.len() && names.iter().zip(&self.tables).all(|(a, (b, _))| a == b) {
+                return Ok(None);
+            }
+            let mut tables = Vec::with_capacity(names.len());
+            let mut vanished = false;
+            for name in names {
+                let table = match self.tables.iter().find(|(existing, _)| *existing == name) {
+                    Some((_, table)) => table.clone(),
+                    None => match Table::at(&self.dir.join(&name), self.object_hash) {
+                        Ok(table) => OwnShared::new(table),
+                        Err(table::open::Error::Io { source, .. })
+                            if source.kind() == std::io::ErrorKind::NotFound && attempt < MAX_LOAD_ATTEMPTS =>
+                        {
+                            vanished = true;
+                            break;
+                        }
+                        Err(err) => return Err(err.into()),
+                    },
+                };
+                tables.push((name, table));
+            }
+            if vanished {
+                continue;
+            }
+            return Ok(Some(Stack {
+                dir: self.dir.clone(),
+                object_hash: self.object_hash,
+                tables,
+            }));
+        }
+    }
+}
+
+fn read_tables_list(dir: &Path) -> Result<Vec<String>, open::Error> {
+    let path = dir.join(TABLES_LIST);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => Ok(content
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(ToOwned::to_owned)
+            .collect()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(source) => Err(open::Error::ReadTablesList { source, path }),
+    }
+}
+
+/// Access
+impl Stack {
+    /// The directory containing the tables and the `tables.list` file.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The kind of object ids stored in the tables.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// Return all tables, oldest first, along with their file names.
+    pub fn tables(&self) -> impl ExactSizeIterator<Item = (&str, &Table)> {
+        self.tables.iter().map(|(name, table)| (name.as_str(), &**table))
+    }
+
+    /// The highest update index of all tables, or `0` if there are none.
+    pub fn max_update_index(&self) -> u64 {
+        self.tables.last().map_or(0, |(_, table)| table.max_update_index())
+    }
+
+    /// Find the reference `name` in the most recent table that knows it, or return `None` if it doesn't exist or was deleted.
+    pub fn find_ref(&self, name: &BStr) -> Result<Option<RefRecord>, table::decode::Error> {
+        for (_, table) in self.tables.iter().rev() {
+            if let Some(record) = table.find_ref(name)? {
+                return Ok((record.value != RefValue::Deletion).then_some(record));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return all references as seen when merging all tables, sorted by name and without deletions.
+    pub fn refs(&self) -> Result<Vec<RefRecord>, table::decode::Error> {
+        self.merged_refs(self.tables.iter().map(|(_, t)| &**t), false)
+    }
+
+    /// Return the log of reference `name` as seen when merging all tables, with the most recent entry first.
+    pub fn logs(&self, name: &BStr) -> Result<Vec<LogRecord>, table::decode::Error> {
+        let mut merged = BTreeMap::new();
+        for (_, table) in &self.tables {
+            for record in table.logs_of(name) {
+                let record = record?;
+                merged.insert(Reverse(record.update_index), record);
+            }
+        }
+        Ok(merged
+            .into_values()
+            .filter(|record| record.value != LogValue::Deletion)
+            .collect())
+    }
+
+    fn merged_refs<'a>(
+        &self,
+        tables: impl Iterator<Item = &'a Table>,
+        keep_deletions: bool,
+    ) -> Result<Vec<RefRecord>, table::decode::Error> {
+        let mut merged = BTreeMap::<BString, RefRecord>::new();
+        for table in tables {
+            for record in table.refs() {
+                let record = record?;
+                merged.insert(record.name.clone(), record);
+            }
+        }
+        Ok(merged
+            .into_values()
+            .filter(|record| keep_deletions || record.value != RefValue::Deletion)
+            .collect())
+    }
+
+    fn merged_logs<'a>(
+        &self,
+        tables: impl Iterator<Item = &'a Table>,
+        keep_deletions: bool,
+    ) -> Result<Vec<LogRecord>, table::decode::Error> {
+        let mut merged = BTreeMap::<(BString, Reverse<u64>), LogRecord>::new();
+        for table in tables {
+            for record in table.logs() {
+                let record = record?;
+                merged.insert((record.name.clone(), Reverse(record.update_index)), record);
+            }
+        }
+        Ok(merged
+            .into_values()
+            .filter(|record| keep_deletions || record.value != LogValue::Deletion)
+            .collect())
+    }
+}
+
+/// A lock on the list of tables of a [`Stack`], which is required to add tables to it or compact it.
+///
+/// Dropping it releases the lock without changing the stack.
+pub struct Lock {
+    stack: Stack,
+    lock: gix_lock::File,
+    fsync: Option<gix_fs::fsync::Method>,
+}
+
+/// Mutation
+impl Stack {
+    /// Lock the list of tables, failing according to `fail_mode` if it is locked already, and return the lock
+    /// which contains the most recent version of this stack.
+    pub fn lock(&self, fail_mode: gix_lock::acquire::Fail) -> Result<Lock, lock::Error> {
+        std::fs::create_dir_all(&self.dir).map_err(gix_lock::acquire::Error::Io)?;
+        let lock = gix_lock::File::acquire_to_update_resource(self.dir.join(TABLES_LIST), fail_mode, None)?;
+        let stack = match self.reload()? {
+            Some(stack) => stack,
+            None => self.clone(),
+        };
+        Ok(Lock {
+            stack,
+            lock,
+            fsync: None,
+        })
+    }
+}
+
+impl Lock {
+    /// If `Some(method)`, make new tables and the list of tables durable with it when they are written.
+    pub fn with_fsync(mut self, method: Option<gix_fs::fsync::Method>) -> Self {
+        self.fsync = method;
+        self
+    }
+
+    /// The stack as it was when the lock was acquired.
+    pub fn stack(&self) -> &Stack {
+        &self.stack
+    }
+
+    /// The update index that records of the next table should use.
+    pub fn next_update_index(&self) -> u64 {
+        self.stack.max_update_index() + 1
+    }
+
+    /// Write `refs` and `logs` into a new table, add it to the stack and release the lock.
+    /// Records can be in any order, but the update index of `refs` must be at least [`next_update_index()`](Self::next_update_index()),
+    /// while `logs` may also use older update indices to delete existing log entries.
+    ///
+    /// Tables are compacted automatically to keep their sizes geometrically decreasing, which keeps the amount of tables
+    /// logarithmic to the amount of updates.
+    /// Return the updated stack.
+    pub fn commit(self, mut refs: Vec<RefRecord>, mut logs: Vec<LogRecord>) -> Result<Stack, commit::Error> {
+        let min = self.next_update_index();
+        let max = refs
+            .iter()
+            .map(|r| r.update_index)
+            .chain(logs.iter().map(|l| l.update_index))
+            .max()
+            .unwrap_or(min);
+        if refs.iter().any(|r| r.update_index < min) {
+            return Err(commit::Error::StaleUpdateIndex { min });
+        }
+        refs.sort_by(|a, b| a.name.cmp(&b.name));
+        logs.sort_by(|a, b| a.name.cmp(&b.name).then(b.update_index.cmp(&a.update_index)));
+
+        let mut tables = self.stack.tables.clone();
+        tables.push(self.write_table(&refs, &logs, min, max)?);
+
+        let sizes: Vec<_> = tables.iter().map(|(_, table)| table.size()).collect();
+        let mut start = sizes.len() - 1;
+        let mut newer_size = sizes[start];
+        while start > 0 && sizes[start - 1] < 2 * newer_size {
+            start -= 1;
+            newer_size += sizes[start];
+        }
+        let mut obsolete = Vec::new();
+        if tables.len() - start > 1 {
+            let compacted = self.compact_tables(&tables[start..], start == 0)?;
+            obsolete.extend(tables.drain(start..).map(|(name, _)| name));
+            tables.push(compacted);
+        }
+        self.finish(tables, obsolete)
+    }
+
+    /// Merge all tables into one, dropping deleted references and log entries, and release the lock.
+    /// Return the updated stack.
+    pub fn compact(self) -> Result<Stack, commit::Error> {
+        if self.stack.tables.len() < 2 {
+            return Ok(self.stack);
+        }
+        let compacted = self.compact_tables(&self.stack.tables, true)?;
+        let obsolete = self.stack.tables.iter().map(|(name, _)| name.clone()).collect();
+        self.finish(vec![compacted], obsolete)
+    }
+
+    /// Merge `tables` into a new one, dropping deletions if `is_base` is `true` as there is nothing left they could shadow.
+    fn compact_tables(
+        &self,
+        tables: &[(String, OwnShared<Table>)],
+        is_base: bool,
+    ) -> Result<(String, OwnShared<Table>), commit::Error> {
+        let refs = self.stack.merged_refs(tables.iter().map(|(_, t)| &**t), !is_base)?;
+        let logs = self.stack.merged_logs(tables.iter().map(|(_, t)| &**t), !is_base)?;
+        let min = tables.first().map_or(0, |(_, t)| t.min_update_index());
+        let max = tables.last().map_or(0, |(_, t)| t.max_update_index());
+        self.write_table(&refs, &logs, min, max)
+    }
+
+    fn write_table(
+        &self,
+        refs: &[RefRecord],
+        logs: &[LogRecord],
+        min: u64,
+        max: u64,
+    ) -> Result<(String, OwnShared<Table>), commit::Error> {
+        let data = write::table(
+            refs,
+            logs,
+            write::Options {
+                object_hash: self.stack.object_hash,
+                block_size: write::DEFAULT_BLOCK_SIZE,
+                min_update_index: min,
+                max_update_index: max,
+            },
+        )?;
+        let name = format!("0x{min:012x}-0x{max:012x}-{:08x}.ref", random_suffix());
+        let path = self.stack.dir.join(&name);
+        let mut file = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let io_err = |source| commit::Error::Io {
+            source,
+            path: path.clone(),
+        };
+        file.write_all(&data).map_err(io_err)?;
+        commit_lock(file, self.fsync).map_err(io_err)?;
+        let table = Table::from_bytes(data, self.stack.object_hash).map_err(|source| table::open::Error::Decode {
+            source,
+            path: path.clone(),
+        })?;
+        Ok((name, OwnShared::new(table)))
+    }
+
+    /// Write `tables` into the list of tables, release the lock and remove the `obsolete` tables.
+    fn finish(self, tables: Vec<(String, OwnShared<Table>)>, obsolete: Vec<String>) -> Result<Stack, commit::Error> {
+        let Lock { stack, mut lock, fsync } = self;
+        let path = stack.dir.join(TABLES_LIST);
+        let io_err = |source| commit::Error::Io {
+            source,
+            path: path.clone(),
+        };
+        for (name, _) in &tables {
+            writeln!(lock, "{name}").map_err(io_err)?;
+        }
+        commit_lock(lock, fsync).map_err(io_err)?;
+        for name in obsolete {
+            // Readers that still have the table open may prevent its deletion on some platforms,
+            // which isn't a problem as it is not listed anymore.
+            std::fs::remove_file(stack.dir.join(name)).ok();
+        }
+        Ok(Stack { tables, ..stack })
+    }
+}
+
+fn commit_lock(lock: gix_lock::File, fsync: Option<gix_fs::fsync::Method>) -> std::io::Result<()> {
+    match fsync {
+        Some(method) => lock.commit_durably(method),
+        None => lock.commit(),
+    }
+    .map(|_| ())
+    .map_err(|err| err.error)
+}
+
+/// Return a value that makes table names unique even if they contain the same update indices.
+fn random_suffix() -> u32 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos()),
+    );
+    hasher.finish() as u32
+}
@@ -0,0 +1,365 @@
+use std::{cmp::Ordering, path::Path};
+
+use gix_object::bstr::{BStr, BString};
+
+use crate::store_impl::reftable::{
+    LogRecord, RefRecord, Table,
+    block::{self, Block},
+};
+
+///
+pub mod decode {
+    /// The error returned when decoding a [`Table`](crate::reftable::Table) or its records.
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("The data doesn't start with a valid reftable header")]
+        Header,
+        #[error("Reftable version {version} is not supported")]
+        UnsupportedVersion { version: u8 },
+        #[error("The hash function with format-id {id:#x} is unknown or unsupported")]
+        UnsupportedHash { id: u32 },
+        #[error("The reftable stores {actual:?} object ids, but {expected:?} was expected")]
+        ObjectHashMismatch {
+            expected: gix_hash::Kind,
+            actual: gix_hash::Kind,
+        },
+        #[error("The reftable footer is missing or doesn't match its header")]
+        Footer,
+        #[error("The checksum of the reftable footer didn't match")]
+        Checksum,
+        #[error("The reftable is corrupt at offset {offset}")]
+        Corrupt { offset: usize },
+        #[error("The log block at offset {offset} could not be inflated")]
+        Inflate {
+            offset: usize,
+            source: gix_zlib::inflate::Error,
+        },
+    }
+}
+use decode::Error;
+
+///
+pub mod open {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Table::at()`](crate::reftable::Table::at()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("The reftable at '{}' could not be read", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("The reftable at '{}' could not be decoded", path.display())]
+        Decode {
+            source: super::decode::Error,
+            path: PathBuf,
+        },
+    }
+}
+
+/// Initialization
+impl Table {
+    /// Read the table at `path`, which is expected to store object ids of kind `object_hash`.
+    pub fn at(path: &Path, object_hash: gix_hash::Kind) -> Result<Self, open::Error> {
+        let data = std::fs::read(path).map_err(|source| open::Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        Table::from_bytes(data, object_hash).map_err(|source| open::Error::Decode {
+            source,
+            path: path.to_owned(),
+        })
+    }
+
+    /// Decode a table from `data`, which is expected to store object ids of kind `object_hash`.
+    ///
+    /// Only the header, the footer and the beginning of each ref block are read right away.
+    pub fn from_bytes(data: Vec<u8>, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        if data.len() < block::header_len(1) || &data[..4] != block::MAGIC {
+            return Err(Error::Header);
+        }
+        let version = data[4];
+        let actual_hash = match version {
+            1 => gix_hash::Kind::try_from(1).map_err(|_| Error::UnsupportedHash { id: block::SHA1_ID })?,
+            2 => {
+                let id = block::be32(data.get(24..28).ok_or(Error::Header)?);
+                match id {
+                    block::SHA1_ID => gix_hash::Kind::try_from(1),
+                    block::SHA256_ID => gix_hash::Kind::try_from(2),
+                    _ => Err(0),
+                }
+                .map_err(|_| Error::UnsupportedHash { id })?
+            }
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        if actual_hash != object_hash {
+            return Err(Error::ObjectHashMismatch {
+                expected: object_hash,
+                actual: actual_hash,
+            });
+        }
+        let header_len = block::header_len(version);
+        let footer_len = block::footer_len(version);
+        let footer_offset = data
+            .len()
+            .checked_sub(footer_len)
+            .filter(|offset| *offset >= header_len)
+            .ok_or(Error::Footer)?;
+        let footer = &data[footer_offset..];
+        if footer[..header_len] != data[..header_len] {
+            return Err(Error::Footer);
+        }
+        let (footer, crc) = footer.split_at(footer_len - 4);
+        if gix_features::hash::crc32(footer) != block::be32(crc) {
+            return Err(Error::Checksum);
+        }
+        let log_offset = block::be64(&footer[header_len + 3 * 8..]) as usize;
+
+        let mut table = Table {
+            block_size: block::be24(&data[5..8]),
+            min_update_index: block::be64(&data[8..]),
+            max_update_index: block::be64(&data[16..]),
+            object_hash,
+            header_len,
+            footer_offset,
+            ref_blocks: Vec::new(),
+            log_offset: None,
+            data,
+        };
+        let mut offset = 0;
+        let mut key = Vec::new();
+        while let Some(block) = Block::at(&table.data, offset, footer_offset, block::REF, header_len)? {
+            let mut pos = block.first_record();
+            if !block.is_exhausted(pos) {
+                key.clear();
+                block.read_key(&mut pos, &mut key)?;
+                table.ref_blocks.push((offset, key.as_slice().into()));
+            }
+            offset = block::skip_padding(&table.data, block.end, footer_offset);
+        }
+        table.log_offset = if log_offset != 0 {
+            (log_offset < footer_offset).then_some(log_offset)
+        } else {
+            // Tables without refs start with the log section.
+            (table.data[header_len] == block::LOG).then_some(0)
+        };
+        Ok(table)
+    }
+}
+
+/// Access
+impl Table {
+    /// The kind of hash used for all object ids in this table.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// The smallest update index of all records in this table.
+    pub fn min_update_index(&self) -> u64 {
+        self.min_update_index
+    }
+
+    /// The largest update index of all records in this table.
+    pub fn max_update_index(&self) -> u64 {
+        self.max_update_index
+    }
+
+    /// The size of the blocks in which ref records are stored.
+    pub fn block_size(&self) -> u32 {
+        self.block_size
+    }
+
+    /// The size of the table in bytes.
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Find the ref record for `name`, which may be a [deletion](crate::reftable::RefValue::Deletion).
+    pub fn find_ref(&self, name: &BStr) -> Result<Option<RefRecord>, Error> {
+        let name: &[u8] = name.as_ref();
+        let block_idx = match self.ref_blocks.partition_point(|(_, first)| first.as_slice() <= name) {
+            0 => return Ok(None),
+            idx => idx - 1,
+        };
+        let block = self.ref_block(self.ref_blocks[block_idx].0)?;
+        let mut pos = block.seek(name)?;
+        let mut key = Vec::new();
+        while !block.is_exhausted(pos) {
+            let value_type = block.read_key(&mut pos, &mut key)?;
+            let record = block::read_ref(
+                &block,
+                &mut pos,
+                &key,
+                value_type,
+                self.object_hash,
+                self.min_update_index,
+            )?;
+            match key.as_slice().cmp(name) {
+                std::cmp::Ordering::Less => continue,
+                std::cmp::Ordering::Equal => return Ok(Some(record)),
+                std::cmp::Ordering::Greater => break,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return an iterator over all ref records in this table, sorted by name.
+    pub fn refs(&self) -> Refs<'_> {
+        Refs {
+            table: self,
+            block_idx: 0,
+            block: None,
+            pos: 0,
+            key: Vec::new(),
+        }
+    }
+
+    /// Return an iterator over all log records in this table, sorted by name and with the most recent entry first.
+    pub fn logs(&self) -> Logs<'_> {
+        Logs {
+            table: self,
+            offset: self.log_offset,
+            block: None,
+            pos: 0,
+            key: Vec::new(),
+            name: None,
+        }
+    }
+
+    /// Return an iterator over the log records of `name` in this table, with the most recent entry first.
+    ///
+    /// Each log block is searched for the first record of `name`, so records of other references are mostly skipped
+    /// without decoding them.
+    pub fn logs_of(&self, name: &BStr) -> Logs<'_> {
+        Logs {
+            name: Some(name.to_owned()),
+            ..self.logs()
+        }
+    }
+
+    fn ref_block(&self, offset: usize) -> Result<Block<'_>, Error> {
+        Block::at(&self.data, offset, self.footer_offset, block::REF, self.header_len)?.ok_or(Error::Corrupt { offset })
+    }
+}
+
+/// An iterator over all ref records of a [`Table`].
+pub struct Refs<'a> {
+    table: &'a Table,
+    block_idx: usize,
+    block: Option<Block<'a>>,
+    pos: usize,
+    key: Vec<u8>,
+}
+
+impl Iterator for Refs<'_> {
+    type Item = Result<RefRecord, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(block) = self.block.as_ref().filter(|block| !block.is_exhausted(self.pos)) {
+                let table = self.table;
+                let res = block.read_key(&mut self.pos, &mut self.key).and_then(|value_type| {
+                    block::read_ref(
+                        block,
+                        &mut self.pos,
+                        &self.key,
+                        value_type,
+                        table.object_hash,
+                        table.min_update_index,
+                    )
+                });
+                if res.is_err() {
+                    self.block = None;
+                    self.block_idx = table.ref_blocks.len();
+                }
+                return Some(res);
+            }
+            let (offset, _) = self.table.ref_blocks.get(self.block_idx)?;
+            self.block_idx += 1;
+            match self.table.ref_block(*offset) {
+                Ok(block) => {
+                    self.pos = block.first_record();
+                    self.key.clear();
+                    self.block = Some(block);
+                }
+                Err(err) => {
+                    self.block_idx = self.table.ref_blocks.len();
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+/// An iterator over all log records of a [`Table`], or only over those of a single reference.
+pub struct Logs<'a> {
+    table: &'a Table,
+    offset: Option<usize>,
+    block: Option<Block<'a>>,
+    pos: usize,
+    key: Vec<u8>,
+    /// If set, only log records of this reference are returned.
+    name: Option<BString>,
+}
+
+impl Iterator for Logs<'_> {
+    type Item = Result<LogRecord, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(block) = self.block.as_ref().filter(|block| !block.is_exhausted(self.pos)) {
+                let res = block.read_key(&mut self.pos, &mut self.key).and_then(|value_type| {
+                    block::read_log(block, &mut self.pos, &self.key, value_type, self.table.object_hash)
+                });
+                match (&res, &self.name) {
+                    (Err(_), _) => {
+                        self.block = None;
+                        self.offset = None;
+                    }
+                    (Ok(record), Some(name)) => match record.name.cmp(name) {
+                        Ordering::Less => continue,
+                        Ordering::Equal => {}
+                        Ordering::Greater => {
+                            self.block = None;
+                            self.offset = None;
+                            return None;
+                        }
+                    },
+                    (Ok(_), None) => {}
+                }
+                return Some(res);
+            }
+            let table = self.table;
+            let offset = self.offset.take()?;
+            let block = match Block::at(&table.data, offset, table.footer_offset, block::LOG, table.header_len) {
+                Ok(Some(block)) => block,
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
+            };
+            self.offset = Some(block::skip_padding(&table.data, block.end, table.footer_offset));
+            self.pos = match &self.name {
+                // The key of the most recent entry is the smallest one of all entries of `name`.
+                Some(name) => match block.seek(&block::log_key(name, u64::MAX)) {
+                    Ok(pos) => pos,
+                    Err(err) => {
+                        self.offset = None;
+                        return Some(Err(err));
+                    }
+                },
+                None => block.first_record(),
+            };
+            self.key.clear();
+            self.block = Some(block);
+        }
+    }
+}
+
+impl std::fmt::Debug for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Table")
+            .field("min_update_index", &self.min_update_index)
+            .field("max_update_index", &self.max_update_index)
+            .field("size", &self.data.len())
+            .finish_non_exhaustive()
+    }
+}
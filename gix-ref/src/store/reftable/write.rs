@@ -0,0 +1,196 @@
+use std::io::Write;
+
+use crate::store_impl::reftable::{LogRecord, RefRecord, block};
+
+/// The block size git uses by default.
+pub const DEFAULT_BLOCK_SIZE: u32 = 4096;
+
+/// Options for use in [`table()`].
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    /// The kind of object ids to write.
+    pub object_hash: gix_hash::Kind,
+    /// The size of ref blocks, which are padded to this size. Log records can't be larger than this either.
+    pub block_size: u32,
+    /// The smallest update index that records in the table may have.
+    pub min_update_index: u64,
+    /// The largest update index that records in the table may have.
+    pub max_update_index: u64,
+}
+
+/// The error returned by [`table()`].
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("Records must be sorted and unique, but {name:?} didn't come after its predecessor")]
+    Unsorted { name: gix_object::bstr::BString },
+    #[error(
+        "The record for {name:?} has update index {update_index}, which is outside of the table's range of {min}..={max}"
+    )]
+    UpdateIndexOutOfRange {
+        name: gix_object::bstr::BString,
+        update_index: u64,
+        min: u64,
+        max: u64,
+    },
+    #[error("The record for {name:?} is too large to fit into a block of {block_size} bytes")]
+    RecordTooLarge {
+        name: gix_object::bstr::BString,
+        block_size: u32,
+    },
+    #[error("Block sizes must be between 256 bytes and 16MB, got {block_size}")]
+    InvalidBlockSize { block_size: u32 },
+    #[error("Could not compress a log block")]
+    Deflate(#[from] std::io::Error),
+}
+
+/// Encode `refs` and `logs` into a complete table according to `options` and return its bytes.
+///
+/// `refs` must be sorted by name, and `logs` by name and then by update index, most recent first.
+/// Only the update indices of `refs` have to be within the range of `options`, as log records may also delete
+/// older entries by their update index.
+/// No object index is written, which is optional as it only speeds up lookups of refs by the object they point to.
+pub fn table(refs: &[RefRecord], logs: &[LogRecord], options: Options) -> Result<Vec<u8>, Error> {
+    let Options {
+        object_hash,
+        block_size,
+        min_update_index,
+        max_update_index,
+    } = options;
+    if !(256..1 << 24).contains(&block_size) {
+        return Err(Error::InvalidBlockSize { block_size });
+    }
+    let check_update_index = |name: &gix_object::bstr::BString, update_index: u64| {
+        if (min_update_index..=max_update_index).contains(&update_index) {
+            Ok(())
+        } else {
+            Err(Error::UpdateIndexOutOfRange {
+                name: name.clone(),
+                update_index,
+                min: min_update_index,
+                max: max_update_index,
+            })
+        }
+    };
+    let version = block::version_for(object_hash);
+    let header_len = block::header_len(version);
+    let mut header = Vec::with_capacity(header_len);
+    block::write_header(&mut header, object_hash, block_size, min_update_index, max_update_index);
+
+    let mut out = Vec::new();
+    let mut value = Vec::new();
+    let new_block = |out: &Vec<u8>, kind: u8| {
+        block::Writer::new(kind, if out.is_empty() { header_len } else { 0 }, block_size as usize)
+    };
+    let finish_ref_block = |out: &mut Vec<u8>, writer: block::Writer| {
+        let mut data = writer.finish();
+        if out.is_empty() {
+            data[..header_len].copy_from_slice(&header);
+        }
+        data.resize(block_size as usize, 0);
+        out.extend_from_slice(&data);
+    };
+
+    let mut writer = new_block(&out, block::REF);
+    for (idx, record) in refs.iter().enumerate() {
+        if idx > 0 && refs[idx - 1].name >= record.name {
+            return Err(Error::Unsorted {
+                name: record.name.clone(),
+            });
+        }
+        check_update_index(&record.name, record.update_index)?;
+        value.clear();
+        let value_type = block::write_ref(record, min_update_index, &mut value);
+        if !writer.add(&record.name, value_type, &value) {
+            if writer.is_empty() {
+                return Err(Error::RecordTooLarge {
+                    name: record.name.clone(),
+                    block_size,
+                });
+            }
+            let full = std::mem::replace(&mut writer, block::Writer::new(block::REF, 0, block_size as usize));
+            finish_ref_block(&mut out, full);
+            if !writer.add(&record.name, value_type, &value) {
+                return Err(Error::RecordTooLarge {
+                    name: record.name.clone(),
+                    block_size,
+                });
+            }
+        }
+    }
+    if !writer.is_empty() {
+        finish_ref_block(&mut out, writer);
+    }
+
+    let log_offset = out.len();
+    let mut previous_key = None::<Vec<u8>>;
+    let mut writer = new_block(&out, block::LOG);
+    let finish_log_block = |out: &mut Vec<u8>, writer: block::Writer| -> Result<(), Error> {
+        // Only the records are compressed, the block header and possibly the file header stay as they are.
+        let records_start = writer.records_start();
+        let mut data = writer.finish();
+        if out.is_empty() {
+            data[..header_len].copy_from_slice(&header);
+        }
+        out.extend_from_slice(&data[..records_start]);
+        let mut deflate = gix_zlib::stream::deflate::Write::new(std::mem::take(out), gix_zlib::Compression::default());
+        deflate.write_all(&data[records_start..])?;
+        deflate.flush()?;
+        *out = deflate.into_inner();
+        Ok(())
+    };
+    for record in logs {
+        let key = block::log_key(&record.name, record.update_index);
+        if previous_key.as_ref().is_some_and(|previous| *previous >= key) {
+            return Err(Error::Unsorted {
+                name: record.name.clone(),
+            });
+        }
+        value.clear();
+        let value_type = block::write_log(record, &mut value);
+        if !writer.add(&key, value_type, &value) {
+            if writer.is_empty() {
+                return Err(Error::RecordTooLarge {
+                    name: record.name.clone(),
+                    block_size,
+                });
+            }
+            let full = std::mem::replace(&mut writer, block::Writer::new(block::LOG, 0, block_size as usize));
+            finish_log_block(&mut out, full)?;
+            if !writer.add(&key, value_type, &value) {
+                return Err(Error::RecordTooLarge {
+                    name: record.name.clone(),
+                    block_size,
+                });
+            }
+        }
+        previous_key = Some(key);
+    }
+    let has_logs = !writer.is_empty();
+    if has_logs {
+        finish_log_block(&mut out, writer)?;
+    }
+
+    if out.is_empty() {
+        out.extend_from_slice(&header);
+    }
+    let footer_start = out.len();
+    out.extend_from_slice(&header);
+    let ref_index_offset = 0u64;
+    let obj_offset_and_id_len = 0u64;
+    let obj_index_offset = 0u64;
+    let log_offset = if has_logs { log_offset as u64 } else { 0 };
+    let log_index_offset = 0u64;
+    for value in [
+        ref_index_offset,
+        obj_offset_and_id_len,
+        obj_index_offset,
+        log_offset,
+        log_index_offset,
+    ] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    let crc = gix_features::hash::crc32(&out[footer_start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+    Ok(out)
+}
@@ -437,6 +437,76 @@ fn symbolic_reference_writes_reflog_if_previous_value_is_set() -> crate::Result
     Ok(())
 }
 
+#[test]
+fn symbolic_reference_switching_between_peelable_targets_writes_reflog_with_peeled_ids() -> crate::Result {
+    let (_keep, store) = empty_store()?;
+    let (a, b, detached) = (
+        hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"),
+        hex_to_id("02a7a22d90d7c02fb494ed25551850b868e634f0"),
+        hex_to_id("01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc"),
+    );
+    let update = |name: &str, new: Target, message: &str| -> crate::Result<RefEdit> {
+        Ok(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.into(),
+                },
+                new,
+                expected: PreviousValue::Any,
+            },
+            name: name.try_into()?,
+            deref: false,
+        })
+    };
+    let mut buf = TimeBuf::default();
+    store
+        .transaction()
+        .prepare(
+            [
+                update("refs/heads/a", Target::Object(a), "create a")?,
+                update("refs/heads/b", Target::Object(b), "create b")?,
+                update("HEAD", Target::Symbolic("refs/heads/a".try_into()?), "init")?,
+            ],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref(&mut buf))?;
+    assert!(
+        store.reflog_iter("HEAD", &mut Vec::new())?.is_none(),
+        "a new symbolic ref has no previous value to log"
+    );
+
+    for (new, message) in [
+        (
+            Target::Symbolic("refs/heads/b".try_into()?),
+            "checkout: moving from a to b",
+        ),
+        (Target::Symbolic("refs/heads/b".try_into()?), "no change"),
+        (Target::Object(detached), "checkout: moving from b to detached"),
+    ] {
+        store
+            .transaction()
+            .prepare(
+                Some(update("HEAD", new, message)?),
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref(&mut buf))?;
+    }
+    assert_eq!(
+        reflog_lines(&store, "HEAD")?,
+        vec![
+            log_line(a, b, "checkout: moving from a to b"),
+            log_line(b, detached, "checkout: moving from b to detached"),
+        ],
+        "switching the target is logged with peeled ids, but updates to the same target are not, \
+         and detaching uses the peeled previous target"
+    );
+    Ok(())
+}
+
 #[test]
 fn windows_device_name_is_illegal_with_enabled_windows_protections() -> crate::Result {
     let (_keep, mut store) = empty_store()?;
@@ -968,3 +1038,59 @@ fn packed_refs_deletion_in_deletions_and_updates_mode() -> crate::Result {
     );
     Ok(())
 }
+
+#[test]
+fn durable_writes_of_loose_refs_reflogs_and_packed_refs() -> crate::Result {
+    for method in [
+        gix_fs::fsync::Method::Fsync,
+        gix_fs::fsync::Method::WriteoutOnly,
+        gix_fs::fsync::Method::Batch,
+    ] {
+        let (_keep, mut store) = store_writable("make_ref_repository.sh")?;
+        store.fsync = Some(method);
+
+        let edits = store
+            .transaction()
+            .prepare(
+                Some(create_at("refs/heads/durable")),
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref(&mut TimeBuf::default()))?;
+        assert_eq!(edits.len(), 1);
+        assert_eq!(
+            store.find_loose("refs/heads/durable")?.target,
+            Target::Object(hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"))
+        );
+        assert_eq!(reflog_lines(&store, "refs/heads/durable")?.len(), 1);
+
+        let odb = crate::file::odb_at(store.git_dir().join("objects"))?;
+        let edits = store
+            .transaction()
+            .packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(
+                Box::new(odb),
+            ))
+            .prepare(
+                Some(RefEdit {
+                    change: Change::Update {
+                        log: LogChange::default(),
+                        expected: PreviousValue::Any,
+                        new: Target::Object(hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03")),
+                    },
+                    name: "refs/heads/durable".try_into()?,
+                    deref: false,
+                }),
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref(&mut TimeBuf::default()))?;
+        assert_eq!(edits.len(), 1);
+        let packed = store.open_packed_buffer()?.expect("packed refs were written");
+        assert_eq!(
+            packed.find("refs/heads/durable")?.target(),
+            hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+            "the update went straight into packed-refs with {method:?}"
+        );
+    }
+    Ok(())
+}
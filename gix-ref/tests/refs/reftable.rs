@@ -0,0 +1,499 @@
+use gix_object::bstr::ByteSlice;
+use gix_ref::reftable::{LogRecord, LogValue, RefRecord, RefValue, Stack, Table, write};
+
+use crate::{fixture_hash_kind, hex_to_id};
+
+fn object(name: &str, update_index: u64, hex: &str) -> RefRecord {
+    RefRecord {
+        name: name.into(),
+        update_index,
+        value: RefValue::Object(hex_to_id(hex)),
+    }
+}
+
+fn log(name: &str, update_index: u64, message: &str) -> LogRecord {
+    LogRecord {
+        name: name.into(),
+        update_index,
+        value: LogValue::Update(gix_ref::log::Line {
+            previous_oid: fixture_hash_kind().null(),
+            new_oid: hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+            signature: gix_actor::Signature {
+                name: "committer".into(),
+                email: "committer@example.com".into(),
+                time: gix_date::parse_header("1234 -0130").unwrap(),
+            },
+            message: message.into(),
+        }),
+    }
+}
+
+fn options(block_size: u32) -> write::Options {
+    write::Options {
+        object_hash: fixture_hash_kind(),
+        block_size,
+        min_update_index: 1,
+        max_update_index: 1,
+    }
+}
+
+mod table {
+    use gix_ref::reftable::{RefValue, Table, write};
+
+    use super::{log, object, options};
+    use crate::{fixture_hash_kind, hex_to_id};
+
+    #[test]
+    fn round_trip_across_multiple_blocks() -> crate::Result {
+        let refs: Vec<_> = (0..200)
+            .map(|idx| {
+                object(
+                    &format!("refs/heads/branch-{idx:03}"),
+                    1,
+                    "134385f6d781b7e97062102c6a483440bfda2a03",
+                )
+            })
+            .chain(Some(gix_ref::reftable::RefRecord {
+                name: "refs/tags/v1".into(),
+                update_index: 1,
+                value: RefValue::Peeled {
+                    target: hex_to_id("4c3f4cce493d7beb45012e478021b5f65295e5a3"),
+                    peeled: hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+                },
+            }))
+            .collect();
+        let logs: Vec<_> = (0..100)
+            .map(|idx| log(&format!("refs/heads/branch-{idx:03}"), 1, &format!("message {idx}")))
+            .collect();
+        let data = write::table(&refs, &logs, options(512))?;
+        let table = Table::from_bytes(data, fixture_hash_kind())?;
+
+        assert_eq!(table.min_update_index(), 1);
+        assert_eq!(table.block_size(), 512);
+        assert_eq!(
+            table.refs().collect::<Result<Vec<_>, _>>()?,
+            refs,
+            "all refs are read back in order"
+        );
+        assert_eq!(
+            table.logs().collect::<Result<Vec<_>, _>>()?,
+            logs,
+            "logs are read back as well"
+        );
+        for record in &refs {
+            assert_eq!(table.find_ref(record.name.as_ref())?.as_ref(), Some(record));
+        }
+        assert_eq!(table.find_ref("refs/heads/a".into())?, None, "before the first record");
+        assert_eq!(
+            table.find_ref("refs/heads/branch-0055".into())?,
+            None,
+            "in between records"
+        );
+        assert_eq!(table.find_ref("refs/zzz".into())?, None, "after the last record");
+        Ok(())
+    }
+
+    #[test]
+    fn logs_of_a_single_reference_across_multiple_blocks() -> crate::Result {
+        let logs: Vec<_> = (0..100)
+            .flat_map(|idx| {
+                (1..=3)
+                    .rev()
+                    .map(move |update_index| log(&format!("refs/heads/branch-{idx:03}"), update_index, "message"))
+            })
+            .collect();
+        let options = write::Options {
+            max_update_index: 3,
+            ..options(512)
+        };
+        let table = Table::from_bytes(write::table(&[], &logs, options)?, fixture_hash_kind())?;
+
+        for idx in [0, 42, 99] {
+            let name = format!("refs/heads/branch-{idx:03}");
+            let expected: Vec<_> = logs.iter().filter(|record| record.name == name).cloned().collect();
+            assert_eq!(expected.len(), 3);
+            assert_eq!(
+                table.logs_of(name.as_str().into()).collect::<Result<Vec<_>, _>>()?,
+                expected,
+                "all entries are found, most recent first"
+            );
+        }
+        assert_eq!(
+            table.logs_of("refs/heads/a".into()).count(),
+            0,
+            "before the first record"
+        );
+        assert_eq!(
+            table.logs_of("refs/heads/branch-0055".into()).count(),
+            0,
+            "in between records"
+        );
+        assert_eq!(table.logs_of("refs/zzz".into()).count(), 0, "after the last record");
+        Ok(())
+    }
+
+    #[test]
+    fn empty_and_log_only_tables() -> crate::Result {
+        let table = Table::from_bytes(write::table(&[], &[], options(4096))?, fixture_hash_kind())?;
+        assert_eq!(table.refs().count(), 0);
+        assert_eq!(table.logs().count(), 0);
+
+        let logs = vec![log("HEAD", 1, "")];
+        let table = Table::from_bytes(write::table(&[], &logs, options(4096))?, fixture_hash_kind())?;
+        assert_eq!(table.refs().count(), 0);
+        assert_eq!(table.logs().collect::<Result<Vec<_>, _>>()?, logs);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() -> crate::Result {
+        let unsorted = [
+            object("refs/heads/b", 1, "134385f6d781b7e97062102c6a483440bfda2a03"),
+            object("refs/heads/a", 1, "134385f6d781b7e97062102c6a483440bfda2a03"),
+        ];
+        assert!(matches!(
+            write::table(&unsorted, &[], options(4096)),
+            Err(write::Error::Unsorted { .. })
+        ));
+        let out_of_range = [object("refs/heads/a", 2, "134385f6d781b7e97062102c6a483440bfda2a03")];
+        assert!(matches!(
+            write::table(&out_of_range, &[], options(4096)),
+            Err(write::Error::UpdateIndexOutOfRange { .. })
+        ));
+
+        let mut data = write::table(&unsorted[1..], &[], options(4096))?;
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert!(matches!(
+            Table::from_bytes(data, fixture_hash_kind()),
+            Err(gix_ref::reftable::table::decode::Error::Checksum)
+        ));
+        Ok(())
+    }
+}
+
+mod stack {
+    use gix_lock::acquire::Fail;
+    use gix_ref::reftable::{LogValue, RefRecord, RefValue, Stack};
+
+    use super::{log, object};
+    use crate::fixture_hash_kind;
+
+    #[test]
+    fn newer_tables_shadow_older_ones_until_compaction() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let stack = Stack::at(tmp.path().join("reftable"), fixture_hash_kind())?;
+        assert_eq!(stack.tables().len(), 0, "missing stacks are empty");
+
+        let lock = stack.lock(Fail::Immediately)?;
+        assert_eq!(lock.next_update_index(), 1);
+        let stack = lock.commit(
+            vec![
+                object("refs/heads/main", 1, "134385f6d781b7e97062102c6a483440bfda2a03"),
+                object("refs/heads/other", 1, "134385f6d781b7e97062102c6a483440bfda2a03"),
+            ],
+            vec![log("refs/heads/main", 1, "first")],
+        )?;
+
+        let first = stack.clone();
+        let lock = stack.lock(Fail::Immediately)?;
+        assert!(
+            stack.lock(Fail::Immediately).is_err(),
+            "the stack is locked exclusively"
+        );
+        assert_eq!(lock.next_update_index(), 2);
+        let stack = lock.commit(
+            vec![
+                object("refs/heads/main", 2, "4c3f4cce493d7beb45012e478021b5f65295e5a3"),
+                RefRecord {
+                    name: "refs/heads/other".into(),
+                    update_index: 2,
+                    value: RefValue::Deletion,
+                },
+            ],
+            vec![log("refs/heads/main", 2, "second")],
+        )?;
+
+        let reopened = Stack::at(stack.dir(), fixture_hash_kind())?;
+        for stack in [&stack, &reopened] {
+            assert_eq!(stack.max_update_index(), 2);
+            let refs = stack.refs()?;
+            assert_eq!(
+                refs,
+                [object("refs/heads/main", 2, "4c3f4cce493d7beb45012e478021b5f65295e5a3")],
+                "deletions hide older records"
+            );
+            assert_eq!(stack.find_ref("refs/heads/other".into())?, None);
+            let messages: Vec<_> = stack
+                .logs("refs/heads/main".into())?
+                .into_iter()
+                .map(|record| match record.value {
+                    LogValue::Update(line) => line.message,
+                    LogValue::Deletion => unreachable!("deletions are filtered"),
+                })
+                .collect();
+            assert_eq!(messages, ["second", "first"], "most recent first");
+        }
+
+        let compacted = stack.lock(Fail::Immediately)?.compact()?;
+        assert_eq!(compacted.tables().len(), 1);
+        assert_eq!(compacted.refs()?, stack.refs()?);
+        assert_eq!(
+            compacted.logs("refs/heads/main".into())?,
+            stack.logs("refs/heads/main".into())?
+        );
+        assert_eq!(
+            std::fs::read_dir(compacted.dir())?.count(),
+            2,
+            "only the compacted table and the list remain"
+        );
+        assert!(first.reload()?.is_some(), "stacks notice that they changed on disk");
+        assert!(compacted.reload()?.is_none(), "…and are reused if they didn't");
+        Ok(())
+    }
+
+    #[test]
+    fn stale_update_indices_are_rejected() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let stack = Stack::at(tmp.path(), fixture_hash_kind())?;
+        let stack = stack.lock(Fail::Immediately)?.commit(
+            vec![object("HEAD", 1, "134385f6d781b7e97062102c6a483440bfda2a03")],
+            vec![],
+        )?;
+        assert!(matches!(
+            stack.lock(Fail::Immediately)?.commit(
+                vec![object("HEAD", 1, "134385f6d781b7e97062102c6a483440bfda2a03")],
+                vec![]
+            ),
+            Err(gix_ref::reftable::stack::commit::Error::StaleUpdateIndex { min: 2 })
+        ));
+        Ok(())
+    }
+}
+
+mod store {
+    use gix_date::parse::TimeBuf;
+    use gix_lock::acquire::Fail;
+    use gix_ref::{
+        Target,
+        file::Store,
+        store::{Format, WriteReflog},
+        transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    };
+
+    use crate::{fixture_hash_kind, hex_to_id};
+
+    fn store(dir: &std::path::Path) -> Store {
+        Store::at(
+            dir.into(),
+            gix_ref::store::init::Options {
+                write_reflog: WriteReflog::Normal,
+                object_hash: fixture_hash_kind(),
+                format: Format::Reftable,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn update(name: &str, new: Target, expected: PreviousValue) -> RefEdit {
+        RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    message: format!("set {name}").into(),
+                    ..Default::default()
+                },
+                expected,
+                new,
+            },
+            name: name.try_into().expect("valid"),
+            deref: false,
+        }
+    }
+
+    fn committer() -> gix_actor::Signature {
+        gix_actor::Signature {
+            name: "committer".into(),
+            email: "committer@example.com".into(),
+            time: gix_date::parse_header("1234 +0800").unwrap(),
+        }
+    }
+
+    #[test]
+    fn transactions_find_iter_and_reflogs() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let store = store(tmp.path());
+        assert_eq!(store.format(), Format::Reftable);
+        let first = hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03");
+        let second = hex_to_id("4c3f4cce493d7beb45012e478021b5f65295e5a3");
+        let mut buf = TimeBuf::default();
+
+        store
+            .transaction()
+            .prepare(
+                [
+                    update(
+                        "HEAD",
+                        Target::Symbolic("refs/heads/main".try_into()?),
+                        PreviousValue::Any,
+                    ),
+                    update("refs/heads/main", Target::Object(first), PreviousValue::MustNotExist),
+                    update("refs/tags/v1", Target::Object(first), PreviousValue::MustNotExist),
+                ],
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref(&mut buf))?;
+        assert!(
+            !tmp.path().join("refs").exists() && !tmp.path().join("HEAD").exists(),
+            "nothing is written as files"
+        );
+
+        let mut edit = update("HEAD", Target::Object(second), PreviousValue::MustExist);
+        edit.deref = true;
+        let edits = store
+            .transaction()
+            .prepare(Some(edit), Fail::Immediately, Fail::Immediately)?
+            .commit(committer().to_ref(&mut buf))?;
+        assert_eq!(edits.len(), 2, "HEAD was split into its referent");
+
+        let err = store
+            .transaction()
+            .prepare(
+                Some(update(
+                    "refs/tags/v1",
+                    Target::Object(second),
+                    PreviousValue::MustNotExist,
+                )),
+                Fail::Immediately,
+                Fail::Immediately,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            gix_ref::file::transaction::prepare::Error::MustNotExist { .. }
+        ));
+
+        let head = store.find("HEAD")?;
+        assert_eq!(head.target, Target::Symbolic("refs/heads/main".try_into()?));
+        assert_eq!(store.find("main")?.target, Target::Object(second), "lookup rules apply");
+        assert_eq!(store.find("v1")?.target, Target::Object(first));
+        assert!(store.try_find("refs/heads/missing")?.is_none());
+
+        let names = |iter: gix_ref::file::iter::LooseThenPacked<'_, '_>| -> crate::Result<Vec<String>> {
+            Ok(iter
+                .map(|r| r.map(|r| r.name.as_bstr().to_string()))
+                .collect::<Result<_, _>>()?)
+        };
+        let platform = store.iter()?;
+        assert_eq!(names(platform.all()?)?, ["refs/heads/main", "refs/tags/v1"]);
+        assert_eq!(names(platform.prefixed(b"refs/tags/".try_into()?)?)?, ["refs/tags/v1"]);
+        assert_eq!(names(platform.pseudo()?)?, ["HEAD"]);
+
+        let messages = |name: &str| -> crate::Result<Vec<String>> {
+            let mut buf = Vec::new();
+            Ok(store
+                .reflog_iter(name, &mut buf)?
+                .expect("log exists")
+                .map(|line| line.map(|line| line.message.to_string()))
+                .collect::<Result<_, _>>()?)
+        };
+        assert_eq!(messages("refs/heads/main")?, ["set refs/heads/main", "set HEAD"]);
+        assert_eq!(
+            messages("HEAD")?,
+            ["set HEAD"],
+            "symbolic updates are only logged with a known object"
+        );
+        assert!(!store.reflog_exists("refs/tags/v1")?, "tags don't get a log by default");
+        let mut buf = [0u8; 256];
+        let newest = store
+            .reflog_iter_rev("refs/heads/main", &mut buf)?
+            .expect("log exists")
+            .next()
+            .expect("one line")?;
+        assert_eq!(newest.new_oid, second);
+
+        store
+            .transaction()
+            .prepare(
+                Some(RefEdit {
+                    change: Change::Delete {
+                        expected: PreviousValue::MustExistAndMatch(Target::Object(second)),
+                        log: RefLog::AndReference,
+                    },
+                    name: "refs/heads/main".try_into()?,
+                    deref: false,
+                }),
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref(&mut buf_time()))?;
+        assert!(store.try_find("refs/heads/main")?.is_none());
+        assert!(
+            !store.reflog_exists("refs/heads/main")?,
+            "the log is deleted with the reference"
+        );
+        assert_eq!(names(store.iter()?.all()?)?, ["refs/tags/v1"]);
+
+        let stack = store.reftable_stack()?.expect("reftable store");
+        assert!(stack.tables().len() < 4, "tables are compacted automatically");
+        Ok(())
+    }
+
+    fn buf_time() -> TimeBuf {
+        TimeBuf::default()
+    }
+
+    #[test]
+    fn namespaces_are_applied_to_records() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut store = store(tmp.path());
+        store.namespace = Some(gix_ref::namespace::expand("ns")?);
+        let mut buf = TimeBuf::default();
+        store
+            .transaction()
+            .prepare(
+                Some(update(
+                    "refs/heads/main",
+                    Target::Object(hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03")),
+                    PreviousValue::MustNotExist,
+                )),
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref(&mut buf))?;
+
+        let stack = store.reftable_stack()?.expect("reftable store");
+        assert_eq!(stack.refs()?[0].name, "refs/namespaces/ns/refs/heads/main");
+        assert_eq!(store.find("main")?.name.as_bstr(), "refs/heads/main");
+        let names: Vec<_> = store
+            .iter()?
+            .all()?
+            .map(|r| r.map(|r| r.name))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[0].as_bstr(), "refs/heads/main");
+
+        store.namespace = None;
+        assert!(store.try_find("refs/heads/main")?.is_none());
+        Ok(())
+    }
+}
+
+#[test]
+fn tables_can_be_opened_from_disk_and_read_back_in_stacks() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let stack = Stack::at(tmp.path(), fixture_hash_kind())?
+        .lock(gix_lock::acquire::Fail::Immediately)?
+        .commit(
+            vec![object("refs/heads/main", 1, "134385f6d781b7e97062102c6a483440bfda2a03")],
+            vec![log("refs/heads/main", 1, "message")],
+        )?;
+    let (name, table) = stack.tables().next().expect("one table");
+    let reopened = Table::at(&stack.dir().join(name), fixture_hash_kind())?;
+    assert_eq!(reopened.size(), table.size());
+    assert_eq!(
+        reopened.find_ref(b"refs/heads/main".as_bstr())?,
+        Some(object("refs/heads/main", 1, "134385f6d781b7e97062102c6a483440bfda2a03"))
+    );
+    Ok(())
+}
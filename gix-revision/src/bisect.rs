@@ -0,0 +1,283 @@
+use gix_hash::ObjectId;
+
+bitflags::bitflags! {
+    /// The flags used in the graph for [bisecting](crate::bisect()).
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+    pub struct Flags: u8 {
+        /// The commit is reachable from the bad commit.
+        const BAD = 1 << 0;
+        /// The commit is reachable from any of the good commits.
+        const GOOD = 1 << 1;
+        /// The commit was already put onto the list of candidates.
+        const CANDIDATE = 1 << 2;
+    }
+}
+
+/// The error returned by the [`bisect()`](function::bisect()) function.
+pub type Error = gix_error::Message;
+
+/// The outcome of the [`bisect()`](function::bisect()) function.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    /// The commit to test next.
+    Next {
+        /// The id of the commit to test next, which is roughly halfway between the bad commit and the good commits.
+        id: ObjectId,
+        /// The amount of commits that are left to test after this one, assuming the worst case.
+        remaining: usize,
+        /// The estimated amount of steps needed to find the first bad commit after this one.
+        steps: usize,
+    },
+    /// The first bad commit was found as it's the only candidate left.
+    FirstBad {
+        /// The id of the first bad commit.
+        id: ObjectId,
+    },
+    /// All candidates that could be the first bad commit were skipped, so the first bad commit can't be determined.
+    OnlySkipped {
+        /// All commits that could be the first bad commit, including the bad commit itself, sorted from oldest to newest.
+        candidates: Vec<ObjectId>,
+    },
+}
+
+/// Estimate the amount of steps needed to find the first bad commit among `candidates` commits, like Git does.
+pub fn estimate_steps(candidates: usize) -> usize {
+    if candidates < 3 {
+        return 0;
+    }
+    let n = candidates.ilog2() as usize;
+    let e = 1 << n;
+    let x = candidates - e;
+    if e < 3 * x { n } else { n - 1 }
+}
+
+pub(crate) mod function {
+    use gix_error::{Exn, ResultExt, message};
+    use gix_hash::ObjectId;
+    use gix_revwalk::graph;
+
+    use super::{Error, Flags, Outcome, estimate_steps};
+    use crate::{Graph, PriorityQueue};
+
+    /// Given the `bad` commit, the `good` commits and the `skipped` commits which can't be tested, traverse the commit `graph`
+    /// to find the commit that splits the commits that may have introduced the bad change roughly in half.
+    ///
+    /// The commits that may have introduced the change are all commits reachable from `bad` which aren't reachable from any
+    /// commit in `good`, with `bad` itself being one of them. Each of these is weighted by the amount of such commits it
+    /// can reach, and the one whose weight is closest to half of all of them is chosen, in the same way as `git bisect` does.
+    /// `skipped` commits are never chosen, but unlike Git, which picks a pseudo-random commit nearby in that case, the
+    /// commit with the next-best weight is chosen instead to keep results deterministic.
+    ///
+    /// # Performance
+    ///
+    /// For repeated calls, be sure to re-use `graph` as its content will be kept and reused for a great speed-up. The contained flags
+    /// will automatically be cleared.
+    pub fn bisect(
+        bad: ObjectId,
+        good: &[ObjectId],
+        skipped: &[ObjectId],
+        graph: &mut Graph<'_, '_, graph::Commit<Flags>>,
+    ) -> Result<Outcome, Exn<Error>> {
+        let _span = gix_trace::coarse!("gix_revision::bisect()", ?bad, ?good, skipped = skipped.len());
+        graph.clear_commit_data(|f| *f = Flags::empty());
+        let candidates = paint_candidates(bad, good, graph)?;
+
+        if candidates.len() <= 1 {
+            return Ok(Outcome::FirstBad { id: bad });
+        }
+        let weights = weights(&candidates, graph, skipped.is_empty());
+        let total = candidates.len();
+        let distance = |weight: usize| weight.min(total - weight);
+
+        if skipped.is_empty() {
+            let (id, weight) = match weights.halfway {
+                Some(idx) => (candidates[idx], weights.by_index[idx].expect("set when halfway")),
+                None => {
+                    let mut best = (bad, 0, None);
+                    for (id, weight) in candidates.iter().zip(weights.by_index.iter()) {
+                        let weight = weight.expect("all weights are set without early exit");
+                        if best.2.is_none_or(|best_distance| distance(weight) > best_distance) {
+                            best = (*id, weight, Some(distance(weight)));
+                        }
+                    }
+                    (best.0, best.1)
+                }
+            };
+            return Ok(if id == bad {
+                Outcome::FirstBad { id }
+            } else {
+                Outcome::Next {
+                    id,
+                    remaining: weight.max(total - weight) - 1,
+                    steps: estimate_steps(total),
+                }
+            });
+        }
+
+        let mut by_distance: Vec<_> = candidates
+            .iter()
+            .zip(weights.by_index.iter())
+            .map(|(id, weight)| (*id, weight.expect("all weights are set without early exit")))
+            .collect();
+        by_distance.sort_by_key(|(_, weight)| std::cmp::Reverse(distance(*weight)));
+        match by_distance
+            .into_iter()
+            .find(|(id, _)| *id != bad && !skipped.contains(id))
+        {
+            Some((id, weight)) => Ok(Outcome::Next {
+                id,
+                remaining: weight.max(total - weight) - 1,
+                steps: estimate_steps(total),
+            }),
+            None => Ok(Outcome::OnlySkipped { candidates }),
+        }
+    }
+
+    /// Paint everything reachable from `bad` and `good`, and return all commits that are only reachable from `bad`,
+    /// sorted from oldest to newest.
+    fn paint_candidates(
+        bad: ObjectId,
+        good: &[ObjectId],
+        graph: &mut Graph<'_, '_, graph::Commit<Flags>>,
+    ) -> Result<Vec<ObjectId>, Exn<Error>> {
+        let mut queue = PriorityQueue::<gix_date::SecondsSinceUnixEpoch, ObjectId>::new();
+        for (id, flags) in std::iter::once((bad, Flags::BAD)).chain(good.iter().map(|id| (*id, Flags::GOOD))) {
+            graph
+                .get_or_insert_full_commit(id, |commit| {
+                    commit.data |= flags;
+                    queue.insert(commit.commit_time, id);
+                })
+                .or_raise(|| message!("could not insert commit {} into graph", id.to_hex()))?;
+        }
+
+        let mut candidates = Vec::new();
+        while queue
+            .iter_unordered()
+            .any(|id| graph.get(id).is_some_and(|commit| !commit.data.contains(Flags::GOOD)))
+        {
+            let (time, commit_id) = queue.pop().expect("we have non-good commits");
+            let commit = graph.get_mut(&commit_id).expect("everything queued is in graph");
+            let flags = commit.data & (Flags::BAD | Flags::GOOD);
+            if flags == Flags::BAD && !commit.data.contains(Flags::CANDIDATE) {
+                commit.data |= Flags::CANDIDATE;
+                candidates.push((commit_id, time));
+            }
+            for parent_id in commit.parents.clone() {
+                graph
+                    .get_or_insert_full_commit(parent_id, |parent| {
+                        if (parent.data & flags) != flags {
+                            parent.data |= flags;
+                            queue.insert(parent.commit_time, parent_id);
+                        }
+                    })
+                    .or_raise(|| message!("could not insert parent of commit {} into graph", commit_id.to_hex()))?;
+            }
+        }
+
+        // Commits may have been painted as good only after they were seen as candidate.
+        candidates.retain(|(id, _)| {
+            let commit = graph.get_mut(id).expect("candidates are in graph");
+            if commit.data.contains(Flags::GOOD) {
+                commit.data.remove(Flags::CANDIDATE);
+                false
+            } else {
+                true
+            }
+        });
+        // Newest first like `git rev-list` would, in a stable way so that commits with the same time stay in traversal order.
+        candidates.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+        Ok(candidates.into_iter().rev().map(|(id, _)| id).collect())
+    }
+
+    struct Weights {
+        /// The amount of candidates reachable from each candidate, including itself, or `None` if it wasn't computed.
+        by_index: Vec<Option<usize>>,
+        /// The index of the first candidate which happened to be halfway, which stopped the computation early.
+        halfway: Option<usize>,
+    }
+
+    /// Compute the weight of each of the `candidates`, sorted from oldest to newest, in the same order as Git
+    /// so that the same commit is chosen even if multiple commits are equally suitable.
+    /// If `stop_at_halfway` is `true`, stop as soon as the first candidate is found to be halfway.
+    fn weights(candidates: &[ObjectId], graph: &Graph<'_, '_, graph::Commit<Flags>>, stop_at_halfway: bool) -> Weights {
+        let total = candidates.len();
+        let is_candidate = |id: &ObjectId| graph.get(id).is_some_and(|c| c.data.contains(Flags::CANDIDATE));
+        let index_by_id: gix_hashtable::HashMap<ObjectId, usize> =
+            candidates.iter().enumerate().map(|(idx, id)| (*id, idx)).collect();
+        let candidate_parents = |id: &ObjectId| {
+            graph
+                .get(id)
+                .expect("candidates are in graph")
+                .parents
+                .iter()
+                .filter(|id| is_candidate(id))
+                .map(|id| index_by_id[id])
+                .collect::<Vec<_>>()
+        };
+        let is_halfway = |weight: usize| stop_at_halfway && (2 * weight).abs_diff(total) <= 1;
+
+        let mut by_index = vec![None; total];
+        let mut has_merge = vec![false; total];
+        for (idx, id) in candidates.iter().enumerate() {
+            match candidate_parents(id).len() {
+                0 => by_index[idx] = Some(1),
+                1 => {}
+                _ => has_merge[idx] = true,
+            }
+        }
+
+        // Merges can't inherit the weight of one of their parents as they would count shared ancestors twice.
+        for (idx, id) in candidates.iter().enumerate().filter(|(idx, _)| has_merge[*idx]) {
+            let weight = count_reachable(*id, &is_candidate, graph);
+            by_index[idx] = Some(weight);
+            if is_halfway(weight) {
+                return Weights {
+                    by_index,
+                    halfway: Some(idx),
+                };
+            }
+        }
+
+        while by_index.iter().any(Option::is_none) {
+            for (idx, id) in candidates.iter().enumerate() {
+                if by_index[idx].is_some() {
+                    continue;
+                }
+                let Some(parent_weight) = candidate_parents(id).into_iter().find_map(|parent| by_index[parent]) else {
+                    continue;
+                };
+                let weight = parent_weight + 1;
+                by_index[idx] = Some(weight);
+                if is_halfway(weight) {
+                    return Weights {
+                        by_index,
+                        halfway: Some(idx),
+                    };
+                }
+            }
+        }
+        Weights {
+            by_index,
+            halfway: None,
+        }
+    }
+
+    /// Count all candidates reachable from `id`, including itself.
+    fn count_reachable(
+        id: ObjectId,
+        is_candidate: &dyn Fn(&ObjectId) -> bool,
+        graph: &Graph<'_, '_, graph::Commit<Flags>>,
+    ) -> usize {
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut stack = vec![id];
+        seen.insert(id);
+        while let Some(id) = stack.pop() {
+            for parent in &graph.get(&id).expect("candidates are in graph").parents {
+                if is_candidate(parent) && seen.insert(*parent) {
+                    stack.push(*parent);
+                }
+            }
+        }
+        seen.len()
+    }
+}
@@ -18,6 +18,11 @@ pub use describe::function::describe;
 pub mod merge_base;
 #[cfg(feature = "merge_base")]
 pub use merge_base::function::merge_base;
+/// Find the commit that introduced a change by bisecting the commit graph.
+#[cfg(feature = "bisect")]
+pub mod bisect;
+#[cfg(feature = "bisect")]
+pub use bisect::function::bisect;
 
 ///
 pub mod spec;
@@ -204,7 +204,7 @@ fn paint_down_to_common(
 // TODO(ST): Should this type be used for `describe` as well?
 #[derive(Debug, Clone, Copy)]
 struct GenThenTime {
-    /// Note that the special [`GENERATION_NUMBER_INFINITY`](gix_commitgraph::GENERATION_NUMBER_INFINITY) is used to indicate
+    /// Note that [`Generation::MAX`](gix_revwalk::graph::Generation) is used as infinity to indicate
     /// that no commitgraph is available.
     generation: gix_revwalk::graph::Generation,
     time: gix_date::SecondsSinceUnixEpoch,
@@ -213,7 +213,7 @@ struct GenThenTime {
 impl From<&graph::Commit<Flags>> for GenThenTime {
     fn from(commit: &graph::Commit<Flags>) -> Self {
         GenThenTime {
-            generation: commit.generation.unwrap_or(gix_commitgraph::GENERATION_NUMBER_INFINITY),
+            generation: commit.generation.unwrap_or(gix_revwalk::graph::Generation::MAX),
             time: commit.commit_time,
         }
     }
@@ -0,0 +1,71 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+EMPTY_TREE=$(git mktree </dev/null)
+function ofs_commit () {
+  local OFFSET_SECONDS=$1
+  local COMMIT_NAME=$2
+  shift 2
+
+  PARENTS=
+  for P; do
+    PARENTS="${PARENTS}-p $(git rev-parse $P) "
+  done
+
+  GIT_COMMITTER_DATE="$((400403349 + OFFSET_SECONDS)) +0000"
+  GIT_AUTHOR_DATE=$GIT_COMMITTER_DATE
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+
+  commit=$(echo $COMMIT_NAME | git commit-tree $EMPTY_TREE ${PARENTS:-})
+
+  git update-ref "refs/tags/$COMMIT_NAME" "$commit"
+}
+
+function baseline() {
+  echo "$@"
+  echo $(git rev-parse "$@")
+  git rev-list --bisect-vars "$1" $(for good in "${@:2}"; do echo "^$good"; done)
+  echo
+}
+
+# A---B---C---D---E-------M1--F---G---H---M2---I
+#          \             /                /
+#           X1---X2---X3                 /
+#                 \                     /
+#                  Y1------------------Y2
+ofs_commit 0 A
+ofs_commit 100 B A
+ofs_commit 200 C B
+ofs_commit 250 X1 C
+ofs_commit 300 D C
+ofs_commit 350 X2 X1
+ofs_commit 375 Y1 X2
+ofs_commit 400 E D
+ofs_commit 450 X3 X2
+ofs_commit 500 M1 E X3
+ofs_commit 600 F M1
+ofs_commit 700 G F
+ofs_commit 750 Y2 Y1
+ofs_commit 800 H G
+ofs_commit 900 M2 H Y2
+ofs_commit 1000 I M2
+
+{
+  baseline I A
+  baseline I B
+  baseline I C
+  baseline I E
+  baseline I X2
+  baseline I Y2
+  baseline I E X3
+  baseline I G Y2
+  baseline M2 H Y2
+  baseline M2 Y1 G
+  baseline H F
+  baseline H G
+  baseline B A
+  baseline X3 A
+  baseline M1 X1 D
+} > bisect.baseline
@@ -0,0 +1,185 @@
+use gix_revision::bisect::{self, Outcome};
+
+#[test]
+fn validate() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_bisect_repo.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let expectations = baseline::parse_expectations(&root.join("bisect.baseline"))?;
+    assert_ne!(expectations.len(), 0, "there must be at least one baseline");
+
+    let mut reused_graph = gix_revision::Graph::new(&odb, None);
+    for expected in expectations {
+        let mut graph = gix_revision::Graph::new(&odb, None);
+        let actual = run(expected.bad, &expected.good, &[], &mut graph)?;
+        assert_eq!(actual, expected.outcome, "{}", expected.plain_input);
+
+        let actual = run(expected.bad, &expected.good, &[], &mut reused_graph)?;
+        assert_eq!(actual, expected.outcome, "reused graph: {}", expected.plain_input);
+    }
+    Ok(())
+}
+
+#[test]
+fn skipped_commits_are_never_chosen() -> crate::Result {
+    let (odb, tag) = repo()?;
+    let mut graph = gix_revision::Graph::new(&odb, None);
+    let (bad, good) = (tag("I")?, [tag("A")?]);
+    let Outcome::Next { id: first_choice, .. } = run(bad, &good, &[], &mut graph)? else {
+        panic!("there are plenty of commits to test")
+    };
+    assert_eq!(first_choice, tag("M1")?);
+
+    let Outcome::Next { id, remaining, steps } = run(bad, &good, &[first_choice], &mut graph)? else {
+        panic!("there are still plenty of commits to test")
+    };
+    assert_eq!(
+        id,
+        tag("F")?,
+        "the commit with the next-best distance is chosen, which is the one right after the skipped one"
+    );
+    assert_eq!(remaining, 8);
+    assert_eq!(steps, 3, "the amount of commits to test is still the same");
+    Ok(())
+}
+
+#[test]
+fn skipping_all_candidates_lists_them() -> crate::Result {
+    let (odb, tag) = repo()?;
+    let mut graph = gix_revision::Graph::new(&odb, None);
+    let (bad, good) = (tag("M2")?, [tag("G")?, tag("Y1")?]);
+    let skipped = [tag("H")?, tag("Y2")?];
+    let actual = run(bad, &good, &skipped, &mut graph)?;
+    assert_eq!(
+        actual,
+        Outcome::OnlySkipped {
+            candidates: vec![tag("Y2")?, tag("H")?, bad]
+        },
+        "candidates are sorted from oldest to newest"
+    );
+
+    let actual = run(bad, &good, &skipped[..1], &mut graph)?;
+    assert_eq!(
+        actual,
+        Outcome::Next {
+            id: tag("Y2")?,
+            remaining: 1,
+            steps: 1
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn bad_commit_is_first_bad_if_it_is_good_as_well() -> crate::Result {
+    let (odb, tag) = repo()?;
+    let mut graph = gix_revision::Graph::new(&odb, None);
+    let bad = tag("F")?;
+    assert_eq!(
+        run(bad, &[tag("I")?], &[], &mut graph)?,
+        Outcome::FirstBad { id: bad },
+        "there is nothing to bisect if the bad commit is reachable from a good one"
+    );
+    Ok(())
+}
+
+#[test]
+fn estimate_steps() {
+    for (candidates, expected) in [
+        (0, 0),
+        (1, 0),
+        (2, 0),
+        (3, 1),
+        (4, 1),
+        (5, 1),
+        (6, 2),
+        (8, 2),
+        (11, 3),
+        (16, 3),
+    ] {
+        assert_eq!(bisect::estimate_steps(candidates), expected, "{candidates}");
+    }
+}
+
+fn run(
+    bad: gix_hash::ObjectId,
+    good: &[gix_hash::ObjectId],
+    skipped: &[gix_hash::ObjectId],
+    graph: &mut gix_revision::Graph<'_, '_, gix_revision::graph::Commit<bisect::Flags>>,
+) -> crate::Result<Outcome> {
+    Ok(gix_revision::bisect(bad, good, skipped, graph).map_err(gix_error::Exn::into_inner)?)
+}
+
+type TagLookup = Box<dyn Fn(&str) -> crate::Result<gix_hash::ObjectId>>;
+
+fn repo() -> crate::Result<(gix_odb::Handle, TagLookup)> {
+    let root = gix_testtools::scripted_fixture_read_only("make_bisect_repo.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let tag = move |name: &str| -> crate::Result<gix_hash::ObjectId> {
+        let hex = std::fs::read(root.join(".git/refs/tags").join(name))?;
+        Ok(gix_hash::ObjectId::from_hex(hex.trim_ascii_end())?)
+    };
+    Ok((odb, Box::new(tag)))
+}
+
+mod baseline {
+    use std::path::Path;
+
+    use bstr::ByteSlice;
+    use gix_hash::ObjectId;
+    use gix_revision::bisect::Outcome;
+
+    /// The expectation as produced by Git itself
+    #[derive(Debug)]
+    pub struct Expectation {
+        pub plain_input: String,
+        pub bad: ObjectId,
+        pub good: Vec<ObjectId>,
+        pub outcome: Outcome,
+    }
+
+    pub fn parse_expectations(baseline: &Path) -> std::io::Result<Vec<Expectation>> {
+        let lines = std::fs::read(baseline)?;
+        let mut lines = lines.lines();
+        let mut out = Vec::new();
+        while let Some(plain_input) = lines.next() {
+            let plain_input = plain_input.to_str_lossy().into_owned();
+            let mut input = lines
+                .next()
+                .expect("second line is resolved input objects")
+                .split(|b| *b == b' ');
+            let bad = ObjectId::from_hex(input.next().expect("at least one object")).unwrap();
+            let good = input.map(|hex_id| ObjectId::from_hex(hex_id).unwrap()).collect();
+            let vars: Vec<_> = lines
+                .by_ref()
+                .take_while(|l| !l.is_empty())
+                .map(|line| {
+                    let (name, value) = line.split_once_str("=").expect("name=value");
+                    (name.to_str().unwrap(), value.trim_with(|c| c == '\''))
+                })
+                .collect();
+            let var = |name: &str| {
+                vars.iter()
+                    .find_map(|(var_name, value)| (*var_name == name).then_some(*value))
+                    .expect("all variables are present")
+            };
+            let number = |name: &str| -> usize { var(name).to_str().unwrap().parse().unwrap() };
+            let id = ObjectId::from_hex(var("bisect_rev")).unwrap();
+            let outcome = if number("bisect_all") == 1 {
+                Outcome::FirstBad { id }
+            } else {
+                Outcome::Next {
+                    id,
+                    remaining: number("bisect_nr"),
+                    steps: number("bisect_steps"),
+                }
+            };
+            out.push(Expectation {
+                plain_input,
+                bad,
+                good,
+                outcome,
+            });
+        }
+        Ok(out)
+    }
+}
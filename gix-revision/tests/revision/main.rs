@@ -1,3 +1,5 @@
+#[cfg(feature = "bisect")]
+mod bisect;
 #[cfg(feature = "describe")]
 mod describe;
 #[cfg(feature = "merge_base")]
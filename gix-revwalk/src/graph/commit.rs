@@ -28,10 +28,12 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
     }
 
     /// Returns the generation of the commit if it is backed by a commit graph.
+    ///
+    /// It's the corrected commit date if the commit-graph contains it, or the topological level of the commit otherwise.
     pub fn generation(&self) -> Option<Generation> {
         match &self.backing {
             Either::Left(_) => None,
-            Either::Right((cache, pos)) => cache.commit_at(*pos).generation().into(),
+            Either::Right((cache, pos)) => generation_of(cache, &cache.commit_at(*pos)).into(),
         }
     }
 
@@ -49,7 +51,7 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
             Either::Right((cache, pos)) => {
                 let commit = cache.commit_at(*pos);
                 (
-                    commit.generation().into(),
+                    generation_of(cache, &commit).into(),
                     // a cast as we cannot represent the error and trying seems overkill
                     cache.commit_at(*pos).committer_timestamp() as SecondsSinceUnixEpoch,
                 )
@@ -104,7 +106,7 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
                             actual: commit.committer_timestamp(),
                         }
                     })?,
-                    generation: Some(commit.generation()),
+                    generation: Some(generation_of(cache, &commit)),
                     data,
                 }
             }
@@ -112,6 +114,19 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
     }
 }
 
+/// Return the corrected commit date of `commit` if `cache` has them for all commits, or its topological level.
+///
+/// The choice is made for the whole `cache` as generations of both kinds can't be compared with each other.
+fn generation_of(cache: &gix_commitgraph::Graph, commit: &gix_commitgraph::file::Commit<'_>) -> Generation {
+    if cache.has_corrected_commit_dates() {
+        commit
+            .corrected_commit_date()
+            .expect("available for all commits if the graph has them")
+    } else {
+        commit.generation().into()
+    }
+}
+
 /// An iterator over the parents of a commit.
 pub struct Parents<'graph, 'cache> {
     backing: Either<
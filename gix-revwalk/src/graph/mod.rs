@@ -47,11 +47,12 @@ mod errors {
 pub use errors::{get_or_insert_default, insert_parents};
 use gix_date::SecondsSinceUnixEpoch;
 
-/// The generation away from the HEAD of graph, useful to limit algorithms by topological depth as well.
+/// The generation of a commit, useful to limit algorithms as no commit can reach another commit with a larger generation.
 ///
-/// 0 would mean the starting point of the hierarchy, and 1 their parents.
+/// It's the corrected commit date (generation number v2) if the commit-graph contains it, or the topological level
+/// (generation number v1) otherwise, where 1 is a commit without parents and each commit is one larger than its parents.
 /// This number is only available natively if there is a commit-graph.
-pub type Generation = u32;
+pub type Generation = u64;
 
 impl<T: std::fmt::Debug> std::fmt::Debug for Graph<'_, '_, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -403,7 +404,7 @@ pub struct Commit<T> {
     /// The time at which the commit was created.
     pub commit_time: SecondsSinceUnixEpoch,
     /// The generation of the commit, if available.
-    pub generation: Option<u32>,
+    pub generation: Option<Generation>,
     /// Any kind of data to associate with this commit.
     pub data: T,
 }
@@ -5,7 +5,7 @@ mod graph {
         #[test]
         fn size_of_commit() {
             let actual = std::mem::size_of::<gix_revwalk::graph::Commit<()>>();
-            let sha1 = 48;
+            let sha1 = 56;
             let sha256_extra = 16;
             let expected = sha1 + sha256_extra;
             assert!(
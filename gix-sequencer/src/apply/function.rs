@@ -0,0 +1,161 @@
+use gix_error::{ErrorExt, ResultExt, message};
+use gix_hash::{ObjectId, oid};
+use gix_merge::blob::builtin_driver::text::Labels;
+use gix_object::FindExt;
+
+use super::{Empty, Error, Options, Outcome, Stop};
+use crate::todo::Action;
+
+/// Cherry-pick or revert the commit `commit_id` as defined by `action` by applying its changes, or undoing them,
+/// on top of the commit `head`, and write a new commit with `head` as its parent.
+///
+/// The changes of `commit_id` are obtained by comparing its tree to the tree of its first parent,
+/// or the [mainline](super::Sequence::mainline) parent if it is a merge commit, or the empty tree if it has no parent.
+/// They are merged into the tree of `head` with [`gix_merge::tree()`].
+///
+/// The new commit is committed by `committer`. Cherry-picked commits keep their author, message and encoding,
+/// while reverted commits are authored by `author` and get a message that refers to the reverted commit.
+/// Extra headers, like signatures, are dropped as they would be invalid.
+///
+/// If the changes can't be applied cleanly as there are [unresolved conflicts](Options::treat_as_unresolved),
+/// or if the result would be empty, the [outcome](Outcome::Stop) indicates why no commit was written.
+///
+/// `objects` are used to read commits and trees and to write merged blobs, trees and the new commit.
+/// `diff_resource_cache` and `blob_merge` are used to merge trees, while `abbreviate_hash(id)` shortens ids for use
+/// in conflict markers.
+#[expect(clippy::too_many_arguments)]
+pub fn apply<'objects>(
+    action: Action,
+    commit_id: ObjectId,
+    head: ObjectId,
+    committer: gix_actor::SignatureRef<'_>,
+    author: gix_actor::SignatureRef<'_>,
+    objects: &'objects (impl gix_object::FindObjectOrHeader + gix_object::Write),
+    diff_resource_cache: &mut gix_diff::blob::Platform,
+    blob_merge: &mut gix_merge::blob::Platform,
+    abbreviate_hash: &mut dyn FnMut(&oid) -> String,
+    options: Options,
+) -> Result<Outcome<'objects>, Error> {
+    let mut buf = Vec::new();
+    let head_tree = objects
+        .find_commit(&head, &mut buf)
+        .or_raise(|| message!("Could not find commit {head} to apply {commit_id} to"))?
+        .tree();
+    let commit = objects
+        .find_commit(&commit_id, &mut buf)
+        .or_raise(|| message!("Could not find commit {commit_id} to {}", action.command_name()))?;
+    let commit_tree = commit.tree();
+    let parents: Vec<_> = commit.parents().collect();
+    let parent = match (parents.len(), options.sequence.mainline) {
+        (0, None) => None,
+        (1, None) => Some(parents[0]),
+        (_, Some(number)) if parents.len() > 1 => Some(
+            *number
+                .checked_sub(1)
+                .and_then(|idx| parents.get(idx))
+                .ok_or_else(|| message!("Commit {commit_id} does not have parent {number}").raise())?,
+        ),
+        (_, None) => {
+            return Err(message!("Commit {commit_id} is a merge but no mainline parent was specified").raise());
+        }
+        (_, Some(_)) => {
+            return Err(message!("A mainline parent was specified but commit {commit_id} is not a merge").raise());
+        }
+    };
+    let summary = commit.message_summary().into_owned();
+    let message = super::message(
+        action,
+        &commit_id,
+        &commit,
+        options.sequence.record_origin,
+        options.sequence.mainline,
+    );
+    let (commit_author, encoding) = match action {
+        Action::Pick => (
+            commit
+                .author()
+                .or_raise(|| message!("Could not decode author of {commit_id}"))?
+                .into(),
+            commit.encoding.map(bstr::BString::from),
+        ),
+        Action::Revert => (author.into(), None),
+    };
+
+    let parent_tree = match parent {
+        Some(parent) => objects
+            .find_commit(&parent, &mut Vec::new())
+            .or_raise(|| message!("Could not find parent {parent} of commit {commit_id}"))?
+            .tree(),
+        None => ObjectId::empty_tree(commit_id.kind()),
+    };
+    let is_empty = parent_tree == commit_tree;
+    if is_empty && !options.sequence.allow_empty {
+        return Ok(Outcome::Stop(Stop::Empty));
+    }
+
+    let commit_label = format!("{} ({summary})", abbreviate_hash(&commit_id));
+    let parent_label = format!("parent of {commit_label}");
+    let (base_tree, their_tree, labels) = match action {
+        Action::Pick => (
+            parent_tree,
+            commit_tree,
+            Labels {
+                ancestor: Some(parent_label.as_str().into()),
+                current: Some("HEAD".into()),
+                other: Some(commit_label.as_str().into()),
+            },
+        ),
+        Action::Revert => (
+            commit_tree,
+            parent_tree,
+            Labels {
+                ancestor: Some(commit_label.as_str().into()),
+                current: Some("HEAD".into()),
+                other: Some(parent_label.as_str().into()),
+            },
+        ),
+    };
+    let mut tree_merge = gix_merge::tree(
+        &base_tree,
+        &head_tree,
+        &their_tree,
+        labels,
+        objects,
+        |buf| objects.write_buf(gix_object::Kind::Blob, buf),
+        &mut gix_diff::tree::State::default(),
+        diff_resource_cache,
+        blob_merge,
+        options.tree_merge,
+    )
+    .or_raise(|| message!("Could not merge the changes of commit {commit_id} into {head}"))?;
+    if tree_merge.has_unresolved_conflicts(options.treat_as_unresolved) {
+        return Ok(Outcome::Stop(Stop::Conflict(tree_merge)));
+    }
+
+    let tree = tree_merge
+        .tree
+        .write(|tree| objects.write(tree))
+        .map_err(std::io::Error::other)
+        .or_raise(|| message!("Could not write the merged tree of commit {commit_id}"))?;
+    if tree == head_tree && !is_empty {
+        match options.sequence.empty {
+            Empty::Stop => return Ok(Outcome::Stop(Stop::Empty)),
+            Empty::Drop => return Ok(Outcome::Dropped),
+            Empty::Keep => {}
+        }
+    }
+
+    let id = objects
+        .write(&gix_object::Commit {
+            tree,
+            parents: [head].into(),
+            author: commit_author,
+            committer: committer.into(),
+            encoding,
+            message,
+            extra_headers: Vec::new(),
+        })
+        .map_err(std::io::Error::other)
+        .or_raise(|| message!("Could not write the new commit for {commit_id}"))?;
+    Ok(Outcome::Commit(id))
+}
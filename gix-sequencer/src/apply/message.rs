@@ -0,0 +1,40 @@
+use bstr::{BString, ByteSlice, ByteVec};
+use gix_hash::oid;
+
+use crate::todo::Action;
+
+/// Produce the message of the commit that performs `action` on the commit with `id` and the decoded `commit`,
+/// as `git cherry-pick` or `git revert` would.
+///
+/// If `record_origin` is `true`, a cherry-picked commit mentions where it was picked from.
+/// Reverts of merge commits mention the `mainline` parent, if set.
+pub fn message(
+    action: Action,
+    id: &oid,
+    commit: &gix_object::CommitRef<'_>,
+    record_origin: bool,
+    mainline: Option<usize>,
+) -> BString {
+    match action {
+        Action::Pick => {
+            let mut out = BString::from(commit.message);
+            if record_origin {
+                if !out.ends_with(b"\n") {
+                    out.push(b'\n');
+                }
+                out.push_str(format!("\n(cherry picked from commit {id})\n"));
+            }
+            out
+        }
+        Action::Revert => {
+            let mut out = BString::from("Revert \"");
+            out.push_str(commit.message_summary().trim());
+            out.push_str(format!("\"\n\nThis reverts commit {id}"));
+            match mainline.and_then(|number| commit.parents().nth(number.checked_sub(1)?)) {
+                Some(parent) => out.push_str(format!(", reversing\nchanges made to {parent}.\n")),
+                None => out.push_str(".\n"),
+            }
+            out
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use gix_hash::ObjectId;
+
+/// The error returned by [`apply()`](crate::apply()).
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// Define what to do with commits that become empty as their changes are already present.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Empty {
+    /// Stop so the caller can decide, just like `git cherry-pick` does by default.
+    #[default]
+    Stop,
+    /// Skip commits that become empty, just like `git cherry-pick --empty=drop` does.
+    Drop,
+    /// Create empty commits, just like `git cherry-pick --keep-redundant-commits` does.
+    Keep,
+}
+
+/// Options that affect how each commit of a sequence is applied, which are stored along with the [state](crate::State).
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct Sequence {
+    /// If `true`, append a line like `(cherry picked from commit <id>)` to the message of each cherry-picked commit,
+    /// just like `git cherry-pick -x` does.
+    pub record_origin: bool,
+    /// The number of the parent, starting at 1, whose changes to apply if the commit to apply is a merge commit,
+    /// just like `git cherry-pick --mainline <parent>` does.
+    ///
+    /// If `None`, merge commits can't be applied.
+    pub mainline: Option<usize>,
+    /// If `true`, commits that don't change anything to begin with are applied as empty commits.
+    /// Otherwise, the operation stops at them, just like `git cherry-pick` without `--allow-empty` would.
+    pub allow_empty: bool,
+    /// What to do with commits that become empty.
+    pub empty: Empty,
+}
+
+/// A way to configure [`apply()`](crate::apply()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// Options to define how the changes of the commit should be merged into the tree of the commit to apply them to.
+    pub tree_merge: gix_merge::tree::Options,
+    /// Determine which conflicts are considered unresolved, which stops the operation.
+    pub treat_as_unresolved: gix_merge::tree::TreatAsUnresolved,
+    /// Options that are shared across all commits of a sequence.
+    pub sequence: Sequence,
+}
+
+/// The reason for the operation to stop, so it requires the caller to decide how to proceed.
+#[derive(Clone)]
+pub enum Stop<'a> {
+    /// The changes of the commit conflicted with the commit they were applied to, and the tree merge result
+    /// has at least one unresolved conflict.
+    Conflict(gix_merge::tree::Outcome<'a>),
+    /// The commit doesn't change anything, either to begin with or after its changes were applied.
+    Empty,
+}
+
+/// The result of [`apply()`](crate::apply()).
+#[derive(Clone)]
+pub enum Outcome<'a> {
+    /// The changes were applied cleanly and a new commit with the given id was written.
+    Commit(ObjectId),
+    /// The commit became empty and was dropped as configured in [`Sequence::empty`].
+    Dropped,
+    /// The commit couldn't be applied.
+    Stop(Stop<'a>),
+}
+
+pub(super) mod function;
+
+mod message;
+pub use message::message;
@@ -1 +1,23 @@
+//! Cherry-pick and revert sequences of commits, entirely in memory, and keep track of them in a way that is compatible with
+//! [`git cherry-pick`](https://git-scm.com/docs/git-cherry-pick) and [`git revert`](https://git-scm.com/docs/git-revert).
+//!
+//! * [apply()] cherry-picks or reverts a single commit on top of another one by merging trees with [`gix_merge::tree()`].
+//! * [`todo`] reads and writes the list of commits that remain to be processed.
+//! * [`State`] reads and writes the state of a sequence that stopped, as stored in `.git/sequencer/`,
+//!   so that `git cherry-pick --continue` or `git revert --continue` can pick it up.
+//!
+//! Updating references, writing state references like `CHERRY_PICK_HEAD` and touching the index or worktree
+//! is left to the caller.
+#![deny(missing_docs)]
 #![forbid(unsafe_code)]
+
+///
+pub mod apply;
+pub use apply::function::apply;
+
+///
+pub mod state;
+pub use state::State;
+
+///
+pub mod todo;
@@ -0,0 +1,168 @@
+use std::path::Path;
+
+use gix_error::{ErrorExt, OptionExt, ResultExt, message};
+use gix_hash::ObjectId;
+
+use crate::{
+    apply::{Empty, Sequence},
+    todo,
+};
+
+/// The error returned by [`State::from_dir()`] and [`State::write_to()`].
+pub type Error = gix_error::Exn<gix_error::Message>;
+
+/// The name of the directory inside of the git directory that holds the state of a sequence, like `.git/sequencer`.
+pub const DIR_NAME: &str = "sequencer";
+
+/// The state of a sequence of cherry-picks or reverts in progress, as stored by Git in [`.git/sequencer`](DIR_NAME).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct State {
+    /// The commit `HEAD` pointed to before the sequence started, which is restored when aborting.
+    pub head: ObjectId,
+    /// The commit `HEAD` pointed to after the last commit was applied, or `None` if unknown.
+    /// If `HEAD` changed since then, aborting would lose commits.
+    pub abort_safety: Option<ObjectId>,
+    /// The commits that remain to be processed, starting with the one the sequence stopped at.
+    pub todo: Vec<todo::Line>,
+    /// Options that affect how each commit is applied.
+    pub options: Sequence,
+}
+
+impl State {
+    /// Read the state of a sequence from `dir`, typically `.git/sequencer`, and use `object_hash` to parse object ids.
+    /// `expand_prefix(prefix)` is used to obtain full object ids from the abbreviated ones Git writes into the todo list.
+    ///
+    /// Return `None` if `dir` doesn't exist, which indicates that there is no sequence in progress.
+    /// Options that aren't supported are ignored.
+    pub fn from_dir(
+        dir: &Path,
+        object_hash: gix_hash::Kind,
+        expand_prefix: &mut dyn FnMut(gix_hash::Prefix) -> Option<ObjectId>,
+    ) -> Result<Option<Self>, Error> {
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+        let read = |name: &str| -> Result<Option<Vec<u8>>, Error> {
+            let path = dir.join(name);
+            match std::fs::read(&path) {
+                Ok(buf) => Ok(Some(buf)),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err).or_raise(|| message!("Could not read '{}'", path.display())),
+            }
+        };
+        let parse_id = |name: &str, hex: &[u8]| -> Result<ObjectId, Error> {
+            let id = ObjectId::from_hex(hex.trim_ascii())
+                .or_raise(|| message!("The file '{name}' didn't contain an object id"))?;
+            if id.kind() != object_hash {
+                return Err(message!("Expected object ids of kind {object_hash}, got {}", id.kind()).raise());
+            }
+            Ok(id)
+        };
+
+        let head = read("head")?.ok_or_raise(|| message!("The file 'head' is missing"))?;
+        let abort_safety = read("abort-safety")?.filter(|hex| !hex.trim_ascii().is_empty());
+        Ok(Some(State {
+            head: parse_id("head", &head)?,
+            abort_safety: abort_safety.map(|hex| parse_id("abort-safety", &hex)).transpose()?,
+            todo: todo::parse(&read("todo")?.unwrap_or_default(), object_hash, expand_prefix)
+                .or_raise(|| message!("Could not parse the todo list"))?,
+            options: match read("opts")? {
+                Some(buf) => parse_options(&buf).or_raise(|| message!("Could not parse 'opts'"))?,
+                None => Sequence::default(),
+            },
+        }))
+    }
+
+    /// Write this state into `dir`, typically `.git/sequencer`, which is created if needed, in the layout
+    /// that Git uses so it can continue the sequence.
+    pub fn write_to(&self, dir: &Path) -> Result<(), Error> {
+        std::fs::create_dir_all(dir).or_raise(|| message!("Could not create directory '{}'", dir.display()))?;
+        let write = |name: &str, content: &[u8]| -> Result<(), Error> {
+            let path = dir.join(name);
+            std::fs::write(&path, content).or_raise(|| message!("Could not write '{}'", path.display()))
+        };
+
+        write("head", format!("{}\n", self.head).as_bytes())?;
+        write(
+            "abort-safety",
+            self.abort_safety
+                .map(|id| format!("{id}\n"))
+                .unwrap_or_default()
+                .as_bytes(),
+        )?;
+        let mut buf = Vec::new();
+        for line in &self.todo {
+            line.write_to(&mut buf).expect("writing to a vector never fails");
+        }
+        write("todo", &buf)?;
+
+        let path = dir.join("opts");
+        if self.options == Sequence::default() {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err).or_raise(|| message!("Could not remove '{}'", path.display())),
+            }
+        } else {
+            write("opts", &options_to_bytes(&self.options))?;
+        }
+        Ok(())
+    }
+
+    /// Return the action of the sequence, based on the first commit in the todo list, or `None` if it's empty.
+    pub fn action(&self) -> Option<todo::Action> {
+        self.todo.first().map(|line| line.action)
+    }
+}
+
+fn parse_options(buf: &[u8]) -> Result<Sequence, gix_error::Exn<gix_error::Message>> {
+    let file = gix_config::File::from_bytes_no_includes(buf, gix_config::file::Metadata::api(), Default::default())
+        .or_raise(|| message!("Invalid configuration file"))?;
+    let boolean = |name: &str| -> Result<bool, Error> {
+        Ok(file
+            .boolean_by("options", None, name)
+            .or_raise(|| message!("Invalid boolean value for 'options.{name}'"))?
+            .unwrap_or_default())
+    };
+    let mainline = file
+        .integer_by("options", None, "mainline")
+        .or_raise(|| message!("Invalid integer value for 'options.mainline'"))?
+        .filter(|number| *number > 0)
+        .map(|number| usize::try_from(number).or_raise(|| message!("'options.mainline' is too large")))
+        .transpose()?;
+    Ok(Sequence {
+        record_origin: boolean("record-origin")?,
+        mainline,
+        allow_empty: boolean("allow-empty")?,
+        empty: if boolean("keep-redundant-commits")? {
+            Empty::Keep
+        } else if boolean("drop-redundant-commits")? {
+            Empty::Drop
+        } else {
+            Empty::Stop
+        },
+    })
+}
+
+fn options_to_bytes(options: &Sequence) -> Vec<u8> {
+    let mut file = gix_config::File::new(gix_config::file::Metadata::api());
+    let mut section = file.new_section("options", None).expect("valid section name");
+    let mut push = |name: &str, value: &str| {
+        section.push(name, Some(value.into())).expect("valid value name");
+    };
+    if options.allow_empty {
+        push("allow-empty", "true");
+    }
+    match options.empty {
+        Empty::Stop => {}
+        Empty::Drop => push("drop-redundant-commits", "true"),
+        Empty::Keep => push("keep-redundant-commits", "true"),
+    }
+    if options.record_origin {
+        push("record-origin", "true");
+    }
+    if let Some(mainline) = options.mainline {
+        push("mainline", &mainline.to_string());
+    }
+    file.to_bstring().into()
+}
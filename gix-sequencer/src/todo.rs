@@ -0,0 +1,132 @@
+//! Read and write the todo list of a sequence, as stored in `.git/sequencer/todo`.
+use bstr::{BStr, BString, ByteSlice};
+use gix_error::{ErrorExt, OptionExt, ResultExt, ValidationError};
+use gix_hash::ObjectId;
+
+/// The error returned by [`parse()`].
+pub type Error = gix_error::Exn<ValidationError>;
+
+/// What to do with a commit in a todo list.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Action {
+    /// Apply the changes of the commit, like `git cherry-pick` does.
+    Pick,
+    /// Undo the changes of the commit, like `git revert` does.
+    Revert,
+}
+
+impl Action {
+    /// Return the name of the action as Git writes it.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Pick => "pick",
+            Action::Revert => "revert",
+        }
+    }
+
+    /// Parse the long or short name of an action, like `pick` or `p`.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"pick" | b"p" => Action::Pick,
+            b"revert" => Action::Revert,
+            _ => return None,
+        })
+    }
+
+    /// Return the name of the reference that points to the commit a sequence of this action stopped at,
+    /// like `CHERRY_PICK_HEAD`.
+    pub fn head_name(&self) -> &'static str {
+        match self {
+            Action::Pick => "CHERRY_PICK_HEAD",
+            Action::Revert => "REVERT_HEAD",
+        }
+    }
+
+    /// Return the name of the command that performs this action, like `cherry-pick`, for use in messages.
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            Action::Pick => "cherry-pick",
+            Action::Revert => "revert",
+        }
+    }
+}
+
+/// A line in a todo list, like `pick <id> <subject>`.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Line {
+    /// What to do with the commit.
+    pub action: Action,
+    /// The id of the commit to process.
+    pub id: ObjectId,
+    /// The subject of the commit, which is only informational.
+    pub subject: BString,
+}
+
+impl Line {
+    /// Serialize this instance as a single line, including the trailing newline, into `out`.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write!(out, "{} {}", self.action.as_str(), self.id)?;
+        if !self.subject.is_empty() {
+            out.write_all(b" ")?;
+            out.write_all(&self.subject)?;
+        }
+        out.write_all(b"\n")
+    }
+}
+
+/// Parse all lines in `buf`, skipping empty lines and comments, with object ids of kind `object_hash`.
+///
+/// As Git abbreviates object ids, `expand_prefix(prefix)` is called to obtain the full id for each of them,
+/// returning `None` if it can't be found or if it's ambiguous.
+pub fn parse(
+    buf: &[u8],
+    object_hash: gix_hash::Kind,
+    expand_prefix: &mut dyn FnMut(gix_hash::Prefix) -> Option<ObjectId>,
+) -> Result<Vec<Line>, Error> {
+    buf.lines()
+        .map(ByteSlice::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with(b"#"))
+        .map(|(idx, line)| parse_line(line.as_bstr(), idx + 1, object_hash, expand_prefix))
+        .collect()
+}
+
+fn parse_line(
+    line: &BStr,
+    line_number: usize,
+    object_hash: gix_hash::Kind,
+    expand_prefix: &mut dyn FnMut(gix_hash::Prefix) -> Option<ObjectId>,
+) -> Result<Line, Error> {
+    let mut tokens = line.splitn_str(3, " ");
+    let action = tokens
+        .next()
+        .and_then(Action::from_bytes)
+        .ok_or_else(|| ValidationError::new_with_input(format!("{line_number}: Unsupported command"), line).raise())?;
+    let hex = tokens.next().unwrap_or_default();
+    let id = if hex.len() == object_hash.len_in_hex() {
+        ObjectId::from_hex(hex)
+            .or_raise(|| ValidationError::new_with_input(format!("{line_number}: Invalid object id"), line))?
+    } else {
+        // Pad the prefix to a full id so that it has the right kind.
+        let prefix = (hex.len() < object_hash.len_in_hex())
+            .then(|| {
+                let mut padded = hex.to_owned();
+                padded.resize(object_hash.len_in_hex(), b'0');
+                ObjectId::from_hex(&padded).ok()
+            })
+            .flatten()
+            .and_then(|id| gix_hash::Prefix::new(&id, hex.len()).ok())
+            .ok_or_raise(|| ValidationError::new_with_input(format!("{line_number}: Invalid object id"), line))?;
+        expand_prefix(prefix).ok_or_raise(|| {
+            ValidationError::new_with_input(
+                format!("{line_number}: Could not find unique object for {prefix}"),
+                line,
+            )
+        })?
+    };
+    Ok(Line {
+        action,
+        id,
+        subject: tokens.next().unwrap_or_default().into(),
+    })
+}
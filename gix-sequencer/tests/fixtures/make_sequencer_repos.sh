@@ -0,0 +1,51 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+cd repo
+git checkout -q -b main
+seq 1 10 > numbers
+echo a > letters
+git add numbers letters
+git commit -q -m base
+git tag base
+
+git checkout -q -b topic
+echo b >> letters
+git commit -q -am "add b"
+sed -i.bak 's/^5$/five/' numbers && rm numbers.bak
+git commit -q -am "change five"
+echo new > new
+git add new
+git commit -q -m "add new file"
+
+git checkout -q -b merge base
+git merge -q --no-ff -m "merge add b" topic~2
+
+git checkout -q main
+sed -i.bak 's/^5$/FIVE/' numbers && rm numbers.bak
+git commit -q -am "change five differently"
+
+# Clean cherry-picks and reverts for comparison.
+git checkout -q -b picked-by-git main
+git cherry-pick -x topic~2 >/dev/null
+git checkout -q -b picked-merge-by-git main
+git cherry-pick -m 1 merge >/dev/null
+git checkout -q -b reverted-by-git topic
+git revert --no-edit topic~1 >/dev/null
+
+# A cherry-pick sequence that stops at its second commit.
+git checkout -q -b cherry-pick-stopped main
+git cherry-pick -x base..topic >/dev/null 2>&1 || true
+cp -R .git/sequencer ../cherry-pick-sequencer
+cp .git/CHERRY_PICK_HEAD .git/MERGE_MSG ../cherry-pick-sequencer/
+git cherry-pick --abort
+
+# A revert sequence that stops at its first commit.
+git checkout -q -b revert-stopped main
+git revert --no-edit topic~1 topic~2 >/dev/null 2>&1 || true
+cp -R .git/sequencer ../revert-sequencer
+cp .git/REVERT_HEAD .git/MERGE_MSG ../revert-sequencer/
+git revert --abort
+
+git checkout -q main
@@ -0,0 +1,191 @@
+use std::path::Path;
+
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_sequencer::{
+    apply::{Empty, Options, Outcome, Sequence, Stop},
+    todo::Action,
+};
+
+use crate::{
+    fixture, id,
+    utils::{new_blob_merge_platform, new_diff_resource_cache, odb},
+};
+
+type Odb = gix_odb::memory::Proxy<gix_odb::Handle>;
+
+#[test]
+fn pick_and_revert_like_git() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let main = id(&root, "refs/heads/main")?;
+    let topic = id(&root, "refs/heads/topic")?;
+    let change_five = parent(&odb, topic)?;
+    let add_b = parent(&odb, change_five)?;
+
+    let sequence = Sequence {
+        record_origin: true,
+        ..Default::default()
+    };
+    let out = apply(&root, &odb, Action::Pick, add_b, main, sequence)?;
+    assert_eq!(
+        commit(&out),
+        Some(id(&root, "refs/heads/picked-by-git")?),
+        "author, committer and message are the same, so are the commits"
+    );
+
+    let out = apply(&root, &odb, Action::Revert, change_five, topic, Sequence::default())?;
+    assert_eq!(commit(&out), Some(id(&root, "refs/heads/reverted-by-git")?));
+    Ok(())
+}
+
+#[test]
+fn merge_commits_need_a_mainline() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let main = id(&root, "refs/heads/main")?;
+    let merge = id(&root, "refs/heads/merge")?;
+
+    let mainline = |number| Sequence {
+        mainline: Some(number),
+        ..Default::default()
+    };
+    let out = apply(&root, &odb, Action::Pick, merge, main, mainline(1))?;
+    assert_eq!(commit(&out), Some(id(&root, "refs/heads/picked-merge-by-git")?));
+
+    let err = apply(&root, &odb, Action::Pick, merge, main, Sequence::default())
+        .err()
+        .expect("no mainline");
+    assert!(
+        err.to_string()
+            .contains("is a merge but no mainline parent was specified")
+    );
+    let err = apply(&root, &odb, Action::Pick, merge, main, mainline(3))
+        .err()
+        .expect("no such parent");
+    assert!(err.to_string().contains("does not have parent 3"));
+    let err = apply(&root, &odb, Action::Pick, main, main, mainline(1))
+        .err()
+        .expect("not a merge");
+    assert!(err.to_string().contains("is not a merge"));
+    Ok(())
+}
+
+#[test]
+fn stop_at_conflicts() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let main = id(&root, "refs/heads/main")?;
+    let change_five = parent(&odb, id(&root, "refs/heads/topic")?)?;
+
+    for action in [Action::Pick, Action::Revert] {
+        let out = apply(&root, &odb, action, change_five, main, Sequence::default())?;
+        let Outcome::Stop(Stop::Conflict(tree_merge)) = out else {
+            panic!("{action:?}: expected a conflict");
+        };
+        assert_eq!(tree_merge.conflicts.len(), 1);
+        assert_eq!(tree_merge.conflicts[0].ours.location(), "numbers");
+    }
+    Ok(())
+}
+
+#[test]
+fn commits_that_become_empty() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let picked = id(&root, "refs/heads/picked-by-git")?;
+    let add_b = parent(&odb, parent(&odb, id(&root, "refs/heads/topic")?)?)?;
+
+    let empty = |empty| Sequence {
+        empty,
+        ..Default::default()
+    };
+    let out = apply(&root, &odb, Action::Pick, add_b, picked, empty(Empty::Stop))?;
+    assert!(
+        matches!(out, Outcome::Stop(Stop::Empty)),
+        "the change is already present"
+    );
+    let out = apply(&root, &odb, Action::Pick, add_b, picked, empty(Empty::Drop))?;
+    assert!(matches!(out, Outcome::Dropped));
+    let out = apply(&root, &odb, Action::Pick, add_b, picked, empty(Empty::Keep))?;
+    let kept = commit(&out).expect("an empty commit was created");
+    assert_eq!(tree(&odb, kept)?, tree(&odb, picked)?);
+    assert_eq!(parent(&odb, kept)?, picked);
+    Ok(())
+}
+
+#[test]
+fn messages_like_git() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let change_five = parent(&odb, id(&root, "refs/heads/topic")?)?;
+    let mut buf = Vec::new();
+    let commit = odb.find_commit(&change_five, &mut buf)?;
+
+    for (action, dir, record_origin) in [
+        (Action::Pick, "cherry-pick-sequencer", true),
+        (Action::Revert, "revert-sequencer", false),
+    ] {
+        let expected = std::fs::read(root.join(dir).join("MERGE_MSG"))?;
+        let message = gix_sequencer::apply::message(action, &change_five, &commit, record_origin, None);
+        assert_eq!(
+            format!("{message}\n# Conflicts:\n#\tnumbers\n"),
+            String::from_utf8(expected)?,
+            "{action:?}"
+        );
+    }
+    Ok(())
+}
+
+fn apply<'a>(
+    root: &Path,
+    odb: &'a Odb,
+    action: Action,
+    commit: ObjectId,
+    head: ObjectId,
+    sequence: Sequence,
+) -> std::result::Result<Outcome<'a>, gix_error::Error> {
+    let committer = gix_actor::SignatureRef {
+        name: "committer".into(),
+        email: "committer@example.com".into(),
+        time: "946771200 +0000",
+    };
+    let author = gix_actor::SignatureRef {
+        name: "author".into(),
+        email: "author@example.com".into(),
+        time: "946684800 +0000",
+    };
+    gix_sequencer::apply(
+        action,
+        commit,
+        head,
+        committer,
+        author,
+        odb,
+        &mut new_diff_resource_cache(root),
+        &mut new_blob_merge_platform(root, None),
+        &mut |id| id.to_hex_with_len(7).to_string(),
+        Options {
+            sequence,
+            ..Default::default()
+        },
+    )
+    .map_err(gix_error::Exn::into_error)
+}
+
+fn commit(out: &Outcome<'_>) -> Option<ObjectId> {
+    match out {
+        Outcome::Commit(id) => Some(*id),
+        _ => None,
+    }
+}
+
+pub(crate) fn parent(odb: &impl gix_object::Find, id: ObjectId) -> crate::Result<ObjectId> {
+    let mut buf = Vec::new();
+    Ok(odb.find_commit(&id, &mut buf)?.parents().next().expect("has parent"))
+}
+
+fn tree(odb: &impl gix_object::Find, id: ObjectId) -> crate::Result<ObjectId> {
+    let mut buf = Vec::new();
+    Ok(odb.find_commit(&id, &mut buf)?.tree())
+}
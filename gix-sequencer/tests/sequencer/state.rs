@@ -0,0 +1,107 @@
+use gix_sequencer::{
+    State,
+    apply::{Empty, Sequence},
+    state::DIR_NAME,
+    todo::Action,
+};
+
+use crate::{
+    fixture, id,
+    utils::{expand_prefix, odb},
+};
+
+#[test]
+fn read_state_written_by_git() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let main = id(&root, "refs/heads/main")?;
+
+    let state = State::from_dir(
+        &root.join("cherry-pick-sequencer"),
+        gix_testtools::object_hash(),
+        &mut expand_prefix(&odb),
+    )
+    .map_err(gix_error::Exn::into_error)?
+    .expect("present");
+    assert_eq!(state.head, main);
+    assert_ne!(state.abort_safety, Some(main), "one commit was picked already");
+    assert_eq!(state.action(), Some(Action::Pick));
+    assert_eq!(state.todo.len(), 2);
+    assert_eq!(
+        state.options,
+        Sequence {
+            record_origin: true,
+            ..Default::default()
+        }
+    );
+
+    let state = State::from_dir(
+        &root.join("revert-sequencer"),
+        gix_testtools::object_hash(),
+        &mut expand_prefix(&odb),
+    )
+    .map_err(gix_error::Exn::into_error)?
+    .expect("present");
+    assert_eq!(state.head, main);
+    assert_eq!(state.abort_safety, Some(main), "nothing was reverted yet");
+    assert_eq!(state.action(), Some(Action::Revert));
+    assert_eq!(state.options, Sequence::default(), "unknown options are ignored");
+    Ok(())
+}
+
+#[test]
+fn write_produces_files_like_git() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let git_dir = root.join("cherry-pick-sequencer");
+    let state = State::from_dir(&git_dir, gix_testtools::object_hash(), &mut expand_prefix(&odb))
+        .map_err(gix_error::Exn::into_error)?
+        .expect("present");
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let dir = tmp.path().join(DIR_NAME);
+    state.write_to(&dir).map_err(gix_error::Exn::into_error)?;
+    for name in ["head", "abort-safety", "opts"] {
+        assert_eq!(
+            std::fs::read(dir.join(name))?,
+            std::fs::read(git_dir.join(name))?,
+            "{name} should match what Git wrote"
+        );
+    }
+    let read = |dir: &std::path::Path| {
+        State::from_dir(dir, gix_testtools::object_hash(), &mut |_| None).map_err(gix_error::Exn::into_error)
+    };
+    assert_eq!(read(&dir)?.as_ref(), Some(&state), "round-trips with full ids");
+
+    let mut state = state;
+    state.options = Sequence {
+        record_origin: false,
+        mainline: Some(2),
+        allow_empty: true,
+        empty: Empty::Keep,
+    };
+    state.abort_safety = None;
+    state.write_to(&dir).map_err(gix_error::Exn::into_error)?;
+    assert_eq!(
+        std::fs::read(dir.join("opts"))?,
+        b"[options]\n\tallow-empty = true\n\tkeep-redundant-commits = true\n\tmainline = 2\n"
+    );
+    assert_eq!(read(&dir)?.as_ref(), Some(&state));
+
+    state.options = Sequence::default();
+    state.write_to(&dir).map_err(gix_error::Exn::into_error)?;
+    assert!(!dir.join("opts").exists(), "default options aren't written");
+    assert_eq!(read(&dir)?.as_ref(), Some(&state));
+    Ok(())
+}
+
+#[test]
+fn missing_directory_means_no_sequence_in_progress() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    assert_eq!(
+        State::from_dir(&tmp.path().join(DIR_NAME), gix_testtools::object_hash(), &mut |_| None)
+            .map_err(gix_error::Exn::into_error)?,
+        None
+    );
+    Ok(())
+}
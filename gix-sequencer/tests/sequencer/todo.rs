@@ -0,0 +1,68 @@
+use gix_sequencer::todo::{Action, Line, parse};
+
+use crate::{
+    fixture, id,
+    utils::{expand_prefix, odb},
+};
+
+#[test]
+fn parse_abbreviated_ids_written_by_git() -> crate::Result {
+    let root = fixture()?;
+    let odb = odb(&root)?;
+    let buf = std::fs::read(root.join("cherry-pick-sequencer/todo"))?;
+    let lines =
+        parse(&buf, gix_testtools::object_hash(), &mut expand_prefix(&odb)).map_err(gix_error::Exn::into_error)?;
+    let topic = id(&root, "refs/heads/topic")?;
+    assert_eq!(
+        lines,
+        [
+            Line {
+                action: Action::Pick,
+                id: crate::apply::parent(&odb, topic)?,
+                subject: "change five".into()
+            },
+            Line {
+                action: Action::Pick,
+                id: topic,
+                subject: "add new file".into()
+            }
+        ]
+    );
+
+    let buf = std::fs::read(root.join("revert-sequencer/todo"))?;
+    let lines =
+        parse(&buf, gix_testtools::object_hash(), &mut expand_prefix(&odb)).map_err(gix_error::Exn::into_error)?;
+    assert!(lines.iter().all(|line| line.action == Action::Revert));
+    assert_eq!(lines.len(), 2);
+
+    let mut buf = Vec::new();
+    lines[0].write_to(&mut buf)?;
+    assert_eq!(
+        buf,
+        format!("revert {} change five\n", lines[0].id).as_bytes(),
+        "ids are written in full"
+    );
+    Ok(())
+}
+
+#[test]
+fn unsupported_lines() {
+    let id = gix_testtools::object_hash().null();
+    for input in [
+        "squash {id}".to_string(),
+        "pick".into(),
+        "pick xyz".into(),
+        "pick abcd".into(),
+    ] {
+        let err = parse(
+            input.replace("{id}", &id.to_string()).as_bytes(),
+            id.kind(),
+            &mut |_| None,
+        )
+        .expect_err("invalid, unsupported or not found");
+        assert!(
+            err.to_string().starts_with("1: "),
+            "{input}: the line number is included"
+        );
+    }
+}
@@ -92,6 +92,7 @@ impl Curl {
         base_url: &str,
         headers: impl IntoIterator<Item = impl AsRef<str>>,
         upload_body_kind: Option<PostBodyDataKind>,
+        put_body_size: Option<u64>,
     ) -> Result<http::PostResponse<io::pipe::Reader, io::pipe::Reader, io::pipe::Writer>, http::Error> {
         let mut list = curl::easy::List::new();
         for header in headers {
@@ -104,6 +105,7 @@ impl Curl {
                 base_url: base_url.to_owned(),
                 headers: list,
                 upload_body_kind,
+                put_body_size,
                 config: self.config.clone(),
             })
             .is_err()
@@ -150,7 +152,7 @@ impl http::Http for Curl {
         base_url: &str,
         headers: impl IntoIterator<Item = impl AsRef<str>>,
     ) -> Result<http::GetResponse<Self::Headers, Self::ResponseBody>, http::Error> {
-        self.make_request(url, base_url, headers, None).map(Into::into)
+        self.make_request(url, base_url, headers, None, None).map(Into::into)
     }
 
     fn post(
@@ -160,7 +162,23 @@ impl http::Http for Curl {
         headers: impl IntoIterator<Item = impl AsRef<str>>,
         body: PostBodyDataKind,
     ) -> Result<http::PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
-        self.make_request(url, base_url, headers, Some(body))
+        self.make_request(url, base_url, headers, Some(body), None)
+    }
+
+    fn put(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        body_size: u64,
+    ) -> Result<http::PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
+        self.make_request(
+            url,
+            base_url,
+            headers,
+            Some(PostBodyDataKind::Unbounded),
+            Some(body_size),
+        )
     }
 
     fn configure(
@@ -298,6 +298,8 @@ pub struct Request {
     pub base_url: String,
     pub headers: curl::easy::List,
     pub upload_body_kind: Option<PostBodyDataKind>,
+    /// If set, the upload body is sent with `PUT` instead of `POST`, with the given size.
+    pub put_body_size: Option<u64>,
     pub config: http::Options,
 }
 
@@ -332,6 +334,7 @@ pub fn new() -> Worker {
             base_url,
             mut headers,
             upload_body_kind,
+            put_body_size,
             config:
                 http::Options {
                     extra_headers,
@@ -357,7 +360,17 @@ pub fn new() -> Worker {
             let effective_url = redirect::swap_tails(redirected_base_url.as_deref(), &base_url, url.clone());
             handle.url(&effective_url)?;
 
-            handle.post(upload_body_kind.is_some())?;
+            match put_body_size {
+                Some(size) => {
+                    handle.upload(true)?;
+                    handle.in_filesize(size)?;
+                }
+                None => {
+                    // This also resets the method in case the previous request was a `PUT`.
+                    handle.upload(false)?;
+                    handle.post(upload_body_kind.is_some())?;
+                }
+            }
             let has_extra_headers = !extra_headers.is_empty();
             for header in extra_headers {
                 headers.append(&header)?;
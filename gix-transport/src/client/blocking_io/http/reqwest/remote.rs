@@ -112,13 +112,16 @@ impl Default for Remote {
                 base_url,
                 headers,
                 upload_body_kind,
+                put_body_size,
                 config,
             } in req_recv
             {
                 let redirected_base_url = redirected_base_url_shared.lock().clone();
                 let effective_url = redirect::swap_tails(redirected_base_url.as_deref(), &base_url, url.clone());
                 let has_configured_extra_headers = !config.extra_headers.is_empty();
-                let mut req_builder = if upload_body_kind.is_some() {
+                let mut req_builder = if put_body_size.is_some() {
+                    client.put(&effective_url)
+                } else if upload_body_kind.is_some() {
                     client.post(&effective_url)
                 } else {
                     client.get(&effective_url)
@@ -139,14 +142,17 @@ impl Default for Remote {
                     // Shut down as something is off.
                     break;
                 }
-                req_builder = match upload_body_kind {
-                    Some(PostBodyDataKind::BoundedAndFitsIntoMemory) => {
+                req_builder = match (put_body_size, upload_body_kind) {
+                    (Some(size), _) => req_builder.body(reqwest::blocking::Body::sized(post_body_rx, size)),
+                    (None, Some(PostBodyDataKind::BoundedAndFitsIntoMemory)) => {
                         let mut buf = Vec::<u8>::with_capacity(512);
                         post_body_rx.read_to_end(&mut buf)?;
                         req_builder.body(buf)
                     }
-                    Some(PostBodyDataKind::Unbounded) => req_builder.body(reqwest::blocking::Body::new(post_body_rx)),
-                    None => req_builder,
+                    (None, Some(PostBodyDataKind::Unbounded)) => {
+                        req_builder.body(reqwest::blocking::Body::new(post_body_rx))
+                    }
+                    (None, None) => req_builder,
                 };
                 let mut req = req_builder.build()?;
                 let mut has_configure_request = false;
@@ -273,6 +279,7 @@ impl Remote {
         base_url: &str,
         headers: impl IntoIterator<Item = impl AsRef<str>>,
         upload_body_kind: Option<PostBodyDataKind>,
+        put_body_size: Option<u64>,
     ) -> Result<http::PostResponse<pipe::Reader, pipe::Reader, pipe::Writer>, http::Error> {
         let mut header_map = reqwest::header::HeaderMap::new();
         for header_line in headers {
@@ -288,6 +295,7 @@ impl Remote {
                 base_url: base_url.to_owned(),
                 headers: header_map,
                 upload_body_kind,
+                put_body_size,
                 config: self.config.clone(),
             })
             .is_err()
@@ -344,7 +352,7 @@ impl http::Http for Remote {
         base_url: &str,
         headers: impl IntoIterator<Item = impl AsRef<str>>,
     ) -> Result<http::GetResponse<Self::Headers, Self::ResponseBody>, http::Error> {
-        self.make_request(url, base_url, headers, None).map(Into::into)
+        self.make_request(url, base_url, headers, None, None).map(Into::into)
     }
 
     fn post(
@@ -354,7 +362,23 @@ impl http::Http for Remote {
         headers: impl IntoIterator<Item = impl AsRef<str>>,
         post_body_kind: PostBodyDataKind,
     ) -> Result<http::PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
-        self.make_request(url, base_url, headers, Some(post_body_kind))
+        self.make_request(url, base_url, headers, Some(post_body_kind), None)
+    }
+
+    fn put(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        body_size: u64,
+    ) -> Result<http::PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
+        self.make_request(
+            url,
+            base_url,
+            headers,
+            Some(PostBodyDataKind::Unbounded),
+            Some(body_size),
+        )
     }
 
     fn configure(&mut self, config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
@@ -374,6 +398,8 @@ pub(crate) struct Request {
     pub base_url: String,
     pub headers: reqwest::header::HeaderMap,
     pub upload_body_kind: Option<PostBodyDataKind>,
+    /// If set, the upload body is sent with `PUT` instead of `POST`, with the given size.
+    pub put_body_size: Option<u64>,
     pub config: http::Options,
 }
 
@@ -123,6 +123,26 @@ pub trait Http {
         body: PostBodyDataKind,
     ) -> Result<PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, Error>;
 
+    /// Initiate a `PUT` request to `url` providing the given `headers`, where `base_url` is so that `base_url + tail == url`,
+    /// to upload exactly `body_size` bytes.
+    ///
+    /// This works just like [`post()`](Self::post()), but the body is always streamed and announced with `Content-Length: <body_size>`,
+    /// which is what servers storing arbitrary content typically require.
+    ///
+    /// The default implementation fails as `PUT` isn't needed for `git` interactions.
+    fn put(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        body_size: u64,
+    ) -> Result<PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, Error> {
+        _ = (url, base_url, headers.into_iter(), body_size);
+        Err(Error::Detail {
+            description: "PUT requests aren't supported by this implementation".into(),
+        })
+    }
+
     /// Pass `config` which can deserialize in the implementation's configuration, as documented separately.
     ///
     /// The caller must know how that `config` data looks like for the intended implementation.
@@ -1239,6 +1239,74 @@ fn check_content_type_is_case_insensitive() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn put_streams_a_sized_body_and_does_not_affect_following_requests() -> crate::Result {
+    use gix_transport::client::blocking_io::http::Http;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
+    let server = std::thread::spawn(move || -> Vec<(Vec<String>, Vec<u8>)> {
+        let mut requests = Vec::new();
+        for _ in 0..2 {
+            let (stream, _) = listener.accept().expect("accept request");
+            let mut reader = std::io::BufReader::new(stream);
+            let lines = read_request_lines(&mut reader);
+            let content_length = lines
+                .iter()
+                .find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length: ")
+                        .map(ToOwned::to_owned)
+                })
+                .map_or(0, |len| len.parse::<usize>().expect("valid content length"));
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).expect("body can be read");
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .expect("write response");
+            reader.get_mut().shutdown(std::net::Shutdown::Both).ok();
+            requests.push((lines, body));
+        }
+        requests
+    });
+
+    let url = format!("http://127.0.0.1:{port}/object");
+    let mut remote = Remote::default();
+    let http::PostResponse {
+        mut post_body,
+        headers,
+        mut body,
+    } = remote.put(&url, &url, ["Content-Type: application/octet-stream"], 5)?;
+    post_body.write_all(b"hello")?;
+    drop(post_body);
+    std::io::copy(&mut { headers }, &mut std::io::sink())?;
+    let mut buf = String::new();
+    body.read_to_string(&mut buf)?;
+    assert_eq!(buf, "ok");
+
+    let http::GetResponse { headers, mut body } = remote.get(&url, &url, None::<&str>)?;
+    std::io::copy(&mut { headers }, &mut std::io::sink())?;
+    buf.clear();
+    body.read_to_string(&mut buf)?;
+    assert_eq!(buf, "ok");
+
+    let requests = server.join().expect("no panic");
+    let (put, put_body) = &requests[0];
+    assert_eq!(put[0], "PUT /object HTTP/1.1");
+    assert!(
+        put.iter().any(|line| line.eq_ignore_ascii_case("content-length: 5")),
+        "the size is announced upfront instead of using a chunked encoding: {put:?}"
+    );
+    assert_eq!(put_body.as_bstr(), "hello");
+    let (get, _) = &requests[1];
+    assert_eq!(
+        get[0], "GET /object HTTP/1.1",
+        "the method is reset for the next request"
+    );
+    Ok(())
+}
+
 fn ignore_reqwest_content_length(header_line: &String) -> bool {
     header_line != "content-length: 0"
 }
@@ -105,7 +105,7 @@ struct GenThenTime {
 impl From<&gix_revwalk::graph::Commit<PaintFlags>> for GenThenTime {
     fn from(commit: &gix_revwalk::graph::Commit<PaintFlags>) -> Self {
         GenThenTime {
-            generation: commit.generation.unwrap_or(gix_commitgraph::GENERATION_NUMBER_INFINITY),
+            generation: commit.generation.unwrap_or(gix_revwalk::graph::Generation::MAX),
             time: commit.commit_time,
         }
     }
@@ -0,0 +1,131 @@
+pub use gix_apply as plumbing;
+pub use gix_apply::{
+    Patch,
+    apply::{Outcome as TreeOutcome, ThreeWay},
+};
+
+use crate::bstr::BString;
+
+/// A way to configure [`Repository::apply()`](crate::Repository::apply())
+/// and [`Repository::apply_to_tree()`](crate::Repository::apply_to_tree()).
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    pub(crate) inner: gix_apply::apply::Options,
+}
+
+impl From<gix_apply::apply::Options> for Options {
+    fn from(inner: gix_apply::apply::Options) -> Self {
+        Options { inner }
+    }
+}
+
+/// Builder
+impl Options {
+    /// If set, allow to reduce the context of each hunk down to this amount of lines if it doesn't apply
+    /// with its full context, just like `git apply -C<n>` does.
+    pub fn with_min_context(mut self, min_context: Option<usize>) -> Self {
+        self.inner.min_context = min_context;
+        self
+    }
+
+    /// If `true`, merge patches that don't apply into the current content using the blob they were created from,
+    /// just like `git apply --3way` does.
+    pub fn with_three_way(mut self, three_way: bool) -> Self {
+        self.inner.three_way = three_way;
+        self
+    }
+
+    /// Use `options` to merge text in case of a [3-way merge](Self::with_three_way()).
+    pub fn with_text_merge(mut self, options: gix_merge::blob::builtin_driver::text::Options) -> Self {
+        self.inner.text_merge = options;
+        self
+    }
+}
+
+/// Define where [`Repository::apply()`](crate::Repository::apply()) applies patches to.
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Location {
+    /// Apply patches to the worktree only, like `git apply` does.
+    Worktree,
+    /// Apply patches to the index only, like `git apply --cached` does.
+    Index,
+    /// Apply patches to the index and the worktree, which must match the index for all patched files,
+    /// like `git apply --index` does.
+    IndexAndWorktree,
+}
+
+/// The outcome of [`Repository::apply()`](crate::Repository::apply()).
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// The paths of all files which had to be merged as their patch didn't apply cleanly, in the order of the patches.
+    pub merged: Vec<BString>,
+    /// The paths of all files which were merged with conflicts, in the order of the patches.
+    ///
+    /// In the worktree, they contain conflict markers, while in the index they are stored as base, our and their version.
+    pub conflicts: Vec<BString>,
+}
+
+/// Parse all patches in `input`, as produced by `git diff` or `git format-patch`, whose object ids are of kind `object_hash`.
+pub fn parse(input: &[u8], object_hash: gix_hash::Kind) -> Result<Vec<Patch>, gix_error::Error> {
+    gix_apply::parse(input, object_hash).map_err(gix_error::Exn::into_error)
+}
+
+/// The error returned by [`Repository::apply()`](crate::Repository::apply())
+/// and [`Repository::apply_to_tree()`](crate::Repository::apply_to_tree()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("A bare repository has no worktree to apply patches to")]
+    MissingWorkDir,
+    #[error("'{path}' does not exist in the {location}")]
+    Missing { path: BString, location: &'static str },
+    #[error("'{path}' already exists in the {location}")]
+    AlreadyExists { path: BString, location: &'static str },
+    #[error("'{path}' has unresolved conflicts in the index")]
+    Unmerged { path: BString },
+    #[error("'{path}' in the worktree does not match the index")]
+    WorktreeDoesNotMatchIndex { path: BString },
+    #[error("'{path}' is not a file and can't be patched")]
+    NotAFile { path: BString },
+    #[error("The patch to remove '{path}' leaves content behind")]
+    RemovalLeavesContent { path: BString },
+    #[error("The checkout was interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    Apply(#[from] gix_error::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    #[error(transparent)]
+    WorktreeFile(#[from] crate::filter::pipeline::worktree_file_to_object::Error),
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+    OpenArcOdb(#[from] std::io::Error),
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    #[error("Could not remove '{}'", path.display())]
+    Remove {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
@@ -0,0 +1,227 @@
+//! Access many objects at once like `git cat-file --batch` does with [`Repository::batch_objects()`](crate::Repository::batch_objects())
+//! and [`Repository::batch_all_objects()`](crate::Repository::batch_all_objects()).
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`Repository::batch_objects()`](crate::Repository::batch_objects())
+/// and [`Repository::batch_all_objects()`](crate::Repository::batch_all_objects()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("Could not read input or write output")]
+    Io(#[from] std::io::Error),
+    #[error("Could not prepare the object database for obtaining on-disk information")]
+    OpenObjectDatabase(#[source] std::io::Error),
+    #[error(transparent)]
+    LoadIndex(#[from] gix_odb::store::load_index::Error),
+    #[error(transparent)]
+    LookupPrefix(#[from] gix_odb::store::prefix::lookup::Error),
+    #[error(transparent)]
+    IterLooseObjects(#[from] gix_odb::loose::iter::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::Error),
+    #[error("Could not decode the pack entry of object {id}")]
+    DecodePackEntry {
+        id: gix_hash::ObjectId,
+        source: gix_pack::data::entry::decode::Error,
+    },
+    #[error("Unknown command: '{command}'")]
+    UnknownCommand { command: BString },
+    #[error("Command '{command}' requires an object name")]
+    MissingArgument { command: BString },
+    #[error("The 'flush' command is only supported if output is buffered")]
+    FlushWithoutBuffer,
+    #[error("Commands can't be read when batching all objects")]
+    CommandModeWithAllObjects,
+}
+
+///
+pub mod format {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Format::parse()`](super::Format::parse()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Unknown format atom '%({atom})'")]
+        UnknownAtom { atom: BString },
+        #[error("Format atom starting at byte {position} isn't terminated with ')'")]
+        Unterminated { position: usize },
+    }
+}
+
+/// A placeholder in a [`Format`], named like its counterpart in `git cat-file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Atom {
+    /// `%(objectname)`, the full hexadecimal object id.
+    ObjectName,
+    /// `%(objecttype)`, the kind of object, like `commit`.
+    ObjectType,
+    /// `%(objectsize)`, the size of the object in bytes.
+    ObjectSize,
+    /// `%(objectsize:disk)`, the amount of bytes the object takes in its pack or as loose object file.
+    ObjectSizeOnDisk,
+    /// `%(deltabase)`, the id of the object this one is stored as delta against in a pack, or the null id.
+    DeltaBase,
+    /// `%(rest)`, whatever followed the first whitespace of the input line, or nothing when batching all objects.
+    ///
+    /// If it's used, the object name in the input line ends at the first whitespace.
+    Rest,
+}
+
+impl Atom {
+    fn from_name(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"objectname" => Atom::ObjectName,
+            b"objecttype" => Atom::ObjectType,
+            b"objectsize" => Atom::ObjectSize,
+            b"objectsize:disk" => Atom::ObjectSizeOnDisk,
+            b"deltabase" => Atom::DeltaBase,
+            b"rest" => Atom::Rest,
+            _ => return None,
+        })
+    }
+}
+
+/// A part of a [`Format`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    /// Text to output as is.
+    Literal(BString),
+    /// A placeholder to replace with information about the object.
+    Atom(Atom),
+}
+
+/// The format of the line that precedes each object, or that is the only output per object if only information is requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Format {
+    items: Vec<Item>,
+}
+
+impl Default for Format {
+    /// `%(objectname) %(objecttype) %(objectsize)`, the format `git cat-file` uses by default.
+    fn default() -> Self {
+        Format {
+            items: vec![
+                Item::Atom(Atom::ObjectName),
+                Item::Literal(" ".into()),
+                Item::Atom(Atom::ObjectType),
+                Item::Literal(" ".into()),
+                Item::Atom(Atom::ObjectSize),
+            ],
+        }
+    }
+}
+
+impl Format {
+    /// Parse `input` with `%(atom)` placeholders like `%(objectname) %(objectsize:disk)`, where `%%` is a literal `%`.
+    pub fn parse(input: &BStr) -> Result<Self, format::Error> {
+        let mut items = Vec::new();
+        let mut literal = BString::default();
+        let mut pos = 0;
+        while let Some(offset) = input[pos..].find_byte(b'%') {
+            literal.extend_from_slice(&input[pos..pos + offset]);
+            let start = pos + offset;
+            match input.get(start + 1) {
+                Some(b'%') => {
+                    literal.push(b'%');
+                    pos = start + 2;
+                }
+                Some(b'(') => {
+                    let name_start = start + 2;
+                    let name_len = input[name_start..]
+                        .find_byte(b')')
+                        .ok_or(format::Error::Unterminated { position: start })?;
+                    let name = &input[name_start..name_start + name_len];
+                    let atom = Atom::from_name(name).ok_or_else(|| format::Error::UnknownAtom { atom: name.into() })?;
+                    if !literal.is_empty() {
+                        items.push(Item::Literal(std::mem::take(&mut literal)));
+                    }
+                    items.push(Item::Atom(atom));
+                    pos = name_start + name_len + 1;
+                }
+                _ => {
+                    literal.push(b'%');
+                    pos = start + 1;
+                }
+            }
+        }
+        literal.extend_from_slice(&input[pos..]);
+        if !literal.is_empty() {
+            items.push(Item::Literal(literal));
+        }
+        Ok(Format { items })
+    }
+
+    /// Return all parts of the format, in order.
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub(crate) fn contains(&self, atom: Atom) -> bool {
+        self.items.contains(&Item::Atom(atom))
+    }
+}
+
+/// Determine what to output for each object.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Output the [format line](Format), followed by the object data and a newline, like `git cat-file --batch`.
+    #[default]
+    Contents,
+    /// Output only the [format line](Format), like `git cat-file --batch-check`.
+    Info,
+    /// Read commands like `contents <object>`, `info <object>` and `flush` from each input line, like `git cat-file --batch-command`.
+    ///
+    /// `flush` writes all output produced so far, and is only valid if [output is buffered](Options::with_buffer()).
+    Command,
+}
+
+/// Configure [`Repository::batch_objects()`](crate::Repository::batch_objects())
+/// and [`Repository::batch_all_objects()`](crate::Repository::batch_all_objects()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    pub(crate) mode: Mode,
+    pub(crate) format: Format,
+    pub(crate) buffer: bool,
+    pub(crate) unordered: bool,
+}
+
+/// Builder
+impl Options {
+    /// Set the `mode` that determines what to output for each object, which is [`Mode::Contents`] by default.
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the `format` of the line that is written for each object.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// If `true`, the output isn't flushed after each object, which is faster if the output is buffered,
+    /// but requires the reader to wait for the end of the input or a `flush` command in [`Mode::Command`].
+    /// It's `false` by default, which makes it possible to interactively exchange object names and objects through a pipe.
+    pub fn with_buffer(mut self, toggle: bool) -> Self {
+        self.buffer = toggle;
+        self
+    }
+
+    /// If `true`, [`batch_all_objects()`](crate::Repository::batch_all_objects()) outputs objects in the order they are
+    /// stored in packs, followed by loose objects, which is faster to access than the default order sorted by object id.
+    pub fn with_unordered(mut self, toggle: bool) -> Self {
+        self.unordered = toggle;
+        self
+    }
+}
+
+/// The outcome of [`Repository::batch_objects()`](crate::Repository::batch_objects())
+/// and [`Repository::batch_all_objects()`](crate::Repository::batch_all_objects()).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of objects that were written.
+    pub objects: usize,
+    /// The amount of object names which didn't resolve to an existing object, and which were reported as `missing` or `ambiguous`.
+    pub missing: usize,
+}
@@ -0,0 +1,180 @@
+pub use gix_revision::bisect::estimate_steps;
+
+use crate::bstr::BString;
+
+/// The name of the reference to the commit to test next if the bisect doesn't check out commits, like in bare repositories.
+pub const HEAD_NAME: &str = "BISECT_HEAD";
+
+/// How a commit was judged by whoever tested it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Mark {
+    /// The commit has the property that is searched for, like a bug, like `git bisect bad` would.
+    Bad,
+    /// The commit doesn't have the property that is searched for, like `git bisect good` would.
+    Good,
+    /// The commit can't be tested, like `git bisect skip` would.
+    Skip,
+}
+
+/// The names used for bad and good commits, as stored in `BISECT_TERMS`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Terms {
+    /// The name for commits with the property that is searched for, like `bad` or `new`.
+    pub bad: BString,
+    /// The name for commits without the property that is searched for, like `good` or `old`.
+    pub good: BString,
+}
+
+impl Default for Terms {
+    fn default() -> Self {
+        Terms {
+            bad: "bad".into(),
+            good: "good".into(),
+        }
+    }
+}
+
+/// A way to configure [`Repository::bisect_start()`](crate::Repository::bisect_start()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    pub(crate) no_checkout: bool,
+    pub(crate) terms: Option<Terms>,
+}
+
+/// Builder
+impl Options {
+    /// If `true`, don't check out the commits to test but point [`HEAD_NAME`] to them instead, like `git bisect start --no-checkout` would.
+    ///
+    /// This is always the case in bare repositories.
+    pub fn with_no_checkout(mut self, no_checkout: bool) -> Self {
+        self.no_checkout = no_checkout;
+        self
+    }
+
+    /// Use `bad` and `good` as names for bad and good commits, like `git bisect start --term-bad=<bad> --term-good=<good>` would.
+    pub fn with_terms(mut self, bad: impl Into<BString>, good: impl Into<BString>) -> Self {
+        self.terms = Some(Terms {
+            bad: bad.into(),
+            good: good.into(),
+        });
+        self
+    }
+}
+
+/// The state of a bisect in progress, as stored in the `BISECT_*` files and the `refs/bisect/*` references.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct State {
+    /// What `HEAD` pointed to when the bisect started, the short name of a branch or the hexadecimal id of a commit,
+    /// as stored in `BISECT_START`.
+    pub start: BString,
+    /// The names for bad and good commits.
+    pub terms: Terms,
+    /// The commit marked as bad, if there is one yet.
+    pub bad: Option<gix_hash::ObjectId>,
+    /// All commits marked as good.
+    pub good: Vec<gix_hash::ObjectId>,
+    /// All commits marked as skipped.
+    pub skipped: Vec<gix_hash::ObjectId>,
+    /// If `true`, commits to test aren't checked out but [`HEAD_NAME`] is pointed to them instead.
+    pub no_checkout: bool,
+}
+
+/// The outcome of a bisect step, as produced by [`Repository::bisect_mark()`](crate::Repository::bisect_mark())
+/// and [`Repository::bisect_next()`](crate::Repository::bisect_next()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    /// There is nothing to bisect yet as a bad commit or a good commit is still missing.
+    Waiting {
+        /// If `true`, a bad commit is known.
+        bad: bool,
+        /// The amount of known good commits.
+        good: usize,
+    },
+    /// The commit to test next, which was checked out or written to [`HEAD_NAME`].
+    Next {
+        /// The id of the commit to test next.
+        id: gix_hash::ObjectId,
+        /// The amount of commits that are left to test after this one, assuming the worst case.
+        remaining: usize,
+        /// The estimated amount of steps needed to find the first bad commit after this one.
+        steps: usize,
+    },
+    /// The first bad commit was found.
+    FirstBad {
+        /// The id of the first bad commit.
+        id: gix_hash::ObjectId,
+    },
+    /// Only skipped commits are left to test, so the first bad commit can't be determined.
+    OnlySkipped {
+        /// All commits that could be the first bad commit, sorted from oldest to newest.
+        candidates: Vec<gix_hash::ObjectId>,
+    },
+}
+
+/// The error returned by the `bisect_*` methods on [`Repository`](crate::Repository).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("A bisect is already in progress")]
+    InProgress,
+    #[error("There is no bisect in progress")]
+    NotInProgress,
+    #[error("Cannot bisect without an initial commit")]
+    UnbornHead,
+    #[error("Bisecting needs a bad commit and at least one good commit")]
+    MissingMarks,
+    #[error("The bisect was interrupted")]
+    Interrupted,
+    #[error("The commit to start from in BISECT_START isn't a valid branch name")]
+    InvalidStart(#[from] gix_validate::reference::name::Error),
+    #[error("The terms in BISECT_TERMS are malformed")]
+    InvalidTerms,
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    ReferenceIterInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    NextReference(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    CommitGraph(#[from] crate::repository::commit_graph_if_enabled::Error),
+    #[error(transparent)]
+    Bisect(#[from] gix_revision::bisect::Error),
+    #[error(transparent)]
+    Switch(#[from] crate::switch::Error),
+    #[error("The test of commit {id} failed")]
+    Run {
+        id: gix_hash::ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("Could not read '{}'", path.display())]
+    ReadFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not write '{}'", path.display())]
+    WriteFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not remove '{}'", path.display())]
+    RemoveFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
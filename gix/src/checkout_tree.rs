@@ -0,0 +1,92 @@
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::checkout_tree()`](crate::Repository::checkout_tree()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("A bare repository has no worktree to check out into")]
+    MissingWorkDir,
+    #[error("{} path(s) conflict with the checkout, first one is '{}'", conflicts.len(), conflicts[0].rela_path)]
+    Conflicts { conflicts: Vec<Conflict> },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    Status(#[from] crate::status::Error),
+    #[error(transparent)]
+    StatusIter(#[from] crate::status::into_iter::Error),
+    #[error(transparent)]
+    StatusItem(#[from] crate::status::index_worktree::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+    OpenArcOdb(#[from] std::io::Error),
+    #[error("Could not remove '{}'", path.display())]
+    Remove {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("The checkout was interrupted")]
+    Interrupted,
+}
+
+/// The reason for a path to prevent a [checkout](crate::Repository::checkout_tree()).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ConflictKind {
+    /// The index or worktree version of the file has local changes that would be lost.
+    LocalChanges,
+    /// An untracked file or directory is in the way of a file that would be written.
+    Untracked,
+    /// The index entry has unresolved merge conflicts.
+    Unmerged,
+}
+
+/// A path that prevents a [checkout](crate::Repository::checkout_tree()) that isn't [forced](Options::with_force()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Conflict {
+    /// The path relative to the root of the worktree.
+    pub rela_path: BString,
+    /// The reason for the conflict.
+    pub kind: ConflictKind,
+}
+
+/// A way to configure [`Repository::checkout_tree()`](crate::Repository::checkout_tree()).
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    pub(crate) force: bool,
+}
+
+/// Builder
+impl Options {
+    /// If `true`, make the index and worktree match the tree exactly, discarding all local changes to tracked files and
+    /// overwriting untracked files that are in the way, like `git checkout --force` or `git reset --hard` would.
+    /// Untracked files that aren't in the way are kept.
+    ///
+    /// It's `false` by default, which refuses to check out if local changes or untracked files would be lost.
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+}
+
+/// The outcome produced by [`Repository::checkout_tree()`](crate::Repository::checkout_tree()).
+#[derive(Default, Debug, Clone)]
+pub struct Outcome {
+    /// The paths of the files that were written or re-written, relative to the root of the worktree.
+    pub files_written: Vec<BString>,
+    /// The paths of the files that were removed, relative to the root of the worktree.
+    pub files_removed: Vec<BString>,
+    /// The amount of bytes written to disk.
+    pub bytes_written: u64,
+}
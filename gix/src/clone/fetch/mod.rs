@@ -374,6 +374,7 @@ impl PrepareFetch {
                 message: reflog_message.clone(),
             })
             .with_shallow(self.shallow.clone())
+            .with_write_fetch_head(false)
             .receive(&repo, &mut progress, should_interrupt)
             .await?;
 
@@ -30,6 +30,20 @@ impl From<std::convert::Infallible> for Error {
     }
 }
 
+///
+#[cfg(feature = "notes")]
+pub mod notes {
+    /// The error returned by [`Commit::notes()`](crate::Commit::notes()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        DisplayRefs(#[from] crate::note::display_refs::Error),
+        #[error(transparent)]
+        Find(#[from] crate::note::find::Error),
+    }
+}
+
 ///
 #[cfg(feature = "revision")]
 pub mod describe {
@@ -262,6 +262,11 @@ impl Cache {
         )
     }
 
+    /// Return the method to use for making files of `component` durable, or `None` if `core.fsync` doesn't include it.
+    pub(crate) fn fsync(&self, component: gix_fs::fsync::Components) -> Option<gix_fs::fsync::Method> {
+        self.fsync_components.contains(component).then_some(self.fsync_method)
+    }
+
     pub(crate) fn apply_leniency<T, E>(&self, res: Result<Option<T>, E>) -> Result<Option<T>, E> {
         res.with_leniency(self.lenient_config)
     }
@@ -543,6 +548,33 @@ pub(crate) fn loose_compression(
     )
 }
 
+/// Return the components to make durable from `core.fsync` and `core.fsyncObjectFiles`, along with the method to use
+/// from `core.fsyncMethod`.
+pub(crate) fn fsync(
+    config: &gix_config::File,
+    lenient: bool,
+    mut filter_config_section: fn(&gix_config::file::Metadata) -> bool,
+) -> Result<(gix_fs::fsync::Components, gix_fs::fsync::Method), config::Error> {
+    let mut components = config
+        .string_filter(Core::FSYNC, &mut filter_config_section)
+        .map(|value| Core::FSYNC.try_into_fsync_components(value))
+        .unwrap_or_default();
+    if Core::FSYNC_OBJECT_FILES
+        .enrich_error(config.boolean_filter(Core::FSYNC_OBJECT_FILES, &mut filter_config_section))
+        .with_leniency(lenient)?
+        .unwrap_or_default()
+    {
+        components |= gix_fs::fsync::Components::LOOSE_OBJECT;
+    }
+    let method = config
+        .string_filter(Core::FSYNC_METHOD, &mut filter_config_section)
+        .map(|value| Core::FSYNC_METHOD.try_into_fsync_method(value))
+        .transpose()
+        .with_leniency(lenient)?
+        .unwrap_or_default();
+    Ok((components, method))
+}
+
 pub(crate) fn pack_compression(
     config: &gix_config::File,
     lenient: bool,
@@ -15,6 +15,7 @@ pub(crate) struct StageOne {
     pub is_bare: Option<bool>,
     pub lossy: bool,
     pub object_hash: gix_hash::Kind,
+    pub ref_format: gix_ref::store::Format,
     pub reflog: Option<gix_ref::store::WriteReflog>,
     pub precompose_unicode: bool,
     pub protect_windows: bool,
@@ -50,6 +51,11 @@ impl StageOne {
             (0 | 1, None) => legacy_object_hash()?,
             (version, _) => return Err(Error::UnsupportedRepositoryFormatVersion { version }),
         };
+        let ref_format = match (repo_format_version, config.string(Extensions::REF_STORAGE)) {
+            (1, Some(format)) => Extensions::REF_STORAGE.try_into_ref_format(format)?,
+            (_, Some(_)) => return Err(Error::RefStorageRequiresV1),
+            (_, None) => gix_ref::store::Format::Files,
+        };
 
         let extension_worktree = util::config_bool(
             &config,
@@ -88,6 +94,7 @@ impl StageOne {
             is_bare,
             lossy,
             object_hash,
+            ref_format,
             reflog,
             precompose_unicode,
             protect_windows,
@@ -26,6 +26,7 @@ impl Cache {
             lossy,
             is_bare,
             object_hash,
+            ref_format: _,
             reflog: _,
             precompose_unicode: _,
             protect_windows: _,
@@ -159,6 +160,7 @@ impl Cache {
         let (static_pack_cache_limit_bytes, pack_cache_bytes, object_cache_bytes, alloc_limit_bytes) =
             util::parse_object_caches(&config, lenient_config, filter_config_section)?;
         let loose_compression = super::access::loose_compression(&config, lenient_config, filter_config_section)?;
+        let (fsync_components, fsync_method) = super::access::fsync(&config, lenient_config, filter_config_section)?;
         // NOTE: When adding a new initial cache, consider adjusting `reread_values_and_clear_caches()` as well.
         Ok(Cache {
             resolved: config.into(),
@@ -171,6 +173,8 @@ impl Cache {
             object_cache_bytes,
             alloc_limit_bytes,
             loose_compression,
+            fsync_components,
+            fsync_method,
             reflog,
             refs_namespace,
             is_bare,
@@ -253,6 +257,8 @@ impl Cache {
         let loose_compression =
             super::access::loose_compression(config, self.lenient_config, self.filter_config_section)?;
         self.loose_compression = loose_compression;
+        (self.fsync_components, self.fsync_method) =
+            super::access::fsync(config, self.lenient_config, self.filter_config_section)?;
         #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
         {
             self.url_scheme = Default::default();
@@ -322,6 +328,7 @@ impl crate::Repository {
 
     fn apply_changed_values(&mut self) {
         self.refs.write_reflog = util::reflog_or_default(self.config.reflog, self.workdir().is_some());
+        self.refs.fsync = self.config.fsync(gix_fs::fsync::Components::REFERENCE);
         self.refs.namespace.clone_from(&self.config.refs_namespace);
     }
 }
@@ -93,6 +93,11 @@ pub enum Error {
          set core.repositoryFormatVersion=1 to use it, or remove extensions.objectFormat to fall back to the default Sha1 format (if supported by this build)"
     )]
     ObjectFormatRequiresV1,
+    #[error(
+        "extensions.refStorage is a v1-only extension, but the repository format version is 0; \
+         set core.repositoryFormatVersion=1 to use it"
+    )]
+    RefStorageRequiresV1,
     #[error("Unsupported repository format version {version}; only versions 0 and 1 are supported")]
     UnsupportedRepositoryFormatVersion { version: usize },
     #[error(transparent)]
@@ -645,6 +650,10 @@ pub(crate) struct Cache {
     pub(crate) alloc_limit_bytes: Option<usize>,
     /// The compression level to use when writing loose objects, from `core.looseCompression` or `core.compression`.
     pub(crate) loose_compression: gix_zlib::Compression,
+    /// The parts of the repository to make durable when writing them, from `core.fsync`.
+    pub(crate) fsync_components: gix_fs::fsync::Components,
+    /// The way to make files durable, from `core.fsyncMethod`.
+    pub(crate) fsync_method: gix_fs::fsync::Method,
     /// The amount of bytes we can hold in our static LRU cache. Otherwise, go with the defaults.
     pub(crate) static_pack_cache_limit_bytes: Option<usize>,
     /// The config section filter from the options used to initialize this instance. Keep these in sync!
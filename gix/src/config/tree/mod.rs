@@ -37,6 +37,8 @@ pub(crate) mod root {
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
         /// The `http` section.
@@ -49,14 +51,20 @@ pub(crate) mod root {
         pub const MAILMAP: sections::Mailmap = sections::Mailmap;
         /// The `merge` section.
         pub const MERGE: sections::Merge = sections::Merge;
+        /// The `notes` section.
+        pub const NOTES: sections::Notes = sections::Notes;
         /// The `pack` section.
         pub const PACK: sections::Pack = sections::Pack;
         /// The `protocol` section.
         pub const PROTOCOL: sections::Protocol = sections::Protocol;
         /// The `push` section.
         pub const PUSH: sections::Push = sections::Push;
+        /// The `receive` section.
+        pub const RECEIVE: sections::Receive = sections::Receive;
         /// The `remote` section.
         pub const REMOTE: sections::Remote = sections::Remote;
+        /// The `repack` section.
+        pub const REPACK: sections::Repack = sections::Repack;
         /// The `safe` section.
         pub const SAFE: sections::Safe = sections::Safe;
         /// The `ssh` section.
@@ -83,16 +91,20 @@ pub(crate) mod root {
                 &Self::DIFF,
                 &Self::EXTENSIONS,
                 &Self::FETCH,
+                &Self::GC,
                 &Self::GITOXIDE,
                 &Self::HTTP,
                 &Self::INDEX,
                 &Self::INIT,
                 &Self::MAILMAP,
                 &Self::MERGE,
+                &Self::NOTES,
                 &Self::PACK,
                 &Self::PROTOCOL,
                 &Self::PUSH,
+                &Self::RECEIVE,
                 &Self::REMOTE,
+                &Self::REPACK,
                 &Self::SAFE,
                 &Self::SSH,
                 #[cfg(feature = "status")]
@@ -106,9 +118,9 @@ pub(crate) mod root {
 
 mod sections;
 pub use sections::{
-    Author, Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init,
-    Mailmap, Merge, Pack, Protocol, Push, Remote, Safe, Ssh, Url, User, branch, checkout, core, credential, extensions,
-    fetch, gitoxide, http, index, protocol, push, remote, ssh,
+    Author, Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gc, Gitoxide, Http, Index, Init,
+    Mailmap, Merge, Notes, Pack, Protocol, Push, Receive, Remote, Repack, Safe, Ssh, Url, User, branch, checkout, core,
+    credential, extensions, fetch, gc, gitoxide, http, index, protocol, push, receive, remote, ssh,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{Diff, diff};
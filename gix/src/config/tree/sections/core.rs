@@ -31,6 +31,14 @@ impl Core {
     pub const EDITOR: keys::Program = keys::Program::new_program("editor", &config::Tree::CORE);
     /// The `core.fileMode` key.
     pub const FILE_MODE: keys::Boolean = keys::Boolean::new_boolean("fileMode", &config::Tree::CORE);
+    /// The `core.fsync` key.
+    pub const FSYNC: Fsync = Fsync::new_with_validate("fsync", &config::Tree::CORE, validate::Fsync);
+    /// The `core.fsyncMethod` key.
+    pub const FSYNC_METHOD: FsyncMethod =
+        FsyncMethod::new_with_validate("fsyncMethod", &config::Tree::CORE, validate::FsyncMethod);
+    /// The `core.fsyncObjectFiles` key.
+    pub const FSYNC_OBJECT_FILES: keys::Boolean = keys::Boolean::new_boolean("fsyncObjectFiles", &config::Tree::CORE)
+        .with_note("deprecated, if `true` it adds `loose-object` to `core.fsync`");
     /// The `core.fsCache` key.
     pub const FS_CACHE: keys::Boolean = keys::Boolean::new_boolean("fsCache", &config::Tree::CORE);
     /// The `core.ignoreCase` key.
@@ -84,6 +92,9 @@ impl Core {
         .with_environment_override("GIT_NO_REPLACE_OBJECTS");
     /// The `core.commitGraph` key.
     pub const COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("commitGraph", &config::Tree::CORE);
+    /// The `core.notesRef` key.
+    pub const NOTES_REF: keys::String = keys::String::new_string("notesRef", &config::Tree::CORE)
+        .with_note("abbreviated names are expanded into the `refs/notes/` namespace");
     /// The `core.safecrlf` key.
     #[cfg(feature = "attributes")]
     pub const SAFE_CRLF: SafeCrlf = SafeCrlf::new_with_validate("safecrlf", &config::Tree::CORE, validate::SafeCrlf);
@@ -119,6 +130,9 @@ impl Section for Core {
             &Self::DISAMBIGUATE,
             &Self::EDITOR,
             &Self::FILE_MODE,
+            &Self::FSYNC,
+            &Self::FSYNC_METHOD,
+            &Self::FSYNC_OBJECT_FILES,
             &Self::FS_CACHE,
             &Self::IGNORE_CASE,
             &Self::FILES_REF_LOCK_TIMEOUT,
@@ -138,6 +152,7 @@ impl Section for Core {
             &Self::SSH_COMMAND,
             &Self::USE_REPLACE_REFS,
             &Self::COMMIT_GRAPH,
+            &Self::NOTES_REF,
             #[cfg(feature = "attributes")]
             &Self::SAFE_CRLF,
             #[cfg(feature = "attributes")]
@@ -162,6 +177,12 @@ pub type LogAllRefUpdates = keys::Any<validate::LogAllRefUpdates>;
 /// The `core.disambiguate` key.
 pub type Disambiguate = keys::Any<validate::Disambiguate>;
 
+/// The `core.fsync` key.
+pub type Fsync = keys::Any<validate::Fsync>;
+
+/// The `core.fsyncMethod` key.
+pub type FsyncMethod = keys::Any<validate::FsyncMethod>;
+
 #[cfg(feature = "attributes")]
 mod filter {
     use super::validate;
@@ -353,6 +374,85 @@ mod log_all_ref_updates {
     }
 }
 
+mod fsync {
+    use gix_fs::fsync::{Components, Method};
+
+    use crate::{
+        bstr::ByteSlice,
+        config,
+        config::tree::core::{Fsync, FsyncMethod},
+    };
+
+    impl Fsync {
+        /// Convert the comma-separated list of components in `value` into the components to make durable, starting
+        /// from the [default](Components::default()).
+        ///
+        /// Components prefixed with `-` are removed, and `none` clears the default, so only explicitly mentioned components remain.
+        /// Unknown components are ignored, just like `git` does, which makes this conversion infallible.
+        pub fn try_into_fsync_components(&'static self, value: impl gix_utils::AsBStr) -> Components {
+            let mut current = Components::default();
+            let (mut positive, mut negative) = (Components::empty(), Components::empty());
+            for token in value.as_bstr().split(|b| *b == b',') {
+                let token = token.trim();
+                if token.is_empty() {
+                    continue;
+                }
+                if token == b"none" {
+                    current = Components::empty();
+                    continue;
+                }
+                let (token, is_negated) = match token.strip_prefix(b"-") {
+                    Some(token) => (token, true),
+                    None => (token, false),
+                };
+                let Some(component) = component_by_name(token) else {
+                    gix_trace::warn!("Ignoring unknown core.fsync component '{}'", token.as_bstr());
+                    continue;
+                };
+                if is_negated {
+                    negative |= component;
+                } else {
+                    positive |= component;
+                }
+            }
+            (current - negative) | positive
+        }
+    }
+
+    fn component_by_name(name: &[u8]) -> Option<Components> {
+        Some(match name {
+            b"loose-object" => Components::LOOSE_OBJECT,
+            b"pack" => Components::PACK,
+            b"pack-metadata" => Components::PACK_METADATA,
+            b"commit-graph" => Components::COMMIT_GRAPH,
+            b"index" => Components::INDEX,
+            b"reference" => Components::REFERENCE,
+            b"objects" => Components::OBJECTS,
+            b"derived-metadata" => Components::DERIVED_METADATA,
+            b"committed" => Components::COMMITTED,
+            b"added" => Components::ADDED,
+            b"all" => Components::all(),
+            _ => return None,
+        })
+    }
+
+    impl FsyncMethod {
+        /// Convert `value` into the method to use for making files durable.
+        pub fn try_into_fsync_method(
+            &'static self,
+            value: impl gix_utils::AsBStr,
+        ) -> Result<Method, config::key::GenericErrorWithValue> {
+            let value = value.as_bstr();
+            Ok(match value.as_bytes() {
+                b"fsync" => Method::Fsync,
+                b"writeout-only" => Method::WriteoutOnly,
+                b"batch" => Method::Batch,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into())),
+            })
+        }
+    }
+}
+
 mod check_stat {
     use crate::{bstr::ByteSlice, config, config::tree::core::CheckStat};
 
@@ -449,6 +549,24 @@ mod validate {
         }
     }
 
+    #[derive(Clone, Copy)]
+    pub struct Fsync;
+    impl keys::Validate for Fsync {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::FSYNC.try_into_fsync_components(value);
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct FsyncMethod;
+    impl keys::Validate for FsyncMethod {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::FSYNC_METHOD.try_into_fsync_method(value)?;
+            Ok(())
+        }
+    }
+
     #[derive(Clone, Copy)]
     pub struct CheckStat;
     impl keys::Validate for CheckStat {
@@ -11,11 +11,35 @@ impl Extensions {
         ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat).with_note(
             "Support for SHA256 is prepared but not fully implemented yet. For now we abort when encountered",
         );
+    /// The `extensions.refStorage` key.
+    pub const REF_STORAGE: RefStorage =
+        RefStorage::new_with_validate("refStorage", &config::Tree::EXTENSIONS, validate::RefStorage);
 }
 
+/// The `extensions.refStorage` key.
+pub type RefStorage = keys::Any<validate::RefStorage>;
+
 /// The `core.checkStat` key.
 pub type ObjectFormat = keys::Any<validate::ObjectFormat>;
 
+mod ref_storage {
+    use crate::{bstr::ByteSlice, config, config::tree::sections::extensions::RefStorage};
+
+    impl RefStorage {
+        pub fn try_into_ref_format(
+            &'static self,
+            value: impl gix_utils::AsBStr,
+        ) -> Result<gix_ref::store::Format, config::key::GenericErrorWithValue> {
+            let value = value.as_bstr();
+            Ok(match value.as_bytes() {
+                b"files" => gix_ref::store::Format::Files,
+                b"reftable" => gix_ref::store::Format::Reftable,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into())),
+            })
+        }
+    }
+}
+
 mod object_format {
     use crate::{bstr::ByteSlice, config, config::tree::sections::extensions::ObjectFormat};
 
@@ -46,7 +70,7 @@ impl Section for Extensions {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::OBJECT_FORMAT, &Self::WORKTREE_CONFIG]
+        &[&Self::OBJECT_FORMAT, &Self::REF_STORAGE, &Self::WORKTREE_CONFIG]
     }
 }
 
@@ -62,4 +86,14 @@ mod validate {
             Ok(())
         }
     }
+
+    #[derive(Clone, Copy)]
+    pub struct RefStorage;
+
+    impl keys::Validate for RefStorage {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Extensions::REF_STORAGE.try_into_ref_format(value)?;
+            Ok(())
+        }
+    }
 }
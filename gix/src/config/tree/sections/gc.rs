@@ -0,0 +1,64 @@
+use crate::{
+    config,
+    config::tree::{Gc, Key, Section, keys},
+};
+
+impl Gc {
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: PruneExpire =
+        PruneExpire::new_with_validate("pruneExpire", &config::Tree::GC, validate::PruneExpire);
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PRUNE_EXPIRE]
+    }
+}
+
+/// The `gc.pruneExpire` key type.
+pub type PruneExpire = keys::Any<validate::PruneExpire>;
+
+mod prune_expire {
+    use crate::{bstr::ByteSlice, config, config::tree::gc::PruneExpire};
+
+    impl PruneExpire {
+        /// Try to interpret `value` as the time before which unreachable objects may be pruned, with `now` as reference
+        /// for relative dates like `2.weeks.ago`, or return `None` if they should never be pruned.
+        pub fn try_into_prune_expire(
+            &'static self,
+            value: impl gix_utils::AsBStr,
+            now: std::time::SystemTime,
+        ) -> Result<Option<gix_date::Time>, config::key::GenericErrorWithValue> {
+            let value = value.as_bstr();
+            let Ok(date) = value.to_str() else {
+                return Err(config::key::GenericErrorWithValue::from_value(self, value.into()));
+            };
+            if date.eq_ignore_ascii_case("never") {
+                return Ok(None);
+            }
+            gix_date::parse(date, Some(now))
+                .or_else(|_| gix_date::parse(&date.replace('.', " "), Some(now)))
+                .map(Some)
+                .map_err(|_| config::key::GenericErrorWithValue::from_value(self, value.into()))
+        }
+    }
+}
+
+mod validate {
+    #[derive(Clone, Copy)]
+    pub struct PruneExpire;
+    use std::error::Error;
+
+    use crate::{bstr::BStr, config::tree::keys::Validate};
+
+    impl Validate for PruneExpire {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            super::Gc::PRUNE_EXPIRE.try_into_prune_expire(value, std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
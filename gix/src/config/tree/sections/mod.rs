@@ -53,6 +53,11 @@ pub mod extensions;
 pub struct Fetch;
 pub mod fetch;
 
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
@@ -81,6 +86,11 @@ mod mailmap;
 pub struct Merge;
 mod merge;
 
+/// The `notes` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Notes;
+mod notes;
+
 /// The `pack` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Pack;
@@ -96,11 +106,21 @@ pub mod protocol;
 pub struct Push;
 pub mod push;
 
+/// The `receive` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Receive;
+pub mod receive;
+
 /// The `remote` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Remote;
 pub mod remote;
 
+/// The `repack` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Repack;
+mod repack;
+
 /// The `safe` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Safe;
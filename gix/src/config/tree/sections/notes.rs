@@ -0,0 +1,20 @@
+use crate::config::{
+    Tree,
+    tree::{Key, Notes, Section, keys},
+};
+
+impl Notes {
+    /// The `notes.displayRef` key
+    pub const DISPLAY_REF: keys::String = keys::String::new_string("displayRef", &Tree::NOTES)
+        .with_note("may be specified multiple times, and may be a glob to match multiple notes references");
+}
+
+impl Section for Notes {
+    fn name(&self) -> &str {
+        "notes"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::DISPLAY_REF]
+    }
+}
@@ -15,6 +15,13 @@ impl Pack {
 
     /// The `pack.compression` key.
     pub const COMPRESSION: keys::Compression = keys::Compression::new_compression("compression", &config::Tree::PACK);
+
+    /// The `pack.window` key.
+    pub const WINDOW: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("window", &config::Tree::PACK);
+
+    /// The `pack.depth` key.
+    pub const DEPTH: keys::UnsignedInteger = keys::UnsignedInteger::new_unsigned_integer("depth", &config::Tree::PACK);
 }
 
 /// The `pack.indexVersion` key.
@@ -47,7 +54,13 @@ impl Section for Pack {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::THREADS, &Self::INDEX_VERSION, &Self::COMPRESSION]
+        &[
+            &Self::THREADS,
+            &Self::INDEX_VERSION,
+            &Self::COMPRESSION,
+            &Self::WINDOW,
+            &Self::DEPTH,
+        ]
     }
 }
 
@@ -0,0 +1,76 @@
+use crate::{
+    config,
+    config::tree::{Key, Receive, Section, keys},
+};
+
+impl Receive {
+    /// The `receive.advertisePushOptions` key.
+    pub const ADVERTISE_PUSH_OPTIONS: keys::Boolean =
+        keys::Boolean::new_boolean("advertisePushOptions", &config::Tree::RECEIVE);
+    /// The `receive.denyCurrentBranch` key.
+    pub const DENY_CURRENT_BRANCH: DenyCurrentBranch =
+        DenyCurrentBranch::new_with_validate("denyCurrentBranch", &config::Tree::RECEIVE, validate::DenyCurrentBranch);
+    /// The `receive.denyDeletes` key.
+    pub const DENY_DELETES: keys::Boolean = keys::Boolean::new_boolean("denyDeletes", &config::Tree::RECEIVE);
+    /// The `receive.denyNonFastForwards` key.
+    pub const DENY_NON_FAST_FORWARDS: keys::Boolean =
+        keys::Boolean::new_boolean("denyNonFastForwards", &config::Tree::RECEIVE);
+}
+
+impl Section for Receive {
+    fn name(&self) -> &str {
+        "receive"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::ADVERTISE_PUSH_OPTIONS,
+            &Self::DENY_CURRENT_BRANCH,
+            &Self::DENY_DELETES,
+            &Self::DENY_NON_FAST_FORWARDS,
+        ]
+    }
+}
+
+/// The `receive.denyCurrentBranch` key type.
+pub type DenyCurrentBranch = keys::Any<validate::DenyCurrentBranch>;
+
+mod deny_current_branch {
+    use crate::{bstr::ByteSlice, config, config::tree::receive::DenyCurrentBranch, receive_pack};
+
+    impl DenyCurrentBranch {
+        /// Try to interpret `value` as `receive.denyCurrentBranch`, which may also be a boolean.
+        pub fn try_into_deny_current_branch(
+            &'static self,
+            value: impl gix_utils::AsBStr,
+        ) -> Result<receive_pack::DenyCurrentBranch, config::key::GenericErrorWithValue> {
+            let value = value.as_bstr();
+            Ok(match value.as_bytes() {
+                b"refuse" => receive_pack::DenyCurrentBranch::Refuse,
+                b"warn" => receive_pack::DenyCurrentBranch::Warn,
+                b"ignore" => receive_pack::DenyCurrentBranch::Ignore,
+                b"updateInstead" => receive_pack::DenyCurrentBranch::UpdateInstead,
+                _ => match gix_config::Boolean::try_from(value) {
+                    Ok(gix_config::Boolean(true)) => receive_pack::DenyCurrentBranch::Refuse,
+                    Ok(gix_config::Boolean(false)) => receive_pack::DenyCurrentBranch::Ignore,
+                    Err(_) => return Err(config::key::GenericErrorWithValue::from_value(self, value.into())),
+                },
+            })
+        }
+    }
+}
+
+mod validate {
+    #[derive(Clone, Copy)]
+    pub struct DenyCurrentBranch;
+    use std::error::Error;
+
+    use crate::{bstr::BStr, config::tree::keys::Validate};
+
+    impl Validate for DenyCurrentBranch {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            super::Receive::DENY_CURRENT_BRANCH.try_into_deny_current_branch(value)?;
+            Ok(())
+        }
+    }
+}
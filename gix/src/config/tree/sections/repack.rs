@@ -0,0 +1,20 @@
+use crate::{
+    config,
+    config::tree::{Key, Repack, Section, keys},
+};
+
+impl Repack {
+    /// The `repack.writeBitmaps` key.
+    pub const WRITE_BITMAPS: keys::Boolean = keys::Boolean::new_boolean("writeBitmaps", &config::Tree::REPACK)
+        .with_note("defaults to `true` in bare repositories, just like in `git`");
+}
+
+impl Section for Repack {
+    fn name(&self) -> &str {
+        "repack"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::WRITE_BITMAPS]
+    }
+}
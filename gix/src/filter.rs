@@ -95,6 +95,9 @@ pub struct Pipeline<'repo> {
 /// Lifecycle
 impl<'repo> Pipeline<'repo> {
     /// Extract options from `repo` that are needed to properly drive a standard git filter pipeline.
+    ///
+    /// With the `lfs` feature, the built-in LFS driver is enabled if a `filter.lfs` driver is configured, as `git lfs install` does,
+    /// with LFS objects stored in `.git/lfs` of the common directory.
     pub fn options(repo: &'repo Repository) -> Result<gix_filter::pipeline::Options, pipeline::options::Error> {
         let config = &repo.config.resolved;
         let encodings =
@@ -120,12 +123,19 @@ impl<'repo> Pipeline<'repo> {
             .map(|value| Core::EOL.try_into_eol(value))
             .transpose()?;
         let drivers = extract_drivers(repo)?;
+        #[cfg(feature = "lfs")]
+        let lfs = drivers
+            .iter()
+            .any(|driver| driver.name == gix_lfs::filter::DRIVER_NAME)
+            .then(|| gix_lfs::Store::at(repo.common_dir().join("lfs")));
         Ok(gix_filter::pipeline::Options {
             drivers,
             eol_config: gix_filter::eol::Configuration { auto_crlf, eol },
             encodings_with_roundtrip_check: encodings,
             crlf_roundtrip_check: safe_crlf,
             object_hash: repo.object_hash(),
+            #[cfg(feature = "lfs")]
+            lfs,
         })
     }
 
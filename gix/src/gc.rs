@@ -0,0 +1,26 @@
+//! Optimize the object database of a repository with [`Repository::gc()`](crate::Repository::gc()).
+
+/// The error returned by [`Repository::gc()`](crate::Repository::gc()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Repack(#[from] crate::repack::Error),
+    #[error(transparent)]
+    PruneExpire(#[from] crate::config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    BooleanConfig(#[from] crate::config::boolean::Error),
+    #[error("Could not iterate the loose objects")]
+    IterLooseObjects(#[from] gix_odb::loose::iter::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// The outcome of [`Repository::gc()`](crate::Repository::gc()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The outcome of repacking all reachable objects.
+    pub repack: crate::repack::Outcome,
+    /// The amount of unreachable loose objects that were removed as they expired.
+    pub pruned_objects: usize,
+}
@@ -129,6 +129,7 @@ pub use gix_features::{
     progress::{Count, DynNestedProgress, NestedProgress, Progress},
     threading,
 };
+pub use gix_fetchhead as fetchhead;
 pub use gix_fs as fs;
 pub use gix_glob as glob;
 pub use gix_hash as hash;
@@ -138,6 +139,8 @@ pub use gix_ignore as ignore;
 #[doc(inline)]
 #[cfg(feature = "index")]
 pub use gix_index as index;
+#[cfg(feature = "lfs")]
+pub use gix_lfs as lfs;
 pub use gix_lock as lock;
 #[cfg(feature = "credentials")]
 pub use gix_negotiate as negotiate;
@@ -414,10 +417,51 @@ pub mod open;
 ///
 pub mod config;
 
+#[cfg(feature = "revision")]
+pub mod batch;
+
+///
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+pub mod checkout_tree;
+///
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+pub mod reset;
+///
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+pub mod switch;
+
 ///
 #[cfg(feature = "mailmap")]
 pub mod mailmap;
 
+#[cfg(feature = "maintenance")]
+pub mod gc;
+#[cfg(feature = "maintenance")]
+pub mod repack;
+
+///
+#[cfg(feature = "notes")]
+pub mod note;
+
+///
+#[cfg(feature = "apply")]
+pub mod apply;
+
+///
+#[cfg(all(feature = "revision", feature = "worktree-mutation", feature = "status"))]
+pub mod bisect;
+
+///
+#[cfg(feature = "rebase")]
+pub mod rebase;
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash;
+
+///
+#[cfg(feature = "sequencer")]
+pub mod sequencer;
+
 ///
 pub mod worktree;
 
@@ -442,6 +486,12 @@ pub mod status;
 ///
 pub mod shallow;
 
+#[cfg(feature = "blocking-network-client")]
+pub mod upload_pack;
+
+///
+pub mod receive_pack;
+
 ///
 pub mod discover;
 
@@ -0,0 +1,282 @@
+//! Read and edit [git notes](https://git-scm.com/docs/git-notes), see [`Repository::notes()`](crate::Repository::notes()).
+
+use gix_hash::ObjectId;
+pub use gix_note::{DEFAULT_REF, combine, expand_ref_name};
+use gix_ref::{
+    FullName, FullNameRef, Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+use gix_error::Exn;
+
+use crate::{Id, Repository, ext::ObjectIdExt};
+
+/// A note attached to an object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note<'repo> {
+    /// The id of the object the note is attached to.
+    pub target: ObjectId,
+    /// The id of the blob that holds the content of the note.
+    pub id: Id<'repo>,
+    /// The name of the notes reference the note was found in.
+    pub reference: FullName,
+}
+
+impl<'repo> Note<'repo> {
+    /// Load the blob of this note to access its content.
+    pub fn blob(&self) -> Result<crate::Blob<'repo>, crate::object::find::existing::with_conversion::Error> {
+        self.id.repo.find_blob(self.id)
+    }
+}
+
+/// A platform to read and edit the notes stored under a single notes reference.
+///
+/// Each edit creates a new notes commit on top of the previous one and updates the notes reference accordingly,
+/// just like `git notes` does.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) name: FullName,
+}
+
+/// Access
+impl<'repo> Platform<'repo> {
+    /// Return the name of the notes reference this platform operates on.
+    pub fn name(&self) -> &FullNameRef {
+        self.name.as_ref()
+    }
+
+    /// Return the id of the commit that the notes reference points to, or `None` if it doesn't exist yet.
+    pub fn commit_id(&self) -> Result<Option<Id<'repo>>, find::Error> {
+        self.repo
+            .try_find_reference(self.name.as_ref())?
+            .map(|mut reference| reference.peel_to_id())
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Return the note attached to `target`, or `None` if there is none.
+    pub fn find(&self, target: impl Into<ObjectId>) -> Result<Option<Note<'repo>>, find::Error> {
+        let target = target.into();
+        let Some(tree_id) = self.tree_id()? else {
+            return Ok(None);
+        };
+        let note = gix_note::find(&tree_id, &target, &self.repo.objects, &mut Vec::new()).map_err(Exn::into_error)?;
+        Ok(note.map(|note| self.to_note(note)))
+    }
+
+    /// Return all notes, sorted by the object they are attached to.
+    pub fn list(&self) -> Result<Vec<Note<'repo>>, find::Error> {
+        let Some(tree_id) = self.tree_id()? else {
+            return Ok(Vec::new());
+        };
+        Ok(gix_note::list(&tree_id, &self.repo.objects)
+            .map_err(Exn::into_error)?
+            .into_iter()
+            .map(|note| self.to_note(note))
+            .collect())
+    }
+
+    fn tree_id(&self) -> Result<Option<ObjectId>, find::Error> {
+        let Some(commit_id) = self.commit_id()? else {
+            return Ok(None);
+        };
+        Ok(Some(self.repo.find_commit(commit_id)?.tree_id()?.detach()))
+    }
+
+    fn to_note(&self, note: gix_note::Note) -> Note<'repo> {
+        Note {
+            target: note.target,
+            id: note.id.attach(self.repo),
+            reference: self.name.clone(),
+        }
+    }
+}
+
+/// Edits
+impl<'repo> Platform<'repo> {
+    /// Attach a note with `content` to `target` and return the id of the new notes commit.
+    ///
+    /// If `target` already has a note, it will be replaced if `overwrite` is `true`, or an error is returned otherwise.
+    pub fn add(
+        &self,
+        target: impl Into<ObjectId>,
+        content: impl AsRef<[u8]>,
+        overwrite: bool,
+    ) -> Result<Id<'repo>, edit::Error> {
+        let target = target.into();
+        let note = self.repo.write_blob(content)?.detach();
+        self.edit("Notes added by 'git notes add'", |editor| {
+            if !overwrite && editor.find(&target).map_err(Exn::into_error)?.is_some() {
+                return Err(edit::Error::Exists { target });
+            }
+            editor.upsert(target, note).map_err(Exn::into_error)?;
+            Ok(())
+        })
+    }
+
+    /// Append `content` to the note of `target`, separated by an empty line, or create a new note if there is none,
+    /// and return the id of the new notes commit.
+    pub fn append(&self, target: impl Into<ObjectId>, content: impl AsRef<[u8]>) -> Result<Id<'repo>, edit::Error> {
+        let target = target.into();
+        let content = content.as_ref();
+        let repo = self.repo;
+        self.edit("Notes added by 'git notes append'", |editor| {
+            let note = match editor.find(&target).map_err(Exn::into_error)? {
+                Some(existing) => {
+                    let existing = repo.find_blob(existing.id)?;
+                    repo.write_blob(combine::concatenate(&existing.data, content))?
+                }
+                None => repo.write_blob(content)?,
+            };
+            editor.upsert(target, note.detach()).map_err(Exn::into_error)?;
+            Ok(())
+        })
+    }
+
+    /// Attach the note of `from` to `to` as well, and return the id of the new notes commit.
+    ///
+    /// It's an error if `from` has no note, or if `to` already has a note and `overwrite` is `false`.
+    pub fn copy(
+        &self,
+        from: impl Into<ObjectId>,
+        to: impl Into<ObjectId>,
+        overwrite: bool,
+    ) -> Result<Id<'repo>, edit::Error> {
+        let (from, to) = (from.into(), to.into());
+        self.edit("Notes added by 'git notes copy'", |editor| {
+            let note = editor
+                .find(&from)
+                .map_err(Exn::into_error)?
+                .ok_or(edit::Error::Missing { target: from })?;
+            if !overwrite && editor.find(&to).map_err(Exn::into_error)?.is_some() {
+                return Err(edit::Error::Exists { target: to });
+            }
+            editor.upsert(to, note.id).map_err(Exn::into_error)?;
+            Ok(())
+        })
+    }
+
+    /// Remove the note of `target`, and return the id of the new notes commit.
+    ///
+    /// It's an error if `target` has no note.
+    pub fn remove(&self, target: impl Into<ObjectId>) -> Result<Id<'repo>, edit::Error> {
+        let target = target.into();
+        self.edit("Notes removed by 'git notes remove'", |editor| {
+            editor
+                .remove(&target)
+                .map_err(Exn::into_error)?
+                .ok_or(edit::Error::Missing { target })?;
+            Ok(())
+        })
+    }
+
+    /// Apply `change` to the current notes tree, write the result into a new notes commit with `message`,
+    /// and point the notes reference to it.
+    fn edit(
+        &self,
+        message: &str,
+        change: impl FnOnce(&mut gix_note::Editor<'_>) -> Result<(), edit::Error>,
+    ) -> Result<Id<'repo>, edit::Error> {
+        let repo = self.repo;
+        let parent = self.commit_id()?;
+        let tree_id = self
+            .tree_id()?
+            .unwrap_or_else(|| ObjectId::empty_tree(repo.object_hash()));
+        let mut editor = gix_note::Editor::new(tree_id, &repo.objects).map_err(Exn::into_error)?;
+        change(&mut editor)?;
+        let tree_id = editor.write(|tree| repo.write_object(tree).map(Id::detach))?;
+        let commit = repo.new_commit(format!("{message}\n"), tree_id, parent.map(Id::detach))?;
+        repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("notes: {message}").into(),
+                },
+                expected: match parent {
+                    Some(parent) => PreviousValue::MustExistAndMatch(Target::Object(parent.detach())),
+                    None => PreviousValue::MustNotExist,
+                },
+                new: Target::Object(commit.id),
+            },
+            name: self.name.clone(),
+            deref: false,
+        })?;
+        Ok(commit.id())
+    }
+}
+
+///
+pub mod find {
+    /// The error returned by [`Platform::find()`](super::Platform::find()) and [`Platform::list()`](super::Platform::list()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        FindNote(#[from] gix_error::Error),
+    }
+}
+
+///
+pub mod edit {
+    /// The error returned by [`Platform::add()`](super::Platform::add()) and the other editing methods.
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Object {target} already has a note")]
+        Exists { target: gix_hash::ObjectId },
+        #[error("Object {target} has no note")]
+        Missing { target: gix_hash::ObjectId },
+        #[error(transparent)]
+        Find(#[from] super::find::Error),
+        #[error(transparent)]
+        EditNotes(#[from] gix_error::Error),
+        #[error(transparent)]
+        FindBlob(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        NewCommit(#[from] crate::repository::new_commit::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+pub mod init {
+    /// The error returned by [`Repository::notes()`](crate::Repository::notes()) and [`Repository::notes_at()`](crate::Repository::notes_at()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("The notes reference name '{name}' is invalid")]
+        InvalidName {
+            name: crate::bstr::BString,
+            source: gix_ref::name::Error,
+        },
+    }
+}
+
+///
+pub mod display_refs {
+    /// The error returned by [`Repository::notes_display_refs()`](crate::Repository::notes_display_refs()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Init(#[from] super::init::Error),
+        #[error(transparent)]
+        OpenPackedRefs(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterInit(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        Iterate(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    }
+}
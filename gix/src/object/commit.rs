@@ -211,6 +211,18 @@ impl<'repo> Commit<'repo> {
         }
     }
 
+    /// Return the notes attached to this commit from all notes references that are configured for display,
+    /// in the order of [`Repository::notes_display_refs()`](crate::Repository::notes_display_refs()).
+    #[cfg(feature = "notes")]
+    pub fn notes(&self) -> Result<Vec<crate::note::Note<'repo>>, crate::commit::notes::Error> {
+        let mut out = Vec::new();
+        for name in self.repo.notes_display_refs()? {
+            let platform = crate::note::Platform { repo: self.repo, name };
+            out.extend(platform.find(self.id)?);
+        }
+        Ok(out)
+    }
+
     /// Extracts the PGP signature and the data that was used to create the signature, or `None` if it wasn't signed.
     // TODO: make it possible to verify the signature, probably by wrapping `SignedData`. It's quite some work to do it properly.
     pub fn signature(
@@ -226,8 +226,10 @@ impl ThreadSafeRepository {
             let ref_store_init_opts = gix_ref::store::init::Options {
                 write_reflog: reflog,
                 object_hash,
+                format: repo_config.ref_format,
                 precompose_unicode: repo_config.precompose_unicode,
                 prohibit_windows_device_names: repo_config.protect_windows,
+                fsync: None,
             };
             match &common_dir {
                 Some(common_dir) => {
@@ -428,6 +430,7 @@ impl ThreadSafeRepository {
 
         refs.write_reflog = config::cache::util::reflog_or_default(config.reflog, worktree_dir.is_some());
         refs.namespace.clone_from(&config.refs_namespace);
+        refs.fsync = config.fsync(gix_fs::fsync::Components::REFERENCE);
         let prefix = replacement_objects_refs_prefix(&config.resolved, lenient_config, filter_config_section)?;
 
         if *git_dir_trust == gix_sec::Trust::Reduced && config.alloc_limit_bytes.is_none() {
@@ -489,6 +492,7 @@ impl ThreadSafeRepository {
                     use_multi_pack_index: config.use_multi_pack_index,
                     alloc_limit_bytes: config.alloc_limit_bytes,
                     loose_compression: config.loose_compression,
+                    loose_fsync: config.fsync(gix_fs::fsync::Components::LOOSE_OBJECT),
                     current_dir: current_dir.to_owned().into(),
                 },
             )?),
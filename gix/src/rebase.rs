@@ -0,0 +1,147 @@
+pub use gix_rebase as plumbing;
+pub use gix_rebase::{
+    State,
+    replay::{Empty, Step},
+};
+
+use crate::Id;
+
+/// Define the commit onto which the commits of a branch are replayed with [`Repository::rebase()`](crate::Repository::rebase()).
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Onto {
+    /// Replay the commits onto the upstream commit, like `git rebase <upstream> <branch>` does.
+    #[default]
+    Upstream,
+    /// Replay the commits onto the given commit, like `git rebase --onto <commit> <upstream> <branch>` does.
+    Commit(gix_hash::ObjectId),
+    /// Replay the commits onto the merge-base of the upstream commit and the branch, which keeps the base of the branch,
+    /// like `git rebase --keep-base <upstream> <branch>` does.
+    KeepBase,
+}
+
+/// A way to configure [`Repository::rebase()`](crate::Repository::rebase()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    pub(crate) tree_merge: crate::merge::tree::Options,
+    pub(crate) treat_as_unresolved: crate::merge::tree::TreatAsUnresolved,
+    pub(crate) onto: Onto,
+    pub(crate) empty: Empty,
+    pub(crate) write_state: bool,
+}
+
+impl From<gix_merge::tree::Options> for Options {
+    fn from(value: gix_merge::tree::Options) -> Self {
+        crate::merge::tree::Options::from(value).into()
+    }
+}
+
+impl From<crate::merge::tree::Options> for Options {
+    fn from(value: crate::merge::tree::Options) -> Self {
+        Options {
+            tree_merge: value,
+            ..Default::default()
+        }
+    }
+}
+
+/// Builder
+impl Options {
+    /// Set the commit to replay the commits of the branch onto, which is the upstream commit by default.
+    pub fn with_onto(mut self, onto: Onto) -> Self {
+        self.onto = onto;
+        self
+    }
+
+    /// Determine what to do with commits that become empty as their changes are already present upstream.
+    pub fn with_empty(mut self, empty: Empty) -> Self {
+        self.empty = empty;
+        self
+    }
+
+    /// Determine which conflicts are considered unresolved, which stops the rebase at the commit that caused them.
+    pub fn with_treat_as_unresolved(mut self, treat_as_unresolved: crate::merge::tree::TreatAsUnresolved) -> Self {
+        self.treat_as_unresolved = treat_as_unresolved;
+        self
+    }
+
+    /// If `true`, when stopping at a conflict, write the state of the rebase into `.git/rebase-merge` and detach `HEAD`
+    /// at the last replayed commit so that `git rebase --continue` or `git rebase --abort` can pick it up.
+    ///
+    /// It's `false` by default, which leaves no trace of a rebase that stopped, besides the objects that were written.
+    pub fn with_write_state(mut self, write_state: bool) -> Self {
+        self.write_state = write_state;
+        self
+    }
+}
+
+/// Information about the commit a rebase stopped at as its changes couldn't be applied cleanly.
+#[derive(Clone)]
+pub struct Stop<'repo> {
+    /// The commit whose changes conflicted with the new base.
+    pub commit: Id<'repo>,
+    /// The outcome of merging the changes of `commit` into the last replayed commit, with at least one unresolved conflict.
+    pub tree_merge: crate::merge::tree::Outcome<'repo>,
+    /// The commits that remain to be replayed after `commit`, oldest first.
+    pub remaining: Vec<gix_hash::ObjectId>,
+}
+
+/// The outcome produced by [`Repository::rebase()`](crate::Repository::rebase()).
+#[derive(Clone)]
+pub struct Outcome<'repo> {
+    /// The commit the branch was rebased onto.
+    pub onto: Id<'repo>,
+    /// The commit that was replayed last, or `onto` if nothing was replayed.
+    /// If the rebase didn't stop, this is the new tip of the branch.
+    pub head: Id<'repo>,
+    /// All commits that were replayed successfully, in order.
+    pub steps: Vec<Step>,
+    /// The commit the rebase stopped at due to conflicts, or `None` if all commits were replayed and the branch was updated.
+    pub stopped: Option<Stop<'repo>>,
+}
+
+/// The error returned by [`Repository::rebase()`](crate::Repository::rebase()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("A rebase is already in progress")]
+    InProgress,
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error(transparent)]
+    CommitterTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    MergeBase(#[from] crate::repository::merge_base::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkIter(#[from] crate::revision::walk::iter::Error),
+    #[error(transparent)]
+    MergeResourceCache(#[from] crate::repository::merge_resource_cache::Error),
+    #[error(transparent)]
+    DiffResourceCache(#[from] crate::repository::diff_resource_cache::Error),
+    #[error(transparent)]
+    ValidationOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Rebase(#[from] gix_error::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error("Could not write '{}'", path.display())]
+    WriteFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+///
+pub mod state {
+    /// The error returned by [`Repository::rebase_state()`](crate::Repository::rebase_state()).
+    pub type Error = gix_error::Error;
+}
@@ -0,0 +1,391 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_protocol::{
+    push::{Command, report::Status},
+    receive_pack::{DelegateError, Update},
+};
+use gix_ref::{
+    FullName, Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+use super::{Check, DenyCurrentBranch, Error};
+use crate::{
+    Repository,
+    bstr::{BString, ByteSlice},
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Pack, Receive},
+    },
+};
+
+/// Serve `receive-pack` into a repository.
+pub(crate) struct Delegate<'repo, 'check> {
+    repo: &'repo Repository,
+    check: &'check mut dyn FnMut(Check<'_>) -> Result<(), BString>,
+    deny_current_branch: DenyCurrentBranch,
+    deny_deletes: bool,
+    deny_non_fast_forwards: bool,
+    write_options: gix_pack::bundle::write::Options,
+    /// The objects that were received with the current push, if any.
+    quarantine: Option<Quarantine>,
+}
+
+/// A directory with the received pack, which is removed along with everything in it when dropped.
+struct Quarantine {
+    /// A repository that can see the received objects as well as all objects of the repository receiving them.
+    repo: Repository,
+    dir: PathBuf,
+    pack: gix_pack::bundle::write::Outcome,
+}
+
+impl Drop for Quarantine {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+impl<'repo, 'check> Delegate<'repo, 'check> {
+    pub fn new(
+        repo: &'repo Repository,
+        check: &'check mut dyn FnMut(Check<'_>) -> Result<(), BString>,
+    ) -> Result<Self, Error> {
+        let config = &repo.config.resolved;
+        let lenient = repo.options.lenient_config;
+        let boolean = |key: &'static crate::config::tree::keys::Boolean| -> Result<bool, Error> {
+            Ok(key
+                .enrich_error(config.boolean_filter(key, &mut repo.filter_config_section()))
+                .with_leniency(lenient)?
+                .unwrap_or(false))
+        };
+        Ok(Delegate {
+            repo,
+            check,
+            deny_current_branch: config
+                .string_filter(Receive::DENY_CURRENT_BRANCH, &mut repo.filter_config_section())
+                .map(|value| Receive::DENY_CURRENT_BRANCH.try_into_deny_current_branch(value))
+                .transpose()
+                .with_leniency(lenient)?
+                .unwrap_or_default(),
+            deny_deletes: boolean(&Receive::DENY_DELETES)?,
+            deny_non_fast_forwards: boolean(&Receive::DENY_NON_FAST_FORWARDS)?,
+            write_options: gix_pack::bundle::write::Options {
+                thread_limit: Pack::THREADS
+                    .try_into_usize(config.integer_filter(Pack::THREADS, &mut repo.filter_config_section()))
+                    .with_leniency(lenient)?,
+                index_version: Pack::INDEX_VERSION
+                    .try_into_index_version(config.integer(Pack::INDEX_VERSION))
+                    .with_leniency(lenient)?
+                    .unwrap_or(gix_pack::index::Version::V2),
+                iteration_mode: gix_pack::data::input::Mode::Verify,
+                object_hash: repo.object_hash(),
+                alloc_limit_bytes: repo.config.alloc_limit_bytes,
+                compression: repo.config.loose_compression,
+                bitmap: None,
+                fsync: repo.config.fsync(gix_fs::fsync::Components::PACK),
+                fsync_metadata: repo.config.fsync(gix_fs::fsync::Components::PACK_METADATA),
+            },
+            quarantine: None,
+        })
+    }
+
+    /// Create an empty object directory within our own that has access to all of our objects, and return it along with
+    /// a repository which uses it as object database.
+    fn create_quarantine(&self) -> Result<(PathBuf, Repository), DelegateError> {
+        let objects_dir = gix_path::realpath(self.repo.objects.store_ref().path())?;
+        let mut attempt = 0;
+        let dir = loop {
+            let dir = objects_dir.join(format!("incoming-{}-{attempt}", std::process::id()));
+            match std::fs::create_dir(&dir) {
+                Ok(()) => break dir,
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+                Err(err) => return Err(err.into()),
+            }
+        };
+        std::fs::create_dir_all(dir.join("info"))?;
+        std::fs::create_dir(dir.join("pack"))?;
+        std::fs::write(
+            dir.join("info").join("alternates"),
+            gix_path::into_bstr(objects_dir.as_path()).as_ref(),
+        )?;
+        let handle = gix_odb::at_opts(
+            dir.clone(),
+            None,
+            gix_odb::store::init::Options {
+                slots: Default::default(),
+                object_hash: self.repo.object_hash(),
+                use_multi_pack_index: true,
+                alloc_limit_bytes: self.repo.config.alloc_limit_bytes,
+                loose_compression: self.repo.config.loose_compression,
+                loose_fsync: self.repo.config.fsync(gix_fs::fsync::Components::LOOSE_OBJECT),
+                current_dir: None,
+            },
+        )?;
+        let mut repo = self.repo.clone();
+        repo.objects = handle.into();
+        Ok((dir, repo))
+    }
+
+    /// Return `true` if all objects reachable from `id` are present, assuming that everything is connected that is in
+    /// our object database, i.e. not in the quarantine.
+    fn is_connected(
+        &self,
+        repo: &Repository,
+        id: ObjectId,
+        seen: &mut HashSet<ObjectId>,
+    ) -> Result<bool, DelegateError> {
+        let mut queue = vec![id];
+        while let Some(id) = queue.pop() {
+            if !seen.insert(id) || self.repo.has_object(id) {
+                continue;
+            }
+            let Some(object) = repo.try_find_object(id)? else {
+                return Ok(false);
+            };
+            match object.kind {
+                gix_object::Kind::Commit => {
+                    let commit = object.into_commit();
+                    let commit = commit.decode()?;
+                    queue.push(commit.tree());
+                    queue.extend(commit.parents());
+                }
+                gix_object::Kind::Tree => {
+                    let tree = object.into_tree();
+                    queue.extend(
+                        tree.decode()?
+                            .entries
+                            .iter()
+                            .filter(|entry| !entry.mode.is_commit())
+                            .map(|entry| entry.oid.to_owned()),
+                    );
+                }
+                gix_object::Kind::Tag => queue.push(object.into_tag().target_id()?.detach()),
+                gix_object::Kind::Blob => {}
+            }
+        }
+        Ok(true)
+    }
+
+    /// Validate `command` with everything but the policy check, returning the reason for refusing it.
+    fn validate(
+        &self,
+        repo: &Repository,
+        command: &Command,
+        head_name: Option<&FullName>,
+        seen: &mut HashSet<ObjectId>,
+    ) -> Result<Option<&'static str>, DelegateError> {
+        let Ok(name) = FullName::try_from(command.name.as_bstr()) else {
+            return Ok(Some("funny refname"));
+        };
+        if !command.name.starts_with(b"refs/") {
+            return Ok(Some("funny refname"));
+        }
+        let is_head = head_name == Some(&name);
+        let is_branch = command.name.starts_with(b"refs/heads/");
+        if is_head
+            && !self.repo.is_bare()
+            && matches!(
+                self.deny_current_branch,
+                DenyCurrentBranch::Refuse | DenyCurrentBranch::UpdateInstead
+            )
+        {
+            return Ok(Some("branch is currently checked out"));
+        }
+        if command.new.is_null() {
+            if self.deny_deletes && is_branch {
+                return Ok(Some("deletion prohibited"));
+            }
+            if is_head {
+                return Ok(Some("deletion of the current branch prohibited"));
+            }
+            return Ok(None);
+        }
+        if !self.is_connected(repo, command.new, seen)? {
+            return Ok(Some("missing necessary objects"));
+        }
+        if self.deny_non_fast_forwards && is_branch && !command.old.is_null() {
+            let mut ancestors = repo.rev_walk([command.new]).all()?;
+            if !ancestors.any(|info| info.is_ok_and(|info| info.id == command.old)) {
+                return Ok(Some("non-fast-forward"));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Move the received pack into our object database, making its objects visible.
+    fn migrate_quarantine(&mut self) -> Result<(), DelegateError> {
+        let Some(quarantine) = self.quarantine.as_mut() else {
+            return Ok(());
+        };
+        let pack_dir = self.repo.objects.store_ref().path().join("pack");
+        let migrate = |path: &mut Option<PathBuf>| -> std::io::Result<()> {
+            if let Some(source) = path.as_ref() {
+                let destination = pack_dir.join(source.file_name().expect("files in the pack directory"));
+                std::fs::rename(source, &destination)?;
+                *path = Some(destination);
+            }
+            Ok(())
+        };
+        // The index goes last as it makes the pack visible.
+        migrate(&mut quarantine.pack.keep_path)?;
+        migrate(&mut quarantine.pack.data_path)?;
+        migrate(&mut quarantine.pack.index_path)?;
+        Ok(())
+    }
+}
+
+impl gix_protocol::receive_pack::Delegate for Delegate<'_, '_> {
+    fn object_hash(&self) -> gix_hash::Kind {
+        self.repo.object_hash()
+    }
+
+    fn refs(&mut self) -> Result<Vec<(BString, ObjectId)>, DelegateError> {
+        let mut out = Vec::new();
+        for reference in self.repo.references()?.all()? {
+            let reference = reference?;
+            if let Some(id) = reference.target().try_id() {
+                out.push((reference.name().as_bstr().to_owned(), id.to_owned()));
+            }
+        }
+        Ok(out)
+    }
+
+    fn receive_pack(
+        &mut self,
+        pack: &mut dyn std::io::BufRead,
+        _commands: &[Command],
+        progress: &mut dyn std::io::Write,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), DelegateError> {
+        let (dir, repo) = self.create_quarantine()?;
+        let pack = gix_pack::Bundle::write_to_directory(
+            pack,
+            Some(&dir.join("pack")),
+            &mut gix_features::progress::Discard,
+            should_interrupt,
+            Some(Box::new(self.repo.objects.clone())),
+            self.write_options.clone(),
+        );
+        let quarantine = self.quarantine.insert(Quarantine {
+            repo,
+            pack: match pack {
+                Ok(pack) => pack,
+                Err(err) => {
+                    std::fs::remove_dir_all(&dir).ok();
+                    return Err(err.into());
+                }
+            },
+            dir,
+        });
+        writeln!(
+            progress,
+            "Received {} objects, done.",
+            quarantine.pack.index.num_objects
+        )?;
+        Ok(())
+    }
+
+    fn update_refs(&mut self, commands: &[Command], update: Update<'_>) -> Result<Vec<Status>, DelegateError> {
+        let repo = self
+            .quarantine
+            .as_ref()
+            .map_or(self.repo, |quarantine| &quarantine.repo);
+        let head_name = self.repo.head_name()?;
+        let mut seen = HashSet::default();
+        let mut statuses = Vec::with_capacity(commands.len());
+        for command in commands {
+            let reason = match self.validate(repo, command, head_name.as_ref(), &mut seen)? {
+                Some(reason) => Some(reason.into()),
+                None => (self.check)(Check {
+                    repo,
+                    command,
+                    push_options: update.push_options,
+                })
+                .err(),
+            };
+            statuses.push(reason.map_or(Status::Ok, Status::Rejected));
+        }
+        if update.atomic && statuses.iter().any(|status| matches!(status, Status::Rejected(_))) {
+            for status in &mut statuses {
+                if *status == Status::Ok {
+                    *status = Status::Rejected("atomic push failure".into());
+                }
+            }
+        }
+
+        let edits: Vec<_> = commands
+            .iter()
+            .zip(&statuses)
+            .enumerate()
+            .filter(|(_, (_, status))| **status == Status::Ok)
+            .map(|(index, (command, _))| to_edit(command).map(|edit| (index, edit)))
+            .collect::<Result<_, _>>()?;
+        if edits
+            .iter()
+            .any(|(_, edit)| matches!(edit.change, Change::Update { .. }))
+        {
+            self.migrate_quarantine()?;
+        }
+
+        let mut repo = self.repo.clone();
+        repo.committer_or_set_generic_fallback()?;
+        if update.atomic {
+            if !edits.is_empty() && repo.edit_references(edits.into_iter().map(|(_, edit)| edit)).is_err() {
+                for status in &mut statuses {
+                    *status = Status::Rejected("failed to update refs".into());
+                }
+            }
+        } else {
+            for (index, edit) in edits {
+                if repo.edit_reference(edit).is_err() {
+                    statuses[index] = Status::Rejected("failed to update ref".into());
+                }
+            }
+        }
+
+        // Remove the quarantine before reporting, along with the `.keep` file that protected the migrated pack.
+        if let Some(keep) = self
+            .quarantine
+            .take()
+            .and_then(|mut quarantine| quarantine.pack.keep_path.take())
+        {
+            std::fs::remove_file(keep).ok();
+        }
+        Ok(statuses)
+    }
+}
+
+/// Turn `command` into an edit for the reference it names, which is assumed to be valid.
+fn to_edit(command: &Command) -> Result<RefEdit, DelegateError> {
+    let expected = if command.old.is_null() {
+        if command.new.is_null() {
+            PreviousValue::MustExist
+        } else {
+            PreviousValue::MustNotExist
+        }
+    } else {
+        PreviousValue::MustExistAndMatch(Target::Object(command.old))
+    };
+    Ok(RefEdit {
+        change: if command.new.is_null() {
+            Change::Delete {
+                expected,
+                log: RefLog::AndReference,
+            }
+        } else {
+            Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: "push".into(),
+                },
+                expected,
+                new: Target::Object(command.new),
+            }
+        },
+        name: command.name.clone().try_into()?,
+        deref: false,
+    })
+}
@@ -0,0 +1,114 @@
+//! Accept pushes into a repository by serving the `receive-pack` service.
+//!
+//! Use [`Repository::receive_pack()`](crate::Repository::receive_pack()) to serve a client through any `Read` and `Write` pair,
+//! or [`in_process()`] to obtain a transport for pushing into a repository without spawning `git receive-pack`.
+//!
+//! Received objects are kept in a quarantine directory until all reference updates were validated, and are only
+//! moved into the object database of the repository if at least one of them is performed.
+
+/// All possible values of `receive.denyCurrentBranch`.
+#[derive(Default, Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash, Debug)]
+pub enum DenyCurrentBranch {
+    /// Refuse to update the branch that is checked out in a non-bare repository.
+    #[default]
+    Refuse,
+    /// Allow the update, which leaves the worktree and index out of sync with the branch.
+    ///
+    /// Note that no warning is sent to the client.
+    Warn,
+    /// Allow the update just like [`Warn`](DenyCurrentBranch::Warn).
+    Ignore,
+    /// Update the worktree along with the branch if it is clean.
+    ///
+    /// This isn't supported, and updates to the checked-out branch are refused instead.
+    UpdateInstead,
+}
+
+#[cfg(feature = "blocking-network-client")]
+pub use gix_protocol::{
+    push::{Command, report::Status},
+    receive_pack::Update,
+};
+
+#[cfg(feature = "blocking-network-client")]
+mod delegate;
+#[cfg(feature = "blocking-network-client")]
+pub(crate) use delegate::Delegate;
+
+/// The error returned by [`Repository::receive_pack()`](crate::Repository::receive_pack()).
+#[cfg(feature = "blocking-network-client")]
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    ReceivePack(#[from] gix_protocol::receive_pack::Error),
+    #[error(transparent)]
+    BooleanConfig(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    DenyCurrentBranch(#[from] crate::config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    IndexThreads(#[from] crate::config::unsigned_integer::Error),
+    #[error(transparent)]
+    IndexVersion(#[from] crate::config::key::GenericError),
+}
+
+/// A reference update that was requested by a client, to be validated by a policy before it's performed.
+#[cfg(feature = "blocking-network-client")]
+#[derive(Clone, Copy)]
+pub struct Check<'a> {
+    /// The repository which is receiving the push, which is able to see all objects that were received with it.
+    pub repo: &'a crate::Repository,
+    /// The reference update to validate.
+    pub command: &'a Command,
+    /// The push options sent along with the commands, like `git push --push-option`.
+    pub push_options: &'a [crate::bstr::BString],
+}
+
+/// The error returned by [`in_process()`].
+#[cfg(feature = "blocking-network-client")]
+pub type InProcessError = std::io::Error;
+
+/// The transport returned by [`in_process()`].
+#[cfg(feature = "blocking-network-client")]
+pub type InProcessTransport =
+    gix_transport::client::git::blocking_io::Connection<gix_features::io::pipe::Reader, gix_features::io::pipe::Writer>;
+
+/// Return a transport to push into `repo`, which is served by [`Repository::receive_pack()`](crate::Repository::receive_pack())
+/// in a separate thread, with `check` validating each reference update.
+///
+/// The thread ends when the transport is dropped. Errors on the serving side are sent to the client,
+/// which is also how they are observed.
+///
+/// This is useful with [`Remote::to_connection_with_transport()`](crate::Remote::to_connection_with_transport()) to push
+/// into local repositories without spawning `git receive-pack`.
+#[cfg(feature = "blocking-network-client")]
+pub fn in_process(
+    repo: crate::ThreadSafeRepository,
+    mut check: impl FnMut(Check<'_>) -> Result<(), crate::bstr::BString> + Send + 'static,
+) -> Result<InProcessTransport, InProcessError> {
+    let trace = repo.config.trace_packet();
+    let path = gix_path::into_bstr(repo.git_dir()).into_owned();
+    let (client_write, server_read) = gix_features::io::pipe::unidirectional(16);
+    let (server_write, client_read) = gix_features::io::pipe::unidirectional(16);
+    std::thread::Builder::new()
+        .name("gix::receive_pack::in_process".into())
+        .spawn(move || {
+            let repo = repo.to_thread_local();
+            repo.receive_pack(
+                server_read,
+                server_write,
+                &mut check,
+                &std::sync::atomic::AtomicBool::default(),
+            )
+            .ok();
+        })?;
+    Ok(InProcessTransport::new(
+        client_read,
+        client_write,
+        gix_transport::Protocol::V1,
+        path,
+        None::<(&str, _)>,
+        gix_transport::client::git::ConnectMode::Process,
+        trace,
+    ))
+}
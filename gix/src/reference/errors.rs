@@ -19,6 +19,8 @@ pub mod edit {
         LockTimeoutConfiguration(#[from] config::lock_timeout::Error),
         #[error(transparent)]
         ParseCommitterTime(#[from] crate::config::time::Error),
+        #[error("Could not make loose objects written in batch mode durable before referencing them")]
+        FlushLooseObjectBatch(#[source] std::io::Error),
     }
 }
 
@@ -108,6 +110,22 @@ pub mod head_tree_id {
     }
 }
 
+///
+pub mod fetch_head {
+    /// The error returned by [`Repository::fetch_head`(…)](crate::Repository::fetch_head()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Could not read FETCH_HEAD at \"{}\"", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Parse(#[from] gix_error::Error),
+    }
+}
+
 ///
 pub mod head_tree {
     /// The error returned by [`Repository::head_tree`(…)](crate::Repository::head_tree()).
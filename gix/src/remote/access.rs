@@ -88,6 +88,15 @@ impl RemoteDetached {
     pub(crate) fn fetch_refspecs(&self) -> &[RefSpec] {
         &self.fetch_specs
     }
+
+    /// Return the first url used for fetching, with rewrites applied, just like [`Remote::url()`].
+    pub(crate) fn fetch_url(&self) -> Option<&gix_url::Url> {
+        self.urls
+            .iter()
+            .zip(&self.url_aliases)
+            .map(|(url, alias)| alias.as_ref().unwrap_or(url))
+            .next()
+    }
 }
 
 /// Modification
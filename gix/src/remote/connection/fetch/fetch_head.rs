@@ -0,0 +1,112 @@
+use gix_fetchhead::Entry;
+use gix_protocol::fetch::refmap::{Source, SpecIndex};
+use gix_ref::FullName;
+
+use super::{Error, refs, refs::update::Mode};
+use crate::{Repository, bstr::BString, remote, remote::fetch::RefMap, types::RemoteDetached};
+
+/// Write `FETCH_HEAD` into the git directory of `repo` with one line per remote ref or object that was fetched
+/// as described by `ref_map` and `update_refs`, similar to what `git fetch` does.
+///
+/// Lines for objects to merge are written first, and they are determined as follows:
+///
+/// - if the current branch is configured to merge from `remote` with `branch.<name>.merge` and `branch.<name>.remote`,
+///   that remote ref is to be merged.
+/// - otherwise, if `remote` is anonymous, everything matched by its refspecs is to be merged, just like refspecs passed on the
+///   command-line.
+/// - otherwise, if the current branch is configured to merge from another remote, nothing is to be merged.
+/// - otherwise, the remote ref matched by the first refspec is to be merged if it isn't a pattern.
+///
+/// Tags that are followed automatically are never to be merged.
+pub(crate) fn write(
+    repo: &Repository,
+    remote: &RemoteDetached,
+    ref_map: &RefMap,
+    update_refs: &refs::update::Outcome,
+) -> Result<(), Error> {
+    let url = remote
+        .fetch_url()
+        .map(|url| {
+            // Like `git`, never write credentials into `FETCH_HEAD`.
+            let mut url = url.clone();
+            url.set_user(None);
+            url.set_password(None);
+            gix_fetchhead::shorten_url(url.to_bstring().as_ref()).to_owned()
+        })
+        .unwrap_or_default();
+    let refspecs = remote.fetch_refspecs();
+    let merge = merge_config(repo, remote);
+    let first_spec_is_pattern = refspecs
+        .first()
+        .is_none_or(|spec| spec.to_ref().source().is_none_or(|source| source.contains(&b'*')));
+
+    let mut entries: Vec<_> = update_refs
+        .iter_mapping_updates(&ref_map.mappings, refspecs, &ref_map.extra_refspecs)
+        .filter(|(update, ..)| {
+            !matches!(
+                update.mode,
+                Mode::ImplicitTagNotSentByRemote | Mode::RejectedSourceObjectNotFound { .. }
+            )
+        })
+        .filter_map(|(_update, mapping, _spec, _edit)| {
+            let id = mapping.remote.as_id()?.to_owned();
+            let remote_ref: BString = match &mapping.remote {
+                Source::ObjectId(id) => id.to_string().into(),
+                Source::Ref(_) => mapping.remote.as_name()?.to_owned(),
+            };
+            let for_merge = match mapping.spec_index {
+                SpecIndex::Implicit(_) => false,
+                SpecIndex::ExplicitInRemote(idx) => match &merge {
+                    Some(merge) if merge.is_from_remote => remote_ref == merge.remote_ref.as_bstr(),
+                    _ if remote.name().is_none() => true,
+                    Some(_) => false,
+                    None => idx == 0 && !first_spec_is_pattern,
+                },
+            };
+            Some(Entry {
+                id,
+                for_merge,
+                remote_ref: Some(remote_ref),
+                url: url.clone(),
+            })
+        })
+        .collect();
+    entries.sort_by_key(|entry| !entry.for_merge);
+
+    let mut buf = Vec::new();
+    for entry in &entries {
+        entry.write_to(&mut buf).expect("writing to a vector never fails");
+    }
+    let path = repo.git_dir().join("FETCH_HEAD");
+    std::fs::write(&path, buf).map_err(|source| Error::WriteFetchHead { path, source })
+}
+
+/// What the current branch is configured to merge.
+struct MergeConfig {
+    /// The remote ref to merge, as configured by `branch.<name>.merge`.
+    remote_ref: FullName,
+    /// `true` if `branch.<name>.remote` is the remote we fetch from.
+    is_from_remote: bool,
+}
+
+/// Return the merge configuration of the current branch, or `None` if there is no current branch or it doesn't merge anything.
+///
+/// Like `git`, `branch.<name>.remote` has to match the name of `remote`, which is its URL if it is anonymous.
+fn merge_config(repo: &Repository, remote: &RemoteDetached) -> Option<MergeConfig> {
+    let head_name = repo.head_name().ok()??;
+    let remote_ref = repo
+        .branch_remote_ref_name(head_name.as_ref(), remote::Direction::Fetch)?
+        .ok()?;
+    let remote_name = match remote.name() {
+        Some(name) => Some(name.as_bstr().to_owned()),
+        None => remote.urls.first().map(gix_url::Url::to_bstring),
+    };
+    let is_from_remote = repo
+        .branch_remote_name(head_name.shorten(), remote::Direction::Fetch)
+        .zip(remote_name)
+        .is_some_and(|(branch_remote_name, remote_name)| branch_remote_name.as_bstr() == remote_name);
+    Some(MergeConfig {
+        remote_ref,
+        is_from_remote,
+    })
+}
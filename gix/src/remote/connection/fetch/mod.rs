@@ -170,6 +170,7 @@ where
             dry_run: DryRun::No,
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
+            write_fetch_head: None,
             shallow: Default::default(),
         })
     }
@@ -196,6 +197,7 @@ where
 }
 
 mod config;
+mod fetch_head;
 mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
@@ -220,6 +222,7 @@ where
     dry_run: DryRun,
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
+    write_fetch_head: Option<bool>,
     shallow: remote::fetch::Shallow,
 }
 
@@ -252,6 +255,14 @@ where
         self
     }
 
+    /// If `enabled`, write the fetched refs into `FETCH_HEAD` after updating refs, or don't touch it otherwise.
+    ///
+    /// If unset, `fetch.writeFetchHead` is used, which defaults to `true`. Nothing is written in dry-run mode.
+    pub fn with_write_fetch_head(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_write_fetch_head(enabled);
+        self
+    }
+
     /// Define what to do when the current repository is a shallow clone.
     ///
     /// *Has no effect if the current repository is not as shallow clone.*
@@ -284,4 +295,9 @@ where
         self.shallow = shallow;
         self
     }
+
+    pub(crate) fn with_write_fetch_head(mut self, enabled: bool) -> Self {
+        self.write_fetch_head = Some(enabled);
+        self
+    }
 }
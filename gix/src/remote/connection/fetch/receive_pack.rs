@@ -13,7 +13,7 @@ use crate::{
         tree::{Clone, Fetch},
     },
     remote::{
-        connection::fetch::{PrepareDetached, config},
+        connection::fetch::{PrepareDetached, config, fetch_head},
         fetch,
         fetch::{Error, Outcome, Prepare, RefLogMessage, Status, negotiate::Algorithm, outcome, refs},
     },
@@ -174,6 +174,9 @@ where
             object_hash: repo.object_hash(),
             alloc_limit_bytes: repo.config.alloc_limit_bytes,
             compression: repo.config.loose_compression,
+            bitmap: None,
+            fsync: repo.config.fsync(gix_fs::fsync::Components::PACK),
+            fsync_metadata: repo.config.fsync(gix_fs::fsync::Components::PACK_METADATA),
         };
         let mut write_pack_bundle = None;
 
@@ -230,6 +233,22 @@ where
             self.write_packed_refs,
         )?;
 
+        let write_fetch_head = match self.write_fetch_head {
+            Some(enabled) => enabled,
+            None => Fetch::WRITE_FETCH_HEAD
+                .enrich_error(
+                    repo.config
+                        .resolved
+                        .boolean_filter(Fetch::WRITE_FETCH_HEAD, &mut repo.filter_config_section()),
+                )
+                .with_leniency(repo.config.lenient_config)
+                .map_err(Error::WriteFetchHeadConfig)?
+                .unwrap_or(true),
+        };
+        if write_fetch_head && matches!(self.dry_run, fetch::DryRun::No) {
+            fetch_head::write(repo, &con.remote, &self.ref_map, &update_refs)?;
+        }
+
         if let Some(bundle) = write_pack_bundle.as_mut() {
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
                 if let Some(path) = bundle.keep_path.take() {
@@ -44,3 +44,7 @@ pub mod ref_map;
 
 ///
 pub mod fetch;
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod push;
@@ -0,0 +1,35 @@
+/// The error returned by [`Prepare::push()`](crate::remote::push::Prepare::push()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    InitWalk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::iter::Error),
+    #[error("Could not prepare the object database for use in multiple threads")]
+    OpenObjectDatabase(#[source] std::io::Error),
+    #[error(transparent)]
+    PackCompression(#[from] crate::config::Error),
+    #[cfg(feature = "attributes")]
+    #[error(transparent)]
+    NoDelta(#[from] crate::repository::pack_no_delta::Error),
+    #[error("Failed to count the objects to send")]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    Push(#[from] gix_protocol::push::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error("Could not update the references tracking the remote")]
+    UpdateTrackingRefs(#[from] crate::reference::edit::Error),
+}
+
+impl gix_protocol::transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Push(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
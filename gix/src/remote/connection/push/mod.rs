@@ -0,0 +1,242 @@
+use gix_features::progress::Progress;
+use gix_transport::client::blocking_io::Transport;
+
+use crate::{bstr::BString, remote::Connection};
+
+mod error;
+pub use error::Error;
+
+mod send_pack;
+
+/// The error returned by [`Connection::prepare_push()`].
+pub mod prepare {
+    use crate::bstr::BString;
+
+    /// The error returned by [`prepare_push()`][crate::remote::Connection::prepare_push()].
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Failed to configure the transport before connecting to {url:?}")]
+        GatherTransportConfig {
+            url: BString,
+            source: crate::config::transport::Error,
+        },
+        #[error("Failed to configure the transport layer")]
+        ConfigureTransport(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        Handshake(#[from] gix_protocol::handshake::Error),
+        #[error("The server didn't advertise its references, which is required for pushing")]
+        MissingRefs,
+    }
+
+    impl gix_protocol::transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Handshake(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+
+pub use gix_protocol::push::ProgressId;
+
+/// A reference on the remote to update as part of a push.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Update {
+    /// The object to set the remote reference to, or `None` to delete it.
+    pub source: Option<gix_hash::ObjectId>,
+    /// The full name of the reference on the remote, like `refs/heads/main`.
+    pub destination: gix_ref::FullName,
+    /// If `true`, update the remote reference even if that's not a fast-forward, which loses the commits it points to.
+    pub force: bool,
+}
+
+/// The status of an [`Update`] after the push.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// The remote reference already had the desired value, so nothing was sent for it.
+    UpToDate,
+    /// The remote updated the reference, or is about to be asked to do so.
+    Ok {
+        /// If `true`, the update wasn't a fast-forward, and the remote reference lost the commits it previously pointed to.
+        forced: bool,
+    },
+    /// The update wasn't a fast-forward, and [`force`](Update::force) wasn't set.
+    RejectedNonFastForward,
+    /// The remote reference is a tag that already exists, and [`force`](Update::force) wasn't set.
+    RejectedAlreadyExists,
+    /// The object the remote reference points to isn't present locally, so it can't be determined if the update is a fast-forward.
+    ///
+    /// This can be resolved by fetching first.
+    RejectedFetchFirst,
+    /// The update wasn't attempted as another update of this atomic push was rejected.
+    RejectedAtomicPushFailed,
+    /// The remote refused to perform the update for the given `reason`.
+    RejectedByRemote {
+        /// The reason the remote gave.
+        reason: BString,
+    },
+}
+
+impl Status {
+    /// Return `true` if the remote reference has the desired value after the push.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Status::UpToDate | Status::Ok { .. })
+    }
+}
+
+/// The outcome of a push.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The outcome of the handshake with the server.
+    pub handshake: gix_protocol::Handshake,
+    /// The outcome of each requested update, in order.
+    pub updates: Vec<outcome::Update>,
+    /// The report of the server, if anything was sent to it.
+    pub report: Option<gix_protocol::push::Report>,
+}
+
+impl Outcome {
+    /// Return `true` if all requested updates were performed, or were unnecessary.
+    pub fn is_success(&self) -> bool {
+        self.updates.iter().all(|update| update.status.is_success())
+    }
+}
+
+/// Additional types related to the outcome of a push operation.
+pub mod outcome {
+    /// The outcome of a single [`Update`](super::Update).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Update {
+        /// The full name of the reference on the remote.
+        pub destination: gix_ref::FullName,
+        /// The object the remote reference pointed to before the push, if it existed.
+        pub previous: Option<gix_hash::ObjectId>,
+        /// The object the remote reference was supposed to point to after the push, or `None` if it was supposed to be deleted.
+        pub new: Option<gix_hash::ObjectId>,
+        /// What happened to the remote reference.
+        pub status: super::Status,
+        /// The local reference tracking the remote reference, like `refs/remotes/origin/main`, if it was updated after the push.
+        pub tracking_ref: Option<gix_ref::FullName>,
+    }
+}
+
+/// A structure to hold the result of the handshake with the remote and configure the upcoming push operation.
+pub struct Prepare<'remote, 'auth, 'repo, T>
+where
+    T: Transport,
+{
+    con: Connection<'remote, 'auth, 'repo, T>,
+    handshake: gix_protocol::Handshake,
+    atomic: bool,
+    push_options: Vec<BString>,
+    update_tracking_refs: bool,
+}
+
+impl<'remote, 'auth, 'repo, T> Connection<'remote, 'auth, 'repo, T>
+where
+    T: Transport,
+{
+    /// Perform a handshake with the `receive-pack` service of the remote to learn about its references, in preparation
+    /// for a [push](Prepare::push()).
+    ///
+    /// The connection is expected to be obtained with [`Direction::Push`](crate::remote::Direction::Push), and must not have
+    /// been used for anything else.
+    /// Note that at this point, the `transport` should already be configured using the [`transport_mut()`][Self::transport_mut()]
+    /// method, as it will be consumed here.
+    ///
+    /// Pushing is only supported in blocking mode.
+    ///
+    /// ### Configuration
+    ///
+    /// - `gitoxide.userAgent` is read to obtain the application user agent for git servers and for HTTP servers as well.
+    pub fn prepare_push(
+        mut self,
+        mut progress: impl Progress,
+    ) -> Result<Prepare<'remote, 'auth, 'repo, T>, prepare::Error> {
+        let _span = gix_trace::coarse!("remote::Connection::prepare_push()");
+        let repo = self.remote.repo;
+        let mut credentials_storage;
+        let url = self.transport.inner.to_url();
+        let authenticate = match self.authenticate.as_mut() {
+            Some(f) => f,
+            None => {
+                credentials_storage = self.configured_credentials_for_current_url();
+                &mut credentials_storage
+            }
+        };
+
+        if self.transport_options.is_none() {
+            self.transport_options = repo
+                .transport_options(url.as_ref(), self.remote.name().map(crate::remote::Name::as_bstr))
+                .map_err(|err| prepare::Error::GatherTransportConfig {
+                    source: err,
+                    url: url.into_owned(),
+                })?;
+        }
+        if let Some(config) = self.transport_options.as_ref() {
+            self.transport.inner.configure(&**config)?;
+        }
+        let handshake = gix_protocol::handshake(
+            &mut self.transport.inner,
+            gix_transport::Service::ReceivePack,
+            authenticate,
+            Vec::new(),
+            &mut progress,
+        )?;
+        if handshake.refs.is_none() {
+            return Err(prepare::Error::MissingRefs);
+        }
+        Ok(Prepare {
+            con: self,
+            handshake,
+            atomic: false,
+            push_options: Vec::new(),
+            update_tracking_refs: true,
+        })
+    }
+}
+
+/// Access
+impl<T> Prepare<'_, '_, '_, T>
+where
+    T: Transport,
+{
+    /// Return the outcome of the handshake, which includes the capabilities of the server.
+    pub fn handshake(&self) -> &gix_protocol::Handshake {
+        &self.handshake
+    }
+
+    /// Return all references advertised by the remote.
+    pub fn remote_refs(&self) -> &[gix_protocol::handshake::Ref] {
+        self.handshake.refs.as_deref().unwrap_or_default()
+    }
+}
+
+/// Builder
+impl<T> Prepare<'_, '_, '_, T>
+where
+    T: Transport,
+{
+    /// If `enabled`, ask the remote to either perform all updates or none of them, like `git push --atomic`.
+    ///
+    /// Note that updates rejected locally prevent anything from being sent in this mode.
+    pub fn with_atomic(mut self, enabled: bool) -> Self {
+        self.atomic = enabled;
+        self
+    }
+
+    /// Pass `options` to the hooks of the remote, like `git push --push-option`.
+    pub fn with_push_options(mut self, options: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.push_options = options.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// If `enabled`, the default, update the local references that track the updated remote references
+    /// according to the fetch refspecs of the remote.
+    pub fn with_update_tracking_refs(mut self, enabled: bool) -> Self {
+        self.update_tracking_refs = enabled;
+        self
+    }
+}
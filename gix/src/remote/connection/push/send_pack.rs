@@ -0,0 +1,394 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_features::{
+    parallel::InOrderIter,
+    progress::{Count, DynNestedProgress, Progress},
+};
+use gix_hash::ObjectId;
+use gix_pack::data::output;
+use gix_ref::{
+    FullName,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+use gix_transport::client::blocking_io::Transport;
+
+use crate::remote::{
+    Direction,
+    push::{Error, Outcome, Prepare, Status, Update, outcome},
+};
+
+impl<T> Prepare<'_, '_, '_, T>
+where
+    T: Transport,
+{
+    /// Ask the remote to perform `updates`, after sending a pack with all objects it needs for that.
+    ///
+    /// Before anything is sent, each update is checked against the reference on the remote, and is rejected locally if it
+    /// isn't a fast-forward and [`force`](Update::force) isn't set, or if it would change an existing tag without force.
+    /// Updates that wouldn't change the remote reference are considered [up-to-date](Status::UpToDate) and aren't sent.
+    ///
+    /// Unless disabled with [`with_update_tracking_refs()`](Self::with_update_tracking_refs()), local references that track
+    /// updated remote references according to the fetch refspecs of the remote are updated as well.
+    ///
+    /// Note that updates which are rejected, locally or by the remote, don't cause an error, and have to be checked in
+    /// the returned [`Outcome`].
+    ///
+    /// ### Deviation
+    ///
+    /// The objects to send are determined by excluding all commits reachable from references of the remote, along with the trees
+    /// and blobs of the parents of the sent commits. This can send more objects than `git` would, but never fewer.
+    ///
+    /// ### Configuration
+    ///
+    /// - `pack.compression` and `core.compression` determine the compression of objects that aren't copied from packs.
+    /// - `pack.window` and `pack.depth` control the search for deltas between objects that aren't copied from packs.
+    pub fn push<P>(
+        self,
+        updates: impl IntoIterator<Item = Update>,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error>
+    where
+        P: gix_features::progress::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("remote::push::Prepare::push()");
+        let Prepare {
+            mut con,
+            handshake,
+            atomic,
+            push_options,
+            update_tracking_refs,
+        } = self;
+        let repo = con.remote.repo;
+        let remote_refs = handshake.refs.as_deref().unwrap_or_default();
+
+        let mut updates: Vec<_> = updates
+            .into_iter()
+            .map(|update| {
+                let previous = remote_refs.iter().find_map(|r| {
+                    let (name, target, _peeled) = r.unpack();
+                    (name == update.destination.as_bstr())
+                        .then(|| target.map(ToOwned::to_owned))
+                        .flatten()
+                });
+                let status = check_update(repo, &update, previous)?;
+                let to_send = matches!(status, Status::Ok { .. });
+                Ok::<_, Error>((
+                    outcome::Update {
+                        destination: update.destination,
+                        previous,
+                        new: update.source,
+                        status,
+                        tracking_ref: None,
+                    },
+                    to_send,
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let has_rejections = updates
+            .iter()
+            .any(|(update, _)| !matches!(update.status, Status::UpToDate | Status::Ok { .. }));
+        if atomic && has_rejections {
+            for (update, to_send) in &mut updates {
+                if *to_send {
+                    update.status = Status::RejectedAtomicPushFailed;
+                    *to_send = false;
+                }
+            }
+        }
+
+        let object_hash = repo.object_hash();
+        let commands: Vec<_> = updates
+            .iter()
+            .filter(|(_, to_send)| *to_send)
+            .map(|(update, _)| gix_protocol::push::Command {
+                old: update.previous.unwrap_or_else(|| object_hash.null()),
+                new: update.new.unwrap_or_else(|| object_hash.null()),
+                name: update.destination.as_bstr().to_owned(),
+            })
+            .collect();
+
+        let pack = if commands.iter().all(gix_protocol::push::Command::is_delete) {
+            None
+        } else {
+            let mut handle = repo
+                .objects
+                .clone()
+                .into_arc()
+                .map_err(Error::OpenObjectDatabase)?
+                .into_inner();
+            handle.prevent_pack_unload();
+            handle.ignore_replacements = true;
+            let tips = commands.iter().filter(|c| !c.is_delete()).map(|c| c.new);
+            let hidden = remote_refs
+                .iter()
+                .filter_map(|r| r.unpack().1.map(ToOwned::to_owned))
+                .filter(|id| repo.has_object(id));
+            let counts = count_objects(repo, &handle, tips, hidden, &mut progress, should_interrupt)?;
+            let (window, depth) = repo.pack_window_and_depth()?;
+            let options = output::entry::iter_from_counts::Options {
+                thread_limit: None,
+                mode: output::entry::iter_from_counts::Mode::DeltaCompression,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+                compression: repo.pack_compression()?,
+                window,
+                depth,
+            };
+            Some((counts, handle, options))
+        };
+
+        let report = if commands.is_empty() {
+            None
+        } else {
+            gix_protocol::push(
+                &commands,
+                |out, progress, should_interrupt| {
+                    let (counts, handle, options) = pack.expect("a pack is only written if there are objects to send");
+                    write_pack(counts, handle, options, out, progress, should_interrupt)
+                },
+                &mut progress,
+                should_interrupt,
+                gix_protocol::push::Context {
+                    handshake: &handshake,
+                    transport: &mut con.transport.inner,
+                    user_agent: repo.config.user_agent_tuple(),
+                    trace_packetlines: con.trace,
+                },
+                gix_protocol::push::Options { atomic, push_options },
+            )?
+        };
+
+        if let Some(report) = &report {
+            for (update, _) in updates.iter_mut().filter(|(_, to_send)| *to_send) {
+                let forced = match update.status {
+                    Status::Ok { forced } => forced,
+                    _ => unreachable!("only updates that were ok locally are sent"),
+                };
+                update.status = match report.find(update.destination.as_bstr()) {
+                    Some(reported) => match &reported.status {
+                        gix_protocol::push::report::Status::Ok => Status::Ok { forced },
+                        gix_protocol::push::report::Status::Rejected(reason) => {
+                            Status::RejectedByRemote { reason: reason.clone() }
+                        }
+                    },
+                    None => Status::RejectedByRemote {
+                        reason: report
+                            .unpack_error
+                            .clone()
+                            .unwrap_or_else(|| "the update wasn't reported by the remote".into()),
+                    },
+                };
+            }
+        }
+
+        let mut updates: Vec<_> = updates.into_iter().map(|(update, _)| update).collect();
+        if update_tracking_refs {
+            self::update_tracking_refs(con.remote, &mut updates)?;
+        }
+        Ok(Outcome {
+            handshake,
+            updates,
+            report,
+        })
+    }
+}
+
+/// Return the status of `update` given that the remote reference currently points to `previous`, with [`Status::Ok`]
+/// indicating that it should be sent.
+fn check_update(repo: &crate::Repository, update: &Update, previous: Option<ObjectId>) -> Result<Status, Error> {
+    let (previous, new) = match (previous, update.source) {
+        (previous, new) if previous == new => return Ok(Status::UpToDate),
+        (Some(previous), Some(new)) => (previous, new),
+        (None, _) | (_, None) => return Ok(Status::Ok { forced: false }),
+    };
+    if !repo.has_object(previous) {
+        return Ok(if update.force {
+            Status::Ok { forced: true }
+        } else {
+            Status::RejectedFetchFirst
+        });
+    }
+    let is_fast_forward = {
+        let previous = repo.find_object(previous)?.peel_tags_to_end()?;
+        let new = repo.find_object(new)?.peel_tags_to_end()?;
+        if previous.kind == gix_object::Kind::Commit && new.kind == gix_object::Kind::Commit {
+            let mut is_ancestor = false;
+            for info in repo.rev_walk([new.id]).all()? {
+                if info?.id == previous.id {
+                    is_ancestor = true;
+                    break;
+                }
+            }
+            is_ancestor
+        } else {
+            false
+        }
+    };
+    Ok(if update.force {
+        Status::Ok {
+            forced: !is_fast_forward,
+        }
+    } else if update.destination.as_bstr().starts_with(b"refs/tags/") {
+        Status::RejectedAlreadyExists
+    } else if is_fast_forward {
+        Status::Ok { forced: false }
+    } else {
+        Status::RejectedNonFastForward
+    })
+}
+
+fn count_objects(
+    repo: &crate::Repository,
+    handle: &gix_odb::HandleArc,
+    tips: impl Iterator<Item = ObjectId>,
+    hidden: impl Iterator<Item = ObjectId>,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<output::Count>, Error> {
+    let mut commit_tips = Vec::new();
+    let mut input = Vec::new();
+    for tip in tips {
+        let object = repo.find_object(tip)?.peel_tags_to_end()?;
+        if object.kind == gix_object::Kind::Commit {
+            commit_tips.push(object.id);
+        }
+        // Annotated tags are expanded while counting, and duplicates are ignored.
+        input.push(tip);
+    }
+    let hidden = hidden
+        .filter_map(|id| {
+            repo.find_object(id)
+                .and_then(crate::Object::peel_tags_to_end)
+                .ok()
+                .filter(|object| object.kind == gix_object::Kind::Commit)
+                .map(|object| object.id)
+        })
+        .collect::<Vec<_>>();
+    if !commit_tips.is_empty() {
+        for info in repo.rev_walk(commit_tips).with_hidden(hidden).all()? {
+            input.push(info?.id);
+        }
+    }
+
+    let mut counting = progress.add_child_with_id("counting".into(), *b"PUCO");
+    counting.init(None, gix_features::progress::count("objects"));
+    #[cfg(feature = "attributes")]
+    let mut no_delta = repo.pack_no_delta()?;
+    #[cfg(feature = "attributes")]
+    let no_delta: Option<&mut dyn FnMut(&crate::bstr::BStr) -> bool> = Some(&mut no_delta);
+    #[cfg(not(feature = "attributes"))]
+    let no_delta = None;
+    let (counts, _stats) = output::count::objects_unthreaded(
+        handle,
+        &mut input.into_iter().map(Ok),
+        &counting,
+        should_interrupt,
+        output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+        no_delta,
+    )?;
+    Ok(counts)
+}
+
+fn write_pack(
+    counts: Vec<output::Count>,
+    handle: gix_odb::HandleArc,
+    options: output::entry::iter_from_counts::Options,
+    out: &mut dyn std::io::Write,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let num_objects = counts.len();
+    let object_hash = handle.store_ref().object_hash();
+    let entries = InOrderIter::from(output::entry::iter_from_counts(
+        counts,
+        handle,
+        Box::new(progress.add_child_with_id("creating entries".into(), *b"PUCE")),
+        options,
+    ));
+    let mut writing = progress.add_child_with_id("writing".into(), *b"PUWR");
+    writing.init(None, gix_features::progress::bytes());
+    let mut pack = output::bytes::FromEntriesIter::new(
+        entries,
+        out,
+        num_objects as u32,
+        gix_pack::data::Version::default(),
+        object_hash,
+    );
+    for written in pack.by_ref() {
+        if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err("Interrupted while sending the pack".into());
+        }
+        writing.inc_by(written? as usize);
+    }
+    Ok(())
+}
+
+/// Update the references tracking the remote references of all successful `updates`, as determined by the fetch refspecs of `remote`.
+fn update_tracking_refs(remote: &crate::Remote<'_>, updates: &mut [outcome::Update]) -> Result<(), Error> {
+    let repo = remote.repo;
+    let Some(remote_name) = remote.name().map(|name| name.as_bstr().to_owned()) else {
+        return Ok(());
+    };
+    let null = repo.object_hash().null();
+    let group =
+        gix_refspec::MatchGroup::from_fetch_specs(remote.refspecs(Direction::Fetch).iter().map(|spec| spec.to_ref()));
+    let items: Vec<_> = updates
+        .iter()
+        .map(|update| gix_refspec::match_group::Item {
+            full_ref_name: update.destination.as_bstr(),
+            target: &null,
+            object: None,
+        })
+        .collect();
+    let mut tracking_refs = vec![None; updates.len()];
+    for mapping in group.match_lhs(items.iter().copied()).mappings {
+        let (Some(item_index), Some(local)) = (mapping.item_index, mapping.rhs) else {
+            continue;
+        };
+        if let Ok(local) = FullName::try_from(local.into_owned()) {
+            tracking_refs[item_index] = Some(local);
+        }
+    }
+
+    let mut edits = Vec::new();
+    for (update, tracking_ref) in updates.iter_mut().zip(tracking_refs) {
+        let Some(tracking_ref) = tracking_ref else { continue };
+        if !update.status.is_success() {
+            continue;
+        }
+        let change = match update.new {
+            Some(new) => Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("update by push to {remote_name}").into(),
+                },
+                expected: PreviousValue::Any,
+                new: gix_ref::Target::Object(new),
+            },
+            None => {
+                if repo.try_find_reference(tracking_ref.as_ref())?.is_none() {
+                    continue;
+                }
+                Change::Delete {
+                    expected: PreviousValue::Any,
+                    log: RefLog::AndReference,
+                }
+            }
+        };
+        edits.push(RefEdit {
+            change,
+            name: tracking_ref.clone(),
+            deref: false,
+        });
+        update.tracking_ref = Some(tracking_ref);
+    }
+    if !edits.is_empty() {
+        repo.edit_references(edits)?;
+    }
+    Ok(())
+}
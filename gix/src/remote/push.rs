@@ -0,0 +1,2 @@
+pub use super::connection::push::{Error, Outcome, Prepare, ProgressId, Status, Update, outcome, prepare};
+pub use gix_protocol::push::{Report, report};
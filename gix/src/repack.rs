@@ -0,0 +1,128 @@
+//! Consolidate all reachable objects of a repository into a single pack with [`Repository::repack()`](crate::Repository::repack()).
+use std::path::PathBuf;
+
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::repack()`](crate::Repository::repack()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("Shallow repositories can't be repacked as their history is incomplete")]
+    Shallow,
+    #[error("Could not prepare the object database for use in multiple threads")]
+    OpenObjectDatabase(#[source] std::io::Error),
+    #[error(transparent)]
+    OpenPackedRefs(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    IterInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    Iterate(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error("Could not read the reflog of '{name}'")]
+    ReadReflog { name: BString, source: std::io::Error },
+    #[error("Could not decode the reflog of '{name}'")]
+    DecodeReflog {
+        name: BString,
+        source: gix_ref::file::log::iter::decode::Error,
+    },
+    #[error("Could not list the linked worktrees")]
+    ListWorktrees(#[source] std::io::Error),
+    #[error("Could not open the repository of a worktree")]
+    OpenWorktree(#[source] Box<crate::open::Error>),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Config(#[from] crate::config::Error),
+    #[error(transparent)]
+    PackThreads(#[from] crate::config::unsigned_integer::Error),
+    #[error(transparent)]
+    PackIndexVersion(#[from] crate::config::key::GenericError),
+    #[cfg(feature = "attributes")]
+    #[error(transparent)]
+    NoDelta(#[from] crate::repository::pack_no_delta::Error),
+    #[error("Failed to count the objects to pack")]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error("Failed to write the pack")]
+    WritePackData(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error("Failed to write the pack along with its index")]
+    WriteBundle(#[from] gix_pack::bundle::write::Error),
+    #[error("Could not open the pack index at '{path}'")]
+    OpenPackIndex {
+        path: PathBuf,
+        source: gix_pack::index::init::Error,
+    },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error("Could not write an unreachable object as loose object")]
+    WriteLooseObject(#[source] gix_object::write::Error),
+    #[error("Could not iterate the loose objects")]
+    IterLooseObjects(#[from] gix_odb::loose::iter::Error),
+    #[error("Interrupted while repacking")]
+    Interrupted,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Configure [`Repository::repack()`](crate::Repository::repack()).
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub(crate) delta_compression: bool,
+    pub(crate) write_bitmap: bool,
+    pub(crate) unpack_unreachable_since: Option<gix_date::SecondsSinceUnixEpoch>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            delta_compression: true,
+            write_bitmap: false,
+            unpack_unreachable_since: None,
+        }
+    }
+}
+
+/// Builder
+impl Options {
+    /// If `true` (the default), objects that aren't stored as delta in an existing pack yet, like loose objects,
+    /// are stored as deltas against similar objects if possible, as configured with `pack.window` and `pack.depth`.
+    /// Otherwise, they are stored as they are, which is faster but produces a larger pack.
+    ///
+    /// Deltas in existing packs are always reused.
+    pub fn with_delta_compression(mut self, toggle: bool) -> Self {
+        self.delta_compression = toggle;
+        self
+    }
+
+    /// If `true`, write a reachability bitmap along with the new pack, which is what `repack.writeBitmaps` controls in `git`.
+    /// It's `false` by default.
+    pub fn with_bitmap(mut self, toggle: bool) -> Self {
+        self.write_bitmap = toggle;
+        self
+    }
+
+    /// If `Some(time)`, objects that aren't reachable and only contained in packs that are removed are written as loose
+    /// objects if their pack was modified after `time`, so that they can expire later, like `git repack -A` does it.
+    /// Note that these objects are written at the current time, so they only expire after another expiry period.
+    ///
+    /// If `None` (the default), unreachable objects in removed packs are lost, like with `git repack -a -d`.
+    pub fn with_unpack_unreachable_since(mut self, time: Option<gix_date::SecondsSinceUnixEpoch>) -> Self {
+        self.unpack_unreachable_since = time;
+        self
+    }
+}
+
+/// The outcome of [`Repository::repack()`](crate::Repository::repack()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The path to the index of the new pack, or `None` if there was nothing to pack.
+    pub index_path: Option<PathBuf>,
+    /// The amount of objects in the new pack.
+    pub num_objects: usize,
+    /// The paths to the indices of packs that were removed as their reachable objects are now in the new pack.
+    pub removed_packs: Vec<PathBuf>,
+    /// The amount of loose objects that were removed as they are now packed.
+    pub removed_loose_objects: usize,
+    /// The amount of unreachable objects of removed packs that were written as loose objects to let them expire later.
+    pub unpacked_objects: usize,
+}
@@ -0,0 +1,344 @@
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::ObjectId;
+
+use crate::{Repository, apply};
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+use crate::{
+    bstr::{BStr, BString},
+    filter,
+};
+
+impl Repository {
+    /// Apply all `patches` in order to `tree`, which may also be a commit that is peeled to its tree, and write the
+    /// resulting tree along with all new blobs to the object database, similar to what `git apply --cached` would do
+    /// with an index that matches `tree`.
+    ///
+    /// This works in bare repositories and never touches the index or the worktree. Use [`apply::parse()`] to obtain
+    /// `patches`, and `options` to control how patches that don't apply cleanly are handled.
+    pub fn apply_to_tree(
+        &self,
+        tree: impl Into<ObjectId>,
+        patches: &[apply::Patch],
+        options: apply::Options,
+    ) -> Result<apply::TreeOutcome, apply::Error> {
+        let tree = self.find_object(tree.into())?.peel_to_tree()?.id;
+        let mut expand_prefix = |prefix| self.expand_prefix(prefix);
+        Ok(
+            gix_apply::apply::tree(tree, patches, self, &mut expand_prefix, &options.inner)
+                .map_err(gix_error::Exn::into_error)?,
+        )
+    }
+
+    /// Apply all `patches` in order to the given `location`, like `git apply` would, and return which files had to be merged.
+    ///
+    /// Files are only changed if all patches apply, otherwise nothing is touched.
+    /// When applying to the worktree, the content of files is converted to and from its form in Git just like when
+    /// checking out or adding files. When applying to the [index and the worktree](apply::Location::IndexAndWorktree),
+    /// all patched files in the worktree must match the index.
+    ///
+    /// If a [3-way merge](apply::Options::with_three_way()) leads to conflicts, the worktree receives a file with conflict
+    /// markers, while the index receives the base, our and their version of the file as unmerged entries.
+    /// `should_interrupt` is checked while writing files to the worktree.
+    #[cfg(all(feature = "worktree-mutation", feature = "status"))]
+    pub fn apply(
+        &self,
+        patches: &[apply::Patch],
+        location: apply::Location,
+        should_interrupt: &AtomicBool,
+        options: apply::Options,
+    ) -> Result<apply::Outcome, apply::Error> {
+        use apply::Location;
+
+        let workdir = match location {
+            Location::Index => None,
+            Location::Worktree | Location::IndexAndWorktree => {
+                Some(self.workdir().ok_or(apply::Error::MissingWorkDir)?)
+            }
+        };
+        let index = self.index_or_empty()?;
+        let mut pipeline = workdir.map(|_| self.filter_pipeline(None)).transpose()?;
+        let mut state = State {
+            location,
+            index: &index,
+            pipeline: pipeline.as_mut().map(|(pipeline, index)| {
+                let index: &gix_index::State = index;
+                (pipeline, index)
+            }),
+            changed: BTreeMap::new(),
+        };
+
+        let mut expand_prefix = |prefix| self.expand_prefix(prefix);
+        let mut conflicts = BTreeMap::<BString, Stages>::new();
+        let mut out = apply::Outcome::default();
+        for patch in patches {
+            let old = match &patch.old_path {
+                Some(path) => Some(state.current(path.as_ref())?.ok_or_else(|| apply::Error::Missing {
+                    path: path.clone(),
+                    location: location.name(),
+                })?),
+                None => None,
+            };
+            let new_path = patch.new_path.as_ref();
+            if let Some(path) = new_path.filter(|_| patch.old_path.as_ref() != new_path) {
+                if state.current(path.as_ref())?.is_some() {
+                    return Err(apply::Error::AlreadyExists {
+                        path: path.clone(),
+                        location: location.name(),
+                    });
+                }
+            }
+
+            let current = match old {
+                Some(file) => {
+                    if !file.mode.is_blob_or_symlink() {
+                        return Err(apply::Error::NotAFile {
+                            path: patch.path().clone(),
+                        });
+                    }
+                    self.find_object(file.id)?.detach().data
+                }
+                None => Vec::new(),
+            };
+            let content = gix_apply::apply::content(patch, &current, self, &mut expand_prefix, &options.inner)
+                .map_err(gix_error::Exn::into_error)?;
+            if let Some(three_way) = &content.three_way {
+                out.merged.push(patch.path().clone());
+                if content.has_conflict() {
+                    out.conflicts.push(patch.path().clone());
+                    let mode = old.map_or_else(|| gix_object::tree::EntryKind::Blob.into(), |file| file.mode);
+                    conflicts.insert(
+                        patch.path().clone(),
+                        Stages {
+                            base: Some(File {
+                                mode,
+                                id: three_way.base,
+                            }),
+                            ours: old,
+                            theirs: Some(File {
+                                mode: patch.new_mode.unwrap_or(mode),
+                                id: self.write_blob(&three_way.theirs)?.detach(),
+                            }),
+                        },
+                    );
+                }
+            }
+
+            if matches!(
+                patch.kind,
+                gix_apply::patch::Kind::Delete | gix_apply::patch::Kind::Rename { .. }
+            ) {
+                let old_path = patch.old_path.as_ref().expect("set for deletions and renames");
+                if patch.kind == gix_apply::patch::Kind::Delete && !content.data.is_empty() {
+                    return Err(apply::Error::RemovalLeavesContent { path: old_path.clone() });
+                }
+                state.changed.insert(old_path.clone(), None);
+            }
+            if let Some(new_path) = new_path {
+                let mode = patch
+                    .new_mode
+                    .or(old.map(|file| file.mode))
+                    .unwrap_or_else(|| gix_object::tree::EntryKind::Blob.into());
+                let id = self.write_blob(&content.data)?.detach();
+                state.changed.insert(new_path.clone(), Some(File { mode, id }));
+            }
+        }
+        let changed = state.changed;
+
+        let mut stats = BTreeMap::<&BStr, gix_index::entry::Stat>::new();
+        let mut written = gix_index::State::new(self.object_hash());
+        if let Some(workdir) = workdir {
+            for (path, file) in &changed {
+                let full_path = workdir.join(gix_path::from_bstr(path));
+                if std::fs::symlink_metadata(&full_path).is_ok_and(|md| !md.is_dir()) {
+                    // Never write through symlinks or into files that may be hard-linked elsewhere.
+                    std::fs::remove_file(&full_path).map_err(|source| apply::Error::Remove {
+                        path: full_path.clone(),
+                        source,
+                    })?;
+                }
+                match file {
+                    Some(file) => written.dangerously_push_entry(
+                        Default::default(),
+                        file.id,
+                        gix_index::entry::Flags::empty(),
+                        file.mode.into(),
+                        path.as_ref(),
+                    ),
+                    None => super::checkout_tree::remove_empty_parent_directories(&full_path, workdir),
+                }
+            }
+
+            if !written.entries().is_empty() {
+                written.sort_entries();
+                let mut opts = self.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
+                opts.destination_is_initially_empty = false;
+                opts.keep_going = false;
+                let outcome = gix_worktree_state::checkout(
+                    &mut written,
+                    workdir,
+                    self.objects.clone().into_arc()?,
+                    &gix_features::progress::Discard,
+                    &gix_features::progress::Discard,
+                    should_interrupt,
+                    opts,
+                )?;
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(apply::Error::Interrupted);
+                }
+                if let Some(collision) = outcome.collisions.into_iter().next() {
+                    return Err(apply::Error::AlreadyExists {
+                        path: collision.path,
+                        location: Location::Worktree.name(),
+                    });
+                }
+                for entry in written.entries() {
+                    stats.insert(entry.path(&written), entry.stat);
+                }
+            }
+        }
+
+        if location != Location::Worktree {
+            let mut index = gix_index::File::clone(&index);
+            index.remove_entries(|_, path, _| changed.contains_key(path));
+            // The cached trees don't match the entries anymore.
+            index.remove_tree();
+            for (path, file) in &changed {
+                let Some(file) = file else { continue };
+                match conflicts.get(path) {
+                    Some(stages) => {
+                        use gix_index::entry::Stage;
+                        for (stage, file) in [
+                            (Stage::Base, stages.base),
+                            (Stage::Ours, stages.ours),
+                            (Stage::Theirs, stages.theirs),
+                        ] {
+                            let Some(file) = file else { continue };
+                            index.dangerously_push_entry(
+                                Default::default(),
+                                file.id,
+                                gix_index::entry::Flags::from_stage(stage),
+                                file.mode.into(),
+                                path.as_ref(),
+                            );
+                        }
+                    }
+                    None => index.dangerously_push_entry(
+                        stats.get(path.as_ref() as &BStr).copied().unwrap_or_default(),
+                        file.id,
+                        gix_index::entry::Flags::empty(),
+                        file.mode.into(),
+                        path.as_ref(),
+                    ),
+                }
+            }
+            index.sort_entries();
+            index.write(gix_index::write::Options {
+                fsync: self.config.fsync(gix_fs::fsync::Components::INDEX),
+                ..Default::default()
+            })?;
+        }
+        Ok(out)
+    }
+
+    fn expand_prefix(&self, prefix: gix_hash::Prefix) -> Option<ObjectId> {
+        self.objects
+            .lookup_prefix(prefix, None)
+            .ok()
+            .flatten()
+            .and_then(Result::ok)
+    }
+}
+
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+impl apply::Location {
+    /// The name of the location to use in error messages.
+    fn name(&self) -> &'static str {
+        match self {
+            apply::Location::Worktree => "worktree",
+            apply::Location::Index | apply::Location::IndexAndWorktree => "index",
+        }
+    }
+}
+
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct File {
+    mode: gix_object::tree::EntryMode,
+    id: ObjectId,
+}
+
+/// The versions of a file that was merged with conflicts, as they are written to the index.
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+struct Stages {
+    base: Option<File>,
+    ours: Option<File>,
+    theirs: Option<File>,
+}
+
+/// Provide the current version of files, taking into account the changes of previous patches.
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+struct State<'repo, 'a> {
+    location: apply::Location,
+    index: &'a gix_index::File,
+    pipeline: Option<(&'a mut filter::Pipeline<'repo>, &'a gix_index::State)>,
+    /// Paths changed by previous patches, or `None` if they were removed.
+    changed: BTreeMap<BString, Option<File>>,
+}
+
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+impl State<'_, '_> {
+    fn current(&mut self, path: &BStr) -> Result<Option<File>, apply::Error> {
+        use apply::Location;
+
+        if let Some(file) = self.changed.get(path) {
+            return Ok(*file);
+        }
+        let in_index = || -> Result<Option<File>, apply::Error> {
+            let Some(range) = self.index.entry_range(path) else {
+                return Ok(None);
+            };
+            let entry = &self.index.entries()[range.start];
+            if range.len() > 1 || entry.stage() != gix_index::entry::Stage::Unconflicted {
+                return Err(apply::Error::Unmerged { path: path.to_owned() });
+            }
+            Ok(entry.mode.to_tree_entry_mode().map(|mode| File { mode, id: entry.id }))
+        };
+        let in_index = match self.location {
+            Location::Worktree => None,
+            Location::Index | Location::IndexAndWorktree => Some(in_index()?),
+        };
+        let in_worktree = match &mut self.pipeline {
+            Some((pipeline, index)) => Some(
+                pipeline
+                    .worktree_file_to_object(path, index)?
+                    .map(|(id, kind, _)| File { mode: kind.into(), id }),
+            ),
+            None => None,
+        };
+        Ok(match (in_index, in_worktree) {
+            (Some(in_index), None) => in_index,
+            (None, Some(in_worktree)) => in_worktree,
+            (Some(Some(in_index)), Some(Some(in_worktree))) => {
+                if in_index.id != in_worktree.id {
+                    return Err(apply::Error::WorktreeDoesNotMatchIndex { path: path.to_owned() });
+                }
+                Some(in_index)
+            }
+            (Some(Some(_)), Some(None)) => {
+                return Err(apply::Error::WorktreeDoesNotMatchIndex { path: path.to_owned() });
+            }
+            (Some(None), Some(Some(_))) => {
+                return Err(apply::Error::AlreadyExists {
+                    path: path.to_owned(),
+                    location: Location::Worktree.name(),
+                });
+            }
+            (Some(None), Some(None)) | (None, None) => None,
+        })
+    }
+}
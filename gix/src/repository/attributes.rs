@@ -93,6 +93,37 @@ impl Repository {
         ))
     }
 
+    /// Return a predicate telling whether the blob at a worktree-relative path is marked with `-delta`, and should thus be
+    /// stored as base object in packs instead of being delta-compressed.
+    ///
+    /// Attributes are read from the worktree if there is one, and from the index or `HEAD` otherwise.
+    #[cfg(all(
+        feature = "attributes",
+        any(feature = "maintenance", feature = "blocking-network-client")
+    ))]
+    pub(crate) fn pack_no_delta(
+        &self,
+    ) -> Result<impl FnMut(&crate::bstr::BStr) -> bool + '_, crate::repository::pack_no_delta::Error> {
+        let index = self.index_or_load_from_head_or_empty()?;
+        let mut attributes = self.attributes_only(
+            &index,
+            if self.workdir().is_some() {
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping
+            } else {
+                gix_worktree::stack::state::attributes::Source::IdMapping
+            },
+        )?;
+        let mut outcome = attributes.selected_attribute_matches(["delta"]);
+        Ok(move |path: &crate::bstr::BStr| {
+            attributes
+                .at_entry(path, Some(gix_index::entry::Mode::FILE))
+                .is_ok_and(|platform| {
+                    platform.matching_attributes(&mut outcome)
+                        && outcome.iter_selected().any(|m| m.assignment.state.is_unset())
+                })
+        })
+    }
+
     /// Configure a file-system cache checking if files below the repository are excluded, reading `.gitignore` files from
     /// the specified `source`.
     ///
@@ -0,0 +1,349 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_pack::Find as _;
+
+use crate::{
+    Repository,
+    batch::{self, Atom, Item, Mode},
+    bstr::{BStr, ByteSlice},
+};
+
+impl Repository {
+    /// Read one object name or rev-spec per line from `input` and write information about each object to `out`, like
+    /// `git cat-file --batch`, `--batch-check` and `--batch-command` would depending on the [mode](batch::Mode) of `options`.
+    ///
+    /// For each object, a line in the [format](batch::Format) of `options` is written, followed by the object data and
+    /// a newline in [`Mode::Contents`]. Names that don't resolve to an existing object are reported as `<name> missing`,
+    /// and abbreviated object ids that match more than one object are reported as `<name> ambiguous`.
+    ///
+    /// Unless [output is buffered](batch::Options::with_buffer()), `out` is flushed after each object so that the caller
+    /// can interactively exchange object names for objects, for instance through a pipe.
+    /// Note that the data of blobs is written as stored, without conversion.
+    pub fn batch_objects(
+        &self,
+        mut input: impl BufRead,
+        mut out: impl Write,
+        options: batch::Options,
+    ) -> Result<batch::Outcome, batch::Error> {
+        let mut state = State::new(self, &options);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if input.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            let line = line.as_bstr();
+            let (contents, name) = match options.mode {
+                Mode::Contents => (true, line),
+                Mode::Info => (false, line),
+                Mode::Command => {
+                    let (command, argument) = line.split_once_str(b" ").unwrap_or((line, b""));
+                    let contents = match command {
+                        b"contents" => true,
+                        b"info" => false,
+                        b"flush" => {
+                            if !options.buffer {
+                                return Err(batch::Error::FlushWithoutBuffer);
+                            }
+                            out.flush()?;
+                            continue;
+                        }
+                        _ => {
+                            return Err(batch::Error::UnknownCommand {
+                                command: command.into(),
+                            });
+                        }
+                    };
+                    if argument.is_empty() {
+                        return Err(batch::Error::MissingArgument {
+                            command: command.into(),
+                        });
+                    }
+                    (contents, argument.as_bstr())
+                }
+            };
+            state.write_by_name(name, contents, &mut out)?;
+            if !options.buffer {
+                out.flush()?;
+            }
+        }
+        out.flush()?;
+        Ok(state.outcome)
+    }
+
+    /// Write information about all objects in the object database and its alternates to `out`, like
+    /// `git cat-file --batch-all-objects` would, using [`Mode::Contents`] or [`Mode::Info`] of `options`.
+    ///
+    /// Objects are sorted by their id, unless [unordered](batch::Options::with_unordered()) is set, in which case they
+    /// are written in the order they are stored in packs, followed by loose objects, which is faster for accessing
+    /// their contents. Either way, each object is written only once, and replacements aren't applied.
+    ///
+    /// [`Mode::Command`] isn't supported as there is no input to read commands from.
+    pub fn batch_all_objects(
+        &self,
+        mut out: impl Write,
+        options: batch::Options,
+    ) -> Result<batch::Outcome, batch::Error> {
+        let contents = match options.mode {
+            Mode::Contents => true,
+            Mode::Info => false,
+            Mode::Command => return Err(batch::Error::CommandModeWithAllObjects),
+        };
+        let mut repo = self.clone();
+        repo.objects.ignore_replacements = true;
+        let mut state = State::new(&repo, &options);
+        let ordering = if options.unordered {
+            gix_odb::store::iter::Ordering::PackAscendingOffsetThenLooseLexicographical
+        } else {
+            gix_odb::store::iter::Ordering::PackLexicographicalThenLooseLexicographical
+        };
+        let iter = repo.objects.iter()?.with_ordering(ordering);
+        if options.unordered {
+            let mut seen = HashSet::default();
+            for id in iter {
+                let id = id?;
+                if seen.insert(id) {
+                    state.write_by_id(id, "".into(), contents, &mut out)?;
+                }
+            }
+        } else {
+            let mut ids = iter.collect::<Result<Vec<_>, _>>()?;
+            ids.sort_unstable();
+            ids.dedup();
+            for id in ids {
+                state.write_by_id(id, "".into(), contents, &mut out)?;
+            }
+        }
+        out.flush()?;
+        Ok(state.outcome)
+    }
+}
+
+struct State<'a> {
+    repo: &'a Repository,
+    options: &'a batch::Options,
+    /// Only set if the format needs information about how objects are stored.
+    disk: Option<Disk>,
+    outcome: batch::Outcome,
+}
+
+impl<'a> State<'a> {
+    fn new(repo: &'a Repository, options: &'a batch::Options) -> Self {
+        State {
+            repo,
+            options,
+            disk: None,
+            outcome: Default::default(),
+        }
+    }
+
+    fn write_by_name(&mut self, line: &BStr, contents: bool, out: &mut dyn Write) -> Result<(), batch::Error> {
+        let (name, rest) = if self.options.format.contains(Atom::Rest) {
+            match line.find_byteset(b" \t") {
+                Some(pos) => (line[..pos].as_bstr(), line[pos + 1..].trim_start().as_bstr()),
+                None => (line, "".into()),
+            }
+        } else {
+            (line, "".into())
+        };
+        match self.resolve(name)? {
+            Resolved::Id(id) => {
+                if !self.write_by_id(id, rest, contents, out)? {
+                    writeln!(out, "{name} missing")?;
+                }
+            }
+            Resolved::Missing => {
+                self.outcome.missing += 1;
+                writeln!(out, "{name} missing")?;
+            }
+            Resolved::Ambiguous => {
+                self.outcome.missing += 1;
+                writeln!(out, "{name} ambiguous")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve(&self, name: &BStr) -> Result<Resolved, batch::Error> {
+        let hex_len = self.repo.object_hash().len_in_hex();
+        if name.len() == hex_len {
+            if let Ok(id) = ObjectId::from_hex(name) {
+                return Ok(Resolved::Id(id));
+            }
+        }
+        if name.len() >= gix_hash::Prefix::MIN_HEX_LEN && name.len() < hex_len {
+            // Pad the prefix to a full id so that it has the hash kind of the repository.
+            let mut hex = name.to_owned();
+            hex.resize(hex_len, b'0');
+            if let Some(prefix) = ObjectId::from_hex(&hex)
+                .ok()
+                .and_then(|id| gix_hash::Prefix::new(&id, name.len()).ok())
+            {
+                match self.repo.objects.lookup_prefix(prefix, None)? {
+                    Some(Ok(id)) => return Ok(Resolved::Id(id)),
+                    Some(Err(())) => return Ok(Resolved::Ambiguous),
+                    // It might still be a reference name.
+                    None => {}
+                }
+            }
+        }
+        Ok(self
+            .repo
+            .rev_parse_single(name)
+            .map_or(Resolved::Missing, |id| Resolved::Id(id.detach())))
+    }
+
+    /// Return `false` if the object with `id` doesn't exist, after counting it as missing.
+    fn write_by_id(
+        &mut self,
+        id: ObjectId,
+        rest: &BStr,
+        contents: bool,
+        out: &mut dyn Write,
+    ) -> Result<bool, batch::Error> {
+        let (kind, size, object) = if contents {
+            match self.repo.try_find_object(id)? {
+                Some(object) => (object.kind, object.data.len() as u64, Some(object)),
+                None => {
+                    self.outcome.missing += 1;
+                    return Ok(false);
+                }
+            }
+        } else {
+            match self.repo.try_find_header(id)? {
+                Some(header) => (header.kind(), header.size(), None),
+                None => {
+                    self.outcome.missing += 1;
+                    return Ok(false);
+                }
+            }
+        };
+
+        let on_disk =
+            if self.options.format.contains(Atom::ObjectSizeOnDisk) || self.options.format.contains(Atom::DeltaBase) {
+                let disk = match self.disk.as_mut() {
+                    Some(disk) => disk,
+                    None => self.disk.insert(Disk::new(self.repo)?),
+                };
+                Some(disk.info(&id)?)
+            } else {
+                None
+            };
+
+        for item in self.options.format.items() {
+            match item {
+                Item::Literal(text) => out.write_all(text)?,
+                Item::Atom(Atom::ObjectName) => write!(out, "{id}")?,
+                Item::Atom(Atom::ObjectType) => out.write_all(kind.as_bytes())?,
+                Item::Atom(Atom::ObjectSize) => write!(out, "{size}")?,
+                Item::Atom(Atom::ObjectSizeOnDisk) => {
+                    write!(out, "{}", on_disk.as_ref().expect("computed if needed").size)?;
+                }
+                Item::Atom(Atom::DeltaBase) => {
+                    write!(out, "{}", on_disk.as_ref().expect("computed if needed").delta_base)?;
+                }
+                Item::Atom(Atom::Rest) => out.write_all(rest)?,
+            }
+        }
+        out.write_all(b"\n")?;
+        if let Some(object) = object {
+            out.write_all(&object.data)?;
+            out.write_all(b"\n")?;
+        }
+        self.outcome.objects += 1;
+        Ok(true)
+    }
+}
+
+enum Resolved {
+    Id(ObjectId),
+    Missing,
+    Ambiguous,
+}
+
+/// How an object is stored.
+struct OnDisk {
+    /// The size of the entry in its pack, or the size of the loose object file.
+    size: u64,
+    /// The id of the object the pack entry is a delta against, or the null id.
+    delta_base: ObjectId,
+}
+
+/// Provide information about how objects are stored, which requires packs to stay available.
+struct Disk {
+    handle: gix_odb::HandleArc,
+    buf: Vec<u8>,
+    /// Object ids by their pack offset, sorted by offset, for each pack id whose deltas were looked up.
+    offsets_by_pack: HashMap<u32, Vec<(gix_pack::data::Offset, ObjectId)>>,
+    /// The `objects` directory of the repository and of all alternates.
+    loose_dbs: Vec<PathBuf>,
+}
+
+impl Disk {
+    fn new(repo: &Repository) -> Result<Self, batch::Error> {
+        let mut handle = repo
+            .objects
+            .clone()
+            .into_arc()
+            .map_err(batch::Error::OpenObjectDatabase)?
+            .into_inner();
+        handle.prevent_pack_unload();
+        handle.ignore_replacements = true;
+        let store = repo.objects.store_ref();
+        let mut loose_dbs = vec![store.path().to_owned()];
+        loose_dbs.extend(store.alternate_db_paths()?);
+        Ok(Disk {
+            handle,
+            buf: Vec::new(),
+            offsets_by_pack: HashMap::new(),
+            loose_dbs,
+        })
+    }
+
+    fn info(&mut self, id: &gix_hash::oid) -> Result<OnDisk, batch::Error> {
+        let null = ObjectId::null(id.kind());
+        let Some(location) = self.handle.location_by_oid(id, &mut self.buf) else {
+            let hex = id.to_hex().to_string();
+            let size = self
+                .loose_dbs
+                .iter()
+                .find_map(|db| std::fs::metadata(db.join(&hex[..2]).join(&hex[2..])).ok())
+                .map_or(0, |metadata| metadata.len());
+            return Ok(OnDisk { size, delta_base: null });
+        };
+        let size = location.entry_size as u64;
+        let Some(entry) = self.handle.entry_by_location(&location) else {
+            return Ok(OnDisk { size, delta_base: null });
+        };
+        let entry = gix_pack::data::Entry::from_bytes(&entry.data, location.pack_offset, id.kind())
+            .map_err(|source| batch::Error::DecodePackEntry { id: id.into(), source })?;
+        let delta_base = match entry.header {
+            gix_pack::data::entry::Header::RefDelta { base_id } => base_id,
+            gix_pack::data::entry::Header::OfsDelta { base_distance } => {
+                let base_offset = entry.base_pack_offset(base_distance);
+                let offsets = match self.offsets_by_pack.entry(location.pack_id) {
+                    std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                    std::collections::hash_map::Entry::Vacant(entry) => {
+                        let mut offsets = self.handle.pack_offsets_and_oid(location.pack_id).unwrap_or_default();
+                        offsets.sort_unstable_by_key(|(offset, _)| *offset);
+                        entry.insert(offsets)
+                    }
+                };
+                offsets
+                    .binary_search_by_key(&base_offset, |(offset, _)| *offset)
+                    .map_or(null, |idx| offsets[idx].1)
+            }
+            _ => null,
+        };
+        Ok(OnDisk { size, delta_base })
+    }
+}
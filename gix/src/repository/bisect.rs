@@ -0,0 +1,341 @@
+use std::{
+    io::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::ObjectId;
+use gix_ref::{
+    FullName, Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+use crate::{
+    Id, Repository,
+    bisect::{self, Error, Mark, Outcome, State, Terms},
+    bstr::{BString, ByteSlice},
+    prelude::ObjectIdExt,
+};
+
+const START: &str = "BISECT_START";
+const TERMS: &str = "BISECT_TERMS";
+const NAMES: &str = "BISECT_NAMES";
+const LOG: &str = "BISECT_LOG";
+const EXPECTED_REV: &str = "BISECT_EXPECTED_REV";
+const REF_PREFIX: &str = "refs/bisect/";
+
+/// Bisecting
+impl Repository {
+    /// Start a bisect to find the commit that introduced a change, like `git bisect start` would.
+    ///
+    /// This writes `BISECT_START` with the branch or commit `HEAD` points to, along with `BISECT_TERMS`, `BISECT_NAMES` and `BISECT_LOG`,
+    /// in a way that is compatible with Git, so that the bisect can be continued by `git bisect` as well.
+    /// Commits are then marked as bad or good with [`bisect_mark()`](Self::bisect_mark()).
+    ///
+    /// In bare repositories, or if [configured](bisect::Options::with_no_checkout()), commits to test aren't checked out but
+    /// [`bisect::HEAD_NAME`] is pointed to them instead, starting out with the commit `HEAD` points to.
+    pub fn bisect_start(&self, options: bisect::Options) -> Result<(), Error> {
+        if self.git_dir().join(START).is_file() {
+            return Err(Error::InProgress);
+        }
+        let head = self.head()?;
+        let head_id = head.id().ok_or(Error::UnbornHead)?.detach();
+        let start: BString = match head.referent_name().and_then(|name| name.category_and_short_name()) {
+            Some((gix_ref::Category::LocalBranch, short_name)) => short_name.to_owned(),
+            _ => head_id.to_string().into(),
+        };
+        let no_checkout = options.no_checkout || self.is_bare();
+        let terms = options.terms.clone().unwrap_or_default();
+
+        let mut command = BString::from("git bisect start");
+        if options.no_checkout {
+            command.extend_from_slice(b" '--no-checkout'");
+        }
+        if let Some(terms) = &options.terms {
+            command.extend_from_slice(format!(" '--term-bad={}' '--term-good={}'", terms.bad, terms.good).as_bytes());
+        }
+        command.push(b'\n');
+
+        if no_checkout {
+            self.edit_bisect_head(head_id)?;
+        }
+        self.write_bisect_file(START, format!("{start}\n").as_bytes())?;
+        self.write_bisect_file(TERMS, format!("{}\n{}\n", terms.bad, terms.good).as_bytes())?;
+        self.write_bisect_file(NAMES, b"\n")?;
+        self.write_bisect_file(LOG, &command)?;
+        Ok(())
+    }
+
+    /// Return the state of the bisect in progress, or `None` if there is none.
+    pub fn bisect_state(&self) -> Result<Option<State>, Error> {
+        let Some(start) = self.read_bisect_file(START)? else {
+            return Ok(None);
+        };
+        let terms = match self.read_bisect_file(TERMS)? {
+            Some(terms) => {
+                let mut lines = terms.lines();
+                match (lines.next(), lines.next()) {
+                    (Some(bad), Some(good)) if !bad.is_empty() && !good.is_empty() => Terms {
+                        bad: bad.into(),
+                        good: good.into(),
+                    },
+                    _ => return Err(Error::InvalidTerms),
+                }
+            }
+            None => Terms::default(),
+        };
+
+        let mut bad = None;
+        let mut good = Vec::new();
+        let mut skipped = Vec::new();
+        for reference in self.references()?.prefixed(REF_PREFIX.as_bytes())? {
+            let mut reference = reference?;
+            let id = reference.peel_to_id()?.detach();
+            let name = reference
+                .name()
+                .as_bstr()
+                .strip_prefix(REF_PREFIX.as_bytes())
+                .expect("prefix was matched");
+            if name == terms.bad.as_slice() {
+                bad = Some(id);
+            } else if name
+                .strip_prefix(terms.good.as_slice())
+                .is_some_and(|suffix| suffix.starts_with(b"-"))
+            {
+                good.push(id);
+            } else if name.starts_with(b"skip-") {
+                skipped.push(id);
+            }
+        }
+
+        Ok(Some(State {
+            start: start.trim_end().into(),
+            terms,
+            bad,
+            good,
+            skipped,
+            no_checkout: self.try_find_reference(bisect::HEAD_NAME)?.is_some(),
+        }))
+    }
+
+    /// Mark `commit`, or the commit that is currently tested if `None`, with `mark` and move on to the next commit to test
+    /// with [`bisect_next()`](Self::bisect_next()), like `git bisect bad|good|skip [<commit>]` would.
+    ///
+    /// The commit that is currently tested is the one [`bisect::HEAD_NAME`] points to when commits aren't checked out,
+    /// or the one `HEAD` points to otherwise.
+    /// Marked commits are stored in `refs/bisect/*` and recorded in `BISECT_LOG`.
+    pub fn bisect_mark(
+        &self,
+        mark: Mark,
+        commit: Option<ObjectId>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let state = self.bisect_state()?.ok_or(Error::NotInProgress)?;
+        let commit = match commit {
+            Some(commit) => commit,
+            None if state.no_checkout => self.find_reference(bisect::HEAD_NAME)?.peel_to_id()?.detach(),
+            None => self.head_id()?.detach(),
+        };
+        let commit = self.find_object(commit)?.peel_to_commit()?;
+        let (term, ref_name) = match mark {
+            Mark::Bad => (&state.terms.bad, state.terms.bad.to_string()),
+            Mark::Good => (&state.terms.good, format!("{}-{}", state.terms.good, commit.id)),
+            Mark::Skip => (&"skip".into(), format!("skip-{}", commit.id)),
+        };
+        let name: FullName = format!("{REF_PREFIX}{ref_name}").try_into()?;
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: Default::default(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(commit.id),
+            },
+            name,
+            deref: false,
+        })?;
+        let subject = commit.message()?.summary();
+        self.append_to_bisect_log(
+            format!("# {term}: [{id}] {subject}\ngit bisect {term} {id}\n", id = commit.id).as_bytes(),
+        )?;
+        self.bisect_next(should_interrupt)
+    }
+
+    /// Determine the next commit to test, roughly halfway between the bad commit and the good commits, and check it out
+    /// as detached `HEAD` or point [`bisect::HEAD_NAME`] to it, like `git bisect next` would.
+    ///
+    /// If the first bad commit is found, or if only skipped commits are left to test, this is recorded in `BISECT_LOG` instead.
+    pub fn bisect_next(&self, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        let state = self.bisect_state()?.ok_or(Error::NotInProgress)?;
+        let Some(bad) = state.bad.filter(|_| !state.good.is_empty()) else {
+            return Ok(Outcome::Waiting {
+                bad: state.bad.is_some(),
+                good: state.good.len(),
+            });
+        };
+
+        let cache = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(cache.as_ref());
+        let outcome =
+            gix_revision::bisect(bad, &state.good, &state.skipped, &mut graph).map_err(gix_error::Exn::into_inner)?;
+        Ok(match outcome {
+            gix_revision::bisect::Outcome::Next { id, remaining, steps } => {
+                if state.no_checkout {
+                    self.edit_bisect_head(id)?;
+                } else {
+                    self.switch(
+                        crate::switch::Target::Detached(id),
+                        should_interrupt,
+                        Default::default(),
+                    )?;
+                }
+                self.write_bisect_file(EXPECTED_REV, format!("{id}\n").as_bytes())?;
+                Outcome::Next { id, remaining, steps }
+            }
+            gix_revision::bisect::Outcome::FirstBad { id } => {
+                let line = format!("# first bad commit: [{id}] {}\n", self.bisect_subject(id)?);
+                self.append_to_bisect_log(line.as_bytes())?;
+                Outcome::FirstBad { id }
+            }
+            gix_revision::bisect::Outcome::OnlySkipped { candidates } => {
+                let mut lines = BString::from("# only skipped commits left to test\n");
+                for id in &candidates {
+                    lines.extend_from_slice(
+                        format!("# possible first bad commit: [{id}] {}\n", self.bisect_subject(*id)?).as_bytes(),
+                    );
+                }
+                self.append_to_bisect_log(&lines)?;
+                Outcome::OnlySkipped { candidates }
+            }
+        })
+    }
+
+    /// Automate the bisect in progress by calling `test` with each commit to test to [mark](Mark) it, until the first bad commit
+    /// is found or only skipped commits are left, like `git bisect run` would, and return the final outcome.
+    ///
+    /// `test` is called with the commit that was checked out, or that [`bisect::HEAD_NAME`] points to when commits aren't checked out.
+    /// A bad commit and at least one good commit must have been marked beforehand.
+    pub fn bisect_run<E>(
+        &self,
+        should_interrupt: &AtomicBool,
+        mut test: impl FnMut(Id<'_>) -> Result<Mark, E>,
+    ) -> Result<Outcome, Error>
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        let mut outcome = self.bisect_next(should_interrupt)?;
+        loop {
+            match outcome {
+                Outcome::Waiting { .. } => return Err(Error::MissingMarks),
+                Outcome::Next { id, .. } => {
+                    if should_interrupt.load(Ordering::Relaxed) {
+                        return Err(Error::Interrupted);
+                    }
+                    let mark = test(id.attach(self)).map_err(|err| Error::Run { id, source: err.into() })?;
+                    outcome = self.bisect_mark(mark, Some(id), should_interrupt)?;
+                }
+                Outcome::FirstBad { .. } | Outcome::OnlySkipped { .. } => return Ok(outcome),
+            }
+        }
+    }
+
+    /// End the bisect in progress and remove its state, like `git bisect reset` would.
+    ///
+    /// Unless commits weren't checked out, `HEAD` is switched back to the branch or commit it pointed to
+    /// when the bisect was started.
+    pub fn bisect_reset(&self, should_interrupt: &AtomicBool) -> Result<(), Error> {
+        let state = self.bisect_state()?.ok_or(Error::NotInProgress)?;
+        if !state.no_checkout {
+            let target = match ObjectId::from_hex(&state.start) {
+                Ok(id) => crate::switch::Target::Detached(id),
+                Err(_) => crate::switch::Target::Branch(format!("refs/heads/{}", state.start).try_into()?),
+            };
+            self.switch(target, should_interrupt, Default::default())?;
+        }
+
+        let mut edits = Vec::new();
+        for reference in self.references()?.prefixed(REF_PREFIX.as_bytes())? {
+            edits.push(reference?.inner.name);
+        }
+        if state.no_checkout {
+            edits.push(bisect::HEAD_NAME.try_into().expect("valid"));
+        }
+        self.edit_references(edits.into_iter().map(|name| RefEdit {
+            change: Change::Delete {
+                expected: PreviousValue::Any,
+                log: RefLog::AndReference,
+            },
+            name,
+            deref: false,
+        }))?;
+        for name in [
+            EXPECTED_REV,
+            "BISECT_ANCESTORS_OK",
+            LOG,
+            NAMES,
+            "BISECT_RUN",
+            TERMS,
+            "BISECT_FIRST_PARENT",
+            START,
+        ] {
+            let path = self.git_dir().join(name);
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(Error::RemoveFile { path, source }),
+            }
+        }
+        Ok(())
+    }
+
+    fn edit_bisect_head(&self, id: ObjectId) -> Result<(), Error> {
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: Default::default(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(id),
+            },
+            name: bisect::HEAD_NAME.try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(())
+    }
+
+    fn bisect_subject(&self, id: ObjectId) -> Result<BString, Error> {
+        Ok(self
+            .find_object(id)?
+            .peel_to_commit()?
+            .message()?
+            .summary()
+            .into_owned())
+    }
+
+    fn read_bisect_file(&self, name: &str) -> Result<Option<BString>, Error> {
+        let path = self.git_dir().join(name);
+        match std::fs::read(&path) {
+            Ok(content) => Ok(Some(content.into())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::ReadFile { path, source }),
+        }
+    }
+
+    fn write_bisect_file(&self, name: &str, content: &[u8]) -> Result<(), Error> {
+        let path = self.git_dir().join(name);
+        std::fs::write(&path, content).map_err(|source| Error::WriteFile { path, source })
+    }
+
+    fn append_to_bisect_log(&self, content: &[u8]) -> Result<(), Error> {
+        let path = self.git_dir().join(LOG);
+        std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(content))
+            .map_err(|source| Error::WriteFile { path, source })
+    }
+}
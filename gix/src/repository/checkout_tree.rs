@@ -0,0 +1,497 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::ObjectId;
+use gix_index::{Entry, entry::Flags};
+use gix_ref::{
+    Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+use crate::{
+    Repository,
+    bstr::{BStr, BString, ByteSlice},
+    checkout_tree,
+    checkout_tree::{Conflict, ConflictKind},
+    reset, switch,
+};
+
+/// The files to remove after [`Repository::reset()`](crate::Repository::reset()) as they belong to operations
+/// that can't be continued anymore.
+const OPERATION_STATE_FILES: &[&str] = &[
+    "MERGE_HEAD",
+    "MERGE_MSG",
+    "MERGE_MODE",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+    "SQUASH_MSG",
+];
+
+impl Repository {
+    /// Make the index and the worktree match `tree`, which may also be a commit that is peeled to its tree,
+    /// by performing a two-way merge between the tree of `HEAD` and `tree`, similar to `git read-tree -m -u HEAD <tree>`.
+    ///
+    /// Only files that differ between both trees are written or removed, and local changes to files that are the same
+    /// in both trees are kept, both in the index and in the worktree.
+    /// Unless [forced](checkout_tree::Options::with_force()), the checkout is refused without touching anything if it would
+    /// lose local changes, overwrite untracked files, or if the index has unresolved conflicts,
+    /// with all offending paths listed in the [error](checkout_tree::Error::Conflicts).
+    ///
+    /// Note that `HEAD` isn't changed, use [`switch()`](Self::switch()) or [`reset()`](Self::reset()) for that,
+    /// and that submodules are neither checked out nor removed.
+    /// If `should_interrupt` is set during the checkout, the worktree is left partially updated, while the index
+    /// remains unchanged.
+    pub fn checkout_tree(
+        &self,
+        tree: impl Into<ObjectId>,
+        should_interrupt: &AtomicBool,
+        options: checkout_tree::Options,
+    ) -> Result<checkout_tree::Outcome, checkout_tree::Error> {
+        let head_tree = self.head_tree_id_or_empty()?.detach();
+        self.checkout_tree_with_base(head_tree, tree, should_interrupt, options)
+    }
+
+    /// Like [`checkout_tree()`](Self::checkout_tree()), but assume that the index is based on `base_tree` instead of
+    /// the tree of `HEAD`.
+    pub(crate) fn checkout_tree_with_base(
+        &self,
+        base_tree: ObjectId,
+        tree: impl Into<ObjectId>,
+        should_interrupt: &AtomicBool,
+        options: checkout_tree::Options,
+    ) -> Result<checkout_tree::Outcome, checkout_tree::Error> {
+        let workdir = self.workdir().ok_or(checkout_tree::Error::MissingWorkDir)?;
+        let tree = self.find_object(tree.into())?.peel_to_tree()?.id;
+        let head = self.index_from_tree(&base_tree)?;
+        let target = self.index_from_tree(&tree)?;
+        let index = self.index_or_empty()?;
+
+        let mut versions = BTreeMap::<&BStr, Versions<'_>>::new();
+        let mut unmerged = BTreeSet::new();
+        for entry in head.entries() {
+            versions.entry(entry.path(&head)).or_default().head = Some(entry);
+        }
+        for entry in target.entries() {
+            versions.entry(entry.path(&target)).or_default().target = Some(entry);
+        }
+        for entry in index.entries() {
+            let path = entry.path(&index);
+            if entry.stage() == gix_index::entry::Stage::Unconflicted {
+                versions.entry(path).or_default().index = Some(entry);
+            } else {
+                versions.entry(path).or_default();
+                unmerged.insert(path);
+            }
+        }
+        let changes = self.local_changes()?;
+
+        let mut conflicts = Vec::new();
+        let mut result = Vec::with_capacity(versions.len());
+        let mut to_write = BTreeSet::new();
+        let mut to_remove = Vec::new();
+        for (path, Versions { head, index, target }) in &versions {
+            let is_modified = changes.modified.contains(*path);
+            let take_target = if options.force {
+                true
+            } else if unmerged.contains(path) {
+                conflicts.push(Conflict {
+                    rela_path: (*path).to_owned(),
+                    kind: ConflictKind::Unmerged,
+                });
+                continue;
+            } else if is_same(*head, *target) || is_same(*index, *target) {
+                false
+            } else if is_same(*index, *head) && !is_modified {
+                true
+            } else {
+                conflicts.push(Conflict {
+                    rela_path: (*path).to_owned(),
+                    kind: ConflictKind::LocalChanges,
+                });
+                continue;
+            };
+
+            if !take_target {
+                result.extend(index.map(|entry| (*path, entry)));
+                continue;
+            }
+            match target {
+                Some(target) => {
+                    let needs_write = !is_same(*index, Some(*target))
+                        || is_modified
+                        || changes.removed.contains(*path)
+                        || unmerged.contains(path);
+                    match index.filter(|_| !needs_write) {
+                        Some(index) => result.push((*path, index)),
+                        None => {
+                            to_write.insert(*path);
+                            result.push((*path, *target));
+                        }
+                    }
+                }
+                None => {
+                    if index.is_some() || head.is_some() {
+                        to_remove.push(*path);
+                    }
+                }
+            }
+        }
+
+        if !options.force {
+            // Tracked files are removed before writing files, which also replaces tracked files that are to be written.
+            let removed: BTreeSet<_> = to_remove
+                .iter()
+                .copied()
+                .chain(to_write.iter().copied().filter(|path| versions[path].index.is_some()))
+                .collect();
+            for path in &to_write {
+                if is_obstructed(workdir, path, &removed) {
+                    conflicts.push(Conflict {
+                        rela_path: (*path).to_owned(),
+                        kind: ConflictKind::Untracked,
+                    });
+                }
+            }
+        }
+        if !conflicts.is_empty() {
+            conflicts.sort_by(|a, b| a.rela_path.cmp(&b.rela_path));
+            return Err(checkout_tree::Error::Conflicts { conflicts });
+        }
+
+        let mut out = checkout_tree::Outcome::default();
+        for path in to_remove {
+            let is_submodule = versions[path]
+                .index
+                .or(versions[path].head)
+                .is_some_and(|entry| entry.mode.is_submodule());
+            let full_path = workdir.join(gix_path::from_bstr(path));
+            match std::fs::symlink_metadata(&full_path) {
+                Ok(md) if md.is_dir() => {
+                    if !is_submodule {
+                        continue;
+                    }
+                    // Submodules are only removed if they aren't checked out.
+                    std::fs::remove_dir(&full_path).ok();
+                }
+                Ok(_) => std::fs::remove_file(&full_path).map_err(|source| checkout_tree::Error::Remove {
+                    path: full_path.clone(),
+                    source,
+                })?,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(source) => {
+                    return Err(checkout_tree::Error::Remove {
+                        path: full_path,
+                        source,
+                    });
+                }
+            }
+            remove_empty_parent_directories(&full_path, workdir);
+            out.files_removed.push(path.to_owned());
+        }
+        for path in &to_write {
+            let full_path = workdir.join(gix_path::from_bstr(*path));
+            if std::fs::symlink_metadata(&full_path).is_ok_and(|md| !md.is_dir()) {
+                // Never write through symlinks or into files that may be hard-linked elsewhere.
+                std::fs::remove_file(&full_path).map_err(|source| checkout_tree::Error::Remove {
+                    path: full_path.clone(),
+                    source,
+                })?;
+            }
+        }
+
+        let mut state = gix_index::State::new(self.object_hash());
+        for (path, entry) in result {
+            state.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path);
+        }
+        state.sort_entries();
+
+        if !to_write.is_empty() {
+            let mut temporarily_skipped = Vec::new();
+            for (idx, (entry, path)) in state.entries_mut_with_paths().enumerate() {
+                if !to_write.contains(path) && !entry.flags.contains(Flags::SKIP_WORKTREE) {
+                    entry.flags.insert(Flags::SKIP_WORKTREE);
+                    temporarily_skipped.push(idx);
+                }
+            }
+
+            let mut opts = self.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
+            opts.destination_is_initially_empty = false;
+            opts.overwrite_existing = options.force;
+            opts.keep_going = false;
+            let outcome = gix_worktree_state::checkout(
+                &mut state,
+                workdir,
+                self.objects.clone().into_arc()?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                should_interrupt,
+                opts,
+            )?;
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(checkout_tree::Error::Interrupted);
+            }
+            if !outcome.collisions.is_empty() {
+                return Err(checkout_tree::Error::Conflicts {
+                    conflicts: outcome
+                        .collisions
+                        .into_iter()
+                        .map(|collision| Conflict {
+                            rela_path: collision.path,
+                            kind: ConflictKind::Untracked,
+                        })
+                        .collect(),
+                });
+            }
+
+            let entries = state.entries_mut();
+            for idx in temporarily_skipped {
+                entries[idx].flags.remove(Flags::SKIP_WORKTREE);
+            }
+            out.files_written = to_write.into_iter().map(ToOwned::to_owned).collect();
+            out.bytes_written = outcome.bytes_written;
+        }
+
+        let mut index = gix_index::File::from_state(state, self.index_path());
+        index.write(gix_index::write::Options {
+            fsync: self.config.fsync(gix_fs::fsync::Components::INDEX),
+            ..Default::default()
+        })?;
+        Ok(out)
+    }
+
+    /// Check out the commit of `target` with [`checkout_tree()`](Self::checkout_tree()) and `options`, and point `HEAD` to it
+    /// as a branch or detached, similar to `git switch <branch>` or `git switch --detach <commit>`.
+    ///
+    /// The reflog of `HEAD` receives an entry like `checkout: moving from main to feature`.
+    pub fn switch(
+        &self,
+        target: switch::Target,
+        should_interrupt: &AtomicBool,
+        options: checkout_tree::Options,
+    ) -> Result<switch::Outcome, switch::Error> {
+        let head = self.head()?;
+        let previous = head.id().map(crate::Id::detach);
+        let from = match head.referent_name() {
+            Some(name) => name.shorten().to_owned(),
+            None => previous.map_or_else(|| "HEAD".into(), |id| id.to_string().into()),
+        };
+        let (commit, to) = match &target {
+            switch::Target::Branch(name) => (
+                self.find_reference(name.as_ref())?.peel_to_id()?.detach(),
+                name.shorten().to_owned(),
+            ),
+            switch::Target::Detached(id) => (*id, id.to_string().into()),
+        };
+        let commit = self.find_object(commit)?.peel_to_commit()?.id;
+
+        let checkout = self.checkout_tree(commit, should_interrupt, options)?;
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("checkout: moving from {from} to {to}").into(),
+                },
+                expected: PreviousValue::Any,
+                new: match target {
+                    switch::Target::Branch(name) => Target::Symbolic(name),
+                    switch::Target::Detached(_) => Target::Object(commit),
+                },
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(switch::Outcome {
+            previous,
+            commit,
+            checkout,
+        })
+    }
+
+    /// Point the branch `HEAD` refers to, or `HEAD` itself if it is detached, to `commit`, and depending on `mode`,
+    /// make the index and worktree match it, similar to `git reset --soft|--mixed|--hard <commit>`.
+    ///
+    /// The previous commit is written to `ORIG_HEAD`, and the state of merges, cherry-picks and reverts in progress is removed.
+    /// The index and the worktree are updated before moving `HEAD`, so that an [`Mode::Hard`](reset::Mode::Hard) reset
+    /// that fails or is interrupted leaves `HEAD` in place.
+    pub fn reset(
+        &self,
+        commit: impl Into<ObjectId>,
+        mode: reset::Mode,
+        should_interrupt: &AtomicBool,
+    ) -> Result<reset::Outcome, reset::Error> {
+        let previous = self.head()?.id().map(crate::Id::detach);
+        let commit = self.find_object(commit.into())?.peel_to_commit()?;
+        let (commit, tree) = (commit.id, commit.tree_id()?.detach());
+
+        let checkout = match mode {
+            reset::Mode::Soft => None,
+            reset::Mode::Mixed => {
+                self.reset_index(&tree)?;
+                None
+            }
+            reset::Mode::Hard => Some(self.checkout_tree(
+                tree,
+                should_interrupt,
+                checkout_tree::Options::default().with_force(true),
+            )?),
+        };
+
+        if let Some(previous) = previous {
+            let path = self.git_dir().join("ORIG_HEAD");
+            std::fs::write(&path, format!("{previous}\n"))
+                .map_err(|source| reset::Error::WriteFile { path, source })?;
+        }
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("reset: moving to {commit}").into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(commit),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        })?;
+        for name in OPERATION_STATE_FILES {
+            std::fs::remove_file(self.git_dir().join(name)).ok();
+        }
+        Ok(reset::Outcome { previous, checkout })
+    }
+
+    /// Make the index match `tree`, but keep the information about the worktree of entries that don't change.
+    fn reset_index(&self, tree: &gix_hash::oid) -> Result<(), reset::Error> {
+        let mut target = self.index_from_tree(tree)?;
+        let index = self.index_or_empty()?;
+        for (entry, path) in target.entries_mut_with_paths() {
+            if let Some(current) = index
+                .entry_by_path(path)
+                .filter(|current| current.id == entry.id && current.mode == entry.mode)
+            {
+                entry.stat = current.stat;
+            }
+        }
+        target.write(gix_index::write::Options {
+            fsync: self.config.fsync(gix_fs::fsync::Components::INDEX),
+            ..Default::default()
+        })?;
+        Ok(())
+    }
+
+    /// Return the paths of tracked files that are changed or removed in the worktree, ignoring submodules.
+    fn local_changes(&self) -> Result<LocalChanges, checkout_tree::Error> {
+        use crate::status::index_worktree::iter::Summary;
+        let mut changes = LocalChanges::default();
+        if self.workdir().is_none() {
+            return Ok(changes);
+        }
+        let iter = self
+            .status(gix_features::progress::Discard)?
+            .index_worktree_rewrites(None)
+            .index_worktree_submodules(crate::status::Submodule::Given {
+                ignore: crate::submodule::config::Ignore::All,
+                check_dirty: false,
+            })
+            .index_worktree_options_mut(|opts| {
+                opts.dirwalk_options = None;
+            })
+            .into_index_worktree_iter(Vec::new())?;
+        for item in iter {
+            let item = item?;
+            let Some(summary) = item.summary() else {
+                continue;
+            };
+            let path = item.rela_path().to_owned();
+            match summary {
+                Summary::Removed => changes.removed.insert(path),
+                _ => changes.modified.insert(path),
+            };
+        }
+        Ok(changes)
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct Versions<'a> {
+    head: Option<&'a Entry>,
+    index: Option<&'a Entry>,
+    target: Option<&'a Entry>,
+}
+
+#[derive(Default)]
+struct LocalChanges {
+    /// Paths whose index or worktree version differ.
+    modified: BTreeSet<BString>,
+    /// Paths that are missing in the worktree.
+    removed: BTreeSet<BString>,
+}
+
+fn is_same(a: Option<&Entry>, b: Option<&Entry>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.id == b.id && a.mode == b.mode,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Return `true` if an untracked file or directory is in the way of writing the file at `rela_path`, or any of its
+/// leading components, unless it's a tracked file that is about to be `removed`.
+fn is_obstructed(workdir: &Path, rela_path: &BStr, removed: &BTreeSet<&BStr>) -> bool {
+    let mut leading = rela_path;
+    let mut components = Vec::new();
+    while let Some(pos) = leading.rfind_byte(b'/') {
+        leading = leading[..pos].as_bstr();
+        components.push(leading);
+    }
+    for component in components.into_iter().rev() {
+        match std::fs::symlink_metadata(workdir.join(gix_path::from_bstr(component))) {
+            Ok(md) if md.is_dir() => continue,
+            Ok(_) => return !removed.contains(component),
+            Err(_) => return false,
+        }
+    }
+    let path = workdir.join(gix_path::from_bstr(rela_path));
+    match std::fs::symlink_metadata(&path) {
+        Ok(md) if md.is_dir() => has_untracked_files(&path, rela_path, removed),
+        Ok(_) => !removed.contains(rela_path),
+        Err(_) => false,
+    }
+}
+
+fn has_untracked_files(dir: &Path, rela_dir: &BStr, removed: &BTreeSet<&BStr>) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return true;
+    };
+    for entry in entries {
+        let Ok(entry) = entry else {
+            return true;
+        };
+        let mut rela_path = rela_dir.to_owned();
+        rela_path.push(b'/');
+        rela_path.extend_from_slice(gix_path::os_str_into_bstr(&entry.file_name()).unwrap_or_default());
+        let is_untracked = if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            has_untracked_files(&entry.path(), rela_path.as_ref(), removed)
+        } else {
+            !removed.contains(rela_path.as_bstr())
+        };
+        if is_untracked {
+            return true;
+        }
+    }
+    false
+}
+
+pub(super) fn remove_empty_parent_directories(path: &Path, workdir: &Path) {
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+}
@@ -18,6 +18,24 @@ impl crate::Repository {
         )
     }
 
+    /// Return the `(window, depth)` to use when searching for deltas while creating packs, as configured with
+    /// `pack.window` and `pack.depth`, or `(10, 50)` if unset, just like in `git`.
+    pub fn pack_window_and_depth(&self) -> Result<(usize, usize), config::Error> {
+        use crate::config::{cache::util::ApplyLeniency, tree::Pack};
+        let value = |key: &'static config::tree::keys::UnsignedInteger, default: usize| {
+            Ok::<_, config::Error>(
+                key.try_into_usize(
+                    self.config
+                        .resolved
+                        .integer_filter(key, &mut self.filter_config_section()),
+                )
+                .with_leniency(self.config.lenient_config)?
+                .unwrap_or(default),
+            )
+        };
+        Ok((value(&Pack::WINDOW, 10)?, value(&Pack::DEPTH, 50)?))
+    }
+
     /// Return a snapshot of the configuration as seen upon opening the repository.
     ///
     /// Use [`reload()`](Self::reload()) to refresh it from disk.
@@ -1,4 +1,8 @@
-use crate::Error;
+use std::collections::VecDeque;
+
+use gix_object::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::{Error, repository::write_commit_graph};
 
 impl crate::Repository {
     /// Create a graph data-structure capable of accelerating graph traversals and storing state of type `T` with each commit
@@ -44,4 +48,173 @@ impl crate::Repository {
                 _ => Err(err.into_error()),
             })?)
     }
+
+    /// Write a commit-graph with all commits reachable from `tips` into the object database, to accelerate future commit walks.
+    ///
+    /// If [`split`](write_commit_graph::Options::split) is `None`, a single commit-graph file with all reachable commits
+    /// replaces any existing commit-graph. Otherwise, only commits which aren't yet part of the split commit-graph are added
+    /// as a new layer, which is merged with existing layers according to the given strategy.
+    ///
+    /// Changed-path Bloom filters are computed if [`changed_paths`](write_commit_graph::Options::changed_paths) is set, or
+    /// if the existing commit-graph contains them, with filters of the existing commit-graph being reused.
+    pub fn write_commit_graph(
+        &self,
+        tips: impl IntoIterator<Item = impl Into<gix_hash::ObjectId>>,
+        write_commit_graph::Options { split, changed_paths }: write_commit_graph::Options,
+    ) -> Result<gix_commitgraph::write::Outcome, write_commit_graph::Error> {
+        let info_dir = self.objects.store_ref().path().join("info");
+        let existing = match split {
+            Some(_) => gix_commitgraph::Graph::from_commit_graphs_dir(&info_dir.join("commit-graphs")).ok(),
+            None => gix_commitgraph::Graph::from_info_dir(&info_dir).ok(),
+        };
+        let bloom_filter_settings = existing
+            .as_ref()
+            .and_then(gix_commitgraph::Graph::bloom_filter_settings)
+            .or_else(|| changed_paths.then(gix_commitgraph::bloom::Settings::default));
+        let mut commits = Vec::new();
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut queue: Vec<_> = tips.into_iter().map(Into::into).collect();
+        let mut changed_paths = ChangedPaths::default();
+        let (mut buf, mut parent_buf) = (Vec::new(), Vec::new());
+        while let Some(id) = queue.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let existing_commit = existing.as_ref().and_then(|graph| graph.commit_by_id(id));
+            if split.is_some() && existing_commit.is_some() {
+                continue;
+            }
+            let commit = self.find_commit(id)?;
+            let parents: Vec<_> = commit.parent_ids().map(crate::Id::detach).collect();
+            queue.extend(parents.iter().copied());
+            let tree = commit.tree_id()?.detach();
+            let changed_paths_filter = match bloom_filter_settings {
+                Some(settings) => Some(
+                    match existing_commit
+                        .and_then(|commit| commit.changed_paths_filter())
+                        .filter(|filter| filter.settings() == settings)
+                    {
+                        Some(filter) => filter.as_bytes().to_vec(),
+                        None => {
+                            let parent_tree = parents
+                                .first()
+                                .map(|parent| -> Result<_, write_commit_graph::Error> {
+                                    Ok(self.find_commit(*parent)?.tree_id()?.detach())
+                                })
+                                .transpose()?;
+                            changed_paths.compute(self, parent_tree, tree, &mut parent_buf, &mut buf)?;
+                            gix_commitgraph::bloom::filter_from_changed_paths(
+                                changed_paths.paths.iter().map(AsRef::as_ref),
+                                &settings,
+                            )
+                        }
+                    },
+                ),
+                None => None,
+            };
+            commits.push(gix_commitgraph::write::Commit {
+                id,
+                tree,
+                parents,
+                committer_timestamp: commit.time()?.seconds.max(0) as u64,
+                changed_paths_filter,
+            });
+        }
+        drop(existing);
+        gix_commitgraph::write(
+            &info_dir,
+            commits,
+            gix_commitgraph::write::Options {
+                object_hash: self.object_hash(),
+                split,
+                changed_paths: bloom_filter_settings,
+            },
+        )
+        .map_err(|err| crate::Error::from(err).into())
+    }
+}
+
+/// Collect the paths of all blobs and submodules that changed between two trees, for use in changed-path Bloom filters.
+#[derive(Default)]
+struct ChangedPaths {
+    paths: Vec<BString>,
+    path: BString,
+    path_deque: VecDeque<BString>,
+}
+
+impl ChangedPaths {
+    /// Diff `parent_tree` or the empty tree with `tree` and collect the changed paths, stopping early if there are too many
+    /// for a filter.
+    fn compute(
+        &mut self,
+        repo: &crate::Repository,
+        parent_tree: Option<gix_hash::ObjectId>,
+        tree: gix_hash::ObjectId,
+        parent_buf: &mut Vec<u8>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), write_commit_graph::Error> {
+        use gix_object::FindExt;
+
+        self.paths.clear();
+        self.path.clear();
+        self.path_deque.clear();
+        let object_hash = repo.object_hash();
+        let lhs = match parent_tree {
+            Some(id) => repo.objects.find_tree_iter(&id, parent_buf)?,
+            None => gix_object::TreeRefIter::from_bytes(&[], object_hash),
+        };
+        let rhs = repo.objects.find_tree_iter(&tree, buf)?;
+        match gix_diff::tree(lhs, rhs, gix_diff::tree::State::default(), &repo.objects, self) {
+            Ok(()) | Err(gix_diff::tree::Error::Cancelled) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn push_element(&mut self, name: &BStr) {
+        if !self.path.is_empty() {
+            self.path.push(b'/');
+        }
+        self.path.push_str(name);
+    }
+}
+
+impl gix_diff::tree::Visit for ChangedPaths {
+    fn pop_front_tracked_path_and_set_current(&mut self) {
+        self.path = self.path_deque.pop_front().expect("every parent is set only once");
+    }
+
+    fn push_back_tracked_path_component(&mut self, component: &BStr) {
+        self.push_element(component);
+        self.path_deque.push_back(self.path.clone());
+    }
+
+    fn push_path_component(&mut self, component: &BStr) {
+        self.push_element(component);
+    }
+
+    fn pop_path_component(&mut self) {
+        match self.path.rfind_byte(b'/') {
+            Some(pos) => self.path.truncate(pos),
+            None => self.path.clear(),
+        }
+    }
+
+    fn visit(&mut self, change: gix_diff::tree::visit::Change) -> gix_diff::tree::visit::Action {
+        use gix_diff::tree::visit::Change;
+        let is_tree = match change {
+            Change::Addition { entry_mode, .. } | Change::Deletion { entry_mode, .. } => entry_mode.is_tree(),
+            Change::Modification {
+                previous_entry_mode,
+                entry_mode,
+                ..
+            } => previous_entry_mode.is_tree() && entry_mode.is_tree(),
+        };
+        if !is_tree {
+            self.paths.push(self.path.clone());
+            if self.paths.len() > gix_commitgraph::bloom::MAX_CHANGED_PATHS {
+                return std::ops::ControlFlow::Break(());
+            }
+        }
+        std::ops::ControlFlow::Continue(())
+    }
 }
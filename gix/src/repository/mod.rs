@@ -14,14 +14,22 @@ pub enum Kind {
     LinkedWorkTree,
 }
 
+#[cfg(feature = "apply")]
+mod apply;
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
+#[cfg(feature = "revision")]
+mod batch;
+#[cfg(all(feature = "revision", feature = "worktree-mutation", feature = "status"))]
+mod bisect;
 ///
 #[cfg(feature = "blame")]
 mod blame;
 mod cache;
 #[cfg(feature = "worktree-mutation")]
 mod checkout;
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+mod checkout_tree;
 mod config;
 
 ///
@@ -47,23 +55,38 @@ mod mailmap;
 ///
 #[cfg(feature = "merge")]
 mod merge;
+#[cfg(feature = "notes")]
+mod note;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "rebase")]
+mod rebase;
+#[cfg(feature = "blocking-network-client")]
+mod receive_pack;
 mod reference;
 mod remote;
+#[cfg(feature = "maintenance")]
+mod repack;
 mod revision;
+#[cfg(feature = "sequencer")]
+mod sequencer;
 mod shallow;
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+mod stash;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
 mod thread_safe;
+#[cfg(feature = "blocking-network-client")]
+mod upload_pack;
 mod worktree;
 
 ///
-mod new_commit {
+pub mod new_commit {
     /// The error returned by [`new_commit(…)`](crate::Repository::new_commit()).
     #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
     pub enum Error {
         #[error(transparent)]
         ParseTime(#[from] crate::config::time::Error),
@@ -386,6 +409,38 @@ pub mod commit_graph_if_enabled {
     }
 }
 
+///
+pub mod write_commit_graph {
+    /// Options for use in [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// If `None`, write a single commit-graph file with all commits. Otherwise, add a layer to the split commit-graph,
+        /// merging existing layers according to the given strategy.
+        pub split: Option<gix_commitgraph::write::SplitStrategy>,
+        /// If `true`, compute changed-path Bloom filters to accelerate path-limited history traversals, similar to
+        /// `git commit-graph write --changed-paths`.
+        pub changed_paths: bool,
+    }
+
+    /// The error returned by [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        CommitTime(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        FindTree(#[from] gix_object::find::existing_iter::Error),
+        #[error("Could not compute the paths changed by a commit")]
+        DiffTree(#[from] gix_diff::tree::Error),
+        #[error(transparent)]
+        WriteCommitGraph(#[from] crate::Error),
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod index_from_tree {
@@ -506,6 +561,23 @@ pub mod index_or_load_from_head_or_empty {
     }
 }
 
+///
+#[cfg(all(
+    feature = "attributes",
+    any(feature = "maintenance", feature = "blocking-network-client")
+))]
+pub mod pack_no_delta {
+    /// The error returned when preparing to look up the `delta` attribute of blobs to pack.
+    #[derive(thiserror::Error, Debug)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Index(#[from] crate::repository::index_or_load_from_head_or_empty::Error),
+        #[error(transparent)]
+        AttributeStack(#[from] crate::config::attribute_stack::Error),
+    }
+}
+
 ///
 #[cfg(feature = "worktree-stream")]
 pub mod worktree_stream {
@@ -0,0 +1,80 @@
+use gix_ref::FullName;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::tree::{Core, Notes},
+    note,
+};
+
+impl crate::Repository {
+    /// Return a platform to read and edit the notes in the default notes reference, which is `core.notesRef`
+    /// or [`refs/notes/commits`](note::DEFAULT_REF) if it isn't set.
+    pub fn notes(&self) -> Result<note::Platform<'_>, note::init::Error> {
+        let name = self
+            .config
+            .resolved
+            .string(Core::NOTES_REF)
+            .map_or_else(|| note::DEFAULT_REF.into(), |name| note::expand_ref_name(name.as_ref()));
+        self.notes_platform(name)
+    }
+
+    /// Return a platform to read and edit the notes in the notes reference `name`, which is expanded
+    /// into the `refs/notes/` namespace if it's abbreviated, so `foo` refers to `refs/notes/foo`.
+    pub fn notes_at<'a>(&self, name: impl Into<&'a BStr>) -> Result<note::Platform<'_>, note::init::Error> {
+        self.notes_platform(note::expand_ref_name(name.into()))
+    }
+
+    /// Return the names of all notes references whose notes should be displayed along with commits, similar to `git log`.
+    ///
+    /// These are the default notes reference as used by [`notes()`](Self::notes()), followed by the ones configured
+    /// in `notes.displayRef`. Values with glob patterns are matched against all existing references in `refs/notes/`,
+    /// while plain values are used whether or not the reference exists.
+    pub fn notes_display_refs(&self) -> Result<Vec<FullName>, note::display_refs::Error> {
+        let mut out = vec![self.notes()?.name];
+        let mut existing = None::<Vec<FullName>>;
+        for name in self
+            .config
+            .resolved
+            .strings(Notes::DISPLAY_REF)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| note::expand_ref_name(name.as_ref()))
+        {
+            if name.find_byteset(b"*?[").is_some() {
+                let existing = match &mut existing {
+                    Some(existing) => existing,
+                    None => existing.insert(
+                        self.references()?
+                            .prefixed(b"refs/notes/")?
+                            .map(|r| r.map(|r| r.inner.name))
+                            .collect::<Result<_, _>>()?,
+                    ),
+                };
+                out.extend(
+                    existing
+                        .iter()
+                        .filter(|existing| {
+                            gix_glob::wildmatch(name.as_bstr(), existing.as_bstr(), gix_glob::wildmatch::Mode::empty())
+                        })
+                        .cloned(),
+                );
+            } else {
+                out.push(to_full_name(name)?);
+            }
+        }
+        let mut seen = std::collections::BTreeSet::new();
+        out.retain(|name| seen.insert(name.clone()));
+        Ok(out)
+    }
+
+    fn notes_platform(&self, name: BString) -> Result<note::Platform<'_>, note::init::Error> {
+        Ok(note::Platform {
+            repo: self,
+            name: to_full_name(name)?,
+        })
+    }
+}
+
+fn to_full_name(name: BString) -> Result<FullName, note::init::Error> {
+    FullName::try_from(name.clone()).map_err(|source| note::init::Error::InvalidName { name, source })
+}
@@ -0,0 +1,239 @@
+use gix_error::Exn;
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_ref::{
+    FullNameRef, Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+use crate::{Repository, bstr::ByteSlice, prelude::ObjectIdExt, rebase};
+
+impl Repository {
+    /// Replay the commits of `branch` that aren't reachable from `upstream` one by one onto a new base, and point `branch`
+    /// to the last replayed commit, similar to `git rebase <upstream> <branch>`, but without touching the index or worktree.
+    ///
+    /// The new base is `upstream` unless configured otherwise in [`options`](rebase::Options::with_onto()).
+    /// Merge commits are skipped, and the commits are replayed with the configured committer as described
+    /// in [`gix_rebase::replay()`].
+    ///
+    /// If a commit doesn't apply cleanly, the rebase stops, `branch` is left unchanged, and the returned
+    /// [outcome](rebase::Outcome::stopped) provides the conflicting merge result along with the commits that remain.
+    /// If [enabled](rebase::Options::with_write_state()), the state of the rebase is then written so that
+    /// `git rebase --continue` can pick it up.
+    /// Note that even then the index and worktree remain untouched, so they have to be updated by the caller
+    /// to resolve the conflicts, for instance based on the [merge result](rebase::Stop::tree_merge).
+    ///
+    /// `options` should be initialized with [`Repository::tree_merge_options().into()`](Self::tree_merge_options()).
+    ///
+    /// ### Performance
+    ///
+    /// It's highly recommended to [set an object cache](Repository::compute_object_cache_size_for_tree_diffs)
+    /// to avoid extracting the same object multiple times.
+    pub fn rebase(
+        &self,
+        upstream: impl Into<ObjectId>,
+        branch: &FullNameRef,
+        options: rebase::Options,
+    ) -> Result<rebase::Outcome<'_>, rebase::Error> {
+        let rebase::Options {
+            tree_merge,
+            treat_as_unresolved,
+            onto,
+            empty,
+            write_state,
+        } = options;
+        let state_dir = self.git_dir().join(rebase::plumbing::state::DIR_NAME);
+        if write_state && state_dir.exists() {
+            return Err(rebase::Error::InProgress);
+        }
+        let committer = self.committer().ok_or(rebase::Error::CommitterMissing)??;
+
+        let upstream = upstream.into();
+        let orig_head = self.find_reference(branch)?.peel_to_id()?.detach();
+        let onto = match onto {
+            rebase::Onto::Upstream => upstream,
+            rebase::Onto::Commit(id) => id,
+            rebase::Onto::KeepBase => self.merge_base(upstream, orig_head)?.detach(),
+        };
+        let mut commits = Vec::new();
+        for info in self.rev_walk(Some(orig_head)).with_hidden(Some(upstream)).all()? {
+            let info = info?;
+            if info.parent_ids.len() <= 1 {
+                commits.push(info.id);
+            }
+        }
+        commits.reverse();
+
+        let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
+        let mut blob_merge = self.merge_resource_cache(Default::default())?;
+        let gix_rebase::replay::Outcome { head, steps, stopped } = gix_rebase::replay(
+            onto,
+            &commits,
+            committer,
+            self,
+            &mut diff_cache,
+            &mut blob_merge,
+            &mut |id| id.to_owned().attach(self).shorten_or_id().to_string(),
+            gix_rebase::replay::Options {
+                tree_merge: tree_merge.into(),
+                treat_as_unresolved,
+                empty,
+            },
+        )
+        .map_err(Exn::into_error)?;
+
+        let Some(gix_rebase::replay::Stop { commit, tree_merge }) = stopped else {
+            let message = format!("rebase (finish): {} onto {onto}", branch.as_bstr());
+            self.edit_references_as(
+                Some(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: message.into(),
+                        },
+                        expected: PreviousValue::MustExistAndMatch(Target::Object(orig_head)),
+                        new: Target::Object(head),
+                    },
+                    name: branch.to_owned(),
+                    deref: false,
+                }),
+                Some(committer),
+            )?;
+            return Ok(rebase::Outcome {
+                onto: onto.attach(self),
+                head: head.attach(self),
+                steps,
+                stopped: None,
+            });
+        };
+
+        let stopped_at = commits
+            .iter()
+            .position(|id| *id == commit)
+            .expect("the commit we stopped at is one of ours");
+        if write_state {
+            self.write_rebase_state(
+                &state_dir,
+                branch,
+                onto,
+                orig_head,
+                head,
+                &commits[..=stopped_at],
+                &commits[stopped_at + 1..],
+                empty,
+                committer,
+            )?;
+        }
+
+        let validate = self.config.protect_options()?;
+        Ok(rebase::Outcome {
+            onto: onto.attach(self),
+            head: head.attach(self),
+            steps,
+            stopped: Some(rebase::Stop {
+                commit: commit.attach(self),
+                tree_merge: crate::merge::tree::Outcome {
+                    tree: crate::object::tree::Editor {
+                        inner: tree_merge.tree,
+                        validate,
+                        repo: self,
+                    },
+                    conflicts: tree_merge.conflicts,
+                    failed_on_first_unresolved_conflict: tree_merge.failed_on_first_unresolved_conflict,
+                },
+                remaining: commits[stopped_at + 1..].to_vec(),
+            }),
+        })
+    }
+
+    /// Return the state of the rebase in progress as stored in `.git/rebase-merge`, or `None` if there is none.
+    ///
+    /// Note that rebases using the `apply` backend, as stored in `.git/rebase-apply`, aren't supported.
+    pub fn rebase_state(&self) -> Result<Option<rebase::State>, rebase::state::Error> {
+        rebase::State::from_dir(
+            &self.git_dir().join(rebase::plumbing::state::DIR_NAME),
+            self.object_hash(),
+        )
+        .map_err(Exn::into_error)
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    fn write_rebase_state(
+        &self,
+        state_dir: &std::path::Path,
+        branch: &FullNameRef,
+        onto: ObjectId,
+        orig_head: ObjectId,
+        head: ObjectId,
+        done: &[ObjectId],
+        todo: &[ObjectId],
+        empty: rebase::Empty,
+        committer: gix_actor::SignatureRef<'_>,
+    ) -> Result<(), rebase::Error> {
+        let mut buf = Vec::new();
+        let mut to_lines = |ids: &[ObjectId]| -> Result<Vec<_>, rebase::Error> {
+            ids.iter()
+                .map(|id| {
+                    Ok(gix_rebase::todo::Line {
+                        action: gix_rebase::todo::Action::Pick,
+                        id: *id,
+                        subject: self.objects.find_commit(id, &mut buf)?.message_summary().into_owned(),
+                    })
+                })
+                .collect()
+        };
+        let (done, todo) = (to_lines(done)?, to_lines(todo)?);
+        let stopped_id = done.last().expect("we stopped at a commit").id;
+        let stopped_commit = self.objects.find_commit(&stopped_id, &mut buf)?;
+        let stopped = gix_rebase::state::Stopped {
+            commit: stopped_id,
+            message: stopped_commit.message.to_owned(),
+            author: stopped_commit.author()?.into(),
+        };
+
+        rebase::State {
+            head_name: Some(branch.as_bstr().to_owned()),
+            onto,
+            orig_head,
+            done,
+            todo,
+            empty,
+            stopped: Some(stopped),
+        }
+        .write_to(state_dir)
+        .map_err(Exn::into_error)?;
+        for (name, id) in [("ORIG_HEAD", orig_head), ("REBASE_HEAD", stopped_id)] {
+            let path = self.git_dir().join(name);
+            std::fs::write(&path, format!("{id}\n")).map_err(|source| rebase::Error::WriteFile { path, source })?;
+        }
+
+        let message = if head == onto {
+            format!("rebase (start): checkout {onto}")
+        } else {
+            let summary = self
+                .objects
+                .find_commit(&head, &mut Vec::new())?
+                .message_summary()
+                .into_owned();
+            format!("rebase (pick): {}", summary.to_str_lossy())
+        };
+        self.edit_references_as(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: message.into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Object(head),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: false,
+            }),
+            Some(committer),
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use std::{
+    io::{Read, Write},
+    sync::atomic::AtomicBool,
+};
+
+use crate::{Repository, bstr::BString, config::tree::Receive, receive_pack};
+
+impl Repository {
+    /// Serve the `receive-pack` service to a client which sends its requests through `read` and receives our responses
+    /// through `write`, allowing it to list our references and to update them along with the objects they need.
+    ///
+    /// The received pack is kept in a quarantine directory until each reference update passed all validations, the last of
+    /// which is `check`, which may refuse an update with a reason that is sent to the client.
+    /// Objects are only moved into the object database if at least one reference is updated.
+    /// `read` must not contain the initial request that is sent to a `git` daemon, and `should_interrupt` aborts receiving
+    /// a pack if set.
+    ///
+    /// ### Configuration
+    ///
+    /// - `receive.advertisePushOptions` allows clients to send push options, which are passed to `check`.
+    /// - `receive.denyCurrentBranch` refuses updates to the branch that is checked out in non-bare repositories unless it's
+    ///   set to `warn`, `ignore` or `false`.
+    /// - `receive.denyDeletes` refuses to delete branches.
+    /// - `receive.denyNonFastForwards` refuses to update branches if the previous commit isn't an ancestor of the new one.
+    ///
+    /// ### Limitations
+    ///
+    /// * pushes into shallow repositories and pushes from shallow clients aren't supported.
+    /// * hooks aren't run, with `check` serving the purpose of the `update` hook.
+    pub fn receive_pack(
+        &self,
+        read: impl Read,
+        write: impl Write,
+        mut check: impl FnMut(receive_pack::Check<'_>) -> Result<(), BString>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), receive_pack::Error> {
+        let advertise_push_options = Receive::ADVERTISE_PUSH_OPTIONS
+            .enrich_error(
+                self.config
+                    .resolved
+                    .boolean_filter(Receive::ADVERTISE_PUSH_OPTIONS, &mut self.filter_config_section()),
+            )?
+            .unwrap_or(false);
+        let mut delegate = receive_pack::Delegate::new(self, &mut check)?;
+        Ok(gix_protocol::receive_pack(
+            read,
+            write,
+            &mut delegate,
+            should_interrupt,
+            gix_protocol::receive_pack::Options {
+                user_agent: self.config.user_agent_tuple(),
+                advertise_push_options,
+                trace_packetlines: self.config.trace_packet(),
+            },
+        )?)
+    }
+}
@@ -154,12 +154,18 @@ impl crate::Repository {
     /// A way to apply reference `edits` similar to [edit_references(…)](Self::edit_references()), but set a specific
     /// `commiter` for use in the reflog. It can be `None` if it's the purpose `edits` are configured to not update the
     /// reference log, or cause a failure otherwise.
+    ///
+    /// Loose objects written with `core.fsyncMethod=batch` are made durable first so references never point to objects
+    /// that could be lost.
     pub fn edit_references_as(
         &self,
         edits: impl IntoIterator<Item = RefEdit>,
         committer: Option<gix_actor::SignatureRef<'_>>,
     ) -> Result<Vec<RefEdit>, reference::edit::Error> {
         let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
+        self.objects
+            .flush_loose_object_batch()
+            .map_err(reference::edit::Error::FlushLooseObjectBatch)?;
         self.refs
             .transaction()
             .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
@@ -225,6 +231,23 @@ impl crate::Repository {
         Ok(self.head()?.try_into_referent())
     }
 
+    /// Parse all entries of the `FETCH_HEAD` file as written by the most recent fetch, in the order they were written,
+    /// or return an empty list if there is no such file.
+    ///
+    /// Note that `FETCH_HEAD` can also be [resolved as reference](Self::find_reference()), which yields the first entry
+    /// as it would typically be the one to merge.
+    pub fn fetch_head(&self) -> Result<Vec<gix_fetchhead::Entry>, reference::fetch_head::Error> {
+        let path = self.git_dir().join("FETCH_HEAD");
+        let buf = match std::fs::read(&path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(reference::fetch_head::Error::Io { path, source }),
+        };
+        Ok(gix_fetchhead::parse(&buf)
+            .collect::<Result<_, _>>()
+            .map_err(gix_error::Exn::into_error)?)
+    }
+
     /// Return the commit object the `HEAD` reference currently points to after peeling it fully,
     /// following symbolic references and tags until a commit is found.
     ///
@@ -0,0 +1,532 @@
+use std::{
+    collections::BTreeSet,
+    io::{BufReader, Seek, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use gix_features::{
+    parallel::InOrderIter,
+    progress::{Count, DynNestedProgress, Progress},
+};
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_object::Write as _;
+use gix_pack::data::output;
+
+use crate::{
+    Repository,
+    bstr::BStr,
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Gc, Pack, Repack},
+    },
+    gc, repack,
+};
+
+impl Repository {
+    /// Write all objects that are reachable from references, their reflogs, and the `HEAD` and index of each worktree
+    /// into a single new pack, reusing deltas of existing packs, and remove all packs and loose objects that became obsolete,
+    /// similar to `git repack -a -d`.
+    ///
+    /// Packs with a `.keep` file are left untouched and objects within them aren't added to the new pack.
+    /// Packs that are added while repacking are also left alone.
+    /// `progress` receives information about counting and writing objects, and `should_interrupt` aborts the operation
+    /// before anything is removed.
+    ///
+    /// Obsolete packs are removed index-first so that the object database stops seeing them before their data disappears.
+    /// Instances which still have them loaded keep using their memory maps, and all others refresh and find the objects
+    /// in the new pack instead. A multi-pack index is removed along with the packs it refers to.
+    ///
+    /// ### Limitations
+    ///
+    /// * shallow repositories aren't supported.
+    /// * objects that are only available through alternates are added to the new pack.
+    /// * packs that can't be removed, for instance because they are still mapped on Windows, are left in place. They are
+    ///   not reported as removed and are picked up again by the next call.
+    pub fn repack(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: repack::Options,
+    ) -> Result<repack::Outcome, repack::Error> {
+        self.repack_inner(progress, should_interrupt, options)
+            .map(|(outcome, _reachable)| outcome)
+    }
+
+    /// Repack all reachable objects like [`repack()`](Self::repack()), and remove unreachable loose objects that
+    /// are older than `gc.pruneExpire`, which is two weeks by default, similar to `git gc`.
+    ///
+    /// Unreachable objects in obsolete packs that weren't modified for as long are removed with them,
+    /// while all others are written as loose objects so they can expire later.
+    /// If `gc.pruneExpire` is `never`, no unreachable object is ever removed.
+    /// A reachability bitmap is written along with the new pack if `repack.writeBitmaps` is set,
+    /// which is the default in bare repositories, unless some of the reachable objects remain in kept packs.
+    ///
+    /// ### Limitations
+    ///
+    /// * references aren't packed, and reflogs don't expire.
+    /// * the same limitations as for [`repack()`](Self::repack()) apply.
+    pub fn gc(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gc::Outcome, gc::Error> {
+        let config = &self.config.resolved;
+        let lenient = self.config.lenient_config;
+        let now = SystemTime::now();
+        let expire = match config.string_filter(Gc::PRUNE_EXPIRE, &mut self.filter_config_section()) {
+            // Invalid values are ignored if lenient, which means nothing is ever pruned.
+            Some(value) => Gc::PRUNE_EXPIRE
+                .try_into_prune_expire(value, now)
+                .with_leniency(lenient)?
+                .map(|time| time.seconds),
+            None => Gc::PRUNE_EXPIRE
+                .try_into_prune_expire("2.weeks.ago", now)
+                .expect("valid default")
+                .map(|time| time.seconds),
+        };
+        let write_bitmap = Repack::WRITE_BITMAPS
+            .enrich_error(config.boolean_filter(Repack::WRITE_BITMAPS, &mut self.filter_config_section()))
+            .with_leniency(lenient)?
+            .unwrap_or_else(|| self.is_bare());
+
+        let (repack, reachable) = self.repack_inner(
+            progress,
+            should_interrupt,
+            repack::Options::default()
+                .with_bitmap(write_bitmap)
+                .with_unpack_unreachable_since(Some(expire.unwrap_or(gix_date::SecondsSinceUnixEpoch::MIN))),
+        )?;
+
+        let mut pruned_objects = 0;
+        if let Some(expire) = expire {
+            let loose = self.loose_objects();
+            let mut directories = BTreeSet::new();
+            for id in loose.iter() {
+                let id = id?;
+                if reachable.contains(&id) {
+                    continue;
+                }
+                let path = loose.object_path(&id);
+                let Ok(mtime) = std::fs::metadata(&path).and_then(|md| md.modified()) else {
+                    continue;
+                };
+                if seconds_since_unix_epoch(mtime) > expire {
+                    continue;
+                }
+                std::fs::remove_file(&path)?;
+                pruned_objects += 1;
+                directories.extend(path.parent().map(ToOwned::to_owned));
+            }
+            remove_empty_directories(directories);
+        }
+        Ok(gc::Outcome { repack, pruned_objects })
+    }
+
+    /// Like [`repack()`](Self::repack()), but also return the set of all reachable objects.
+    fn repack_inner(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: repack::Options,
+    ) -> Result<(repack::Outcome, HashSet<ObjectId>), repack::Error> {
+        if self.is_shallow() {
+            return Err(repack::Error::Shallow);
+        }
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        // Packs are listed before counting so those which are added concurrently are never removed.
+        let mut packs = Packs::at(&pack_dir, self.object_hash())?;
+
+        let mut handle = self
+            .objects
+            .clone()
+            .into_arc()
+            .map_err(repack::Error::OpenObjectDatabase)?
+            .into_inner();
+        handle.prevent_pack_unload();
+        handle.ignore_replacements = true;
+
+        let tips = self.reachability_tips()?;
+        let mut counting = progress.add_child_with_id("counting".into(), *b"RPCO");
+        counting.init(None, gix_features::progress::count("objects"));
+        #[cfg(feature = "attributes")]
+        let mut no_delta = self.pack_no_delta()?;
+        #[cfg(feature = "attributes")]
+        let no_delta: Option<&mut dyn FnMut(&crate::bstr::BStr) -> bool> = Some(&mut no_delta);
+        #[cfg(not(feature = "attributes"))]
+        let no_delta = None;
+        let (counts, _stats) = output::count::objects_unthreaded(
+            &handle,
+            &mut tips.into_iter().map(Ok),
+            &counting,
+            should_interrupt,
+            output::count::objects::ObjectExpansion::AllReachable,
+            no_delta,
+        )?;
+        let reachable: HashSet<ObjectId> = counts.iter().map(|count| count.id).collect();
+        let counts: Vec<_> = counts.into_iter().filter(|count| !packs.is_kept(&count.id)).collect();
+        let num_objects = counts.len();
+
+        let mut _keep_file = None;
+        let new_index = if counts.is_empty() {
+            None
+        } else {
+            let (index_path, keep) = self.write_pack(counts, handle, &pack_dir, progress, should_interrupt, options)?;
+            _keep_file = keep.map(KeepFile);
+            let index = gix_pack::index::File::at(&index_path, self.object_hash()).map_err(|source| {
+                repack::Error::OpenPackIndex {
+                    path: index_path.clone(),
+                    source,
+                }
+            })?;
+            Some(index)
+        };
+        let obsolete: Vec<_> = std::mem::take(&mut packs.obsolete)
+            .into_iter()
+            .filter(|(index_path, _)| new_index.as_ref().is_none_or(|new| new.path() != index_path))
+            .collect();
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(repack::Error::Interrupted);
+        }
+
+        let loose = self.loose_objects();
+        let mut unpacked_objects = 0;
+        if let Some(since) = options.unpack_unreachable_since {
+            for (index_path, _mtime) in obsolete.iter().filter(|(_, mtime)| *mtime > since) {
+                let index = gix_pack::index::File::at(index_path, self.object_hash()).map_err(|source| {
+                    repack::Error::OpenPackIndex {
+                        path: index_path.clone(),
+                        source,
+                    }
+                })?;
+                for entry in index.iter() {
+                    if reachable.contains(&entry.oid) || packs.is_kept(&entry.oid) || loose.contains(&entry.oid) {
+                        continue;
+                    }
+                    if should_interrupt.load(Ordering::Relaxed) {
+                        return Err(repack::Error::Interrupted);
+                    }
+                    let object = self.find_object(entry.oid)?;
+                    loose
+                        .write_buf(object.kind, &object.data)
+                        .map_err(repack::Error::WriteLooseObject)?;
+                    unpacked_objects += 1;
+                }
+            }
+        }
+
+        let mut removed_packs = Vec::new();
+        if !obsolete.is_empty() {
+            // The multi-pack index refers to the packs that are about to be removed, and it's no use without them.
+            for entry in std::fs::read_dir(&pack_dir)? {
+                let path = entry?.path();
+                if path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("multi-pack-index"))
+                {
+                    if path.is_dir() {
+                        // The layers of an incremental multi-pack index and their chain.
+                        std::fs::remove_dir_all(path)?;
+                    } else {
+                        std::fs::remove_file(path)?;
+                    }
+                }
+            }
+        }
+        for (index_path, _mtime) in obsolete {
+            // Without its index, the pack is invisible to the object database, so its data can't be accessed anymore.
+            if std::fs::remove_file(&index_path).is_err() {
+                continue;
+            }
+            for extension in ["bitmap", "rev", "pack"] {
+                std::fs::remove_file(index_path.with_extension(extension)).ok();
+            }
+            removed_packs.push(index_path);
+        }
+
+        let mut removed_loose_objects = 0;
+        if new_index.is_some() || !packs.kept.is_empty() {
+            let mut directories = BTreeSet::new();
+            for id in loose.iter() {
+                let id = id?;
+                let is_packed =
+                    new_index.as_ref().is_some_and(|index| index.lookup(id).is_some()) || packs.is_kept(&id);
+                if !is_packed {
+                    continue;
+                }
+                let path = loose.object_path(&id);
+                std::fs::remove_file(&path)?;
+                removed_loose_objects += 1;
+                directories.extend(path.parent().map(ToOwned::to_owned));
+            }
+            remove_empty_directories(directories);
+        }
+
+        Ok((
+            repack::Outcome {
+                index_path: new_index.map(|index| index.path().to_owned()),
+                num_objects,
+                removed_packs,
+                removed_loose_objects,
+                unpacked_objects,
+            },
+            reachable,
+        ))
+    }
+
+    /// Write a pack with all objects in `counts` into `pack_dir` and return the path to its index,
+    /// along with the path to the `.keep` file that protects it until the caller is done.
+    fn write_pack(
+        &self,
+        counts: Vec<output::Count>,
+        handle: gix_odb::HandleArc,
+        pack_dir: &Path,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: repack::Options,
+    ) -> Result<(PathBuf, Option<PathBuf>), repack::Error> {
+        let config = &self.config.resolved;
+        let lenient = self.config.lenient_config;
+        let thread_limit = Pack::THREADS
+            .try_into_usize(config.integer_filter(Pack::THREADS, &mut self.filter_config_section()))
+            .with_leniency(lenient)?;
+        let (window, depth) = self.pack_window_and_depth()?;
+        let num_objects = counts.len();
+        let entries = InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            handle,
+            Box::new(progress.add_child_with_id("creating entries".into(), *b"RPCE")),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: if options.delta_compression {
+                    output::entry::iter_from_counts::Mode::DeltaCompression
+                } else {
+                    output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects
+                },
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+                compression: self.pack_compression()?,
+                window,
+                depth,
+            },
+        ));
+
+        let mut data = gix_tempfile::new(
+            pack_dir,
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        {
+            let mut writing = progress.add_child_with_id("writing".into(), *b"RPWR");
+            writing.init(None, gix_features::progress::bytes());
+            let mut pack = output::bytes::FromEntriesIter::new(
+                entries,
+                std::io::BufWriter::new(&mut data),
+                num_objects as u32,
+                gix_pack::data::Version::default(),
+                self.object_hash(),
+            );
+            for written in pack.by_ref() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(repack::Error::Interrupted);
+                }
+                writing.inc_by(written? as usize);
+            }
+            pack.into_write().flush()?;
+        }
+        data.rewind()?;
+
+        let outcome = gix_pack::Bundle::write_to_directory(
+            &mut BufReader::new(&mut data),
+            Some(pack_dir),
+            progress,
+            should_interrupt,
+            None::<gix_object::find::Never>,
+            gix_pack::bundle::write::Options {
+                thread_limit,
+                index_version: Pack::INDEX_VERSION
+                    .try_into_index_version(config.integer(Pack::INDEX_VERSION))
+                    .with_leniency(lenient)?
+                    .unwrap_or(gix_pack::index::Version::V2),
+                iteration_mode: gix_pack::data::input::Mode::Verify,
+                object_hash: self.object_hash(),
+                alloc_limit_bytes: self.config.alloc_limit_bytes,
+                compression: self.config.loose_compression,
+                bitmap: options.write_bitmap.then(|| gix_pack::bitmap::write::Options {
+                    fsync: self.config.fsync(gix_fs::fsync::Components::PACK_METADATA),
+                    ..Default::default()
+                }),
+                fsync: self.config.fsync(gix_fs::fsync::Components::PACK),
+                fsync_metadata: self.config.fsync(gix_fs::fsync::Components::PACK_METADATA),
+            },
+        )?;
+        Ok((
+            outcome.index_path.expect("the pack is written to a directory"),
+            outcome.keep_path,
+        ))
+    }
+
+    /// Return the objects from which all objects that must be kept are reachable, which are the targets of references and
+    /// their reflogs, along with the `HEAD`, its reflog and the index of each worktree.
+    fn reachability_tips(&self) -> Result<Vec<ObjectId>, repack::Error> {
+        let mut tips = Vec::new();
+        for reference in self.references()?.all()? {
+            let reference = reference?;
+            tips.extend(reference.target().try_id().map(ToOwned::to_owned));
+            extend_with_reflog(&mut tips, reference.name().as_bstr(), reference.log_iter())?;
+        }
+
+        let mut worktree_repos = Vec::new();
+        if self.git_dir() != self.common_dir() {
+            worktree_repos.push(
+                self.main_repo()
+                    .map_err(|err| repack::Error::OpenWorktree(Box::new(err)))?,
+            );
+        }
+        for proxy in self.worktrees().map_err(repack::Error::ListWorktrees)? {
+            if proxy.git_dir() == self.git_dir() {
+                continue;
+            }
+            worktree_repos.push(
+                proxy
+                    .into_repo_with_possibly_inaccessible_worktree()
+                    .map_err(|err| repack::Error::OpenWorktree(Box::new(err)))?,
+            );
+        }
+        for repo in std::iter::once(self).chain(worktree_repos.iter()) {
+            let head = repo.head()?;
+            tips.extend(head.id().map(crate::Id::detach));
+            extend_with_reflog(&mut tips, "HEAD".into(), head.log_iter())?;
+            if let Some(index) = repo.try_index()? {
+                tips.extend(
+                    index
+                        .entries()
+                        .iter()
+                        .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                        .map(|entry| entry.id),
+                );
+                let mut trees: Vec<_> = index.tree().into_iter().collect();
+                while let Some(tree) = trees.pop() {
+                    // Trees without amount of entries were invalidated and may not exist.
+                    if tree.num_entries.is_some() {
+                        tips.push(tree.id);
+                    }
+                    trees.extend(&tree.children);
+                }
+            }
+        }
+
+        tips.sort();
+        tips.dedup();
+        tips.retain(|id| !id.is_null() && self.has_object(id));
+        Ok(tips)
+    }
+
+    fn loose_objects(&self) -> gix_odb::loose::Store {
+        gix_odb::loose::Store::at(
+            self.objects.store_ref().path(),
+            gix_odb::loose::Options {
+                object_hash: self.object_hash(),
+                alloc_limit_bytes: self.config.alloc_limit_bytes,
+                compression: self.loose_compression(),
+                fsync: self.config.fsync(gix_fs::fsync::Components::LOOSE_OBJECT),
+            },
+        )
+    }
+}
+
+/// The `.keep` file of the new pack, which protects it from concurrent repacks until we are done, even if we fail.
+struct KeepFile(PathBuf);
+
+impl Drop for KeepFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
+
+/// The packs in a pack directory.
+struct Packs {
+    /// The paths to the indices of packs that may be removed, along with the time their data was last modified.
+    obsolete: Vec<(PathBuf, gix_date::SecondsSinceUnixEpoch)>,
+    /// The indices of packs with a `.keep` file.
+    kept: Vec<gix_pack::index::File>,
+}
+
+impl Packs {
+    fn at(pack_dir: &Path, object_hash: gix_hash::Kind) -> Result<Self, repack::Error> {
+        let mut packs = Packs {
+            obsolete: Vec::new(),
+            kept: Vec::new(),
+        };
+        let entries = match std::fs::read_dir(pack_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(packs),
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let index_path = entry?.path();
+            if index_path.extension().is_none_or(|ext| ext != "idx") {
+                continue;
+            }
+            let Ok(mtime) = std::fs::metadata(index_path.with_extension("pack")).and_then(|md| md.modified()) else {
+                continue;
+            };
+            if index_path.with_extension("keep").is_file() {
+                packs
+                    .kept
+                    .push(gix_pack::index::File::at(&index_path, object_hash).map_err(|source| {
+                        repack::Error::OpenPackIndex {
+                            path: index_path,
+                            source,
+                        }
+                    })?);
+            } else {
+                packs.obsolete.push((index_path, seconds_since_unix_epoch(mtime)));
+            }
+        }
+        packs.obsolete.sort();
+        Ok(packs)
+    }
+
+    fn is_kept(&self, id: &gix_hash::oid) -> bool {
+        self.kept.iter().any(|index| index.lookup(id).is_some())
+    }
+}
+
+fn extend_with_reflog(
+    tips: &mut Vec<ObjectId>,
+    name: &BStr,
+    mut log: gix_ref::file::log::iter::Platform<'_, '_>,
+) -> Result<(), repack::Error> {
+    let Some(lines) = log.all().map_err(|source| repack::Error::ReadReflog {
+        name: name.to_owned(),
+        source,
+    })?
+    else {
+        return Ok(());
+    };
+    for line in lines {
+        let line = line.map_err(|source| repack::Error::DecodeReflog {
+            name: name.to_owned(),
+            source,
+        })?;
+        tips.extend([line.previous_oid(), line.new_oid()]);
+    }
+    Ok(())
+}
+
+fn remove_empty_directories(directories: BTreeSet<PathBuf>) {
+    for directory in directories {
+        // Fails if the directory isn't empty, which is expected.
+        std::fs::remove_dir(directory).ok();
+    }
+}
+
+fn seconds_since_unix_epoch(time: SystemTime) -> gix_date::SecondsSinceUnixEpoch {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as gix_date::SecondsSinceUnixEpoch)
+}
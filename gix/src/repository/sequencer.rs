@@ -0,0 +1,407 @@
+use gix_error::Exn;
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_ref::{
+    Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+use crate::{
+    Repository,
+    bstr::{BString, ByteVec},
+    prelude::ObjectIdExt,
+    sequencer,
+    sequencer::{Action, plumbing::state::DIR_NAME},
+};
+
+impl Repository {
+    /// Apply the changes of each of `commits` in order on top of `HEAD` and commit them, moving `HEAD` along,
+    /// similar to `git cherry-pick <commits>…`, but without touching the index or worktree.
+    ///
+    /// If a commit doesn't apply cleanly or would be empty, the sequence stops and the returned
+    /// [outcome](sequencer::Outcome::stopped) provides the conflicting merge result along with the commits that remain.
+    /// Then the state of the sequence is written into `.git/sequencer`, along with `CHERRY_PICK_HEAD` and `MERGE_MSG`,
+    /// just like Git does, so that it can be resumed with [`sequencer_continue()`](Self::sequencer_continue())
+    /// or `git cherry-pick --continue`.
+    /// Note that the index and worktree remain untouched, so they have to be updated by the caller
+    /// to resolve the conflicts, for instance based on the [merge result](sequencer::Stop::tree_merge).
+    ///
+    /// `ORIG_HEAD` is set to the commit `HEAD` pointed to before the sequence started.
+    /// `options` should be initialized with [`Repository::tree_merge_options().into()`](Self::tree_merge_options()).
+    ///
+    /// ### Performance
+    ///
+    /// It's highly recommended to [set an object cache](Repository::compute_object_cache_size_for_tree_diffs)
+    /// to avoid extracting the same object multiple times.
+    pub fn cherry_pick(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: sequencer::Options,
+    ) -> Result<sequencer::Outcome<'_>, sequencer::Error> {
+        self.start_sequence(Action::Pick, commits, options)
+    }
+
+    /// Undo the changes of each of `commits` in order on top of `HEAD` and commit them, moving `HEAD` along,
+    /// similar to `git revert --no-edit <commits>…`, but without touching the index or worktree.
+    ///
+    /// It works just like [`cherry_pick()`](Self::cherry_pick()), but writes `REVERT_HEAD` when stopping.
+    pub fn revert(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: sequencer::Options,
+    ) -> Result<sequencer::Outcome<'_>, sequencer::Error> {
+        self.start_sequence(Action::Revert, commits, options)
+    }
+
+    /// Return the state of the cherry-pick or revert sequence in progress as stored in `.git/sequencer`,
+    /// or `None` if there is none.
+    pub fn sequencer_state(&self) -> Result<Option<sequencer::State>, sequencer::state::Error> {
+        sequencer::State::from_dir(&self.git_dir().join(DIR_NAME), self.object_hash(), &mut |prefix| {
+            self.objects
+                .lookup_prefix(prefix, None)
+                .ok()
+                .flatten()
+                .and_then(Result::ok)
+        })
+        .map_err(Exn::into_error)
+    }
+
+    /// Continue the sequence in progress, similar to `git cherry-pick --continue`.
+    ///
+    /// If it stopped at a commit that couldn't be applied, `resolved_tree` is the tree with all conflicts resolved,
+    /// which is committed on top of `HEAD` with the message and author the commit would have had.
+    /// Otherwise, `resolved_tree` must be `None` as the caller is expected to have committed the changes.
+    /// Then the remaining commits are applied like [`cherry_pick()`](Self::cherry_pick()) does, using the options
+    /// stored along with the sequence, `tree_merge` to merge trees and `treat_as_unresolved` to decide which conflicts
+    /// stop the sequence, as these aren't stored.
+    pub fn sequencer_continue(
+        &self,
+        resolved_tree: Option<ObjectId>,
+        tree_merge: crate::merge::tree::Options,
+        treat_as_unresolved: crate::merge::tree::TreatAsUnresolved,
+    ) -> Result<sequencer::Outcome<'_>, sequencer::Error> {
+        let mut state = self.sequencer_state()?.ok_or(sequencer::Error::NotInProgress)?;
+        match (self.sequencer_stopped_at()?, resolved_tree) {
+            (Some((action, commit)), Some(tree)) => {
+                self.commit_resolution(action, commit, tree, &state.options)?;
+                self.remove_sequencer_files(&[action.head_name(), "MERGE_MSG"])?;
+            }
+            (Some((_, commit)), None) => return Err(sequencer::Error::ResolutionMissing { commit }),
+            (None, Some(_)) => return Err(sequencer::Error::ResolutionUnexpected),
+            (None, None) => {}
+        }
+        if !state.todo.is_empty() {
+            state.todo.remove(0);
+        }
+        self.run_sequence(state, tree_merge, treat_as_unresolved)
+    }
+
+    /// Skip the commit the sequence in progress stopped at and continue with the remaining ones,
+    /// similar to `git cherry-pick --skip`.
+    ///
+    /// `tree_merge` and `treat_as_unresolved` are used like in [`sequencer_continue()`](Self::sequencer_continue()).
+    pub fn sequencer_skip(
+        &self,
+        tree_merge: crate::merge::tree::Options,
+        treat_as_unresolved: crate::merge::tree::TreatAsUnresolved,
+    ) -> Result<sequencer::Outcome<'_>, sequencer::Error> {
+        let mut state = self.sequencer_state()?.ok_or(sequencer::Error::NotInProgress)?;
+        self.remove_sequencer_files(&[Action::Pick.head_name(), Action::Revert.head_name(), "MERGE_MSG"])?;
+        if !state.todo.is_empty() {
+            state.todo.remove(0);
+        }
+        self.run_sequence(state, tree_merge, treat_as_unresolved)
+    }
+
+    /// Stop the sequence in progress and point `HEAD` back to the commit it pointed to before the sequence started,
+    /// similar to `git cherry-pick --abort`, but without touching the index or worktree.
+    ///
+    /// Fail if `HEAD` was moved since the sequence stopped, as commits made in the meantime would be lost.
+    pub fn sequencer_abort(&self) -> Result<(), sequencer::abort::Error> {
+        let state = self.sequencer_state()?.ok_or(sequencer::abort::Error::NotInProgress)?;
+        let head = self.head_id()?.detach();
+        match state.abort_safety {
+            Some(expected) if expected != head => {
+                return Err(sequencer::abort::Error::HeadMoved { expected, actual: head });
+            }
+            _ => {}
+        }
+        if head != state.head {
+            let action = state.action().unwrap_or(Action::Pick);
+            self.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("{}: abort", action.command_name()).into(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Object(head)),
+                    new: Target::Object(state.head),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: true,
+            })?;
+        }
+        self.sequencer_quit()?;
+        Ok(())
+    }
+
+    /// Forget about the sequence in progress without changing `HEAD`, similar to `git cherry-pick --quit`.
+    ///
+    /// This removes `.git/sequencer` along with `CHERRY_PICK_HEAD` and `REVERT_HEAD`, and does nothing if they don't exist.
+    pub fn sequencer_quit(&self) -> Result<(), sequencer::quit::Error> {
+        let path = self.git_dir().join(DIR_NAME);
+        match std::fs::remove_dir_all(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(source) => return Err(sequencer::quit::Error { path, source }),
+        }
+        self.remove_sequencer_files(&[Action::Pick.head_name(), Action::Revert.head_name()])
+    }
+
+    fn start_sequence(
+        &self,
+        action: Action,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: sequencer::Options,
+    ) -> Result<sequencer::Outcome<'_>, sequencer::Error> {
+        let sequencer::Options {
+            tree_merge,
+            treat_as_unresolved,
+            sequence,
+        } = options;
+        if self.git_dir().join(DIR_NAME).exists() || self.sequencer_stopped_at()?.is_some() {
+            return Err(sequencer::Error::InProgress);
+        }
+        let head = self.head_id()?.detach();
+        let mut buf = Vec::new();
+        let todo = commits
+            .into_iter()
+            .map(|id| {
+                let id = id.into();
+                Ok(sequencer::plumbing::todo::Line {
+                    action,
+                    id,
+                    subject: self.objects.find_commit(&id, &mut buf)?.message_summary().into_owned(),
+                })
+            })
+            .collect::<Result<_, sequencer::Error>>()?;
+        self.write_sequencer_file("ORIG_HEAD", format!("{head}\n").as_bytes())?;
+
+        let state = sequencer::State {
+            head,
+            abort_safety: None,
+            todo,
+            options: sequence,
+        };
+        self.run_sequence(state, tree_merge, treat_as_unresolved)
+    }
+
+    /// Apply all commits in the todo-list of `state`, and write it to disk if one of them can't be applied.
+    fn run_sequence(
+        &self,
+        mut state: sequencer::State,
+        tree_merge: crate::merge::tree::Options,
+        treat_as_unresolved: crate::merge::tree::TreatAsUnresolved,
+    ) -> Result<sequencer::Outcome<'_>, sequencer::Error> {
+        let committer = self.committer().ok_or(sequencer::Error::CommitterMissing)??;
+        let author = self.author().ok_or(sequencer::Error::AuthorMissing)??;
+        let mut head = self.head_id()?.detach();
+        let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
+        let mut blob_merge = self.merge_resource_cache(Default::default())?;
+
+        let mut steps = Vec::new();
+        while let Some(line) = state.todo.first() {
+            let (action, commit) = (line.action, line.id);
+            let outcome = gix_sequencer::apply(
+                action,
+                commit,
+                head,
+                committer,
+                author,
+                self,
+                &mut diff_cache,
+                &mut blob_merge,
+                &mut |id| id.to_owned().attach(self).shorten_or_id().to_string(),
+                gix_sequencer::apply::Options {
+                    tree_merge: tree_merge.clone().into(),
+                    treat_as_unresolved,
+                    sequence: state.options.clone(),
+                },
+            )
+            .map_err(Exn::into_error)?;
+            let stop = match outcome {
+                gix_sequencer::apply::Outcome::Commit(new) => {
+                    self.move_head(action, head, new, committer)?;
+                    steps.push(sequencer::Step { commit, new: Some(new) });
+                    head = new;
+                    state.todo.remove(0);
+                    continue;
+                }
+                gix_sequencer::apply::Outcome::Dropped => {
+                    steps.push(sequencer::Step { commit, new: None });
+                    state.todo.remove(0);
+                    continue;
+                }
+                gix_sequencer::apply::Outcome::Stop(stop) => stop,
+            };
+
+            state.abort_safety = Some(head);
+            state
+                .write_to(&self.git_dir().join(DIR_NAME))
+                .map_err(Exn::into_error)?;
+            self.write_sequencer_file(action.head_name(), format!("{commit}\n").as_bytes())?;
+            let mut message = {
+                let mut buf = Vec::new();
+                let decoded = self.objects.find_commit(&commit, &mut buf)?;
+                gix_sequencer::apply::message(
+                    action,
+                    &commit,
+                    &decoded,
+                    state.options.record_origin,
+                    state.options.mainline,
+                )
+            };
+            let tree_merge = match stop {
+                gix_sequencer::apply::Stop::Conflict(outcome) => {
+                    let mut paths: Vec<_> = outcome
+                        .conflicts
+                        .iter()
+                        .filter(|c| c.is_unresolved(treat_as_unresolved))
+                        .map(|c| c.ours.location())
+                        .collect();
+                    paths.sort();
+                    paths.dedup();
+                    message.push_str("\n# Conflicts:\n");
+                    for path in paths {
+                        message.push_str("#\t");
+                        message.push_str(path);
+                        message.push_byte(b'\n');
+                    }
+                    Some(crate::merge::tree::Outcome {
+                        tree: crate::object::tree::Editor {
+                            inner: outcome.tree,
+                            validate: self.config.protect_options()?,
+                            repo: self,
+                        },
+                        conflicts: outcome.conflicts,
+                        failed_on_first_unresolved_conflict: outcome.failed_on_first_unresolved_conflict,
+                    })
+                }
+                gix_sequencer::apply::Stop::Empty => None,
+            };
+            self.write_sequencer_file("MERGE_MSG", &message)?;
+
+            return Ok(sequencer::Outcome {
+                head: head.attach(self),
+                steps,
+                stopped: Some(sequencer::Stop {
+                    commit: commit.attach(self),
+                    action,
+                    tree_merge,
+                    remaining: state.todo[1..].iter().map(|line| line.id).collect(),
+                }),
+            });
+        }
+
+        self.sequencer_quit()?;
+        Ok(sequencer::Outcome {
+            head: head.attach(self),
+            steps,
+            stopped: None,
+        })
+    }
+
+    /// Commit `tree` on top of `HEAD` as result of applying `commit` with `action`.
+    fn commit_resolution(
+        &self,
+        action: Action,
+        commit: ObjectId,
+        tree: ObjectId,
+        options: &sequencer::Sequence,
+    ) -> Result<(), sequencer::Error> {
+        let committer = self.committer().ok_or(sequencer::Error::CommitterMissing)??;
+        let head = self.head_id()?.detach();
+        let mut buf = Vec::new();
+        let decoded = self.objects.find_commit(&commit, &mut buf)?;
+        let message = gix_sequencer::apply::message(action, &commit, &decoded, options.record_origin, options.mainline);
+        let (author, encoding) = match action {
+            Action::Pick => (decoded.author()?.into(), decoded.encoding.map(BString::from)),
+            Action::Revert => (self.author().ok_or(sequencer::Error::AuthorMissing)??.into(), None),
+        };
+        let new = self
+            .write_object(&gix_object::Commit {
+                tree,
+                parents: [head].into(),
+                author,
+                committer: committer.into(),
+                encoding,
+                message,
+                extra_headers: Vec::new(),
+            })?
+            .detach();
+        self.move_head(action, head, new, committer)
+    }
+
+    /// Point `HEAD`, or the branch it points to, from `previous` to the `new` commit that was created by `action`.
+    fn move_head(
+        &self,
+        action: Action,
+        previous: ObjectId,
+        new: ObjectId,
+        committer: gix_actor::SignatureRef<'_>,
+    ) -> Result<(), sequencer::Error> {
+        let message = crate::reference::log::message(
+            action.command_name(),
+            self.objects.find_commit(&new, &mut Vec::new())?.message,
+            1,
+        );
+        self.edit_references_as(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message,
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Object(previous)),
+                    new: Target::Object(new),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: true,
+            }),
+            Some(committer),
+        )?;
+        Ok(())
+    }
+
+    /// Return the action and the commit the sequence stopped at, as indicated by `CHERRY_PICK_HEAD` or `REVERT_HEAD`.
+    fn sequencer_stopped_at(&self) -> Result<Option<(Action, ObjectId)>, sequencer::Error> {
+        for action in [Action::Pick, Action::Revert] {
+            if let Some(id) = self
+                .try_find_reference(action.head_name())?
+                .and_then(|r| r.target().try_id().map(ToOwned::to_owned))
+            {
+                return Ok(Some((action, id)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn write_sequencer_file(&self, name: &str, content: &[u8]) -> Result<(), sequencer::Error> {
+        let path = self.git_dir().join(name);
+        std::fs::write(&path, content).map_err(|source| sequencer::Error::WriteFile { path, source })
+    }
+
+    fn remove_sequencer_files(&self, names: &[&str]) -> Result<(), sequencer::quit::Error> {
+        for name in names {
+            let path = self.git_dir().join(name);
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(sequencer::quit::Error { path, source }),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,438 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::ObjectId;
+use gix_ref::{
+    Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+use crate::{
+    Id, Repository,
+    bstr::{BString, ByteSlice},
+    checkout_tree,
+    checkout_tree::{Conflict, ConflictKind},
+    prelude::ObjectIdExt,
+    stash,
+};
+
+impl Repository {
+    /// Record the changes of the index and the worktree relative to `HEAD` in a new stash entry and make the index and worktree
+    /// match `HEAD` again, like `git stash push` would. Return the id of the new stash commit, or `None` if there was nothing
+    /// to stash.
+    ///
+    /// The stash commit has the worktree as its tree, and `HEAD` along with a commit of the tree of the index as its parents.
+    /// If [configured](stash::save::Options::with_include_untracked()), a third parent holds the untracked files which are
+    /// then removed from the worktree.
+    /// [`stash::REF_NAME`] is pointed to the new stash commit, with its reflog holding all previous stash entries.
+    ///
+    /// Note that submodules aren't stashed, and that the index can't have unresolved conflicts.
+    pub fn stash_save(
+        &self,
+        should_interrupt: &AtomicBool,
+        options: stash::save::Options,
+    ) -> Result<Option<Id<'_>>, stash::save::Error> {
+        use stash::save::Error;
+
+        let workdir = self.workdir().ok_or(Error::MissingWorkDir)?;
+        let head = self.head()?;
+        if head.is_unborn() {
+            return Err(Error::UnbornHead);
+        }
+        let branch: BString = head
+            .referent_name()
+            .map_or_else(|| "(no branch)".into(), |name| name.shorten().to_owned());
+        let head_commit = self.head_commit()?;
+        let head_id = head_commit.id;
+        let head_tree = head_commit.tree_id()?.detach();
+        let summary = format!(
+            "{branch}: {short_id} {subject}",
+            short_id = head_commit.id().shorten_or_id(),
+            subject = head_commit.message()?.summary()
+        );
+
+        let index = self.index_or_empty()?;
+        let index_tree = self.tree_from_index(&index)?;
+
+        let (mut pipeline, pipeline_index) = self.filter_pipeline(None)?;
+        let mut worktree_tree = self.edit_tree(index_tree)?;
+        let iter = self
+            .status(gix_features::progress::Discard)?
+            .index_worktree_rewrites(None)
+            .index_worktree_submodules(crate::status::Submodule::Given {
+                ignore: crate::submodule::config::Ignore::All,
+                check_dirty: false,
+            })
+            .index_worktree_options_mut(|opts| {
+                opts.dirwalk_options = None;
+            })
+            .into_index_worktree_iter(Vec::new())?;
+        for item in iter {
+            let item = item?;
+            if item.summary().is_none() {
+                continue;
+            }
+            let rela_path = item.rela_path();
+            match pipeline.worktree_file_to_object(rela_path, &pipeline_index)? {
+                Some((id, kind, _)) => worktree_tree.upsert(rela_path, kind, id)?,
+                None => worktree_tree.remove(rela_path)?,
+            };
+        }
+        let worktree_tree = worktree_tree.write()?.detach();
+
+        let mut untracked = Vec::new();
+        let mut untracked_tree = self.edit_tree(ObjectId::empty_tree(self.object_hash()))?;
+        if options.include_untracked {
+            let dirwalk_options = self
+                .dirwalk_options()?
+                .emit_untracked(gix_dir::walk::EmissionMode::Matching);
+            for item in self.dirwalk_iter(index.clone(), None::<BString>, Default::default(), dirwalk_options)? {
+                let entry = item?.entry;
+                if entry.status != gix_dir::entry::Status::Untracked
+                    || !matches!(
+                        entry.disk_kind,
+                        Some(gix_dir::entry::Kind::File | gix_dir::entry::Kind::Symlink)
+                    )
+                {
+                    continue;
+                }
+                if let Some((id, kind, _)) =
+                    pipeline.worktree_file_to_object(entry.rela_path.as_ref(), &pipeline_index)?
+                {
+                    untracked_tree.upsert(entry.rela_path.as_bstr(), kind, id)?;
+                    untracked.push(entry.rela_path);
+                }
+            }
+        }
+
+        if index_tree == head_tree && worktree_tree == index_tree && untracked.is_empty() {
+            return Ok(None);
+        }
+
+        let index_commit = self.new_commit(format!("index on {summary}\n"), index_tree, Some(head_id))?;
+        let mut parents = vec![head_id, index_commit.id];
+        if !untracked.is_empty() {
+            let untracked_tree = untracked_tree.write()?.detach();
+            let untracked_commit = self.new_commit(
+                format!("untracked files on {summary}\n"),
+                untracked_tree,
+                None::<ObjectId>,
+            )?;
+            parents.push(untracked_commit.id);
+        }
+        let message = match &options.message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {summary}"),
+        };
+        let stash_id = self.new_commit(&message, worktree_tree, parents)?.id;
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: true,
+                    message: message.lines().next().unwrap_or_default().into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(stash_id),
+            },
+            name: stash::REF_NAME.try_into().expect("valid"),
+            deref: false,
+        })?;
+
+        self.checkout_tree(
+            head_tree,
+            should_interrupt,
+            checkout_tree::Options::default().with_force(true),
+        )?;
+        for rela_path in untracked {
+            let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+            std::fs::remove_file(&path).map_err(|source| Error::RemoveUntracked {
+                path: path.clone(),
+                source,
+            })?;
+            super::checkout_tree::remove_empty_parent_directories(&path, workdir);
+        }
+        Ok(Some(stash_id.attach(self)))
+    }
+
+    /// Return all stash entries, most recent first, so that the entry at index `n` is the one known to git as `stash@{n}`.
+    pub fn stash_list(&self) -> Result<Vec<stash::Entry>, stash::list::Error> {
+        let Some(stash_ref) = self.try_find_reference(stash::REF_NAME)? else {
+            return Ok(Vec::new());
+        };
+        let mut log = stash_ref.log_iter();
+        let Some(lines) = log.all().map_err(stash::list::Error::ReadLog)? else {
+            return Ok(Vec::new());
+        };
+        let mut entries = lines
+            .map(|line| {
+                line.map(|line| stash::Entry {
+                    id: line.new_oid(),
+                    message: line.message.to_owned(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// Apply the changes of the stash entry at `index` to the index and the worktree, like `git stash apply stash@{<index>}` would.
+    ///
+    /// The changes are merged into the tree of the current index, and the result is checked out while keeping local changes
+    /// of all files that aren't affected by the stash entry.
+    /// Files that were added by the stash entry are added to the index, but other changes remain unstaged.
+    /// Untracked files stored with the stash entry are restored as well.
+    ///
+    /// If the merge has unresolved conflicts, nothing is changed and the merge result is returned in
+    /// [`conflicts`](stash::apply::Outcome::conflicts) to allow the caller to resolve them.
+    /// If the stash entry would overwrite local changes or untracked files, a [conflict error](checkout_tree::Error::Conflicts)
+    /// is returned instead.
+    ///
+    /// `options` should be initialized with [`Repository::tree_merge_options().into()`](Self::tree_merge_options()).
+    pub fn stash_apply(
+        &self,
+        index: usize,
+        should_interrupt: &AtomicBool,
+        options: stash::apply::Options,
+    ) -> Result<stash::apply::Outcome<'_>, stash::apply::Error> {
+        use stash::apply::{Error, Outcome};
+
+        let id = self
+            .stash_list()?
+            .into_iter()
+            .nth(index)
+            .ok_or(Error::NotFound { index })?
+            .id;
+        let stash_commit = self.find_commit(id)?;
+        let parents: Vec<_> = stash_commit.parent_ids().map(Id::detach).collect();
+        if parents.len() < 2 {
+            return Err(Error::NotAStash { id });
+        }
+        let base_tree = self.find_commit(parents[0])?.tree_id()?.detach();
+        let stash_tree = stash_commit.tree_id()?.detach();
+
+        let previous_index = self.index_or_empty()?;
+        let ours = self.tree_from_index(&previous_index)?;
+        let mut tree_merge = self.merge_trees(
+            base_tree,
+            ours,
+            stash_tree,
+            gix_merge::blob::builtin_driver::text::Labels {
+                ancestor: Some("Stash base".into()),
+                current: Some("Updated upstream".into()),
+                other: Some("Stashed changes".into()),
+            },
+            options.tree_merge,
+        )?;
+        if tree_merge.has_unresolved_conflicts(options.treat_as_unresolved) {
+            return Ok(Outcome {
+                id,
+                conflicts: Some(tree_merge),
+                checkout: None,
+                untracked: Vec::new(),
+            });
+        }
+        let merged_tree = tree_merge.tree.write()?.detach();
+
+        let workdir = self.workdir().ok_or(checkout_tree::Error::MissingWorkDir)?;
+        let mut untracked_index = match parents.get(2) {
+            Some(untracked) => Some(self.index_from_tree(&self.find_commit(*untracked)?.tree_id()?)?),
+            None => None,
+        };
+        if let Some(untracked_index) = &untracked_index {
+            let conflicts: Vec<_> = untracked_index
+                .entries()
+                .iter()
+                .map(|entry| entry.path(untracked_index))
+                .filter(|rela_path| workdir.join(gix_path::from_bstr(*rela_path)).symlink_metadata().is_ok())
+                .map(|rela_path| Conflict {
+                    rela_path: rela_path.to_owned(),
+                    kind: ConflictKind::Untracked,
+                })
+                .collect();
+            if !conflicts.is_empty() {
+                return Err(checkout_tree::Error::Conflicts { conflicts }.into());
+            }
+        }
+
+        let checkout = self.checkout_tree_with_base(ours, merged_tree, should_interrupt, Default::default())?;
+
+        // Like git, only stage files that were added by the stash entry, and keep all other changes unstaged.
+        let merged_index = self.open_index()?;
+        let mut state = gix_index::State::new(self.object_hash());
+        for entry in previous_index.entries() {
+            state.dangerously_push_entry(
+                entry.stat,
+                entry.id,
+                entry.flags,
+                entry.mode,
+                entry.path(&previous_index),
+            );
+        }
+        for entry in merged_index.entries() {
+            let rela_path = entry.path(&merged_index);
+            if previous_index.entry_by_path(rela_path).is_none() {
+                state.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, rela_path);
+            }
+        }
+        state.sort_entries();
+        gix_index::File::from_state(state, self.index_path()).write(gix_index::write::Options {
+            fsync: self.config.fsync(gix_fs::fsync::Components::INDEX),
+            ..Default::default()
+        })?;
+
+        let mut untracked = Vec::new();
+        if let Some(untracked_index) = &mut untracked_index {
+            let mut opts = self.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
+            opts.destination_is_initially_empty = false;
+            opts.overwrite_existing = false;
+            opts.keep_going = false;
+            gix_worktree_state::checkout(
+                untracked_index,
+                workdir,
+                self.objects.clone().into_arc()?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                should_interrupt,
+                opts,
+            )?;
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            untracked = untracked_index
+                .entries()
+                .iter()
+                .map(|entry| entry.path(untracked_index).to_owned())
+                .collect();
+        }
+
+        Ok(Outcome {
+            id,
+            conflicts: None,
+            checkout: Some(checkout),
+            untracked,
+        })
+    }
+
+    /// Like [`stash_apply()`](Self::stash_apply()), but also drop the stash entry at `index` if it could be applied,
+    /// like `git stash pop stash@{<index>}` would.
+    pub fn stash_pop(
+        &self,
+        index: usize,
+        should_interrupt: &AtomicBool,
+        options: stash::apply::Options,
+    ) -> Result<stash::apply::Outcome<'_>, stash::apply::Error> {
+        if self.refs.format() == gix_ref::store::Format::Reftable {
+            return Err(stash::drop::Error::UnsupportedRefFormat.into());
+        }
+        let outcome = self.stash_apply(index, should_interrupt, options)?;
+        if outcome.conflicts.is_none() {
+            self.stash_drop(index)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Remove the stash entry at `index` and return its id, like `git stash drop stash@{<index>}` would.
+    ///
+    /// [`stash::REF_NAME`] is deleted along with its reflog once the last stash entry is dropped.
+    ///
+    /// Note that this isn't yet supported if references are stored in [reftables](gix_ref::store::Format::Reftable),
+    /// as their reflogs can't be rewritten yet.
+    pub fn stash_drop(&self, index: usize) -> Result<ObjectId, stash::drop::Error> {
+        use stash::drop::Error;
+
+        if self.refs.format() == gix_ref::store::Format::Reftable {
+            return Err(Error::UnsupportedRefFormat);
+        }
+        let path = self.stash_log_path();
+        let buf = match std::fs::read(&path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Err(Error::NotFound { index }),
+            Err(source) => return Err(Error::ReadLog { path, source }),
+        };
+        let mut lines = gix_ref::file::log::iter::forward(&buf)
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(stash::list::Error::from)?;
+        if index >= lines.len() {
+            return Err(Error::NotFound { index });
+        }
+        let pos = lines.len() - 1 - index;
+        let dropped = lines.remove(pos);
+        if let Some(next) = lines.get(pos).map(|line| line.previous_oid) {
+            let previous = pos
+                .checked_sub(1)
+                .map_or_else(|| ObjectId::null(next.kind()), |prev| lines[prev].new_oid);
+            lines[pos].previous_oid = previous;
+        }
+
+        let stash_ref = self.find_reference(stash::REF_NAME)?;
+        let Some(newest) = lines.last() else {
+            stash_ref.delete()?;
+            std::fs::remove_file(&path).ok();
+            return Ok(dropped.new_oid);
+        };
+        if stash_ref.id() != newest.new_oid {
+            self.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "stash: drop".into(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Object(stash_ref.id().detach())),
+                    new: Target::Object(newest.new_oid),
+                },
+                name: stash::REF_NAME.try_into().expect("valid"),
+                deref: false,
+            })?;
+        }
+        // Rewrite the reflog only now as updating the reference may have appended to it.
+        let write_log = || -> std::io::Result<()> {
+            let mut lock =
+                gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)
+                    .map_err(std::io::Error::other)?;
+            for line in &lines {
+                line.write_to(&mut lock)?;
+            }
+            lock.flush()?;
+            lock.commit().map_err(|err| err.error)?;
+            Ok(())
+        };
+        write_log().map_err(|source| Error::WriteLog {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(dropped.new_oid)
+    }
+
+    /// The path to the reflog of the stash, which only exists if references are stored as files.
+    fn stash_log_path(&self) -> PathBuf {
+        self.common_dir()
+            .join("logs")
+            .join(gix_path::from_bstr(stash::REF_NAME.as_bytes().as_bstr()))
+    }
+
+    /// Write the tree of `index`, skipping entries that are only intended to be added.
+    fn tree_from_index(&self, index: &gix_index::State) -> Result<ObjectId, stash::index_tree::Error> {
+        let mut editor = self.edit_tree(ObjectId::empty_tree(self.object_hash()))?;
+        for entry in index.entries() {
+            let rela_path = entry.path(index);
+            if entry.stage() != gix_index::entry::Stage::Unconflicted {
+                return Err(stash::index_tree::Error::Unmerged {
+                    rela_path: rela_path.to_owned(),
+                });
+            }
+            if entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD) {
+                continue;
+            }
+            if let Some(mode) = entry.mode.to_tree_entry_mode() {
+                editor.upsert(rela_path, mode.kind(), entry.id)?;
+            }
+        }
+        Ok(editor.write()?.detach())
+    }
+}
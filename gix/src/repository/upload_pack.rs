@@ -0,0 +1,36 @@
+use std::{
+    io::{Read, Write},
+    sync::atomic::AtomicBool,
+};
+
+use crate::{Repository, upload_pack};
+
+impl Repository {
+    /// Serve the `upload-pack` service to a client which sends its requests through `read` and receives our responses
+    /// through `write`, allowing it to list our references and fetch objects.
+    ///
+    /// Only protocol V2 is supported, and `read` must not contain the initial request that is sent to a `git` daemon.
+    /// `should_interrupt` aborts sending a pack if set.
+    ///
+    /// ### Limitations
+    ///
+    /// * `deepen-relative` and `tree:<depth>` filters with a depth larger than `0` aren't supported.
+    /// * packs never contain deltas, other than the ones copied from existing packs.
+    pub fn upload_pack(
+        &self,
+        read: impl Read,
+        write: impl Write,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), upload_pack::Error> {
+        gix_protocol::upload_pack(
+            read,
+            write,
+            &mut upload_pack::Delegate { repo: self },
+            should_interrupt,
+            gix_protocol::upload_pack::Options {
+                user_agent: self.config.user_agent_tuple(),
+                trace_packetlines: self.config.trace_packet(),
+            },
+        )
+    }
+}
@@ -1,4 +1,6 @@
-use crate::bstr::BStr;
+#[cfg(all(feature = "worktree-mutation", feature = "revision"))]
+use crate::bstr::ByteSlice;
+use crate::bstr::{BStr, BString};
 use crate::{Worktree, worktree};
 #[cfg(feature = "worktree-archive")]
 use gix_error::ResultExt;
@@ -39,6 +41,345 @@ impl crate::Repository {
         )
     }
 
+    /// Create a new linked worktree at `path` and check out `commitish` into it, like `git worktree add <path> [<commitish>]` would,
+    /// and return the repository of the new worktree.
+    ///
+    /// Its administrative files are placed into `.git/worktrees/<id>`, where `<id>` is derived from the last component of `path`.
+    /// If `commitish` names a local branch, it's checked out unless [detaching](worktree::add::Options::with_detach()) is requested,
+    /// otherwise `HEAD` is detached at the commit it resolves to.
+    /// Without `commitish`, a branch named after the last component of `path` is checked out, and created at `HEAD` if needed.
+    ///
+    /// If `should_interrupt` is set during the checkout, the partially created worktree is removed again.
+    #[cfg(all(feature = "worktree-mutation", feature = "revision"))]
+    pub fn worktree_add(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        commitish: Option<&BStr>,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+        options: worktree::add::Options,
+    ) -> Result<crate::Repository, worktree::add::Error> {
+        use worktree::add::Error;
+
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .map(|name| gix_path::os_str_into_bstr(name).map(ToOwned::to_owned))
+            .transpose()
+            .map_err(|_| Error::PathExists { path: path.to_owned() })?
+            .unwrap_or_default();
+        let path_existed = match std::fs::read_dir(path) {
+            Ok(mut entries) => {
+                if entries.next().is_some() {
+                    return Err(Error::PathExists { path: path.to_owned() });
+                }
+                true
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+            Err(_) => return Err(Error::PathExists { path: path.to_owned() }),
+        };
+
+        let (head, commit_id) = self.worktree_add_target(commitish, name.as_ref(), &options)?;
+        if let Some(branch) = head.as_ref().filter(|_| !options.force) {
+            if let Some(path) = self.worktree_with_branch_checked_out(branch.as_ref())? {
+                return Err(Error::BranchCheckedOut {
+                    name: branch.clone(),
+                    path,
+                });
+            }
+        }
+        let tree_id = self.find_object(commit_id)?.peel_to_tree()?.id;
+
+        let worktrees = self.common_dir().join("worktrees");
+        std::fs::create_dir_all(&worktrees)?;
+        let id = worktree_id(name.as_ref());
+        let mut git_dir = worktrees.join(gix_path::from_bstr(id.as_bstr()));
+        let mut counter = 1;
+        loop {
+            match std::fs::create_dir(&git_dir) {
+                Ok(()) => break,
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    git_dir = worktrees.join(gix_path::from_bstr(format!("{id}{counter}").as_bytes().as_bstr()));
+                    counter += 1;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let res = (|| -> Result<crate::Repository, Error> {
+            std::fs::write(git_dir.join("locked"), "initializing")?;
+            std::fs::create_dir_all(path)?;
+            let base = gix_path::realpath(path)?;
+            let git_dir = gix_path::realpath(&git_dir)?;
+            worktree::proxy::write_gitdir_file(&git_dir, &base)?;
+            std::fs::write(git_dir.join("commondir"), "../..\n")?;
+            std::fs::write(
+                git_dir.join("HEAD"),
+                match &head {
+                    Some(branch) => format!("ref: {}\n", branch.as_bstr()),
+                    None => format!("{commit_id}\n"),
+                },
+            )?;
+            let mut dot_git = BString::from("gitdir: ");
+            dot_git.extend_from_slice(&gix_path::into_bstr(&git_dir));
+            dot_git.push(b'\n');
+            std::fs::write(base.join(gix_discover::DOT_GIT_DIR), dot_git)?;
+
+            let repo: crate::Repository = crate::ThreadSafeRepository::open_from_paths(
+                git_dir.clone(),
+                Some(base.clone()),
+                self.options.clone(),
+            )?
+            .into();
+            if options.checkout {
+                let mut index = repo.index_from_tree(&tree_id)?;
+                let mut opts = repo.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
+                opts.destination_is_initially_empty = true;
+                gix_worktree_state::checkout(
+                    &mut index,
+                    &base,
+                    repo.objects.clone().into_arc()?,
+                    &gix_features::progress::Discard,
+                    &gix_features::progress::Discard,
+                    should_interrupt,
+                    opts,
+                )?;
+                if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                index.write(gix_index::write::Options {
+                    fsync: repo.config.fsync(gix_fs::fsync::Components::INDEX),
+                    ..Default::default()
+                })?;
+            }
+            match &options.lock_reason {
+                Some(reason) => std::fs::write(git_dir.join("locked"), reason)?,
+                None => std::fs::remove_file(git_dir.join("locked"))?,
+            }
+            Ok(repo)
+        })();
+        if res.is_err() {
+            if path_existed {
+                if let Ok(entries) = std::fs::read_dir(path) {
+                    for entry in entries.filter_map(Result::ok) {
+                        let entry = entry.path();
+                        if entry.is_dir() {
+                            std::fs::remove_dir_all(entry).ok();
+                        } else {
+                            std::fs::remove_file(entry).ok();
+                        }
+                    }
+                }
+            } else {
+                std::fs::remove_dir_all(path).ok();
+            }
+            worktree::proxy::remove_git_dir(&git_dir).ok();
+        }
+        res
+    }
+
+    /// Return the branch to check out in a new worktree, or `None` if `HEAD` should be detached, along with the commit to check out.
+    #[cfg(all(feature = "worktree-mutation", feature = "revision"))]
+    fn worktree_add_target(
+        &self,
+        commitish: Option<&BStr>,
+        name: &BStr,
+        options: &worktree::add::Options,
+    ) -> Result<(Option<gix_ref::FullName>, gix_hash::ObjectId), worktree::add::Error> {
+        use gix_ref::transaction::PreviousValue;
+        use worktree::add::Error;
+
+        let commit_id = |spec: Option<&BStr>| -> Result<gix_hash::ObjectId, Error> {
+            Ok(match spec {
+                Some(spec) => self.rev_parse_single(spec)?.object()?.peel_to_commit()?.id,
+                None => self.head_id()?.detach(),
+            })
+        };
+        let branch_name = |name: &BStr| -> Result<gix_ref::FullName, Error> {
+            let mut full_name = BString::from("refs/heads/");
+            full_name.extend_from_slice(name);
+            Ok(full_name.try_into()?)
+        };
+        let create_branch =
+            |branch: gix_ref::FullName, target: gix_hash::ObjectId, message: BString| -> Result<_, Error> {
+                let constraint = if options.force {
+                    PreviousValue::Any
+                } else {
+                    PreviousValue::MustNotExist
+                };
+                if !options.force && self.try_find_reference(branch.as_ref())?.is_some() {
+                    return Err(Error::BranchExists { name: branch });
+                }
+                self.reference(branch.clone(), target, constraint, message)?;
+                Ok((Some(branch), target))
+            };
+
+        if let Some(new_branch) = &options.new_branch {
+            let target = commit_id(commitish)?;
+            let message = format!("branch: Created from {}", commitish.unwrap_or("HEAD".into()));
+            return create_branch(branch_name(new_branch.as_ref())?, target, message.into());
+        }
+        match commitish {
+            Some(commitish) => {
+                if !options.detach {
+                    if let Ok(branch) = branch_name(commitish) {
+                        if let Some(mut reference) = self.try_find_reference(branch.as_ref())? {
+                            let target = reference.peel_to_commit()?.id;
+                            return Ok((Some(branch), target));
+                        }
+                    }
+                }
+                Ok((None, commit_id(Some(commitish))?))
+            }
+            None if options.detach => Ok((None, commit_id(None)?)),
+            None => {
+                let branch = branch_name(name)?;
+                match self.try_find_reference(branch.as_ref())? {
+                    Some(mut reference) => {
+                        let target = reference.peel_to_commit()?.id;
+                        Ok((Some(branch), target))
+                    }
+                    None => create_branch(branch, commit_id(None)?, "branch: Created from HEAD".into()),
+                }
+            }
+        }
+    }
+
+    /// Return the base of the worktree that has `branch` checked out, if there is one.
+    #[cfg(all(feature = "worktree-mutation", feature = "revision"))]
+    fn worktree_with_branch_checked_out(
+        &self,
+        branch: &gix_ref::FullNameRef,
+    ) -> std::io::Result<Option<std::path::PathBuf>> {
+        let has_branch_checked_out = |git_dir: &std::path::Path| -> bool {
+            std::fs::read(git_dir.join("HEAD")).is_ok_and(|head| {
+                head.strip_prefix(b"ref: ")
+                    .is_some_and(|name| name.trim_end() == branch.as_bstr())
+            })
+        };
+        let common_dir = self.common_dir();
+        if has_branch_checked_out(common_dir) {
+            let main_base = gix_discover::path::without_dot_git_dir(common_dir.to_owned());
+            return Ok(Some(main_base));
+        }
+        Ok(self
+            .worktrees()?
+            .into_iter()
+            .find(|proxy| has_branch_checked_out(proxy.git_dir()))
+            .map(|proxy| proxy.base().unwrap_or_else(|_| proxy.git_dir().to_owned())))
+    }
+
+    /// Remove the administrative files of linked worktrees which don't exist anymore, like `git worktree prune` would,
+    /// and return the pruned worktrees sorted by id.
+    ///
+    /// Locked worktrees are never pruned, see [`worktree::Proxy::prunable()`] for details.
+    pub fn worktrees_prune(
+        &self,
+        options: worktree::prune::Options,
+    ) -> Result<Vec<worktree::prune::Pruned>, worktree::prune::Error> {
+        let mut res = Vec::new();
+        let iter = match std::fs::read_dir(self.common_dir().join("worktrees")) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(res),
+            Err(err) => return Err(err),
+        };
+        for entry in iter {
+            let git_dir = entry?.path();
+            let Some(reason) = worktree::proxy::prunable(&git_dir) else {
+                continue;
+            };
+            if let Some(expire) = options.expire {
+                let gitdir_file = git_dir.join("gitdir");
+                let modified = gitdir_file
+                    .symlink_metadata()
+                    .or_else(|_| git_dir.symlink_metadata())?
+                    .modified()?;
+                if modified >= expire {
+                    continue;
+                }
+            }
+            if !options.dry_run {
+                worktree::proxy::remove_git_dir(&git_dir)?;
+            }
+            res.push(worktree::prune::Pruned {
+                id: gix_path::into_bstr(std::path::Path::new(git_dir.file_name().expect("read from directory")))
+                    .into_owned(),
+                reason,
+            });
+        }
+        res.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(res)
+    }
+
+    /// Repair the links between linked worktrees and their administrative files in `.git/worktrees/<id>`,
+    /// like `git worktree repair [<path>…]` would, and return the links that were repaired.
+    ///
+    /// The `.git` file of each known linked worktree is made to point to its administrative files, which fixes worktrees after
+    /// the main worktree or the repository was moved.
+    /// Each of the given `moved_worktrees` is expected to be the base of a linked worktree that was moved manually, which
+    /// makes its administrative files point back to it.
+    pub fn worktrees_repair(
+        &self,
+        moved_worktrees: impl IntoIterator<Item = impl AsRef<std::path::Path>>,
+    ) -> Result<Vec<worktree::repair::Repaired>, worktree::repair::Error> {
+        use worktree::repair::{Kind, Repaired};
+
+        let mut res = Vec::new();
+        let worktrees = self.common_dir().join("worktrees");
+        let points_to = |path: Option<std::path::PathBuf>, expected: &std::path::Path| -> bool {
+            path.and_then(|path| gix_path::realpath(path).ok())
+                .is_some_and(|path| gix_path::realpath(expected).is_ok_and(|expected| expected == path))
+        };
+        let repair_dot_git_file = |id: BString, base: std::path::PathBuf, git_dir: &std::path::Path| {
+            let dot_git = base.join(gix_discover::DOT_GIT_DIR);
+            if !dot_git.is_file() || points_to(gix_discover::path::from_gitdir_file(&dot_git).ok(), git_dir) {
+                return Ok(None);
+            }
+            let mut content = BString::from("gitdir: ");
+            content.extend_from_slice(&gix_path::into_bstr(
+                gix_path::realpath(git_dir).map_err(std::io::Error::other)?,
+            ));
+            content.push(b'\n');
+            std::fs::write(dot_git, content)?;
+            Ok::<_, std::io::Error>(Some(Repaired {
+                id,
+                base,
+                kind: Kind::DotGitFile,
+            }))
+        };
+
+        for proxy in self.worktrees()? {
+            if let Ok(base) = proxy.base() {
+                res.extend(repair_dot_git_file(proxy.id().to_owned(), base, proxy.git_dir())?);
+            }
+        }
+        for base in moved_worktrees {
+            let base = gix_path::realpath(base.as_ref()).map_err(std::io::Error::other)?;
+            let dot_git = base.join(gix_discover::DOT_GIT_DIR);
+            let Some(id) = gix_discover::path::from_gitdir_file(&dot_git)
+                .ok()
+                .and_then(|git_dir| git_dir.file_name().map(ToOwned::to_owned))
+            else {
+                continue;
+            };
+            let git_dir = worktrees.join(&id);
+            if !git_dir.is_dir() {
+                continue;
+            }
+            let id = gix_path::into_bstr(std::path::PathBuf::from(id)).into_owned();
+            res.extend(repair_dot_git_file(id.clone(), base.clone(), &git_dir)?);
+            let gitdir_file = gix_discover::path::from_plain_file_relative_to_file(&git_dir.join("gitdir"));
+            if !points_to(gitdir_file.and_then(Result::ok), &dot_git) {
+                worktree::proxy::write_gitdir_file(&git_dir, &base)?;
+                res.push(Repaired {
+                    id,
+                    base,
+                    kind: Kind::GitDirFile,
+                });
+            }
+        }
+        Ok(res)
+    }
+
     /// Return the repository owning the main worktree, typically from a linked worktree.
     ///
     /// Note that it might be the one that is currently open if this repository doesn't point to a linked worktree.
@@ -158,3 +499,21 @@ impl crate::Repository {
         Ok(())
     }
 }
+
+/// Derive the id of a new linked worktree from its `name`, like git does.
+#[cfg(all(feature = "worktree-mutation", feature = "revision"))]
+fn worktree_id(name: &BStr) -> BString {
+    let id: BString = name
+        .iter()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-') {
+                *b
+            } else {
+                b'-'
+            }
+        })
+        .skip_while(|b| *b == b'.')
+        .collect::<Vec<_>>()
+        .into();
+    if id.is_empty() { "worktree".into() } else { id }
+}
@@ -0,0 +1,50 @@
+/// The error returned by [`Repository::reset()`](crate::Repository::reset()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    CheckoutTree(#[from] crate::checkout_tree::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error("Could not write '{}'", path.display())]
+    WriteFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Determine what [`Repository::reset()`](crate::Repository::reset()) affects besides the branch `HEAD` points to.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Mode {
+    /// Only move the branch, leaving the index and the worktree untouched, like `git reset --soft` would.
+    Soft,
+    /// Also make the index match the commit, but leave the worktree untouched, like `git reset --mixed` would.
+    #[default]
+    Mixed,
+    /// Also make the index and the worktree match the commit, discarding all local changes to tracked files,
+    /// like `git reset --hard` would.
+    Hard,
+}
+
+/// The outcome produced by [`Repository::reset()`](crate::Repository::reset()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The commit `HEAD` pointed to before the reset, or `None` if it was unborn.
+    pub previous: Option<gix_hash::ObjectId>,
+    /// The outcome of updating the worktree if the [mode](Mode::Hard) asked for it.
+    pub checkout: Option<crate::checkout_tree::Outcome>,
+}
@@ -0,0 +1,197 @@
+pub use gix_sequencer as plumbing;
+pub use gix_sequencer::{
+    State,
+    apply::{Empty, Sequence},
+    todo::Action,
+};
+
+use crate::Id;
+
+/// A way to configure [`Repository::cherry_pick()`](crate::Repository::cherry_pick())
+/// and [`Repository::revert()`](crate::Repository::revert()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    pub(crate) tree_merge: crate::merge::tree::Options,
+    pub(crate) treat_as_unresolved: crate::merge::tree::TreatAsUnresolved,
+    pub(crate) sequence: Sequence,
+}
+
+impl From<gix_merge::tree::Options> for Options {
+    fn from(value: gix_merge::tree::Options) -> Self {
+        crate::merge::tree::Options::from(value).into()
+    }
+}
+
+impl From<crate::merge::tree::Options> for Options {
+    fn from(value: crate::merge::tree::Options) -> Self {
+        Options {
+            tree_merge: value,
+            ..Default::default()
+        }
+    }
+}
+
+/// Builder
+impl Options {
+    /// If `true`, append a line like `(cherry picked from commit <id>)` to the message of each cherry-picked commit,
+    /// just like `git cherry-pick -x` does.
+    pub fn with_record_origin(mut self, record_origin: bool) -> Self {
+        self.sequence.record_origin = record_origin;
+        self
+    }
+
+    /// Set the number of the parent, starting at 1, whose changes to apply if a commit is a merge commit,
+    /// just like `git cherry-pick --mainline <parent>` does.
+    pub fn with_mainline(mut self, mainline: Option<usize>) -> Self {
+        self.sequence.mainline = mainline;
+        self
+    }
+
+    /// If `true`, commits that don't change anything to begin with are applied as empty commits instead of stopping at them.
+    pub fn with_allow_empty(mut self, allow_empty: bool) -> Self {
+        self.sequence.allow_empty = allow_empty;
+        self
+    }
+
+    /// Determine what to do with commits that become empty as their changes are already present.
+    pub fn with_empty(mut self, empty: Empty) -> Self {
+        self.sequence.empty = empty;
+        self
+    }
+
+    /// Determine which conflicts are considered unresolved, which stops the sequence at the commit that caused them.
+    pub fn with_treat_as_unresolved(mut self, treat_as_unresolved: crate::merge::tree::TreatAsUnresolved) -> Self {
+        self.treat_as_unresolved = treat_as_unresolved;
+        self
+    }
+}
+
+/// A commit that was processed as part of a sequence.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Step {
+    /// The commit that was cherry-picked or reverted.
+    pub commit: gix_hash::ObjectId,
+    /// The commit that was written, or `None` if it became empty and was dropped.
+    pub new: Option<gix_hash::ObjectId>,
+}
+
+/// Information about the commit a sequence stopped at.
+#[derive(Clone)]
+pub struct Stop<'repo> {
+    /// The commit that couldn't be applied.
+    pub commit: Id<'repo>,
+    /// What was attempted with `commit`.
+    pub action: Action,
+    /// The outcome of merging the changes of `commit` into `HEAD`, with at least one unresolved conflict,
+    /// or `None` if the sequence stopped because the commit is or became empty.
+    pub tree_merge: Option<crate::merge::tree::Outcome<'repo>>,
+    /// The commits that remain to be processed after `commit`.
+    pub remaining: Vec<gix_hash::ObjectId>,
+}
+
+/// The outcome of running a sequence of cherry-picks or reverts.
+#[derive(Clone)]
+pub struct Outcome<'repo> {
+    /// The commit `HEAD` points to after the sequence ran or stopped.
+    pub head: Id<'repo>,
+    /// All commits that were processed, in order.
+    pub steps: Vec<Step>,
+    /// The commit the sequence stopped at, or `None` if all commits were processed.
+    ///
+    /// If set, the state of the sequence was written to `.git/sequencer` so that it can be continued
+    /// by [`Repository::sequencer_continue()`](crate::Repository::sequencer_continue()) or `git cherry-pick --continue`.
+    pub stopped: Option<Stop<'repo>>,
+}
+
+/// The error returned by [`Repository::cherry_pick()`](crate::Repository::cherry_pick()),
+/// [`Repository::revert()`](crate::Repository::revert()) and the methods to continue a sequence.
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("A cherry-pick or revert is already in progress")]
+    InProgress,
+    #[error("There is no cherry-pick or revert in progress")]
+    NotInProgress,
+    #[error("A resolved tree is needed to commit the changes of {commit} that the sequence stopped at")]
+    ResolutionMissing { commit: gix_hash::ObjectId },
+    #[error("A resolved tree was provided, but the sequence didn't stop at a commit to commit it for")]
+    ResolutionUnexpected,
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error("Author identity is not configured")]
+    AuthorMissing,
+    #[error(transparent)]
+    SignatureTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    MergeResourceCache(#[from] crate::repository::merge_resource_cache::Error),
+    #[error(transparent)]
+    DiffResourceCache(#[from] crate::repository::diff_resource_cache::Error),
+    #[error(transparent)]
+    ValidationOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    Sequencer(#[from] gix_error::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    Quit(#[from] quit::Error),
+    #[error("Could not write '{}'", path.display())]
+    WriteFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+///
+pub mod abort {
+    /// The error returned by [`Repository::sequencer_abort()`](crate::Repository::sequencer_abort()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("There is no cherry-pick or revert in progress")]
+        NotInProgress,
+        #[error(
+            "HEAD moved to {actual} since the sequence stopped at {expected}, refusing to abort as commits would be lost"
+        )]
+        HeadMoved {
+            expected: gix_hash::ObjectId,
+            actual: gix_hash::ObjectId,
+        },
+        #[error(transparent)]
+        State(#[from] gix_error::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        Quit(#[from] super::quit::Error),
+    }
+}
+
+///
+pub mod quit {
+    /// The error returned by [`Repository::sequencer_quit()`](crate::Repository::sequencer_quit()).
+    #[derive(Debug, thiserror::Error)]
+    #[error("Could not remove '{}'", path.display())]
+    pub struct Error {
+        /// The path that couldn't be removed.
+        pub path: std::path::PathBuf,
+        /// The underlying error.
+        pub source: std::io::Error,
+    }
+}
+
+///
+pub mod state {
+    /// The error returned by [`Repository::sequencer_state()`](crate::Repository::sequencer_state()).
+    pub type Error = gix_error::Error;
+}
@@ -0,0 +1,257 @@
+use crate::bstr::BString;
+
+/// The name of the reference that points to the most recent stash entry, with older entries in its reflog.
+pub const REF_NAME: &str = "refs/stash";
+
+/// A stash entry as listed by [`Repository::stash_list()`](crate::Repository::stash_list()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Entry {
+    /// The id of the stash commit, whose tree is the worktree at the time the stash was created.
+    ///
+    /// Its first parent is the commit `HEAD` pointed to, its second parent is a commit with the tree of the index,
+    /// and its optional third parent is a commit with the untracked files.
+    pub id: gix_hash::ObjectId,
+    /// The message of the stash entry, like `WIP on main: 1234567 subject`.
+    pub message: BString,
+}
+
+///
+pub mod save {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::stash_save()`](crate::Repository::stash_save()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("A bare repository has no worktree to stash changes from")]
+        MissingWorkDir,
+        #[error("Cannot stash changes without an initial commit")]
+        UnbornHead,
+        #[error(transparent)]
+        Head(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        HeadCommit(#[from] crate::reference::head_commit::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        IndexTree(#[from] super::index_tree::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error(transparent)]
+        StatusIter(#[from] crate::status::into_iter::Error),
+        #[error(transparent)]
+        StatusItem(#[from] crate::status::index_worktree::Error),
+        #[error(transparent)]
+        DirwalkOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        Dirwalk(#[from] crate::dirwalk::iter::Error),
+        #[error(transparent)]
+        DirwalkItem(#[from] crate::dirwalk::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        WorktreeFile(#[from] crate::filter::pipeline::worktree_file_to_object::Error),
+        #[error(transparent)]
+        EditTree(#[from] crate::repository::edit_tree::Error),
+        #[error(transparent)]
+        TreeEdit(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        NewCommit(#[from] crate::repository::new_commit::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        Reset(#[from] crate::checkout_tree::Error),
+        #[error("Could not remove untracked file at '{}'", path.display())]
+        RemoveUntracked {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+
+    /// A way to configure [`Repository::stash_save()`](crate::Repository::stash_save()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Options {
+        pub(crate) message: Option<BString>,
+        pub(crate) include_untracked: bool,
+    }
+
+    /// Builder
+    impl Options {
+        /// Use `message` to describe the stash entry, like `git stash push -m <message>` would.
+        ///
+        /// By default, the message is derived from `HEAD`, like `WIP on main: 1234567 subject`.
+        pub fn with_message(mut self, message: impl Into<BString>) -> Self {
+            self.message = Some(message.into());
+            self
+        }
+
+        /// If `true`, also stash untracked files and remove them from the worktree, like `git stash push --include-untracked` would.
+        /// Ignored files are never stashed.
+        pub fn with_include_untracked(mut self, include_untracked: bool) -> Self {
+            self.include_untracked = include_untracked;
+            self
+        }
+    }
+}
+
+///
+pub mod list {
+    /// The error returned by [`Repository::stash_list()`](crate::Repository::stash_list()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error("Could not read the reflog of the stash")]
+        ReadLog(#[source] std::io::Error),
+        #[error(transparent)]
+        DecodeLog(#[from] gix_ref::file::log::iter::decode::Error),
+    }
+}
+
+///
+pub mod apply {
+    use crate::checkout_tree;
+
+    /// The error returned by [`Repository::stash_apply()`](crate::Repository::stash_apply())
+    /// and [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("There is no stash entry at index {index}")]
+        NotFound { index: usize },
+        #[error("Commit {id} isn't a stash commit as it has less than two parents")]
+        NotAStash { id: gix_hash::ObjectId },
+        #[error(transparent)]
+        List(#[from] super::list::Error),
+        #[error(transparent)]
+        Drop(#[from] super::drop::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        IndexTree(#[from] super::index_tree::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error(transparent)]
+        MergeTrees(#[from] crate::repository::merge_trees::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        Checkout(#[from] checkout_tree::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        CheckoutUntracked(#[from] gix_worktree_state::checkout::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[from] std::io::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error("The checkout was interrupted")]
+        Interrupted,
+    }
+
+    /// A way to configure [`Repository::stash_apply()`](crate::Repository::stash_apply())
+    /// and [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Options {
+        pub(crate) tree_merge: crate::merge::tree::Options,
+        pub(crate) treat_as_unresolved: crate::merge::tree::TreatAsUnresolved,
+    }
+
+    impl From<gix_merge::tree::Options> for Options {
+        fn from(value: gix_merge::tree::Options) -> Self {
+            crate::merge::tree::Options::from(value).into()
+        }
+    }
+
+    impl From<crate::merge::tree::Options> for Options {
+        fn from(value: crate::merge::tree::Options) -> Self {
+            Options {
+                tree_merge: value,
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Builder
+    impl Options {
+        /// Determine which conflicts are considered unresolved, which prevents the stash entry from being applied.
+        pub fn with_treat_as_unresolved(mut self, treat_as_unresolved: crate::merge::tree::TreatAsUnresolved) -> Self {
+            self.treat_as_unresolved = treat_as_unresolved;
+            self
+        }
+    }
+
+    /// The outcome produced by [`Repository::stash_apply()`](crate::Repository::stash_apply())
+    /// and [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+    #[derive(Clone)]
+    pub struct Outcome<'repo> {
+        /// The id of the stash commit that was applied.
+        pub id: gix_hash::ObjectId,
+        /// The outcome of merging the stashed changes into the tree of the index, or `None` if the stash entry was applied.
+        ///
+        /// If `Some`, it has at least one unresolved conflict, and neither the index nor the worktree were changed.
+        /// The stash entry is also kept when [popping](crate::Repository::stash_pop()).
+        pub conflicts: Option<crate::merge::tree::Outcome<'repo>>,
+        /// The tracked files that were written or removed in the worktree, or `None` if there were `conflicts`.
+        pub checkout: Option<checkout_tree::Outcome>,
+        /// The paths of the untracked files that were restored, relative to the root of the worktree.
+        pub untracked: Vec<crate::bstr::BString>,
+    }
+}
+
+///
+pub mod drop {
+    /// The error returned by [`Repository::stash_drop()`](crate::Repository::stash_drop()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("There is no stash entry at index {index}")]
+        NotFound { index: usize },
+        #[error("Stash entries can't be dropped yet if references are stored in reftables")]
+        UnsupportedRefFormat,
+        #[error("Could not read the reflog of the stash at '{}'", path.display())]
+        ReadLog {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        List(#[from] super::list::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error("Could not rewrite the reflog of the stash at '{}'", path.display())]
+        WriteLog {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+///
+pub mod index_tree {
+    use crate::bstr::BString;
+
+    /// The error returned when writing the tree of the index for stash operations.
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("The index has unresolved conflicts, first one is '{rela_path}'")]
+        Unmerged { rela_path: BString },
+        #[error(transparent)]
+        EditTree(#[from] crate::repository::edit_tree::Error),
+        #[error(transparent)]
+        TreeEdit(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+    }
+}
@@ -58,6 +58,7 @@ where
             .enrich_error(self.repo.config.resolved.boolean(crate::config::tree::Index::SKIP_HASH))
             .with_lenient_default(self.repo.config.lenient_config)?
             .unwrap_or_default();
+        let fsync = self.repo.config.fsync(gix_fs::fsync::Components::INDEX);
         let should_interrupt = self.should_interrupt.clone().unwrap_or_default();
         let submodule = BuiltinSubmoduleStatus::new(self.repo.clone().into_sync(), self.submodules)?;
         #[cfg(feature = "parallel")]
@@ -136,6 +137,7 @@ where
                             worktree_index: index,
                             changes: None,
                             skip_hash,
+                            fsync,
                         })
                     }
                 })
@@ -204,6 +206,7 @@ where
                 tree_index,
                 changes: None,
                 skip_hash,
+                fsync,
             };
             items.extend(
                 collect
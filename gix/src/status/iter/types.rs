@@ -70,6 +70,7 @@ pub struct Outcome {
     /// The worktree index that was used for the operation.
     pub worktree_index: IndexPersistedOrInMemory,
     pub(super) skip_hash: bool,
+    pub(super) fsync: Option<gix_fs::fsync::Method>,
     pub(super) changes: Option<Vec<(usize, ApplyChange)>>,
 }
 
@@ -108,6 +109,7 @@ impl Outcome {
         Some(index.write(crate::index::write::Options {
             extensions: Default::default(),
             skip_hash: self.skip_hash,
+            fsync: self.fsync,
         }))
     }
 }
@@ -0,0 +1,39 @@
+use gix_ref::FullName;
+
+/// The error returned by [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    CheckoutTree(#[from] crate::checkout_tree::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+}
+
+/// What to point `HEAD` to with [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Target {
+    /// Make `HEAD` a symbolic reference to the given existing branch, like `git switch <branch>` would.
+    Branch(FullName),
+    /// Detach `HEAD` at the given commit, like `git switch --detach <commit>` would.
+    Detached(gix_hash::ObjectId),
+}
+
+/// The outcome produced by [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The commit `HEAD` pointed to before the switch, or `None` if it was unborn.
+    pub previous: Option<gix_hash::ObjectId>,
+    /// The commit `HEAD` points to now.
+    pub commit: gix_hash::ObjectId,
+    /// The outcome of updating the index and the worktree.
+    pub checkout: crate::checkout_tree::Outcome,
+}
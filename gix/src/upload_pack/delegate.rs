@@ -0,0 +1,380 @@
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::{parallel::InOrderIter, progress};
+use gix_hash::{ObjectId, oid};
+use gix_hashtable::HashSet;
+use gix_pack::data::output;
+use gix_protocol::{
+    handshake::Ref,
+    upload_pack::{
+        DelegateError, Pack, Shallow,
+        arguments::{Deepen, Fetch, Filter},
+    },
+};
+
+use crate::{
+    Repository,
+    bstr::{BString, ByteSlice},
+};
+
+/// Serve `upload-pack` from a repository.
+pub(crate) struct Delegate<'repo> {
+    pub repo: &'repo Repository,
+}
+
+impl Delegate<'_> {
+    /// Turn the reference `name` pointing to `target` into its advertised form.
+    fn to_ref(&self, name: BString, target: gix_ref::Target) -> Result<Ref, DelegateError> {
+        let target = match target {
+            gix_ref::Target::Object(id) => {
+                let (tag, object) = self.peel(id)?;
+                return Ok(match tag {
+                    Some(tag) => Ref::Peeled {
+                        full_ref_name: name,
+                        tag,
+                        object,
+                    },
+                    None => Ref::Direct {
+                        full_ref_name: name,
+                        object,
+                    },
+                });
+            }
+            gix_ref::Target::Symbolic(target) => target,
+        };
+        Ok(match self.repo.try_find_reference(target.as_ref())? {
+            Some(mut reference) => {
+                let id = reference.peel_to_id()?.detach();
+                // Avoid peeling the tag away, as it's the tag we want to advertise.
+                let id = match reference.target() {
+                    gix_ref::TargetRef::Object(direct) => direct.to_owned(),
+                    gix_ref::TargetRef::Symbolic(_) => id,
+                };
+                let (tag, object) = self.peel(id)?;
+                Ref::Symbolic {
+                    full_ref_name: name,
+                    target: target.into_inner(),
+                    tag,
+                    object,
+                }
+            }
+            None => Ref::Unborn {
+                full_ref_name: name,
+                target: target.into_inner(),
+            },
+        })
+    }
+
+    /// Return the annotated tag `id` points to, if it is one, along with the object it ultimately points to.
+    fn peel(&self, id: ObjectId) -> Result<(Option<ObjectId>, ObjectId), DelegateError> {
+        if self.repo.find_header(id)?.kind() != gix_object::Kind::Tag {
+            return Ok((None, id));
+        }
+        Ok((Some(id), self.repo.find_object(id)?.peel_tags_to_end()?.id))
+    }
+
+    /// Return the commits `ids` ultimately point to, ignoring all other objects.
+    fn commits<'a>(&self, ids: impl IntoIterator<Item = &'a ObjectId>) -> Result<Vec<ObjectId>, DelegateError> {
+        let mut out = Vec::new();
+        for id in ids {
+            let object = self.repo.find_object(*id)?.peel_tags_to_end()?;
+            if object.kind == gix_object::Kind::Commit {
+                out.push(object.id);
+            }
+        }
+        Ok(out)
+    }
+
+    fn parents(&self, id: ObjectId) -> Result<Vec<ObjectId>, DelegateError> {
+        Ok(self.repo.find_commit(id)?.parent_ids().map(crate::Id::detach).collect())
+    }
+
+    /// Compute the shallow boundary for `depth` commits along each line of history starting at `tips`.
+    fn shallow_by_depth(
+        &self,
+        tips: Vec<ObjectId>,
+        depth: u32,
+        client_shallow: &[ObjectId],
+    ) -> Result<Shallow, DelegateError> {
+        let depth = depth.max(1);
+        let mut out = Shallow::default();
+        let mut seen = HashSet::default();
+        let mut queue: VecDeque<_> = tips.into_iter().map(|id| (id, 1)).collect();
+        while let Some((id, commit_depth)) = queue.pop_front() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let parents = self.parents(id)?;
+            if commit_depth >= depth {
+                if !parents.is_empty() {
+                    out.shallow.push(id);
+                }
+                continue;
+            }
+            if client_shallow.contains(&id) && !parents.is_empty() {
+                out.unshallow.push(id);
+            }
+            queue.extend(parents.into_iter().map(|id| (id, commit_depth + 1)));
+        }
+        Ok(out)
+    }
+
+    /// Compute the shallow boundary that leaves out all commits for which `is_excluded` returns `true`.
+    fn shallow_by_exclusion(
+        &self,
+        tips: Vec<ObjectId>,
+        client_shallow: &[ObjectId],
+        mut is_excluded: impl FnMut(ObjectId) -> Result<bool, DelegateError>,
+    ) -> Result<Shallow, DelegateError> {
+        let mut out = Shallow::default();
+        let mut seen = HashSet::default();
+        let mut queue: VecDeque<_> = tips.into();
+        while let Some(id) = queue.pop_front() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let parents = self.parents(id)?;
+            let mut included = Vec::with_capacity(parents.len());
+            for parent in &parents {
+                if !is_excluded(*parent)? {
+                    included.push(*parent);
+                }
+            }
+            if included.len() != parents.len() {
+                out.shallow.push(id);
+                continue;
+            }
+            if client_shallow.contains(&id) && !parents.is_empty() {
+                out.unshallow.push(id);
+            }
+            queue.extend(included);
+        }
+        Ok(out)
+    }
+
+    /// Collect all commits the client needs to get to `tips` from the `common` commits, without going past `boundary`.
+    ///
+    /// Return the commits whose trees can be sent as changes compared to their parents, and the ones
+    /// on the `boundary` which need their whole tree.
+    fn commits_to_send(
+        &self,
+        tips: Vec<ObjectId>,
+        common: Vec<ObjectId>,
+        boundary: &[ObjectId],
+    ) -> Result<(Vec<ObjectId>, Vec<ObjectId>), DelegateError> {
+        if tips.is_empty() {
+            return Ok(Default::default());
+        }
+        if boundary.is_empty() {
+            let commits = self
+                .repo
+                .rev_walk(tips)
+                .with_hidden(common)
+                .all()?
+                .map(|info| info.map(|info| info.id))
+                .collect::<Result<_, _>>()?;
+            return Ok((commits, Vec::new()));
+        }
+
+        let (mut commits, mut boundary_commits) = (Vec::new(), Vec::new());
+        let mut seen: HashSet<_> = common.into_iter().collect();
+        let mut queue: VecDeque<_> = tips.into();
+        while let Some(id) = queue.pop_front() {
+            if !seen.insert(id) {
+                continue;
+            }
+            if boundary.contains(&id) {
+                boundary_commits.push(id);
+                continue;
+            }
+            commits.push(id);
+            queue.extend(self.parents(id)?);
+        }
+        Ok((commits, boundary_commits))
+    }
+
+    /// Return `true` if the object with `id` should be left out of the pack due to `filter`.
+    fn is_filtered(&self, filter: Filter, id: &oid) -> Result<bool, DelegateError> {
+        let header = self.repo.find_header(id)?;
+        Ok(match filter {
+            Filter::BlobNone => header.kind() == gix_object::Kind::Blob,
+            Filter::BlobLimit(limit) => header.kind() == gix_object::Kind::Blob && header.size() > limit,
+            Filter::TreeDepth(_) => matches!(header.kind(), gix_object::Kind::Blob | gix_object::Kind::Tree),
+        })
+    }
+}
+
+impl gix_protocol::upload_pack::Delegate for Delegate<'_> {
+    fn object_hash(&self) -> gix_hash::Kind {
+        self.repo.object_hash()
+    }
+
+    fn refs(&mut self) -> Result<Vec<Ref>, DelegateError> {
+        let mut out = Vec::new();
+        if let Some(head) = self.repo.try_find_reference("HEAD")? {
+            let head = head.detach();
+            out.push(self.to_ref(head.name.into_inner(), head.target)?);
+        }
+        for reference in self.repo.references()?.all()? {
+            let reference = reference?.detach();
+            out.push(self.to_ref(reference.name.into_inner(), reference.target)?);
+        }
+        Ok(out)
+    }
+
+    fn contains(&mut self, id: &oid) -> bool {
+        self.repo.has_object(id)
+    }
+
+    fn shallow(&mut self, arguments: &Fetch) -> Result<Shallow, DelegateError> {
+        if arguments.deepen_relative {
+            return Err("Deepening relative to the current shallow boundary isn't supported".into());
+        }
+        let Some(deepen) = &arguments.deepen else {
+            return Ok(Shallow::default());
+        };
+        let tips = self.commits(&arguments.wants)?;
+        let client_shallow = arguments.shallow.as_slice();
+        match deepen {
+            Deepen::Depth(depth) => self.shallow_by_depth(tips, *depth, client_shallow),
+            Deepen::Since(since) => self.shallow_by_exclusion(tips, client_shallow, |id| {
+                Ok(self.repo.find_commit(id)?.time()?.seconds < *since)
+            }),
+            Deepen::Not(names) => {
+                let mut excluded_tips = Vec::with_capacity(names.len());
+                for name in names {
+                    excluded_tips.push(self.repo.find_reference(name.as_bstr())?.peel_to_id()?.detach());
+                }
+                let excluded_tips = self.commits(&excluded_tips)?;
+                let excluded: HashSet<_> = self
+                    .repo
+                    .rev_walk(excluded_tips)
+                    .all()?
+                    .map(|info| info.map(|info| info.id))
+                    .collect::<Result<_, _>>()?;
+                self.shallow_by_exclusion(tips, client_shallow, |id| Ok(excluded.contains(&id)))
+            }
+        }
+    }
+
+    fn write_pack(
+        &mut self,
+        Pack {
+            arguments,
+            common,
+            shallow,
+        }: Pack<'_>,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn std::io::Write,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), DelegateError> {
+        if let Some(Filter::TreeDepth(depth @ 1..)) = arguments.filter {
+            return Err(format!("The filter tree:{depth} isn't supported").into());
+        }
+        let repo = self.repo;
+        let mut handle = repo.objects.clone().into_arc()?.into_inner();
+        handle.prevent_pack_unload();
+        handle.ignore_replacements = true;
+
+        use output::count::objects::ObjectExpansion;
+        let common = self.commits(common)?;
+        let inputs = if common.is_empty() && shallow.is_empty() {
+            // Everything reachable is sent, which can be counted using reachability bitmaps if there are any.
+            vec![(arguments.wants.clone(), ObjectExpansion::AllReachable)]
+        } else {
+            let mut commit_tips = Vec::new();
+            let (mut additions, mut contents) = (Vec::new(), Vec::new());
+            for want in &arguments.wants {
+                let object = repo.find_object(*want)?.peel_tags_to_end()?;
+                if object.kind == gix_object::Kind::Commit {
+                    commit_tips.push(object.id);
+                    // Annotated tags are expanded while counting, and duplicates are ignored.
+                    additions.push(*want);
+                } else {
+                    contents.push(*want);
+                }
+            }
+            let (commits, boundary) = self.commits_to_send(commit_tips, common, shallow)?;
+            additions.extend(commits);
+            contents.extend(boundary);
+            vec![
+                (additions, ObjectExpansion::TreeAdditionsComparedToAncestor),
+                (contents, ObjectExpansion::TreeContents),
+            ]
+        };
+
+        let mut counts = Vec::new();
+        let mut seen = HashSet::default();
+        let objects = progress::Discard;
+        for (input, expansion) in inputs {
+            if input.is_empty() {
+                continue;
+            }
+            let (input_counts, _stats) = output::count::objects_unthreaded(
+                &handle,
+                &mut input.into_iter().map(Ok),
+                &objects,
+                should_interrupt,
+                expansion,
+                None,
+            )?;
+            counts.extend(input_counts.into_iter().filter(|count| seen.insert(count.id)));
+        }
+        if arguments.include_tag {
+            for reference in repo.references()?.tags()? {
+                let reference = reference?;
+                let gix_ref::TargetRef::Object(id) = reference.target() else {
+                    continue;
+                };
+                if let (Some(tag), object) = self.peel(id.to_owned())? {
+                    if seen.contains(&object) && seen.insert(tag) {
+                        counts.push(output::Count::from_data(tag, None));
+                    }
+                }
+            }
+        }
+        if let Some(filter) = arguments.filter {
+            let mut kept = Vec::with_capacity(counts.len());
+            for count in counts {
+                if arguments.wants.contains(&count.id) || !self.is_filtered(filter, &count.id)? {
+                    kept.push(count);
+                }
+            }
+            counts = kept;
+        }
+        writeln!(progress, "Counting objects: {}, done.", counts.len())?;
+
+        let num_objects = counts.len();
+        let entries = InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            handle,
+            Box::new(progress::Discard),
+            output::entry::iter_from_counts::Options {
+                thread_limit: None,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+                compression: repo.pack_compression()?,
+                ..Default::default()
+            },
+        ));
+        let mut pack = output::bytes::FromEntriesIter::new(
+            entries,
+            out,
+            num_objects as u32,
+            gix_pack::data::Version::default(),
+            repo.object_hash(),
+        );
+        for written in pack.by_ref() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err("Interrupted while sending the pack".into());
+            }
+            written?;
+        }
+        Ok(())
+    }
+}
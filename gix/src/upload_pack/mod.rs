@@ -0,0 +1,46 @@
+//! Serve the `upload-pack` service from a repository, allowing clients to fetch from it.
+//!
+//! Use [`Repository::upload_pack()`](crate::Repository::upload_pack()) to serve a client through any `Read` and `Write` pair,
+//! or [`in_process()`] to obtain a transport for fetching from a repository without spawning `git upload-pack`.
+pub use gix_protocol::upload_pack::{Error, arguments};
+
+mod delegate;
+pub(crate) use delegate::Delegate;
+
+/// The error returned by [`in_process()`].
+pub type InProcessError = std::io::Error;
+
+/// The transport returned by [`in_process()`].
+pub type InProcessTransport =
+    gix_transport::client::git::blocking_io::Connection<gix_features::io::pipe::Reader, gix_features::io::pipe::Writer>;
+
+/// Return a transport to fetch from `repo`, which is served by [`Repository::upload_pack()`](crate::Repository::upload_pack())
+/// in a separate thread.
+///
+/// The thread ends when the transport is dropped. Errors on the serving side are sent to the client,
+/// which is also how they are observed.
+///
+/// This is useful with [`Remote::to_connection_with_transport()`](crate::Remote::to_connection_with_transport()) to fetch
+/// from local repositories without spawning `git upload-pack`.
+pub fn in_process(repo: crate::ThreadSafeRepository) -> Result<InProcessTransport, InProcessError> {
+    let trace = repo.config.trace_packet();
+    let path = gix_path::into_bstr(repo.git_dir()).into_owned();
+    let (client_write, server_read) = gix_features::io::pipe::unidirectional(16);
+    let (server_write, client_read) = gix_features::io::pipe::unidirectional(16);
+    std::thread::Builder::new()
+        .name("gix::upload_pack::in_process".into())
+        .spawn(move || {
+            let repo = repo.to_thread_local();
+            repo.upload_pack(server_read, server_write, &std::sync::atomic::AtomicBool::default())
+                .ok();
+        })?;
+    Ok(InProcessTransport::new(
+        client_read,
+        client_write,
+        gix_transport::Protocol::V2,
+        path,
+        None::<(&str, _)>,
+        gix_transport::client::git::ConnectMode::Process,
+        trace,
+    ))
+}
@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+#[derive(Debug, thiserror::Error)]
+#[expect(missing_docs)]
+pub enum Error {
+    #[error("'{}' already exists and isn't an empty directory", path.display())]
+    PathExists { path: PathBuf },
+    #[error("Branch '{}' already exists", name.as_bstr())]
+    BranchExists { name: gix_ref::FullName },
+    #[error("Branch '{}' is already checked out at '{}'", name.as_bstr(), path.display())]
+    BranchCheckedOut { name: gix_ref::FullName, path: PathBuf },
+    #[error(transparent)]
+    InvalidBranchName(#[from] gix_validate::reference::name::Error),
+    #[error(transparent)]
+    RevParse(#[from] crate::revision::spec::parse::single::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::to_kind::Error),
+    #[error(transparent)]
+    Realpath(#[from] gix_path::realpath::Error),
+    #[error(transparent)]
+    Open(#[from] crate::open::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("The checkout was interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// A way to configure [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub(crate) new_branch: Option<BString>,
+    pub(crate) detach: bool,
+    pub(crate) force: bool,
+    pub(crate) lock_reason: Option<BString>,
+    pub(crate) checkout: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            new_branch: None,
+            detach: false,
+            force: false,
+            lock_reason: None,
+            checkout: true,
+        }
+    }
+}
+
+/// Builder
+impl Options {
+    /// Create a new branch with the given short `name` at the commit to check out, and check it out,
+    /// like `git worktree add -b <name>` would.
+    pub fn with_new_branch(mut self, name: impl Into<BString>) -> Self {
+        self.new_branch = Some(name.into());
+        self
+    }
+
+    /// If `true`, detach `HEAD` of the new worktree at the commit to check out, even if it's given as branch name,
+    /// like `git worktree add --detach` would.
+    pub fn with_detach(mut self, detach: bool) -> Self {
+        self.detach = detach;
+        self
+    }
+
+    /// If `true`, check out a branch even if it's already checked out in another worktree, and reset a new branch
+    /// if it already exists, like `git worktree add --force` and `-B` would.
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Lock the new worktree with the given `reason`, which may be empty, like `git worktree add --lock --reason` would.
+    pub fn with_lock(mut self, reason: impl Into<BString>) -> Self {
+        self.lock_reason = Some(reason.into());
+        self
+    }
+
+    /// If `false`, don't check out any files and leave the index of the new worktree empty,
+    /// like `git worktree add --no-checkout` would. It's `true` by default.
+    pub fn with_checkout(mut self, checkout: bool) -> Self {
+        self.checkout = checkout;
+        self
+    }
+}
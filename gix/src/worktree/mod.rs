@@ -112,8 +112,15 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
     (maybe_worktrees.file_name()?.to_str()? == "worktrees").then_some(candidate)
 }
 
+///
+#[cfg(all(feature = "worktree-mutation", feature = "revision"))]
+pub mod add;
 ///
 pub mod proxy;
+///
+pub mod prune;
+///
+pub mod repair;
 
 ///
 #[cfg(feature = "index")]
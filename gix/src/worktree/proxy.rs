@@ -24,6 +24,66 @@ pub mod into_repo {
     }
 }
 
+///
+pub mod lock {
+    /// The error returned by [`Proxy::lock()`](super::Proxy::lock()) and [`Proxy::unlock()`](super::Proxy::unlock()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is already locked")]
+        AlreadyLocked { id: crate::bstr::BString },
+        #[error("Worktree '{id}' is not locked")]
+        NotLocked { id: crate::bstr::BString },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+pub mod move_to {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Proxy::move_to()`](super::Proxy::move_to()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked and can't be moved")]
+        Locked { id: crate::bstr::BString },
+        #[error("Cannot move the worktree to '{}' as it already exists", path.display())]
+        DestinationExists { path: PathBuf },
+        #[error(transparent)]
+        Realpath(#[from] gix_path::realpath::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+#[cfg(feature = "status")]
+pub mod remove {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Proxy::remove()`](super::Proxy::remove()).
+    #[derive(Debug, thiserror::Error)]
+    #[expect(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked and can't be removed")]
+        Locked { id: crate::bstr::BString },
+        #[error("Worktree at '{}' contains modified or untracked files", base.display())]
+        Dirty { base: PathBuf },
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error(transparent)]
+        StatusIter(#[from] crate::status::into_iter::Error),
+        #[error(transparent)]
+        StatusItem(#[from] crate::status::iter::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
 impl<'repo> Proxy<'repo> {
     pub(crate) fn new(parent: &'repo Repository, git_dir: impl Into<PathBuf>) -> Self {
         Proxy {
@@ -83,6 +143,92 @@ impl Proxy<'_> {
             .map(|contents| contents.trim().into())
     }
 
+    /// Lock the worktree so it can't be pruned, moved or removed, and store `reason` for it, which may be empty,
+    /// like `git worktree lock --reason <reason>` would.
+    pub fn lock(&self, reason: impl AsRef<BStr>) -> Result<(), lock::Error> {
+        if self.is_locked() {
+            return Err(lock::Error::AlreadyLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::write(self.git_dir.join("locked"), reason.as_ref())?;
+        Ok(())
+    }
+
+    /// Unlock a worktree that was previously [locked](Self::lock()), like `git worktree unlock` would.
+    pub fn unlock(&self) -> Result<(), lock::Error> {
+        if !self.is_locked() {
+            return Err(lock::Error::NotLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::remove_file(self.git_dir.join("locked"))?;
+        Ok(())
+    }
+
+    /// Return the reason for this worktree to be prunable, or `None` if it is still in use or [locked](Self::is_locked()).
+    pub fn prunable(&self) -> Option<crate::worktree::prune::Reason> {
+        prunable(&self.git_dir)
+    }
+
+    /// Move the checkout of this worktree to `destination`, or into `destination` if it is an existing directory,
+    /// and update its administrative files accordingly, like `git worktree move` would.
+    ///
+    /// Return the new base of the worktree. Note that locked worktrees can't be moved.
+    pub fn move_to(&self, destination: impl AsRef<Path>) -> Result<PathBuf, move_to::Error> {
+        if self.is_locked() {
+            return Err(move_to::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let base = self.base()?;
+        let mut destination = destination.as_ref().to_owned();
+        if destination.is_dir() {
+            if let Some(name) = base.file_name() {
+                destination.push(name);
+            }
+        }
+        if destination.symlink_metadata().is_ok() {
+            return Err(move_to::Error::DestinationExists { path: destination });
+        }
+        std::fs::rename(&base, &destination)?;
+        let destination = gix_path::realpath(destination)?;
+        write_gitdir_file(&self.git_dir, &destination)?;
+        Ok(destination)
+    }
+
+    /// Delete the checkout of this worktree along with its administrative files, like `git worktree remove` would.
+    ///
+    /// Unless `force` is `true`, this fails if the worktree contains untracked files, or if its index or any of its tracked
+    /// files are modified.
+    /// Note that locked worktrees can't be removed, and submodules within the worktree aren't checked for changes.
+    #[cfg(feature = "status")]
+    pub fn remove(self, force: bool) -> Result<(), remove::Error> {
+        if self.is_locked() {
+            return Err(remove::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        if let Some(base) = self.base().ok().filter(|base| base.is_dir()) {
+            if !force {
+                let repo = self.clone().into_repo_with_possibly_inaccessible_worktree()?;
+                let is_dirty = repo
+                    .status(gix_features::progress::Discard)?
+                    .index_worktree_submodules(None)
+                    .into_iter(Vec::new())?
+                    .next()
+                    .transpose()?
+                    .is_some();
+                if is_dirty {
+                    return Err(remove::Error::Dirty { base });
+                }
+            }
+            std::fs::remove_dir_all(&base)?;
+        }
+        remove_git_dir(&self.git_dir)?;
+        Ok(())
+    }
+
     /// Transform this proxy into a [`Repository`] while ignoring issues reading `base()` and ignoring that it might not exist.
     ///
     /// Most importantly, the `Repository` might be initialized with a non-existing work tree directory as the checkout
@@ -108,3 +254,41 @@ impl Proxy<'_> {
         Ok(repo.into())
     }
 }
+
+/// Return the reason for the worktree with administrative files in `git_dir` to be prunable, or `None` if it isn't.
+pub(crate) fn prunable(git_dir: &Path) -> Option<crate::worktree::prune::Reason> {
+    use crate::worktree::prune::Reason;
+    if !git_dir.is_dir() {
+        return Some(Reason::NotADirectory);
+    }
+    if git_dir.join("locked").is_file() {
+        return None;
+    }
+    let dot_git = match gix_discover::path::from_plain_file_relative_to_file(&git_dir.join("gitdir")) {
+        None => return Some(Reason::GitDirFileMissing),
+        Some(Err(_)) => return Some(Reason::GitDirFileInvalid),
+        Some(Ok(path)) if path.as_os_str().is_empty() => return Some(Reason::GitDirFileInvalid),
+        Some(Ok(path)) => path,
+    };
+    (!dot_git.exists()).then_some(Reason::WorktreeMissing)
+}
+
+/// Point the `gitdir` file in `git_dir` to the `.git` file in `base`.
+pub(crate) fn write_gitdir_file(git_dir: &Path, base: &Path) -> std::io::Result<()> {
+    let mut content: BString = gix_path::into_bstr(base.join(gix_discover::DOT_GIT_DIR)).into_owned();
+    content.push(b'\n');
+    std::fs::write(git_dir.join("gitdir"), content)
+}
+
+/// Remove the administrative files of a worktree in `git_dir`, along with the `worktrees` directory if it's empty then.
+pub(crate) fn remove_git_dir(git_dir: &Path) -> std::io::Result<()> {
+    if git_dir.is_dir() {
+        std::fs::remove_dir_all(git_dir)?;
+    } else {
+        std::fs::remove_file(git_dir)?;
+    }
+    if let Some(worktrees) = git_dir.parent() {
+        std::fs::remove_dir(worktrees).ok();
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::worktrees_prune()`](crate::Repository::worktrees_prune()).
+pub type Error = std::io::Error;
+
+/// The reason for a linked worktree to be prunable, as its administrative files in `.git/worktrees/<id>` are of no use anymore.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Reason {
+    /// `.git/worktrees/<id>` isn't a directory.
+    NotADirectory,
+    /// The `gitdir` file that points to the worktree is missing.
+    GitDirFileMissing,
+    /// The `gitdir` file that points to the worktree can't be read or is empty.
+    GitDirFileInvalid,
+    /// The `gitdir` file points to a location that doesn't exist anymore, typically because the worktree was deleted.
+    WorktreeMissing,
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Reason::NotADirectory => "not a valid directory",
+            Reason::GitDirFileMissing => "gitdir file does not exist",
+            Reason::GitDirFileInvalid => "invalid gitdir file",
+            Reason::WorktreeMissing => "gitdir file points to non-existent location",
+        })
+    }
+}
+
+/// A way to configure [`Repository::worktrees_prune()`](crate::Repository::worktrees_prune()).
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    pub(crate) expire: Option<std::time::SystemTime>,
+    pub(crate) dry_run: bool,
+}
+
+/// Builder
+impl Options {
+    /// Only prune worktrees whose administrative files were last modified before `time`, like
+    /// `git worktree prune --expire <time>` would.
+    ///
+    /// By default, all prunable worktrees are pruned.
+    pub fn with_expire(mut self, time: std::time::SystemTime) -> Self {
+        self.expire = Some(time);
+        self
+    }
+
+    /// If `true`, only report what would be pruned without removing anything, like `git worktree prune --dry-run` would.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+/// A linked worktree that was pruned by [`Repository::worktrees_prune()`](crate::Repository::worktrees_prune()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Pruned {
+    /// The id of the worktree, which is the name of its directory in `.git/worktrees`.
+    pub id: BString,
+    /// The reason for the worktree to be pruned.
+    pub reason: Reason,
+}
@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::worktrees_repair()`](crate::Repository::worktrees_repair()).
+pub type Error = std::io::Error;
+
+/// The kind of link that was repaired by [`Repository::worktrees_repair()`](crate::Repository::worktrees_repair()).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Kind {
+    /// The `.git` file in the worktree was missing or didn't point to the administrative files of the worktree.
+    DotGitFile,
+    /// The `gitdir` file in `.git/worktrees/<id>` didn't point to the worktree, typically because it was moved.
+    GitDirFile,
+}
+
+/// A link between a linked worktree and its administrative files that was repaired.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Repaired {
+    /// The id of the worktree, which is the name of its directory in `.git/worktrees`.
+    pub id: BString,
+    /// The base of the worktree.
+    pub base: PathBuf,
+    /// The link that was repaired.
+    pub kind: Kind,
+}
@@ -0,0 +1,41 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+seq 10 > a
+mkdir dir && seq 20 30 > dir/b
+echo gone > gone
+printf 'bin\0ary' > bin
+git add .
+git commit -q -m "base"
+git tag base
+
+git checkout -q -b theirs
+sed -i.bak 's/^5$/five/' a && rm a.bak
+git commit -q -am "theirs"
+git checkout -q main
+
+sed -i.bak 's/^5$/FIVE/' a && rm a.bak
+git commit -q -am "ours"
+
+git checkout -q -b patched
+sed -i.bak 's/^2$/two/' a && rm a.bak
+git mv dir/b dir/moved
+echo 31 >> dir/moved
+git rm -q gone
+echo new > new
+printf 'bin\0ary\0changed' > bin
+git add .
+git commit -q -m "patched"
+git checkout -q main
+
+git diff --binary -M main patched > change.patch
+git diff base theirs > conflict.patch
+
+GIT_INDEX_FILE=.git/baseline-index git read-tree main
+GIT_INDEX_FILE=.git/baseline-index git apply --cached change.patch
+GIT_INDEX_FILE=.git/baseline-index git write-tree > change.tree
+rm .git/baseline-index
+
+git clone -q --bare . bare.git
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+seq 1 1000 > file && git add file && git commit -q -m c1
+seq 1 1001 > file && git add file && git commit -q -m c2
+git tag -a -m "annotated" v1
+seq 2 1002 > file && git add file && git commit -q -m c3
+git repack -q -a -d -f --depth=10 --window=10
+
+echo loose > loose && git add loose && git commit -q -m "loose"
+
+format='%(objectname) %(objecttype) %(objectsize) %(objectsize:disk) %(deltabase)'
+git cat-file --batch-all-objects --batch-check="$format" > .git/all-objects-info
+git cat-file --batch-all-objects --batch > .git/all-objects-contents
+
+printf 'HEAD\nv1\nHEAD~1:file\ndoes-not-exist\n%s\n' "$(git rev-parse HEAD~2:file)" | git cat-file --batch > .git/batch-contents
+printf 'HEAD first\nv1  second\nnope third\n' | git cat-file --batch-check='%(objecttype) %(rest)' > .git/batch-check-rest
+printf 'info HEAD\ncontents HEAD~1:file\ninfo does-not-exist\n' | git cat-file --batch-command > .git/batch-command
@@ -0,0 +1,17 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+for i in $(seq 1 12); do
+  if [ "$i" -ge 8 ]; then echo bug > state; else echo ok > state; fi
+  echo "$i" > count
+  git add .
+  git commit -q -m "c$i"
+  git tag "c$i"
+done
+
+git init -q --bare bare.git
+git -C bare.git symbolic-ref HEAD refs/heads/main
+git push -q bare.git main 'refs/tags/*'
+echo bare.git >> .git/info/exclude
@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo unchanged > unchanged
+echo a > changed
+echo removed > removed
+mkdir dir && echo d > dir/file
+git add .
+git commit -q -m base
+
+git checkout -q -b feature
+echo b > changed
+git rm -q removed
+git rm -q -r dir
+echo dir-as-file > dir
+echo added > added
+mkdir -p new/deep && echo deep > new/deep/file
+git add .
+git commit -q -m feature
+
+git checkout -q main
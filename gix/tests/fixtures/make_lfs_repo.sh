@@ -0,0 +1,11 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+git config filter.lfs.process "git-lfs filter-process"
+git config filter.lfs.required true
+echo "*.bin filter=lfs -text" >.gitattributes
+
+git add .gitattributes
+git commit -q -m "track binaries with lfs"
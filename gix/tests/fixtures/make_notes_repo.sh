@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+git commit -q --allow-empty -m c1
+git commit -q --allow-empty -m c2
+git commit -q --allow-empty -m c3
+
+git notes add -m "note for c3" HEAD
+git notes add -m "note for c2" HEAD~1
+git notes --ref review add -m "review of c3" HEAD
+git notes --ref other add -m "other note for c3" HEAD
+
+git config notes.displayRef "refs/notes/rev*"
@@ -0,0 +1,42 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --bare remote.git
+
+git init -q local
+(cd local
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  echo 2 > file && git commit -q -am c2
+  git tag v1
+  git branch protected
+  git branch to-delete
+  git checkout -q -b diverged
+  echo diverged > file && git commit -q -am "diverged on the remote"
+  git remote add origin ../remote.git
+  git push -q origin main protected to-delete diverged v1
+  git reset -q --hard main
+  echo "diverged locally" > file && git commit -q -am "diverged locally"
+  git checkout -q main
+  echo 3 > file && git commit -q -am c3
+  mkdir dir && echo new > dir/new && git add dir && git commit -q -m "c4 with new tree"
+  git branch new-branch
+)
+
+(cd remote.git
+  git config receive.advertisePushOptions true
+  cat <<'HOOK' > hooks/update
+#!/bin/sh
+test "$1" != refs/heads/protected || { echo "protected branches can't be changed"; exit 1; }
+HOOK
+  cat <<'HOOK' > hooks/post-receive
+#!/bin/sh
+: > push-options
+i=0
+while test "$i" -lt "${GIT_PUSH_OPTION_COUNT:-0}"; do
+  eval "echo \"\$GIT_PUSH_OPTION_$i\"" >> push-options
+  i=$((i + 1))
+done
+HOOK
+  chmod +x hooks/update hooks/post-receive
+)
@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+seq 1 10 > numbers
+echo a > letters
+git add numbers letters
+git commit -q -m base
+git tag base
+
+git checkout -q -b topic
+echo b >> letters
+git commit -q -am "add b"
+echo upstream > shared
+git add shared
+git commit -q -m "add shared file"
+sed -i.bak 's/^5$/five/' numbers && rm numbers.bak
+git commit -q -am "change five"
+
+git checkout -q main
+echo upstream > shared
+git add shared
+git commit -q -m "add shared file upstream"
+seq 0 10 > numbers
+git commit -q -am "prepend zero"
+
+git checkout -q -b conflicting
+sed -i.bak 's/^5$/FIVE/' numbers && rm numbers.bak
+git commit -q -am "change five differently"
+
+git checkout -q -b rebased-by-git topic
+git rebase -q main
+
+git checkout -q main
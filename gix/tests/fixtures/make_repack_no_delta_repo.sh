@@ -0,0 +1,14 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo '*.bin -delta' > .gitattributes
+seq 1 2000 > similar.txt
+seq 10001 12000 > similar.bin
+git add . && git commit -q -m c1
+
+seq 1 2001 > similar.txt
+seq 10001 12001 > similar.bin
+git commit -q -am c2
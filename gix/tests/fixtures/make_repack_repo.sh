@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a && git add a && git commit -q -m c1
+echo b > b && git add b && git commit -q -m c2
+git tag -a -m "annotated" v1
+git repack -q -d
+
+git checkout -q -b side
+echo c > c && git add c && git commit -q -m c3
+git checkout -q main
+git repack -q -d
+
+echo d > d && git add d && git commit -q -m c4
+name=$(printf 'HEAD\n^HEAD~1\n' | git pack-objects -q --revs .git/objects/pack/pack)
+touch ".git/objects/pack/pack-$name.keep"
+git prune-packed
+
+unreachable_packed=$(echo unreachable-packed | git hash-object -w --stdin)
+echo "$unreachable_packed" | git pack-objects -q .git/objects/pack/pack >/dev/null
+git prune-packed
+
+echo e > e && git add e && git commit -q -m c5
+echo f > f && git add f && git commit -q -m "only in reflog"
+git reset -q --hard HEAD~1
+echo staged > staged && git add staged
+echo unreachable-loose | git hash-object -w --stdin >/dev/null
+
+git rev-list --objects --all --reflog --indexed-objects | cut -d ' ' -f 1 | sort > .git/reachable
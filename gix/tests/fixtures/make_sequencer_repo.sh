@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+seq 1 10 > numbers
+echo a > letters
+git add numbers letters
+git commit -q -m base
+git tag base
+
+git checkout -q -b topic
+echo b >> letters
+git commit -q -am "add b"
+sed -i.bak 's/^5$/five/' numbers && rm numbers.bak
+git commit -q -am "change five"
+echo new > new
+git add new
+git commit -q -m "add new file"
+
+git checkout -q main
+seq 0 10 > numbers
+git commit -q -am "prepend zero"
+sed -i.bak 's/^5$/FIVE/' numbers && rm numbers.bak
+git commit -q -am "change five differently"
+
+git checkout -q -b picked-by-git main
+git cherry-pick topic~2 >/dev/null
+
+git checkout -q -b reverted-by-git main
+git revert --no-edit main~1 >/dev/null
+
+git checkout -q main
+
+git clone -q . git-stopped
+(cd git-stopped
+  git cherry-pick -x base..origin/topic >/dev/null 2>&1 && exit 1
+  test -f .git/sequencer/todo
+)
@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo a > a
+echo b > b
+mkdir dir && echo c > dir/c
+git add .
+git commit -q -m "first"
+
+git checkout -q -b conflicting
+echo conflicting > a
+git commit -q -am "conflicting"
+git checkout -q main
+
+echo changed > a
+echo staged > b
+git add b
+echo added > added
+git add added
+mkdir untracked-dir
+echo untracked > untracked-dir/untracked
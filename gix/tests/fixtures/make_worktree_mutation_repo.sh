@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo a > file
+mkdir dir && echo b > dir/file
+git add .
+git commit -q -m first
+
+git branch other
+echo c > file
+git commit -q -am second
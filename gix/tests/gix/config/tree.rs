@@ -231,6 +231,79 @@ mod push {
     }
 }
 
+mod receive {
+    use gix::{config::tree::Receive, receive_pack::DenyCurrentBranch};
+
+    #[test]
+    fn deny_current_branch() -> crate::Result {
+        for (actual, expected) in [
+            ("refuse", DenyCurrentBranch::Refuse),
+            ("true", DenyCurrentBranch::Refuse),
+            ("warn", DenyCurrentBranch::Warn),
+            ("ignore", DenyCurrentBranch::Ignore),
+            ("false", DenyCurrentBranch::Ignore),
+            ("updateInstead", DenyCurrentBranch::UpdateInstead),
+        ] {
+            assert_eq!(
+                Receive::DENY_CURRENT_BRANCH.try_into_deny_current_branch(actual)?,
+                expected
+            );
+        }
+
+        assert_eq!(
+            Receive::DENY_CURRENT_BRANCH
+                .try_into_deny_current_branch("updateinstead")
+                .unwrap_err()
+                .to_string(),
+            "The key \"receive.denyCurrentBranch=updateinstead\" was invalid",
+            "case-sensitive comparisons"
+        );
+        Ok(())
+    }
+}
+
+mod gc {
+    use std::time::{Duration, SystemTime};
+
+    use gix::config::tree::Gc;
+
+    #[test]
+    fn prune_expire() -> crate::Result {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(Gc::PRUNE_EXPIRE.try_into_prune_expire("never", now)?, None);
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_prune_expire("now", now)?
+                .expect("set")
+                .seconds,
+            1_700_000_000
+        );
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_prune_expire("2.weeks.ago", now)?
+                .expect("set")
+                .seconds,
+            1_700_000_000 - 14 * 24 * 60 * 60
+        );
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_prune_expire("1700000000", now)?
+                .expect("set")
+                .seconds,
+            1_700_000_000,
+            "absolute dates work as well"
+        );
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_prune_expire("not a date", now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.pruneExpire=not a date\" was invalid"
+        );
+        Ok(())
+    }
+}
+
 mod fetch {
 
     #[test]
@@ -568,6 +641,52 @@ mod core {
         Ok(())
     }
 
+    #[test]
+    fn fsync() {
+        use gix::fs::fsync::Components;
+        for (value, expected) in [
+            ("", Components::default()),
+            ("none", Components::empty()),
+            ("all", Components::all()),
+            ("none,loose-object", Components::LOOSE_OBJECT),
+            (
+                "loose-object",
+                Components::all() - Components::INDEX - Components::REFERENCE,
+            ),
+            ("-pack", Components::PACK_METADATA | Components::COMMIT_GRAPH),
+            ("committed,-reference", Components::all() - Components::INDEX),
+            ("index, reference", Components::all() - Components::LOOSE_OBJECT),
+            ("none,added", Components::ADDED),
+            ("added,none", Components::ADDED),
+            ("pack,unknown-component", Components::default()),
+        ] {
+            assert_eq!(Core::FSYNC.try_into_fsync_components(value), expected, "{value:?}");
+            assert!(Core::FSYNC.validate(value.into()).is_ok());
+        }
+    }
+
+    #[test]
+    fn fsync_method() -> crate::Result {
+        use gix::fs::fsync::Method;
+        for (value, expected) in [
+            ("fsync", Method::Fsync),
+            ("writeout-only", Method::WriteoutOnly),
+            ("batch", Method::Batch),
+        ] {
+            assert_eq!(Core::FSYNC_METHOD.try_into_fsync_method(value)?, expected);
+            assert!(Core::FSYNC_METHOD.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Core::FSYNC_METHOD
+                .try_into_fsync_method("sync")
+                .unwrap_err()
+                .to_string(),
+            "The key \"core.fsyncMethod=sync\" was invalid"
+        );
+        assert!(Core::FSYNC_METHOD.validate("sync".into()).is_err());
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "attributes")]
     fn safecrlf() -> crate::Result {
@@ -723,6 +842,28 @@ mod extensions {
         assert!(Extensions::OBJECT_FORMAT.validate("invalid".into()).is_err());
         Ok(())
     }
+
+    #[test]
+    fn ref_storage() -> crate::Result {
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_format("files")?,
+            gix_ref::store::Format::Files
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_format("reftable")?,
+            gix_ref::store::Format::Reftable
+        );
+        assert!(Extensions::REF_STORAGE.validate("reftable".into()).is_ok());
+        assert_eq!(
+            Extensions::REF_STORAGE
+                .try_into_ref_format("invalid")
+                .unwrap_err()
+                .to_string(),
+            "The key \"extensions.refStorage=invalid\" was invalid"
+        );
+        assert!(Extensions::REF_STORAGE.validate("invalid".into()).is_err());
+        Ok(())
+    }
 }
 
 mod checkout {
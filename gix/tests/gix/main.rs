@@ -11,6 +11,8 @@ mod head;
 mod id;
 mod init;
 mod object;
+#[cfg(feature = "blocking-network-client")]
+mod receive_pack;
 mod reference;
 mod remote;
 mod repository;
@@ -20,3 +22,5 @@ mod revision;
 mod status;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(feature = "blocking-network-client")]
+mod upload_pack;
@@ -0,0 +1,239 @@
+use std::sync::{Arc, Mutex, atomic::AtomicBool};
+
+use gix::{
+    bstr::BString,
+    receive_pack::Check,
+    remote::push::{Status, Update},
+};
+use gix_testtools::tempfile::TempDir;
+
+/// Return the repository to push from, along with the directory that also contains the bare `remote.git` to push into.
+fn repo_rw() -> crate::Result<(gix::Repository, TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable("make_push_repos.sh")?;
+    let repo = gix::open_opts(tmp.path().join("local"), crate::restricted())?;
+    Ok((repo, tmp))
+}
+
+fn remote_repo(tmp: &TempDir) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(tmp.path().join("remote.git"), crate::restricted())?)
+}
+
+fn update(repo: &gix::Repository, source: Option<&str>, destination: &str, force: bool) -> crate::Result<Update> {
+    Ok(Update {
+        source: source
+            .map(|spec| repo.rev_parse_single(spec))
+            .transpose()?
+            .map(gix::Id::detach),
+        destination: destination.try_into()?,
+        force,
+    })
+}
+
+/// Push `updates` from `repo` into the remote repository in `tmp` while `check` is validating each update.
+fn push(
+    repo: &gix::Repository,
+    tmp: &TempDir,
+    updates: Vec<Update>,
+    atomic: bool,
+    check: impl FnMut(Check<'_>) -> Result<(), BString> + Send + 'static,
+) -> crate::Result<gix::remote::push::Outcome> {
+    let transport = gix::receive_pack::in_process(remote_repo(tmp)?.into_sync(), check)?;
+    Ok(repo
+        .find_remote("origin")?
+        .to_connection_with_transport(transport)
+        .prepare_push(gix::progress::Discard)?
+        .with_atomic(atomic)
+        .with_push_options(["ci.skip"])
+        .with_update_tracking_refs(false)
+        .push(updates, gix::progress::Discard, &AtomicBool::default())?)
+}
+
+fn assert_no_quarantine(remote: &gix::Repository) -> crate::Result {
+    let objects_dir = remote.objects.store_ref().path();
+    for entry in std::fs::read_dir(objects_dir)? {
+        let name = entry?.file_name();
+        assert!(
+            !name.to_string_lossy().starts_with("incoming-"),
+            "the quarantine directory {name:?} was removed"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn create_and_fast_forward() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let checks = Arc::new(Mutex::new(Vec::new()));
+    let outcome = push(
+        &repo,
+        &tmp,
+        vec![
+            update(&repo, Some("main"), "refs/heads/main", false)?,
+            update(&repo, Some("new-branch"), "refs/heads/new-branch", false)?,
+        ],
+        false,
+        {
+            let checks = checks.clone();
+            move |check| {
+                assert!(
+                    check.repo.has_object(check.command.new),
+                    "received objects are visible to the check"
+                );
+                checks
+                    .lock()
+                    .unwrap()
+                    .push((check.command.name.clone(), check.push_options.to_vec()));
+                Ok(())
+            }
+        },
+    )?;
+    assert!(outcome.is_success());
+    assert_eq!(
+        outcome.updates.iter().map(|u| u.status.clone()).collect::<Vec<_>>(),
+        [Status::Ok { forced: false }, Status::Ok { forced: false }]
+    );
+    assert_eq!(
+        *checks.lock().unwrap(),
+        [
+            ("refs/heads/main".into(), vec!["ci.skip".into()]),
+            ("refs/heads/new-branch".into(), vec!["ci.skip".into()])
+        ],
+        "push options are passed as `receive.advertisePushOptions` is set"
+    );
+
+    let remote = remote_repo(&tmp)?;
+    let main = repo.rev_parse_single("main")?;
+    assert_eq!(remote.rev_parse_single("main")?, main);
+    assert_eq!(remote.rev_parse_single("new-branch")?, main);
+    assert!(remote.rev_parse_single("main:dir/new").is_ok());
+    assert!(
+        gix_testtools::run_git(remote.git_dir(), &["fsck", "--connectivity-only"])?.success(),
+        "the received objects were moved into the object database"
+    );
+    assert_no_quarantine(&remote)?;
+    Ok(())
+}
+
+#[test]
+fn rejections_by_the_check_and_atomic_pushes() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let updates = vec![
+        update(&repo, Some("main"), "refs/heads/main", false)?,
+        update(&repo, Some("main"), "refs/heads/protected", false)?,
+    ];
+    let protect = |check: Check<'_>| {
+        if check.command.name == "refs/heads/protected" {
+            Err("protected branches can't be changed".into())
+        } else {
+            Ok(())
+        }
+    };
+    let outcome = push(&repo, &tmp, updates.clone(), true, protect)?;
+    assert_eq!(
+        outcome.updates.iter().map(|u| u.status.clone()).collect::<Vec<_>>(),
+        [
+            Status::RejectedByRemote {
+                reason: "atomic push failure".into()
+            },
+            Status::RejectedByRemote {
+                reason: "protected branches can't be changed".into()
+            }
+        ]
+    );
+    let remote = remote_repo(&tmp)?;
+    let main = repo.rev_parse_single("main")?;
+    assert_ne!(remote.rev_parse_single("main")?, main, "nothing was changed");
+    assert!(
+        !remote.has_object(main),
+        "the received objects are discarded if no reference is updated"
+    );
+    assert_no_quarantine(&remote)?;
+
+    let outcome = push(&repo, &tmp, updates, false, protect)?;
+    assert_eq!(outcome.updates[0].status, Status::Ok { forced: false });
+    assert_eq!(
+        outcome.updates[1].status,
+        Status::RejectedByRemote {
+            reason: "protected branches can't be changed".into()
+        }
+    );
+    let remote = remote_repo(&tmp)?;
+    assert_eq!(remote.rev_parse_single("main")?, main);
+    assert_ne!(remote.rev_parse_single("protected")?, main);
+    Ok(())
+}
+
+#[test]
+fn configuration_denies_deletions_and_non_fast_forwards() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let remote_dir = tmp.path().join("remote.git");
+    for key in ["receive.denyDeletes", "receive.denyNonFastForwards"] {
+        assert!(gix_testtools::run_git(&remote_dir, &["config", key, "true"])?.success());
+    }
+    let updates = vec![
+        update(&repo, Some("diverged"), "refs/heads/diverged", true)?,
+        update(&repo, None, "refs/heads/to-delete", false)?,
+        update(&repo, Some("main"), "refs/tags/v1", true)?,
+    ];
+    let outcome = push(&repo, &tmp, updates.clone(), false, |_| Ok(()))?;
+    assert_eq!(
+        outcome.updates.iter().map(|u| u.status.clone()).collect::<Vec<_>>(),
+        [
+            Status::RejectedByRemote {
+                reason: "non-fast-forward".into()
+            },
+            Status::RejectedByRemote {
+                reason: "deletion prohibited".into()
+            },
+            Status::Ok { forced: false },
+        ],
+        "only branches are protected"
+    );
+
+    for key in ["receive.denyDeletes", "receive.denyNonFastForwards"] {
+        assert!(gix_testtools::run_git(&remote_dir, &["config", key, "false"])?.success());
+    }
+    let outcome = push(&repo, &tmp, updates, false, |_| Ok(()))?;
+    assert!(outcome.is_success(), "{:?}", outcome.updates);
+    let remote = remote_repo(&tmp)?;
+    assert_eq!(remote.rev_parse_single("diverged")?, repo.rev_parse_single("diverged")?);
+    assert!(remote.try_find_reference("to-delete")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn checked_out_branches_are_protected_in_non_bare_repositories() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let push_into_self = |repo: &gix::Repository| -> crate::Result<Status> {
+        let transport = gix::receive_pack::in_process(
+            gix::open_opts(repo.workdir().expect("non-bare"), crate::restricted())?.into_sync(),
+            |_| Ok(()),
+        )?;
+        let outcome = repo
+            .find_remote("origin")?
+            .to_connection_with_transport(transport)
+            .prepare_push(gix::progress::Discard)?
+            .with_update_tracking_refs(false)
+            .push(
+                vec![update(repo, Some("diverged"), "refs/heads/main", true)?],
+                gix::progress::Discard,
+                &AtomicBool::default(),
+            )?;
+        Ok(outcome.updates[0].status.clone())
+    };
+    assert_eq!(
+        push_into_self(&repo)?,
+        Status::RejectedByRemote {
+            reason: "branch is currently checked out".into()
+        }
+    );
+
+    let workdir = tmp.path().join("local");
+    assert!(gix_testtools::run_git(&workdir, &["config", "receive.denyCurrentBranch", "ignore"])?.success());
+    assert_eq!(push_into_self(&repo)?, Status::Ok { forced: true });
+    assert_eq!(
+        gix::open_opts(workdir, crate::restricted())?.head_id()?,
+        repo.rev_parse_single("diverged")?
+    );
+    Ok(())
+}
@@ -66,6 +66,8 @@ pub(crate) fn into_daemon_remote_if_async<'repo, 'a>(
 
 mod connect;
 pub(crate) mod fetch;
+#[cfg(feature = "blocking-network-client")]
+mod push;
 mod ref_map;
 mod save;
 mod name {
@@ -0,0 +1,236 @@
+#[cfg(feature = "blocking-network-client")]
+mod blocking_io {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::remote::{
+        Direction::Push,
+        push::{Status, Update},
+    };
+
+    fn repo_rw() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_push_repos.sh")?;
+        let mut repo = gix::open_opts(tmp.path().join("local"), crate::restricted())?;
+        let remote_path = gix::path::realpath(tmp.path().join("remote.git"))?;
+        repo.config_snapshot_mut().set_subsection_value(
+            &gix::config::tree::Remote::URL,
+            "origin",
+            gix::path::into_bstr(remote_path).as_ref(),
+        )?;
+        Ok((repo, tmp))
+    }
+
+    fn remote_repo(tmp: &gix_testtools::tempfile::TempDir) -> crate::Result<gix::Repository> {
+        Ok(gix::open_opts(tmp.path().join("remote.git"), crate::restricted())?)
+    }
+
+    fn update(repo: &gix::Repository, source: Option<&str>, destination: &str, force: bool) -> crate::Result<Update> {
+        Ok(Update {
+            source: source
+                .map(|spec| repo.rev_parse_single(spec))
+                .transpose()?
+                .map(gix::Id::detach),
+            destination: destination.try_into()?,
+            force,
+        })
+    }
+
+    type Prepare<'a> = gix::remote::push::Prepare<
+        'a,
+        'a,
+        'a,
+        Box<dyn gix::protocol::transport::client::blocking_io::Transport + Send>,
+    >;
+
+    fn push(
+        repo: &gix::Repository,
+        updates: Vec<Update>,
+        configure: impl for<'a> FnOnce(Prepare<'a>) -> Prepare<'a>,
+    ) -> crate::Result<gix::remote::push::Outcome> {
+        let remote = repo.find_remote("origin")?;
+        let prepare = remote.connect(Push)?.prepare_push(gix::progress::Discard)?;
+        Ok(configure(prepare).push(updates, gix::progress::Discard, &AtomicBool::default())?)
+    }
+
+    #[test]
+    fn create_fast_forward_and_up_to_date() -> crate::Result {
+        let (repo, tmp) = repo_rw()?;
+        let outcome = push(
+            &repo,
+            vec![
+                update(&repo, Some("main"), "refs/heads/main", false)?,
+                update(&repo, Some("new-branch"), "refs/heads/new-branch", false)?,
+                update(&repo, Some("v1"), "refs/tags/v1", false)?,
+            ],
+            |p| p,
+        )?;
+        assert!(outcome.is_success());
+        assert_eq!(
+            outcome.updates.iter().map(|u| u.status.clone()).collect::<Vec<_>>(),
+            [
+                Status::Ok { forced: false },
+                Status::Ok { forced: false },
+                Status::UpToDate
+            ]
+        );
+        let report = outcome.report.expect("something was sent");
+        assert!(report.is_success());
+        assert_eq!(report.refs.len(), 2, "only what changes is sent");
+
+        let remote = remote_repo(&tmp)?;
+        let main = repo.rev_parse_single("main")?;
+        assert_eq!(remote.rev_parse_single("main")?, main);
+        assert_eq!(remote.rev_parse_single("new-branch")?, main);
+        assert!(
+            remote.rev_parse_single("main:dir/new").is_ok(),
+            "the new tree and blob were sent along with the commits"
+        );
+        assert!(
+            gix_testtools::run_git(remote.git_dir(), &["fsck", "--connectivity-only"])?.success(),
+            "the remote is consistent after receiving the pack"
+        );
+
+        assert_eq!(
+            outcome.updates[0].tracking_ref.as_ref().map(|n| n.as_bstr().to_owned()),
+            Some("refs/remotes/origin/main".into())
+        );
+        assert_eq!(
+            repo.find_reference("refs/remotes/origin/main")?.id(),
+            main,
+            "tracking refs are updated"
+        );
+        assert_eq!(repo.find_reference("refs/remotes/origin/new-branch")?.id(), main);
+        assert_eq!(outcome.updates[2].tracking_ref, None, "tags don't have tracking refs");
+
+        let outcome = push(
+            &repo,
+            vec![update(&repo, Some("main"), "refs/heads/main", false)?],
+            |p| p,
+        )?;
+        assert_eq!(outcome.updates[0].status, Status::UpToDate);
+        assert!(outcome.report.is_none(), "nothing was sent");
+        Ok(())
+    }
+
+    #[test]
+    fn non_fast_forward_is_rejected_unless_forced() -> crate::Result {
+        let (repo, tmp) = repo_rw()?;
+        let diverged = update(&repo, Some("diverged"), "refs/heads/diverged", false)?;
+        let tag = update(&repo, Some("main"), "refs/tags/v1", false)?;
+        let outcome = push(&repo, vec![diverged.clone(), tag.clone()], |p| {
+            p.with_update_tracking_refs(false)
+        })?;
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.updates[0].status, Status::RejectedNonFastForward);
+        assert_eq!(outcome.updates[1].status, Status::RejectedAlreadyExists);
+        assert!(outcome.report.is_none());
+
+        let outcome = push(
+            &repo,
+            vec![
+                Update {
+                    force: true,
+                    ..diverged
+                },
+                Update { force: true, ..tag },
+            ],
+            |p| p.with_update_tracking_refs(false),
+        )?;
+        assert!(outcome.is_success());
+        assert_eq!(outcome.updates[0].status, Status::Ok { forced: true });
+        assert_eq!(outcome.updates[1].status, Status::Ok { forced: false });
+        let remote = remote_repo(&tmp)?;
+        assert_eq!(remote.rev_parse_single("diverged")?, repo.rev_parse_single("diverged")?);
+        assert_ne!(
+            repo.find_reference("refs/remotes/origin/diverged")?.id(),
+            repo.rev_parse_single("diverged")?,
+            "tracking refs can be left untouched"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delete() -> crate::Result {
+        let (repo, tmp) = repo_rw()?;
+        assert!(repo.try_find_reference("refs/remotes/origin/to-delete")?.is_some());
+        let outcome = push(&repo, vec![update(&repo, None, "refs/heads/to-delete", false)?], |p| p)?;
+        assert!(outcome.is_success());
+        assert_eq!(outcome.updates[0].status, Status::Ok { forced: false });
+        assert!(remote_repo(&tmp)?.try_find_reference("to-delete")?.is_none());
+        assert!(
+            repo.try_find_reference("refs/remotes/origin/to-delete")?.is_none(),
+            "the tracking ref was deleted as well"
+        );
+
+        let outcome = push(&repo, vec![update(&repo, None, "refs/heads/to-delete", false)?], |p| p)?;
+        assert_eq!(outcome.updates[0].status, Status::UpToDate, "it's already gone");
+        Ok(())
+    }
+
+    #[test]
+    fn rejections_by_the_remote_and_atomic_pushes() -> crate::Result {
+        let (repo, tmp) = repo_rw()?;
+        let updates = vec![
+            update(&repo, Some("main"), "refs/heads/main", false)?,
+            update(&repo, Some("main"), "refs/heads/protected", false)?,
+        ];
+        let outcome = push(&repo, updates.clone(), |p| p.with_atomic(true))?;
+        assert!(!outcome.is_success());
+        assert!(
+            outcome
+                .updates
+                .iter()
+                .all(|u| matches!(u.status, Status::RejectedByRemote { .. })),
+            "the remote refused all updates as one was declined by a hook: {:?}",
+            outcome.updates
+        );
+        let remote = remote_repo(&tmp)?;
+        assert_ne!(
+            remote.rev_parse_single("main")?,
+            repo.rev_parse_single("main")?,
+            "nothing was changed"
+        );
+
+        let outcome = push(
+            &repo,
+            vec![
+                updates[0].clone(),
+                update(&repo, Some("diverged"), "refs/heads/diverged", false)?,
+            ],
+            |p| p.with_atomic(true),
+        )?;
+        assert_eq!(outcome.updates[0].status, Status::RejectedAtomicPushFailed);
+        assert_eq!(outcome.updates[1].status, Status::RejectedNonFastForward);
+        assert!(
+            outcome.report.is_none(),
+            "local rejections prevent atomic pushes entirely"
+        );
+
+        let outcome = push(&repo, updates, |p| p)?;
+        assert_eq!(outcome.updates[0].status, Status::Ok { forced: false });
+        assert_eq!(
+            outcome.updates[1].status,
+            Status::RejectedByRemote {
+                reason: "hook declined".into()
+            }
+        );
+        assert!(outcome.updates[1].tracking_ref.is_none());
+        assert_eq!(remote.rev_parse_single("main")?, repo.rev_parse_single("main")?);
+        Ok(())
+    }
+
+    #[test]
+    fn push_options_are_passed_to_hooks() -> crate::Result {
+        let (repo, tmp) = repo_rw()?;
+        let outcome = push(
+            &repo,
+            vec![update(&repo, Some("main"), "refs/heads/main", false)?],
+            |p| p.with_push_options(["ci.skip", "key=value"]),
+        )?;
+        assert!(outcome.is_success());
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("remote.git").join("push-options"))?,
+            "ci.skip\nkey=value\n"
+        );
+        Ok(())
+    }
+}
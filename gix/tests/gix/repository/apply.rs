@@ -0,0 +1,164 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::apply::{Error, Location, Options};
+
+use crate::util::{repo_rw, restricted};
+
+fn patches(repo: &gix::Repository, name: &str) -> crate::Result<Vec<gix::apply::Patch>> {
+    let input = std::fs::read(repo.workdir().expect("non-bare").join(name))?;
+    Ok(gix::apply::parse(&input, repo.object_hash())?)
+}
+
+fn apply(
+    repo: &gix::Repository,
+    name: &str,
+    location: Location,
+    options: Options,
+) -> crate::Result<gix::apply::Outcome> {
+    let patches = patches(repo, name)?;
+    Ok(repo.apply(&patches, location, &AtomicBool::default(), options)?)
+}
+
+fn git(dir: &std::path::Path, args: &[&str]) -> crate::Result<std::process::Output> {
+    Ok(std::process::Command::new("git").current_dir(dir).args(args).output()?)
+}
+
+/// The tree of the index as written by Git.
+fn index_tree(dir: &std::path::Path) -> crate::Result<String> {
+    let out = git(dir, &["write-tree"])?;
+    assert!(out.status.success(), "{out:?}");
+    Ok(String::from_utf8(out.stdout)?.trim_end().into())
+}
+
+fn expected_tree(dir: &std::path::Path) -> crate::Result<String> {
+    Ok(std::fs::read_to_string(dir.join("change.tree"))?.trim_end().into())
+}
+
+fn read(dir: &std::path::Path, path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(dir.join(path))
+}
+
+#[test]
+fn apply_to_tree_in_bare_repository_matches_git() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_apply_workflow_repo.sh")?;
+    let patches = patches(&repo, "change.patch")?;
+    let bare = gix::open_opts(tmp.path().join("bare.git"), restricted())?;
+    let main = bare.rev_parse_single("main")?.detach();
+
+    let outcome = bare.apply_to_tree(main, &patches, Options::default())?;
+    assert_eq!(outcome.tree.to_string(), expected_tree(tmp.path())?);
+    assert!(outcome.merged.is_empty());
+    assert!(outcome.conflicts.is_empty());
+    assert!(
+        matches!(
+            bare.apply(&patches, Location::Worktree, &AtomicBool::default(), Options::default()),
+            Err(Error::MissingWorkDir)
+        ),
+        "bare repositories have no worktree"
+    );
+    Ok(())
+}
+
+#[test]
+fn apply_to_index_and_worktree_matches_git() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_apply_workflow_repo.sh")?;
+    let outcome = apply(&repo, "change.patch", Location::IndexAndWorktree, Options::default())?;
+    assert_eq!(outcome, gix::apply::Outcome::default(), "everything applied cleanly");
+
+    assert_eq!(index_tree(tmp.path())?, expected_tree(tmp.path())?);
+    assert!(
+        git(tmp.path(), &["diff", "--quiet"])?.status.success(),
+        "the worktree matches the index"
+    );
+    assert_eq!(read(tmp.path(), "new")?, "new\n");
+    assert!(read(tmp.path(), "a")?.starts_with("1\ntwo\n"));
+    assert!(!tmp.path().join("dir/b").exists(), "renamed files are removed");
+    assert!(!tmp.path().join("gone").exists(), "deleted files are removed");
+    Ok(())
+}
+
+#[test]
+fn apply_to_index_or_worktree_only() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_apply_workflow_repo.sh")?;
+    let original = read(tmp.path(), "a")?;
+    apply(&repo, "change.patch", Location::Index, Options::default())?;
+    assert_eq!(index_tree(tmp.path())?, expected_tree(tmp.path())?);
+    assert_eq!(read(tmp.path(), "a")?, original, "the worktree isn't touched");
+    assert!(!tmp.path().join("new").exists());
+
+    let (repo, tmp) = repo_rw("make_apply_workflow_repo.sh")?;
+    apply(&repo, "change.patch", Location::Worktree, Options::default())?;
+    assert_eq!(
+        index_tree(tmp.path())?,
+        repo.rev_parse_single("main^{tree}")?.to_string(),
+        "the index isn't touched"
+    );
+    assert_eq!(read(tmp.path(), "new")?, "new\n");
+    assert_eq!(read(tmp.path(), "dir/moved")?.lines().last(), Some("31"));
+    assert!(!tmp.path().join("dir/b").exists());
+    Ok(())
+}
+
+#[test]
+fn three_way_merge_writes_conflicts_to_index_and_worktree() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_apply_workflow_repo.sh")?;
+    assert!(
+        matches!(
+            apply(&repo, "conflict.patch", Location::IndexAndWorktree, Options::default())
+                .unwrap_err()
+                .downcast_ref::<Error>(),
+            Some(Error::Apply(_))
+        ),
+        "the patch doesn't apply as the same line was changed"
+    );
+
+    let outcome = apply(
+        &repo,
+        "conflict.patch",
+        Location::IndexAndWorktree,
+        Options::default().with_three_way(true),
+    )?;
+    assert_eq!(outcome.merged, ["a"]);
+    assert_eq!(outcome.conflicts, ["a"]);
+
+    let out = git(tmp.path(), &["ls-files", "--unmerged", "a"])?;
+    let stages: Vec<_> = String::from_utf8(out.stdout)?
+        .lines()
+        .map(|line| line.split_whitespace().nth(2).expect("stage").to_owned())
+        .collect();
+    assert_eq!(
+        stages,
+        ["1", "2", "3"],
+        "base, ours and theirs are recorded in the index"
+    );
+    let content = read(tmp.path(), "a")?;
+    assert!(
+        content.contains("<<<<<<< ours\nFIVE\n=======\nfive\n>>>>>>> theirs\n"),
+        "{content}"
+    );
+    Ok(())
+}
+
+#[test]
+fn nothing_is_touched_if_preconditions_are_not_met() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_apply_workflow_repo.sh")?;
+    std::fs::write(tmp.path().join("a"), "modified\n")?;
+    assert!(matches!(
+        apply(&repo, "change.patch", Location::IndexAndWorktree, Options::default())
+            .unwrap_err()
+            .downcast_ref::<Error>(),
+        Some(Error::WorktreeDoesNotMatchIndex { path }) if path == "a"
+    ));
+
+    let (repo, tmp) = repo_rw("make_apply_workflow_repo.sh")?;
+    std::fs::write(tmp.path().join("new"), "untracked\n")?;
+    assert!(matches!(
+        apply(&repo, "change.patch", Location::Worktree, Options::default())
+            .unwrap_err()
+            .downcast_ref::<Error>(),
+        Some(Error::AlreadyExists { path, location: "worktree" }) if path == "new"
+    ));
+    assert!(tmp.path().join("dir/b").exists(), "nothing was changed");
+    assert!(tmp.path().join("gone").exists());
+    Ok(())
+}
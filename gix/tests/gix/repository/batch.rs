@@ -0,0 +1,159 @@
+use gix::{
+    batch::{Format, Mode, Options},
+    bstr::ByteSlice,
+};
+
+use crate::util::named_repo;
+
+fn expected(repo: &gix::Repository, name: &str) -> crate::Result<Vec<u8>> {
+    Ok(std::fs::read(repo.git_dir().join(name))?)
+}
+
+fn format(input: &str) -> crate::Result<Format> {
+    Ok(Format::parse(input.into())?)
+}
+
+#[test]
+fn all_objects_with_on_disk_information_match_git() -> crate::Result {
+    let repo = named_repo("make_batch_repo.sh")?;
+    let options = Options::default().with_mode(Mode::Info).with_format(format(
+        "%(objectname) %(objecttype) %(objectsize) %(objectsize:disk) %(deltabase)",
+    )?);
+
+    let mut out = Vec::new();
+    let outcome = repo.batch_all_objects(&mut out, options.clone())?;
+    assert_eq!(out.as_bstr(), expected(&repo, "all-objects-info")?.as_bstr());
+    assert_eq!(outcome.objects, 13);
+    assert_eq!(outcome.missing, 0);
+    assert!(
+        out.lines()
+            .any(|line| !line.ends_with(repo.object_hash().null().to_hex().to_string().as_bytes())),
+        "some objects are deltified"
+    );
+
+    let mut unordered = Vec::new();
+    let outcome = repo.batch_all_objects(&mut unordered, options.with_unordered(true))?;
+    assert_eq!(outcome.objects, 13, "each object is listed only once");
+    let mut lines: Vec<_> = unordered.lines().collect();
+    assert_ne!(
+        lines,
+        out.lines().collect::<Vec<_>>(),
+        "objects are listed in pack order"
+    );
+    lines.sort();
+    assert_eq!(lines, out.lines().collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn all_objects_with_contents_match_git() -> crate::Result {
+    let repo = named_repo("make_batch_repo.sh")?;
+    let mut out = Vec::new();
+    repo.batch_all_objects(&mut out, Options::default())?;
+    assert_eq!(out.as_bstr(), expected(&repo, "all-objects-contents")?.as_bstr());
+
+    let err = repo
+        .batch_all_objects(&mut out, Options::default().with_mode(Mode::Command))
+        .unwrap_err();
+    assert!(matches!(err, gix::batch::Error::CommandModeWithAllObjects));
+    Ok(())
+}
+
+#[test]
+fn rev_specs_with_contents_match_git() -> crate::Result {
+    let repo = named_repo("make_batch_repo.sh")?;
+    let file_id = repo.rev_parse_single("HEAD~2:file")?;
+    let input = format!("HEAD\nv1\nHEAD~1:file\ndoes-not-exist\n{file_id}\n");
+
+    let mut out = Vec::new();
+    let outcome = repo.batch_objects(input.as_bytes(), &mut out, Options::default())?;
+    assert_eq!(out.as_bstr(), expected(&repo, "batch-contents")?.as_bstr());
+    assert_eq!(outcome.objects, 4);
+    assert_eq!(outcome.missing, 1);
+    Ok(())
+}
+
+#[test]
+fn rest_of_line_is_retained() -> crate::Result {
+    let repo = named_repo("make_batch_repo.sh")?;
+    let mut out = Vec::new();
+    repo.batch_objects(
+        "HEAD first\nv1  second\nnope third\n".as_bytes(),
+        &mut out,
+        Options::default()
+            .with_mode(Mode::Info)
+            .with_format(format("%(objecttype) %(rest)")?),
+    )?;
+    assert_eq!(out.as_bstr(), expected(&repo, "batch-check-rest")?.as_bstr());
+    Ok(())
+}
+
+#[test]
+fn abbreviated_ids_are_resolved() -> crate::Result {
+    let repo = named_repo("make_batch_repo.sh")?;
+    let head = repo.head_id()?;
+    let mut out = Vec::new();
+    repo.batch_objects(
+        format!("{}\n", head.shorten()?).as_bytes(),
+        &mut out,
+        Options::default()
+            .with_mode(Mode::Info)
+            .with_format(format("%(objectname)")?),
+    )?;
+    assert_eq!(out, format!("{head}\n").into_bytes());
+    Ok(())
+}
+
+#[test]
+fn commands_match_git() -> crate::Result {
+    let repo = named_repo("make_batch_repo.sh")?;
+    let mut out = Vec::new();
+    repo.batch_objects(
+        "info HEAD\ncontents HEAD~1:file\ninfo does-not-exist\n".as_bytes(),
+        &mut out,
+        Options::default().with_mode(Mode::Command),
+    )?;
+    assert_eq!(out.as_bstr(), expected(&repo, "batch-command")?.as_bstr());
+
+    let mut out = Vec::new();
+    repo.batch_objects(
+        "info HEAD\nflush\n".as_bytes(),
+        &mut out,
+        Options::default().with_mode(Mode::Command).with_buffer(true),
+    )?;
+    assert_eq!(out.lines().count(), 1, "flushing is possible with buffered output");
+
+    for (input, buffer) in [("flush\n", false), ("frobnicate HEAD\n", true), ("info\n", true)] {
+        assert!(
+            repo.batch_objects(
+                input.as_bytes(),
+                Vec::new(),
+                Options::default().with_mode(Mode::Command).with_buffer(buffer)
+            )
+            .is_err(),
+            "{input:?} is invalid"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn format_parsing() -> crate::Result {
+    assert_eq!(format("%(objectname) %(objecttype) %(objectsize)")?, Format::default());
+    assert_eq!(
+        format("100%% %(rest)")?.items(),
+        &[
+            gix::batch::Item::Literal("100% ".into()),
+            gix::batch::Item::Atom(gix::batch::Atom::Rest)
+        ]
+    );
+    assert!(matches!(
+        Format::parse("%(objectname".into()),
+        Err(gix::batch::format::Error::Unterminated { position: 0 })
+    ));
+    assert!(matches!(
+        Format::parse("%(unknown)".into()),
+        Err(gix::batch::format::Error::UnknownAtom { .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    bisect::{Error, Mark, Options, Outcome},
+    bstr::ByteSlice,
+};
+
+use crate::util::{repo_rw, restricted};
+
+fn tag(repo: &gix::Repository, name: &str) -> crate::Result<gix_hash::ObjectId> {
+    Ok(repo.rev_parse_single(name)?.detach())
+}
+
+fn mark(repo: &gix::Repository, mark: Mark, commit: Option<gix_hash::ObjectId>) -> crate::Result<Outcome> {
+    Ok(repo.bisect_mark(mark, commit, &AtomicBool::default())?)
+}
+
+fn read_git_file(repo: &gix::Repository, name: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.git_dir().join(name))
+}
+
+/// Judge a commit by the content of the `state` file in its tree.
+fn test_commit(commit: gix::Id<'_>) -> crate::Result<Mark> {
+    let tree = commit.object()?.peel_to_tree()?;
+    let entry = tree.find_entry("state").expect("present");
+    Ok(if entry.object()?.data.as_bstr() == "bug\n" {
+        Mark::Bad
+    } else {
+        Mark::Good
+    })
+}
+
+#[test]
+fn run_finds_first_bad_commit_and_reset_restores_the_branch() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_bisect_workflow_repo.sh")?;
+    let main = tag(&repo, "main")?;
+    assert_eq!(repo.bisect_state()?, None);
+
+    repo.bisect_start(Options::default())?;
+    assert_eq!(repo.state(), Some(gix::state::InProgress::Bisect));
+    assert_eq!(read_git_file(&repo, "BISECT_START")?, "main\n");
+    assert_eq!(read_git_file(&repo, "BISECT_TERMS")?, "bad\ngood\n");
+    assert_eq!(read_git_file(&repo, "BISECT_NAMES")?, "\n");
+
+    assert_eq!(
+        mark(&repo, Mark::Bad, None)?,
+        Outcome::Waiting { bad: true, good: 0 },
+        "HEAD is bad by default"
+    );
+    let Outcome::Next { id, remaining, steps } = mark(&repo, Mark::Good, Some(tag(&repo, "c1")?))? else {
+        panic!("there are commits to test")
+    };
+    assert_eq!((remaining, steps), (5, 3));
+    assert_eq!(repo.head_id()?, id, "the commit to test is checked out");
+    assert!(repo.head()?.is_detached());
+    assert_eq!(read_git_file(&repo, "BISECT_EXPECTED_REV")?, format!("{id}\n"));
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("count"))?,
+        format!(
+            "{}\n",
+            repo.find_commit(id)?
+                .message()?
+                .summary()
+                .to_str_lossy()
+                .trim_start_matches('c')
+        ),
+        "the worktree matches the commit to test"
+    );
+
+    let mut tested = Vec::new();
+    let outcome = repo.bisect_run(&AtomicBool::default(), |commit| {
+        tested.push(commit.detach());
+        test_commit(commit)
+    })?;
+    let first_bad = tag(&repo, "c8")?;
+    assert_eq!(outcome, Outcome::FirstBad { id: first_bad });
+    assert_eq!(tested[0], id, "the commit that is checked out is tested first");
+    assert_eq!(tested.len(), 4, "a handful of steps suffice for 11 candidates");
+
+    let state = repo.bisect_state()?.expect("still in progress");
+    assert_eq!(state.bad, Some(first_bad));
+    assert!(state.good.contains(&tag(&repo, "c1")?));
+    assert!(!state.no_checkout);
+    let log = read_git_file(&repo, "BISECT_LOG")?;
+    assert!(log.starts_with(&format!(
+        "git bisect start\n# bad: [{main}] c12\ngit bisect bad {main}\n# good: [{c1}] c1\ngit bisect good {c1}\n",
+        c1 = tag(&repo, "c1")?
+    )));
+    assert!(
+        log.ends_with(&format!("# first bad commit: [{first_bad}] c8\n")),
+        "{log}"
+    );
+
+    repo.bisect_reset(&AtomicBool::default())?;
+    assert_eq!(repo.state(), None);
+    assert_eq!(repo.bisect_state()?, None);
+    assert_eq!(
+        repo.head_name()?.map(|name| name.as_bstr().to_string()),
+        Some("refs/heads/main".into())
+    );
+    assert_eq!(repo.references()?.prefixed(b"refs/bisect/")?.count(), 0);
+    for name in ["BISECT_LOG", "BISECT_TERMS", "BISECT_NAMES", "BISECT_EXPECTED_REV"] {
+        assert!(!repo.git_dir().join(name).exists(), "{name} was removed");
+    }
+
+    let log_path = tmp.path().join("bisect.log");
+    std::fs::write(&log_path, log)?;
+    let out = std::process::Command::new("git")
+        .current_dir(tmp.path())
+        .args(["bisect", "replay"])
+        .arg(&log_path)
+        .output()?;
+    assert!(out.status.success(), "{out:?}");
+    assert!(
+        out.stdout.contains_str(format!("{first_bad} is the first bad commit")),
+        "Git can replay the log and comes to the same conclusion"
+    );
+    Ok(())
+}
+
+#[test]
+fn bare_repositories_use_bisect_head_instead_of_checking_out() -> crate::Result {
+    let (_repo, tmp) = repo_rw("make_bisect_workflow_repo.sh")?;
+    let repo = gix::open_opts(tmp.path().join("bare.git"), restricted())?;
+    let main = tag(&repo, "main")?;
+
+    repo.bisect_start(Options::default())?;
+    assert_eq!(repo.find_reference("BISECT_HEAD")?.id(), main);
+    mark(&repo, Mark::Bad, None)?;
+    let Outcome::Next { id, .. } = mark(&repo, Mark::Good, Some(tag(&repo, "c1")?))? else {
+        panic!("there are commits to test")
+    };
+    assert_eq!(repo.find_reference("BISECT_HEAD")?.id(), id);
+    assert_eq!(repo.head_id()?, main, "HEAD isn't touched");
+    assert!(repo.bisect_state()?.expect("in progress").no_checkout);
+
+    let outcome = repo.bisect_run(&AtomicBool::default(), test_commit)?;
+    assert_eq!(outcome, Outcome::FirstBad { id: tag(&repo, "c8")? });
+
+    repo.bisect_reset(&AtomicBool::default())?;
+    assert_eq!(repo.try_find_reference("BISECT_HEAD")?.map(|r| r.id().detach()), None);
+    assert_eq!(repo.state(), None);
+    assert_eq!(repo.head_id()?, main);
+    Ok(())
+}
+
+#[test]
+fn custom_terms_skip_and_errors() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_bisect_workflow_repo.sh")?;
+    assert!(matches!(
+        repo.bisect_mark(Mark::Bad, None, &AtomicBool::default()),
+        Err(Error::NotInProgress)
+    ));
+
+    repo.bisect_start(Options::default().with_terms("new", "old"))?;
+    assert!(matches!(repo.bisect_start(Options::default()), Err(Error::InProgress)));
+    assert!(matches!(
+        repo.bisect_run(&AtomicBool::default(), test_commit),
+        Err(Error::MissingMarks)
+    ));
+    assert_eq!(read_git_file(&repo, "BISECT_TERMS")?, "new\nold\n");
+
+    let c1 = tag(&repo, "c1")?;
+    assert_eq!(
+        mark(&repo, Mark::Good, Some(c1))?,
+        Outcome::Waiting { bad: false, good: 1 }
+    );
+    let Outcome::Next { id: first, .. } = mark(&repo, Mark::Bad, None)? else {
+        panic!("there are commits to test")
+    };
+    assert!(repo.find_reference("refs/bisect/new").is_ok());
+    assert!(repo.find_reference(format!("refs/bisect/old-{c1}").as_str()).is_ok());
+
+    let Outcome::Next { id: second, .. } = mark(&repo, Mark::Skip, None)? else {
+        panic!("there are still commits to test")
+    };
+    assert_ne!(first, second, "skipped commits aren't tested again");
+    assert_eq!(repo.head_id()?, second);
+    let state = repo.bisect_state()?.expect("in progress");
+    assert_eq!(state.skipped, [first]);
+    assert_eq!(state.terms.bad, "new");
+    assert!(
+        read_git_file(&repo, "BISECT_LOG")?.contains(&format!("git bisect skip {first}\n")),
+        "skips are logged"
+    );
+    assert!(
+        read_git_file(&repo, "BISECT_LOG")?.starts_with("git bisect start '--term-bad=new' '--term-good=old'\n"),
+        "terms are recorded so the log can be replayed"
+    );
+    Ok(())
+}
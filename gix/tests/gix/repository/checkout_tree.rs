@@ -0,0 +1,272 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    checkout_tree::{Conflict, ConflictKind, Error, Options},
+    reset::Mode,
+    switch::Target,
+};
+
+use crate::util::{index_id, read_worktree_file, repo_rw};
+
+fn write(repo: &gix::Repository, rela_path: &str, content: &str) -> std::io::Result<()> {
+    std::fs::write(repo.workdir().expect("non-bare").join(rela_path), content)
+}
+
+fn blob_id_at(repo: &gix::Repository, spec: &str) -> crate::Result<gix_hash::ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+fn last_head_log(repo: &gix::Repository) -> crate::Result<gix_ref::log::Line> {
+    Ok(repo
+        .find_reference("HEAD")?
+        .log_iter()
+        .rev()?
+        .expect("present")
+        .next()
+        .expect("at least one")?)
+}
+
+#[test]
+fn switch_to_branch_updates_changed_files_and_keeps_local_changes() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_checkout_tree_repo.sh")?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let feature = repo.rev_parse_single("feature")?.detach();
+    write(&repo, "unchanged", "local change\n")?;
+
+    let out = repo.switch(
+        Target::Branch("refs/heads/feature".try_into()?),
+        &AtomicBool::default(),
+        Options::default(),
+    )?;
+    assert_eq!(out.previous, Some(main));
+    assert_eq!(out.commit, feature);
+    assert_eq!(
+        out.checkout.files_written,
+        ["added", "changed", "dir", "new/deep/file"],
+        "only files that differ between the trees are written"
+    );
+    assert_eq!(out.checkout.files_removed, ["dir/file", "removed"]);
+
+    assert_eq!(read_worktree_file(&repo, "changed")?, "b\n");
+    assert_eq!(
+        read_worktree_file(&repo, "dir")?,
+        "dir-as-file\n",
+        "the directory was replaced by a file"
+    );
+    assert_eq!(read_worktree_file(&repo, "new/deep/file")?, "deep\n");
+    assert!(!repo.workdir().expect("non-bare").join("removed").exists());
+    assert_eq!(
+        read_worktree_file(&repo, "unchanged")?,
+        "local change\n",
+        "local changes to files that don't change are kept"
+    );
+
+    assert_eq!(index_id(&repo, "changed")?, Some(blob_id_at(&repo, "feature:changed")?));
+    assert_eq!(index_id(&repo, "removed")?, None);
+    assert_eq!(index_id(&repo, "dir/file")?, None);
+
+    let head = repo.head()?;
+    assert_eq!(
+        head.referent_name().map(|name| name.as_bstr().to_string()),
+        Some("refs/heads/feature".into())
+    );
+    let log = last_head_log(&repo)?;
+    assert_eq!(log.message, "checkout: moving from main to feature");
+    assert_eq!(log.previous_oid, main);
+    assert_eq!(log.new_oid, feature);
+
+    write(&repo, "unchanged", "unchanged\n")?;
+    assert!(
+        !repo.is_dirty()?,
+        "index and worktree are in sync after undoing the local change"
+    );
+    Ok(())
+}
+
+#[test]
+fn switch_detached_points_head_to_the_commit() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_checkout_tree_repo.sh")?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let feature = repo.rev_parse_single("feature")?.detach();
+
+    repo.switch(Target::Detached(feature), &AtomicBool::default(), Options::default())?;
+    let head = repo.head()?;
+    assert!(head.is_detached());
+    assert_eq!(head.id().map(gix::Id::detach), Some(feature));
+    assert_eq!(read_worktree_file(&repo, "changed")?, "b\n");
+
+    let log = last_head_log(&repo)?;
+    assert_eq!(log.message, format!("checkout: moving from main to {feature}"));
+    assert_eq!(log.previous_oid, main, "the previous id is the one of the branch");
+
+    repo.switch(
+        Target::Branch("refs/heads/main".try_into()?),
+        &AtomicBool::default(),
+        Options::default(),
+    )?;
+    assert_eq!(read_worktree_file(&repo, "changed")?, "a\n");
+    assert_eq!(
+        read_worktree_file(&repo, "dir/file")?,
+        "d\n",
+        "the file was replaced by a directory"
+    );
+    assert!(
+        !repo.workdir().expect("non-bare").join("new").exists(),
+        "empty directories are removed"
+    );
+    assert!(!repo.is_dirty()?);
+    Ok(())
+}
+
+#[test]
+fn local_changes_and_untracked_files_prevent_switching_unless_forced() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_checkout_tree_repo.sh")?;
+    write(&repo, "changed", "local change\n")?;
+    write(&repo, "added", "untracked\n")?;
+
+    let err = repo
+        .switch(
+            Target::Branch("refs/heads/feature".try_into()?),
+            &AtomicBool::default(),
+            Options::default(),
+        )
+        .unwrap_err();
+    let gix::switch::Error::CheckoutTree(Error::Conflicts { conflicts }) = err else {
+        panic!("unexpected error: {err:?}")
+    };
+    assert_eq!(
+        conflicts,
+        [
+            Conflict {
+                rela_path: "added".into(),
+                kind: ConflictKind::Untracked
+            },
+            Conflict {
+                rela_path: "changed".into(),
+                kind: ConflictKind::LocalChanges
+            }
+        ]
+    );
+    assert_eq!(
+        read_worktree_file(&repo, "changed")?,
+        "local change\n",
+        "nothing was changed"
+    );
+    assert!(repo.workdir().expect("non-bare").join("removed").exists());
+    assert_eq!(
+        repo.head_name()?.map(|name| name.as_bstr().to_string()),
+        Some("refs/heads/main".into())
+    );
+
+    repo.switch(
+        Target::Branch("refs/heads/feature".try_into()?),
+        &AtomicBool::default(),
+        Options::default().with_force(true),
+    )?;
+    assert_eq!(read_worktree_file(&repo, "changed")?, "b\n");
+    assert_eq!(read_worktree_file(&repo, "added")?, "added\n");
+    assert!(!repo.is_dirty()?);
+    Ok(())
+}
+
+#[test]
+fn untracked_files_in_place_of_deleted_files_prevent_checkout_before_anything_is_changed() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_checkout_tree_repo.sh")?;
+    let workdir = repo.workdir().expect("non-bare");
+    std::fs::remove_file(workdir.join("changed"))?;
+    std::fs::create_dir(workdir.join("changed"))?;
+    write(&repo, "changed/untracked", "untracked\n")?;
+
+    let err = repo
+        .switch(
+            Target::Branch("refs/heads/feature".try_into()?),
+            &AtomicBool::default(),
+            Options::default(),
+        )
+        .unwrap_err();
+    let gix::switch::Error::CheckoutTree(Error::Conflicts { conflicts }) = err else {
+        panic!("unexpected error: {err:?}")
+    };
+    assert_eq!(
+        conflicts,
+        [Conflict {
+            rela_path: "changed".into(),
+            kind: ConflictKind::Untracked
+        }]
+    );
+    assert_eq!(read_worktree_file(&repo, "changed/untracked")?, "untracked\n");
+    assert_eq!(
+        read_worktree_file(&repo, "removed")?,
+        "removed\n",
+        "files that are removed by the checkout are still present"
+    );
+    assert_eq!(read_worktree_file(&repo, "dir/file")?, "d\n");
+    assert_eq!(index_id(&repo, "removed")?, Some(blob_id_at(&repo, "main:removed")?));
+    Ok(())
+}
+
+#[test]
+fn staged_changes_to_files_that_change_prevent_checkout() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_checkout_tree_repo.sh")?;
+    let mut index = repo.open_index()?;
+    let (entry, _) = index
+        .entries_mut_with_paths()
+        .find(|(_, path)| *path == "changed")
+        .expect("present");
+    entry.id = blob_id_at(&repo, "main:unchanged")?;
+    index.write(Default::default())?;
+
+    let tree = repo.rev_parse_single("feature^{tree}")?;
+    let err = repo
+        .checkout_tree(tree, &AtomicBool::default(), Options::default())
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::Conflicts { conflicts } if conflicts.len() == 1 && conflicts[0].rela_path == "changed"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn reset_moves_the_branch_and_updates_index_and_worktree_by_mode() -> crate::Result {
+    for mode in [Mode::Soft, Mode::Mixed, Mode::Hard] {
+        let (repo, _tmp) = repo_rw("make_checkout_tree_repo.sh")?;
+        let main = repo.rev_parse_single("main")?.detach();
+        let feature = repo.rev_parse_single("feature")?.detach();
+        write(&repo, "changed", "local change\n")?;
+        std::fs::write(repo.git_dir().join("MERGE_HEAD"), format!("{feature}\n"))?;
+
+        let out = repo.reset(feature, mode, &AtomicBool::default())?;
+        assert_eq!(out.previous, Some(main));
+        assert_eq!(out.checkout.is_some(), mode == Mode::Hard);
+        assert_eq!(
+            repo.find_reference("refs/heads/main")?.id(),
+            feature,
+            "{mode:?}: the branch moves"
+        );
+        assert_eq!(
+            repo.head_name()?.map(|name| name.as_bstr().to_string()),
+            Some("refs/heads/main".into()),
+            "HEAD stays on the branch"
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.git_dir().join("ORIG_HEAD"))?,
+            format!("{main}\n")
+        );
+        assert!(!repo.git_dir().join("MERGE_HEAD").exists());
+        assert_eq!(last_head_log(&repo)?.message, format!("reset: moving to {feature}"));
+
+        let expected_index_id = if mode == Mode::Soft {
+            blob_id_at(&repo, &format!("{main}:changed"))?
+        } else {
+            blob_id_at(&repo, &format!("{feature}:changed"))?
+        };
+        assert_eq!(index_id(&repo, "changed")?, Some(expected_index_id), "{mode:?}");
+        let expected_content = if mode == Mode::Hard { "b\n" } else { "local change\n" };
+        assert_eq!(read_worktree_file(&repo, "changed")?, expected_content, "{mode:?}");
+        if mode == Mode::Hard {
+            assert!(!repo.is_dirty()?);
+        }
+    }
+    Ok(())
+}
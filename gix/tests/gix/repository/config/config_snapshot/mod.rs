@@ -139,6 +139,23 @@ fn compression_levels() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn pack_window_and_depth() -> crate::Result {
+    let mut repo = named_repo("make_basic_repo.sh")?;
+    assert_eq!(
+        repo.pack_window_and_depth()?,
+        (10, 50),
+        "defaults are the same as in git"
+    );
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_value(&Pack::WINDOW, "20")?;
+    config.set_value(&Pack::DEPTH, "5")?;
+    config.commit()?;
+    assert_eq!(repo.pack_window_and_depth()?, (20, 5));
+    Ok(())
+}
+
 #[test]
 fn values_are_set_in_memory_only() {
     let mut repo = named_repo("make_config_repo.sh").unwrap();
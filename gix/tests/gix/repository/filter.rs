@@ -165,3 +165,35 @@ fn pipeline_with_autocrlf() -> crate::Result {
     }
     Ok(())
 }
+
+#[test]
+#[cfg(feature = "lfs")]
+fn pipeline_with_lfs_uses_builtin_driver_for_local_objects() -> crate::Result {
+    let (repo, _tmp) = crate::util::repo_rw("make_lfs_repo.sh")?;
+    let (mut pipe, index) = repo.filter_pipeline(None)?;
+
+    let content = "hello world\n";
+    let pointer = "version https://git-lfs.github.com/spec/v1\noid sha256:a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447\nsize 12\n";
+    {
+        let out = pipe.convert_to_git(content.as_bytes(), Path::new("file.bin"), &index)?;
+        assert_eq!(
+            out.as_bytes().expect("pointers are kept in memory").as_bstr(),
+            pointer,
+            "the content is replaced with a pointer without spawning git-lfs"
+        );
+    }
+    assert!(
+        repo.common_dir()
+            .join("lfs/objects/a9/48/a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447")
+            .is_file(),
+        "the content is stored in the local LFS store"
+    );
+
+    {
+        let mut out = pipe.convert_to_worktree(pointer.as_bytes(), "file.bin".into(), Delay::Forbid)?;
+        let mut buf = Vec::new();
+        std::io::Read::read_to_end(&mut out, &mut buf)?;
+        assert_eq!(buf.as_bstr(), content, "locally present objects are smudged directly");
+    }
+    Ok(())
+}
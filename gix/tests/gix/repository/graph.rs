@@ -0,0 +1,106 @@
+use gix::{commitgraph::write::SplitStrategy, repository::write_commit_graph::Options};
+
+#[test]
+fn write_commit_graph() -> crate::Result {
+    let (repo, _tmp) = crate::util::basic_rw_repo()?;
+    let head = repo.head_id()?;
+    let num_commits = head.ancestors().all()?.count();
+    let tree = head.object()?.into_commit().tree_id()?.detach();
+
+    let outcome = repo.write_commit_graph(Some(head), Options::default())?;
+    assert_eq!(outcome.num_commits as usize, num_commits);
+    assert_eq!(outcome.num_files, 1);
+    let graph = repo.commit_graph()?;
+    assert_eq!(graph.num_commits() as usize, num_commits);
+    let commit = graph.commit_by_id(head).expect("tip is present");
+    assert_eq!(commit.root_tree_id(), tree);
+    drop(graph);
+
+    let new_head = repo.commit("HEAD", "another commit", tree, Some(head))?;
+    let outcome = repo.write_commit_graph(
+        Some(new_head),
+        Options {
+            split: Some(SplitStrategy::NoMerge),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        (outcome.num_commits as usize, outcome.num_files),
+        (num_commits + 1, 1),
+        "the single file is turned into the first layer of the chain"
+    );
+
+    let newest = repo.commit("HEAD", "yet another commit", tree, Some(new_head))?;
+    let outcome = repo.write_commit_graph(
+        Some(newest),
+        Options {
+            split: Some(SplitStrategy::NoMerge),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        (outcome.num_commits, outcome.num_files),
+        (1, 2),
+        "only the new commit is written, into its own layer"
+    );
+    let graph = repo.commit_graph()?;
+    assert_eq!(graph.num_commits() as usize, num_commits + 2);
+    assert_eq!(
+        graph.commit_by_id(newest).expect("present").generation(),
+        graph.commit_by_id(head).expect("present").generation() + 2
+    );
+    Ok(())
+}
+
+#[test]
+fn write_commit_graph_with_changed_paths() -> crate::Result {
+    let (repo, _tmp) = crate::util::repo_rw("make_diff_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let status = std::process::Command::new("git")
+        .args([
+            "commit-graph",
+            "write",
+            "--no-progress",
+            "--changed-paths",
+            "--stdin-commits",
+        ])
+        .current_dir(repo.git_dir())
+        .env_remove("GIT_DIR")
+        .stdin({
+            let path = repo.git_dir().join("tips");
+            std::fs::write(&path, head.to_string())?;
+            std::fs::File::open(path)?
+        })
+        .status()?;
+    assert!(status.success());
+    let expected = repo.commit_graph()?;
+    assert!(expected.bloom_filter_settings().is_some());
+    drop(expected);
+    let path = repo.objects.store_ref().path().join("info/commit-graph");
+    let expected_checksum = gix::commitgraph::File::at(&path)
+        .map_err(gix::Error::from)?
+        .checksum()
+        .to_owned();
+    std::fs::remove_file(&path)?;
+
+    let outcome = repo.write_commit_graph(
+        Some(head),
+        Options {
+            changed_paths: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.checksum,
+        Some(expected_checksum),
+        "the changed paths of each commit are the same as the ones git sees"
+    );
+
+    let outcome = repo.write_commit_graph(Some(head), Options::default())?;
+    assert_eq!(
+        outcome.checksum,
+        Some(expected_checksum),
+        "filters are written if the existing commit-graph has them"
+    );
+    Ok(())
+}
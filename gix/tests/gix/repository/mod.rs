@@ -4,24 +4,43 @@ fn blob_id(repo: &Repository, data: &[u8]) -> gix_hash::ObjectId {
     gix_object::compute_hash(repo.object_hash(), gix_object::Kind::Blob, data).expect("valid object hash")
 }
 
+#[cfg(all(feature = "apply", feature = "worktree-mutation", feature = "status"))]
+mod apply;
+#[cfg(feature = "revision")]
+mod batch;
+#[cfg(all(feature = "revision", feature = "worktree-mutation", feature = "status"))]
+mod bisect;
 #[cfg(feature = "blame")]
 mod blame;
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+mod checkout_tree;
 mod config;
 #[cfg(feature = "excludes")]
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+mod graph;
 #[cfg(feature = "mailmap")]
 mod mailmap;
 #[cfg(feature = "merge")]
 mod merge;
+#[cfg(feature = "notes")]
+mod note;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "rebase")]
+mod rebase;
 mod reference;
 mod remote;
+#[cfg(feature = "maintenance")]
+mod repack;
+#[cfg(feature = "sequencer")]
+mod sequencer;
 mod shallow;
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+mod stash;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
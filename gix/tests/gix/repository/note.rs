@@ -0,0 +1,135 @@
+use gix::bstr::ByteSlice;
+
+use crate::util::{named_repo, repo_rw};
+
+fn content(note: &gix::note::Note<'_>) -> crate::Result<String> {
+    Ok(note.blob()?.data.to_str()?.to_owned())
+}
+
+#[test]
+fn find_and_list() -> crate::Result {
+    let repo = named_repo("make_notes_repo.sh")?;
+    let head = repo.head_id()?;
+    let notes = repo.notes()?;
+    assert_eq!(notes.name().as_bstr(), gix::note::DEFAULT_REF);
+
+    let note = notes.find(head)?.expect("HEAD has a note");
+    assert_eq!(note.target, head);
+    assert_eq!(note.reference.as_bstr(), "refs/notes/commits");
+    assert_eq!(content(&note)?, "note for c3\n");
+
+    let first = repo.rev_parse_single("HEAD~2")?;
+    assert_eq!(notes.find(first)?, None, "the first commit has no note");
+
+    let listed = notes.list()?;
+    assert_eq!(listed.len(), 2);
+    assert!(listed.windows(2).all(|w| w[0].target < w[1].target), "sorted by target");
+
+    let review = repo.notes_at("review")?;
+    assert_eq!(review.name().as_bstr(), "refs/notes/review");
+    assert_eq!(content(&review.find(head)?.expect("present"))?, "review of c3\n");
+
+    let missing = repo.notes_at("notes/missing")?;
+    assert_eq!(missing.name().as_bstr(), "refs/notes/missing");
+    assert_eq!(missing.commit_id()?, None);
+    assert_eq!(missing.find(head)?, None);
+    assert!(missing.list()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn commit_notes_follow_display_refs() -> crate::Result {
+    let repo = named_repo("make_notes_repo.sh")?;
+    assert_eq!(
+        repo.notes_display_refs()?
+            .iter()
+            .map(|name| name.as_bstr().to_str_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        ["refs/notes/commits", "refs/notes/review"],
+        "the default notes ref comes first, followed by the globbed `notes.displayRef`"
+    );
+
+    let head = repo.head_commit()?;
+    let notes = head.notes()?;
+    assert_eq!(
+        notes.iter().map(content).collect::<Result<Vec<_>, _>>()?,
+        ["note for c3\n", "review of c3\n"]
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_notes_ref_name() -> crate::Result {
+    let repo = named_repo("make_notes_repo.sh")?;
+    assert!(matches!(
+        repo.notes_at("with space"),
+        Err(gix::note::init::Error::InvalidName { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn add_append_copy_and_remove() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_notes_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let first = repo.rev_parse_single("HEAD~2")?.detach();
+    let notes = repo.notes()?;
+    let previous = notes.commit_id()?.expect("notes exist");
+
+    assert!(matches!(
+        notes.add(head, "replacement", false),
+        Err(gix::note::edit::Error::Exists { .. })
+    ));
+    let commit_id = notes.add(first, "new note\n", false)?;
+    let commit = commit_id.object()?.into_commit();
+    assert_eq!(commit.message_raw()?, "Notes added by 'git notes add'\n");
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [previous]);
+    assert_eq!(notes.commit_id()?, Some(commit_id));
+    assert_eq!(content(&notes.find(first)?.expect("just added"))?, "new note\n");
+    assert_eq!(notes.list()?.len(), 3);
+
+    notes.append(first, "more")?;
+    assert_eq!(content(&notes.find(first)?.expect("present"))?, "new note\n\nmore");
+
+    assert!(matches!(
+        notes.copy(first, head, false),
+        Err(gix::note::edit::Error::Exists { .. })
+    ));
+    notes.copy(first, head, true)?;
+    assert_eq!(
+        notes.find(head)?.expect("copied").id,
+        notes.find(first)?.expect("present").id
+    );
+
+    notes.remove(first)?;
+    assert_eq!(notes.find(first)?, None);
+    assert!(matches!(
+        notes.remove(first),
+        Err(gix::note::edit::Error::Missing { .. })
+    ));
+
+    let fresh = repo.notes_at("fresh")?;
+    let commit_id = fresh.append(head, "from scratch")?;
+    assert_eq!(commit_id.object()?.into_commit().parent_ids().count(), 0);
+    assert_eq!(fresh.list()?.len(), 1);
+
+    let reference = repo.find_reference("refs/notes/fresh")?;
+    let mut log = reference.log_iter();
+    let line = log.all()?.expect("reflog exists").last().expect("one entry")?;
+    assert_eq!(line.message, "notes: Notes added by 'git notes append'");
+    Ok(())
+}
+
+#[test]
+fn core_notes_ref_is_respected() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_notes_repo.sh")?;
+    repo.config_snapshot_mut()
+        .set_raw_value(gix::config::tree::Core::NOTES_REF, "review")?;
+    let notes = repo.notes()?;
+    assert_eq!(notes.name().as_bstr(), "refs/notes/review");
+    assert_eq!(
+        repo.notes_display_refs()?.first().map(|name| name.as_bstr().to_owned()),
+        Some("refs/notes/review".into())
+    );
+    Ok(())
+}
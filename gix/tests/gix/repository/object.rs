@@ -286,7 +286,33 @@ mod edit_tree {
     }
 }
 mod write_object {
-    use crate::repository::object::empty_bare_in_memory_repo;
+    use crate::repository::object::{empty_bare_in_memory_repo, empty_bare_repo};
+
+    #[test]
+    fn durably_with_core_fsync() -> crate::Result {
+        for method in ["fsync", "writeout-only", "batch"] {
+            let (tmp, _repo) = empty_bare_repo()?;
+            let repo: gix::Repository = gix::open_opts(
+                tmp.path(),
+                gix::open::Options::isolated().config_overrides([
+                    "core.fsync=all".to_string(),
+                    format!("core.fsyncMethod={method}"),
+                    "user.name=committer".into(),
+                    "user.email=committer@example.com".into(),
+                ]),
+            )?;
+            let tree_id = repo.write_object(gix::objs::Tree::empty())?;
+            let commit_id = repo.commit("refs/heads/main", "durable", tree_id, gix::commit::NO_PARENT_IDS)?;
+
+            let repo = gix::open_opts(tmp.path(), gix::open::Options::isolated())?;
+            assert_eq!(
+                repo.find_reference("refs/heads/main")?.id(),
+                commit_id,
+                "the reference and its object can be read back with {method}"
+            );
+        }
+        Ok(())
+    }
 
     #[test]
     fn empty_tree() -> crate::Result {
@@ -99,17 +99,22 @@ fn non_bare_reftable() -> crate::Result {
         }
         Err(err) => panic!("{err}"),
     };
-    assert!(
-        repo.head_id().is_err(),
-        "Trying to do anything with head will fail as we don't support reftables yet"
+    assert_eq!(repo.refs.format(), gix::refs::store::Format::Reftable);
+    let head_id = repo.head_id()?;
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/main",
+        "HEAD is read from the reftable, not from the placeholder file"
+    );
+    assert_eq!(repo.find_reference("origin/main")?.id(), head_id);
+    assert_eq!(
+        repo.head()?.log_iter().all()?.expect("reflog exists").count(),
+        1,
+        "logs are read from the reftable as well"
     );
     assert!(!repo.is_bare());
     assert_eq!(repo.kind(), gix::repository::Kind::Common);
-    assert_ne!(
-        repo.workdir(),
-        None,
-        "Otherwise it can be used, but it's hard to do without refs"
-    );
+    assert_ne!(repo.workdir(), None);
     Ok(())
 }
 
@@ -0,0 +1,174 @@
+use gix::rebase::{Empty, Onto};
+
+use crate::util::{named_repo, repo_rw, tree_of};
+
+fn options(repo: &gix::Repository) -> crate::Result<gix::rebase::Options> {
+    Ok(repo.tree_merge_options()?.into())
+}
+
+#[test]
+fn clean_rebase_updates_the_branch() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_rebase_repo.sh")?;
+    let main = repo.rev_parse_single("main")?;
+    let topic = "refs/heads/topic".try_into()?;
+    let orig_head = repo.rev_parse_single("topic")?.detach();
+
+    let out = repo.rebase(main, topic, options(&repo)?)?;
+    assert!(out.stopped.is_none());
+    assert_eq!(out.onto, main);
+    assert_eq!(out.steps.len(), 3);
+    assert_eq!(
+        out.steps.iter().filter(|step| step.rewritten.is_none()).count(),
+        1,
+        "the commit that adds the shared file is already upstream, and was dropped"
+    );
+    assert_eq!(repo.rev_parse_single("topic")?, out.head, "the branch was updated");
+    assert_eq!(
+        tree_of(&repo, "topic")?,
+        tree_of(&repo, "rebased-by-git")?,
+        "the result is the same as the one of Git"
+    );
+    assert_eq!(
+        out.head
+            .object()?
+            .peel_to_commit()?
+            .parent_ids()
+            .next()
+            .map(gix::Id::detach),
+        out.steps[0].rewritten,
+        "the empty commit is skipped"
+    );
+
+    let log = repo
+        .find_reference("topic")?
+        .log_iter()
+        .rev()?
+        .expect("present")
+        .next()
+        .expect("one")?;
+    assert_eq!(log.previous_oid, orig_head);
+    assert_eq!(log.message, format!("rebase (finish): refs/heads/topic onto {main}"));
+    assert_eq!(repo.rebase_state()?, None, "no state is written on success");
+    Ok(())
+}
+
+#[test]
+fn onto_and_keep_base() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_rebase_repo.sh")?;
+    let topic = "refs/heads/topic".try_into()?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let base = repo.rev_parse_single("base")?.detach();
+    let orig_tree = tree_of(&repo, "topic")?;
+
+    let out = repo.rebase(main, topic, options(&repo)?.with_onto(Onto::KeepBase))?;
+    assert_eq!(out.onto, base, "the base is the merge-base of upstream and the branch");
+    assert_eq!(out.steps.len(), 3);
+    assert!(out.steps.iter().all(|step| step.rewritten.is_some()));
+    assert_eq!(tree_of(&repo, "topic")?, orig_tree, "nothing changed");
+
+    let out = repo.rebase(
+        base,
+        topic,
+        options(&repo)?.with_onto(Onto::Commit(main)).with_empty(Empty::Keep),
+    )?;
+    assert!(out.stopped.is_none());
+    assert_eq!(out.steps.len(), 3);
+    assert!(
+        out.steps.iter().all(|step| step.rewritten.is_some()),
+        "empty commits are kept"
+    );
+    assert_eq!(tree_of(&repo, "topic")?, tree_of(&repo, "rebased-by-git")?);
+    Ok(())
+}
+
+#[test]
+fn stop_at_conflict_without_state() -> crate::Result {
+    let repo = named_repo("make_rebase_repo.sh")?.with_object_memory();
+    let conflicting = repo.rev_parse_single("conflicting")?;
+    let out = repo.rebase(conflicting, "refs/heads/topic".try_into()?, options(&repo)?)?;
+
+    let stopped = out.stopped.expect("the last commit conflicts");
+    assert_eq!(stopped.commit, repo.rev_parse_single("topic")?);
+    assert!(stopped.remaining.is_empty());
+    assert_eq!(stopped.tree_merge.conflicts.len(), 1);
+    assert!(stopped.tree_merge.has_unresolved_conflicts(Default::default()));
+    assert_eq!(out.steps.len(), 2);
+    assert_ne!(out.head, out.onto, "one commit was picked");
+    assert_eq!(
+        repo.rev_parse_single("topic")?,
+        stopped.commit,
+        "the branch wasn't changed"
+    );
+    assert_eq!(repo.rebase_state()?, None);
+    Ok(())
+}
+
+#[test]
+fn stop_at_conflict_with_state_to_continue_with_git() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_rebase_repo.sh")?;
+    let conflicting = repo.rev_parse_single("conflicting")?.detach();
+    let topic = "refs/heads/topic".try_into()?;
+    let orig_head = repo.rev_parse_single("topic")?.detach();
+    let out = repo.rebase(conflicting, topic, options(&repo)?.with_write_state(true))?;
+    let stopped = out.stopped.expect("conflict");
+
+    let state = repo.rebase_state()?.expect("written");
+    assert_eq!(
+        state.head_name.as_ref().map(ToString::to_string).as_deref(),
+        Some("refs/heads/topic")
+    );
+    assert_eq!(state.onto, conflicting);
+    assert_eq!(state.orig_head, orig_head);
+    assert_eq!(state.done.len(), 3, "all commits, including the one we stopped at");
+    assert!(state.todo.is_empty());
+    let state_stopped = state.stopped.expect("stopped");
+    assert_eq!(state_stopped.commit, stopped.commit);
+    assert_eq!(state_stopped.message, "change five\n");
+    assert_eq!(repo.state(), Some(gix::state::InProgress::RebaseInteractive));
+    assert_eq!(
+        repo.head_id()?,
+        out.head,
+        "HEAD is detached at the last replayed commit"
+    );
+    assert!(repo.head()?.is_detached());
+    assert_eq!(repo.rev_parse_single("topic")?, orig_head, "the branch is unchanged");
+
+    assert!(
+        matches!(
+            repo.rebase(conflicting, topic, options(&repo)?.with_write_state(true)),
+            Err(gix::rebase::Error::InProgress)
+        ),
+        "rebases that persist their state can't be started while one is in progress"
+    );
+
+    let git = |args: &[&str]| -> crate::Result {
+        let status = std::process::Command::new("git")
+            .current_dir(tmp.path())
+            .env("GIT_EDITOR", "true")
+            .env("GIT_AUTHOR_NAME", "author")
+            .env("GIT_AUTHOR_EMAIL", "author@example.com")
+            .env("GIT_COMMITTER_NAME", "committer")
+            .env("GIT_COMMITTER_EMAIL", "committer@example.com")
+            .args(args)
+            .stdout(std::process::Stdio::null())
+            .status()?;
+        assert!(status.success(), "git {args:?} failed");
+        Ok(())
+    };
+    git(&["reset", "-q", "--hard", "HEAD"])?;
+    git(&["checkout", "REBASE_HEAD", "--", "numbers"])?;
+    git(&["add", "numbers"])?;
+    git(&["rebase", "--continue"])?;
+
+    let repo = gix::open_opts(repo.git_dir(), crate::util::restricted())?;
+    let tip = repo.rev_parse_single("topic")?.object()?.peel_to_commit()?;
+    assert_eq!(tip.message_raw()?, "change five\n");
+    assert_eq!(
+        tip.parent_ids().next().map(gix::Id::detach),
+        Some(out.head.detach()),
+        "Git continued where we stopped"
+    );
+    assert_eq!(repo.rebase_state()?, None, "the rebase is done");
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/topic");
+    Ok(())
+}
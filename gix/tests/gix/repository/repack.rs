@@ -0,0 +1,229 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix::{config::tree::Gc, prelude::ObjectIdExt};
+use gix_features::progress::Discard;
+
+use crate::{repository::blob_id, util::repo_rw};
+
+fn reachable_according_to_git(repo: &gix::Repository) -> crate::Result<Vec<gix_hash::ObjectId>> {
+    let list = std::fs::read_to_string(repo.git_dir().join("reachable"))?;
+    Ok(list
+        .lines()
+        .map(|hex| gix_hash::ObjectId::from_hex(hex.as_bytes()))
+        .collect::<Result<_, _>>()?)
+}
+
+fn pack_indices(repo: &gix::Repository) -> crate::Result<Vec<PathBuf>> {
+    let mut indices = Vec::new();
+    for entry in std::fs::read_dir(repo.objects.store_ref().path().join("pack"))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "idx") {
+            indices.push(path);
+        }
+    }
+    indices.sort();
+    Ok(indices)
+}
+
+fn loose_objects(repo: &gix::Repository) -> crate::Result<Vec<gix_hash::ObjectId>> {
+    let store = gix::odb::loose::Store::at(
+        repo.objects.store_ref().path(),
+        gix::odb::loose::Options {
+            object_hash: repo.object_hash(),
+            ..Default::default()
+        },
+    );
+    let mut ids = store.iter().collect::<Result<Vec<_>, _>>()?;
+    ids.sort();
+    Ok(ids)
+}
+
+fn kept_index(repo: &gix::Repository) -> crate::Result<PathBuf> {
+    Ok(pack_indices(repo)?
+        .into_iter()
+        .find(|idx| idx.with_extension("keep").is_file())
+        .expect("the fixture has a kept pack"))
+}
+
+fn assert_all_reachable_objects_exist(repo: &gix::Repository) -> crate::Result {
+    let repo = gix::open_opts(repo.git_dir(), crate::util::restricted())?;
+    for id in reachable_according_to_git(&repo)? {
+        assert!(
+            id.attach(&repo).object().is_ok(),
+            "{id} is reachable and must still be readable"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn all_reachable_objects_end_up_in_a_single_pack_next_to_kept_packs() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let kept = kept_index(&repo)?;
+    assert_eq!(
+        pack_indices(&repo)?.len(),
+        4,
+        "three packs to consolidate, and one kept pack"
+    );
+    let unreachable_loose = blob_id(&repo, b"unreachable-loose\n");
+    let unreachable_packed = blob_id(&repo, b"unreachable-packed\n");
+    assert_eq!(
+        loose_objects(&repo)?.len(),
+        8,
+        "reachable and unreachable loose objects"
+    );
+
+    let outcome = repo.repack(&mut Discard, &AtomicBool::default(), Default::default())?;
+    let index_path = outcome.index_path.clone().expect("there was something to pack");
+    assert_eq!(outcome.removed_packs.len(), 3, "all but the kept pack are obsolete");
+    assert_eq!(
+        outcome.removed_loose_objects, 7,
+        "all reachable loose objects are now packed"
+    );
+    assert_eq!(
+        outcome.unpacked_objects, 0,
+        "unreachable objects aren't preserved by default"
+    );
+
+    let mut expected_packs = vec![kept.clone(), index_path.clone()];
+    expected_packs.sort();
+    assert_eq!(pack_indices(&repo)?, expected_packs);
+    assert!(
+        !index_path.with_extension("bitmap").exists(),
+        "bitmaps aren't written by default"
+    );
+
+    let kept = gix::odb::pack::index::File::at(&kept, repo.object_hash())?;
+    assert_eq!(
+        outcome.num_objects + kept.num_objects() as usize,
+        reachable_according_to_git(&repo)?.len(),
+        "objects in the kept pack aren't duplicated"
+    );
+    assert_all_reachable_objects_exist(&repo)?;
+
+    let repo = gix::open_opts(repo.git_dir(), crate::util::restricted())?;
+    assert!(
+        !repo.has_object(unreachable_packed),
+        "unreachable packed objects are dropped"
+    );
+    assert_eq!(
+        loose_objects(&repo)?,
+        [unreachable_loose],
+        "unreachable loose objects are left for pruning"
+    );
+
+    let outcome = repo.repack(&mut Discard, &AtomicBool::default(), Default::default())?;
+    assert_eq!(
+        outcome.index_path,
+        Some(index_path),
+        "repacking again yields the same pack"
+    );
+    assert!(outcome.removed_packs.is_empty(), "the new pack isn't removed");
+    assert_eq!(outcome.removed_loose_objects, 0);
+    assert_eq!(pack_indices(&repo)?, expected_packs);
+    assert_all_reachable_objects_exist(&repo)?;
+    Ok(())
+}
+
+#[test]
+fn gc_keeps_recent_unreachable_objects_by_default() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let unreachable_loose = blob_id(&repo, b"unreachable-loose\n");
+    let unreachable_packed = blob_id(&repo, b"unreachable-packed\n");
+
+    let outcome = repo.gc(&mut Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.pruned_objects, 0, "nothing is older than two weeks");
+    assert_eq!(
+        outcome.repack.unpacked_objects, 1,
+        "the unreachable object of a removed pack is written as loose object to expire later"
+    );
+    assert_all_reachable_objects_exist(&repo)?;
+
+    let mut expected = vec![unreachable_loose, unreachable_packed];
+    expected.sort();
+    assert_eq!(loose_objects(&repo)?, expected);
+    Ok(())
+}
+
+#[test]
+fn gc_prunes_unreachable_objects_if_they_expire_now() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    repo.config_snapshot_mut().set_raw_value(Gc::PRUNE_EXPIRE, "now")?;
+    repo.config_snapshot_mut()
+        .set_raw_value(gix::config::tree::Repack::WRITE_BITMAPS, "true")?;
+    std::fs::remove_file(kept_index(&repo)?.with_extension("keep"))?;
+
+    let outcome = repo.gc(&mut Discard, &AtomicBool::default())?;
+    assert_eq!(
+        outcome.repack.unpacked_objects, 0,
+        "unreachable objects expire right away"
+    );
+    assert_eq!(outcome.pruned_objects, 1, "the unreachable loose object is pruned");
+    assert!(
+        outcome
+            .repack
+            .index_path
+            .expect("written")
+            .with_extension("bitmap")
+            .is_file(),
+        "repack.writeBitmaps is respected, and bitmaps can be written as the pack is self-contained"
+    );
+    assert_all_reachable_objects_exist(&repo)?;
+    assert!(
+        loose_objects(&repo)?.is_empty(),
+        "all loose objects are packed or pruned"
+    );
+    assert_eq!(
+        pack_indices(&repo)?.len(),
+        1,
+        "without kept packs, only one pack remains"
+    );
+    Ok(())
+}
+
+#[test]
+fn gc_never_prunes_if_configured() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    repo.config_snapshot_mut().set_raw_value(Gc::PRUNE_EXPIRE, "never")?;
+
+    let outcome = repo.gc(&mut Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.pruned_objects, 0);
+    assert_eq!(
+        outcome.repack.unpacked_objects, 1,
+        "unreachable objects are kept no matter how old their pack is"
+    );
+    assert_eq!(loose_objects(&repo)?.len(), 2);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "attributes")]
+fn blobs_with_unset_delta_attribute_are_stored_as_base_objects() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_no_delta_repo.sh")?;
+    let outcome = repo.repack(&mut Discard, &AtomicBool::default(), Default::default())?;
+    let index_path = outcome.index_path.expect("there was something to pack");
+    let index = gix::odb::pack::index::File::at(&index_path, repo.object_hash())?;
+    let pack = gix::odb::pack::data::File::at(index_path.with_extension("pack"), repo.object_hash())?;
+    let is_delta = |content: Vec<u8>| -> crate::Result<bool> {
+        let entry = index
+            .lookup(blob_id(&repo, &content))
+            .expect("all blobs are in the new pack");
+        Ok(pack.entry(index.pack_offset_at_index(entry))?.header.is_delta())
+    };
+
+    assert!(
+        is_delta(lines(1..=2000))? || is_delta(lines(1..=2001))?,
+        "similar blobs are delta-compressed by default"
+    );
+    for content in [lines(10001..=12000), lines(10001..=12001)] {
+        assert!(
+            !is_delta(content)?,
+            "'*.bin -delta' in .gitattributes makes these base objects"
+        );
+    }
+    Ok(())
+}
+
+fn lines(range: std::ops::RangeInclusive<u32>) -> Vec<u8> {
+    range.flat_map(|n| format!("{n}\n").into_bytes()).collect()
+}
@@ -0,0 +1,342 @@
+use gix::sequencer::Action;
+
+use crate::util::{repo_rw, restricted, tree_of};
+
+fn message_of(repo: &gix::Repository, spec: &str) -> crate::Result<String> {
+    Ok(repo
+        .rev_parse_single(spec)?
+        .object()?
+        .peel_to_commit()?
+        .message_raw()?
+        .to_string())
+}
+
+fn options(repo: &gix::Repository) -> crate::Result<gix::sequencer::Options> {
+    Ok(repo.tree_merge_options()?.into())
+}
+
+fn read(repo: &gix::Repository, name: &str) -> crate::Result<String> {
+    Ok(std::fs::read_to_string(repo.git_dir().join(name))?)
+}
+
+fn git(dir: &std::path::Path, args: &[&str]) -> crate::Result {
+    let status = std::process::Command::new("git")
+        .current_dir(dir)
+        .env("GIT_EDITOR", "true")
+        .env("GIT_AUTHOR_NAME", "author")
+        .env("GIT_AUTHOR_EMAIL", "author@example.com")
+        .env("GIT_COMMITTER_NAME", "committer")
+        .env("GIT_COMMITTER_EMAIL", "committer@example.com")
+        .args(args)
+        .stdout(std::process::Stdio::null())
+        .status()?;
+    assert!(status.success(), "git {args:?} failed");
+    Ok(())
+}
+
+#[test]
+fn clean_cherry_pick_and_revert_match_git() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let add_b = repo.rev_parse_single("topic~2")?.detach();
+
+    let out = repo.cherry_pick(Some(add_b), options(&repo)?)?;
+    assert!(out.stopped.is_none());
+    assert_eq!(out.steps.len(), 1);
+    assert_eq!(out.steps[0].commit, add_b);
+    assert_eq!(Some(out.head.detach()), out.steps[0].new);
+    assert_eq!(
+        repo.rev_parse_single("main")?,
+        out.head,
+        "the branch HEAD points to moved"
+    );
+    assert_eq!(tree_of(&repo, "main")?, tree_of(&repo, "picked-by-git")?);
+    assert_eq!(message_of(&repo, "main")?, message_of(&repo, "picked-by-git")?);
+    assert_eq!(read(&repo, "ORIG_HEAD")?, format!("{main}\n"));
+
+    let log = repo
+        .find_reference("main")?
+        .log_iter()
+        .rev()?
+        .expect("present")
+        .next()
+        .expect("one")?;
+    assert_eq!(log.previous_oid, main);
+    assert_eq!(log.message, "cherry-pick: add b");
+
+    let out = repo.revert(Some(out.head.detach()), options(&repo)?)?;
+    assert!(out.stopped.is_none());
+    assert_eq!(
+        tree_of(&repo, "main")?,
+        tree_of(&repo, &main.to_string())?,
+        "back to where we started"
+    );
+    assert_eq!(
+        message_of(&repo, "main")?,
+        format!("Revert \"add b\"\n\nThis reverts commit {}.\n", out.steps[0].commit)
+    );
+
+    repo.revert(Some(repo.rev_parse_single("main~3")?.detach()), options(&repo)?)?;
+    assert_eq!(tree_of(&repo, "main")?, tree_of(&repo, "reverted-by-git")?);
+    assert_eq!(repo.sequencer_state()?, None, "no state is written on success");
+    Ok(())
+}
+
+#[test]
+fn stop_at_conflict_to_continue_with_git() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let commits = ["topic~2", "topic~1", "topic"]
+        .into_iter()
+        .map(|spec| Ok(repo.rev_parse_single(spec)?.detach()))
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    let out = repo.cherry_pick(commits.iter().copied(), options(&repo)?.with_record_origin(true))?;
+    let stopped = out.stopped.expect("the second commit conflicts");
+    assert_eq!(stopped.commit, commits[1]);
+    assert_eq!(stopped.action, Action::Pick);
+    assert_eq!(stopped.remaining, [commits[2]]);
+    let tree_merge = stopped.tree_merge.expect("conflict");
+    assert!(tree_merge.has_unresolved_conflicts(Default::default()));
+    assert_eq!(out.steps.len(), 1);
+    assert_eq!(repo.head_id()?, out.head, "HEAD points to the last picked commit");
+
+    let state = repo.sequencer_state()?.expect("written");
+    assert_eq!(state.head, main);
+    assert_eq!(state.abort_safety, Some(out.head.detach()));
+    assert_eq!(
+        state.todo.iter().map(|line| line.id).collect::<Vec<_>>(),
+        commits[1..],
+        "the todo list includes the commit we stopped at"
+    );
+    assert!(state.options.record_origin);
+    assert_eq!(read(&repo, "CHERRY_PICK_HEAD")?, format!("{}\n", commits[1]));
+    assert_eq!(
+        read(&repo, "MERGE_MSG")?,
+        format!(
+            "change five\n\n(cherry picked from commit {})\n\n# Conflicts:\n#\tnumbers\n",
+            commits[1]
+        )
+    );
+    assert_eq!(read(&repo, "ORIG_HEAD")?, format!("{main}\n"));
+    assert_eq!(repo.state(), Some(gix::state::InProgress::CherryPickSequence));
+    assert!(
+        matches!(
+            repo.cherry_pick(Some(commits[0]), options(&repo)?),
+            Err(gix::sequencer::Error::InProgress)
+        ),
+        "sequences can't be started while one is in progress"
+    );
+
+    git(tmp.path(), &["read-tree", "-u", "--reset", "HEAD"])?;
+    git(tmp.path(), &["checkout", "CHERRY_PICK_HEAD", "--", "numbers"])?;
+    git(tmp.path(), &["add", "numbers"])?;
+    git(tmp.path(), &["cherry-pick", "--continue"])?;
+
+    let repo = gix::open_opts(repo.git_dir(), restricted())?;
+    assert_eq!(
+        message_of(&repo, "main")?,
+        format!("add new file\n\n(cherry picked from commit {})\n", commits[2]),
+        "Git picked the remaining commit with our options"
+    );
+    assert_eq!(
+        message_of(&repo, "main~1")?,
+        format!("change five\n\n(cherry picked from commit {})\n", commits[1])
+    );
+    assert_eq!(repo.rev_parse_single("main~2")?, out.head);
+    assert_eq!(repo.sequencer_state()?, None, "the sequence is done");
+    Ok(())
+}
+
+#[test]
+fn skip_abort_and_continue() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let commits = ["topic~2", "topic~1", "topic"]
+        .into_iter()
+        .map(|spec| Ok(repo.rev_parse_single(spec)?.detach()))
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    let out = repo.cherry_pick(commits[1..].iter().copied(), options(&repo)?)?;
+    assert_eq!(out.stopped.expect("conflict").commit, commits[1]);
+    let out = repo.sequencer_skip(repo.tree_merge_options()?, Default::default())?;
+    assert!(out.stopped.is_none());
+    assert_eq!(out.steps.len(), 1, "only the remaining commit was picked");
+    assert_eq!(out.steps[0].commit, commits[2]);
+    assert_eq!(repo.rev_parse_single("main~1")?, main);
+    assert_eq!(repo.sequencer_state()?, None);
+    assert!(!repo.git_dir().join("CHERRY_PICK_HEAD").exists());
+    assert!(!repo.git_dir().join("MERGE_MSG").exists());
+
+    let head = repo.head_id()?.detach();
+    let out = repo.cherry_pick(commits[..2].iter().copied(), options(&repo)?)?;
+    assert_eq!(out.stopped.expect("conflict").commit, commits[1]);
+    assert_ne!(repo.head_id()?, head, "one commit was picked");
+    repo.sequencer_abort()?;
+    assert_eq!(repo.head_id()?, head, "HEAD is back where it was");
+    assert_eq!(repo.sequencer_state()?, None);
+    assert!(matches!(
+        repo.sequencer_abort(),
+        Err(gix::sequencer::abort::Error::NotInProgress)
+    ));
+
+    let out = repo.revert(commits[1..2].iter().copied(), options(&repo)?)?;
+    let stopped = out.stopped.expect("conflict");
+    assert_eq!(stopped.action, Action::Revert);
+    assert_eq!(read(&repo, "REVERT_HEAD")?, format!("{}\n", commits[1]));
+    assert_eq!(repo.state(), Some(gix::state::InProgress::RevertSequence));
+    assert!(matches!(
+        repo.sequencer_continue(None, repo.tree_merge_options()?, Default::default()),
+        Err(gix::sequencer::Error::ResolutionMissing { commit }) if commit == commits[1]
+    ));
+    let resolved = tree_of(&repo, "HEAD")?;
+    let out = repo.sequencer_continue(Some(resolved), repo.tree_merge_options()?, Default::default())?;
+    assert!(out.stopped.is_none());
+    assert!(out.steps.is_empty(), "there was nothing left to revert");
+    assert_eq!(repo.rev_parse_single("main~1")?, head);
+    assert_eq!(tree_of(&repo, "main")?, resolved);
+    assert_eq!(
+        message_of(&repo, "main")?,
+        format!("Revert \"change five\"\n\nThis reverts commit {}.\n", commits[1])
+    );
+    assert_eq!(repo.sequencer_state()?, None);
+    assert!(!repo.git_dir().join("REVERT_HEAD").exists());
+    assert!(matches!(
+        repo.sequencer_continue(None, repo.tree_merge_options()?, Default::default()),
+        Err(gix::sequencer::Error::NotInProgress)
+    ));
+    Ok(())
+}
+
+#[test]
+fn continue_and_skip_use_the_given_treat_as_unresolved() -> crate::Result {
+    use gix::merge::tree::{FileFavor, TreatAsUnresolved};
+
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let change_five = repo.rev_parse_single("topic~1")?.detach();
+    let mut tree = repo.edit_tree(tree_of(&repo, "topic~1")?)?;
+    tree.upsert("extra", gix::object::tree::EntryKind::Blob, repo.write_blob("extra\n")?)?;
+    let tree = tree.write()?.detach();
+    let change_five_and_add_extra = repo
+        .new_commit(
+            "change five and add extra",
+            tree,
+            Some(repo.rev_parse_single("topic~2")?),
+        )?
+        .id;
+    let tree_merge = repo.tree_merge_options()?.with_file_favor(Some(FileFavor::Ours));
+    let forced = TreatAsUnresolved::forced_resolution();
+
+    for skip in [false, true] {
+        let out = repo.cherry_pick(
+            [change_five, change_five_and_add_extra],
+            gix::sequencer::Options::from(tree_merge.clone()).with_treat_as_unresolved(forced),
+        )?;
+        let stopped = out.stopped.expect("conflicts resolved by favoring ours are unresolved");
+        assert_eq!(stopped.remaining, [change_five_and_add_extra]);
+
+        let out = if skip {
+            repo.sequencer_skip(tree_merge.clone(), forced)?
+        } else {
+            repo.sequencer_continue(Some(tree_of(&repo, "HEAD")?), tree_merge.clone(), forced)?
+        };
+        let stopped = out
+            .stopped
+            .expect("the next commit stops as well instead of being resolved by favoring ours");
+        assert_eq!(stopped.commit, change_five_and_add_extra);
+        assert!(out.steps.is_empty());
+        repo.sequencer_abort()?;
+
+        repo.cherry_pick(
+            [change_five, change_five_and_add_extra],
+            gix::sequencer::Options::from(tree_merge.clone()).with_treat_as_unresolved(forced),
+        )?;
+        let out = if skip {
+            repo.sequencer_skip(tree_merge.clone(), TreatAsUnresolved::git())?
+        } else {
+            repo.sequencer_continue(
+                Some(tree_of(&repo, "HEAD")?),
+                tree_merge.clone(),
+                TreatAsUnresolved::git(),
+            )?
+        };
+        assert!(
+            out.stopped.is_none(),
+            "with Git's settings, favoring ours resolves the conflict"
+        );
+        assert_eq!(out.steps.len(), 1);
+        assert_eq!(out.steps[0].commit, change_five_and_add_extra);
+        assert!(out.steps[0].new.is_some());
+        repo.reference(
+            "refs/heads/main",
+            main,
+            gix::refs::transaction::PreviousValue::Any,
+            "reset",
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn abort_refuses_to_lose_commits() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let out = repo.cherry_pick(
+        [
+            repo.rev_parse_single("topic~2")?.detach(),
+            repo.rev_parse_single("topic~1")?.detach(),
+        ],
+        options(&repo)?,
+    )?;
+    assert!(out.stopped.is_some());
+    let head = out.head.detach();
+    let main = repo.rev_parse_single("main~1")?.detach();
+    repo.reference(
+        "refs/heads/main",
+        main,
+        gix::refs::transaction::PreviousValue::Any,
+        "moved",
+    )?;
+
+    assert!(matches!(
+        repo.sequencer_abort(),
+        Err(gix::sequencer::abort::Error::HeadMoved { expected, actual }) if expected == head && actual == main
+    ));
+    repo.sequencer_quit()?;
+    assert_eq!(repo.sequencer_state()?, None);
+    assert_eq!(repo.head_id()?, main, "quitting doesn't touch HEAD");
+    Ok(())
+}
+
+#[test]
+fn continue_a_sequence_stopped_by_git() -> crate::Result {
+    let (_repo, tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let repo = gix::open_opts(tmp.path().join("git-stopped"), restricted())?;
+    let state = repo.sequencer_state()?.expect("written by git");
+    let topic = repo.rev_parse_single("origin/topic")?.detach();
+    let change_five = repo.rev_parse_single("origin/topic~1")?.detach();
+    assert_eq!(state.todo.len(), 2);
+    assert_eq!(state.todo[0].id, change_five, "abbreviated ids are expanded");
+    assert_eq!(state.todo[1].id, topic);
+    assert_eq!(state.action(), Some(Action::Pick));
+    assert!(state.options.record_origin);
+
+    let out = repo.sequencer_continue(
+        Some(tree_of(&repo, "origin/topic~1")?),
+        repo.tree_merge_options()?,
+        Default::default(),
+    )?;
+    assert!(out.stopped.is_none());
+    assert_eq!(out.steps.len(), 1);
+    assert_eq!(
+        message_of(&repo, "main~1")?,
+        format!("change five\n\n(cherry picked from commit {change_five})\n"),
+        "the options stored by Git are used"
+    );
+    assert_eq!(
+        message_of(&repo, "main")?,
+        format!("add new file\n\n(cherry picked from commit {topic})\n")
+    );
+    assert_eq!(repo.sequencer_state()?, None);
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    bstr::ByteSlice,
+    stash::{apply, save},
+};
+
+use crate::util::{index_id, read_worktree_file, repo_rw};
+
+fn exists(repo: &gix::Repository, rela_path: &str) -> bool {
+    repo.workdir().expect("non-bare").join(rela_path).exists()
+}
+
+fn save(repo: &gix::Repository, options: save::Options) -> crate::Result<Option<gix_hash::ObjectId>> {
+    Ok(repo.stash_save(&AtomicBool::default(), options)?.map(gix::Id::detach))
+}
+
+fn apply_options(repo: &gix::Repository) -> crate::Result<apply::Options> {
+    Ok(repo.tree_merge_options()?.into())
+}
+
+#[test]
+fn save_records_index_and_worktree_and_pop_restores_them() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let head_b = repo.rev_parse_single("HEAD:b")?.detach();
+
+    let id = save(&repo, save::Options::default())?.expect("there are changes");
+    let stash = repo.find_commit(id)?;
+    assert_eq!(
+        stash.message_raw()?,
+        format!("WIP on main: {} first", head.to_hex_with_len(7)).as_str()
+    );
+    let parents: Vec<_> = stash.parent_ids().map(gix::Id::detach).collect();
+    assert_eq!(parents.len(), 2, "no untracked files were stashed");
+    assert_eq!(parents[0], head);
+    let index_commit = repo.find_commit(parents[1])?;
+    assert_eq!(
+        index_commit.message_raw()?,
+        format!("index on main: {} first\n", head.to_hex_with_len(7)).as_str()
+    );
+    assert_eq!(
+        index_commit.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(),
+        [head]
+    );
+
+    let blob = |spec: String| -> crate::Result<String> {
+        Ok(repo
+            .rev_parse_single(spec.as_str())?
+            .object()?
+            .data
+            .to_str_lossy()
+            .into_owned())
+    };
+    assert_eq!(blob(format!("{}:b", parents[1]))?, "staged\n");
+    assert_eq!(blob(format!("{}:added", parents[1]))?, "added\n");
+    assert_eq!(
+        blob(format!("{}:a", parents[1]))?,
+        "a\n",
+        "unstaged changes aren't in the index"
+    );
+    assert_eq!(blob(format!("{id}:a"))?, "changed\n");
+    assert_eq!(blob(format!("{id}:b"))?, "staged\n");
+
+    assert_eq!(read_worktree_file(&repo, "a")?, "a\n", "the worktree was reset");
+    assert_eq!(read_worktree_file(&repo, "b")?, "b\n");
+    assert!(!exists(&repo, "added"));
+    assert!(exists(&repo, "untracked-dir/untracked"), "untracked files are kept");
+    assert!(!repo.is_dirty()?);
+
+    let list = repo.stash_list()?;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].id, id);
+    assert_eq!(list[0].message, stash.message_raw()?);
+    assert_eq!(
+        save(&repo, save::Options::default())?,
+        None,
+        "there is nothing left to stash"
+    );
+
+    let out = repo.stash_pop(0, &AtomicBool::default(), apply_options(&repo)?)?;
+    assert_eq!(out.id, id);
+    assert!(out.conflicts.is_none());
+    assert!(out.untracked.is_empty());
+    assert_eq!(read_worktree_file(&repo, "a")?, "changed\n");
+    assert_eq!(read_worktree_file(&repo, "b")?, "staged\n");
+    assert_eq!(read_worktree_file(&repo, "added")?, "added\n");
+    assert_eq!(
+        index_id(&repo, "b")?,
+        Some(head_b),
+        "like git, changes to existing files remain unstaged"
+    );
+    assert!(index_id(&repo, "added")?.is_some(), "new files are staged");
+    assert!(repo.stash_list()?.is_empty());
+    assert!(repo.try_find_reference(gix::stash::REF_NAME)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn save_with_untracked_files_and_message_can_be_applied_by_git() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_stash_repo.sh")?;
+    let id = save(
+        &repo,
+        save::Options::default()
+            .with_include_untracked(true)
+            .with_message("custom"),
+    )?
+    .expect("there are changes");
+    let stash = repo.find_commit(id)?;
+    assert_eq!(stash.message_raw()?, "On main: custom");
+    assert_eq!(stash.parent_ids().count(), 3);
+    assert!(
+        !exists(&repo, "untracked-dir"),
+        "untracked files and their directories are removed"
+    );
+
+    let git = |args: &[&str]| -> crate::Result<String> {
+        let out = std::process::Command::new("git")
+            .current_dir(tmp.path())
+            .args(args)
+            .output()?;
+        assert!(
+            out.status.success(),
+            "git {args:?} failed: {}",
+            out.stderr.to_str_lossy()
+        );
+        Ok(out.stdout.to_str_lossy().into_owned())
+    };
+    assert_eq!(git(&["stash", "list"])?, "stash@{0}: On main: custom\n");
+    git(&["stash", "pop", "--index"])?;
+    assert_eq!(read_worktree_file(&repo, "a")?, "changed\n");
+    assert_eq!(read_worktree_file(&repo, "untracked-dir/untracked")?, "untracked\n");
+    assert_eq!(
+        git(&["status", "--porcelain"])?,
+        " M a\nA  added\nM  b\n?? untracked-dir/\n"
+    );
+
+    git(&["stash", "push", "-q", "-m", "by git"])?;
+    let list = repo.stash_list()?;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].message, "On main: by git", "stashes by git can be read as well");
+    assert_eq!(read_worktree_file(&repo, "a")?, "a\n");
+    Ok(())
+}
+
+#[test]
+fn apply_restores_untracked_files_unless_they_are_in_the_way() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    save(&repo, save::Options::default().with_include_untracked(true))?;
+
+    let out = repo.stash_apply(0, &AtomicBool::default(), apply_options(&repo)?)?;
+    assert_eq!(out.untracked, ["untracked-dir/untracked"]);
+    assert_eq!(read_worktree_file(&repo, "untracked-dir/untracked")?, "untracked\n");
+    assert_eq!(read_worktree_file(&repo, "a")?, "changed\n");
+    assert_eq!(repo.stash_list()?.len(), 1, "applying keeps the stash entry");
+    assert!(index_id(&repo, "untracked-dir/untracked")?.is_none());
+
+    let Err(err) = repo.stash_apply(0, &AtomicBool::default(), apply_options(&repo)?) else {
+        panic!("untracked files are in the way")
+    };
+    assert!(
+        matches!(&err, apply::Error::Checkout(gix::checkout_tree::Error::Conflicts { conflicts })
+            if conflicts[0].rela_path == "untracked-dir/untracked"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn apply_with_conflicts_changes_nothing_and_pop_keeps_the_entry() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let id = save(&repo, save::Options::default())?.expect("there are changes");
+    repo.switch(
+        gix::switch::Target::Branch("refs/heads/conflicting".try_into()?),
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+
+    let out = repo.stash_pop(0, &AtomicBool::default(), apply_options(&repo)?)?;
+    assert_eq!(out.id, id);
+    let conflicts = out.conflicts.expect("a conflicts");
+    assert_eq!(conflicts.conflicts.len(), 1);
+    assert!(out.checkout.is_none());
+    assert_eq!(read_worktree_file(&repo, "a")?, "conflicting\n", "nothing was changed");
+    assert_eq!(read_worktree_file(&repo, "b")?, "b\n");
+    assert!(!repo.is_dirty()?);
+    assert_eq!(repo.stash_list()?.len(), 1, "the entry is kept");
+    Ok(())
+}
+
+#[test]
+fn drop_removes_entries_from_the_middle_and_rewrites_the_reflog() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let first = save(&repo, save::Options::default().with_message("first"))?.expect("changes");
+    let write =
+        |rela_path: &str, content: &str| std::fs::write(repo.workdir().expect("non-bare").join(rela_path), content);
+    write("a", "second\n")?;
+    let second = save(&repo, save::Options::default().with_message("second"))?.expect("changes");
+    write("a", "third\n")?;
+    let third = save(&repo, save::Options::default().with_message("third"))?.expect("changes");
+
+    let ids = |repo: &gix::Repository| -> crate::Result<Vec<_>> {
+        Ok(repo.stash_list()?.into_iter().map(|entry| entry.id).collect())
+    };
+    assert_eq!(ids(&repo)?, [third, second, first], "most recent first");
+
+    assert_eq!(repo.stash_drop(1)?, second);
+    assert_eq!(ids(&repo)?, [third, first]);
+    let stash_ref = repo.find_reference(gix::stash::REF_NAME)?;
+    let mut log = stash_ref.log_iter();
+    let log = log.all()?.expect("present").collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(log[1].previous_oid(), first, "the previous id is rewritten");
+
+    assert_eq!(repo.stash_drop(0)?, third);
+    assert_eq!(repo.find_reference(gix::stash::REF_NAME)?.id(), first);
+    assert_eq!(repo.stash_list()?[0].message, "On main: first");
+    assert!(matches!(
+        repo.stash_drop(1),
+        Err(gix::stash::drop::Error::NotFound { index: 1 })
+    ));
+    assert_eq!(repo.stash_drop(0)?, first);
+    assert!(repo.stash_list()?.is_empty());
+    assert!(repo.try_find_reference(gix::stash::REF_NAME)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn reftable_entries_are_listed_but_cannot_be_dropped() -> crate::Result {
+    use gix::refs::reftable::{RefRecord, RefValue, Stack};
+
+    let (repo, tmp) = repo_rw("make_stash_repo.sh")?;
+    let main = repo.head_id()?.detach();
+    let stack = Stack::at(repo.common_dir().join("reftable"), repo.object_hash())?;
+    let update_index = 1;
+    stack.lock(gix_lock::acquire::Fail::Immediately)?.commit(
+        vec![
+            RefRecord {
+                name: "HEAD".into(),
+                update_index,
+                value: RefValue::Symbolic("refs/heads/main".into()),
+            },
+            RefRecord {
+                name: "refs/heads/main".into(),
+                update_index,
+                value: RefValue::Object(main),
+            },
+        ],
+        Vec::new(),
+    )?;
+    let mut config = std::fs::read_to_string(repo.common_dir().join("config"))?;
+    config = config.replace("repositoryformatversion = 0", "repositoryformatversion = 1");
+    config.push_str("[extensions]\n\trefStorage = reftable\n");
+    std::fs::write(repo.common_dir().join("config"), config)?;
+    let repo = gix::open_opts(tmp.path(), crate::restricted())?;
+    assert_eq!(repo.refs.format(), gix::refs::store::Format::Reftable);
+
+    let id = save(&repo, save::Options::default().with_message("in reftable"))?.expect("changes");
+    assert!(
+        !repo.common_dir().join("logs").join(gix::stash::REF_NAME).exists(),
+        "the log is stored in the reftable"
+    );
+    let list = repo.stash_list()?;
+    assert_eq!(list.len(), 1, "the log is read through the reference store");
+    assert_eq!(list[0].id, id);
+    assert_eq!(list[0].message, "On main: in reftable");
+
+    assert!(matches!(
+        repo.stash_drop(0),
+        Err(gix::stash::drop::Error::UnsupportedRefFormat)
+    ));
+    assert!(
+        matches!(
+            repo.stash_pop(0, &AtomicBool::default(), apply_options(&repo)?),
+            Err(apply::Error::Drop(gix::stash::drop::Error::UnsupportedRefFormat))
+        ),
+        "popping fails before anything is applied"
+    );
+    assert_eq!(read_worktree_file(&repo, "a")?, "a\n");
+    assert_eq!(repo.stash_list()?.len(), 1);
+    Ok(())
+}
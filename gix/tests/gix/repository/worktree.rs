@@ -490,3 +490,251 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         );
     }
 }
+
+#[cfg(all(feature = "worktree-mutation", feature = "revision", feature = "status"))]
+mod mutation {
+    use std::{path::Path, sync::atomic::AtomicBool};
+
+    use gix::worktree::{add, prune, repair};
+
+    use crate::util::repo_rw;
+
+    fn add(
+        repo: &gix::Repository,
+        path: &Path,
+        commitish: Option<&str>,
+        options: add::Options,
+    ) -> Result<gix::Repository, add::Error> {
+        repo.worktree_add(path, commitish.map(Into::into), &AtomicBool::default(), options)
+    }
+
+    fn head_name(repo: &gix::Repository) -> crate::Result<Option<String>> {
+        Ok(repo.head_name()?.map(|name| name.as_bstr().to_string()))
+    }
+
+    #[test]
+    fn add_with_new_branch_checks_out_files_and_links_to_the_main_repository() -> crate::Result {
+        let (repo, tmp) = repo_rw("make_worktree_mutation_repo.sh")?;
+        let path = tmp.path().join("wt");
+        let main = repo.rev_parse_single("main")?.detach();
+
+        let wt = add(&repo, &path, None, add::Options::default().with_new_branch("topic"))?;
+        assert_eq!(std::fs::read_to_string(path.join("file"))?, "c\n");
+        assert_eq!(std::fs::read_to_string(path.join("dir/file"))?, "b\n");
+        assert_eq!(head_name(&wt)?.as_deref(), Some("refs/heads/topic"));
+        assert_eq!(wt.head_id()?, main);
+        assert_eq!(repo.find_reference("refs/heads/topic")?.id(), main);
+        assert!(!wt.is_dirty()?, "the index matches the checkout");
+        assert_eq!(
+            gix_path::realpath(wt.common_dir())?,
+            gix_path::realpath(repo.common_dir())?
+        );
+
+        let proxies = repo.worktrees()?;
+        assert_eq!(proxies.len(), 1);
+        let proxy = &proxies[0];
+        assert_eq!(proxy.id(), "wt");
+        assert_eq!(proxy.base()?, gix_path::realpath(&path)?);
+        assert!(!proxy.is_locked(), "the lock held during initialization is released");
+        assert_eq!(proxy.prunable(), None);
+        assert_eq!(std::fs::read_to_string(proxy.git_dir().join("commondir"))?, "../..\n");
+        assert_eq!(
+            std::fs::read_to_string(proxy.git_dir().join("HEAD"))?,
+            "ref: refs/heads/topic\n"
+        );
+
+        let err = add(&repo, &path, None, add::Options::default()).unwrap_err();
+        assert!(matches!(err, add::Error::PathExists { .. }), "{err:?}");
+        let err = add(
+            &repo,
+            &tmp.path().join("wt2"),
+            None,
+            add::Options::default().with_new_branch("topic"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, add::Error::BranchExists { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn add_checks_out_existing_branches_only_once_unless_detached() -> crate::Result {
+        let (repo, tmp) = repo_rw("make_worktree_mutation_repo.sh")?;
+        let other = repo.rev_parse_single("other")?.detach();
+
+        let err = add(&repo, &tmp.path().join("a"), Some("main"), add::Options::default()).unwrap_err();
+        assert!(
+            matches!(&err, add::Error::BranchCheckedOut { name, .. } if name.as_bstr() == "refs/heads/main"),
+            "{err:?}"
+        );
+        assert!(!tmp.path().join("a").exists(), "nothing is left behind on failure");
+        assert!(repo.worktrees()?.is_empty());
+
+        let wt = add(
+            &repo,
+            &tmp.path().join("a"),
+            Some("main"),
+            add::Options::default().with_detach(true),
+        )?;
+        assert!(wt.head()?.is_detached());
+
+        let wt = add(&repo, &tmp.path().join("b"), Some("other"), add::Options::default())?;
+        assert_eq!(head_name(&wt)?.as_deref(), Some("refs/heads/other"));
+        assert_eq!(std::fs::read_to_string(tmp.path().join("b/file"))?, "a\n");
+
+        let wt = add(&repo, &tmp.path().join("c"), Some("other~0"), add::Options::default())?;
+        assert_eq!(wt.head_id()?, other, "revspecs lead to a detached HEAD");
+        assert!(wt.head()?.is_detached());
+
+        let wt = add(&repo, &tmp.path().join("sub/b"), None, add::Options::default())?;
+        assert_eq!(
+            head_name(&wt)?.as_deref(),
+            Some("refs/heads/b"),
+            "a branch named after the worktree is created"
+        );
+        assert_eq!(
+            repo.worktrees()?
+                .iter()
+                .map(|wt| wt.id().to_string())
+                .collect::<Vec<_>>(),
+            ["a", "b", "b1", "c"],
+            "ids are made unique"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lock_move_and_remove() -> crate::Result {
+        let (repo, tmp) = repo_rw("make_worktree_mutation_repo.sh")?;
+        let path = tmp.path().join("wt");
+        add(&repo, &path, None, add::Options::default().with_lock("on a stick"))?;
+
+        let proxy = repo.worktree_proxy_by_id("wt").expect("present");
+        assert_eq!(proxy.lock_reason().map(|r| r.to_string()), Some("on a stick".into()));
+        assert!(matches!(
+            proxy.lock(""),
+            Err(gix::worktree::proxy::lock::Error::AlreadyLocked { .. })
+        ));
+        assert!(matches!(
+            proxy.move_to(tmp.path().join("moved")),
+            Err(gix::worktree::proxy::move_to::Error::Locked { .. })
+        ));
+        proxy.unlock()?;
+        assert!(!proxy.is_locked());
+
+        std::fs::create_dir(tmp.path().join("target"))?;
+        let new_base = proxy.move_to(tmp.path().join("target"))?;
+        assert_eq!(new_base, gix_path::realpath(tmp.path().join("target/wt"))?);
+        assert_eq!(proxy.base()?, new_base);
+        assert!(!path.exists());
+        let wt = proxy.clone().into_repo()?;
+        assert!(!wt.is_dirty()?, "the moved worktree is still functional");
+
+        std::fs::write(new_base.join("untracked"), "")?;
+        let err = proxy.clone().remove(false).unwrap_err();
+        assert!(
+            matches!(err, gix::worktree::proxy::remove::Error::Dirty { .. }),
+            "{err:?}"
+        );
+        proxy.remove(true)?;
+        assert!(!new_base.exists());
+        assert!(repo.worktrees()?.is_empty());
+        assert!(
+            !repo.common_dir().join("worktrees").exists(),
+            "the empty worktrees directory is removed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn prune_removes_worktrees_that_are_gone() -> crate::Result {
+        let (repo, tmp) = repo_rw("make_worktree_mutation_repo.sh")?;
+        add(&repo, &tmp.path().join("gone"), None, add::Options::default())?;
+        add(
+            &repo,
+            &tmp.path().join("locked"),
+            None,
+            add::Options::default().with_lock(""),
+        )?;
+        add(&repo, &tmp.path().join("kept"), None, add::Options::default())?;
+        std::fs::remove_dir_all(tmp.path().join("gone"))?;
+        std::fs::remove_dir_all(tmp.path().join("locked"))?;
+        std::fs::write(repo.common_dir().join("worktrees/file"), "")?;
+
+        assert_eq!(
+            repo.worktree_proxy_by_id("gone").expect("present").prunable(),
+            Some(prune::Reason::WorktreeMissing)
+        );
+        assert_eq!(repo.worktree_proxy_by_id("locked").expect("present").prunable(), None);
+
+        let expected = [
+            prune::Pruned {
+                id: "file".into(),
+                reason: prune::Reason::NotADirectory,
+            },
+            prune::Pruned {
+                id: "gone".into(),
+                reason: prune::Reason::WorktreeMissing,
+            },
+        ];
+        assert_eq!(
+            repo.worktrees_prune(prune::Options::default().with_expire(std::time::UNIX_EPOCH))?,
+            [],
+            "nothing is old enough"
+        );
+        assert_eq!(
+            repo.worktrees_prune(prune::Options::default().with_dry_run(true))?,
+            expected
+        );
+        assert_eq!(repo.worktrees()?.len(), 3, "nothing was removed");
+
+        assert_eq!(repo.worktrees_prune(prune::Options::default())?, expected);
+        assert_eq!(
+            repo.worktrees()?
+                .iter()
+                .map(|wt| wt.id().to_string())
+                .collect::<Vec<_>>(),
+            ["kept", "locked"]
+        );
+        assert!(!repo.common_dir().join("worktrees/file").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn repair_fixes_links_in_both_directions() -> crate::Result {
+        let (repo, tmp) = repo_rw("make_worktree_mutation_repo.sh")?;
+        let path = tmp.path().join("wt");
+        add(&repo, &path, None, add::Options::default())?;
+        assert_eq!(repo.worktrees_repair(None::<&Path>)?, [], "nothing to repair");
+
+        let moved = tmp.path().join("moved");
+        std::fs::rename(&path, &moved)?;
+        let moved = gix_path::realpath(&moved)?;
+        assert_eq!(
+            repo.worktree_proxy_by_id("wt").expect("present").prunable(),
+            Some(prune::Reason::WorktreeMissing)
+        );
+        assert_eq!(
+            repo.worktrees_repair([&moved])?,
+            [repair::Repaired {
+                id: "wt".into(),
+                base: moved.clone(),
+                kind: repair::Kind::GitDirFile
+            }]
+        );
+        let proxy = repo.worktree_proxy_by_id("wt").expect("present");
+        assert_eq!(proxy.base()?, moved);
+        assert_eq!(proxy.prunable(), None);
+
+        std::fs::write(moved.join(".git"), "gitdir: /does/not/exist\n")?;
+        assert_eq!(
+            repo.worktrees_repair(None::<&Path>)?,
+            [repair::Repaired {
+                id: "wt".into(),
+                base: moved.clone(),
+                kind: repair::Kind::DotGitFile
+            }]
+        );
+        assert!(!proxy.into_repo()?.is_dirty()?);
+        Ok(())
+    }
+}
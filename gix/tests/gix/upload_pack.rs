@@ -0,0 +1,206 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    bstr::{BString, ByteSlice},
+    odb::pack,
+    protocol::transport::packetline::{self, PacketLineRef, blocking_io::encode},
+    remote::{Direction::Fetch, fetch::Shallow},
+};
+use gix_testtools::tempfile::TempDir;
+
+use crate::remote;
+
+fn source() -> gix::Repository {
+    remote::repo("base")
+}
+
+fn fetch_in_process(shallow: Shallow) -> crate::Result<(gix::Repository, TempDir, gix::remote::fetch::Outcome)> {
+    let tmp = TempDir::new()?;
+    let source = source();
+    let repo = gix::ThreadSafeRepository::init_opts(
+        tmp.path(),
+        gix::create::Kind::Bare,
+        gix::create::Options {
+            object_hash: Some(source.object_hash()),
+            ..Default::default()
+        },
+        crate::restricted(),
+    )?
+    .to_thread_local();
+    let remote = repo
+        .remote_at(source.git_dir())?
+        .with_refspecs(["+refs/heads/*:refs/remotes/origin/*"], Fetch)?;
+    let transport = gix::upload_pack::in_process(source.into_sync())?;
+    let outcome = remote
+        .to_connection_with_transport(transport)
+        .prepare_fetch(gix::progress::Discard, Default::default())?
+        .with_shallow(shallow)
+        .receive(gix::progress::Discard, &AtomicBool::default())?;
+    Ok((repo, tmp, outcome))
+}
+
+fn assert_branches_match(repo: &gix::Repository) -> crate::Result {
+    let source = source();
+    let mut num_branches = 0;
+    for branch in source.references()?.local_branches()? {
+        let branch = branch?;
+        let tracking = format!("refs/remotes/origin/{}", branch.name().shorten());
+        assert_eq!(
+            repo.find_reference(tracking.as_str())?.id(),
+            branch.id(),
+            "{tracking} matches the branch it was fetched from"
+        );
+        num_branches += 1;
+    }
+    assert!(num_branches > 1, "there is more than one branch to fetch");
+    assert!(
+        gix_testtools::run_git(repo.git_dir(), &["fsck", "--connectivity-only"])?.success(),
+        "all objects needed by the fetched references were received"
+    );
+    Ok(())
+}
+
+#[test]
+fn fetch_everything() -> crate::Result {
+    let (repo, _tmp, outcome) = fetch_in_process(Shallow::NoChange)?;
+    assert!(
+        matches!(outcome.status, gix::remote::fetch::Status::Change { .. }),
+        "a pack was received"
+    );
+    assert_branches_match(&repo)?;
+    assert!(!repo.is_shallow());
+    Ok(())
+}
+
+#[test]
+fn fetch_shallow_with_depth() -> crate::Result {
+    let (repo, _tmp, _outcome) = fetch_in_process(Shallow::DepthAtRemote(1.try_into()?))?;
+    assert_branches_match(&repo)?;
+
+    let mut expected: Vec<_> = Vec::new();
+    for reference in repo.references()?.remote_branches()? {
+        let id = reference?.id();
+        if id.object()?.into_commit().parent_ids().next().is_some() {
+            expected.push(id.detach());
+        }
+    }
+    expected.sort();
+    expected.dedup();
+    let shallow = repo.shallow_commits()?.expect("the repository is shallow");
+    assert_eq!(
+        shallow.iter().copied().collect::<Vec<_>>(),
+        expected,
+        "all branch tips with parents are on the shallow boundary"
+    );
+    let tips: Vec<_> = repo
+        .references()?
+        .remote_branches()?
+        .map(|reference| reference.map(|r| r.id().detach()))
+        .collect::<Result<_, _>>()?;
+    for id in &expected {
+        let commit = repo.find_commit(*id)?;
+        assert!(
+            commit
+                .parent_ids()
+                .all(|parent| !repo.has_object(parent) || tips.contains(&parent.detach())),
+            "parents of the shallow commit {id} are only sent if they are wanted themselves"
+        );
+    }
+    Ok(())
+}
+
+/// Run `upload-pack` on `repo` with `lines` of arguments for the `fetch` command, and return all text lines
+/// of the response following the advertisement, along with the pack.
+fn fetch_raw(repo: &gix::Repository, lines: &[String]) -> (Result<(), gix::upload_pack::Error>, Vec<BString>, Vec<u8>) {
+    let mut input = Vec::new();
+    encode::text_to_write(b"command=fetch", &mut input).unwrap();
+    encode::delim_to_write(&mut input).unwrap();
+    for line in lines {
+        encode::text_to_write(line.as_bytes(), &mut input).unwrap();
+    }
+    encode::flush_to_write(&mut input).unwrap();
+
+    let mut output = Vec::new();
+    let res = repo.upload_pack(input.as_slice(), &mut output, &AtomicBool::default());
+
+    let (mut text, mut pack) = (Vec::new(), Vec::new());
+    let mut data = output.as_slice();
+    let mut in_advertisement = true;
+    while !data.is_empty() {
+        let packetline::decode::Stream::Complete { line, bytes_consumed } =
+            packetline::decode::streaming(data).expect("valid")
+        else {
+            panic!("incomplete line")
+        };
+        data = &data[bytes_consumed..];
+        if in_advertisement {
+            in_advertisement = line != PacketLineRef::Flush;
+            continue;
+        }
+        match line {
+            PacketLineRef::Data([1, rest @ ..]) => pack.extend_from_slice(rest),
+            PacketLineRef::Data([2, ..]) => {}
+            PacketLineRef::Data([3, rest @ ..]) => text.push(rest.trim_end().into()),
+            PacketLineRef::Data(line) => text.push(line.trim_end().into()),
+            _ => {}
+        }
+    }
+    (res, text, pack)
+}
+
+fn pack_kinds(pack: &[u8], object_hash: gix::hash::Kind) -> crate::Result<Vec<gix::object::Kind>> {
+    let mut kinds = Vec::new();
+    for entry in pack::data::input::BytesToEntriesIter::new_from_header(
+        std::io::BufReader::new(pack),
+        pack::data::input::Mode::Verify,
+        pack::data::input::EntryDataMode::Ignore,
+        object_hash,
+    )? {
+        kinds.extend(entry?.header.as_kind());
+    }
+    Ok(kinds)
+}
+
+#[test]
+fn fetch_with_blob_filter() -> crate::Result {
+    let repo = source();
+    let head = repo.head_id()?;
+    let (res, text, pack) = fetch_raw(
+        &repo,
+        &[format!("want {head}"), "filter blob:none".into(), "done".into()],
+    );
+    res?;
+    assert_eq!(text.first().map(|line| line.as_bstr()), Some("packfile".into()));
+    let kinds = pack_kinds(&pack, repo.object_hash())?;
+    assert!(kinds.contains(&gix::object::Kind::Commit));
+    assert!(kinds.contains(&gix::object::Kind::Tree));
+    assert!(
+        !kinds.contains(&gix::object::Kind::Blob),
+        "blobs are omitted from the pack"
+    );
+
+    let (res, _text, pack) = fetch_raw(&repo, &[format!("want {head}"), "done".into()]);
+    res?;
+    assert!(
+        pack_kinds(&pack, repo.object_hash())?.contains(&gix::object::Kind::Blob),
+        "blobs are sent without a filter"
+    );
+    Ok(())
+}
+
+#[test]
+fn unknown_wants_are_rejected() -> crate::Result {
+    let repo = source();
+    let missing = repo.object_hash().null();
+    let (res, text, _pack) = fetch_raw(&repo, &[format!("want {missing}"), "done".into()]);
+    assert!(matches!(res, Err(gix::upload_pack::Error::NotOurRef { id }) if id == missing));
+    assert_eq!(
+        text.last().map(|line| line.as_bstr()),
+        Some(
+            format!("ERR upload-pack: The client wants {missing}, which doesn't exist")
+                .as_bytes()
+                .as_bstr()
+        )
+    );
+    Ok(())
+}
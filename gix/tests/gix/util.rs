@@ -194,6 +194,30 @@ pub fn freeze_time() -> gix_testtools::Env<'static> {
         .unset("GIT_COMMITTER_EMAIL")
         .set("GIT_COMMITTER_DATE", frozen_time)
 }
+
+/// Return the id of the tree of the commit that `spec` resolves to.
+#[cfg(any(feature = "rebase", feature = "sequencer"))]
+pub fn tree_of(repo: &Repository, spec: &str) -> Result<gix_hash::ObjectId> {
+    Ok(repo
+        .rev_parse_single(spec)?
+        .object()?
+        .peel_to_commit()?
+        .tree_id()?
+        .detach())
+}
+
+/// Read the worktree file at `rela_path` as string.
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+pub fn read_worktree_file(repo: &Repository, rela_path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.workdir().expect("non-bare").join(rela_path))
+}
+
+/// Return the id of the index entry at `rela_path`, if there is one.
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+pub fn index_id(repo: &Repository, rela_path: &str) -> Result<Option<gix_hash::ObjectId>> {
+    Ok(repo.open_index()?.entry_by_path(rela_path.into()).map(|entry| entry.id))
+}
+
 pub fn repo(name: &str) -> Result<ThreadSafeRepository> {
     let repo_path = gix_testtools::scripted_fixture_read_only(name)?;
     Ok(ThreadSafeRepository::open_opts(repo_path, restricted())?)
@@ -1211,7 +1211,10 @@ pub fn main() -> Result<()> {
                                 core::pack::multi_index::verify(multi_index_path, progress, &should_interrupt)
                             },
                         ),
-                        free::pack::multi_index::Subcommands::Create { index_paths } => prepare_and_run(
+                        free::pack::multi_index::Subcommands::Create {
+                            index_paths,
+                            preferred_pack,
+                        } => prepare_and_run(
                             "pack-multi-index-create",
                             trace,
                             verbose,
@@ -1222,12 +1225,44 @@ pub fn main() -> Result<()> {
                                 core::pack::multi_index::create(
                                     index_paths,
                                     multi_index_path,
+                                    preferred_pack,
                                     progress,
                                     &should_interrupt,
                                     object_hash,
                                 )
                             },
                         ),
+                        free::pack::multi_index::Subcommands::Append {
+                            preferred_pack,
+                            stdin_packs,
+                        } => prepare_and_run(
+                            "pack-multi-index-append",
+                            trace,
+                            verbose,
+                            progress,
+                            progress_keep_open,
+                            core::pack::multi_index::PROGRESS_RANGE,
+                            move |progress, out, _err| {
+                                let packs = stdin_packs
+                                    .then(|| {
+                                        std::io::BufRead::lines(stdin().lock())
+                                            .map(|line| line.map(PathBuf::from))
+                                            .collect::<std::io::Result<Vec<_>>>()
+                                    })
+                                    .transpose()?;
+                                core::pack::multi_index::append(
+                                    multi_index_path,
+                                    core::pack::multi_index::AppendOptions {
+                                        preferred_pack,
+                                        packs,
+                                        object_hash,
+                                    },
+                                    progress,
+                                    out,
+                                    &should_interrupt,
+                                )
+                            },
+                        ),
                     }
                 }
                 free::pack::Subcommands::Index(subcommands) => match subcommands {
@@ -1402,15 +1437,58 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
-        Subcommands::Cat { revspec } => prepare_and_run(
-            "cat",
-            trace,
-            verbose,
-            progress,
-            progress_keep_open,
-            None,
-            move |_progress, out, _err| core::repository::cat(repository(Mode::Lenient)?, &revspec, out),
-        ),
+        Subcommands::Cat {
+            batch,
+            batch_check,
+            batch_command,
+            batch_all_objects,
+            unordered,
+            buffer,
+            revspec,
+        } => {
+            use gix::batch::Mode as BatchMode;
+            let batch = match (batch, batch_check, batch_command) {
+                (Some(format), _, _) => Some((BatchMode::Contents, format)),
+                (_, Some(format), _) => Some((BatchMode::Info, format)),
+                (_, _, Some(format)) => Some((BatchMode::Command, format)),
+                (None, None, None) => None,
+            };
+            match (batch, revspec) {
+                (Some((mode, format)), None) => prepare_and_run(
+                    "cat-batch",
+                    trace,
+                    verbose,
+                    progress,
+                    progress_keep_open,
+                    None,
+                    move |_progress, out, _err| {
+                        core::repository::cat_batch(
+                            repository(Mode::Lenient)?,
+                            stdin().lock(),
+                            out,
+                            core::repository::cat::BatchOptions {
+                                mode,
+                                format,
+                                all_objects: batch_all_objects,
+                                unordered,
+                                buffer,
+                            },
+                        )
+                    },
+                ),
+                (None, Some(revspec)) if !batch_all_objects => prepare_and_run(
+                    "cat",
+                    trace,
+                    verbose,
+                    progress,
+                    progress_keep_open,
+                    None,
+                    move |_progress, out, _err| core::repository::cat(repository(Mode::Lenient)?, &revspec, out),
+                ),
+                (Some(_), Some(_)) => Err(anyhow!("Cannot specify an object in batch mode")),
+                (None, _) => Err(anyhow!("--batch-all-objects requires --batch or --batch-check")),
+            }
+        }
         Subcommands::Commit(cmd) => match cmd {
             commit::Subcommands::Verify { rev_spec } => prepare_and_run(
                 "commit-verify",
@@ -1574,6 +1652,17 @@ pub fn main() -> Result<()> {
             None,
             move |_progress, out, _err| core::repository::fsck(repository(Mode::Strict)?, spec, out),
         ),
+        Subcommands::Gc => prepare_and_run(
+            "gc",
+            trace,
+            auto_verbose,
+            progress,
+            progress_keep_open,
+            core::repository::gc::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::gc(repository(Mode::Strict)?, progress, out, &should_interrupt, format)
+            },
+        ),
         Subcommands::Mailmap(cmd) => match cmd {
             mailmap::Subcommands::Entries => prepare_and_run(
                 "mailmap-entries",
@@ -357,6 +357,20 @@ pub mod pack {
                 /// Note for the multi-index to be useful, it should be side-by-side with the supplied `.idx` files.
                 #[clap(required = true)]
                 index_paths: Vec<PathBuf>,
+                /// The file name of the pack whose objects should be preferred over duplicates in other packs.
+                #[clap(long)]
+                preferred_pack: Option<PathBuf>,
+            },
+            /// Add all packs next to the multi-index that aren't yet contained in it as new layer of an incremental multi-index.
+            ///
+            /// The layers are stored in the `multi-pack-index.d` directory next to the multi-index path, which itself is ignored.
+            Append {
+                /// The file name of the pack whose objects should come first in the new layer.
+                #[clap(long)]
+                preferred_pack: Option<PathBuf>,
+                /// Only add the packs whose index or pack file names are read from standard input, one per line.
+                #[clap(long)]
+                stdin_packs: bool,
             },
         }
     }
@@ -97,6 +97,8 @@ pub enum Subcommands {
     Odb(odb::Subcommands),
     /// Check for missing objects.
     Fsck(fsck::Platform),
+    /// Pack all reachable objects into a single pack and prune unreachable loose objects once they expired.
+    Gc,
     /// Interact with tree objects.
     #[clap(subcommand)]
     Tree(tree::Subcommands),
@@ -141,10 +143,37 @@ pub enum Subcommands {
     /// Interact with submodules.
     #[clap(alias = "submodules")]
     Submodule(submodule::Platform),
-    /// Show whatever object is at the given spec.
+    /// Show whatever object is at the given spec, or many objects like `git cat-file --batch`.
     Cat {
-        /// The object to print to stdout.
-        revspec: String,
+        /// Read object names or rev-specs from stdin and print a line in the given format, followed by the object data.
+        ///
+        /// The format defaults to `%(objectname) %(objecttype) %(objectsize)`, and also supports
+        /// `%(objectsize:disk)`, `%(deltabase)` and `%(rest)`.
+        #[clap(long, require_equals = true, value_name = "FORMAT", conflicts_with_all = ["batch_check", "batch_command"])]
+        batch: Option<Option<String>>,
+        /// Like `--batch`, but only print a line in the given format for each object.
+        #[clap(long, require_equals = true, value_name = "FORMAT", conflicts_with = "batch_command")]
+        batch_check: Option<Option<String>>,
+        /// Like `--batch`, but read `contents <object>`, `info <object>` or `flush` commands from stdin.
+        #[clap(
+            long,
+            require_equals = true,
+            value_name = "FORMAT",
+            conflicts_with = "batch_all_objects"
+        )]
+        batch_command: Option<Option<String>>,
+        /// Print all objects in the object database instead of reading object names from stdin, sorted by object id.
+        #[clap(long)]
+        batch_all_objects: bool,
+        /// With `--batch-all-objects`, print objects in the order they are stored in packs, which is faster.
+        #[clap(long, requires = "batch_all_objects")]
+        unordered: bool,
+        /// Don't flush output after each object, but only at the end of the input or on `flush` commands.
+        #[clap(long)]
+        buffer: bool,
+        /// The object to print to stdout, if not in batch mode.
+        #[clap(required_unless_present_any = ["batch", "batch_check", "batch_command"])]
+        revspec: Option<String>,
     },
     /// Check for changes in the repository, treating this as an error.
     IsClean,